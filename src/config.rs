@@ -0,0 +1,278 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use crate::guitar::Tuning;
+use crate::paths;
+use crate::practice::Goal;
+use crate::theory::{NoteNames, Preferences};
+use crate::ui::components::PianoStyle;
+use crate::ui::i18n::Language;
+use crate::ui::keymap::KeysConfig;
+use crate::ui::status::StatusSegment;
+use crate::ui::theme::QualityColors;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    pub on_chord: Option<String>,
+    pub on_key: Option<String>,
+    pub on_mode: Option<String>,
+    pub on_clear: Option<String>,
+    pub on_session_start: Option<String>,
+    pub on_session_end: Option<String>,
+    /// Run on practice milestones, with `{milestone}` and `{kind}`
+    pub on_milestone: Option<String>,
+    /// Show milestones as desktop notifications
+    pub notify: bool,
+    pub pipe: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    pub piano: PianoStyle,
+    /// Chord change flashes, sliding history and the tree cross-fade
+    pub animations: bool,
+    /// UI language code, from the locale if unset
+    pub language: Option<Language>,
+    /// `english`, `german` or `solfege`, from the language if unset
+    pub note_names: Option<NoteNames>,
+    /// Status bar segments to show, in order
+    pub status: Vec<StatusSegment>,
+    /// Colour chord names by quality family
+    pub color_qualities: bool,
+    pub quality_colors: QualityColors,
+    /// Chords kept in the history
+    pub history_length: usize,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            piano: PianoStyle::default(),
+            animations: true,
+            language: None,
+            note_names: None,
+            status: StatusSegment::defaults(),
+            color_qualities: false,
+            quality_colors: QualityColors::default(),
+            history_length: 16,
+        }
+    }
+}
+
+impl DisplayConfig {
+    pub fn language(&self) -> Language {
+        self.language.unwrap_or_else(Language::from_env)
+    }
+
+    pub fn note_names(&self) -> NoteNames {
+        self.note_names
+            .unwrap_or_else(|| self.language().note_names())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GuitarConfig {
+    /// Fret the capo starts on, 0 for none
+    pub capo: u8,
+    pub tuning: Tuning,
+    /// Draw the low string on the right, as seen playing left-handed
+    pub left_handed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PracticeConfig {
+    /// Daily goals, e.g. `"minutes 20"`, `"chords 30"` or `"all m7"`
+    pub goals: Vec<Goal>,
+}
+
+impl Default for PracticeConfig {
+    fn default() -> Self {
+        Self {
+            goals: Goal::defaults(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub hooks: HooksConfig,
+    pub detection: Preferences,
+    pub display: DisplayConfig,
+    pub guitar: GuitarConfig,
+    pub practice: PracticeConfig,
+    pub keys: KeysConfig,
+}
+
+impl Config {
+    pub fn default_path() -> PathBuf {
+        paths::config_dir().join("config.toml")
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Could not read {}: {}", path.display(), e))?;
+        Self::from_toml(&text).map_err(|e| anyhow!("{}: {}", path.display(), e))
+    }
+
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::SixthPreference;
+
+    #[test]
+    fn test_hooks_section() {
+        let config = Config::from_toml(
+            r#"
+            [hooks]
+            on_chord = "notify-send {chord}"
+            pipe = "/tmp/chordvery.fifo"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.hooks.on_chord.as_deref(),
+            Some("notify-send {chord}")
+        );
+        assert_eq!(
+            config.hooks.pipe,
+            Some(PathBuf::from("/tmp/chordvery.fifo"))
+        );
+        assert!(config.hooks.on_key.is_none());
+    }
+
+    #[test]
+    fn test_detection_section() {
+        let config = Config::from_toml(
+            r#"
+            [detection]
+            sixths = "minor7"
+            quartal = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.detection.sixths, SixthPreference::Minor7);
+        assert!(config.detection.quartal);
+        assert!(!config.detection.slash);
+        assert!(Config::from_toml("[detection]\nsixths = \"ninth\"").is_err());
+    }
+
+    #[test]
+    fn test_display_section() {
+        let config = Config::from_toml("[display]\npiano = \"hires\"").unwrap();
+        assert_eq!(config.display.piano, PianoStyle::Hires);
+        assert!(config.display.animations);
+        assert_eq!(Config::default().display.piano, PianoStyle::Blocks);
+        let config = Config::from_toml("[display]\nanimations = false").unwrap();
+        assert!(!config.display.animations);
+        assert!(Config::from_toml("[display]\npiano = \"braille\"").is_err());
+        let config = Config::from_toml("[display]\nlanguage = \"de\"").unwrap();
+        assert_eq!(config.display.language(), Language::German);
+        assert_eq!(config.display.note_names(), NoteNames::German);
+        let config =
+            Config::from_toml("[display]\nlanguage = \"fr\"\nnote_names = \"english\"").unwrap();
+        assert_eq!(config.display.note_names(), NoteNames::English);
+        assert!(Config::from_toml("[display]\nlanguage = \"xx\"").is_err());
+        let config =
+            Config::from_toml("[display]\nstatus = [\"chord\", \"port\", \"mode\"]").unwrap();
+        assert_eq!(
+            config.display.status,
+            [
+                StatusSegment::Chord,
+                StatusSegment::Port,
+                StatusSegment::Mode
+            ]
+        );
+        assert_eq!(Config::default().display.status, StatusSegment::defaults());
+        let config = Config::from_toml("[display]\nstatus = [\"latency\", \"clock\"]").unwrap();
+        assert_eq!(
+            config.display.status,
+            [StatusSegment::Latency, StatusSegment::Clock]
+        );
+        let mut app = crate::ui::App::new();
+        app.apply_config(&config);
+        let screen = crate::ui::snapshot::app(&app, 120, 40);
+        assert!(screen.contains("Latency: —"));
+        assert!(screen.contains("Clock: internal"));
+        assert!(Config::from_toml("[display]\nstatus = [\"jitter\"]").is_err());
+
+        let config = Config::from_toml(
+            "[display]\ncolor_qualities = true\n\n[display.quality_colors]\nminor = \"cyan\"",
+        )
+        .unwrap();
+        assert!(config.display.color_qualities);
+        assert_eq!(
+            config.display.quality_colors.minor,
+            ratatui::style::Color::Cyan
+        );
+        assert!(!Config::default().display.color_qualities);
+
+        let config = Config::from_toml("[display]\nhistory_length = 2000").unwrap();
+        assert_eq!(config.display.history_length, 2000);
+        assert_eq!(Config::default().display.history_length, 16);
+    }
+
+    #[test]
+    fn test_guitar_section() {
+        assert_eq!(Config::default().guitar.capo, 0);
+        let config = Config::from_toml("[guitar]\ncapo = 3").unwrap();
+        assert_eq!(config.guitar.capo, 3);
+        assert!(Config::from_toml("[guitar]\ncapo = -1").is_err());
+
+        let config = Config::from_toml(
+            r#"
+            [guitar]
+            tuning = "drop-d"
+            left_handed = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.guitar.tuning.strings[0].name(), "D");
+        assert!(config.guitar.left_handed);
+        assert_eq!(Config::default().guitar.tuning, Tuning::standard());
+        assert!(Config::from_toml("[guitar]\ntuning = \"E2 A2\"").is_err());
+    }
+
+    #[test]
+    fn test_practice_section() {
+        assert_eq!(Config::default().practice.goals, Goal::defaults());
+        let config =
+            Config::from_toml("[practice]\ngoals = [\"minutes 30\", \"all dim7\"]").unwrap();
+        assert_eq!(
+            config.practice.goals,
+            vec![
+                Goal::Minutes(30),
+                Goal::AllRoots(crate::theory::Quality::Diminished7)
+            ]
+        );
+        assert!(Config::from_toml("[practice]\ngoals = [\"all day\"]").is_err());
+    }
+
+    #[test]
+    fn test_empty_and_unknown() {
+        assert_eq!(Config::from_toml("").unwrap(), Config::default());
+        assert!(Config::from_toml("[hooks]\non_chrod = \"x\"").is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use crate::session::Session;
+use crate::theory::{Chord, Note, NoteNames, Quality, Spelling};
+
+const LILYPOND_VERSION: &str = "2.24.0";
+
+const SHARP_NAMES: [&str; 12] = [
+    "c", "cis", "d", "dis", "e", "f", "fis", "g", "gis", "a", "ais", "b",
+];
+
+const FLAT_NAMES: [&str; 12] = [
+    "c", "des", "d", "es", "e", "f", "ges", "g", "as", "a", "bes", "b",
+];
+
+#[derive(Clone, Debug)]
+pub struct LilypondOptions {
+    pub spelling: Spelling,
+    pub duration: u8,
+    pub block_chords: bool,
+    /// How the chord names above the staff are printed
+    pub note_names: NoteNames,
+}
+
+impl Default for LilypondOptions {
+    fn default() -> Self {
+        Self {
+            spelling: Spelling::Sharp,
+            duration: 1,
+            block_chords: false,
+            note_names: NoteNames::English,
+        }
+    }
+}
+
+impl LilypondOptions {
+    pub fn block_chords(mut self, enabled: bool) -> Self {
+        self.block_chords = enabled;
+        self
+    }
+
+    pub fn spelling(mut self, spelling: Spelling) -> Self {
+        self.spelling = spelling;
+        self
+    }
+
+    pub fn note_names(mut self, note_names: NoteNames) -> Self {
+        self.note_names = note_names;
+        self
+    }
+}
+
+pub fn render(session: &Session, options: &LilypondOptions) -> String {
+    let options = LilypondOptions {
+        spelling: session
+            .key()
+            .map(Spelling::for_key)
+            .unwrap_or(options.spelling),
+        ..options.clone()
+    };
+    let voiced = session.voiced_chords();
+
+    let chord_mode: Vec<String> = chord_names(options.note_names)
+        .into_iter()
+        .map(str::to_string)
+        .chain(voiced.iter().map(|(chord, _)| chord_token(chord, &options)))
+        .collect();
+
+    let mut out = format!("\\version \"{}\"\n\n", LILYPOND_VERSION);
+
+    if !options.block_chords {
+        out.push_str(&format!("\\chords {{ {} }}\n", chord_mode.join(" ")));
+        return out;
+    }
+
+    let blocks: Vec<String> = voiced
+        .iter()
+        .map(|(_, notes)| block_token(notes, &options))
+        .collect();
+
+    out.push_str("<<\n");
+    out.push_str(&format!("  \\chords {{ {} }}\n", chord_mode.join(" ")));
+    out.push_str(&format!("  \\new Staff {{ {} }}\n", blocks.join(" ")));
+    out.push_str(">>\n");
+    out
+}
+
+pub fn chord_token(chord: &Chord, options: &LilypondOptions) -> String {
+    let mut token = format!(
+        "{}{}{}",
+        pitch_name(chord.root, options.spelling),
+        options.duration,
+        modifier(chord.quality)
+    );
+
+    if let Some(bass) = chord.bass {
+        if bass.pitch_class() != chord.root.pitch_class() {
+            token.push('/');
+            token.push_str(pitch_name(bass, options.spelling));
+        }
+    }
+
+    token
+}
+
+/// Switches Lilypond's chord names to the convention, which its input
+/// doesn't change: that's always in Dutch note names.
+fn chord_names(note_names: NoteNames) -> Option<&'static str> {
+    match note_names {
+        NoteNames::English => None,
+        NoteNames::German => Some("\\germanChords"),
+        NoteNames::Solfege => Some("\\italianChords"),
+    }
+}
+
+fn block_token(notes: &[u8], options: &LilypondOptions) -> String {
+    let pitches: Vec<String> = notes
+        .iter()
+        .map(|&midi| absolute_pitch(Note::new(midi), options.spelling))
+        .collect();
+    format!("<{}>{}", pitches.join(" "), options.duration)
+}
+
+fn pitch_name(note: Note, spelling: Spelling) -> &'static str {
+    match spelling {
+        Spelling::Sharp => SHARP_NAMES[note.pitch_class() as usize],
+        Spelling::Flat => FLAT_NAMES[note.pitch_class() as usize],
+    }
+}
+
+fn absolute_pitch(note: Note, spelling: Spelling) -> String {
+    let octave = note.octave() as i32 - 3;
+    let marks = if octave >= 0 {
+        "'".repeat(octave as usize)
+    } else {
+        ",".repeat((-octave) as usize)
+    };
+    format!("{}{}", pitch_name(note, spelling), marks)
+}
+
+fn modifier(quality: Quality) -> &'static str {
+    match quality {
+        Quality::Major | Quality::Unknown => "",
+        Quality::Minor => ":m",
+        Quality::Diminished => ":dim",
+        Quality::Augmented => ":aug",
+        Quality::Major7 => ":maj7",
+        Quality::Minor7 => ":m7",
+        Quality::Dominant7 => ":7",
+        Quality::Diminished7 => ":dim7",
+        Quality::HalfDim7 => ":m7.5-",
+        Quality::MinorMajor7 => ":m7+",
+        Quality::Augmented7 => ":aug7",
+        Quality::Major6 => ":6",
+        Quality::Minor6 => ":m6",
+        Quality::Sus2 => ":sus2",
+        Quality::Sus4 => ":sus4",
+        Quality::Quartal => ":sus4.7^5",
+        Quality::Add9 => ":5.9",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(names: &[&str]) -> Session {
+        let mut session = Session::new();
+        for name in names {
+            session.push(&Chord::from_name(name).unwrap());
+        }
+        session
+    }
+
+    #[test]
+    fn test_chord_mode() {
+        let out = render(
+            &session(&["C", "Am", "F", "G"]),
+            &LilypondOptions::default(),
+        );
+        assert!(out.starts_with("\\version"));
+        assert!(out.contains("\\chords { c1 a1:m f1 g1 }"));
+        assert!(!out.contains("Staff"));
+    }
+
+    #[test]
+    fn test_note_names() {
+        let options = LilypondOptions::default().note_names(NoteNames::German);
+        let out = render(&session(&["C", "Am"]), &options);
+        assert!(out.contains("\\chords { \\germanChords c1 a1:m }"));
+    }
+
+    #[test]
+    fn test_modifiers_and_slash() {
+        let options = LilypondOptions::default();
+        let chord = Chord::from_name("Bm7b5").unwrap();
+        assert_eq!(chord_token(&chord, &options), "b1:m7.5-");
+
+        let chord = Chord::from_name("C/E").unwrap();
+        assert_eq!(chord_token(&chord, &options), "c1/e");
+
+        let chord = Chord::from_name("Bb7").unwrap();
+        let flats = LilypondOptions::default().spelling(Spelling::Flat);
+        assert_eq!(chord_token(&chord, &flats), "bes1:7");
+    }
+
+    #[test]
+    fn test_block_chords() {
+        let mut session = Session::new();
+        session.push_voiced(&Chord::from_name("C").unwrap(), vec![48, 64, 67]);
+
+        let out = render(&session, &LilypondOptions::default().block_chords(true));
+        assert!(out.contains("\\new Staff { <c e' g'>1 }"));
+    }
+
+    #[test]
+    fn test_key_spelling() {
+        let mut session = session(&["Bb", "Eb"]);
+        session.set_key(Some(Note::new(65)));
+
+        let out = render(&session, &LilypondOptions::default());
+        assert!(out.contains("\\chords { bes1 es1 }"));
+    }
+}
@@ -0,0 +1,363 @@
+use crate::rhythm::{Accent, Quantizer, TimeSignature};
+use crate::session::Session;
+use crate::theory::bass::{self, BassStyle};
+use crate::theory::Note;
+
+const TICKS_PER_QUARTER: u16 = 480;
+const VELOCITY: u8 = 80;
+const BASS_PROGRAM: u8 = 32;
+const DEFAULT_BPM: f32 = 120.0;
+/// General MIDI high and low wood blocks, on the drum channel
+const CLICK_ACCENT: u8 = 76;
+const CLICK: u8 = 77;
+const CLICK_VELOCITY: (u8, u8) = (110, 70);
+/// Sharps (positive) or flats (negative) in each major key's signature,
+/// by tonic pitch class
+const KEY_SIGNATURES: [i8; 12] = [0, -5, 2, -3, 4, -1, 6, 1, -4, 3, -2, 5];
+
+#[derive(Clone, Debug)]
+pub struct MidiOptions {
+    pub bass: Option<BassStyle>,
+    pub quantize: Option<Quantizer>,
+    /// A metronome track on the drum channel
+    pub click: bool,
+}
+
+impl Default for MidiOptions {
+    fn default() -> Self {
+        Self {
+            bass: Some(BassStyle::default()),
+            quantize: None,
+            click: true,
+        }
+    }
+}
+
+impl MidiOptions {
+    pub fn bass(mut self, style: Option<BassStyle>) -> Self {
+        self.bass = style;
+        self
+    }
+
+    pub fn quantize(mut self, quantizer: Option<Quantizer>) -> Self {
+        self.quantize = quantizer;
+        self
+    }
+
+    pub fn click(mut self, click: bool) -> Self {
+        self.click = click;
+        self
+    }
+}
+
+/// Length of each chord in beats: quantized from the capture times when a
+/// quantizer is set and the session was timed, otherwise one bar each in
+/// the time signature it was played in.
+pub fn durations(session: &Session, quantizer: Option<&Quantizer>) -> Vec<f32> {
+    match (quantizer, session.onsets()) {
+        (Some(q), Some(onsets)) => {
+            let bar = (q.time_signature.quarters() * 60.0 / q.bpm) as f64;
+            let end = onsets.last().copied().unwrap_or_default() + bar;
+            q.durations(&onsets, end)
+        }
+        _ => (0..session.len())
+            .map(|i| session.time_signature_at(i).quarters())
+            .collect(),
+    }
+}
+
+/// Where each time signature takes over, in beats: the session's first at
+/// the top, then each change from the chord it was made before.
+fn meters(session: &Session, starts: &[f32]) -> Vec<(f32, TimeSignature)> {
+    let mut meters = vec![(0.0, session.time_signature_at(0))];
+    for change in session.meter.iter().filter(|m| m.from > 0) {
+        if let (Some(&at), Some(t)) = (
+            starts.get(change.from),
+            TimeSignature::from_name(&change.time_signature),
+        ) {
+            meters.push((at, t));
+        }
+    }
+    meters
+}
+
+/// A type 1 file: a conductor track with the tempo, time and key
+/// signatures, then a named track each for the chords, the bass line and
+/// the click, ready to import into a DAW as stems.
+pub fn render(session: &Session, options: &MidiOptions) -> Vec<u8> {
+    let voiced = session.voiced_chords();
+    let quarter = TICKS_PER_QUARTER as u32;
+    let ticks = |beats: f32| (beats * quarter as f32).round() as u32;
+
+    let lengths = durations(session, options.quantize.as_ref());
+    let starts: Vec<f32> = lengths
+        .iter()
+        .scan(0.0, |at, &length| {
+            let start = *at;
+            *at += length;
+            Some(start)
+        })
+        .collect();
+
+    let mut chords = Vec::new();
+    for (i, (_, notes)) in voiced.iter().enumerate() {
+        let (start, end) = (ticks(starts[i]), ticks(starts[i] + lengths[i]));
+        for &note in notes {
+            chords.push((start, [0x90, note, VELOCITY]));
+            chords.push((end, [0x80, note, 0]));
+        }
+    }
+
+    let bpm = options.quantize.map(|q| q.bpm).unwrap_or(DEFAULT_BPM);
+    let meters = meters(session, &starts);
+    let mut conductor = name("chordvery");
+    conductor.extend(time_signature(meters[0].1));
+    if let Some(key) = session.key() {
+        conductor.extend(key_signature(key));
+    }
+    conductor.extend(tempo(bpm));
+    let mut last = 0;
+    for &(at, meter) in &meters[1..] {
+        conductor.extend(vlq(ticks(at) - last));
+        conductor.extend(&time_signature(meter)[1..]);
+        last = ticks(at);
+    }
+    let mut tracks = vec![
+        track(&conductor, Vec::new()),
+        track(&name("Chords"), chords),
+    ];
+
+    if let Some(style) = options.bass {
+        let progression: Vec<_> = voiced.iter().map(|(chord, _)| chord.clone()).collect();
+        let mut line = vec![(0, [0xC1, BASS_PROGRAM, 0])];
+        for (i, notes) in bass::bass_lines(&progression, style).iter().enumerate() {
+            let beats = lengths[i].ceil() as usize;
+            for beat in 0..beats {
+                let note = match beat {
+                    0 => notes[0],
+                    b if b + 1 == beats => notes[3],
+                    b => notes[b.min(2)],
+                };
+                let start = starts[i] + beat as f32;
+                let end = (start + 1.0).min(starts[i] + lengths[i]);
+                line.push((ticks(start), [0x91, note, VELOCITY]));
+                line.push((ticks(end), [0x81, note, 0]));
+            }
+        }
+        tracks.push(track(&name("Bass"), line));
+    }
+
+    if options.click {
+        let total = lengths.iter().sum::<f32>();
+        let mut click = Vec::new();
+        for (i, &(from, meter)) in meters.iter().enumerate() {
+            let until = meters.get(i + 1).map(|&(at, _)| at).unwrap_or(total);
+            let pulses = meter.pulses();
+            let (mut pulse, mut at) = (0, from);
+            while at < until - 0.01 {
+                let (note, velocity) = match pulses[pulse % pulses.len()] {
+                    Accent::Downbeat => (CLICK_ACCENT, CLICK_VELOCITY.0),
+                    Accent::Secondary => (CLICK, CLICK_VELOCITY.0),
+                    Accent::Weak => (CLICK, CLICK_VELOCITY.1),
+                };
+                click.push((ticks(at), [0x99, note, velocity]));
+                click.push((ticks(at) + quarter / 4, [0x89, note, 0]));
+                pulse += 1;
+                at = from + pulse as f32 * meter.pulse();
+            }
+        }
+        tracks.push(track(&name("Click"), click));
+    }
+
+    let mut out = Vec::new();
+    out.extend_from_slice(b"MThd");
+    out.extend_from_slice(&6u32.to_be_bytes());
+    out.extend_from_slice(&1u16.to_be_bytes());
+    out.extend_from_slice(&(tracks.len() as u16).to_be_bytes());
+    out.extend_from_slice(&TICKS_PER_QUARTER.to_be_bytes());
+    for t in tracks {
+        out.extend(t);
+    }
+    out
+}
+
+fn name(name: &str) -> Vec<u8> {
+    let mut meta = vec![0x00, 0xFF, 0x03];
+    meta.extend(vlq(name.len() as u32));
+    meta.extend_from_slice(name.as_bytes());
+    meta
+}
+
+/// The denominator as a power of two, a metronome click every pulse.
+fn time_signature(meter: TimeSignature) -> Vec<u8> {
+    let power = meter.denominator().trailing_zeros() as u8;
+    let clocks = (24.0 * meter.pulse()) as u8;
+    vec![0x00, 0xFF, 0x58, 0x04, meter.numerator(), power, clocks, 8]
+}
+
+fn key_signature(tonic: Note) -> Vec<u8> {
+    let sharps = KEY_SIGNATURES[tonic.pitch_class() as usize];
+    vec![0x00, 0xFF, 0x59, 0x02, sharps as u8, 0]
+}
+
+fn tempo(bpm: f32) -> Vec<u8> {
+    let microseconds = (60_000_000.0 / bpm).round() as u32;
+    let mut meta = vec![0x00, 0xFF, 0x51, 0x03];
+    meta.extend_from_slice(&microseconds.to_be_bytes()[1..]);
+    meta
+}
+
+fn track(prelude: &[u8], mut events: Vec<(u32, [u8; 3])>) -> Vec<u8> {
+    // Note-offs sort before note-ons on the same tick so repeated notes retrigger
+    events.sort_by_key(|(tick, message)| (*tick, message[0] & 0xF0 != 0x80));
+
+    let mut body = prelude.to_vec();
+    let mut last = 0;
+    for (tick, message) in events {
+        body.extend(vlq(tick - last));
+        let len = if message[0] & 0xF0 == 0xC0 { 2 } else { 3 };
+        body.extend_from_slice(&message[..len]);
+        last = tick;
+    }
+    body.extend_from_slice(&[0x00, 0xFF, 0x2F, 0x00]);
+
+    let mut out = Vec::new();
+    out.extend_from_slice(b"MTrk");
+    out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    out.extend(body);
+    out
+}
+
+fn vlq(mut value: u32) -> Vec<u8> {
+    let mut bytes = vec![(value & 0x7F) as u8];
+    value >>= 7;
+    while value > 0 {
+        bytes.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.reverse();
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::Chord;
+
+    fn session() -> Session {
+        let mut session = Session::new();
+        session.push_voiced(&Chord::from_name("Dm7").unwrap(), vec![50, 60, 65, 69]);
+        session.push_voiced(&Chord::from_name("G7").unwrap(), vec![43, 59, 65, 67]);
+        session
+    }
+
+    #[test]
+    fn test_vlq() {
+        assert_eq!(vlq(0), vec![0x00]);
+        assert_eq!(vlq(0x7F), vec![0x7F]);
+        assert_eq!(vlq(480), vec![0x83, 0x60]);
+        assert_eq!(vlq(0x0FFFFFFF), vec![0xFF, 0xFF, 0xFF, 0x7F]);
+    }
+
+    #[test]
+    fn test_header_and_tracks() {
+        let bytes = render(&session(), &MidiOptions::default());
+        assert_eq!(&bytes[..4], b"MThd");
+        assert_eq!(u16::from_be_bytes([bytes[10], bytes[11]]), 4);
+        assert_eq!(bytes.windows(4).filter(|w| w == b"MTrk").count(), 4);
+
+        let chords_only = render(&session(), &MidiOptions::default().bass(None).click(false));
+        assert_eq!(u16::from_be_bytes([chords_only[10], chords_only[11]]), 2);
+    }
+
+    #[test]
+    fn test_track_names_and_signatures() {
+        let has = |bytes: &[u8], wanted: &[u8]| bytes.windows(wanted.len()).any(|w| w == wanted);
+        let mut session = session();
+        let bytes = render(&session, &MidiOptions::default());
+        for track in ["Chords", "Bass", "Click"] {
+            let mut meta = vec![0xFF, 0x03, track.len() as u8];
+            meta.extend_from_slice(track.as_bytes());
+            assert!(has(&bytes, &meta), "{}", track);
+        }
+        assert!(has(&bytes, &[0xFF, 0x58, 0x04, 4, 2]));
+        assert!(!has(&bytes, &[0xFF, 0x59]));
+
+        session.set_key(Some(Note::new(65)));
+        let bytes = render(&session, &MidiOptions::default());
+        assert!(has(&bytes, &[0xFF, 0x59, 0x02, (-1i8) as u8, 0]));
+        assert_eq!(key_signature(Note::new(66))[4], 6);
+    }
+
+    #[test]
+    fn test_click_track() {
+        let bytes = render(&session(), &MidiOptions::default());
+        let hits: Vec<u8> = bytes
+            .windows(3)
+            .filter(|w| w[0] == 0x99)
+            .map(|w| w[1])
+            .collect();
+        // Two bars, accented on each downbeat
+        assert_eq!(hits.len(), 8);
+        assert_eq!(hits.iter().filter(|&&n| n == CLICK_ACCENT).count(), 2);
+    }
+
+    #[test]
+    fn test_time_signature_changes() {
+        let has = |bytes: &[u8], wanted: &[u8]| bytes.windows(wanted.len()).any(|w| w == wanted);
+        let mut session = Session::new();
+        session.set_time_signature(TimeSignature::SixEight);
+        session.push(&Chord::from_name("C").unwrap());
+        session.set_time_signature(TimeSignature::FiveFour);
+        session.push(&Chord::from_name("F").unwrap());
+        assert_eq!(durations(&session, None), vec![3.0, 5.0]);
+
+        let bytes = render(&session, &MidiOptions::default().bass(None));
+        assert!(has(&bytes, &[0x00, 0xFF, 0x58, 0x04, 6, 3, 12, 8]));
+        // 5/4 from the F, three quarter notes in
+        assert!(has(&bytes, &[0x8B, 0x20, 0xFF, 0x58, 0x04, 5, 2, 24, 8]));
+
+        // Six eighths then five quarters, accented 3+3 and 3+2
+        let hits: Vec<(u8, u8)> = bytes
+            .windows(3)
+            .filter(|w| w[0] == 0x99)
+            .map(|w| (w[1], w[2]))
+            .collect();
+        assert_eq!(hits.len(), 11);
+        let strong: Vec<usize> = (0..hits.len())
+            .filter(|&i| hits[i].1 == CLICK_VELOCITY.0)
+            .collect();
+        assert_eq!(strong, [0, 3, 6, 9]);
+        assert_eq!(hits[6].0, CLICK_ACCENT);
+    }
+
+    #[test]
+    fn test_quantized_durations() {
+        let mut session = Session::new();
+        let c = Chord::from_name("C").unwrap();
+        let f = Chord::from_name("F").unwrap();
+        session.push_timed(&c, c.notes(), 0.0);
+        session.push_timed(&f, f.notes(), 1.05);
+        session.push_timed(&c, c.notes(), 1.9);
+
+        let quantizer = Quantizer::new(120.0);
+        assert_eq!(durations(&session, Some(&quantizer)), vec![2.0, 2.0, 4.0]);
+        assert_eq!(durations(&session, None), vec![4.0; 3]);
+
+        // Untimed sessions fall back to a bar per chord
+        assert_eq!(durations(&self::session(), Some(&quantizer)), vec![4.0; 2]);
+
+        let bytes = render(
+            &session,
+            &MidiOptions::default().quantize(Some(quantizer.feel(crate::rhythm::Feel::Swing))),
+        );
+        assert_eq!(bytes.windows(2).filter(|w| w[0] == 0x91).count(), 8);
+    }
+
+    #[test]
+    fn test_bass_notes_on_channel_two() {
+        let bytes = render(&session(), &MidiOptions::default());
+        let bass_on = bytes.windows(2).filter(|w| w[0] == 0x91).count();
+        assert_eq!(bass_on, 8);
+    }
+}
@@ -0,0 +1,50 @@
+pub mod lilypond;
+pub mod midi;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::session::Session;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Lilypond,
+    Midi,
+}
+
+impl ExportFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Lilypond => "Lilypond",
+            ExportFormat::Midi => "MIDI",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Lilypond => "ly",
+            ExportFormat::Midi => "mid",
+        }
+    }
+}
+
+pub fn render(session: &Session, format: ExportFormat) -> Vec<u8> {
+    match format {
+        ExportFormat::Lilypond => {
+            let options = lilypond::LilypondOptions::default().block_chords(true);
+            lilypond::render(session, &options).into_bytes()
+        }
+        ExportFormat::Midi => midi::render(session, &midi::MidiOptions::default()),
+    }
+}
+
+pub fn write(session: &Session, format: ExportFormat, path: &Path) -> Result<()> {
+    fs::write(path, render(session, format))?;
+    Ok(())
+}
+
+pub fn default_path(format: ExportFormat, timestamp: u64) -> PathBuf {
+    PathBuf::from(format!("chordvery-{}.{}", timestamp, format.extension()))
+}
@@ -0,0 +1,277 @@
+use std::collections::HashSet;
+
+use super::tuning::Tuning;
+use crate::theory::Chord;
+
+/// Highest fret searched, counted from the capo
+const MAX_FRET: u8 = 12;
+/// Frets a hand covers without stretching
+const SPAN: u8 = 3;
+const MAX_FINGERS: usize = 4;
+/// Open strings only ring out next to low positions
+const OPEN_WITH_BASE: u8 = 5;
+/// Grips offered per chord
+const LIMIT: usize = 8;
+
+/// One way of fretting a chord: a fret for each string from the lowest,
+/// None for a muted string. Frets count from the capo.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Fingering {
+    pub frets: Vec<Option<u8>>,
+    /// Fret the index finger lays across several strings
+    pub barre: Option<u8>,
+    /// What a guitarist would call the grip: "open", "E shape, fret 1"
+    pub label: String,
+}
+
+impl Fingering {
+    /// Tab-style notation from the lowest string, `x32010`; frets past 9
+    /// are separated with dashes.
+    pub fn tab(&self) -> String {
+        let marks: Vec<String> = self
+            .frets
+            .iter()
+            .map(|f| f.map_or("x".to_string(), |f| f.to_string()))
+            .collect();
+        if self.frets.iter().flatten().any(|&f| f > 9) {
+            marks.join("-")
+        } else {
+            marks.concat()
+        }
+    }
+
+    pub fn lowest_fret(&self) -> Option<u8> {
+        self.frets
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|&f| f > 0)
+            .min()
+    }
+
+    pub fn highest_fret(&self) -> u8 {
+        self.frets.iter().flatten().copied().max().unwrap_or(0)
+    }
+
+    fn fingers(&self) -> usize {
+        let fretted = self.frets.iter().flatten().filter(|&&f| f > 0).count();
+        match self.barre {
+            Some(barre) => 1 + self.frets.iter().flatten().filter(|&&f| f > barre).count(),
+            None => fretted,
+        }
+    }
+
+    /// Lower is easier: few fingers, a small stretch, a low position and
+    /// as many strings ringing as possible.
+    fn difficulty(&self) -> i32 {
+        let played = self.frets.iter().flatten().count() as i32;
+        let span = self
+            .lowest_fret()
+            .map_or(0, |low| self.highest_fret() - low) as i32;
+        self.fingers() as i32 * 2 + span * 2 + self.highest_fret() as i32 - played * 3
+            + if self.barre.is_some() { 2 } else { 0 }
+    }
+}
+
+/// Playable grips for `chord` on `tuning` with a capo at `capo`, easiest
+/// first. A grip plays only chord tones, starts on the bass note, covers
+/// every tone (the fifth may go in seventh chords), mutes strings only on
+/// the low side, stays within a four-fret span and needs at most four
+/// fingers, a barre counting as one.
+pub fn fingerings(chord: &Chord, tuning: &Tuning, capo: u8) -> Vec<Fingering> {
+    let open: Vec<u8> = tuning
+        .strings
+        .iter()
+        .map(|s| (s.pitch_class() + capo) % 12)
+        .collect();
+    let tones = chord.pitch_classes();
+    let fifth = (chord.root.pitch_class() + 7) % 12;
+    let required: HashSet<u8> = if tones.len() >= 4 {
+        tones.iter().copied().filter(|&pc| pc != fifth).collect()
+    } else {
+        tones.clone()
+    };
+    let bass = chord.bass.unwrap_or(chord.root).pitch_class();
+    let min_played = (open.len().min(6).saturating_sub(2)).max(3);
+
+    let mut found: HashSet<Vec<Option<u8>>> = HashSet::new();
+    let mut grips = Vec::new();
+    for base in 1..=MAX_FRET - SPAN {
+        let choices: Vec<Vec<u8>> = open
+            .iter()
+            .map(|&pc| {
+                let open_ok = base <= OPEN_WITH_BASE && tones.contains(&pc);
+                open_ok
+                    .then_some(0)
+                    .into_iter()
+                    .chain((base..=base + SPAN).filter(|f| tones.contains(&((pc + f) % 12))))
+                    .collect()
+            })
+            .collect();
+
+        for muted in 0..=open.len().saturating_sub(min_played) {
+            let mut frets = vec![None; muted];
+            search(&open, &choices, &mut frets, &mut |frets| {
+                let sounding: Vec<u8> = frets
+                    .iter()
+                    .zip(&open)
+                    .filter_map(|(f, pc)| f.map(|f| (pc + f) % 12))
+                    .collect();
+                if sounding.first() != Some(&bass)
+                    || !required.iter().all(|pc| sounding.contains(pc))
+                    || found.contains(frets)
+                {
+                    return;
+                }
+                let fingering = finish(chord, tuning, frets.to_vec());
+                if fingering.fingers() <= MAX_FINGERS {
+                    found.insert(frets.to_vec());
+                    grips.push(fingering);
+                }
+            });
+        }
+    }
+
+    grips.sort_by_key(|g| (g.difficulty(), g.lowest_fret()));
+    grips.truncate(LIMIT);
+    grips
+}
+
+fn search(
+    open: &[u8],
+    choices: &[Vec<u8>],
+    frets: &mut Vec<Option<u8>>,
+    found: &mut impl FnMut(&[Option<u8>]),
+) {
+    let string = frets.len();
+    if string == open.len() {
+        found(frets);
+        return;
+    }
+    for &fret in &choices[string] {
+        frets.push(Some(fret));
+        search(open, choices, frets, found);
+        frets.pop();
+    }
+}
+
+/// Works out the barre and the grip's name.
+fn finish(chord: &Chord, tuning: &Tuning, frets: Vec<Option<u8>>) -> Fingering {
+    let mut fingering = Fingering {
+        frets,
+        barre: None,
+        label: String::new(),
+    };
+    let Some(low) = fingering.lowest_fret() else {
+        fingering.label = "open".to_string();
+        return fingering;
+    };
+
+    // The index finger can lie across everything from the first string
+    // stopped at the lowest fret up to the top, if nothing there rings open
+    let first = fingering.frets.iter().position(|&f| f == Some(low));
+    let at_low = fingering.frets.iter().filter(|&&f| f == Some(low)).count();
+    let covered = first.is_some_and(|first| {
+        fingering.frets[first..]
+            .iter()
+            .all(|f| f.is_some_and(|f| f >= low))
+    });
+    if at_low >= 2 && covered {
+        fingering.barre = Some(low);
+    }
+
+    let has_open = fingering.frets.contains(&Some(0));
+    fingering.label = if has_open && fingering.highest_fret() <= 4 {
+        "open".to_string()
+    } else if has_open {
+        format!("fret {}", low)
+    } else {
+        let shape = chord.capo_shape(low);
+        let caged = *tuning == Tuning::standard() && "CAGED".contains(shape.root.name());
+        match fingering.barre {
+            Some(_) if caged => format!("{} shape, fret {}", shape.root.name(), low),
+            Some(_) => format!("barre, fret {}", low),
+            None => format!("fret {}", low),
+        }
+    };
+    fingering
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tabs(name: &str, tuning: &Tuning, capo: u8) -> Vec<String> {
+        let chord = Chord::from_name(name).unwrap();
+        fingerings(&chord, tuning, capo)
+            .iter()
+            .map(|f| f.tab())
+            .collect()
+    }
+
+    #[test]
+    fn test_open_chords_come_first() {
+        let standard = Tuning::standard();
+        assert_eq!(tabs("C", &standard, 0)[0], "x32010");
+        assert_eq!(tabs("G", &standard, 0)[0], "320003");
+        assert_eq!(tabs("D", &standard, 0)[0], "xx0232");
+        assert_eq!(tabs("Am", &standard, 0)[0], "x02210");
+        assert_eq!(tabs("E", &standard, 0)[0], "022100");
+    }
+
+    #[test]
+    fn test_barre_shapes() {
+        let standard = Tuning::standard();
+        let f = fingerings(&Chord::from_name("F").unwrap(), &standard, 0);
+        assert_eq!(f[0].tab(), "133211");
+        assert_eq!(f[0].barre, Some(1));
+        assert_eq!(f[0].label, "E shape, fret 1");
+
+        let bb = fingerings(&Chord::from_name("Bb").unwrap(), &standard, 0);
+        let a_shape = bb.iter().find(|g| g.tab() == "x13331").unwrap();
+        assert_eq!(a_shape.label, "A shape, fret 1");
+    }
+
+    #[test]
+    fn test_grips_are_playable() {
+        let standard = Tuning::standard();
+        for name in ["C", "Dm7", "G7", "Bbmaj7", "F#m", "C/E", "Ebdim7"] {
+            let chord = Chord::from_name(name).unwrap();
+            let grips = fingerings(&chord, &standard, 0);
+            assert!(!grips.is_empty(), "{}", name);
+            for grip in grips {
+                assert!(grip.fingers() <= MAX_FINGERS, "{} {}", name, grip.tab());
+                let lowest = grip
+                    .frets
+                    .iter()
+                    .zip(&standard.strings)
+                    .find_map(|(f, s)| f.map(|f| s.midi + f));
+                let bass = chord.bass.unwrap_or(chord.root).pitch_class();
+                assert_eq!(lowest.unwrap() % 12, bass, "{} {}", name, grip.tab());
+            }
+        }
+    }
+
+    #[test]
+    fn test_capo_and_tunings() {
+        // With the capo on 2 a sounding D is fingered as a C shape
+        assert_eq!(tabs("D", &Tuning::standard(), 2)[0], "x32010");
+
+        let drop_d = Tuning::preset("drop-d").unwrap();
+        assert!(tabs("D", &drop_d, 0).contains(&"000232".to_string()));
+
+        let bass = Tuning::preset("bass").unwrap();
+        assert!(!tabs("G", &bass, 0).is_empty());
+        assert_eq!(tabs("G", &bass, 0)[0].len(), 4);
+    }
+
+    #[test]
+    fn test_tab_notation() {
+        let grip = Fingering {
+            frets: vec![Some(8), Some(10), Some(10), Some(9), Some(8), Some(8)],
+            barre: Some(8),
+            label: String::new(),
+        };
+        assert_eq!(grip.tab(), "8-10-10-9-8-8");
+    }
+}
@@ -0,0 +1,5 @@
+pub mod fingering;
+pub mod tuning;
+
+pub use fingering::{fingerings, Fingering};
+pub use tuning::Tuning;
@@ -0,0 +1,142 @@
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use crate::theory::Note;
+
+/// Open-string pitches of a fretted instrument, lowest string first.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Tuning {
+    pub name: String,
+    pub strings: Vec<Note>,
+}
+
+const PRESETS: [(&str, &[u8]); 8] = [
+    ("standard", &[40, 45, 50, 55, 59, 64]),
+    ("drop-d", &[38, 45, 50, 55, 59, 64]),
+    ("dadgad", &[38, 45, 50, 55, 57, 62]),
+    ("open-g", &[38, 43, 50, 55, 59, 62]),
+    ("7-string", &[35, 40, 45, 50, 55, 59, 64]),
+    ("8-string", &[30, 35, 40, 45, 50, 55, 59, 64]),
+    ("bass", &[28, 33, 38, 43]),
+    ("5-string-bass", &[23, 28, 33, 38, 43]),
+];
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl Tuning {
+    pub fn standard() -> Self {
+        Self::preset("standard").expect("standard tuning is a preset")
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        let (name, midi) = PRESETS.iter().find(|(n, _)| *n == name)?;
+        Some(Self {
+            name: name.to_string(),
+            strings: midi.iter().map(|&m| Note::new(m)).collect(),
+        })
+    }
+
+    pub fn preset_names() -> impl Iterator<Item = &'static str> {
+        PRESETS.iter().map(|(name, _)| *name)
+    }
+
+    /// A preset name, or open-string notes from low to high such as
+    /// `C2 G2 D3 G3 B3 D4`.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        if let Some(tuning) = Self::preset(&text.to_lowercase()) {
+            return Ok(tuning);
+        }
+
+        let strings = text
+            .split_whitespace()
+            .map(|name| {
+                Note::from_name(name).ok_or_else(|| {
+                    anyhow!(
+                        "Unknown tuning '{}': use one of {} or notes like E2 A2 D3",
+                        text,
+                        Self::preset_names().collect::<Vec<_>>().join(", ")
+                    )
+                })
+            })
+            .collect::<Result<Vec<Note>>>()?;
+        if strings.len() < 4 {
+            return Err(anyhow!("Tuning '{}' needs at least four strings", text));
+        }
+        if strings.windows(2).any(|w| w[0].midi > w[1].midi + 12) {
+            return Err(anyhow!("Tuning '{}' should go from low to high", text));
+        }
+        Ok(Self {
+            name: text.to_string(),
+            strings,
+        })
+    }
+
+    pub fn string_count(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// The note sounding on `string` (0 is the lowest) stopped at `fret`.
+    pub fn note_at(&self, string: usize, fret: u8) -> Option<Note> {
+        self.strings.get(string)?.transpose(fret as i8)
+    }
+}
+
+impl TryFrom<String> for Tuning {
+    type Error = anyhow::Error;
+
+    fn try_from(text: String) -> Result<Self> {
+        Self::parse(&text)
+    }
+}
+
+impl fmt::Display for Tuning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        for name in Tuning::preset_names() {
+            let tuning = Tuning::parse(name).unwrap();
+            assert!(tuning.string_count() >= 4, "{}", name);
+        }
+        let standard = Tuning::default();
+        assert_eq!(standard.string_count(), 6);
+        assert_eq!(standard.note_at(0, 3), Some(Note::new(43)));
+        assert_eq!(standard.note_at(6, 0), None);
+
+        let dadgad: Vec<&str> = Tuning::parse("DADGAD")
+            .unwrap()
+            .strings
+            .iter()
+            .map(|n| n.name())
+            .collect();
+        assert_eq!(dadgad, ["D", "A", "D", "G", "A", "D"]);
+        assert_eq!(Tuning::parse("bass").unwrap().strings[0], Note::new(28));
+    }
+
+    #[test]
+    fn test_custom() {
+        let open_c = Tuning::parse("C2 G2 C3 G3 C4 E4").unwrap();
+        assert_eq!(open_c.strings[0], Note::new(36));
+        assert_eq!(open_c.to_string(), "C2 G2 C3 G3 C4 E4");
+
+        assert!(Tuning::parse("E2 A2 D3").is_err());
+        assert!(Tuning::parse("E4 A2 D3 G3").is_err());
+        let err = Tuning::parse("nashville").unwrap_err().to_string();
+        assert!(err.contains("drop-d"));
+    }
+}
@@ -0,0 +1,309 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use crate::config::HooksConfig;
+use crate::practice::Milestone;
+use crate::session::Event;
+
+#[derive(Clone, Debug, Default)]
+pub struct Hooks {
+    config: HooksConfig,
+    pipe: Option<Pipe>,
+}
+
+impl Hooks {
+    pub fn new(config: HooksConfig) -> Self {
+        let pipe = config.pipe.clone().map(Pipe::open);
+        Self { config, pipe }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.config == HooksConfig::default()
+    }
+
+    pub fn command_for(&self, event: &Event) -> Option<String> {
+        let template = match event {
+            Event::Chord { .. } => self.config.on_chord.as_ref(),
+            Event::Key { .. } => self.config.on_key.as_ref(),
+            Event::Mode { .. } => self.config.on_mode.as_ref(),
+            Event::Clear => self.config.on_clear.as_ref(),
+            _ => None,
+        }?;
+        Some(expand(template, &variables(event)))
+    }
+
+    pub fn fire(&self, event: &Event) {
+        if let Some(command) = self.command_for(event) {
+            run(command);
+        }
+
+        if let Some(pipe) = &self.pipe {
+            if !matches!(event, Event::NoteOn { .. } | Event::NoteOff { .. }) {
+                if let Ok(line) = serde_json::to_string(event) {
+                    pipe.send(line);
+                }
+            }
+        }
+    }
+
+    pub fn milestone(&self, milestone: &Milestone) {
+        if let Some(command) = self.milestone_command(milestone) {
+            run(command);
+        }
+        if self.config.notify {
+            run(notification(&milestone.message()));
+        }
+    }
+
+    pub fn milestone_command(&self, milestone: &Milestone) -> Option<String> {
+        let template = self.config.on_milestone.as_ref()?;
+        Some(expand(
+            template,
+            &[
+                ("milestone", milestone.message()),
+                ("kind", milestone.kind().to_string()),
+            ],
+        ))
+    }
+
+    pub fn session_start(&self) {
+        if let Some(command) = &self.config.on_session_start {
+            run(command.clone());
+        }
+    }
+
+    pub fn session_end(&self) {
+        if let Some(command) = &self.config.on_session_end {
+            run(command.clone());
+        }
+    }
+}
+
+fn variables(event: &Event) -> Vec<(&'static str, String)> {
+    match event {
+        Event::Chord { name, notes } => vec![
+            ("chord", name.clone()),
+            (
+                "notes",
+                notes
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+        ],
+        Event::Key { key } => vec![("key", key.clone().unwrap_or_default())],
+        Event::Mode { mode } => vec![("mode", mode.clone())],
+        Event::TimeSignature { time_signature } => {
+            vec![("time_signature", time_signature.clone())]
+        }
+        Event::Suggestion {
+            expected,
+            surprise,
+            followed,
+        } => vec![
+            ("expected", expected.clone()),
+            ("surprise", surprise.clone()),
+            ("followed", followed.name().to_string()),
+        ],
+        Event::NoteOn { note } | Event::NoteOff { note } => vec![("note", note.to_string())],
+        Event::Bookmark { note } => vec![("note", note.clone().unwrap_or_default())],
+        Event::Clear => Vec::new(),
+    }
+}
+
+/// A command that pops up a desktop notification.
+fn notification(message: &str) -> String {
+    if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification \"{}\" with title \"chordvery\"",
+            message.replace(['\\', '"'], "")
+        );
+        format!("osascript -e {}", shell_quote(&script))
+    } else {
+        format!("notify-send chordvery {}", shell_quote(message))
+    }
+}
+
+fn expand(template: &str, variables: &[(&str, String)]) -> String {
+    variables
+        .iter()
+        .fold(template.to_string(), |acc, (name, value)| {
+            acc.replace(&format!("{{{}}}", name), &shell_quote(value))
+        })
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn run(command: String) {
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+
+    if let Ok(mut child) = child {
+        thread::spawn(move || child.wait());
+    }
+}
+
+/// Events written to a named pipe, one JSON line each, from a thread of
+/// its own. Lines are dropped while nothing reads the pipe or the reader
+/// falls behind, rather than held up for it.
+#[derive(Clone, Debug)]
+struct Pipe(Sender<String>);
+
+impl Pipe {
+    fn open(path: PathBuf) -> Self {
+        let (lines, received) = mpsc::channel::<String>();
+        thread::spawn(move || {
+            let mut pipe = None;
+            for line in received {
+                if pipe.is_none() {
+                    pipe = open_pipe(&path).ok();
+                }
+                let Some(file) = &mut pipe else {
+                    continue;
+                };
+                // Short lines go through whole or not at all
+                match file.write_all(format!("{}\n", line).as_bytes()) {
+                    Err(e) if e.kind() != ErrorKind::WouldBlock => pipe = None,
+                    _ => {}
+                }
+            }
+        });
+        Self(lines)
+    }
+
+    fn send(&self, line: String) {
+        let _ = self.0.send(line);
+    }
+}
+
+/// Opens without waiting for a reader, failing if there isn't one.
+#[cfg(unix)]
+fn open_pipe(path: &Path) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    OpenOptions::new()
+        .append(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn open_pipe(path: &Path) -> io::Result<File> {
+    OpenOptions::new().append(true).open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hooks() -> Hooks {
+        Hooks::new(HooksConfig {
+            on_chord: Some("notify-send {chord} -- {notes}".to_string()),
+            on_key: Some("echo key={key}".to_string()),
+            ..HooksConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_templated_command() {
+        let event = Event::Chord {
+            name: "C#m7".to_string(),
+            notes: vec![49, 52, 56, 59],
+        };
+        assert_eq!(
+            hooks().command_for(&event).as_deref(),
+            Some("notify-send 'C#m7' -- '49 52 56 59'")
+        );
+
+        let event = Event::Key {
+            key: Some("Eb".to_string()),
+        };
+        assert_eq!(
+            hooks().command_for(&event).as_deref(),
+            Some("echo key='Eb'")
+        );
+    }
+
+    #[test]
+    fn test_unconfigured_events() {
+        assert!(hooks().command_for(&Event::Clear).is_none());
+        assert!(hooks().command_for(&Event::NoteOn { note: 60 }).is_none());
+        assert!(Hooks::default().is_empty());
+    }
+
+    #[test]
+    fn test_milestone_command() {
+        let hooks = Hooks::new(HooksConfig {
+            on_milestone: Some("echo {kind}: {milestone}".to_string()),
+            ..HooksConfig::default()
+        });
+        assert_eq!(
+            hooks.milestone_command(&Milestone::Streak(4)).as_deref(),
+            Some("echo 'streak': '4-day practice streak'")
+        );
+        assert!(Hooks::default()
+            .milestone_command(&Milestone::Streak(4))
+            .is_none());
+        if cfg!(target_os = "linux") {
+            assert_eq!(notification("it's"), r"notify-send chordvery 'it'\''s'");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipe() {
+        use std::io::{BufRead, BufReader};
+        use std::time::Duration;
+
+        let path = std::env::temp_dir().join(format!("chordvery-hooks-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let made = Command::new("mkfifo").arg(&path).status().unwrap();
+        assert!(made.success());
+        let hooks = Hooks::new(HooksConfig {
+            pipe: Some(path.clone()),
+            ..HooksConfig::default()
+        });
+
+        // Nothing's reading yet, so this one is dropped
+        hooks.fire(&Event::Clear);
+        thread::sleep(Duration::from_millis(50));
+
+        let reader = {
+            let path = path.clone();
+            thread::spawn(move || {
+                let mut line = String::new();
+                BufReader::new(File::open(&path).unwrap())
+                    .read_line(&mut line)
+                    .unwrap();
+                line
+            })
+        };
+        let event = Event::Mode {
+            mode: "Jam".to_string(),
+        };
+        while !reader.is_finished() {
+            hooks.fire(&event);
+            thread::sleep(Duration::from_millis(10));
+        }
+        let expected = format!("{}\n", serde_json::to_string(&event).unwrap());
+        assert_eq!(reader.join().unwrap(), expected);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+}
@@ -1,3 +1,29 @@
+// Only the theory engine builds for the browser; the rest needs a terminal,
+// MIDI ports and a file system.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod clock;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod config;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod export;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod guitar;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod hooks;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod midi;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod monitor;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod paths;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod practice;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod rhythm;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod session;
 pub mod theory;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod ui;
+#[cfg(feature = "wasm")]
+pub mod wasm;
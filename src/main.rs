@@ -1,16 +1,31 @@
-use std::io;
-use std::time::Duration;
-
-use anyhow::Result;
-use clap::Parser;
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{backend::CrosstermBackend, Terminal};
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use signal_hook::consts::{SIGINT, SIGTERM};
 
-use chordvery::midi::MidiInput;
+use chordvery::config::Config;
+use chordvery::hooks::Hooks;
+use chordvery::midi::device::parse_range;
+use chordvery::midi::{
+    Demo, DemoSong, DeviceProfiles, DeviceSettings, Groove, Humanize, MidiFile, MidiInput,
+    MidiOutput, VelocityCurve,
+};
+use chordvery::monitor::Monitor;
+use chordvery::paths::{self, UserDirs};
+use chordvery::rhythm::{Feel, Resolution, TimeSignature};
+use chordvery::session::{Autosave, SessionWriter};
+use chordvery::theory::{keys, Chord, NamingStyle};
+use chordvery::ui::controls::ControlMap;
+use chordvery::ui::frontend::{self, Frontend, TerminalFrontend};
+#[cfg(feature = "gui")]
+use chordvery::ui::gui::GuiFrontend;
 use chordvery::ui::App;
 
 #[derive(Parser)]
@@ -18,15 +33,199 @@ use chordvery::ui::App;
 #[command(about = "TUI chord finder with MIDI input and progression suggestions")]
 #[command(version)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// MIDI port index (default: first available)
     #[arg(short, long)]
     port: Option<usize>,
 
+    /// Second MIDI input port index for duet mode
+    #[arg(long, value_name = "PORT")]
+    partner_port: Option<usize>,
+
+    /// MIDI output port index for playback
+    #[arg(short, long)]
+    out_port: Option<usize>,
+
+    /// Send MIDI Start, Clock and Stop on the output port while the looper
+    /// runs, so a drum machine or DAW follows the tempo
+    #[arg(long)]
+    send_clock: bool,
+
+    /// Only listen to this MIDI channel (1-16, 0 for all); remembered per device
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=16))]
+    channel: Option<u8>,
+
+    /// Transpose incoming notes by semitones; remembered per device
+    #[arg(long, allow_hyphen_values = true, value_parser = clap::value_parser!(i8).range(-48..=48))]
+    transpose: Option<i8>,
+
+    /// Ignore notes outside this range, e.g. 36-96; remembered per device
+    #[arg(long, value_name = "LOW-HIGH")]
+    note_range: Option<String>,
+
+    /// Ignore note-ons softer than this velocity; remembered per device
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=127))]
+    velocity_floor: Option<u8>,
+
+    /// Reshape note-on velocities: linear, soft or hard (press `Y` to
+    /// calibrate one to your playing); remembered per device
+    #[arg(long, value_name = "CURVE")]
+    velocity_curve: Option<String>,
+
+    /// Follow pitch bends with this range in semitones (48 for MPE, 0 to
+    /// ignore them), reading bent notes as the nearest semitone; remembered
+    /// per device
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=96))]
+    bend_range: Option<u8>,
+
     /// List available MIDI ports
     #[arg(short, long)]
     list: bool,
+
+    /// Chord naming style: plain, jazz, short, unicode
+    #[arg(short, long, default_value = "plain")]
+    naming: String,
+
+    /// Configuration file (default: config.toml in the config directory)
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Load the progression transition table from a JSON file
+    #[arg(long, value_name = "FILE")]
+    progression_model: Option<PathBuf>,
+
+    /// Print the active progression model as JSON and exit
+    #[arg(long)]
+    dump_progression_model: bool,
+
+    /// Tempo in BPM (tap `t` to change it while playing)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(40..=240))]
+    bpm: Option<u16>,
+
+    /// Estimate the tempo from the timing of chord changes
+    #[arg(long)]
+    auto_tempo: bool,
+
+    /// Quantize grid for MIDI export once the tempo is known (bar, beat, eighth)
+    #[arg(long, default_value = "beat")]
+    quantize: String,
+
+    /// Swing the eighth-note quantize grid
+    #[arg(long)]
+    swing: bool,
+
+    /// Length of the loop recorder in bars
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..=16))]
+    loop_bars: u32,
+
+    /// Time signature of the looper, click and export (4/4, 3/4, 6/8, 5/4, 7/8)
+    #[arg(long, default_value = "4/4")]
+    time_signature: String,
+
+    /// Comp progression playback and loops to a groove (pad, pop eighths,
+    /// bossa, waltz, gospel or one of your own in grooves.toml)
+    #[arg(long)]
+    groove: Option<String>,
+
+    /// Humanize auditioned and played-back chords (0-100)
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=100))]
+    humanize: u8,
+
+    /// Play humanized chords as block chords instead of rolling them
+    #[arg(long)]
+    no_roll: bool,
+
+    /// Keep chords detected with less confidence out of the history (0-100)
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=100))]
+    min_confidence: u8,
+
+    /// Open the saved-session browser on startup
+    #[arg(long)]
+    sessions: bool,
+
+    /// Open in a window instead of the terminal
+    #[cfg(feature = "gui")]
+    #[arg(long)]
+    gui: bool,
+
+    /// Play a demo song through the app instead of listening for MIDI
+    /// input: pop (the default), jazz, blues or canon. Nothing is saved
+    #[arg(long, value_name = "SONG", num_args = 0..=1, default_missing_value = "pop")]
+    demo: Option<String>,
+
+    /// Suggest with a Rhai script in the config scripts directory
+    #[arg(long, value_name = "NAME")]
+    script: Option<String>,
+
+    /// User profile: whose config, stats and sessions to use (default: the
+    /// shared ones)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// How adventurous the suggestions are, in percent (0-100)
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=100))]
+    creativity: u8,
+
+    /// Adapt suggestions to transitions learned from your playing
+    #[arg(long)]
+    adaptive: bool,
+
+    /// Weight of learned transitions against the base model (0.0-1.0)
+    #[arg(long, default_value_t = 0.5)]
+    blend: f32,
+
+    /// Forget all learned transitions
+    #[arg(long)]
+    reset_learning: bool,
+
+    /// Disable periodic session autosave and crash recovery
+    #[arg(long)]
+    no_autosave: bool,
+
+    /// Append timestamped session events to this file (JSON lines)
+    #[arg(long, value_name = "FILE")]
+    event_log: Option<PathBuf>,
+
+    /// Do not write a session event log
+    #[arg(long, conflicts_with = "event_log")]
+    no_event_log: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Play a MIDI file (through --out-port) and score how well you follow its chords
+    Play {
+        /// Standard MIDI file to play along with
+        file: PathBuf,
+    },
+    /// Log detected chords with timestamps without the TUI (stop with Ctrl-C)
+    Monitor {
+        /// Append the chord log to this file instead of printing it
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Time drawing the TUI off screen, with a progression played in
+    Bench {
+        /// Frames to draw
+        #[arg(short, long, default_value_t = 1000)]
+        frames: usize,
+        /// Screen size to draw at, as COLSxROWS
+        #[arg(long, default_value = "120x40", value_parser = parse_size)]
+        size: (u16, u16),
+    },
+    /// Rank the keys that fit a list of chords
+    Key {
+        /// Chord names, e.g. C Am F G
+        #[arg(required = true)]
+        chords: Vec<String>,
+    },
 }
 
+/// Keys listed by `chordvery key`
+const KEYS_SHOWN: usize = 5;
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -35,16 +234,167 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    let naming = NamingStyle::from_name(&cli.naming)
+        .ok_or_else(|| anyhow!("Unknown naming style: {}", cli.naming))?;
+
+    if let Some(Command::Key { chords }) = &cli.command {
+        return print_keys(chords, naming);
+    }
+
+    let user = UserDirs::new(cli.profile.as_deref())?;
+    let config_path = cli.config.clone().unwrap_or_else(|| user.config_path());
+    let config = if cli.config.is_some() {
+        Config::load(&config_path)?
+    } else {
+        Config::load_or_default(&config_path)?
+    };
+
+    let demo = cli
+        .demo
+        .as_deref()
+        .map(|name| {
+            DemoSong::from_name(name).ok_or_else(|| {
+                let songs: Vec<&str> = DemoSong::all().iter().map(|s| s.name()).collect();
+                anyhow!("Unknown demo song: {} (try {})", name, songs.join(", "))
+            })
+        })
+        .transpose()?;
+
+    let note_range = cli.note_range.as_deref().map(parse_range).transpose()?;
+    let velocity_curve = cli
+        .velocity_curve
+        .as_deref()
+        .map(|name| {
+            VelocityCurve::from_name(name)
+                .ok_or_else(|| anyhow!("Unknown velocity curve: {}", name))
+        })
+        .transpose()?;
+    let (channel, transpose, velocity_floor) = (cli.channel, cli.transpose, cli.velocity_floor);
+    let bend_range = cli.bend_range;
+    let device_overrides = |settings: &mut DeviceSettings| {
+        if let Some(channel) = channel {
+            settings.channel = (channel > 0).then_some(channel);
+        }
+        if let Some(transpose) = transpose {
+            settings.transpose = transpose;
+        }
+        if let Some(range) = note_range {
+            settings.range = Some(range);
+        }
+        if let Some(floor) = velocity_floor {
+            settings.velocity_floor = floor;
+        }
+        if let Some(curve) = &velocity_curve {
+            settings.velocity_curve = curve.clone();
+        }
+        if let Some(range) = bend_range {
+            settings.bend_range = (range > 0).then_some(range);
+        }
+    };
+
     let mut app = App::new();
+    app.set_naming(naming);
+    for conflict in app.apply_config(&config) {
+        eprintln!("Warning: {}", conflict);
+    }
+    app.min_confidence = cli.min_confidence as f32 / 100.0;
+
+    if let Some(path) = cli.progression_model {
+        app.load_model(path)?;
+    }
+
+    app.set_user(user.clone());
+    if let Some(script) = &cli.script {
+        load_script(&mut app, script)?;
+    }
+
+    app.bpm = cli.bpm.map(f32::from);
+    app.auto_tempo = cli.auto_tempo;
+    app.loop_bars = cli.loop_bars;
+    app.send_clock = cli.send_clock;
+    app.humanize = Humanize::new(cli.humanize as f32 / 100.0).roll(!cli.no_roll);
+    app.resolution = Resolution::from_name(&cli.quantize)
+        .ok_or_else(|| anyhow!("Unknown quantize grid: {}", cli.quantize))?;
+    if cli.swing {
+        app.feel = Feel::Swing;
+    }
+    let time_signature = TimeSignature::from_name(&cli.time_signature)
+        .ok_or_else(|| anyhow!("Unknown time signature: {}", cli.time_signature))?;
+    app.set_blend(cli.blend);
+    app.set_creativity(cli.creativity as f32 / 100.0);
+
+    if cli.adaptive || cli.reset_learning {
+        app.enable_adaptive(user.adaptive_model_path());
+        app.adaptive_enabled = cli.adaptive;
+    }
+
+    if cli.reset_learning {
+        app.reset_adaptive();
+        if let Some(message) = app.message.take() {
+            eprintln!("{}", message);
+        }
+        if !cli.adaptive {
+            return Ok(());
+        }
+    }
+
+    if cli.dump_progression_model {
+        println!("{}", app.tree.model().to_json()?);
+        return Ok(());
+    }
+
+    if let Some(Command::Bench { frames, size }) = cli.command {
+        return run_bench(app, frames, size);
+    }
+
+    if let Some(port) = cli.out_port {
+        if let Err(e) = app.connect_output_port(port) {
+            eprintln!("Warning: Could not connect to MIDI output {}: {}", port, e);
+        }
+    }
+
+    let monitoring = matches!(cli.command, Some(Command::Monitor { .. }));
+
+    // A demo is for watching, so it keeps nothing
+    if !cli.no_autosave && !monitoring && demo.is_none() {
+        match Autosave::claim(&user.autosave_dir()) {
+            Ok(autosave) => app.enable_autosave(autosave),
+            Err(e) => eprintln!("Warning: Could not set up autosave: {}", e),
+        }
+    }
+
+    if !cli.no_event_log && demo.is_none() {
+        let path = cli.event_log.unwrap_or_else(|| {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            user.sessions_dir()
+                .join(format!("session-{}.jsonl", timestamp))
+        });
+        match SessionWriter::open(&path) {
+            Ok(writer) => app.enable_event_log(writer),
+            Err(e) => eprintln!(
+                "Warning: Could not open event log {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+    // Set once the log is open, so replays of the session keep it
+    if time_signature != TimeSignature::default() {
+        app.set_time_signature(time_signature);
+    }
 
-    match cli.port {
-        Some(port) => {
+    match (demo, cli.port) {
+        (Some(song), _) => app.set_note_source(Box::new(Demo::new(song))),
+        (None, Some(port)) => {
             if let Err(e) = app.connect_midi_port(port) {
                 eprintln!("Warning: Could not connect to MIDI port {}: {}", port, e);
                 eprintln!("Continuing without MIDI input...");
             }
         }
-        None => {
+        (None, None) => {
             if let Err(e) = app.connect_midi() {
                 eprintln!("Warning: Could not connect to MIDI: {}", e);
                 eprintln!("Continuing without MIDI input...");
@@ -52,11 +402,99 @@ fn main() -> Result<()> {
         }
     }
 
-    run_app(app)?;
+    let devices_path = paths::devices_path();
+    let mut devices = DeviceProfiles::load_or_default(&devices_path)?;
+    app.set_devices_path(devices_path.clone());
+    if let (Some(midi), None) = (&app.midi, demo) {
+        if apply_device_settings(midi, &mut devices, device_overrides) {
+            if let Err(e) = devices.save(&devices_path) {
+                eprintln!("Warning: Could not save device settings: {}", e);
+            }
+        }
+    }
+
+    app.grooves = Groove::load_all(&paths::grooves_path())?;
+    if let Some(name) = &cli.groove {
+        app.set_groove(name)?;
+    }
+
+    let controls_path = paths::controls_path();
+    app.set_controls(ControlMap::load_or_default(&controls_path)?, controls_path);
+
+    app.set_hooks(Hooks::new(config.hooks));
+
+    if let Some(port) = cli.partner_port {
+        if let Err(e) = app.connect_partner_port(port) {
+            eprintln!(
+                "Warning: Could not connect to partner MIDI port {}: {}",
+                port, e
+            );
+        }
+        if let Some(partner) = &app.partner {
+            partner.set_settings(devices.get(partner.port_name()));
+        }
+    }
+
+    match &cli.command {
+        Some(Command::Play { file }) => {
+            let name = file
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "File".to_string());
+            app.start_play_along(&name, MidiFile::load(file)?);
+        }
+        Some(Command::Monitor { output }) => return run_monitor(app, output.as_deref()),
+        Some(Command::Bench { .. }) | Some(Command::Key { .. }) | None => {}
+    }
+
+    if cli.sessions && app.screen().is_none() {
+        app.toggle_browser();
+    }
 
+    #[cfg(feature = "gui")]
+    if cli.gui {
+        GuiFrontend::default().run(&mut app)?;
+        return app.shutdown();
+    }
+    TerminalFrontend.run(&mut app)?;
+    app.shutdown()
+}
+
+/// Applies the saved settings for the connected device, updated with any
+/// given on the command line. Returns whether the saved settings changed.
+fn apply_device_settings(
+    midi: &MidiInput,
+    devices: &mut DeviceProfiles,
+    overrides: impl Fn(&mut DeviceSettings),
+) -> bool {
+    let port = midi.port_name();
+    let saved = devices.get(port);
+    let mut settings = saved.clone();
+    overrides(&mut settings);
+
+    if !settings.is_default() {
+        eprintln!("Using device settings for {}", port);
+    }
+    midi.set_settings(settings.clone());
+    devices.set(port, settings.clone());
+    settings != saved
+}
+
+#[cfg(feature = "scripting")]
+fn load_script(app: &mut App, name: &str) -> Result<()> {
+    let script = chordvery::theory::ScriptSuggester::find(name)?;
+    app.set_suggester(Box::new(script));
     Ok(())
 }
 
+#[cfg(not(feature = "scripting"))]
+fn load_script(_app: &mut App, name: &str) -> Result<()> {
+    Err(anyhow!(
+        "Cannot load script '{}': built without the `scripting` feature",
+        name
+    ))
+}
+
 fn list_ports() -> Result<()> {
     let ports = MidiInput::list_ports()?;
 
@@ -69,47 +507,118 @@ fn list_ports() -> Result<()> {
         }
     }
 
+    let outputs = MidiOutput::list_ports()?;
+
+    if outputs.is_empty() {
+        println!("No MIDI output ports available.");
+    } else {
+        println!("Available MIDI output ports:");
+        for (i, name) in outputs.iter().enumerate() {
+            println!("  {}: {}", i, name);
+        }
+    }
+
     Ok(())
 }
 
-fn run_app(mut app: App) -> Result<()> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    let tick_rate = Duration::from_millis(50);
-
-    loop {
-        terminal.draw(|f| app.render(f))?;
-
-        if event::poll(tick_rate)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    if app.show_help {
-                        app.show_help = false;
-                    } else {
-                        app.handle_key(key.code);
-                    }
+/// Prints the likeliest keys for the chords, marking the chords that fall
+/// outside each with `*`.
+fn print_keys(names: &[String], naming: NamingStyle) -> Result<()> {
+    let chords = names
+        .iter()
+        .map(|name| Chord::from_name(name).ok_or_else(|| anyhow!("Unknown chord: {}", name)))
+        .collect::<Result<Vec<Chord>>>()?;
+    for candidate in keys::rank_keys(&chords).iter().take(KEYS_SHOWN) {
+        let marked: Vec<String> = chords
+            .iter()
+            .zip(&candidate.diatonic)
+            .map(|(chord, &diatonic)| {
+                let name = chord.name_styled(naming);
+                if diatonic {
+                    name
+                } else {
+                    format!("{}*", name)
                 }
-            }
-        }
+            })
+            .collect();
+        println!(
+            "{:<4} {:>3}%  {}",
+            candidate.scale.name(),
+            candidate.percent(),
+            marked.join(" ")
+        );
+    }
+    Ok(())
+}
 
-        app.tick();
+fn run_monitor(mut app: App, output: Option<&Path>) -> Result<()> {
+    if app.midi.is_none() {
+        return Err(anyhow!("Monitor mode needs a MIDI input"));
+    }
+
+    let out: Box<dyn Write> = match output {
+        Some(path) => Box::new(OpenOptions::new().create(true).append(true).open(path)?),
+        None => Box::new(io::stdout()),
+    };
+    let mut monitor = Monitor::new(out);
+    let tick_rate = Duration::from_millis(20);
+
+    // Ctrl-C ends the session like quitting the TUI does
+    let stop = Arc::new(AtomicBool::new(false));
+    for signal in [SIGINT, SIGTERM] {
+        signal_hook::flag::register(signal, Arc::clone(&stop))?;
+    }
 
-        if app.should_quit {
-            break;
+    while !stop.load(Ordering::Relaxed) {
+        app.tick();
+        // Runs until interrupted, so keep the event log on disk as we go
+        if monitor.poll(&app)? > 0 {
+            app.flush_event_log()?;
         }
+        thread::sleep(tick_rate);
     }
+    app.shutdown()
+}
+
+fn parse_size(text: &str) -> Result<(u16, u16)> {
+    let (cols, rows) = text
+        .split_once('x')
+        .ok_or_else(|| anyhow!("Expected COLSxROWS, like 120x40: {}", text))?;
+    Ok((cols.trim().parse()?, rows.trim().parse()?))
+}
 
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+/// A ii-V-I-vi round in C, voiced as played
+const BENCH_PROGRESSION: [&[u8]; 4] = [
+    &[50, 60, 65, 69],
+    &[43, 59, 65, 69],
+    &[48, 59, 64, 67],
+    &[45, 60, 64, 67],
+];
+
+fn run_bench(mut app: App, frames: usize, (width, height): (u16, u16)) -> Result<()> {
+    app.midi = Some(MidiInput::new());
+    let play = |app: &mut App, notes: &[u8], status: u8| {
+        let midi = app.midi.as_ref().unwrap();
+        for &note in notes {
+            midi.receive(&[status, note, 90]);
+        }
+        app.tick();
+    };
+    for chord in BENCH_PROGRESSION.iter().cycle().take(16) {
+        play(&mut app, chord, 0x90);
+        play(&mut app, chord, 0x80);
+    }
+    // Leave a chord held so the pianos have something on them
+    play(&mut app, BENCH_PROGRESSION[0], 0x90);
 
+    let times = frontend::bench(&app, frames, width, height)?;
+    println!(
+        "{} frames at {}x{}: mean {:.3} ms, worst {:.3} ms",
+        times.frames,
+        width,
+        height,
+        times.mean.as_secs_f64() * 1000.0,
+        times.worst.as_secs_f64() * 1000.0
+    );
     Ok(())
 }
@@ -0,0 +1,199 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+/// Tempo the arpeggiator runs at before one has been tapped or detected
+pub const DEFAULT_BPM: f32 = 120.0;
+/// Share of each step a note sounds for
+const GATE: f32 = 0.9;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ArpPattern {
+    #[default]
+    Off,
+    Up,
+    Down,
+    UpDown,
+    Random,
+}
+
+impl ArpPattern {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArpPattern::Off => "Off",
+            ArpPattern::Up => "Up",
+            ArpPattern::Down => "Down",
+            ArpPattern::UpDown => "Up-Down",
+            ArpPattern::Random => "Random",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ArpPattern::Off => ArpPattern::Up,
+            ArpPattern::Up => ArpPattern::Down,
+            ArpPattern::Down => ArpPattern::UpDown,
+            ArpPattern::UpDown => ArpPattern::Random,
+            ArpPattern::Random => ArpPattern::Off,
+        }
+    }
+
+    /// The order `steps` notes of a chord are played in, from its notes in
+    /// any order. Random never repeats a note straight away.
+    pub fn sequence<R: Rng>(&self, notes: &[u8], steps: usize, rng: &mut R) -> Vec<u8> {
+        let mut sorted = notes.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.is_empty() {
+            return Vec::new();
+        }
+
+        let cycle: Vec<u8> = match self {
+            ArpPattern::Off | ArpPattern::Up => sorted,
+            ArpPattern::Down => sorted.into_iter().rev().collect(),
+            ArpPattern::UpDown => {
+                let down = sorted
+                    .iter()
+                    .rev()
+                    .skip(1)
+                    .take(sorted.len().saturating_sub(2));
+                sorted.iter().chain(down).copied().collect()
+            }
+            ArpPattern::Random => {
+                let mut out: Vec<u8> = Vec::with_capacity(steps);
+                while out.len() < steps {
+                    let note = sorted[rng.random_range(0..sorted.len())];
+                    if sorted.len() == 1 || out.last() != Some(&note) {
+                        out.push(note);
+                    }
+                }
+                return out;
+            }
+        };
+        cycle.iter().copied().cycle().take(steps).collect()
+    }
+}
+
+/// Arpeggiator steps per beat.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ArpRate {
+    #[default]
+    Eighth,
+    Triplet,
+    Sixteenth,
+}
+
+impl ArpRate {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArpRate::Eighth => "1/8",
+            ArpRate::Triplet => "1/8T",
+            ArpRate::Sixteenth => "1/16",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ArpRate::Eighth => ArpRate::Triplet,
+            ArpRate::Triplet => ArpRate::Sixteenth,
+            ArpRate::Sixteenth => ArpRate::Eighth,
+        }
+    }
+
+    pub fn steps_per_beat(&self) -> u32 {
+        match self {
+            ArpRate::Eighth => 2,
+            ArpRate::Triplet => 3,
+            ArpRate::Sixteenth => 4,
+        }
+    }
+
+    pub fn step(&self, bpm: f32) -> Duration {
+        Duration::from_secs_f32(60.0 / bpm / self.steps_per_beat() as f32)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Arpeggiator {
+    pub pattern: ArpPattern,
+    pub rate: ArpRate,
+}
+
+impl Arpeggiator {
+    pub fn is_on(&self) -> bool {
+        self.pattern != ArpPattern::Off
+    }
+
+    /// Notes with their start offset and length, filling `duration` with
+    /// steps at `bpm`.
+    pub fn notes<R: Rng>(
+        &self,
+        chord: &[u8],
+        duration: Duration,
+        bpm: f32,
+        rng: &mut R,
+    ) -> Vec<(Duration, Duration, u8)> {
+        let step = self.rate.step(bpm);
+        let steps = (duration.as_secs_f32() / step.as_secs_f32())
+            .floor()
+            .max(1.0) as usize;
+        self.pattern
+            .sequence(chord, steps, rng)
+            .into_iter()
+            .enumerate()
+            .map(|(i, note)| (step * i as u32, step.mul_f32(GATE), note))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_patterns() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let c = [67, 60, 64];
+        assert_eq!(
+            ArpPattern::Up.sequence(&c, 5, &mut rng),
+            [60, 64, 67, 60, 64]
+        );
+        assert_eq!(ArpPattern::Down.sequence(&c, 4, &mut rng), [67, 64, 60, 67]);
+        assert_eq!(
+            ArpPattern::UpDown.sequence(&c, 7, &mut rng),
+            [60, 64, 67, 64, 60, 64, 67]
+        );
+        assert_eq!(
+            ArpPattern::UpDown.sequence(&[60, 64], 3, &mut rng),
+            [60, 64, 60]
+        );
+
+        let random = ArpPattern::Random.sequence(&c, 32, &mut rng);
+        assert_eq!(random.len(), 32);
+        assert!(random.windows(2).all(|w| w[0] != w[1]));
+        assert!(random.iter().all(|n| c.contains(n)));
+        assert!(ArpPattern::Up.sequence(&[], 4, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn test_rate_follows_tempo() {
+        assert_eq!(ArpRate::Eighth.step(120.0), Duration::from_millis(250));
+        assert_eq!(ArpRate::Sixteenth.step(60.0), Duration::from_millis(250));
+
+        let arp = Arpeggiator {
+            pattern: ArpPattern::Up,
+            rate: ArpRate::Eighth,
+        };
+        let notes = arp.notes(
+            &[60, 64, 67],
+            Duration::from_secs(1),
+            120.0,
+            &mut StdRng::seed_from_u64(0),
+        );
+        assert_eq!(notes.len(), 4);
+        assert_eq!(notes[3].0, Duration::from_millis(750));
+        assert!(notes[0].1 < Duration::from_millis(250));
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use crate::theory::pitch::Pitch;
+
+/// Follows pitch bends per channel, as MPE and microtonal controllers send
+/// them, so each sounding note is read as the 12-EDO note it is bent
+/// nearest to.
+#[derive(Clone, Debug, Default)]
+pub struct PitchBends {
+    /// Semitones a full bend reaches; bends are ignored when unset
+    range: Option<u8>,
+    channels: [i16; 16],
+    /// The note each (channel, key) is currently read as
+    sounding: BTreeMap<(u8, u8), u8>,
+}
+
+impl PitchBends {
+    pub fn new(range: Option<u8>) -> Self {
+        Self {
+            range,
+            ..Self::default()
+        }
+    }
+
+    pub fn set_range(&mut self, range: Option<u8>) {
+        *self = Self::new(range);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.range.is_some()
+    }
+
+    fn quantize(&self, channel: u8, key: u8) -> u8 {
+        let Some(range) = self.range else {
+            return key;
+        };
+        let bend = self.channels[channel as usize & 0x0F];
+        Pitch::bent(key, bend, range)
+            .nearest()
+            .map_or(key, |note| note.midi)
+    }
+
+    /// Returns the note to read the key as.
+    pub fn note_on(&mut self, channel: u8, key: u8) -> u8 {
+        let note = self.quantize(channel, key);
+        if self.is_enabled() {
+            self.sounding.insert((channel, key), note);
+        }
+        note
+    }
+
+    /// Returns the note the key was being read as.
+    pub fn note_off(&mut self, channel: u8, key: u8) -> u8 {
+        self.sounding.remove(&(channel, key)).unwrap_or(key)
+    }
+
+    /// Sets a channel's bend from the message's two data bytes, returning
+    /// the sounding notes it moved as (from, to).
+    pub fn bend(&mut self, channel: u8, lsb: u8, msb: u8) -> Vec<(u8, u8)> {
+        if !self.is_enabled() {
+            return Vec::new();
+        }
+        let value = (((msb as i16 & 0x7F) << 7) | (lsb as i16 & 0x7F)) - 8192;
+        self.channels[channel as usize & 0x0F] = value;
+
+        let keys: Vec<u8> = self
+            .sounding
+            .keys()
+            .filter(|(ch, _)| *ch == channel)
+            .map(|&(_, key)| key)
+            .collect();
+        let mut moved = Vec::new();
+        for key in keys {
+            let note = self.quantize(channel, key);
+            if let Some(old) = self.sounding.insert((channel, key), note) {
+                if old != note {
+                    moved.push((old, note));
+                }
+            }
+        }
+        moved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bends_quantize() {
+        let mut bends = PitchBends::new(Some(48));
+        // 31-EDO step above E4, sent as E4 bent up 39 cents on channel 2
+        let bend = 8192 + 8192 * 39 / 4800;
+        bends.bend(2, (bend & 0x7F) as u8, (bend >> 7) as u8);
+        assert_eq!(bends.note_on(2, 64), 64);
+        assert_eq!(bends.note_on(3, 64), 64);
+
+        // Bent further, the sounding note is read as F
+        let bend = 8192 + 8192 * 70 / 4800;
+        let moved = bends.bend(2, (bend & 0x7F) as u8, (bend >> 7) as u8);
+        assert_eq!(moved, vec![(64, 65)]);
+        assert_eq!(bends.note_off(2, 64), 65);
+        assert_eq!(bends.note_off(3, 64), 64);
+    }
+
+    #[test]
+    fn test_disabled_passes_through() {
+        let mut bends = PitchBends::default();
+        assert!(bends.bend(0, 0x7F, 0x7F).is_empty());
+        assert_eq!(bends.note_on(0, 60), 60);
+        assert_eq!(bends.note_off(0, 60), 60);
+    }
+}
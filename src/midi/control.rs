@@ -0,0 +1,177 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Controls pressed but not yet read, beyond which the oldest are dropped
+const KEPT_CONTROLS: usize = 16;
+/// A controller counts as pressed from this value up, as for a sustain pedal
+const PRESSED: u8 = 64;
+pub const SUSTAIN_PEDAL: u8 = 64;
+
+/// A button, pedal, pad or key on the controller that can be mapped to an
+/// action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Control {
+    Program(u8),
+    Cc(u8),
+    /// A key or pad, which then no longer plays its note
+    Note(u8),
+}
+
+impl Control {
+    /// Parses `program 5`, `cc 64` or `note 36`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        let (kind, number) = name.split_once(' ')?;
+        let number: u8 = number.trim().parse().ok().filter(|&n| n < 128)?;
+        match kind {
+            "program" => Some(Control::Program(number)),
+            "cc" => Some(Control::Cc(number)),
+            "note" => Some(Control::Note(number)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Control {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Control::Program(n) => write!(f, "program {}", n),
+            Control::Cc(n) => write!(f, "cc {}", n),
+            Control::Note(n) => write!(f, "note {}", n),
+        }
+    }
+}
+
+impl TryFrom<String> for Control {
+    type Error = anyhow::Error;
+
+    fn try_from(name: String) -> anyhow::Result<Self> {
+        Self::from_name(&name).ok_or_else(|| anyhow::anyhow!("Unknown control '{}'", name))
+    }
+}
+
+impl From<Control> for String {
+    fn from(control: Control) -> Self {
+        control.to_string()
+    }
+}
+
+/// Program changes and controller presses as they come in. A controller
+/// is pressed when it rises past halfway, so a pedal held down or a knob
+/// turned through the top half counts once. Mapped notes are taken out of
+/// the music; while learning, any note or controller moved is.
+#[derive(Clone, Debug, Default)]
+pub struct ControlEvents {
+    pressed: VecDeque<Control>,
+    down: HashSet<u8>,
+    notes: HashSet<u8>,
+    learning: bool,
+}
+
+impl ControlEvents {
+    /// Notes to read as controls rather than play.
+    pub fn set_notes(&mut self, notes: HashSet<u8>) {
+        self.notes = notes;
+    }
+
+    pub fn set_learning(&mut self, learning: bool) {
+        self.learning = learning;
+    }
+
+    /// Takes a note-on or note-off if it's a control, returning whether it
+    /// did.
+    pub fn note(&mut self, note: u8, velocity: u8) -> bool {
+        let pressed = velocity > 0;
+        if pressed && self.learning {
+            self.push(Control::Note(note));
+            return true;
+        }
+        if !self.notes.contains(&note) {
+            return false;
+        }
+        if pressed {
+            self.push(Control::Note(note));
+        }
+        true
+    }
+
+    pub fn program(&mut self, program: u8) {
+        self.push(Control::Program(program));
+    }
+
+    pub fn cc(&mut self, number: u8, value: u8) {
+        let rose = if value < PRESSED {
+            self.down.remove(&number);
+            false
+        } else {
+            self.down.insert(number)
+        };
+        if rose || self.learning {
+            self.push(Control::Cc(number));
+        }
+    }
+
+    fn push(&mut self, control: Control) {
+        if self.pressed.len() == KEPT_CONTROLS {
+            self.pressed.pop_front();
+        }
+        self.pressed.push_back(control);
+    }
+
+    pub fn take(&mut self) -> Vec<Control> {
+        self.pressed.drain(..).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(Control::from_name("program 5"), Some(Control::Program(5)));
+        assert_eq!(Control::from_name(" CC 64 "), Some(Control::Cc(64)));
+        assert_eq!(Control::from_name("cc 128"), None);
+        assert_eq!(Control::from_name("note 36"), Some(Control::Note(36)));
+        assert_eq!(Control::from_name("key 60"), None);
+        assert_eq!(Control::Cc(20).to_string(), "cc 20");
+    }
+
+    #[test]
+    fn test_presses() {
+        let mut events = ControlEvents::default();
+        events.cc(64, 127);
+        events.cc(64, 100);
+        events.program(3);
+        events.cc(64, 0);
+        events.cc(64, 90);
+        events.cc(1, 20);
+        assert_eq!(
+            events.take(),
+            [Control::Cc(64), Control::Program(3), Control::Cc(64)]
+        );
+        assert!(events.take().is_empty());
+    }
+
+    #[test]
+    fn test_notes() {
+        let mut events = ControlEvents::default();
+        assert!(!events.note(36, 100));
+        events.set_notes(HashSet::from([36]));
+        assert!(events.note(36, 100));
+        assert!(events.note(36, 0));
+        assert!(!events.note(60, 100));
+
+        // Learning takes whatever moves first
+        events.set_learning(true);
+        assert!(events.note(60, 100));
+        events.cc(1, 10);
+        assert_eq!(
+            events.take(),
+            [Control::Note(36), Control::Note(60), Control::Cc(1)]
+        );
+    }
+}
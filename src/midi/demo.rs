@@ -0,0 +1,160 @@
+use std::time::{Duration, Instant};
+
+use super::script::{MidiScript, NoteSource};
+use crate::theory::{Chord, VoicingOptions};
+
+/// Held for most of each chord's length, with a breath before the next
+const LEGATO: f64 = 0.9;
+const VELOCITY: u8 = 90;
+
+/// A progression to show the app working with nothing plugged in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DemoSong {
+    /// I–V–vi–IV in C
+    Pop,
+    /// ii–V–I–VI turnaround in C
+    Jazz,
+    /// Twelve-bar blues in A
+    Blues,
+    /// Pachelbel's Canon in D
+    Canon,
+}
+
+impl DemoSong {
+    pub fn all() -> &'static [DemoSong] {
+        &[
+            DemoSong::Pop,
+            DemoSong::Jazz,
+            DemoSong::Blues,
+            DemoSong::Canon,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DemoSong::Pop => "pop",
+            DemoSong::Jazz => "jazz",
+            DemoSong::Blues => "blues",
+            DemoSong::Canon => "canon",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::all().iter().copied().find(|s| s.name() == name)
+    }
+
+    fn chords(&self) -> &'static [&'static str] {
+        match self {
+            DemoSong::Pop => &["C", "G", "Am", "F"],
+            DemoSong::Jazz => &["Dm7", "G7", "Cmaj7", "A7"],
+            DemoSong::Blues => &[
+                "A7", "D7", "A7", "A7", "D7", "D7", "A7", "A7", "E7", "D7", "A7", "E7",
+            ],
+            DemoSong::Canon => &["D", "A", "Bm", "F#m", "G", "D", "G", "A"],
+        }
+    }
+
+    /// Tempo and beats per chord
+    fn pace(&self) -> (f64, u32) {
+        match self {
+            DemoSong::Pop => (96.0, 4),
+            DemoSong::Jazz => (120.0, 4),
+            DemoSong::Blues => (110.0, 4),
+            DemoSong::Canon => (80.0, 2),
+        }
+    }
+
+    /// One time through, voiced open over the root.
+    pub fn script(&self) -> MidiScript {
+        let (bpm, beats) = self.pace();
+        let step = Duration::from_secs_f64(60.0 / bpm * beats as f64);
+        let options = VoicingOptions {
+            octave: 3,
+            inversion: 0,
+            spread: true,
+        };
+        self.chords()
+            .iter()
+            .filter_map(|name| Chord::from_name(name))
+            .enumerate()
+            .fold(MidiScript::new(), |script, (i, chord)| {
+                script.chord(
+                    step * i as u32,
+                    &chord.voicing(options),
+                    VELOCITY,
+                    step.mul_f64(LEGATO),
+                )
+            })
+    }
+}
+
+/// Plays a demo song round and round.
+#[derive(Clone, Debug)]
+pub struct Demo {
+    song: DemoSong,
+    script: MidiScript,
+}
+
+impl Demo {
+    pub fn new(song: DemoSong) -> Self {
+        Self {
+            song,
+            script: song.script(),
+        }
+    }
+}
+
+impl NoteSource for Demo {
+    fn due(&mut self, now: Instant) -> Vec<Vec<u8>> {
+        let due = self.script.due(now);
+        if self.script.is_finished() {
+            self.script = self.song.script();
+        }
+        due
+    }
+
+    fn is_finished(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        for song in DemoSong::all() {
+            assert_eq!(DemoSong::from_name(song.name()), Some(*song));
+        }
+        assert_eq!(DemoSong::from_name(" Blues "), Some(DemoSong::Blues));
+        assert_eq!(DemoSong::from_name("polka"), None);
+    }
+
+    #[test]
+    fn test_songs_play_every_chord() {
+        for song in DemoSong::all() {
+            let script = song.script();
+            let (bpm, beats) = song.pace();
+            let step = 60.0 / bpm * beats as f64;
+            let chords = song.chords().len() as f64;
+            let length = script.length().as_secs_f64();
+            assert!((length - step * (chords - 1.0 + LEGATO)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_demo_loops() {
+        let mut demo = Demo::new(DemoSong::Pop);
+        let start = Instant::now();
+        let first = demo.due(start);
+        assert_eq!(first.len(), 3);
+        let rest = demo.due(start + Duration::from_secs(60));
+        assert!(!rest.is_empty());
+        // Round again from the next ask
+        let later = start + Duration::from_secs(61);
+        assert_eq!(demo.due(later), first);
+        assert!(!demo.is_finished());
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::velocity::VelocityCurve;
+
+/// Input settings for one MIDI device.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeviceSettings {
+    /// Only listen to this channel (1-16); all channels when unset
+    pub channel: Option<u8>,
+    pub transpose: i8,
+    /// Lowest and highest note accepted, after transposing
+    pub range: Option<(u8, u8)>,
+    /// Note-ons softer than this are ignored, after the curve
+    pub velocity_floor: u8,
+    pub velocity_curve: VelocityCurve,
+    /// Semitones a full pitch bend reaches. When set, bends are followed
+    /// per channel and bent notes read as the nearest semitone (48 for MPE)
+    pub bend_range: Option<u8>,
+}
+
+impl DeviceSettings {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn accepts_channel(&self, status: u8) -> bool {
+        self.channel
+            .is_none_or(|channel| status & 0x0F == channel.saturating_sub(1))
+    }
+
+    /// Filters and transforms a note message, returning the note to use.
+    pub fn apply(&self, status: u8, note: u8, velocity: u8) -> Option<u8> {
+        if !self.accepts_channel(status) {
+            return None;
+        }
+
+        let is_note_on = status & 0xF0 == 0x90 && velocity > 0;
+        if is_note_on && self.velocity_curve.apply(velocity) < self.velocity_floor {
+            return None;
+        }
+
+        let note = note as i16 + self.transpose as i16;
+        let note = u8::try_from(note).ok().filter(|&n| n < 128)?;
+        match self.range {
+            Some((low, high)) if note < low || note > high => None,
+            _ => Some(note),
+        }
+    }
+}
+
+/// Parses a note range such as `36-96`.
+pub fn parse_range(text: &str) -> Result<(u8, u8)> {
+    let (low, high) = text
+        .split_once('-')
+        .ok_or_else(|| anyhow!("Expected a range like 36-96, got {}", text))?;
+    let low: u8 = low.trim().parse()?;
+    let high: u8 = high.trim().parse()?;
+    if low > high || high > 127 {
+        return Err(anyhow!("Invalid note range {}", text));
+    }
+    Ok((low, high))
+}
+
+/// Settings for every device seen so far, keyed by port name.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DeviceProfiles {
+    devices: BTreeMap<String, DeviceSettings>,
+}
+
+impl DeviceProfiles {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::from_toml(&text).map_err(|e| anyhow!("{}: {}", path.display(), e))
+    }
+
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, port: &str) -> DeviceSettings {
+        self.devices.get(port).cloned().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, port: &str, settings: DeviceSettings) {
+        if settings.is_default() {
+            self.devices.remove(port);
+        } else {
+            self.devices.insert(port.to_string(), settings);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let settings = DeviceSettings {
+            channel: Some(2),
+            transpose: -12,
+            range: Some((36, 72)),
+            velocity_floor: 10,
+            velocity_curve: VelocityCurve::Linear,
+            bend_range: None,
+        };
+
+        assert_eq!(settings.apply(0x91, 60, 100), Some(48));
+        assert_eq!(settings.apply(0x90, 60, 100), None);
+        assert_eq!(settings.apply(0x91, 60, 5), None);
+        // Releases get through regardless of velocity
+        assert_eq!(settings.apply(0x81, 60, 0), Some(48));
+        assert_eq!(settings.apply(0x91, 96, 100), None);
+        assert_eq!(settings.apply(0x91, 5, 100), None);
+
+        assert_eq!(DeviceSettings::default().apply(0x9F, 60, 1), Some(60));
+    }
+
+    #[test]
+    fn test_curve_before_floor() {
+        let settings = DeviceSettings {
+            velocity_floor: 20,
+            velocity_curve: VelocityCurve::Soft,
+            ..DeviceSettings::default()
+        };
+        assert_eq!(settings.apply(0x90, 60, 10), Some(60));
+        assert_eq!(settings.apply(0x90, 60, 2), None);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("36-96").unwrap(), (36, 96));
+        assert_eq!(parse_range(" 21 - 108 ").unwrap(), (21, 108));
+        assert!(parse_range("96-36").is_err());
+        assert!(parse_range("36").is_err());
+    }
+
+    #[test]
+    fn test_profiles_round_trip() {
+        let mut profiles = DeviceProfiles::default();
+        let settings = DeviceSettings {
+            transpose: 12,
+            range: Some((21, 108)),
+            velocity_curve: VelocityCurve::Custom(vec![0, 40, 90, 127]),
+            ..DeviceSettings::default()
+        };
+        profiles.set("Digital Piano:MIDI 1", settings.clone());
+        profiles.set("Pads", DeviceSettings::default());
+
+        let text = toml::to_string_pretty(&profiles).unwrap();
+        let loaded = DeviceProfiles::from_toml(&text).unwrap();
+        assert_eq!(loaded, profiles);
+        assert_eq!(loaded.get("Digital Piano:MIDI 1"), settings);
+        assert!(loaded.get("Pads").is_default());
+    }
+}
@@ -0,0 +1,56 @@
+/// The latest pitch bend and channel pressure from the controller, for
+/// showing how notes are being shaped. Whichever channel moved last is
+/// shown, so with MPE it follows the note being played.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Expression {
+    /// -1 for a full bend down to 1 for a full bend up
+    pub bend: f32,
+    /// Channel aftertouch, 0 to 1
+    pub pressure: f32,
+}
+
+impl Expression {
+    /// Sets the bend from a pitch-bend message's two data bytes.
+    pub fn set_bend(&mut self, lsb: u8, msb: u8) {
+        let value = (((msb as i16 & 0x7F) << 7) | (lsb as i16 & 0x7F)) - 8192;
+        self.bend = value as f32 / 8192.0;
+    }
+
+    pub fn set_pressure(&mut self, value: u8) {
+        self.pressure = (value & 0x7F) as f32 / 127.0;
+    }
+
+    /// Whether there's nothing to show: no bend to speak of and no
+    /// pressure.
+    pub fn is_resting(&self) -> bool {
+        self.bend.abs() < 0.01 && self.pressure == 0.0
+    }
+
+    /// Semitones bent, given how far a full bend reaches.
+    pub fn semitones(&self, range: u8) -> f32 {
+        self.bend * range as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expression() {
+        let mut expression = Expression::default();
+        assert!(expression.is_resting());
+        expression.set_bend(0, 0x40);
+        assert_eq!(expression.bend, 0.0);
+        expression.set_bend(0, 0x60);
+        assert_eq!(expression.bend, 0.5);
+        assert_eq!(expression.semitones(2), 1.0);
+        expression.set_bend(0, 0);
+        assert_eq!(expression.semitones(12), -12.0);
+
+        expression.set_bend(0, 0x40);
+        expression.set_pressure(127);
+        assert!(!expression.is_resting());
+        assert_eq!(expression.pressure, 1.0);
+    }
+}
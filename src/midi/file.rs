@@ -0,0 +1,373 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+
+use super::playback::{Playback, PlaybackEvent};
+use crate::theory::Chord;
+
+const DEFAULT_TEMPO: u32 = 500_000;
+const DRUM_CHANNEL: u8 = 9;
+const MIN_SEGMENT: f64 = 0.15;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FileNote {
+    pub note: u8,
+    pub velocity: u8,
+    pub start: f64,
+    pub end: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChordSegment {
+    pub start: f64,
+    pub end: f64,
+    pub chord: Chord,
+    pub notes: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MidiFile {
+    pub notes: Vec<FileNote>,
+}
+
+enum Raw {
+    NoteOn { channel: u8, note: u8, velocity: u8 },
+    NoteOff { channel: u8, note: u8 },
+    Tempo(u32),
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self.pos + n;
+        if end > self.bytes.len() {
+            bail!("Unexpected end of MIDI data");
+        }
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn peek(&self) -> Result<u8> {
+        self.bytes
+            .get(self.pos)
+            .copied()
+            .ok_or_else(|| anyhow!("Unexpected end of MIDI data"))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn vlq(&mut self) -> Result<u32> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let b = self.byte()?;
+            value = (value << 7) | (b & 0x7F) as u32;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        bail!("Invalid variable-length quantity")
+    }
+}
+
+impl MidiFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path)?;
+        Self::parse(&bytes).map_err(|e| anyhow!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        if reader.take(4)? != b"MThd" {
+            bail!("Not a Standard MIDI File");
+        }
+        let header_len = reader.u32()? as usize;
+        let header = reader.take(header_len)?;
+        if header.len() < 6 {
+            bail!("Truncated MIDI header");
+        }
+        let tracks = u16::from_be_bytes([header[2], header[3]]);
+        let division = u16::from_be_bytes([header[4], header[5]]);
+        if division & 0x8000 != 0 {
+            bail!("SMPTE time division is not supported");
+        }
+
+        let mut events = Vec::new();
+        for _ in 0..tracks {
+            if reader.is_empty() {
+                break;
+            }
+            let id = reader.take(4)?;
+            let len = reader.u32()? as usize;
+            let body = reader.take(len)?;
+            if id == b"MTrk" {
+                read_track(body, &mut events)?;
+            }
+        }
+
+        // Stable sort keeps each track's own order for simultaneous events
+        events.sort_by_key(|(tick, _)| *tick);
+        Ok(Self {
+            notes: to_notes(&events, division.max(1)),
+        })
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.notes.iter().map(|n| n.end).fold(0.0, f64::max)
+    }
+
+    pub fn sounding_at(&self, time: f64) -> HashSet<u8> {
+        self.notes
+            .iter()
+            .filter(|n| n.start <= time && time < n.end)
+            .map(|n| n.note)
+            .collect()
+    }
+
+    pub fn playback(&self) -> Playback {
+        let mut playback = Playback::new();
+        for note in &self.notes {
+            playback.schedule(
+                Duration::from_secs_f64(note.start),
+                PlaybackEvent::NoteOn {
+                    note: note.note,
+                    velocity: note.velocity,
+                },
+            );
+            playback.schedule(
+                Duration::from_secs_f64(note.end),
+                PlaybackEvent::NoteOff { note: note.note },
+            );
+        }
+        playback
+    }
+
+    /// The file's harmony as a sequence of detected chords. Blips shorter
+    /// than a passing note are dropped and repeated chords are merged.
+    pub fn chord_timeline(&self) -> Vec<ChordSegment> {
+        let mut boundaries: Vec<f64> = self.notes.iter().flat_map(|n| [n.start, n.end]).collect();
+        boundaries.sort_by(|a, b| a.total_cmp(b));
+        boundaries.dedup();
+
+        let mut segments: Vec<ChordSegment> = Vec::new();
+        for window in boundaries.windows(2) {
+            let (start, end) = (window[0], window[1]);
+            let sounding = self.sounding_at(start);
+            let Some(chord) = Chord::detect(&sounding) else {
+                continue;
+            };
+
+            if let Some(last) = segments.last_mut() {
+                if last.chord.name() == chord.name() && (start - last.end).abs() < 1e-6 {
+                    last.end = end;
+                    continue;
+                }
+            }
+            if end - start < MIN_SEGMENT {
+                continue;
+            }
+
+            let mut notes: Vec<u8> = sounding.into_iter().collect();
+            notes.sort_unstable();
+            segments.push(ChordSegment {
+                start,
+                end,
+                chord,
+                notes,
+            });
+        }
+
+        segments
+    }
+}
+
+fn read_track(body: &[u8], events: &mut Vec<(u64, Raw)>) -> Result<()> {
+    let mut reader = Reader::new(body);
+    let mut tick = 0u64;
+    let mut running = 0u8;
+
+    while !reader.is_empty() {
+        tick += reader.vlq()? as u64;
+
+        let status = if reader.peek()? & 0x80 != 0 {
+            reader.byte()?
+        } else {
+            running
+        };
+
+        match status {
+            0xFF => {
+                let kind = reader.byte()?;
+                let len = reader.vlq()? as usize;
+                let data = reader.take(len)?;
+                match kind {
+                    0x2F => break,
+                    0x51 if len == 3 => {
+                        let tempo = u32::from_be_bytes([0, data[0], data[1], data[2]]);
+                        events.push((tick, Raw::Tempo(tempo)));
+                    }
+                    _ => {}
+                }
+            }
+            0xF0 | 0xF7 => {
+                let len = reader.vlq()? as usize;
+                reader.take(len)?;
+            }
+            0x80..=0xEF => {
+                running = status;
+                let channel = status & 0x0F;
+                match status & 0xF0 {
+                    0x80 => {
+                        let note = reader.byte()?;
+                        reader.byte()?;
+                        events.push((tick, Raw::NoteOff { channel, note }));
+                    }
+                    0x90 => {
+                        let note = reader.byte()?;
+                        let velocity = reader.byte()?;
+                        let event = if velocity == 0 {
+                            Raw::NoteOff { channel, note }
+                        } else {
+                            Raw::NoteOn {
+                                channel,
+                                note,
+                                velocity,
+                            }
+                        };
+                        events.push((tick, event));
+                    }
+                    0xC0 | 0xD0 => {
+                        reader.byte()?;
+                    }
+                    _ => {
+                        reader.take(2)?;
+                    }
+                }
+            }
+            _ => bail!("Unexpected MIDI status byte {:#04x}", status),
+        }
+    }
+
+    Ok(())
+}
+
+fn to_notes(events: &[(u64, Raw)], division: u16) -> Vec<FileNote> {
+    let mut tempo = DEFAULT_TEMPO;
+    let mut last_tick = 0u64;
+    let mut seconds = 0.0;
+    let mut open: HashMap<(u8, u8), (f64, u8)> = HashMap::new();
+    let mut notes = Vec::new();
+
+    for (tick, event) in events {
+        seconds += (tick - last_tick) as f64 * tempo as f64 / 1_000_000.0 / division as f64;
+        last_tick = *tick;
+
+        match *event {
+            Raw::Tempo(t) => tempo = t,
+            Raw::NoteOn { channel, .. } | Raw::NoteOff { channel, .. }
+                if channel == DRUM_CHANNEL => {}
+            Raw::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => {
+                open.insert((channel, note), (seconds, velocity));
+            }
+            Raw::NoteOff { channel, note } => {
+                if let Some((start, velocity)) = open.remove(&(channel, note)) {
+                    notes.push(FileNote {
+                        note,
+                        velocity,
+                        start,
+                        end: seconds,
+                    });
+                }
+            }
+        }
+    }
+
+    notes.sort_by(|a, b| a.start.total_cmp(&b.start).then(a.note.cmp(&b.note)));
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::midi::{self, MidiOptions};
+    use crate::session::Session;
+
+    fn exported() -> Vec<u8> {
+        let mut session = Session::new();
+        for name in ["C", "Am", "F", "G7"] {
+            session.push(&Chord::from_name(name).unwrap());
+        }
+        midi::render(&session, &MidiOptions::default().bass(None))
+    }
+
+    #[test]
+    fn test_round_trip_export() {
+        let file = MidiFile::parse(&exported()).unwrap();
+
+        // 120 BPM, a bar (two seconds) per chord
+        assert_eq!(file.notes.len(), 13);
+        assert_eq!(file.duration(), 8.0);
+        let am = Chord::from_name("Am").unwrap().notes();
+        assert_eq!(file.sounding_at(2.5), am.into_iter().collect());
+
+        let names: Vec<String> = file
+            .chord_timeline()
+            .iter()
+            .map(|s| s.chord.name())
+            .collect();
+        assert_eq!(names, vec!["C", "Am", "F", "G7"]);
+    }
+
+    #[test]
+    fn test_running_status_and_drums() {
+        let track: Vec<u8> = vec![
+            0x00, 0x90, 60, 100, // note on
+            0x00, 64, 100, // running status
+            0x00, 0x99, 36, 100, // drum hit, ignored
+            0x83, 0x60, 0x80, 60, 0, // a quarter later
+            0x00, 0x90, 64, 0, // note on with velocity 0
+            0x00, 0xFF, 0x2F, 0x00,
+        ];
+        let mut bytes = b"MThd".to_vec();
+        bytes.extend_from_slice(&[0, 0, 0, 6, 0, 0, 0, 1, 0x01, 0xE0]);
+        bytes.extend_from_slice(b"MTrk");
+        bytes.extend_from_slice(&(track.len() as u32).to_be_bytes());
+        bytes.extend(track);
+
+        let file = MidiFile::parse(&bytes).unwrap();
+        assert_eq!(file.notes.len(), 2);
+        assert!(file.notes.iter().all(|n| n.end == 0.5));
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        assert!(MidiFile::parse(b"RIFF").is_err());
+        assert!(MidiFile::parse(b"MThd\0\0\0\x06\0\0\0\x01\xE7\x28").is_err());
+    }
+}
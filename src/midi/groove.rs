@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+
+const BUILT_IN: &str = include_str!("grooves.toml");
+
+/// One strike of the chord, in beats from the start of the pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hit {
+    pub at: f32,
+    pub length: f32,
+    pub velocity: u8,
+}
+
+/// A comping pattern played under each chord of a progression played back
+/// or looped, instead of holding it.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Groove {
+    pub name: String,
+    /// Length of the pattern in quarter notes
+    pub beats: f32,
+    pub hits: Vec<Hit>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GrooveFile {
+    #[serde(default)]
+    groove: Vec<Groove>,
+}
+
+impl Groove {
+    /// Pad, pop eighths, bossa, waltz and gospel.
+    pub fn built_in() -> Vec<Groove> {
+        Self::from_toml(BUILT_IN).expect("built-in grooves are valid")
+    }
+
+    pub fn from_toml(text: &str) -> Result<Vec<Groove>> {
+        let mut grooves = toml::from_str::<GrooveFile>(text)?.groove;
+        for groove in &mut grooves {
+            groove.check()?;
+            groove.hits.sort_by(|a, b| a.at.total_cmp(&b.at));
+        }
+        Ok(grooves)
+    }
+
+    /// The built-in grooves and the user's own from `path`, which replace
+    /// built-in ones of the same name.
+    pub fn load_all(path: &Path) -> Result<Vec<Groove>> {
+        let mut grooves = Self::built_in();
+        if !path.exists() {
+            return Ok(grooves);
+        }
+
+        let text = fs::read_to_string(path)?;
+        let own = Self::from_toml(&text).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+        for groove in own {
+            match grooves
+                .iter_mut()
+                .find(|g| g.name.eq_ignore_ascii_case(&groove.name))
+            {
+                Some(existing) => *existing = groove,
+                None => grooves.push(groove),
+            }
+        }
+        Ok(grooves)
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.beats <= 0.0 {
+            bail!("Groove '{}' needs a length in beats", self.name);
+        }
+        for hit in &self.hits {
+            if !(0.0..self.beats).contains(&hit.at) || hit.length <= 0.0 {
+                bail!(
+                    "Groove '{}': the hit at {} doesn't fit in {} beats",
+                    self.name,
+                    hit.at,
+                    self.beats
+                );
+            }
+            if !(1..=127).contains(&hit.velocity) {
+                bail!(
+                    "Groove '{}': velocity {} is out of range",
+                    self.name,
+                    hit.velocity
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// The hits under a chord lasting `beats`: the pattern from its start,
+    /// repeated while the chord lasts, with the last hit cut off at its
+    /// end. Each is `(start, length, velocity)` in beats.
+    pub fn hits(&self, beats: f32) -> Vec<(f32, f32, u8)> {
+        let mut hits = Vec::new();
+        let mut pass = 0.0;
+        while pass < beats {
+            for hit in &self.hits {
+                let at = pass + hit.at;
+                if at < beats {
+                    hits.push((at, hit.length.min(beats - at), hit.velocity));
+                }
+            }
+            pass += self.beats;
+        }
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_built_in() {
+        let names: Vec<String> = Groove::built_in().into_iter().map(|g| g.name).collect();
+        assert_eq!(names, ["Pad", "Pop eighths", "Bossa", "Waltz", "Gospel"]);
+    }
+
+    #[test]
+    fn test_hits_repeat_and_cut_off() {
+        let waltz = &Groove::built_in()[3];
+        let hits = waltz.hits(4.0);
+        assert_eq!(hits.len(), 4);
+        assert_eq!(hits[3], (3.0, 0.9, 95));
+
+        let pad = &Groove::built_in()[0];
+        assert_eq!(pad.hits(2.0), [(0.0, 2.0, 80)]);
+    }
+
+    #[test]
+    fn test_invalid_grooves() {
+        let groove = |hits: &str| format!("[[groove]]\nname = \"x\"\nbeats = 2\nhits = [{}]", hits);
+        assert!(Groove::from_toml(&groove("{ at = 1, length = 1, velocity = 90 }")).is_ok());
+        assert!(Groove::from_toml(&groove("{ at = 2, length = 1, velocity = 90 }")).is_err());
+        assert!(Groove::from_toml(&groove("{ at = 0, length = 1, velocity = 0 }")).is_err());
+        assert!(Groove::from_toml(&groove("{ at = 0, length = 1 }")).is_err());
+    }
+
+    #[test]
+    fn test_own_grooves_replace_built_in() {
+        let path = std::env::temp_dir().join(format!(
+            "chordvery-grooves-test-{}.toml",
+            std::process::id()
+        ));
+        fs::write(
+            &path,
+            "[[groove]]\nname = \"pad\"\nbeats = 2\nhits = [{ at = 0, length = 2, velocity = 50 }]\n\n\
+             [[groove]]\nname = \"Reggae\"\nbeats = 2\nhits = [{ at = 0.5, length = 0.25, velocity = 90 }]",
+        )
+        .unwrap();
+
+        let grooves = Groove::load_all(&path).unwrap();
+        assert_eq!(grooves.len(), 6);
+        assert_eq!(grooves[0].beats, 2.0);
+        assert_eq!(grooves[5].name, "Reggae");
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(Groove::load_all(&path).unwrap().len(), 5);
+    }
+}
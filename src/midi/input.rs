@@ -1,19 +1,53 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 use anyhow::{anyhow, Result};
 use midir::{MidiInput as MidirInput, MidiInputConnection};
 
+use super::bend::PitchBends;
+use super::control::{Control, ControlEvents};
+use super::device::DeviceSettings;
+use super::expression::Expression;
+use super::latch::NoteLatch;
+use super::sync::{self, ClockFollower};
+use crate::clock::{Clock, SystemClock};
+
 pub struct MidiInput {
     _connection: Option<MidiInputConnection<()>>,
-    held_notes: Arc<Mutex<HashSet<u8>>>,
+    state: Arc<State>,
+    port_name: String,
+    /// Times notes received by hand rather than from the port
+    clock: Arc<dyn Clock>,
+}
+
+/// What the port's callback keeps up to date for the app to read.
+#[derive(Default)]
+struct State {
+    held_notes: Mutex<NoteLatch>,
+    bends: Mutex<PitchBends>,
+    settings: Mutex<DeviceSettings>,
+    /// Raw velocities of the latest note-ons, before any curve
+    velocities: Mutex<VecDeque<u8>>,
+    /// How hard each key down was struck, after the curve
+    strikes: Mutex<HashMap<u8, u8>>,
+    expression: Mutex<Expression>,
+    controls: Mutex<ControlEvents>,
+    /// When the latest note-on came in
+    last_note_on: Mutex<Option<Instant>>,
+    clock: Mutex<ClockFollower>,
 }
 
+/// Note-on velocities kept for whoever reads them
+const KEPT_VELOCITIES: usize = 32;
+
 impl MidiInput {
     pub fn new() -> Self {
         Self {
             _connection: None,
-            held_notes: Arc::new(Mutex::new(HashSet::new())),
+            state: Arc::default(),
+            port_name: String::new(),
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -40,39 +74,27 @@ impl MidiInput {
         let port = &ports[port_index];
         let port_name = midi_in.port_name(port)?;
 
-        let held_notes = Arc::new(Mutex::new(HashSet::new()));
-        let held_notes_clone = Arc::clone(&held_notes);
-
-        let connection = midi_in.connect(
-            port,
-            "chordvery-input",
-            move |_timestamp, message, _| {
-                if message.len() >= 3 {
-                    let status = message[0] & 0xF0;
-                    let note = message[1];
-                    let velocity = message[2];
-
-                    let mut notes = held_notes_clone.lock().unwrap();
-
-                    match status {
-                        0x90 if velocity > 0 => {
-                            notes.insert(note);
-                        }
-                        0x80 | 0x90 => {
-                            notes.remove(&note);
-                        }
-                        _ => {}
-                    }
-                }
-            },
-            (),
-        )?;
+        let state = Arc::new(State::default());
+        let state_clone = Arc::clone(&state);
+
+        let connection = midi_in
+            .connect(
+                port,
+                "chordvery-input",
+                move |_timestamp, message, _| {
+                    receive(message, Instant::now(), &state_clone);
+                },
+                (),
+            )
+            .map_err(|e| anyhow!("{}", e))?;
 
         eprintln!("Connected to MIDI port: {}", port_name);
 
         Ok(Self {
             _connection: Some(connection),
-            held_notes,
+            state,
+            port_name,
+            clock: Arc::new(SystemClock),
         })
     }
 
@@ -86,8 +108,98 @@ impl MidiInput {
         Self::connect(0)
     }
 
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    pub fn settings(&self) -> DeviceSettings {
+        self.state.settings.lock().unwrap().clone()
+    }
+
+    pub fn set_settings(&self, settings: DeviceSettings) {
+        self.state
+            .bends
+            .lock()
+            .unwrap()
+            .set_range(settings.bend_range);
+        *self.state.settings.lock().unwrap() = settings;
+        self.state.held_notes.lock().unwrap().clear();
+        self.state.strikes.lock().unwrap().clear();
+    }
+
+    /// The notes sounding, including latched ones when latching is on.
     pub fn held_notes(&self) -> HashSet<u8> {
-        self.held_notes.lock().unwrap().clone()
+        self.state.held_notes.lock().unwrap().notes()
+    }
+
+    pub fn is_latched(&self) -> bool {
+        self.state.held_notes.lock().unwrap().is_enabled()
+    }
+
+    pub fn set_latched(&self, latched: bool) {
+        self.state.held_notes.lock().unwrap().set_enabled(latched);
+    }
+
+    pub fn note_on(&self, note: u8) {
+        let now = self.clock.now();
+        self.state.held_notes.lock().unwrap().note_on(note, now);
+        *self.state.last_note_on.lock().unwrap() = Some(now);
+    }
+
+    pub fn note_off(&self, note: u8) {
+        self.state.held_notes.lock().unwrap().note_off(note);
+    }
+
+    /// Handles a message as if it came from the port.
+    pub fn receive(&self, message: &[u8]) {
+        receive(message, self.clock.now(), &self.state);
+    }
+
+    /// The latest pitch bend and channel pressure.
+    pub fn expression(&self) -> Expression {
+        *self.state.expression.lock().unwrap()
+    }
+
+    /// Program changes and controller presses since the last call, oldest
+    /// first.
+    pub fn take_controls(&self) -> Vec<Control> {
+        self.state.controls.lock().unwrap().take()
+    }
+
+    /// Notes to read as controls instead of playing them.
+    pub fn set_control_notes(&self, notes: HashSet<u8>) {
+        self.state.controls.lock().unwrap().set_notes(notes);
+    }
+
+    /// While learning, the next note or controller moved is read as a
+    /// control.
+    pub fn set_learning(&self, learning: bool) {
+        self.state.controls.lock().unwrap().set_learning(learning);
+    }
+
+    /// Raw velocities of the note-ons since the last call, oldest first.
+    pub fn take_velocities(&self) -> Vec<u8> {
+        self.state.velocities.lock().unwrap().drain(..).collect()
+    }
+
+    /// How hard each key down was struck, after the velocity curve. Notes
+    /// pressed by hand are left out.
+    pub fn held_velocities(&self) -> HashMap<u8, u8> {
+        self.state.strikes.lock().unwrap().clone()
+    }
+
+    /// When the latest note-on came in, from the port or by hand.
+    pub fn last_note_on(&self) -> Option<Instant> {
+        *self.state.last_note_on.lock().unwrap()
+    }
+
+    /// The tempo of the MIDI clock coming in, while it runs.
+    pub fn clock_tempo(&self) -> Option<f32> {
+        self.state.clock.lock().unwrap().tempo(self.clock.now())
     }
 
     pub fn disconnect(&mut self) {
@@ -95,6 +207,116 @@ impl MidiInput {
     }
 }
 
+fn receive(message: &[u8], now: Instant, state: &State) {
+    // System realtime messages are a status byte alone
+    if let [status] = *message {
+        let mut clock = state.clock.lock().unwrap();
+        match status {
+            sync::CLOCK => clock.pulse(now),
+            sync::STOP => clock.stop(),
+            _ => {}
+        }
+        return;
+    }
+    let settings = state.settings.lock().unwrap();
+    // Channel aftertouch and program changes have a single data byte
+    if let [status, value] = *message {
+        if settings.accepts_channel(status) {
+            match status & 0xF0 {
+                0xD0 => state.expression.lock().unwrap().set_pressure(value),
+                0xC0 => state.controls.lock().unwrap().program(value),
+                _ => {}
+            }
+        }
+        return;
+    }
+    if message.len() < 3 {
+        return;
+    }
+    let status = message[0];
+    let velocity = message[2];
+    let mut bends = state.bends.lock().unwrap();
+
+    if status & 0xF0 == 0xE0 {
+        if settings.accepts_channel(status) {
+            state
+                .expression
+                .lock()
+                .unwrap()
+                .set_bend(message[1], velocity);
+            let mut notes = state.held_notes.lock().unwrap();
+            apply_bend(&mut notes, &mut bends, status, message[1], velocity, now);
+        }
+        return;
+    }
+
+    if status & 0xF0 == 0xB0 {
+        if settings.accepts_channel(status) {
+            state.controls.lock().unwrap().cc(message[1], velocity);
+        }
+        return;
+    }
+
+    let is_note = matches!(status & 0xF0, 0x80 | 0x90);
+    if is_note && settings.accepts_channel(status) {
+        let velocity = if status & 0xF0 == 0x80 { 0 } else { velocity };
+        if state.controls.lock().unwrap().note(message[1], velocity) {
+            return;
+        }
+    }
+
+    if status & 0xF0 == 0x90 && velocity > 0 && settings.accepts_channel(status) {
+        let mut velocities = state.velocities.lock().unwrap();
+        if velocities.len() == KEPT_VELOCITIES {
+            velocities.pop_front();
+        }
+        velocities.push_back(velocity);
+    }
+    if let Some(note) = settings.apply(status, message[1], velocity) {
+        let mut strikes = state.strikes.lock().unwrap();
+        if status & 0xF0 == 0x90 && velocity > 0 {
+            strikes.insert(note, settings.velocity_curve.apply(velocity));
+            *state.last_note_on.lock().unwrap() = Some(now);
+        } else {
+            strikes.remove(&note);
+        }
+        let mut notes = state.held_notes.lock().unwrap();
+        apply_message(&mut notes, &mut bends, status, note, velocity, now);
+    }
+}
+
+fn apply_message(
+    notes: &mut NoteLatch,
+    bends: &mut PitchBends,
+    status: u8,
+    note: u8,
+    velocity: u8,
+    at: Instant,
+) {
+    let channel = status & 0x0F;
+    match status & 0xF0 {
+        0x90 if velocity > 0 => notes.note_on(bends.note_on(channel, note), at),
+        0x80 | 0x90 => notes.note_off(bends.note_off(channel, note)),
+        _ => {}
+    }
+}
+
+/// Moves notes held on the bent channel to the semitone they now sound
+/// nearest.
+fn apply_bend(
+    notes: &mut NoteLatch,
+    bends: &mut PitchBends,
+    status: u8,
+    lsb: u8,
+    msb: u8,
+    at: Instant,
+) {
+    for (from, to) in bends.bend(status & 0x0F, lsb, msb) {
+        notes.note_off(from);
+        notes.note_on(to, at);
+    }
+}
+
 impl Default for MidiInput {
     fn default() -> Self {
         Self::new()
@@ -104,6 +326,7 @@ impl Default for MidiInput {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::midi::VelocityCurve;
 
     #[test]
     fn test_new() {
@@ -150,4 +373,152 @@ mod tests {
             assert_eq!(notes.len(), 2);
         }
     }
+
+    #[test]
+    fn test_note_on_off() {
+        let midi = MidiInput::new();
+        midi.note_on(60);
+        midi.note_on(64);
+        midi.note_off(60);
+        assert_eq!(midi.held_notes(), HashSet::from([64]));
+    }
+
+    #[test]
+    fn test_set_settings_clears_held() {
+        let midi = MidiInput::new();
+        midi.note_on(60);
+        midi.set_settings(DeviceSettings {
+            transpose: 12,
+            ..DeviceSettings::default()
+        });
+        assert!(midi.held_notes().is_empty());
+        assert_eq!(midi.settings().transpose, 12);
+    }
+
+    #[test]
+    fn test_receive() {
+        let midi = MidiInput::new();
+        midi.set_settings(DeviceSettings {
+            velocity_floor: 30,
+            velocity_curve: VelocityCurve::Soft,
+            ..DeviceSettings::default()
+        });
+        midi.receive(&[0x90, 60, 12]);
+        midi.receive(&[0x90, 64, 2]);
+        midi.receive(&[0x80, 70, 40]);
+        // The curve lifts the first over the floor
+        assert_eq!(midi.held_notes(), HashSet::from([60]));
+        assert_eq!(midi.take_velocities(), [12, 2]);
+        assert!(midi.take_velocities().is_empty());
+    }
+
+    #[test]
+    fn test_clock_and_note_on_times() {
+        use crate::clock::ManualClock;
+        use std::time::Duration;
+
+        let clock = ManualClock::new();
+        let mut midi = MidiInput::new();
+        midi.set_clock(Arc::new(clock.clone()));
+        assert_eq!(midi.last_note_on(), None);
+        midi.receive(&[0x90, 60, 90]);
+        assert_eq!(midi.last_note_on(), Some(clock.now()));
+
+        // A beat of pulses at 120 bpm
+        for _ in 0..=sync::PPQN {
+            midi.receive(&[sync::CLOCK]);
+            clock.advance(Duration::from_millis(500) / sync::PPQN);
+        }
+        assert_eq!(midi.clock_tempo().map(f32::round), Some(120.0));
+        midi.receive(&[sync::STOP]);
+        assert_eq!(midi.clock_tempo(), None);
+    }
+
+    #[test]
+    fn test_held_velocities() {
+        let midi = MidiInput::new();
+        midi.receive(&[0x90, 60, 100]);
+        midi.receive(&[0x90, 61, 12]);
+        midi.note_on(64);
+        assert_eq!(midi.held_velocities(), HashMap::from([(60, 100), (61, 12)]));
+
+        midi.receive(&[0x80, 61, 0]);
+        midi.receive(&[0x90, 60, 0]);
+        assert!(midi.held_velocities().is_empty());
+    }
+
+    #[test]
+    fn test_expression_messages() {
+        let midi = MidiInput::new();
+        midi.set_settings(DeviceSettings {
+            channel: Some(1),
+            ..DeviceSettings::default()
+        });
+        midi.receive(&[0xE0, 0, 0x60]);
+        midi.receive(&[0xD0, 64]);
+        midi.receive(&[0xD1, 127]);
+        let expression = midi.expression();
+        assert_eq!(expression.bend, 0.5);
+        assert!((expression.pressure - 64.0 / 127.0).abs() < 1e-6);
+        // Bends are shown without a bend range to follow them with
+        assert!(midi.held_notes().is_empty());
+    }
+
+    #[test]
+    fn test_control_messages() {
+        let midi = MidiInput::new();
+        midi.receive(&[0xC0, 5]);
+        midi.receive(&[0xB0, 20, 127]);
+        midi.receive(&[0xB0, 20, 0]);
+        assert_eq!(midi.take_controls(), [Control::Program(5), Control::Cc(20)]);
+        assert!(midi.held_notes().is_empty());
+
+        midi.set_control_notes(HashSet::from([36]));
+        midi.receive(&[0x90, 36, 100]);
+        midi.receive(&[0x90, 60, 100]);
+        assert_eq!(midi.take_controls(), [Control::Note(36)]);
+        assert_eq!(midi.held_notes(), HashSet::from([60]));
+    }
+
+    #[test]
+    fn test_apply_message() {
+        let mut notes = NoteLatch::default();
+        let mut bends = PitchBends::default();
+        apply_message(&mut notes, &mut bends, 0x90, 60, 100, Instant::now());
+        assert!(notes.notes().contains(&60));
+        apply_message(&mut notes, &mut bends, 0x90, 60, 0, Instant::now());
+        assert!(notes.notes().is_empty());
+    }
+
+    #[test]
+    fn test_bent_notes() {
+        let mut notes = NoteLatch::default();
+        let mut bends = PitchBends::new(Some(2));
+        let now = Instant::now();
+        // Channel 2 bent a semitone down before its note starts
+        apply_bend(&mut notes, &mut bends, 0xE1, 0, 0x20, now);
+        apply_message(&mut notes, &mut bends, 0x91, 61, 100, now);
+        apply_message(&mut notes, &mut bends, 0x90, 64, 100, now);
+        assert_eq!(notes.notes(), HashSet::from([60, 64]));
+
+        apply_bend(&mut notes, &mut bends, 0xE1, 0, 0x40, now);
+        assert_eq!(notes.notes(), HashSet::from([61, 64]));
+        apply_message(&mut notes, &mut bends, 0x81, 61, 0, now);
+        assert_eq!(notes.notes(), HashSet::from([64]));
+    }
+
+    #[test]
+    fn test_latched_notes_outlast_release() {
+        let midi = MidiInput::new();
+        midi.set_latched(true);
+        midi.note_on(60);
+        midi.note_off(60);
+        midi.note_on(64);
+        midi.note_off(64);
+        assert!(midi.is_latched());
+        assert_eq!(midi.held_notes(), HashSet::from([60, 64]));
+
+        midi.set_latched(false);
+        assert!(midi.held_notes().is_empty());
+    }
 }
@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Notes started this soon after the last one join the latched chord;
+/// anything later, once every key is up, starts a new one.
+pub const LATCH_WINDOW: Duration = Duration::from_millis(1500);
+
+/// The notes held down, and with latching on the ones that keep sounding
+/// after release until a new chord is started.
+#[derive(Clone, Debug, Default)]
+pub struct NoteLatch {
+    enabled: bool,
+    held: HashSet<u8>,
+    latched: HashSet<u8>,
+    last_on: Option<Instant>,
+}
+
+impl NoteLatch {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turning latching off lets go of everything not physically held.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.latched.clear();
+        self.last_on = None;
+    }
+
+    pub fn note_on(&mut self, note: u8, at: Instant) {
+        if self.enabled {
+            let lapsed = self
+                .last_on
+                .is_none_or(|last| at.saturating_duration_since(last) > LATCH_WINDOW);
+            if self.held.is_empty() && lapsed {
+                self.latched.clear();
+            }
+            self.latched.insert(note);
+            self.last_on = Some(at);
+        }
+        self.held.insert(note);
+    }
+
+    pub fn note_off(&mut self, note: u8) {
+        self.held.remove(&note);
+    }
+
+    pub fn clear(&mut self) {
+        self.held.clear();
+        self.latched.clear();
+        self.last_on = None;
+    }
+
+    /// The notes sounding: held ones, plus the latched chord.
+    pub fn notes(&self) -> HashSet<u8> {
+        self.held.union(&self.latched).copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlatched_follows_keys() {
+        let mut latch = NoteLatch::default();
+        let now = Instant::now();
+        latch.note_on(60, now);
+        latch.note_on(64, now);
+        latch.note_off(60);
+        assert_eq!(latch.notes(), HashSet::from([64]));
+        latch.note_off(64);
+        assert!(latch.notes().is_empty());
+    }
+
+    #[test]
+    fn test_builds_chord_one_note_at_a_time() {
+        let mut latch = NoteLatch::default();
+        latch.set_enabled(true);
+        let start = Instant::now();
+        for (i, note) in [60, 64, 67].into_iter().enumerate() {
+            latch.note_on(note, start + Duration::from_millis(500 * i as u64));
+            latch.note_off(note);
+        }
+        assert_eq!(latch.notes(), HashSet::from([60, 64, 67]));
+
+        // After a pause the next note starts a new chord
+        let later = start + Duration::from_secs(5);
+        latch.note_on(62, later);
+        latch.note_off(62);
+        assert_eq!(latch.notes(), HashSet::from([62]));
+
+        // A note added while another is still down joins it, however late
+        latch.note_on(65, later + Duration::from_secs(1));
+        latch.note_on(69, later + Duration::from_secs(4));
+        assert_eq!(latch.notes(), HashSet::from([62, 65, 69]));
+    }
+
+    #[test]
+    fn test_disabling_releases() {
+        let mut latch = NoteLatch::default();
+        latch.set_enabled(true);
+        let now = Instant::now();
+        latch.note_on(60, now);
+        latch.note_on(64, now);
+        latch.note_off(60);
+        latch.set_enabled(false);
+        assert_eq!(latch.notes(), HashSet::from([64]));
+    }
+}
@@ -1,3 +1,31 @@
+pub mod arpeggio;
+pub mod bend;
+pub mod control;
+pub mod demo;
+pub mod device;
+pub mod expression;
+pub mod file;
+pub mod groove;
 pub mod input;
+pub mod latch;
+pub mod output;
+pub mod playback;
+pub mod script;
+pub mod sync;
+pub mod velocity;
 
+pub use arpeggio::{ArpPattern, ArpRate, Arpeggiator};
+pub use bend::PitchBends;
+pub use control::{Control, ControlEvents, SUSTAIN_PEDAL};
+pub use demo::{Demo, DemoSong};
+pub use device::{DeviceProfiles, DeviceSettings};
+pub use expression::Expression;
+pub use file::{ChordSegment, MidiFile};
+pub use groove::Groove;
 pub use input::MidiInput;
+pub use latch::NoteLatch;
+pub use output::MidiOutput;
+pub use playback::{Humanize, NoteSink, Playback, PlaybackEvent};
+pub use script::{MidiScript, NoteSource};
+pub use sync::{ClockFollower, ClockSender, ClockSource};
+pub use velocity::{Calibration, CalibrationStage, VelocityCurve};
@@ -0,0 +1,61 @@
+use anyhow::{anyhow, Result};
+use midir::{MidiOutput as MidirOutput, MidiOutputConnection};
+
+use super::playback::{NoteSink, PlaybackEvent};
+
+pub struct MidiOutput {
+    connection: MidiOutputConnection,
+    channel: u8,
+}
+
+impl MidiOutput {
+    pub fn list_ports() -> Result<Vec<String>> {
+        let midi_out = MidirOutput::new("chordvery-list")?;
+        let ports = midi_out.ports();
+
+        let names: Vec<String> = ports
+            .iter()
+            .filter_map(|p| midi_out.port_name(p).ok())
+            .collect();
+
+        Ok(names)
+    }
+
+    pub fn connect(port_index: usize) -> Result<Self> {
+        let midi_out = MidirOutput::new("chordvery")?;
+        let ports = midi_out.ports();
+
+        if port_index >= ports.len() {
+            return Err(anyhow!("Port index {} out of range", port_index));
+        }
+
+        let port = &ports[port_index];
+        let port_name = midi_out.port_name(port)?;
+
+        let connection = midi_out
+            .connect(port, "chordvery-output")
+            .map_err(|e| anyhow!("{}", e))?;
+
+        eprintln!("Connected to MIDI output: {}", port_name);
+
+        Ok(Self {
+            connection,
+            channel: 0,
+        })
+    }
+
+    pub fn set_channel(&mut self, channel: u8) {
+        self.channel = channel & 0x0F;
+    }
+
+    pub fn send(&mut self, message: &[u8]) {
+        let _ = self.connection.send(message);
+    }
+}
+
+impl NoteSink for MidiOutput {
+    fn send(&mut self, event: PlaybackEvent) {
+        let message = event.to_midi(self.channel);
+        MidiOutput::send(self, &message);
+    }
+}
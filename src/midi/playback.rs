@@ -0,0 +1,469 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use super::arpeggio::Arpeggiator;
+use super::groove::Groove;
+use crate::theory::{Chord, VoicingOptions};
+
+const DEFAULT_VELOCITY: u8 = 90;
+const MAX_ROLL_MS: f32 = 35.0;
+const MAX_TIMING_MS: f32 = 15.0;
+const MAX_VELOCITY_SPREAD: f32 = 20.0;
+const MIN_NOTE: Duration = Duration::from_millis(10);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaybackEvent {
+    NoteOn { note: u8, velocity: u8 },
+    NoteOff { note: u8 },
+}
+
+impl PlaybackEvent {
+    pub fn to_midi(&self, channel: u8) -> [u8; 3] {
+        match *self {
+            PlaybackEvent::NoteOn { note, velocity } => [0x90 | channel, note, velocity],
+            PlaybackEvent::NoteOff { note } => [0x80 | channel, note, 0],
+        }
+    }
+}
+
+pub trait NoteSink {
+    fn send(&mut self, event: PlaybackEvent);
+}
+
+impl NoteSink for Vec<PlaybackEvent> {
+    fn send(&mut self, event: PlaybackEvent) {
+        self.push(event);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Humanize {
+    pub amount: f32,
+    pub roll: bool,
+}
+
+impl Humanize {
+    pub fn new(amount: f32) -> Self {
+        Self {
+            amount: amount.clamp(0.0, 1.0),
+            roll: true,
+        }
+    }
+
+    pub fn roll(mut self, roll: bool) -> Self {
+        self.roll = roll;
+        self
+    }
+
+    pub fn is_off(&self) -> bool {
+        self.amount <= 0.0
+    }
+}
+
+#[derive(Default)]
+pub struct Playback {
+    events: VecDeque<(Duration, PlaybackEvent)>,
+    started: Option<Instant>,
+    sounding: HashSet<u8>,
+}
+
+impl Playback {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn chords(chords: &[Chord], chord_duration: Duration) -> Self {
+        let voicings: Vec<Vec<u8>> = chords
+            .iter()
+            .map(|c| c.voicing(VoicingOptions::default()))
+            .collect();
+        Self::voicings(&voicings, chord_duration)
+    }
+
+    pub fn voicings(voicings: &[Vec<u8>], chord_duration: Duration) -> Self {
+        let mut playback = Self::new();
+        let release = chord_duration.mul_f32(0.95);
+
+        for (i, notes) in voicings.iter().enumerate() {
+            let start = chord_duration * i as u32;
+            for &note in notes {
+                playback.schedule(
+                    start,
+                    PlaybackEvent::NoteOn {
+                        note,
+                        velocity: DEFAULT_VELOCITY,
+                    },
+                );
+                playback.schedule(start + release, PlaybackEvent::NoteOff { note });
+            }
+        }
+
+        playback
+    }
+
+    /// Each voicing broken up by `arp` at `bpm`, one after another.
+    pub fn arpeggiated<R: Rng>(
+        voicings: &[Vec<u8>],
+        chord_duration: Duration,
+        arp: &Arpeggiator,
+        bpm: f32,
+        rng: &mut R,
+    ) -> Self {
+        let mut playback = Self::new();
+        for (i, notes) in voicings.iter().enumerate() {
+            let start = chord_duration * i as u32;
+            for (offset, length, note) in arp.notes(notes, chord_duration, bpm, rng) {
+                playback.schedule(
+                    start + offset,
+                    PlaybackEvent::NoteOn {
+                        note,
+                        velocity: DEFAULT_VELOCITY,
+                    },
+                );
+                playback.schedule(start + offset + length, PlaybackEvent::NoteOff { note });
+            }
+        }
+        playback
+    }
+
+    /// Each voicing comped to `groove` at `bpm`, one after another.
+    pub fn grooved(
+        voicings: &[Vec<u8>],
+        chord_duration: Duration,
+        groove: &Groove,
+        bpm: f32,
+    ) -> Self {
+        let mut playback = Self::new();
+        let beat = Duration::from_secs_f32(60.0 / bpm);
+        let beats = chord_duration.as_secs_f32() / beat.as_secs_f32();
+        for (i, notes) in voicings.iter().enumerate() {
+            playback.schedule_groove(chord_duration * i as u32, notes, groove, beats, beat);
+        }
+        playback
+    }
+
+    /// Strikes `notes` to `groove` for `beats` from `start`.
+    pub fn schedule_groove(
+        &mut self,
+        start: Duration,
+        notes: &[u8],
+        groove: &Groove,
+        beats: f32,
+        beat: Duration,
+    ) {
+        for (at, length, velocity) in groove.hits(beats) {
+            let on = start + beat.mul_f32(at);
+            let off = on + beat.mul_f32(length * 0.95);
+            for &note in notes {
+                self.schedule(on, PlaybackEvent::NoteOn { note, velocity });
+                self.schedule(off, PlaybackEvent::NoteOff { note });
+            }
+        }
+    }
+
+    pub fn melody(notes: &[u8], note_duration: Duration) -> Self {
+        let mut playback = Self::new();
+        let release = note_duration.mul_f32(0.95);
+
+        for (i, &note) in notes.iter().enumerate() {
+            let start = note_duration * i as u32;
+            playback.schedule(
+                start,
+                PlaybackEvent::NoteOn {
+                    note,
+                    velocity: DEFAULT_VELOCITY,
+                },
+            );
+            playback.schedule(start + release, PlaybackEvent::NoteOff { note });
+        }
+
+        playback
+    }
+
+    /// Rolls chords from the bottom up and adds small timing and velocity
+    /// variations, scaled by `humanize.amount`.
+    pub fn humanize<R: Rng>(self, humanize: &Humanize, rng: &mut R) -> Self {
+        if humanize.is_off() {
+            return self;
+        }
+
+        let roll = if humanize.roll {
+            humanize.amount * MAX_ROLL_MS
+        } else {
+            0.0
+        };
+        let timing = humanize.amount * MAX_TIMING_MS;
+        let spread = (humanize.amount * MAX_VELOCITY_SPREAD) as i16;
+
+        let mut out = Self::new();
+        let mut chord_start = None;
+        let mut chord_index = 0;
+        let mut started: HashMap<u8, Duration> = HashMap::new();
+
+        for (at, event) in self.events {
+            match event {
+                PlaybackEvent::NoteOn { note, velocity } => {
+                    if chord_start == Some(at) {
+                        chord_index += 1;
+                    } else {
+                        chord_start = Some(at);
+                        chord_index = 0;
+                    }
+
+                    let offset_ms = roll * chord_index as f32 + rng.random_range(-timing..=timing);
+                    let shifted = if offset_ms >= 0.0 {
+                        at + Duration::from_secs_f32(offset_ms / 1000.0)
+                    } else {
+                        at.saturating_sub(Duration::from_secs_f32(-offset_ms / 1000.0))
+                    };
+                    let velocity =
+                        (velocity as i16 + rng.random_range(-spread..=spread)).clamp(1, 127) as u8;
+
+                    started.insert(note, shifted);
+                    out.schedule(shifted, PlaybackEvent::NoteOn { note, velocity });
+                }
+                PlaybackEvent::NoteOff { note } => {
+                    let earliest = started.get(&note).map(|&on| on + MIN_NOTE);
+                    out.schedule(at.max(earliest.unwrap_or(at)), event);
+                }
+            }
+        }
+
+        out
+    }
+
+    pub fn schedule(&mut self, at: Duration, event: PlaybackEvent) {
+        let index = self.events.partition_point(|(t, _)| *t <= at);
+        self.events.insert(index, (at, event));
+    }
+
+    pub fn start(&mut self, now: Instant) {
+        self.started = Some(now);
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.started.is_some() && (!self.events.is_empty() || !self.sounding.is_empty())
+    }
+
+    pub fn tick(&mut self, now: Instant, sink: &mut dyn NoteSink) {
+        let Some(started) = self.started else {
+            return;
+        };
+        let elapsed = now.saturating_duration_since(started);
+
+        while let Some(&(at, event)) = self.events.front() {
+            if at > elapsed {
+                break;
+            }
+            self.events.pop_front();
+
+            match event {
+                PlaybackEvent::NoteOn { note, .. } => {
+                    self.sounding.insert(note);
+                }
+                PlaybackEvent::NoteOff { note } => {
+                    self.sounding.remove(&note);
+                }
+            }
+            sink.send(event);
+        }
+    }
+
+    pub fn stop(&mut self, sink: &mut dyn NoteSink) {
+        for note in self.sounding.drain() {
+            sink.send(PlaybackEvent::NoteOff { note });
+        }
+        self.events.clear();
+        self.started = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::{Note, Quality};
+
+    #[test]
+    fn test_schedule_order() {
+        let mut playback = Playback::new();
+        playback.schedule(
+            Duration::from_millis(20),
+            PlaybackEvent::NoteOff { note: 60 },
+        );
+        playback.schedule(
+            Duration::from_millis(10),
+            PlaybackEvent::NoteOn {
+                note: 60,
+                velocity: 100,
+            },
+        );
+
+        let start = Instant::now();
+        playback.start(start);
+
+        let mut sink = Vec::new();
+        playback.tick(start + Duration::from_millis(15), &mut sink);
+        assert_eq!(
+            sink,
+            vec![PlaybackEvent::NoteOn {
+                note: 60,
+                velocity: 100
+            }]
+        );
+
+        playback.tick(start + Duration::from_millis(25), &mut sink);
+        assert_eq!(sink.len(), 2);
+        assert!(!playback.is_playing());
+    }
+
+    #[test]
+    fn test_arpeggiated() {
+        use crate::midi::{ArpPattern, ArpRate};
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let arp = Arpeggiator {
+            pattern: ArpPattern::Down,
+            rate: ArpRate::Eighth,
+        };
+        let voicings = [vec![60, 64, 67], vec![62, 65, 69]];
+        let mut playback = Playback::arpeggiated(
+            &voicings,
+            Duration::from_secs(1),
+            &arp,
+            120.0,
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        let start = Instant::now();
+        playback.start(start);
+        let mut sink = Vec::new();
+        playback.tick(start + Duration::from_millis(1100), &mut sink);
+        let ons: Vec<u8> = sink
+            .iter()
+            .filter_map(|e| match e {
+                PlaybackEvent::NoteOn { note, .. } => Some(*note),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ons, [67, 64, 60, 67, 69]);
+    }
+
+    #[test]
+    fn test_chords_playback() {
+        let chords = vec![
+            Chord::new(Note::new(60), Quality::Major),
+            Chord::new(Note::new(67), Quality::Major),
+        ];
+        let mut playback = Playback::chords(&chords, Duration::from_secs(1));
+
+        let start = Instant::now();
+        playback.start(start);
+
+        let mut sink = Vec::new();
+        playback.tick(start, &mut sink);
+        assert_eq!(sink.len(), 3);
+        assert!(playback.is_playing());
+
+        playback.tick(start + Duration::from_secs(2), &mut sink);
+        assert_eq!(sink.len(), 12);
+        assert!(!playback.is_playing());
+    }
+
+    #[test]
+    fn test_humanize_rolls_chord() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let chords = vec![Chord::new(Note::new(60), Quality::Major)];
+        let humanize = Humanize::new(1.0);
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut playback =
+            Playback::chords(&chords, Duration::from_secs(1)).humanize(&humanize, &mut rng);
+
+        let ons: Vec<(Duration, u8)> = playback
+            .events
+            .iter()
+            .filter_map(|(at, e)| match e {
+                PlaybackEvent::NoteOn { velocity, .. } => Some((*at, *velocity)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ons.len(), 3);
+        assert!(ons[2].0 >= Duration::from_millis(40));
+        assert!(ons.iter().all(|(_, v)| (70..=110).contains(v)));
+
+        let start = Instant::now();
+        playback.start(start);
+        let mut sink = Vec::new();
+        playback.tick(start + Duration::from_secs(2), &mut sink);
+        assert_eq!(sink.len(), 6);
+    }
+
+    #[test]
+    fn test_humanize_off_is_identity() {
+        let chords = vec![Chord::new(Note::new(60), Quality::Major)];
+        let playback = Playback::chords(&chords, Duration::from_secs(1));
+        let expected = playback.events.clone();
+
+        let mut rng = rand::rng();
+        let same = playback.humanize(&Humanize::default(), &mut rng);
+        assert_eq!(same.events, expected);
+    }
+
+    #[test]
+    fn test_melody_is_sequential() {
+        let mut playback = Playback::melody(&[60, 67], Duration::from_millis(500));
+
+        let start = Instant::now();
+        playback.start(start);
+
+        let mut sink = Vec::new();
+        playback.tick(start, &mut sink);
+        assert_eq!(
+            sink,
+            vec![PlaybackEvent::NoteOn {
+                note: 60,
+                velocity: DEFAULT_VELOCITY
+            }]
+        );
+
+        playback.tick(start + Duration::from_millis(500), &mut sink);
+        assert_eq!(sink.len(), 3);
+    }
+
+    #[test]
+    fn test_stop_releases_sounding_notes() {
+        let chords = vec![Chord::new(Note::new(60), Quality::Major)];
+        let mut playback = Playback::chords(&chords, Duration::from_secs(1));
+
+        let start = Instant::now();
+        playback.start(start);
+
+        let mut sink = Vec::new();
+        playback.tick(start, &mut sink);
+        sink.clear();
+
+        playback.stop(&mut sink);
+        assert_eq!(sink.len(), 3);
+        assert!(sink
+            .iter()
+            .all(|e| matches!(e, PlaybackEvent::NoteOff { .. })));
+        assert!(!playback.is_playing());
+    }
+
+    #[test]
+    fn test_to_midi() {
+        let on = PlaybackEvent::NoteOn {
+            note: 60,
+            velocity: 90,
+        };
+        assert_eq!(on.to_midi(1), [0x91, 60, 90]);
+        assert_eq!(
+            PlaybackEvent::NoteOff { note: 60 }.to_midi(0),
+            [0x80, 60, 0]
+        );
+    }
+}
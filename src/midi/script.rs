@@ -0,0 +1,87 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Somewhere MIDI comes from other than a port: messages handed over as
+/// their time comes, to be received as if the port had sent them.
+pub trait NoteSource: Send {
+    /// Messages due by `now`, oldest first.
+    fn due(&mut self, now: Instant) -> Vec<Vec<u8>>;
+
+    /// Whether there's nothing left to come.
+    fn is_finished(&self) -> bool;
+}
+
+/// MIDI messages at set times from when it's first asked for them.
+#[derive(Clone, Debug, Default)]
+pub struct MidiScript {
+    started: Option<Instant>,
+    events: VecDeque<(Duration, Vec<u8>)>,
+}
+
+impl MidiScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `message` at `at`, after anything already at that time.
+    pub fn message(mut self, at: Duration, message: &[u8]) -> Self {
+        let index = self.events.partition_point(|(t, _)| *t <= at);
+        self.events.insert(index, (at, message.to_vec()));
+        self
+    }
+
+    /// Adds `notes` pressed at `at` and let go `length` later.
+    pub fn chord(self, at: Duration, notes: &[u8], velocity: u8, length: Duration) -> Self {
+        let script = notes.iter().fold(self, |script, &note| {
+            script.message(at, &[0x90, note, velocity])
+        });
+        notes.iter().fold(script, |script, &note| {
+            script.message(at + length, &[0x80, note, 0])
+        })
+    }
+
+    /// When the last message is, from the start.
+    pub fn length(&self) -> Duration {
+        self.events.back().map(|(t, _)| *t).unwrap_or_default()
+    }
+}
+
+impl NoteSource for MidiScript {
+    fn due(&mut self, now: Instant) -> Vec<Vec<u8>> {
+        let started = *self.started.get_or_insert(now);
+        let elapsed = now.saturating_duration_since(started);
+        let count = self.events.partition_point(|(t, _)| *t <= elapsed);
+        self.events
+            .drain(..count)
+            .map(|(_, message)| message)
+            .collect()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script() {
+        let second = Duration::from_secs(1);
+        let mut script = MidiScript::new()
+            .chord(second, &[60, 64], 100, second)
+            .message(Duration::ZERO, &[0xC0, 3]);
+        assert_eq!(script.length(), second * 2);
+
+        let start = Instant::now();
+        assert_eq!(script.due(start), vec![vec![0xC0, 3]]);
+        assert!(script.due(start + second / 2).is_empty());
+        assert_eq!(
+            script.due(start + second),
+            vec![vec![0x90, 60, 100], vec![0x90, 64, 100]]
+        );
+        assert_eq!(script.due(start + second * 5).len(), 2);
+        assert!(script.is_finished());
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// MIDI clock pulses per quarter note
+pub const PPQN: u32 = 24;
+pub const CLOCK: u8 = 0xF8;
+pub const START: u8 = 0xFA;
+pub const STOP: u8 = 0xFC;
+/// Most pulses sent at once after a stall; a receiver that missed more
+/// than a beat is better left to drift than rushed
+const MAX_BURST: u64 = PPQN as u64;
+/// Longest gap between pulses coming in before their clock counts as
+/// stopped
+const DROPOUT: Duration = Duration::from_millis(500);
+
+/// Where the tempo comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockSource {
+    Internal,
+    /// Kept here and sent out as MIDI clock
+    Sending,
+    /// Read from MIDI clock coming in
+    Following,
+}
+
+impl ClockSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClockSource::Internal => "internal",
+            ClockSource::Sending => "sending",
+            ClockSource::Following => "following",
+        }
+    }
+}
+
+/// Paces MIDI clock pulses for a tempo, so a drum machine or DAW on the
+/// output follows along after a MIDI Start.
+#[derive(Clone, Debug)]
+pub struct ClockSender {
+    pulse: Duration,
+    started: Instant,
+    sent: u64,
+}
+
+impl ClockSender {
+    pub fn start(bpm: f32, now: Instant) -> Self {
+        Self {
+            pulse: Duration::from_secs_f32(60.0 / bpm / PPQN as f32),
+            started: now,
+            sent: 0,
+        }
+    }
+
+    /// Pulses due by `now` that haven't been sent yet, the first one right
+    /// at the start.
+    pub fn due(&mut self, now: Instant) -> u64 {
+        let elapsed = now.saturating_duration_since(self.started);
+        let total = (elapsed.as_secs_f64() / self.pulse.as_secs_f64()) as u64 + 1;
+        let due = total.saturating_sub(self.sent);
+        self.sent = total;
+        due.min(MAX_BURST)
+    }
+}
+
+/// Times the MIDI clock pulses coming in, to read the tempo they keep.
+#[derive(Clone, Debug, Default)]
+pub struct ClockFollower {
+    /// The last beat's worth
+    pulses: VecDeque<Instant>,
+}
+
+impl ClockFollower {
+    pub fn pulse(&mut self, at: Instant) {
+        if self
+            .pulses
+            .back()
+            .is_some_and(|&last| at.saturating_duration_since(last) > DROPOUT)
+        {
+            self.pulses.clear();
+        }
+        self.pulses.push_back(at);
+        if self.pulses.len() > PPQN as usize + 1 {
+            self.pulses.pop_front();
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.pulses.clear();
+    }
+
+    /// The tempo of the last beat of pulses, once there's a beat of them
+    /// and while they keep coming.
+    pub fn tempo(&self, now: Instant) -> Option<f32> {
+        let (&first, &last) = (self.pulses.front()?, self.pulses.back()?);
+        if self.pulses.len() <= PPQN as usize || now.saturating_duration_since(last) > DROPOUT {
+            return None;
+        }
+        let beat = last.duration_since(first).as_secs_f32();
+        (beat > 0.0).then(|| 60.0 / beat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pulses_follow_tempo() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        // A beat at 120 bpm is 500ms, so a pulse every 20.8ms
+        let mut clock = ClockSender::start(120.0, start);
+        assert_eq!(clock.due(start), 1);
+        assert_eq!(clock.due(start), 0);
+        assert_eq!(clock.due(ms(499)), 23);
+        assert_eq!(clock.due(ms(990)), 24);
+    }
+
+    #[test]
+    fn test_burst_is_capped() {
+        let start = Instant::now();
+        let mut clock = ClockSender::start(120.0, start);
+        clock.due(start);
+        assert_eq!(clock.due(start + Duration::from_secs(5)), MAX_BURST);
+        assert_eq!(clock.due(start + Duration::from_secs(5)), 0);
+    }
+
+    #[test]
+    fn test_following_a_clock() {
+        let start = Instant::now();
+        let mut clock = ClockFollower::default();
+        // 24 pulses 25ms apart: a beat of 600ms
+        for n in 0..PPQN as u64 {
+            clock.pulse(start + Duration::from_millis(25 * n));
+        }
+        let last = start + Duration::from_millis(25 * (PPQN as u64 - 1));
+        assert_eq!(clock.tempo(last), None);
+
+        let last = last + Duration::from_millis(25);
+        clock.pulse(last);
+        assert_eq!(clock.tempo(last).map(f32::round), Some(100.0));
+        assert_eq!(clock.tempo(last + DROPOUT * 2), None);
+        clock.stop();
+        assert_eq!(clock.tempo(last), None);
+    }
+}
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+/// Notes played at each stage of a calibration
+pub const CALIBRATION_NOTES: usize = 6;
+/// What the softest playing is mapped to after calibrating, clear of
+/// a velocity floor set low
+const SOFT_TARGET: f32 = 24.0;
+/// Points in a calibrated table
+const TABLE_POINTS: usize = 9;
+
+/// How note-on velocities are reshaped as they come in, before the
+/// velocity floor is checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VelocityCurve {
+    #[default]
+    Linear,
+    /// Louder for less effort, for heavy or weighted keys
+    Soft,
+    /// Takes more effort to play loud
+    Hard,
+    /// Outputs at evenly spaced inputs from 0 to 127, joined by straight
+    /// lines
+    Custom(Vec<u8>),
+}
+
+impl VelocityCurve {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "linear" => Some(VelocityCurve::Linear),
+            "soft" => Some(VelocityCurve::Soft),
+            "hard" => Some(VelocityCurve::Hard),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VelocityCurve::Linear => "linear",
+            VelocityCurve::Soft => "soft",
+            VelocityCurve::Hard => "hard",
+            VelocityCurve::Custom(_) => "custom",
+        }
+    }
+
+    /// The velocity to use for one played at `velocity`. A note-on stays a
+    /// note-on: only 0 maps to 0.
+    pub fn apply(&self, velocity: u8) -> u8 {
+        if velocity == 0 {
+            return 0;
+        }
+        let x = velocity.min(127) as f32 / 127.0;
+        let y = match self {
+            VelocityCurve::Linear => return velocity,
+            VelocityCurve::Soft => x.powf(0.6),
+            VelocityCurve::Hard => x.powf(1.7),
+            VelocityCurve::Custom(points) if points.len() < 2 => return velocity,
+            VelocityCurve::Custom(points) => {
+                let at = x * (points.len() - 1) as f32;
+                let i = (at.floor() as usize).min(points.len() - 2);
+                let (a, b) = (points[i] as f32, points[i + 1] as f32);
+                (a + (b - a) * (at - i as f32)) / 127.0
+            }
+        };
+        ((y * 127.0).round() as u8).clamp(1, 127)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CalibrationStage {
+    Soft,
+    Hard,
+    Done,
+}
+
+/// Measures how softly and how hard someone plays: a few notes of each,
+/// then a curve that spreads that range over the full one.
+#[derive(Clone, Debug, Default)]
+pub struct Calibration {
+    soft: Vec<u8>,
+    hard: Vec<u8>,
+}
+
+fn median(velocities: &[u8]) -> Option<u8> {
+    let mut sorted = velocities.to_vec();
+    sorted.sort_unstable();
+    sorted.get(sorted.len() / 2).copied()
+}
+
+impl Calibration {
+    pub fn stage(&self) -> CalibrationStage {
+        if self.soft.len() < CALIBRATION_NOTES {
+            CalibrationStage::Soft
+        } else if self.hard.len() < CALIBRATION_NOTES {
+            CalibrationStage::Hard
+        } else {
+            CalibrationStage::Done
+        }
+    }
+
+    /// Notes recorded so far in the current stage.
+    pub fn count(&self) -> usize {
+        match self.stage() {
+            CalibrationStage::Soft => self.soft.len(),
+            CalibrationStage::Hard => self.hard.len(),
+            CalibrationStage::Done => CALIBRATION_NOTES,
+        }
+    }
+
+    /// Records a raw note-on velocity towards the current stage.
+    pub fn record(&mut self, velocity: u8) {
+        match self.stage() {
+            CalibrationStage::Soft => self.soft.push(velocity),
+            CalibrationStage::Hard => self.hard.push(velocity),
+            CalibrationStage::Done => {}
+        }
+    }
+
+    pub fn soft(&self) -> Option<u8> {
+        median(&self.soft)
+    }
+
+    pub fn hard(&self) -> Option<u8> {
+        median(&self.hard)
+    }
+
+    /// Once done, a curve taking typical soft playing to a quiet but
+    /// certain velocity and typical hard playing to the top. None if the
+    /// hard notes weren't any harder.
+    pub fn curve(&self) -> Option<VelocityCurve> {
+        if self.stage() != CalibrationStage::Done {
+            return None;
+        }
+        let (soft, hard) = (self.soft()? as f32, self.hard()? as f32);
+        if hard <= soft {
+            return None;
+        }
+        let points = (0..TABLE_POINTS)
+            .map(|i| {
+                let x = i as f32 * 127.0 / (TABLE_POINTS - 1) as f32;
+                let y = if x <= soft {
+                    x / soft * SOFT_TARGET
+                } else {
+                    SOFT_TARGET + (x - soft) / (hard - soft) * (127.0 - SOFT_TARGET)
+                };
+                y.round().min(127.0) as u8
+            })
+            .collect();
+        Some(VelocityCurve::Custom(points))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curves() {
+        assert_eq!(VelocityCurve::Linear.apply(40), 40);
+        assert!(VelocityCurve::Soft.apply(40) > 60);
+        assert!(VelocityCurve::Hard.apply(40) < 20);
+        for curve in [VelocityCurve::Soft, VelocityCurve::Hard] {
+            assert_eq!(curve.apply(0), 0);
+            assert_eq!(curve.apply(127), 127);
+        }
+        // Still a note-on
+        assert_eq!(VelocityCurve::Hard.apply(1), 1);
+
+        let custom = VelocityCurve::Custom(vec![0, 100, 127]);
+        assert_eq!(custom.apply(32), 50);
+        assert_eq!(custom.apply(127), 127);
+        assert_eq!(VelocityCurve::Custom(vec![]).apply(33), 33);
+        assert_eq!(VelocityCurve::from_name(" Soft"), Some(VelocityCurve::Soft));
+        assert_eq!(VelocityCurve::from_name("custom"), None);
+    }
+
+    #[test]
+    fn test_calibration() {
+        let mut calibration = Calibration::default();
+        for velocity in [12, 9, 15, 10, 11, 30] {
+            calibration.record(velocity);
+        }
+        assert_eq!(calibration.stage(), CalibrationStage::Hard);
+        assert_eq!(calibration.curve(), None);
+        for velocity in [90, 96, 100, 92, 95, 98] {
+            calibration.record(velocity);
+        }
+        assert_eq!(calibration.stage(), CalibrationStage::Done);
+        assert_eq!(calibration.soft(), Some(12));
+        assert_eq!(calibration.hard(), Some(96));
+
+        let curve = calibration.curve().unwrap();
+        // Near the targets, as far as a table of a few points gets
+        assert!((20..=28).contains(&curve.apply(12)));
+        assert!(curve.apply(96) >= 124);
+        assert_eq!(curve.apply(127), 127);
+        assert!(curve.apply(5) < curve.apply(12));
+    }
+
+    #[test]
+    fn test_calibration_needs_a_range() {
+        let mut calibration = Calibration::default();
+        for _ in 0..CALIBRATION_NOTES * 2 {
+            calibration.record(64);
+        }
+        assert_eq!(calibration.curve(), None);
+    }
+}
@@ -0,0 +1,96 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+use crate::session::SessionEntry;
+use crate::theory::{Chord, NamingStyle, Spelling};
+use crate::ui::App;
+
+/// Headless chord journal: writes a timestamped line for every chord the
+/// app records, without drawing the TUI.
+pub struct Monitor<W: Write> {
+    out: W,
+    seen: usize,
+}
+
+impl<W: Write> Monitor<W> {
+    pub fn new(out: W) -> Self {
+        Self { out, seen: 0 }
+    }
+
+    /// Writes the chords recorded since the last poll and returns how many.
+    pub fn poll(&mut self, app: &App) -> Result<usize> {
+        let entries = &app.session.entries;
+        if entries.len() < self.seen {
+            self.seen = 0;
+        }
+        let new = &entries[self.seen..];
+        if new.is_empty() {
+            return Ok(0);
+        }
+
+        for entry in new {
+            writeln!(self.out, "{}", line(entry, app.naming, app.spelling()))?;
+        }
+        self.seen = entries.len();
+        self.out.flush()?;
+        Ok(new.len())
+    }
+}
+
+pub fn timestamp(seconds: f64) -> String {
+    let tenths = (seconds.max(0.0) * 10.0).round() as u64;
+    let (minutes, tenths) = (tenths / 600, tenths % 600);
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:04.1}", hours, minutes, tenths as f64 / 10.0)
+    } else {
+        format!("{:02}:{:04.1}", minutes, tenths as f64 / 10.0)
+    }
+}
+
+fn line(entry: &SessionEntry, naming: NamingStyle, spelling: Spelling) -> String {
+    let name = Chord::from_name(&entry.chord)
+        .map(|c| c.name_spelled(naming, spelling))
+        .unwrap_or_else(|| entry.chord.clone());
+    let notes: Vec<String> = entry.notes.iter().map(|n| n.to_string()).collect();
+    format!(
+        "{}  {:<10} {}",
+        timestamp(entry.at.unwrap_or_default()),
+        name,
+        notes.join(" ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(0.0), "00:00.0");
+        assert_eq!(timestamp(75.25), "01:15.3");
+        assert_eq!(timestamp(3725.0), "1:02:05.0");
+    }
+
+    #[test]
+    fn test_poll_writes_new_chords_once() {
+        let mut app = App::new();
+        let mut monitor = Monitor::new(Vec::new());
+
+        let c = Chord::from_name("C").unwrap();
+        app.session.push_timed(&c, vec![48, 52, 55], 1.5);
+        assert_eq!(monitor.poll(&app).unwrap(), 1);
+        assert_eq!(monitor.poll(&app).unwrap(), 0);
+
+        let g = Chord::from_name("G7").unwrap();
+        app.session.push_timed(&g, vec![43, 47, 50, 53], 3.0);
+        monitor.poll(&app).unwrap();
+
+        let text = String::from_utf8(monitor.out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "00:01.5  C          48 52 55");
+        assert!(lines[1].starts_with("00:03.0  G7"));
+    }
+}
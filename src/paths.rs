@@ -0,0 +1,224 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+const APP_DIR: &str = "chordvery";
+const USERS_DIR: &str = "users";
+
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(APP_DIR)
+}
+
+pub fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(APP_DIR)
+}
+
+/// Shared by every user: device settings belong to the hardware.
+pub fn devices_path() -> PathBuf {
+    config_dir().join("devices.toml")
+}
+
+/// Controller buttons mapped to actions, shared like device settings.
+pub fn controls_path() -> PathBuf {
+    config_dir().join("controls.toml")
+}
+
+/// The user's own comping grooves, added to the built-in ones.
+pub fn grooves_path() -> PathBuf {
+    config_dir().join("grooves.toml")
+}
+
+pub fn scripts_dir() -> PathBuf {
+    config_dir().join("scripts")
+}
+
+/// Names of the suggestion profiles in the scripts directory, sorted.
+pub fn profiles() -> Vec<String> {
+    profiles_in(&scripts_dir())
+}
+
+fn profiles_in(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "rhai"))
+        .filter_map(|p| Some(p.file_stem()?.to_str()?.to_string()))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Where one user's config, stats and sessions live. The default user's
+/// are straight in the app directories, named users' under `users/<name>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UserDirs {
+    pub name: Option<String>,
+    pub data: PathBuf,
+    pub config: PathBuf,
+}
+
+impl UserDirs {
+    pub fn new(name: Option<&str>) -> Result<Self> {
+        Self::under(&data_dir(), &config_dir(), name)
+    }
+
+    /// A user's directories inside the given data and config directories.
+    pub fn under(data: &Path, config: &Path, name: Option<&str>) -> Result<Self> {
+        let Some(name) = name else {
+            return Ok(Self {
+                name: None,
+                data: data.to_path_buf(),
+                config: config.to_path_buf(),
+            });
+        };
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(anyhow!(
+                "Invalid user name '{}': use letters, digits, '-' and '_'",
+                name
+            ));
+        }
+        Ok(Self {
+            name: Some(name.to_string()),
+            data: data.join(USERS_DIR).join(name),
+            config: config.join(USERS_DIR).join(name),
+        })
+    }
+
+    /// "default" for the default user.
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("default")
+    }
+
+    pub fn config_path(&self) -> PathBuf {
+        self.config.join("config.toml")
+    }
+
+    pub fn adaptive_model_path(&self) -> PathBuf {
+        self.data.join("adaptive.json")
+    }
+
+    pub fn dictation_stats_path(&self) -> PathBuf {
+        self.data.join("dictation.json")
+    }
+
+    pub fn practice_log_path(&self) -> PathBuf {
+        self.data.join("practice.json")
+    }
+
+    pub fn milestones_path(&self) -> PathBuf {
+        self.data.join("milestones.json")
+    }
+
+    pub fn sessions_dir(&self) -> PathBuf {
+        self.data.join("sessions")
+    }
+
+    /// Sessions in progress, saved now and then to recover after a crash.
+    pub fn autosave_dir(&self) -> PathBuf {
+        self.data.join("autosave")
+    }
+
+    /// The app directories these users live in.
+    fn base(&self) -> (PathBuf, PathBuf) {
+        let up = |dir: &Path| match self.name {
+            Some(_) => dir.ancestors().nth(2).unwrap_or(dir).to_path_buf(),
+            None => dir.to_path_buf(),
+        };
+        (up(&self.data), up(&self.config))
+    }
+
+    /// Another user alongside this one.
+    pub fn switch(&self, name: Option<&str>) -> Result<Self> {
+        let (data, config) = self.base();
+        Self::under(&data, &config, name)
+    }
+
+    /// Named users with a data or config directory, sorted.
+    pub fn users(&self) -> Vec<String> {
+        let (data, config) = self.base();
+        users_in(&data, &config)
+    }
+}
+
+fn users_in(data: &Path, config: &Path) -> Vec<String> {
+    let mut names: Vec<String> = [data, config]
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir.join(USERS_DIR)).ok())
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_dirs() {
+        let (data, config) = (Path::new("/d"), Path::new("/c"));
+        let default = UserDirs::under(data, config, None).unwrap();
+        assert_eq!(default.practice_log_path(), Path::new("/d/practice.json"));
+        assert_eq!(default.display_name(), "default");
+
+        let kid = UserDirs::under(data, config, Some("kid")).unwrap();
+        assert_eq!(
+            kid.practice_log_path(),
+            Path::new("/d/users/kid/practice.json")
+        );
+        assert_eq!(kid.config_path(), Path::new("/c/users/kid/config.toml"));
+
+        assert_eq!(kid.switch(None).unwrap(), default);
+        assert_eq!(
+            default
+                .switch(Some("kid"))
+                .unwrap()
+                .switch(Some("kid"))
+                .unwrap(),
+            kid
+        );
+        assert!(UserDirs::under(data, config, Some("../x")).is_err());
+        assert!(UserDirs::under(data, config, Some("")).is_err());
+    }
+
+    #[test]
+    fn test_profiles_in() {
+        let dir = std::env::temp_dir().join(format!("chordvery-scripts-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for file in ["jazz.rhai", "backdoor.rhai", "notes.txt"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        assert_eq!(profiles_in(&dir), vec!["backdoor", "jazz"]);
+        assert!(profiles_in(&dir.join("missing")).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_users_in() {
+        let root = std::env::temp_dir().join(format!("chordvery-users-{}", std::process::id()));
+        let (data, config) = (root.join("data"), root.join("config"));
+        fs::create_dir_all(data.join("users/sam")).unwrap();
+        fs::create_dir_all(config.join("users/ana")).unwrap();
+        fs::create_dir_all(config.join("users/sam")).unwrap();
+
+        assert_eq!(users_in(&data, &config), vec!["ana", "sam"]);
+        let sam = UserDirs::under(&data, &config, Some("sam")).unwrap();
+        assert_eq!(sam.users(), vec!["ana", "sam"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
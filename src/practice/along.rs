@@ -0,0 +1,96 @@
+use crate::midi::file::ChordSegment;
+use crate::theory::Chord;
+
+use super::{same_chord, Score};
+
+/// Scores playing along with a file: each of the file's chords counts as
+/// a hit if the player matches it at some point while it sounds.
+#[derive(Clone, Debug)]
+pub struct PlayAlong {
+    name: String,
+    segments: Vec<ChordSegment>,
+    hits: Vec<bool>,
+}
+
+impl PlayAlong {
+    pub fn new(name: &str, segments: Vec<ChordSegment>) -> Self {
+        let hits = vec![false; segments.len()];
+        Self {
+            name: name.to_string(),
+            segments,
+            hits,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn segment_index(&self, time: f64) -> Option<usize> {
+        self.segments
+            .iter()
+            .position(|s| s.start <= time && time < s.end)
+    }
+
+    pub fn segment_at(&self, time: f64) -> Option<&ChordSegment> {
+        self.segment_index(time).map(|i| &self.segments[i])
+    }
+
+    pub fn is_hit(&self, index: usize) -> bool {
+        self.hits.get(index).copied().unwrap_or(false)
+    }
+
+    pub fn check(&mut self, played: &Chord, time: f64) -> bool {
+        let Some(index) = self.segment_index(time) else {
+            return false;
+        };
+        if same_chord(&self.segments[index].chord, played) {
+            self.hits[index] = true;
+        }
+        self.hits[index]
+    }
+
+    /// Hits out of the chords that have started by `time`.
+    pub fn score(&self, time: f64) -> Score {
+        let attempts = self.segments.iter().filter(|s| s.start <= time).count();
+        let correct = self.hits[..attempts].iter().filter(|&&h| h).count();
+        Score {
+            correct: correct as u32,
+            attempts: attempts as u32,
+        }
+    }
+
+    pub fn is_finished(&self, time: f64) -> bool {
+        self.segments.last().is_none_or(|s| time >= s.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f64, name: &str) -> ChordSegment {
+        let chord = Chord::from_name(name).unwrap();
+        ChordSegment {
+            start,
+            end: start + 2.0,
+            notes: chord.notes(),
+            chord,
+        }
+    }
+
+    #[test]
+    fn test_scoring() {
+        let mut along = PlayAlong::new("song", vec![segment(0.0, "C"), segment(2.0, "G")]);
+
+        assert!(along.check(&Chord::from_name("C").unwrap(), 0.5));
+        // Wrong chord later in the segment doesn't undo the hit
+        assert!(along.check(&Chord::from_name("Dm").unwrap(), 1.0));
+        assert!(!along.check(&Chord::from_name("C").unwrap(), 2.5));
+
+        let score = along.score(2.5);
+        assert_eq!((score.correct, score.attempts), (1, 2));
+        assert!(!along.is_finished(3.9));
+        assert!(along.is_finished(4.0));
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use super::schedule::Schedule;
+use super::{quality_label, same_chord};
+use crate::theory::{Chord, Note, Quality};
+
+const LOWEST_ROOT: u8 = 48;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    pub correct: u32,
+    pub attempts: u32,
+}
+
+impl Score {
+    pub fn accuracy(&self) -> f32 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.correct as f32 / self.attempts as f32
+        }
+    }
+
+    fn record(&mut self, correct: bool) {
+        self.attempts += 1;
+        if correct {
+            self.correct += 1;
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DictationStats {
+    #[serde(default)]
+    pub by_quality: BTreeMap<String, Score>,
+    #[serde(default)]
+    pub by_root: BTreeMap<String, Score>,
+    /// When each chord is next due, so missed ones come back sooner
+    #[serde(default)]
+    pub schedule: Schedule,
+}
+
+impl DictationStats {
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn load_or_default(path: &Path) -> Self {
+        Self::load(path).unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, chord: &Chord, correct: bool) {
+        let quality = quality_label(chord.quality);
+        self.by_quality
+            .entry(quality.to_string())
+            .or_default()
+            .record(correct);
+        self.by_root
+            .entry(chord.root.name().to_string())
+            .or_default()
+            .record(correct);
+    }
+
+    pub fn total(&self) -> Score {
+        self.by_root
+            .values()
+            .fold(Score::default(), |acc, s| Score {
+                correct: acc.correct + s.correct,
+                attempts: acc.attempts + s.attempts,
+            })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DictationResult {
+    Correct,
+    Wrong,
+}
+
+pub struct Dictation {
+    rng: StdRng,
+    qualities: Vec<Quality>,
+    current: Option<Chord>,
+    answered: bool,
+    session: Score,
+    stats: DictationStats,
+}
+
+impl Dictation {
+    pub fn new(stats: DictationStats) -> Self {
+        Self::with_rng(StdRng::from_os_rng(), stats)
+    }
+
+    pub fn with_seed(seed: u64, stats: DictationStats) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed), stats)
+    }
+
+    fn with_rng(rng: StdRng, stats: DictationStats) -> Self {
+        Self {
+            rng,
+            qualities: Quality::all_triads()
+                .iter()
+                .chain(Quality::all_sevenths())
+                .copied()
+                .collect(),
+            current: None,
+            answered: false,
+            session: Score::default(),
+            stats,
+        }
+    }
+
+    pub fn qualities(mut self, qualities: &[Quality]) -> Self {
+        self.qualities = qualities.to_vec();
+        self
+    }
+
+    /// Every chord the drill can ask, by name.
+    fn items(&self) -> Vec<String> {
+        self.qualities
+            .iter()
+            .flat_map(|&quality| {
+                (0..12).map(move |pc| Chord::new(Note::new(LOWEST_ROOT + pc), quality).name())
+            })
+            .collect()
+    }
+
+    /// The chord the schedule says is most due.
+    pub fn next_chord(&mut self) -> Chord {
+        let chord = self
+            .stats
+            .schedule
+            .pick(&self.items(), &mut self.rng)
+            .and_then(|name| Chord::from_name(name))
+            .map(|c| Chord::new(Note::new(LOWEST_ROOT + c.root.pitch_class()), c.quality))
+            .unwrap_or_else(|| Chord::new(Note::new(LOWEST_ROOT), Quality::Major));
+
+        self.current = Some(chord.clone());
+        self.answered = false;
+        chord
+    }
+
+    pub fn current(&self) -> Option<&Chord> {
+        self.current.as_ref()
+    }
+
+    pub fn is_answered(&self) -> bool {
+        self.answered
+    }
+
+    pub fn answer(&mut self, played: &Chord) -> Option<DictationResult> {
+        let target = self.current.as_ref().filter(|_| !self.answered)?;
+        let correct = same_chord(target, played);
+
+        self.stats.record(target, correct);
+        self.stats.schedule.review(&target.name(), correct);
+        self.session.record(correct);
+        self.answered = true;
+
+        Some(if correct {
+            DictationResult::Correct
+        } else {
+            DictationResult::Wrong
+        })
+    }
+
+    pub fn session(&self) -> Score {
+        self.session
+    }
+
+    pub fn stats(&self) -> &DictationStats {
+        &self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_once_per_chord() {
+        let mut dictation = Dictation::with_seed(7, DictationStats::default());
+        assert!(dictation.answer(&Chord::from_name("C").unwrap()).is_none());
+
+        let target = dictation.next_chord();
+        let played = Chord::new(Note::new(target.root.midi + 12), target.quality);
+        assert_eq!(dictation.answer(&played), Some(DictationResult::Correct));
+        assert!(dictation.answer(&played).is_none());
+
+        let target = dictation.next_chord();
+        let wrong = Chord::new(Note::new(target.root.midi + 1), target.quality);
+        assert_eq!(dictation.answer(&wrong), Some(DictationResult::Wrong));
+
+        assert_eq!(
+            dictation.session(),
+            Score {
+                correct: 1,
+                attempts: 2
+            }
+        );
+        assert_eq!(dictation.stats().total().attempts, 2);
+    }
+
+    #[test]
+    fn test_stats_by_quality_and_root() {
+        let mut stats = DictationStats::default();
+        stats.record(&Chord::from_name("C").unwrap(), true);
+        stats.record(&Chord::from_name("Am7").unwrap(), false);
+        stats.record(&Chord::from_name("A").unwrap(), true);
+
+        assert_eq!(stats.by_quality["maj"].attempts, 2);
+        assert_eq!(stats.by_quality["m7"].accuracy(), 0.0);
+        assert_eq!(stats.by_root["A"].attempts, 2);
+        assert_eq!(stats.total().correct, 2);
+    }
+
+    #[test]
+    fn test_missed_chords_return() {
+        let mut dictation =
+            Dictation::with_seed(5, DictationStats::default()).qualities(&[Quality::Major]);
+        let missed = dictation.next_chord();
+        let wrong = Chord::new(missed.root, Quality::Minor);
+        dictation.answer(&wrong);
+
+        let asked: Vec<Chord> = (0..3)
+            .map(|_| {
+                let target = dictation.next_chord();
+                dictation.answer(&target);
+                target
+            })
+            .collect();
+        assert!(asked.contains(&missed));
+        assert!(dictation.stats().schedule.card(&missed.name()).is_some());
+    }
+
+    #[test]
+    fn test_quality_pool() {
+        let mut dictation =
+            Dictation::with_seed(1, DictationStats::default()).qualities(&[Quality::Minor7]);
+        for _ in 0..10 {
+            assert_eq!(dictation.next_chord().quality, Quality::Minor7);
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::quality_label;
+use crate::theory::{Chord, Quality};
+
+/// Gaps between chords longer than this are a break, not practice
+const IDLE_SECONDS: f64 = 30.0;
+
+/// What was played on one day.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DayStats {
+    #[serde(default)]
+    pub seconds: f64,
+    /// Root pitch classes played, by quality
+    #[serde(default)]
+    pub roots: BTreeMap<String, BTreeSet<u8>>,
+    /// Times each root pitch class was played, by quality
+    #[serde(default)]
+    pub plays: BTreeMap<String, [u32; 12]>,
+}
+
+impl DayStats {
+    pub fn minutes(&self) -> u32 {
+        (self.seconds / 60.0) as u32
+    }
+
+    /// Different chords played, ignoring inversions and octaves.
+    pub fn unique_chords(&self) -> usize {
+        self.roots.values().map(|r| r.len()).sum()
+    }
+
+    /// Qualities played on all twelve roots.
+    pub fn mastered(&self) -> Vec<&str> {
+        self.roots
+            .iter()
+            .filter(|(_, roots)| roots.len() == 12)
+            .map(|(quality, _)| quality.as_str())
+            .collect()
+    }
+}
+
+/// A daily target, written in the config as `"minutes 20"`, `"chords 30"`
+/// or `"all m7"`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Goal {
+    Minutes(u32),
+    Chords(usize),
+    /// Every root of one quality
+    AllRoots(Quality),
+}
+
+impl Goal {
+    pub fn defaults() -> Vec<Goal> {
+        vec![
+            Goal::Minutes(15),
+            Goal::Chords(24),
+            Goal::AllRoots(Quality::Minor7),
+        ]
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let (kind, value) = text
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| anyhow!("Invalid goal '{}'", text))?;
+        let value = value.trim();
+        match kind {
+            "minutes" => Ok(Goal::Minutes(value.parse()?)),
+            "chords" => Ok(Goal::Chords(value.parse()?)),
+            "all" => Quality::from_symbol(value)
+                .filter(|&q| q != Quality::Unknown)
+                .map(Goal::AllRoots)
+                .ok_or_else(|| anyhow!("Unknown chord quality '{}'", value)),
+            _ => Err(anyhow!("Unknown goal '{}'", kind)),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Goal::Minutes(minutes) => format!("Practice {} minutes", minutes),
+            Goal::Chords(count) => format!("Play {} different chords", count),
+            Goal::AllRoots(quality) => {
+                format!("Play all 12 {} chords", quality_label(*quality))
+            }
+        }
+    }
+
+    /// How far along the day is, and the target.
+    pub fn progress(&self, day: &DayStats) -> (u32, u32) {
+        let (done, target) = match self {
+            Goal::Minutes(minutes) => (day.minutes(), *minutes),
+            Goal::Chords(count) => (day.unique_chords() as u32, *count as u32),
+            Goal::AllRoots(quality) => {
+                let roots = day.roots.get(quality_label(*quality));
+                (roots.map_or(0, |r| r.len() as u32), 12)
+            }
+        };
+        (done.min(target), target)
+    }
+
+    pub fn is_met(&self, day: &DayStats) -> bool {
+        let (done, target) = self.progress(day);
+        done >= target
+    }
+}
+
+impl TryFrom<String> for Goal {
+    type Error = anyhow::Error;
+
+    fn try_from(text: String) -> Result<Self> {
+        Self::parse(&text)
+    }
+}
+
+/// Practice time and chords by day, kept between sessions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PracticeLog {
+    /// Keyed by days since the Unix epoch (UTC)
+    #[serde(default)]
+    pub days: BTreeMap<u64, DayStats>,
+    #[serde(skip)]
+    last_played: Option<f64>,
+}
+
+impl PracticeLog {
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn load_or_default(path: &Path) -> Self {
+        Self::load(path).unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Records a chord played `at` seconds into the session. Time since the
+    /// previous chord counts as practice unless it was a long break.
+    pub fn record(&mut self, day: u64, chord: &Chord, at: f64) {
+        let stats = self.days.entry(day).or_default();
+        if let Some(last) = self.last_played {
+            let gap = at - last;
+            if (0.0..=IDLE_SECONDS).contains(&gap) {
+                stats.seconds += gap;
+            }
+        }
+        self.last_played = Some(at);
+
+        if chord.quality != Quality::Unknown {
+            let quality = quality_label(chord.quality);
+            let root = chord.root.pitch_class();
+            stats
+                .roots
+                .entry(quality.to_string())
+                .or_default()
+                .insert(root);
+            stats.plays.entry(quality.to_string()).or_default()[root as usize] += 1;
+        }
+    }
+
+    /// Times each root was played, by quality, over every day logged. Days
+    /// from before plays were counted count each chord once.
+    pub fn chord_counts(&self) -> BTreeMap<String, [u32; 12]> {
+        let mut counts: BTreeMap<String, [u32; 12]> = BTreeMap::new();
+        for stats in self.days.values() {
+            if stats.plays.is_empty() {
+                for (quality, roots) in &stats.roots {
+                    let row = counts.entry(quality.clone()).or_default();
+                    for &root in roots {
+                        row[root as usize % 12] += 1;
+                    }
+                }
+            }
+            for (quality, plays) in &stats.plays {
+                let row = counts.entry(quality.clone()).or_default();
+                for (count, played) in row.iter_mut().zip(plays) {
+                    *count += played;
+                }
+            }
+        }
+        counts
+    }
+
+    pub fn day(&self, day: u64) -> DayStats {
+        self.days.get(&day).cloned().unwrap_or_default()
+    }
+
+    /// Days in a row played up to `day`. A day not yet played doesn't break
+    /// the streak until it is over.
+    pub fn streak(&self, day: u64) -> u32 {
+        let end = if self.days.contains_key(&day) {
+            day
+        } else {
+            day.saturating_sub(1)
+        };
+        (0..=end)
+            .rev()
+            .take_while(|d| self.days.contains_key(d))
+            .count() as u32
+    }
+
+    /// Minutes practised on each of the `count` days up to `day`, oldest
+    /// first.
+    pub fn recent_minutes(&self, day: u64, count: u64) -> Vec<u32> {
+        ((day + 1).saturating_sub(count)..=day)
+            .map(|d| self.days.get(&d).map_or(0, |s| s.minutes()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(name: &str) -> Chord {
+        Chord::from_name(name).unwrap()
+    }
+
+    #[test]
+    fn test_parse_goals() {
+        assert_eq!(Goal::parse("minutes 20").unwrap(), Goal::Minutes(20));
+        assert_eq!(Goal::parse("chords 30").unwrap(), Goal::Chords(30));
+        assert_eq!(
+            Goal::parse("all m7").unwrap(),
+            Goal::AllRoots(Quality::Minor7)
+        );
+        assert_eq!(
+            Goal::parse("all m7").unwrap().describe(),
+            "Play all 12 m7 chords"
+        );
+        assert!(Goal::parse("all xyz").is_err());
+        assert!(Goal::parse("hours 2").is_err());
+        assert!(Goal::parse("minutes").is_err());
+    }
+
+    #[test]
+    fn test_record_day() {
+        let mut log = PracticeLog::default();
+        log.record(3, &chord("C"), 0.0);
+        log.record(3, &chord("Am7"), 20.0);
+        log.record(3, &chord("C/E"), 40.0);
+        // A long break doesn't count
+        log.record(3, &chord("Dm7"), 400.0);
+
+        let day = log.day(3);
+        assert_eq!(day.seconds, 40.0);
+        assert_eq!(day.unique_chords(), 3);
+        assert_eq!(Goal::Chords(2).progress(&day), (2, 2));
+        assert_eq!(Goal::AllRoots(Quality::Minor7).progress(&day), (2, 12));
+        assert!(!Goal::Minutes(1).is_met(&day));
+    }
+
+    #[test]
+    fn test_mastered() {
+        let mut log = PracticeLog::default();
+        for pc in 0..12 {
+            let root = crate::theory::Note::new(60 + pc);
+            log.record(0, &Chord::new(root, Quality::Minor7), pc as f64);
+        }
+        let day = log.day(0);
+        assert_eq!(day.mastered(), vec!["m7"]);
+        assert!(Goal::AllRoots(Quality::Minor7).is_met(&day));
+    }
+
+    #[test]
+    fn test_streak() {
+        let mut log = PracticeLog::default();
+        for day in [1, 3, 4, 5] {
+            log.record(day, &chord("C"), 0.0);
+        }
+        assert_eq!(log.streak(5), 3);
+        // Not played yet today: yesterday's streak still stands
+        assert_eq!(log.streak(6), 3);
+        assert_eq!(log.streak(7), 0);
+        assert_eq!(log.recent_minutes(5, 3), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_chord_counts() {
+        let mut log = PracticeLog::default();
+        log.record(1, &chord("C"), 0.0);
+        log.record(1, &chord("C/E"), 1.0);
+        log.record(2, &chord("Am7"), 0.0);
+        // Logged before plays were counted
+        log.days
+            .entry(0)
+            .or_default()
+            .roots
+            .insert("maj".to_string(), [0, 7].into_iter().collect());
+
+        let counts = log.chord_counts();
+        assert_eq!(counts["maj"][0], 3);
+        assert_eq!(counts["maj"][7], 1);
+        assert_eq!(counts["m7"][9], 1);
+        assert_eq!(counts.len(), 2);
+    }
+}
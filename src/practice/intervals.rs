@@ -0,0 +1,190 @@
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+
+use super::dictation::Score;
+
+const NAMES: [&str; 13] = [
+    "P1", "m2", "M2", "m3", "M3", "P4", "TT", "P5", "m6", "M6", "m7", "M7", "P8",
+];
+
+const LEVELS: [&[u8]; 4] = [
+    &[5, 7, 12],
+    &[3, 4, 5, 7, 12],
+    &[2, 3, 4, 5, 7, 8, 9, 12],
+    &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+];
+
+const PROMOTE_STREAK: u32 = 5;
+const DEMOTE_MISSES: u32 = 3;
+
+pub fn interval_name(semitones: u8) -> &'static str {
+    NAMES.get(semitones as usize).copied().unwrap_or("?")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntervalQuestion {
+    pub low: u8,
+    pub semitones: u8,
+}
+
+impl IntervalQuestion {
+    pub fn notes(&self) -> [u8; 2] {
+        [self.low, self.low + self.semitones]
+    }
+}
+
+pub struct IntervalDrill {
+    rng: StdRng,
+    level: usize,
+    current: Option<IntervalQuestion>,
+    answered: bool,
+    streak: u32,
+    misses: u32,
+    score: Score,
+}
+
+impl IntervalDrill {
+    pub fn new() -> Self {
+        Self::with_rng(StdRng::from_os_rng())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> Self {
+        Self {
+            rng,
+            level: 0,
+            current: None,
+            answered: false,
+            streak: 0,
+            misses: 0,
+            score: Score::default(),
+        }
+    }
+
+    pub fn level(&self) -> usize {
+        self.level + 1
+    }
+
+    pub fn choices(&self) -> &'static [u8] {
+        LEVELS[self.level]
+    }
+
+    pub fn current(&self) -> Option<IntervalQuestion> {
+        self.current
+    }
+
+    pub fn is_answered(&self) -> bool {
+        self.answered
+    }
+
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    pub fn next_question(&mut self) -> IntervalQuestion {
+        let semitones = *self.choices().choose(&mut self.rng).unwrap_or(&7);
+        let low = self.rng.random_range(48..=66);
+        let question = IntervalQuestion { low, semitones };
+
+        self.current = Some(question);
+        self.answered = false;
+        question
+    }
+
+    pub fn answer(&mut self, semitones: u8) -> Option<bool> {
+        let question = self.current.filter(|_| !self.answered)?;
+        let correct = question.semitones == semitones;
+
+        self.answered = true;
+        self.score.attempts += 1;
+        if correct {
+            self.score.correct += 1;
+            self.streak += 1;
+            self.misses = 0;
+            if self.streak >= PROMOTE_STREAK && self.level + 1 < LEVELS.len() {
+                self.level += 1;
+                self.streak = 0;
+            }
+        } else {
+            self.misses += 1;
+            self.streak = 0;
+            if self.misses >= DEMOTE_MISSES && self.level > 0 {
+                self.level -= 1;
+                self.misses = 0;
+            }
+        }
+
+        Some(correct)
+    }
+
+    pub fn answer_played(&mut self, a: u8, b: u8) -> Option<bool> {
+        let span = a.abs_diff(b);
+        let semitones = if span > 12 && !span.is_multiple_of(12) {
+            span % 12
+        } else {
+            span.min(12)
+        };
+        self.answer(semitones)
+    }
+}
+
+impl Default for IntervalDrill {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_question_in_level() {
+        let mut drill = IntervalDrill::with_seed(3);
+        for _ in 0..20 {
+            let question = drill.next_question();
+            assert!(drill.choices().contains(&question.semitones));
+            assert_eq!(
+                question.notes()[1] - question.notes()[0],
+                question.semitones
+            );
+        }
+    }
+
+    #[test]
+    fn test_adaptive_difficulty() {
+        let mut drill = IntervalDrill::with_seed(5);
+        for _ in 0..PROMOTE_STREAK {
+            let question = drill.next_question();
+            assert_eq!(drill.answer(question.semitones), Some(true));
+        }
+        assert_eq!(drill.level(), 2);
+
+        for _ in 0..DEMOTE_MISSES {
+            let question = drill.next_question();
+            assert_eq!(drill.answer(question.semitones + 13), Some(false));
+        }
+        assert_eq!(drill.level(), 1);
+        assert_eq!(drill.score().attempts, 8);
+    }
+
+    #[test]
+    fn test_answer_played() {
+        let mut drill = IntervalDrill::with_seed(9);
+        let question = drill.next_question();
+        let [low, high] = question.notes();
+        assert_eq!(drill.answer_played(high + 12, low), Some(true));
+        assert_eq!(drill.answer_played(high, low), None);
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(interval_name(7), "P5");
+        assert_eq!(interval_name(6), "TT");
+        assert_eq!(interval_name(20), "?");
+    }
+}
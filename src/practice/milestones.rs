@@ -0,0 +1,178 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::quality_label;
+use crate::theory::{Chord, Quality};
+
+/// Streaks shorter than this aren't worth announcing
+const MIN_STREAK: u32 = 2;
+
+/// Something worth celebrating in practice.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Milestone {
+    /// A chord quality played for the first time ever
+    NewQuality(String),
+    /// A trainer pattern played through to the end
+    PatternComplete { name: String, misses: usize },
+    /// Days in a row with something played, counting today
+    Streak(u32),
+    /// One of the day's practice goals met
+    Goal(String),
+}
+
+impl Milestone {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Milestone::NewQuality(_) => "new_quality",
+            Milestone::PatternComplete { .. } => "pattern",
+            Milestone::Streak(_) => "streak",
+            Milestone::Goal(_) => "goal",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Milestone::NewQuality(quality) => {
+                format!("First {} chord played", quality)
+            }
+            Milestone::PatternComplete { name, misses: 0 } => {
+                format!("{} complete without a miss", name)
+            }
+            Milestone::PatternComplete { name, misses } => {
+                format!("{} complete ({} missed)", name, misses)
+            }
+            Milestone::Streak(days) => format!("{}-day practice streak", days),
+            Milestone::Goal(goal) => format!("Goal met: {}", goal),
+        }
+    }
+}
+
+/// What has been reached so far, kept between sessions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Milestones {
+    #[serde(default)]
+    pub qualities: BTreeSet<String>,
+    /// Days since the Unix epoch (UTC) with at least one chord played
+    #[serde(default)]
+    pub days: BTreeSet<u64>,
+}
+
+impl Milestones {
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn load_or_default(path: &Path) -> Self {
+        Self::load(path).unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Records a chord played on `day`, returning what it achieved.
+    pub fn chord_played(&mut self, chord: &Chord, day: u64) -> Vec<Milestone> {
+        let mut reached = Vec::new();
+        if chord.quality != Quality::Unknown {
+            let quality = quality_label(chord.quality);
+            if self.qualities.insert(quality.to_string()) {
+                reached.push(Milestone::NewQuality(quality.to_string()));
+            }
+        }
+        if self.days.insert(day) {
+            let streak = self.streak(day);
+            if streak >= MIN_STREAK {
+                reached.push(Milestone::Streak(streak));
+            }
+        }
+        reached
+    }
+
+    /// Consecutive days played, ending on `day`.
+    pub fn streak(&self, day: u64) -> u32 {
+        (0..=day)
+            .rev()
+            .take_while(|d| self.days.contains(d))
+            .count() as u32
+    }
+}
+
+/// Today as days since the Unix epoch, in UTC.
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(name: &str) -> Chord {
+        Chord::from_name(name).unwrap()
+    }
+
+    #[test]
+    fn test_new_qualities() {
+        let mut milestones = Milestones::default();
+        assert_eq!(
+            milestones.chord_played(&chord("C"), 10),
+            vec![Milestone::NewQuality("maj".to_string())]
+        );
+        assert!(milestones.chord_played(&chord("G"), 10).is_empty());
+        assert_eq!(
+            milestones.chord_played(&chord("Bdim7"), 10),
+            vec![Milestone::NewQuality("dim7".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_streak() {
+        let mut milestones = Milestones::default();
+        milestones.chord_played(&chord("C"), 10);
+        assert_eq!(
+            milestones.chord_played(&chord("C"), 11),
+            vec![Milestone::Streak(2)]
+        );
+        assert!(milestones.chord_played(&chord("C"), 11).is_empty());
+        assert_eq!(milestones.streak(11), 2);
+
+        // A missed day starts again
+        assert!(milestones.chord_played(&chord("C"), 13).is_empty());
+        assert_eq!(milestones.streak(13), 1);
+    }
+
+    #[test]
+    fn test_messages() {
+        let done = Milestone::PatternComplete {
+            name: "ii–V–I".to_string(),
+            misses: 0,
+        };
+        assert_eq!(done.message(), "ii–V–I complete without a miss");
+        assert_eq!(done.kind(), "pattern");
+        assert_eq!(Milestone::Streak(3).message(), "3-day practice streak");
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!(
+            "chordvery-milestones-test-{}.json",
+            std::process::id()
+        ));
+        let mut milestones = Milestones::default();
+        milestones.chord_played(&chord("Am7"), 5);
+        milestones.save(&path).unwrap();
+        assert_eq!(Milestones::load_or_default(&path), milestones);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,29 @@
+pub mod along;
+pub mod dictation;
+pub mod goals;
+pub mod intervals;
+pub mod milestones;
+pub mod schedule;
+pub mod trainer;
+
+pub use along::PlayAlong;
+pub use dictation::{Dictation, DictationResult, DictationStats, Score};
+pub use goals::{DayStats, Goal, PracticeLog};
+pub use intervals::{interval_name, IntervalDrill, IntervalQuestion};
+pub use milestones::{Milestone, Milestones};
+pub use schedule::{Card, Schedule};
+pub use trainer::{Trainer, TrainerResult};
+
+use crate::theory::{Chord, Quality};
+
+/// A quality's symbol, with "maj" standing in for the plain major triad.
+pub(crate) fn quality_label(quality: Quality) -> &'static str {
+    match quality.symbol() {
+        "" => "maj",
+        symbol => symbol,
+    }
+}
+
+pub(crate) fn same_chord(expected: &Chord, played: &Chord) -> bool {
+    expected.root.pitch_class() == played.root.pitch_class() && expected.quality == played.quality
+}
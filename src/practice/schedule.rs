@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+const START_EASE: f32 = 2.5;
+const MIN_EASE: f32 = 1.3;
+/// SM-2 grades (0-5) for a right and a wrong answer
+const GRADE_CORRECT: u8 = 5;
+const GRADE_WRONG: u8 = 2;
+/// A missed item comes back after this many other questions
+const RETRY_AFTER: u64 = 2;
+
+/// How well one drill item is known. Intervals count questions asked
+/// rather than days, so items come round again within a session.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Card {
+    pub ease: f32,
+    pub interval: u64,
+    pub repetitions: u32,
+    /// Question number the item is next due at
+    pub due: u64,
+}
+
+impl Default for Card {
+    fn default() -> Self {
+        Self {
+            ease: START_EASE,
+            interval: 0,
+            repetitions: 0,
+            due: 0,
+        }
+    }
+}
+
+impl Card {
+    /// SM-2: right answers push the item further out, by more the easier
+    /// it has been; a wrong one brings it straight back and makes it harder.
+    fn review(&mut self, grade: u8, clock: u64) {
+        if grade >= 3 {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval as f32 * self.ease).round() as u64,
+            };
+            self.repetitions += 1;
+        } else {
+            self.interval = RETRY_AFTER;
+            self.repetitions = 0;
+        }
+        let miss = (5 - grade.min(5)) as f32;
+        self.ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE);
+        self.due = clock + self.interval;
+    }
+}
+
+/// A spaced-repetition schedule over named drill items.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    #[serde(default)]
+    pub cards: BTreeMap<String, Card>,
+    /// Questions answered so far
+    #[serde(default)]
+    pub clock: u64,
+}
+
+impl Schedule {
+    pub fn card(&self, item: &str) -> Option<&Card> {
+        self.cards.get(item)
+    }
+
+    pub fn review(&mut self, item: &str, correct: bool) {
+        self.clock += 1;
+        let grade = if correct { GRADE_CORRECT } else { GRADE_WRONG };
+        let clock = self.clock;
+        self.cards
+            .entry(item.to_string())
+            .or_default()
+            .review(grade, clock);
+    }
+
+    /// Picks the next item: the most overdue one seen before, else one
+    /// not seen yet, else whichever comes due soonest.
+    pub fn pick<'a>(&self, items: &'a [String], rng: &mut impl Rng) -> Option<&'a String> {
+        let overdue = items
+            .iter()
+            .filter_map(|item| Some((item, self.cards.get(item)?)))
+            .filter(|(_, card)| card.due <= self.clock)
+            .min_by_key(|(_, card)| card.due);
+        if let Some((item, _)) = overdue {
+            return Some(item);
+        }
+
+        let unseen: Vec<&String> = items
+            .iter()
+            .filter(|item| !self.cards.contains_key(*item))
+            .collect();
+        if let Some(item) = unseen.choose(rng) {
+            return Some(item);
+        }
+
+        items
+            .iter()
+            .min_by_key(|item| self.cards.get(*item).map_or(0, |c| c.due))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_sm2_intervals() {
+        let mut card = Card::default();
+        card.review(GRADE_CORRECT, 0);
+        assert_eq!(card.interval, 1);
+        card.review(GRADE_CORRECT, 1);
+        assert_eq!(card.interval, 6);
+        card.review(GRADE_CORRECT, 7);
+        assert_eq!(card.interval, 16);
+        assert!(card.ease > START_EASE);
+
+        card.review(GRADE_WRONG, 23);
+        assert_eq!(card.interval, RETRY_AFTER);
+        assert_eq!(card.repetitions, 0);
+        assert_eq!(card.due, 25);
+        for _ in 0..20 {
+            card.review(GRADE_WRONG, 0);
+        }
+        assert_eq!(card.ease, MIN_EASE);
+    }
+
+    #[test]
+    fn test_missed_items_come_back() {
+        let items: Vec<String> = ["C", "Cm", "Cdim"].map(String::from).to_vec();
+        let mut schedule = Schedule::default();
+        let mut rng = StdRng::seed_from_u64(3);
+
+        schedule.review("C", true);
+        schedule.review("Cm", false);
+        schedule.review("Cdim", true);
+        // Cm is due again once two more questions have gone by
+        assert_eq!(schedule.pick(&items, &mut rng).unwrap(), "C");
+        schedule.review("C", true);
+        assert_eq!(schedule.pick(&items, &mut rng).unwrap(), "Cm");
+
+        let asked = (0..60)
+            .map(|_| {
+                let item = schedule.pick(&items, &mut rng).unwrap().clone();
+                schedule.review(&item, item != "Cm");
+                item
+            })
+            .collect::<Vec<_>>();
+        let count = |name: &str| asked.iter().filter(|i| *i == name).count();
+        assert!(count("Cm") > count("C") + count("Cdim"));
+    }
+
+    #[test]
+    fn test_unseen_before_waiting() {
+        let items: Vec<String> = ["C", "D"].map(String::from).to_vec();
+        let mut schedule = Schedule::default();
+        schedule.review("C", true);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(schedule.pick(&items, &mut rng).unwrap(), "D");
+        assert!(schedule.pick(&[], &mut rng).is_none());
+    }
+}
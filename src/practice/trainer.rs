@@ -0,0 +1,144 @@
+use super::same_chord;
+use crate::theory::Chord;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrainerResult {
+    Hit,
+    Miss,
+    Complete,
+}
+
+#[derive(Clone, Debug)]
+pub struct Trainer {
+    name: String,
+    target: Vec<Chord>,
+    position: usize,
+    hits: usize,
+    misses: usize,
+}
+
+impl Trainer {
+    pub fn new(name: impl Into<String>, target: Vec<Chord>) -> Self {
+        Self {
+            name: name.into(),
+            target,
+            position: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn target(&self) -> &[Chord] {
+        &self.target
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn expected(&self) -> Option<&Chord> {
+        self.target.get(self.position)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.position >= self.target.len()
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn check(&mut self, played: &Chord) -> TrainerResult {
+        let Some(expected) = self.expected() else {
+            return TrainerResult::Complete;
+        };
+
+        if same_chord(expected, played) {
+            self.hits += 1;
+            self.position += 1;
+            if self.is_complete() {
+                TrainerResult::Complete
+            } else {
+                TrainerResult::Hit
+            }
+        } else {
+            self.misses += 1;
+            TrainerResult::Miss
+        }
+    }
+
+    pub fn skip(&mut self) {
+        if !self.is_complete() {
+            self.position += 1;
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.position = 0;
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::{Note, Quality};
+
+    fn ii_v_i() -> Trainer {
+        Trainer::new(
+            "ii–V–I",
+            vec![
+                Chord::new(Note::new(62), Quality::Minor7),
+                Chord::new(Note::new(67), Quality::Dominant7),
+                Chord::new(Note::new(60), Quality::Major7),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_progress_through_target() {
+        let mut trainer = ii_v_i();
+        assert_eq!(trainer.expected().unwrap().name(), "Dm7");
+
+        let played = Chord::new(Note::new(50), Quality::Minor7);
+        assert_eq!(trainer.check(&played), TrainerResult::Hit);
+        assert_eq!(trainer.expected().unwrap().name(), "G7");
+
+        let wrong = Chord::new(Note::new(67), Quality::Major);
+        assert_eq!(trainer.check(&wrong), TrainerResult::Miss);
+        assert_eq!(trainer.position(), 1);
+
+        trainer.check(&Chord::new(Note::new(67), Quality::Dominant7));
+        let result = trainer.check(&Chord::new(Note::new(72), Quality::Major7));
+        assert_eq!(result, TrainerResult::Complete);
+        assert!(trainer.is_complete());
+        assert_eq!(trainer.hits(), 3);
+        assert_eq!(trainer.misses(), 1);
+    }
+
+    #[test]
+    fn test_inversion_matches() {
+        let mut trainer = ii_v_i();
+        let inverted = Chord::new(Note::new(62), Quality::Minor7).with_bass(Note::new(65));
+        assert_eq!(trainer.check(&inverted), TrainerResult::Hit);
+    }
+
+    #[test]
+    fn test_restart() {
+        let mut trainer = ii_v_i();
+        trainer.skip();
+        trainer.skip();
+        trainer.restart();
+        assert_eq!(trainer.position(), 0);
+        assert_eq!(trainer.hits(), 0);
+    }
+}
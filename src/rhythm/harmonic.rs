@@ -0,0 +1,78 @@
+use super::TimeSignature;
+
+/// Seconds of playing the change rate is measured over
+const WINDOW: f64 = 60.0;
+/// Too little playing to call it a rate yet
+const MIN_SPAN: f64 = 10.0;
+/// Seconds per sparkline bar
+pub const BUCKET: f64 = 15.0;
+
+/// Chord changes per minute over the last minute, from the times each chord
+/// was played. The first chord isn't a change, so it isn't counted.
+pub fn changes_per_minute(onsets: &[f64], now: f64) -> Option<f64> {
+    let first = *onsets.first()?;
+    let span = (now - first).min(WINDOW);
+    if span < MIN_SPAN {
+        return None;
+    }
+    let changes = onsets
+        .iter()
+        .filter(|&&t| t > now - span && t <= now)
+        .count();
+    Some(changes as f64 * 60.0 / span)
+}
+
+/// Changes per bar at `bpm`.
+pub fn per_bar(per_minute: f64, bpm: f32, time_signature: TimeSignature) -> f64 {
+    per_minute * time_signature.quarters() as f64 / bpm as f64
+}
+
+/// Changes in each of the last `buckets` spans of [`BUCKET`] seconds,
+/// oldest first.
+pub fn recent(onsets: &[f64], now: f64, buckets: usize) -> Vec<u32> {
+    let mut counts = vec![0; buckets];
+    let Some(&first) = onsets.first() else {
+        return counts;
+    };
+    for &t in onsets.iter().filter(|&&t| t > first) {
+        let ago = ((now - t) / BUCKET).floor();
+        if ago >= 0.0 && (ago as usize) < buckets {
+            counts[buckets - 1 - ago as usize] += 1;
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_per_minute() {
+        assert_eq!(changes_per_minute(&[], 30.0), None);
+        assert_eq!(changes_per_minute(&[0.0, 2.0], 5.0), None);
+
+        // A change every two seconds for the last minute
+        let onsets: Vec<f64> = (0..=60).map(|i| i as f64 * 2.0).collect();
+        assert_eq!(changes_per_minute(&onsets, 120.0), Some(30.0));
+
+        // Twenty seconds in: four changes after the opening chord
+        let onsets = [0.0, 5.0, 10.0, 15.0, 20.0];
+        assert_eq!(changes_per_minute(&onsets, 20.0), Some(12.0));
+    }
+
+    #[test]
+    fn test_per_bar() {
+        let four = TimeSignature::FourFour;
+        assert_eq!(per_bar(30.0, 120.0, four), 1.0);
+        assert_eq!(per_bar(15.0, 60.0, four), 1.0);
+        assert_eq!(per_bar(40.0, 120.0, TimeSignature::ThreeFour), 1.0);
+    }
+
+    #[test]
+    fn test_recent() {
+        let onsets = [0.0, 1.0, 20.0, 25.0, 44.0];
+        assert_eq!(recent(&onsets, 45.0, 4), vec![0, 1, 2, 1]);
+        assert_eq!(recent(&[], 45.0, 3), vec![0, 0, 0]);
+    }
+}
@@ -0,0 +1,264 @@
+use std::time::{Duration, Instant};
+
+use crate::midi::{Groove, Playback, PlaybackEvent};
+use crate::rhythm::TimeSignature;
+
+const VELOCITY: u8 = 80;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transport {
+    Recording,
+    Playing,
+    Overdubbing,
+}
+
+impl Transport {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Transport::Recording => "REC",
+            Transport::Playing => "PLAY",
+            Transport::Overdubbing => "DUB",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoopChord {
+    pub beat: f32,
+    pub notes: Vec<u8>,
+}
+
+/// A chord loop of a fixed number of bars. The first pass records; later
+/// passes play the loop back, optionally layering overdubs on top.
+#[derive(Clone, Debug)]
+pub struct Looper {
+    bars: u32,
+    bpm: f32,
+    time_signature: TimeSignature,
+    state: Transport,
+    pass_started: Instant,
+    layers: Vec<Vec<LoopChord>>,
+}
+
+impl Looper {
+    pub fn record(bars: u32, bpm: f32, now: Instant) -> Self {
+        Self {
+            bars: bars.max(1),
+            bpm,
+            time_signature: TimeSignature::default(),
+            state: Transport::Recording,
+            pass_started: now,
+            layers: vec![Vec::new()],
+        }
+    }
+
+    pub fn time_signature(mut self, time_signature: TimeSignature) -> Self {
+        self.time_signature = time_signature;
+        self
+    }
+
+    pub fn state(&self) -> Transport {
+        self.state
+    }
+
+    pub fn bpm(&self) -> f32 {
+        self.bpm
+    }
+
+    pub fn bars(&self) -> u32 {
+        self.bars
+    }
+
+    pub fn meter(&self) -> TimeSignature {
+        self.time_signature
+    }
+
+    pub fn layers(&self) -> &[Vec<LoopChord>] {
+        &self.layers
+    }
+
+    pub fn beats(&self) -> f32 {
+        self.bars as f32 * self.time_signature.quarters()
+    }
+
+    fn beat_length(&self) -> Duration {
+        Duration::from_secs_f32(60.0 / self.bpm)
+    }
+
+    fn loop_length(&self) -> Duration {
+        self.beat_length().mul_f32(self.beats())
+    }
+
+    /// Position within the loop in beats.
+    pub fn position(&self, now: Instant) -> f32 {
+        now.duration_since(self.pass_started).as_secs_f32() / self.beat_length().as_secs_f32()
+    }
+
+    /// Current bar and beat, both counted from 1. Beats are the time
+    /// signature's pulses, so eighth notes in 6/8 and 7/8.
+    pub fn bar_beat(&self, now: Instant) -> (u32, u32) {
+        let last = self.beats() - self.time_signature.pulse();
+        self.time_signature.bar_pulse(self.position(now).min(last))
+    }
+
+    pub fn toggle_overdub(&mut self) {
+        self.state = match self.state {
+            Transport::Playing => {
+                self.layers.push(Vec::new());
+                Transport::Overdubbing
+            }
+            Transport::Overdubbing => Transport::Playing,
+            Transport::Recording => Transport::Recording,
+        };
+    }
+
+    pub fn capture(&mut self, notes: Vec<u8>, now: Instant) {
+        if self.state == Transport::Playing {
+            return;
+        }
+
+        let pulse = self.time_signature.pulse();
+        let beat = (self.position(now) / pulse).round() * pulse;
+        if beat >= self.beats() {
+            return;
+        }
+        if let Some(layer) = self.layers.last_mut() {
+            layer.retain(|c| c.beat != beat);
+            layer.push(LoopChord { beat, notes });
+            layer.sort_by(|a, b| a.beat.total_cmp(&b.beat));
+        }
+    }
+
+    /// Advances the transport; returns true when a new pass of the loop
+    /// starts and its playback should be (re)started.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        if now.duration_since(self.pass_started) < self.loop_length() {
+            return false;
+        }
+
+        self.pass_started += self.loop_length();
+        if self.state == Transport::Recording {
+            self.state = Transport::Playing;
+        }
+        self.layers.retain(|layer| !layer.is_empty());
+        if self.state == Transport::Overdubbing {
+            self.layers.push(Vec::new());
+        }
+        true
+    }
+
+    /// The loop's chords held until the next one, or comped to `groove`.
+    pub fn playback(&self, groove: Option<&Groove>) -> Playback {
+        let mut playback = Playback::new();
+        let beat = self.beat_length();
+
+        for layer in &self.layers {
+            for (i, chord) in layer.iter().enumerate() {
+                let end = layer.get(i + 1).map(|c| c.beat).unwrap_or(self.beats());
+                let start = beat.mul_f32(chord.beat);
+                if let Some(groove) = groove {
+                    playback.schedule_groove(start, &chord.notes, groove, end - chord.beat, beat);
+                    continue;
+                }
+                let release = beat.mul_f32(end - 0.05);
+                for &note in &chord.notes {
+                    playback.schedule(
+                        start,
+                        PlaybackEvent::NoteOn {
+                            note,
+                            velocity: VELOCITY,
+                        },
+                    );
+                    playback.schedule(release, PlaybackEvent::NoteOff { note });
+                }
+            }
+        }
+
+        playback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(start: Instant, beats: f32) -> Instant {
+        // 120 BPM: half a second per beat
+        start + Duration::from_secs_f32(beats * 0.5)
+    }
+
+    #[test]
+    fn test_record_then_play() {
+        let start = Instant::now();
+        let mut looper = Looper::record(4, 120.0, start);
+
+        looper.capture(vec![48, 52, 55], at(start, 0.1));
+        looper.capture(vec![53, 57, 60], at(start, 7.9));
+        assert!(!looper.tick(at(start, 15.0)));
+        assert_eq!(looper.bar_beat(at(start, 5.5)), (2, 2));
+
+        assert!(looper.tick(at(start, 16.0)));
+        assert_eq!(looper.state(), Transport::Playing);
+        assert_eq!(looper.layers()[0][1].beat, 8.0);
+
+        // Playing does not capture
+        looper.capture(vec![43, 47, 50], at(start, 17.0));
+        assert_eq!(looper.layers().len(), 1);
+        assert_eq!(looper.layers()[0].len(), 2);
+    }
+
+    #[test]
+    fn test_compound_meter() {
+        let start = Instant::now();
+        let mut looper = Looper::record(2, 120.0, start).time_signature(TimeSignature::SixEight);
+        assert_eq!(looper.beats(), 6.0);
+        assert_eq!(looper.bar_beat(at(start, 1.6)), (1, 4));
+        assert_eq!(looper.bar_beat(at(start, 3.0)), (2, 1));
+        assert_eq!(looper.bar_beat(at(start, 7.0)), (2, 6));
+
+        // Chords land on the nearest eighth note
+        looper.capture(vec![48, 52, 55], at(start, 1.4));
+        assert_eq!(looper.layers()[0][0].beat, 1.5);
+        assert!(!looper.tick(at(start, 5.9)));
+        assert!(looper.tick(at(start, 6.0)));
+    }
+
+    #[test]
+    fn test_overdub_layers() {
+        let start = Instant::now();
+        let mut looper = Looper::record(4, 120.0, start);
+        looper.capture(vec![48, 52, 55], start);
+        looper.tick(at(start, 16.0));
+
+        looper.toggle_overdub();
+        looper.capture(vec![72, 76], at(start, 20.0));
+        assert_eq!(looper.layers().len(), 2);
+        assert_eq!(looper.layers()[1][0].beat, 4.0);
+
+        looper.toggle_overdub();
+        assert!(looper.tick(at(start, 32.0)));
+        assert_eq!(looper.layers().len(), 2);
+
+        let mut events = Vec::new();
+        let mut playback = looper.playback(None);
+        playback.start(start);
+        playback.tick(at(start, 16.0), &mut events);
+        let ons = events
+            .iter()
+            .filter(|e| matches!(e, PlaybackEvent::NoteOn { .. }))
+            .count();
+        assert_eq!(ons, 5);
+
+        // A waltz groove strikes the first layer's chord on every beat
+        let waltz = &Groove::built_in()[3];
+        let mut events = Vec::new();
+        let mut playback = looper.playback(Some(waltz));
+        playback.start(start);
+        playback.tick(at(start, 15.9), &mut events);
+        let ons = events
+            .iter()
+            .filter(|e| matches!(e, PlaybackEvent::NoteOn { note: 48, .. }))
+            .count();
+        assert_eq!(ons, 16);
+    }
+}
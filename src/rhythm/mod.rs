@@ -0,0 +1,10 @@
+pub mod harmonic;
+pub mod looper;
+pub mod quantize;
+pub mod tempo;
+pub mod time_signature;
+
+pub use looper::{LoopChord, Looper, Transport};
+pub use quantize::{Feel, Quantizer, Resolution};
+pub use tempo::{TapTempo, TempoEstimator};
+pub use time_signature::{Accent, TimeSignature};
@@ -0,0 +1,182 @@
+use super::TimeSignature;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Resolution {
+    Bar,
+    #[default]
+    Beat,
+    Eighth,
+}
+
+impl Resolution {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "bar" => Some(Resolution::Bar),
+            "beat" => Some(Resolution::Beat),
+            "eighth" | "8th" => Some(Resolution::Eighth),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Feel {
+    #[default]
+    Straight,
+    Swing,
+}
+
+impl Feel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feel::Straight => "Straight",
+            Feel::Swing => "Swing",
+        }
+    }
+
+    pub fn toggle(&self) -> Self {
+        match self {
+            Feel::Straight => Feel::Swing,
+            Feel::Swing => Feel::Straight,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quantizer {
+    pub bpm: f32,
+    pub resolution: Resolution,
+    pub feel: Feel,
+    pub time_signature: TimeSignature,
+}
+
+impl Quantizer {
+    pub fn new(bpm: f32) -> Self {
+        Self {
+            bpm,
+            resolution: Resolution::default(),
+            feel: Feel::default(),
+            time_signature: TimeSignature::default(),
+        }
+    }
+
+    pub fn resolution(mut self, resolution: Resolution) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    pub fn feel(mut self, feel: Feel) -> Self {
+        self.feel = feel;
+        self
+    }
+
+    pub fn time_signature(mut self, time_signature: TimeSignature) -> Self {
+        self.time_signature = time_signature;
+        self
+    }
+
+    /// Grid positions within one beat that an onset may snap to.
+    fn offsets(&self) -> &'static [f32] {
+        match (self.resolution, self.feel) {
+            (Resolution::Eighth, Feel::Straight) => &[0.0, 0.5, 1.0],
+            (Resolution::Eighth, Feel::Swing) => &[0.0, 2.0 / 3.0, 1.0],
+            _ => &[0.0, 1.0],
+        }
+    }
+
+    fn step(&self) -> f32 {
+        match self.resolution {
+            Resolution::Bar => self.time_signature.quarters(),
+            Resolution::Beat => 1.0,
+            Resolution::Eighth => 0.5,
+        }
+    }
+
+    /// Snaps a time in seconds to the nearest grid position, in beats.
+    pub fn snap(&self, seconds: f64) -> f32 {
+        let beats = (seconds * self.bpm as f64 / 60.0) as f32;
+
+        if self.resolution == Resolution::Bar {
+            let bar = self.time_signature.quarters();
+            return (beats / bar).round() * bar;
+        }
+
+        let whole = beats.floor();
+        let fraction = beats - whole;
+        let offset = self
+            .offsets()
+            .iter()
+            .copied()
+            .min_by(|a, b| (a - fraction).abs().total_cmp(&(b - fraction).abs()))
+            .unwrap_or(0.0);
+        whole + offset
+    }
+
+    /// Quantized durations in beats for chords starting at `onsets`
+    /// (seconds), the last one lasting until `end`. Chords that would snap
+    /// onto the same grid position are pushed apart by one step.
+    pub fn durations(&self, onsets: &[f64], end: f64) -> Vec<f32> {
+        let first = onsets.first().copied().unwrap_or_default();
+        let mut starts: Vec<f32> = Vec::with_capacity(onsets.len());
+        for &onset in onsets {
+            let mut start = self.snap(onset - first);
+            if let Some(&previous) = starts.last() {
+                start = start.max(previous + self.step());
+            }
+            starts.push(start);
+        }
+
+        let last_end = starts
+            .last()
+            .map(|&s| self.snap(end - first).max(s + self.step()));
+        starts
+            .iter()
+            .zip(starts.iter().skip(1).copied().chain(last_end))
+            .map(|(start, next)| next - start)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap_to_beats_and_bars() {
+        let q = Quantizer::new(120.0);
+        assert_eq!(q.snap(0.0), 0.0);
+        assert_eq!(q.snap(1.1), 2.0);
+        assert_eq!(q.snap(1.9), 4.0);
+
+        let bars = q.resolution(Resolution::Bar);
+        assert_eq!(bars.snap(1.9), 4.0);
+        assert_eq!(bars.snap(0.9), 0.0);
+
+        // Bars of 6/8 are three quarter notes long
+        let compound = bars.time_signature(TimeSignature::SixEight);
+        assert_eq!(compound.snap(1.9), 3.0);
+        assert_eq!(compound.snap(2.6), 6.0);
+    }
+
+    #[test]
+    fn test_swing_eighths() {
+        let straight = Quantizer::new(60.0).resolution(Resolution::Eighth);
+        let swing = straight.feel(Feel::Swing);
+
+        assert_eq!(straight.snap(0.55), 0.5);
+        assert!((swing.snap(0.62) - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(swing.snap(0.3), 0.0);
+    }
+
+    #[test]
+    fn test_durations() {
+        let q = Quantizer::new(120.0);
+        // Chords at roughly 0, 2 and 3 seconds: a bar, two beats, then a bar
+        let onsets = [0.2, 2.15, 3.05];
+        assert_eq!(q.durations(&onsets, 5.0), vec![4.0, 2.0, 4.0]);
+
+        // Two changes inside one beat don't collapse to zero length
+        assert_eq!(q.durations(&[0.0, 0.1], 0.2), vec![1.0, 1.0]);
+        assert!(q.durations(&[], 1.0).is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+const MAX_TAPS: usize = 8;
+const TAP_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_CHANGES: usize = 12;
+pub const MIN_BPM: f32 = 40.0;
+pub const MAX_BPM: f32 = 240.0;
+
+#[derive(Clone, Debug, Default)]
+pub struct TapTempo {
+    taps: VecDeque<Instant>,
+}
+
+impl TapTempo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a tap and returns the tempo once there are at least two.
+    pub fn tap(&mut self, now: Instant) -> Option<f32> {
+        if let Some(&last) = self.taps.back() {
+            if now.duration_since(last) > TAP_TIMEOUT {
+                self.taps.clear();
+            }
+        }
+
+        self.taps.push_back(now);
+        if self.taps.len() > MAX_TAPS {
+            self.taps.pop_front();
+        }
+        self.bpm()
+    }
+
+    pub fn bpm(&self) -> Option<f32> {
+        let (first, last) = (self.taps.front()?, self.taps.back()?);
+        let beats = self.taps.len().checked_sub(1).filter(|&n| n > 0)?;
+        let beat = last.duration_since(*first).as_secs_f32() / beats as f32;
+        (beat > 0.0).then(|| (60.0 / beat).clamp(MIN_BPM, MAX_BPM))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TempoEstimator {
+    last: Option<Instant>,
+    intervals: VecDeque<Duration>,
+}
+
+impl TempoEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, now: Instant) {
+        if let Some(last) = self.last {
+            self.intervals.push_back(now.duration_since(last));
+            if self.intervals.len() > MAX_CHANGES {
+                self.intervals.pop_front();
+            }
+        }
+        self.last = Some(now);
+    }
+
+    pub fn bpm(&self) -> Option<f32> {
+        let intervals: Vec<Duration> = self.intervals.iter().copied().collect();
+        estimate_bpm(&intervals)
+    }
+}
+
+/// Guesses a tempo from the gaps between chord changes, assuming changes
+/// fall on beats. The median gap is folded into a comfortable 70-140 range.
+pub fn estimate_bpm(intervals: &[Duration]) -> Option<f32> {
+    if intervals.len() < 3 {
+        return None;
+    }
+
+    let mut secs: Vec<f32> = intervals.iter().map(|d| d.as_secs_f32()).collect();
+    secs.sort_by(|a, b| a.total_cmp(b));
+    let median = secs[secs.len() / 2];
+    if median <= 0.0 {
+        return None;
+    }
+
+    let mut bpm = 60.0 / median;
+    while bpm < 70.0 {
+        bpm *= 2.0;
+    }
+    while bpm > 140.0 {
+        bpm /= 2.0;
+    }
+    Some(bpm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tap_tempo() {
+        let start = Instant::now();
+        let mut tap = TapTempo::new();
+
+        assert_eq!(tap.tap(start), None);
+        for i in 1..4 {
+            tap.tap(start + Duration::from_millis(500 * i));
+        }
+        assert!((tap.bpm().unwrap() - 120.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_tap_timeout_resets() {
+        let start = Instant::now();
+        let mut tap = TapTempo::new();
+        tap.tap(start);
+        tap.tap(start + Duration::from_millis(1000));
+
+        assert_eq!(tap.tap(start + Duration::from_secs(5)), None);
+    }
+
+    #[test]
+    fn test_estimate_from_chord_changes() {
+        // Chords every two seconds at 120 BPM (one per bar of 4/4)
+        let changes = vec![Duration::from_millis(2000); 4];
+        let bpm = estimate_bpm(&changes).unwrap();
+        assert!((bpm - 120.0).abs() < 0.5);
+
+        let mut estimator = TempoEstimator::new();
+        let start = Instant::now();
+        for i in 0..5 {
+            estimator.record(start + Duration::from_millis(750 * i));
+        }
+        assert!((estimator.bpm().unwrap() - 80.0).abs() < 0.5);
+        assert_eq!(estimate_bpm(&changes[..2]), None);
+    }
+}
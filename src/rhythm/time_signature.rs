@@ -0,0 +1,142 @@
+/// Beats in a bar and how they're grouped. Tempos are always counted in
+/// quarter notes, so a bar of 6/8 lasts three of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimeSignature {
+    #[default]
+    FourFour,
+    ThreeFour,
+    SixEight,
+    FiveFour,
+    SevenEight,
+}
+
+/// How strongly a pulse of the bar is felt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Accent {
+    Weak,
+    /// The start of a group inside the bar: beat 3 of 4/4, the second
+    /// dotted quarter of 6/8
+    Secondary,
+    Downbeat,
+}
+
+impl TimeSignature {
+    pub fn all() -> &'static [TimeSignature] {
+        &[
+            TimeSignature::FourFour,
+            TimeSignature::ThreeFour,
+            TimeSignature::SixEight,
+            TimeSignature::FiveFour,
+            TimeSignature::SevenEight,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeSignature::FourFour => "4/4",
+            TimeSignature::ThreeFour => "3/4",
+            TimeSignature::SixEight => "6/8",
+            TimeSignature::FiveFour => "5/4",
+            TimeSignature::SevenEight => "7/8",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::all().iter().copied().find(|t| t.name() == name)
+    }
+
+    pub fn next(&self) -> Self {
+        let all = Self::all();
+        let at = all.iter().position(|t| t == self).unwrap_or(0);
+        all[(at + 1) % all.len()]
+    }
+
+    pub fn numerator(&self) -> u8 {
+        self.pulses().len() as u8
+    }
+
+    pub fn denominator(&self) -> u8 {
+        match self {
+            TimeSignature::SixEight | TimeSignature::SevenEight => 8,
+            _ => 4,
+        }
+    }
+
+    /// The accent of each pulse of the bar, counted in the denominator's
+    /// note value: 5/4 goes 3+2, 7/8 goes 2+2+3.
+    pub fn pulses(&self) -> &'static [Accent] {
+        use Accent::*;
+        match self {
+            TimeSignature::FourFour => &[Downbeat, Weak, Secondary, Weak],
+            TimeSignature::ThreeFour => &[Downbeat, Weak, Weak],
+            TimeSignature::SixEight => &[Downbeat, Weak, Weak, Secondary, Weak, Weak],
+            TimeSignature::FiveFour => &[Downbeat, Weak, Weak, Secondary, Weak],
+            TimeSignature::SevenEight => &[Downbeat, Weak, Secondary, Weak, Secondary, Weak, Weak],
+        }
+    }
+
+    /// Length of one pulse in quarter notes.
+    pub fn pulse(&self) -> f32 {
+        4.0 / self.denominator() as f32
+    }
+
+    /// Length of a bar in quarter notes.
+    pub fn quarters(&self) -> f32 {
+        self.numerator() as f32 * self.pulse()
+    }
+
+    /// The bar and pulse a position in quarter notes falls on, both
+    /// counted from 1.
+    pub fn bar_pulse(&self, quarters: f32) -> (u32, u32) {
+        let pulse = (quarters.max(0.0) / self.pulse()).floor() as u32;
+        let per_bar = self.numerator() as u32;
+        (pulse / per_bar + 1, pulse % per_bar + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        for signature in TimeSignature::all() {
+            assert_eq!(TimeSignature::from_name(signature.name()), Some(*signature));
+        }
+        assert_eq!(TimeSignature::from_name("2/2"), None);
+        assert_eq!(TimeSignature::SevenEight.next(), TimeSignature::FourFour);
+    }
+
+    #[test]
+    fn test_bar_lengths() {
+        let quarters: Vec<f32> = TimeSignature::all().iter().map(|t| t.quarters()).collect();
+        assert_eq!(quarters, [4.0, 3.0, 3.0, 5.0, 3.5]);
+        assert_eq!(TimeSignature::SixEight.numerator(), 6);
+        assert_eq!(TimeSignature::SixEight.denominator(), 8);
+    }
+
+    #[test]
+    fn test_accents() {
+        let secondary = |t: TimeSignature| -> Vec<usize> {
+            (0..t.pulses().len())
+                .filter(|&i| t.pulses()[i] == Accent::Secondary)
+                .collect()
+        };
+        assert_eq!(secondary(TimeSignature::SixEight), [3]);
+        assert_eq!(secondary(TimeSignature::FiveFour), [3]);
+        assert_eq!(secondary(TimeSignature::SevenEight), [2, 4]);
+        assert!(TimeSignature::all()
+            .iter()
+            .all(|t| t.pulses()[0] == Accent::Downbeat));
+    }
+
+    #[test]
+    fn test_bar_pulse() {
+        assert_eq!(TimeSignature::FourFour.bar_pulse(5.5), (2, 2));
+        // Eighth-note pulses in 6/8
+        assert_eq!(TimeSignature::SixEight.bar_pulse(2.5), (1, 6));
+        assert_eq!(TimeSignature::SixEight.bar_pulse(3.0), (2, 1));
+        assert_eq!(TimeSignature::SevenEight.bar_pulse(3.5), (2, 1));
+    }
+}
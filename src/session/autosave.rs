@@ -0,0 +1,168 @@
+use std::fs::{self, File, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use super::Session;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct Autosave {
+    path: PathBuf,
+    interval: Duration,
+    last_save: Option<Instant>,
+    last_saved: Option<Session>,
+    /// Held while running, so no other instance claims the same file
+    lock: Option<File>,
+}
+
+impl Autosave {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            interval: DEFAULT_INTERVAL,
+            last_save: None,
+            last_saved: None,
+            lock: None,
+        }
+    }
+
+    /// Takes the first file in `dir` no running instance holds. One left
+    /// by an instance that crashed is free again, and offered back.
+    pub fn claim(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let mut slot = 0;
+        loop {
+            let lock = File::options()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(dir.join(format!("{}.lock", slot)))?;
+            match lock.try_lock() {
+                Ok(()) => {
+                    let mut autosave = Self::new(dir.join(format!("{}.json", slot)));
+                    autosave.lock = Some(lock);
+                    return Ok(autosave);
+                }
+                Err(TryLockError::WouldBlock) => slot += 1,
+                Err(TryLockError::Error(e)) => return Err(e.into()),
+            }
+        }
+    }
+
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn recover(&self) -> Option<Session> {
+        Session::load(&self.path).ok().filter(|s| !s.is_empty())
+    }
+
+    pub fn save_if_due(&mut self, session: &Session, now: Instant) -> Result<bool> {
+        let due = self
+            .last_save
+            .is_none_or(|last| now.duration_since(last) >= self.interval);
+
+        if !due || self.last_saved.as_ref() == Some(session) {
+            return Ok(false);
+        }
+
+        session.save(&self.path)?;
+        self.last_save = Some(now);
+        self.last_saved = Some(session.clone());
+        Ok(true)
+    }
+
+    pub fn finish(&mut self) -> Result<()> {
+        self.last_saved = None;
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::{Chord, Note, Quality};
+
+    fn test_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "chordvery-autosave-{}-{}.json",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_save_respects_interval() {
+        let mut autosave = Autosave::new(test_path("interval")).interval(Duration::from_secs(5));
+        let start = Instant::now();
+
+        let mut session = Session::new();
+        session.push(&Chord::new(Note::new(60), Quality::Major));
+        assert!(autosave.save_if_due(&session, start).unwrap());
+
+        session.push(&Chord::new(Note::new(65), Quality::Major));
+        assert!(!autosave
+            .save_if_due(&session, start + Duration::from_secs(1))
+            .unwrap());
+        assert!(autosave
+            .save_if_due(&session, start + Duration::from_secs(6))
+            .unwrap());
+        assert!(!autosave
+            .save_if_due(&session, start + Duration::from_secs(12))
+            .unwrap());
+
+        autosave.finish().unwrap();
+    }
+
+    #[test]
+    fn test_recover_after_unclean_exit() {
+        let path = test_path("recover");
+        let mut session = Session::new();
+        session.push(&Chord::new(Note::new(69), Quality::Minor));
+
+        let mut autosave = Autosave::new(path.clone());
+        autosave.save_if_due(&session, Instant::now()).unwrap();
+        drop(autosave);
+
+        let mut relaunched = Autosave::new(path.clone());
+        assert_eq!(relaunched.recover(), Some(session));
+
+        relaunched.finish().unwrap();
+        assert!(!path.exists());
+        assert!(Autosave::new(path).recover().is_none());
+    }
+
+    #[test]
+    fn test_instances_keep_their_own_files() {
+        let dir =
+            std::env::temp_dir().join(format!("chordvery-autosave-claim-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut session = Session::new();
+        session.push(&Chord::new(Note::new(62), Quality::Minor));
+
+        let mut first = Autosave::claim(&dir).unwrap();
+        let mut second = Autosave::claim(&dir).unwrap();
+        assert_ne!(first.path(), second.path());
+        first.save_if_due(&session, Instant::now()).unwrap();
+        assert!(second.recover().is_none());
+        second.finish().unwrap();
+        assert!(first.path().exists());
+
+        // The first crashes, and the next to start picks up where it left off
+        drop(first);
+        let mut relaunched = Autosave::claim(&dir).unwrap();
+        assert_eq!(relaunched.recover(), Some(session));
+        relaunched.finish().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
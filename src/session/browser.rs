@@ -0,0 +1,461 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use anyhow::Result;
+
+use super::diff::TakeDiff;
+use super::log::{self, Event, TimedEvent};
+use super::{Session, SessionEntry};
+use crate::midi::{Playback, PlaybackEvent};
+
+const REPLAY_CHORD: Duration = Duration::from_millis(1500);
+const MAX_REPLAY_GAP: f64 = 4.0;
+const VELOCITY: u8 = 80;
+
+/// A moment flagged during a session, and the note typed for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bookmark {
+    pub at: Duration,
+    pub note: Option<String>,
+}
+
+/// A saved session event log with what the browser shows about it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionSummary {
+    pub path: PathBuf,
+    /// Unix time the session started, from the file name or its mtime
+    pub started: Option<u64>,
+    pub duration: Duration,
+    pub keys: Vec<String>,
+    pub bookmarks: Vec<Bookmark>,
+    pub session: Session,
+}
+
+impl SessionSummary {
+    pub fn load(path: &Path) -> Result<Self> {
+        let events = log::read(path)?;
+        let started = started_from_name(path).or_else(|| {
+            let modified = fs::metadata(path).ok()?.modified().ok()?;
+            Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+        });
+        Ok(Self::from_events(path, started, &events))
+    }
+
+    pub fn from_events(path: &Path, started: Option<u64>, events: &[TimedEvent]) -> Self {
+        let session = Session::from_events(events);
+
+        let mut keys: Vec<String> = Vec::new();
+        let mut bookmarks = Vec::new();
+        for timed in events {
+            match &timed.event {
+                Event::Key { key: Some(key) } if keys.last() != Some(key) => {
+                    keys.push(key.clone());
+                }
+                Event::Bookmark { note } => bookmarks.push(Bookmark {
+                    at: Duration::from_millis(timed.t),
+                    note: note.clone(),
+                }),
+                _ => {}
+            }
+        }
+        if keys.is_empty() {
+            keys.extend(session.key.clone());
+        }
+
+        Self {
+            path: path.to_path_buf(),
+            started,
+            duration: Duration::from_millis(events.last().map(|e| e.t).unwrap_or_default()),
+            keys,
+            bookmarks,
+            session,
+        }
+    }
+
+    pub fn chord_count(&self) -> usize {
+        self.session.len()
+    }
+
+    /// The chord sounding at a moment of the session.
+    pub fn chord_at(&self, at: Duration) -> Option<&SessionEntry> {
+        let seconds = at.as_secs_f64();
+        self.session
+            .entries
+            .iter()
+            .rev()
+            .find(|e| e.at.is_some_and(|t| t <= seconds))
+    }
+
+    pub fn date(&self) -> String {
+        self.started
+            .map(format_date)
+            .unwrap_or_else(|| "—".to_string())
+    }
+}
+
+/// Saved sessions with at least one chord, newest first.
+pub fn list(dir: &Path) -> Vec<SessionSummary> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut sessions: Vec<SessionSummary> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|p| SessionSummary::load(&p).ok())
+        .filter(|s| s.chord_count() > 0)
+        .collect();
+    sessions.sort_by(|a, b| b.started.cmp(&a.started).then(b.path.cmp(&a.path)));
+    sessions
+}
+
+fn started_from_name(path: &Path) -> Option<u64> {
+    path.file_stem()?
+        .to_str()?
+        .strip_prefix("session-")?
+        .parse()
+        .ok()
+}
+
+/// Plays a session back with its recorded timing, long pauses shortened.
+pub fn replay(session: &Session) -> Playback {
+    let voiced = session.voiced_chords();
+    let starts: Vec<Duration> = match session.onsets() {
+        Some(onsets) => {
+            let mut at = 0.0;
+            let mut starts = Vec::with_capacity(onsets.len());
+            for (i, onset) in onsets.iter().enumerate() {
+                if i > 0 {
+                    at += (onset - onsets[i - 1]).clamp(0.0, MAX_REPLAY_GAP);
+                }
+                starts.push(Duration::from_secs_f64(at));
+            }
+            starts
+        }
+        None => (0..voiced.len() as u32).map(|i| REPLAY_CHORD * i).collect(),
+    };
+
+    let mut playback = Playback::new();
+    for (i, (_, notes)) in voiced.iter().enumerate() {
+        let start = starts[i];
+        let end = starts.get(i + 1).copied().unwrap_or(start + REPLAY_CHORD);
+        for &note in notes {
+            playback.schedule(
+                start,
+                PlaybackEvent::NoteOn {
+                    note,
+                    velocity: VELOCITY,
+                },
+            );
+            playback.schedule(end, PlaybackEvent::NoteOff { note });
+        }
+    }
+    playback
+}
+
+/// Plays a session back from the chord sounding at `at`.
+pub fn replay_from(session: &Session, at: Duration) -> Playback {
+    let seconds = at.as_secs_f64();
+    let first = session
+        .entries
+        .iter()
+        .rposition(|e| e.at.is_some_and(|t| t <= seconds))
+        .unwrap_or(0);
+    replay(&Session {
+        key: session.key.clone(),
+        entries: session.entries[first..].to_vec(),
+        ..Session::default()
+    })
+}
+
+/// `YYYY-MM-DD HH:MM` in UTC.
+pub fn format_date(unix: u64) -> String {
+    let days = (unix / 86_400) as i64;
+    let (hours, minutes) = ((unix % 86_400) / 3600, (unix % 3600) / 60);
+
+    // Civil-from-days, after Howard Hinnant
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year, month, day, hours, minutes
+    )
+}
+
+pub struct SessionBrowser {
+    pub sessions: Vec<SessionSummary>,
+    pub cursor: usize,
+    pub confirm_delete: bool,
+    /// The take marked to compare the next one against
+    pub reference: Option<PathBuf>,
+    /// The selected session's bookmark picked to replay from
+    pub bookmark: Option<usize>,
+}
+
+impl SessionBrowser {
+    pub fn open(dir: &Path) -> Self {
+        Self {
+            sessions: list(dir),
+            cursor: 0,
+            confirm_delete: false,
+            reference: None,
+            bookmark: None,
+        }
+    }
+
+    pub fn selected(&self) -> Option<&SessionSummary> {
+        self.sessions.get(self.cursor)
+    }
+
+    pub fn next(&mut self) {
+        if !self.sessions.is_empty() {
+            self.cursor = (self.cursor + 1) % self.sessions.len();
+        }
+        self.bookmark = None;
+    }
+
+    pub fn prev(&mut self) {
+        if !self.sessions.is_empty() {
+            self.cursor = (self.cursor + self.sessions.len() - 1) % self.sessions.len();
+        }
+        self.bookmark = None;
+    }
+
+    /// Steps through the selected session's bookmarks, then back to none.
+    pub fn next_bookmark(&mut self) {
+        let count = self.selected().map_or(0, |s| s.bookmarks.len());
+        self.bookmark = match self.bookmark {
+            None if count > 0 => Some(0),
+            Some(i) if i + 1 < count => Some(i + 1),
+            _ => None,
+        };
+    }
+
+    pub fn selected_bookmark(&self) -> Option<&Bookmark> {
+        self.selected()?.bookmarks.get(self.bookmark?)
+    }
+
+    /// Marks the selected take to compare against, or lines it up against
+    /// the one marked. Choosing the marked take again unmarks it.
+    pub fn compare_selected(&mut self) -> Option<TakeDiff> {
+        let reference = self.reference.take();
+        let selected = self.selected()?;
+        if reference.as_ref() == Some(&selected.path) {
+            return None;
+        }
+        let Some(reference) = reference.and_then(|p| self.sessions.iter().find(|s| s.path == p))
+        else {
+            self.reference = Some(selected.path.clone());
+            return None;
+        };
+        Some(TakeDiff::new(
+            reference.date(),
+            &reference.session.chords(),
+            selected.date(),
+            &selected.session.chords(),
+        ))
+    }
+
+    pub fn delete_selected(&mut self) -> Result<()> {
+        if let Some(summary) = self.selected() {
+            fs::remove_file(&summary.path)?;
+            self.sessions.remove(self.cursor);
+            self.cursor = self.cursor.min(self.sessions.len().saturating_sub(1));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionWriter;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "chordvery-browser-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_log(path: &Path, events: &[(u64, Event)]) {
+        let mut writer = SessionWriter::open(path).unwrap();
+        for (t, event) in events {
+            writer
+                .write(&TimedEvent {
+                    t: *t,
+                    event: event.clone(),
+                })
+                .unwrap();
+        }
+    }
+
+    fn chord(name: &str) -> Event {
+        Event::Chord {
+            name: name.to_string(),
+            notes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01 00:00");
+        assert_eq!(format_date(951_782_400), "2000-02-29 00:00");
+        assert_eq!(format_date(1_792_150_380), "2026-10-16 11:33");
+    }
+
+    #[test]
+    fn test_summary() {
+        let events = [
+            (0, chord("C")),
+            (
+                500,
+                Event::Key {
+                    key: Some("C".to_string()),
+                },
+            ),
+            (2000, chord("Eb")),
+            (
+                2100,
+                Event::Key {
+                    key: Some("Eb".to_string()),
+                },
+            ),
+            (4000, chord("Bb")),
+        ]
+        .map(|(t, event)| TimedEvent { t, event });
+
+        let summary = SessionSummary::from_events(Path::new("x.jsonl"), Some(0), &events);
+        assert_eq!(summary.chord_count(), 3);
+        assert_eq!(summary.keys, vec!["C", "Eb"]);
+        assert_eq!(summary.duration, Duration::from_secs(4));
+    }
+
+    #[test]
+    fn test_bookmarks() {
+        let dir = temp_dir("bookmarks");
+        let note = Some("that turnaround".to_string());
+        write_log(
+            &dir.join("session-100.jsonl"),
+            &[
+                (0, chord("C")),
+                (2000, chord("Am")),
+                (3000, Event::Bookmark { note: note.clone() }),
+                (4000, chord("F")),
+                (6000, Event::Bookmark { note: None }),
+            ],
+        );
+
+        let mut browser = SessionBrowser::open(&dir);
+        let summary = browser.selected().unwrap();
+        assert_eq!(summary.bookmarks.len(), 2);
+        assert_eq!(summary.bookmarks[0].note, note);
+        let at = summary.bookmarks[0].at;
+        assert_eq!(summary.chord_at(at).unwrap().chord, "Am");
+
+        browser.next_bookmark();
+        assert_eq!(browser.selected_bookmark().unwrap().at, at);
+        browser.next_bookmark();
+        browser.next_bookmark();
+        assert!(browser.selected_bookmark().is_none());
+
+        // Replaying from the bookmark starts on the chord sounding there
+        let summary = browser.selected().unwrap();
+        let mut playback = replay_from(&summary.session, at);
+        let start = std::time::Instant::now();
+        playback.start(start);
+        let mut events = Vec::new();
+        playback.tick(start, &mut events);
+        assert!(events.contains(&PlaybackEvent::NoteOn {
+            note: 69,
+            velocity: VELOCITY
+        }));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_list_and_delete() {
+        let dir = temp_dir("list");
+        write_log(&dir.join("session-100.jsonl"), &[(0, chord("C"))]);
+        write_log(&dir.join("session-200.jsonl"), &[(0, chord("G7"))]);
+        write_log(
+            &dir.join("session-300.jsonl"),
+            &[(0, Event::NoteOn { note: 60 })],
+        );
+        fs::write(dir.join("notes.txt"), "not a session").unwrap();
+
+        let mut browser = SessionBrowser::open(&dir);
+        assert_eq!(browser.sessions.len(), 2);
+        assert_eq!(browser.selected().unwrap().started, Some(200));
+
+        browser.next();
+        browser.delete_selected().unwrap();
+        assert_eq!(browser.sessions.len(), 1);
+        assert_eq!(browser.cursor, 0);
+        assert!(!dir.join("session-100.jsonl").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_compare_takes() {
+        let dir = temp_dir("compare");
+        write_log(
+            &dir.join("session-100.jsonl"),
+            &[(0, chord("C")), (1, chord("F")), (2, chord("G"))],
+        );
+        write_log(
+            &dir.join("session-200.jsonl"),
+            &[(0, chord("C")), (1, chord("Dm")), (2, chord("G"))],
+        );
+
+        let mut browser = SessionBrowser::open(&dir);
+        assert!(browser.compare_selected().is_none());
+        assert!(browser.reference.is_some());
+        browser.next();
+        let diff = browser.compare_selected().unwrap();
+        assert_eq!(diff.count(crate::session::Change::Changed), 1);
+        assert!(browser.reference.is_none());
+
+        // Marking and choosing the same take again unmarks it
+        assert!(browser.compare_selected().is_none());
+        assert!(browser.compare_selected().is_none());
+        assert!(browser.reference.is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_replay_shortens_pauses() {
+        let mut session = Session::new();
+        let c = crate::theory::Chord::from_name("C").unwrap();
+        session.push_timed(&c, vec![60, 64, 67], 0.0);
+        session.push_timed(&c, vec![62, 65, 69], 60.0);
+
+        let mut playback = replay(&session);
+        let start = std::time::Instant::now();
+        playback.start(start);
+        let mut events = Vec::new();
+        playback.tick(start + Duration::from_secs_f64(MAX_REPLAY_GAP), &mut events);
+        let ons = events
+            .iter()
+            .filter(|e| matches!(e, PlaybackEvent::NoteOn { .. }))
+            .count();
+        assert_eq!(ons, 6);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::theory::Chord;
+
+/// Edit costs for lining up two takes: the same root read as another
+/// chord counts as a near miss
+const SAME_ROOT: u32 = 1;
+const WRONG_CHORD: u32 = 2;
+const GAP: u32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Same,
+    /// Another chord played in place of the reference one
+    Changed,
+    /// A reference chord the take left out
+    Missed,
+    /// A chord played that the reference doesn't have
+    Extra,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffRow {
+    pub reference: Option<Chord>,
+    pub take: Option<Chord>,
+}
+
+impl DiffRow {
+    pub fn change(&self) -> Change {
+        match (&self.reference, &self.take) {
+            (Some(r), Some(t)) if r.name() == t.name() => Change::Same,
+            (Some(_), Some(_)) => Change::Changed,
+            (Some(_), None) => Change::Missed,
+            _ => Change::Extra,
+        }
+    }
+}
+
+/// A take lined up chord by chord against a reference: another take, or a
+/// progression it was meant to follow.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TakeDiff {
+    pub reference_name: String,
+    pub take_name: String,
+    pub rows: Vec<DiffRow>,
+}
+
+impl TakeDiff {
+    pub fn new(
+        reference_name: impl Into<String>,
+        reference: &[Chord],
+        take_name: impl Into<String>,
+        take: &[Chord],
+    ) -> Self {
+        Self {
+            reference_name: reference_name.into(),
+            take_name: take_name.into(),
+            rows: align(reference, take),
+        }
+    }
+
+    /// Against a progression that loops, repeated as often as the take
+    /// needs. Stopping partway round isn't counted as missing the rest.
+    pub fn looped(
+        reference_name: impl Into<String>,
+        progression: &[Chord],
+        take_name: impl Into<String>,
+        take: &[Chord],
+    ) -> Self {
+        let reference: Vec<Chord> = if progression.is_empty() {
+            Vec::new()
+        } else {
+            let times = take.len().div_ceil(progression.len()).max(1);
+            progression
+                .iter()
+                .cycle()
+                .take(times * progression.len())
+                .cloned()
+                .collect()
+        };
+        let mut diff = Self::new(reference_name, &reference, take_name, take);
+        while diff
+            .rows
+            .last()
+            .is_some_and(|r| r.change() == Change::Missed)
+        {
+            diff.rows.pop();
+        }
+        diff
+    }
+
+    pub fn count(&self, change: Change) -> usize {
+        self.rows.iter().filter(|r| r.change() == change).count()
+    }
+
+    /// Share of the lined-up rows where the take matched, 0 to 1.
+    pub fn accuracy(&self) -> f32 {
+        if self.rows.is_empty() {
+            return 1.0;
+        }
+        self.count(Change::Same) as f32 / self.rows.len() as f32
+    }
+}
+
+fn cost(a: &Chord, b: &Chord) -> u32 {
+    if a.name() == b.name() {
+        0
+    } else if a.root.pitch_class() == b.root.pitch_class() {
+        SAME_ROOT
+    } else {
+        WRONG_CHORD
+    }
+}
+
+/// Lines the two up with the fewest edits (Needleman–Wunsch), preferring
+/// a changed chord to a missed one plus an extra one.
+fn align(reference: &[Chord], take: &[Chord]) -> Vec<DiffRow> {
+    let (n, m) = (reference.len(), take.len());
+    let mut table = vec![vec![0u32; m + 1]; n + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i as u32 * GAP;
+    }
+    for (j, cell) in table[0].iter_mut().enumerate() {
+        *cell = j as u32 * GAP;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            table[i][j] = (table[i - 1][j - 1] + cost(&reference[i - 1], &take[j - 1]))
+                .min(table[i - 1][j] + GAP)
+                .min(table[i][j - 1] + GAP);
+        }
+    }
+
+    let mut rows = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0
+            && j > 0
+            && table[i][j] == table[i - 1][j - 1] + cost(&reference[i - 1], &take[j - 1])
+        {
+            rows.push(DiffRow {
+                reference: Some(reference[i - 1].clone()),
+                take: Some(take[j - 1].clone()),
+            });
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && table[i][j] == table[i - 1][j] + GAP {
+            rows.push(DiffRow {
+                reference: Some(reference[i - 1].clone()),
+                take: None,
+            });
+            i -= 1;
+        } else {
+            rows.push(DiffRow {
+                reference: None,
+                take: Some(take[j - 1].clone()),
+            });
+            j -= 1;
+        }
+    }
+    rows.reverse();
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chords(names: &[&str]) -> Vec<Chord> {
+        names.iter().map(|n| Chord::from_name(n).unwrap()).collect()
+    }
+
+    fn changes(diff: &TakeDiff) -> Vec<Change> {
+        diff.rows.iter().map(|r| r.change()).collect()
+    }
+
+    #[test]
+    fn test_align_takes() {
+        let reference = chords(&["Dm7", "G7", "Cmaj7", "A7"]);
+        let take = chords(&["Dm7", "G7", "C", "E", "A7"]);
+        let diff = TakeDiff::new("first", &reference, "second", &take);
+        assert_eq!(
+            changes(&diff),
+            [
+                Change::Same,
+                Change::Same,
+                Change::Changed,
+                Change::Extra,
+                Change::Same
+            ]
+        );
+        assert_eq!(diff.rows[2].take.as_ref().unwrap().name(), "C");
+        assert_eq!(diff.count(Change::Same), 3);
+        assert!((diff.accuracy() - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_missed_chord() {
+        let diff = TakeDiff::new(
+            "a",
+            &chords(&["C", "F", "G", "C"]),
+            "b",
+            &chords(&["C", "G", "C"]),
+        );
+        assert_eq!(
+            changes(&diff),
+            [Change::Same, Change::Missed, Change::Same, Change::Same]
+        );
+        assert!(TakeDiff::new("a", &[], "b", &[]).rows.is_empty());
+    }
+
+    #[test]
+    fn test_looped_reference() {
+        let diff = TakeDiff::looped(
+            "Axis",
+            &chords(&["C", "G", "Am", "F"]),
+            "take",
+            &chords(&["C", "G", "Am", "F", "C", "G"]),
+        );
+        assert_eq!(diff.rows.len(), 6);
+        assert_eq!(diff.count(Change::Same), 6);
+        assert_eq!(diff.accuracy(), 1.0);
+    }
+}
@@ -0,0 +1,262 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::Session;
+use crate::rhythm::TimeSignature;
+use crate::theory::{Chord, Followed, Note};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    NoteOn {
+        note: u8,
+    },
+    NoteOff {
+        note: u8,
+    },
+    Chord {
+        name: String,
+        notes: Vec<u8>,
+    },
+    Key {
+        key: Option<String>,
+    },
+    Mode {
+        mode: String,
+    },
+    TimeSignature {
+        time_signature: String,
+    },
+    /// What was suggested after the previous chord and whether the chord
+    /// that followed took a suggestion
+    Suggestion {
+        expected: String,
+        surprise: String,
+        followed: Followed,
+    },
+    /// A moment flagged to come back to, with a note typed for it
+    Bookmark {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        note: Option<String>,
+    },
+    Clear,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimedEvent {
+    pub t: u64,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+pub struct SessionWriter {
+    path: PathBuf,
+    out: BufWriter<File>,
+}
+
+impl SessionWriter {
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            out: BufWriter::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn write(&mut self, event: &TimedEvent) -> Result<()> {
+        serde_json::to_writer(&mut self.out, event)?;
+        self.out.write_all(b"\n")?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for SessionWriter {
+    fn drop(&mut self) {
+        let _ = self.out.flush();
+    }
+}
+
+pub fn read(path: &Path) -> Result<Vec<TimedEvent>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut events: Vec<TimedEvent> = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(&line)
+            .map_err(|e| anyhow!("{}:{}: {}", path.display(), i + 1, e))?;
+        events.push(event);
+    }
+    // A bookmark is written once its note is typed, after what followed it
+    events.sort_by_key(|e| e.t);
+
+    Ok(events)
+}
+
+impl Session {
+    pub fn from_events(events: &[TimedEvent]) -> Self {
+        let mut session = Session::new();
+
+        for timed in events {
+            match &timed.event {
+                Event::Chord { name, notes } => {
+                    if let Some(chord) = Chord::from_name(name) {
+                        session.push_timed(&chord, notes.clone(), timed.t as f64 / 1000.0);
+                    }
+                }
+                Event::Key { key } => {
+                    session.set_key(key.as_deref().and_then(Note::from_pitch_name));
+                }
+                Event::TimeSignature { time_signature } => {
+                    if let Some(t) = TimeSignature::from_name(time_signature) {
+                        session.set_time_signature(t);
+                    }
+                }
+                Event::Clear => session.clear(),
+                _ => {}
+            }
+        }
+
+        session
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "chordvery-log-test-{}-{}.jsonl",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_compact_line_format() {
+        let event = TimedEvent {
+            t: 1500,
+            event: Event::NoteOn { note: 60 },
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"t":1500,"type":"note_on","note":60}"#
+        );
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let path = temp_path("append");
+        let _ = fs::remove_file(&path);
+
+        {
+            let mut writer = SessionWriter::open(&path).unwrap();
+            for event in [Event::NoteOn { note: 60 }, Event::NoteOff { note: 60 }] {
+                writer.write(&TimedEvent { t: 0, event }).unwrap();
+            }
+        }
+        {
+            let mut writer = SessionWriter::open(&path).unwrap();
+            let clear = TimedEvent {
+                t: 0,
+                event: Event::Clear,
+            };
+            writer.write(&clear).unwrap();
+        }
+
+        let events = read(&path).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[2].event, Event::Clear);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_in_time_order() {
+        let path = temp_path("order");
+        let _ = fs::remove_file(&path);
+
+        let note = Some("nice turnaround".to_string());
+        {
+            let mut writer = SessionWriter::open(&path).unwrap();
+            for (t, event) in [
+                (10, Event::NoteOn { note: 60 }),
+                (20, Event::Clear),
+                (5, Event::Bookmark { note: note.clone() }),
+            ] {
+                writer.write(&TimedEvent { t, event }).unwrap();
+            }
+        }
+
+        let events = read(&path).unwrap();
+        assert_eq!(events[0].event, Event::Bookmark { note });
+        assert_eq!(events[2].event, Event::Clear);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_session_from_events() {
+        let events = vec![
+            TimedEvent {
+                t: 0,
+                event: Event::Key {
+                    key: Some("G".to_string()),
+                },
+            },
+            TimedEvent {
+                t: 10,
+                event: Event::Chord {
+                    name: "G".to_string(),
+                    notes: vec![43, 59, 62],
+                },
+            },
+            TimedEvent {
+                t: 20,
+                event: Event::Mode {
+                    mode: "Jam".to_string(),
+                },
+            },
+            TimedEvent {
+                t: 25,
+                event: Event::TimeSignature {
+                    time_signature: "6/8".to_string(),
+                },
+            },
+            TimedEvent {
+                t: 30,
+                event: Event::Chord {
+                    name: "Em".to_string(),
+                    notes: vec![40, 55, 59],
+                },
+            },
+        ];
+
+        let session = Session::from_events(&events);
+        assert_eq!(session.key().map(|k| k.pitch_class()), Some(7));
+        assert_eq!(session.len(), 2);
+        assert_eq!(session.entries[0].notes, vec![43, 59, 62]);
+        assert_eq!(session.time_signature_at(0), TimeSignature::FourFour);
+        assert_eq!(session.time_signature_at(1), TimeSignature::SixEight);
+    }
+}
@@ -0,0 +1,315 @@
+pub mod autosave;
+pub mod browser;
+pub mod diff;
+pub mod log;
+pub mod sketch;
+
+pub use autosave::Autosave;
+pub use browser::{SessionBrowser, SessionSummary};
+pub use diff::{Change, TakeDiff};
+pub use log::{Event, SessionWriter, TimedEvent};
+pub use sketch::{ChartPosition, Section, Sketch};
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::rhythm::TimeSignature;
+use crate::theory::{Chord, NamingStyle, Note, Spelling};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionEntry {
+    pub chord: String,
+    #[serde(default)]
+    pub notes: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<f64>,
+}
+
+impl SessionEntry {
+    /// The entry moved by `semitones`, notes and all, its chord named with
+    /// `spelling`. A chord name that can't be read is kept as it was.
+    pub fn transposed(&self, semitones: i8, spelling: Spelling) -> SessionEntry {
+        SessionEntry {
+            chord: Chord::from_name(&self.chord)
+                .map(|c| {
+                    c.transpose(semitones)
+                        .name_spelled(NamingStyle::Plain, spelling)
+                })
+                .unwrap_or_else(|| self.chord.clone()),
+            notes: self
+                .notes
+                .iter()
+                .map(|&n| Note::new(n).transpose_saturating(semitones).midi)
+                .collect(),
+            at: self.at,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub entries: Vec<SessionEntry>,
+    /// Where the time signature changed; 4/4 until the first change
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub meter: Vec<MeterChange>,
+}
+
+/// A time signature taking effect from the entry at `from`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MeterChange {
+    pub from: usize,
+    pub time_signature: String,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, chord: &Chord) {
+        self.push_voiced(chord, chord.notes());
+    }
+
+    pub fn push_voiced(&mut self, chord: &Chord, notes: Vec<u8>) {
+        self.entries.push(SessionEntry {
+            chord: chord.name(),
+            notes,
+            at: None,
+        });
+    }
+
+    pub fn push_timed(&mut self, chord: &Chord, notes: Vec<u8>, at: f64) {
+        self.push_voiced(chord, notes);
+        if let Some(entry) = self.entries.last_mut() {
+            entry.at = Some(at);
+        }
+    }
+
+    /// Start times in seconds, if every entry was captured with one.
+    pub fn onsets(&self) -> Option<Vec<f64>> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        self.entries.iter().map(|e| e.at).collect()
+    }
+
+    pub fn set_key(&mut self, key: Option<Note>) {
+        self.key = key.map(|k| k.name().to_string());
+    }
+
+    pub fn key(&self) -> Option<Note> {
+        self.key.as_deref().and_then(Note::from_pitch_name)
+    }
+
+    /// Changes the time signature from the next chord on.
+    pub fn set_time_signature(&mut self, time_signature: TimeSignature) {
+        let from = self.len();
+        self.meter.retain(|m| m.from < from);
+        if self.time_signature_at(from) != time_signature {
+            self.meter.push(MeterChange {
+                from,
+                time_signature: time_signature.name().to_string(),
+            });
+        }
+    }
+
+    /// The time signature the entry at `index` was played in.
+    pub fn time_signature_at(&self, index: usize) -> TimeSignature {
+        self.meter
+            .iter()
+            .rev()
+            .find(|m| m.from <= index)
+            .and_then(|m| TimeSignature::from_name(&m.time_signature))
+            .unwrap_or_default()
+    }
+
+    /// The session moved by `semitones`, its key along with it. Names are
+    /// spelled for the new key.
+    pub fn transposed(&self, semitones: i8) -> Session {
+        let key = self.key().map(|k| k.transpose_saturating(semitones));
+        let spelling = key.map(Spelling::for_key).unwrap_or_default();
+        Session {
+            key: key.map(|k| k.name_spelled(spelling).to_string()),
+            entries: self
+                .entries
+                .iter()
+                .map(|e| e.transposed(semitones, spelling))
+                .collect(),
+            meter: self.meter.clone(),
+        }
+    }
+
+    pub fn chords(&self) -> Vec<Chord> {
+        self.entries
+            .iter()
+            .filter_map(|e| Chord::from_name(&e.chord))
+            .collect()
+    }
+
+    pub fn voiced_chords(&self) -> Vec<(Chord, Vec<u8>)> {
+        self.entries
+            .iter()
+            .filter_map(|e| {
+                let chord = Chord::from_name(&e.chord)?;
+                let notes = if e.notes.is_empty() {
+                    chord.notes()
+                } else {
+                    e.notes.clone()
+                };
+                Some((chord, notes))
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.key = None;
+        self.meter.clear();
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::Quality;
+
+    #[test]
+    fn test_push_and_chords() {
+        let mut session = Session::new();
+        session.push(&Chord::new(Note::new(60), Quality::Major));
+        session.push(&Chord::new(Note::new(69), Quality::Minor7));
+
+        let chords = session.chords();
+        assert_eq!(chords.len(), 2);
+        assert_eq!(chords[1].name(), "Am7");
+    }
+
+    #[test]
+    fn test_voiced_chords_fallback() {
+        let mut session = Session::new();
+        session.push_voiced(&Chord::new(Note::new(60), Quality::Major), vec![48, 64, 67]);
+        session.entries.push(SessionEntry {
+            chord: "G".to_string(),
+            notes: Vec::new(),
+            at: None,
+        });
+
+        let voiced = session.voiced_chords();
+        assert_eq!(voiced[0].1, vec![48, 64, 67]);
+        assert_eq!(voiced[1].1, vec![67, 71, 74]);
+    }
+
+    #[test]
+    fn test_onsets() {
+        let mut session = Session::new();
+        assert_eq!(session.onsets(), None);
+
+        let c = Chord::new(Note::new(60), Quality::Major);
+        session.push_timed(&c, c.notes(), 0.5);
+        session.push_timed(
+            &Chord::new(Note::new(67), Quality::Major),
+            vec![55, 59, 62],
+            2.5,
+        );
+        assert_eq!(session.onsets(), Some(vec![0.5, 2.5]));
+
+        session.push(&c);
+        assert_eq!(session.onsets(), None);
+    }
+
+    #[test]
+    fn test_key_round_trip() {
+        let mut session = Session::new();
+        session.set_key(Some(Note::new(70)));
+        assert_eq!(session.key().map(|k| k.pitch_class()), Some(10));
+    }
+
+    #[test]
+    fn test_time_signature_changes() {
+        let mut session = Session::new();
+        let c = Chord::new(Note::new(60), Quality::Major);
+        session.push(&c);
+        session.set_time_signature(TimeSignature::SixEight);
+        session.set_time_signature(TimeSignature::ThreeFour);
+        session.push(&c);
+        session.set_time_signature(TimeSignature::ThreeFour);
+        session.push(&c);
+
+        assert_eq!(session.meter.len(), 1);
+        assert_eq!(session.time_signature_at(0), TimeSignature::FourFour);
+        assert_eq!(session.time_signature_at(2), TimeSignature::ThreeFour);
+
+        session.clear();
+        assert_eq!(session.time_signature_at(0), TimeSignature::FourFour);
+    }
+
+    #[test]
+    fn test_transposed() {
+        let mut session = Session::new();
+        session.set_key(Some(Note::new(67)));
+        session.push_timed(
+            &Chord::from_name("D7/A").unwrap(),
+            vec![57, 62, 66, 72],
+            1.5,
+        );
+        session.entries.push(SessionEntry {
+            chord: "?".to_string(),
+            ..SessionEntry::default()
+        });
+
+        let moved = session.transposed(3);
+        assert_eq!(moved.key.as_deref(), Some("Bb"));
+        assert_eq!(moved.entries[0].chord, "F7/C");
+        assert_eq!(moved.entries[0].notes, [60, 65, 69, 75]);
+        assert_eq!(moved.entries[0].at, Some(1.5));
+        assert_eq!(moved.entries[1].chord, "?");
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!(
+            "chordvery-session-test-{}.json",
+            std::process::id()
+        ));
+
+        let mut session = Session::new();
+        session.set_key(Some(Note::new(60)));
+        session.push(&Chord::new(Note::new(67), Quality::Dominant7));
+        session.save(&path).unwrap();
+
+        let loaded = Session::load(&path).unwrap();
+        assert_eq!(loaded, session);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Session, SessionEntry};
+use crate::theory::{Chord, NamingStyle, Note, NoteNames, Spelling};
+
+/// Names new sections are given, in order; `n` cycles through them
+pub const SECTION_NAMES: [&str; 7] = [
+    "Verse",
+    "Chorus",
+    "Bridge",
+    "Intro",
+    "Pre-Chorus",
+    "Solo",
+    "Outro",
+];
+pub const MAX_REPEATS: u32 = 16;
+
+/// A named run of captured chords, played `repeats` times.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Section {
+    pub name: String,
+    pub repeats: u32,
+    pub chords: Vec<SessionEntry>,
+}
+
+impl Section {
+    /// Moves on to the next of the usual section names.
+    pub fn cycle_name(&mut self) {
+        let at = SECTION_NAMES.iter().position(|n| *n == self.name);
+        self.name = SECTION_NAMES[at.map_or(0, |at| (at + 1) % SECTION_NAMES.len())].to_string();
+    }
+
+    /// The name to show, with the usual names taken from `usual`, a
+    /// translation of `SECTION_NAMES`. Names typed in are kept.
+    pub fn name_in<'a>(&'a self, usual: &'a [&str; 7]) -> &'a str {
+        match SECTION_NAMES.iter().position(|n| *n == self.name) {
+            Some(at) => usual[at],
+            None => &self.name,
+        }
+    }
+
+    pub fn set_repeats(&mut self, repeats: u32) {
+        self.repeats = repeats.clamp(1, MAX_REPEATS);
+    }
+}
+
+/// A place in the arrangement while stepping through it: a chord of one
+/// pass through a section.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChartPosition {
+    pub section: usize,
+    pub pass: u32,
+    pub chord: usize,
+}
+
+/// A song arranged from captured chords: sections in order, each repeated.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sketch {
+    pub sections: Vec<Section>,
+}
+
+impl Sketch {
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Adds a section of `chords`, named after the next usual name not yet
+    /// used, and returns its index.
+    pub fn add(&mut self, chords: Vec<SessionEntry>) -> usize {
+        let name = SECTION_NAMES
+            .iter()
+            .find(|name| !self.sections.iter().any(|s| s.name == **name))
+            .unwrap_or(&SECTION_NAMES[0]);
+        self.sections.push(Section {
+            name: name.to_string(),
+            repeats: 1,
+            chords,
+        });
+        self.sections.len() - 1
+    }
+
+    /// Adds a copy of section `index` at the end, as for a chorus coming
+    /// round again.
+    pub fn duplicate(&mut self, index: usize) -> Option<usize> {
+        let section = self.sections.get(index)?.clone();
+        self.sections.push(section);
+        Some(self.sections.len() - 1)
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.sections.len() {
+            self.sections.remove(index);
+        }
+    }
+
+    /// Moves section `index` one place earlier or later, returning where
+    /// it ends up.
+    pub fn shift(&mut self, index: usize, later: bool) -> usize {
+        let to = if later {
+            index + 1
+        } else {
+            index.wrapping_sub(1)
+        };
+        if index < self.sections.len() && to < self.sections.len() {
+            self.sections.swap(index, to);
+            return to;
+        }
+        index
+    }
+
+    pub fn chord_at(&self, at: ChartPosition) -> Option<&SessionEntry> {
+        self.sections.get(at.section)?.chords.get(at.chord)
+    }
+
+    /// The chord after `at`, through each section's repeats and back round
+    /// to the start after the last. From nowhere, or somewhere no longer in
+    /// the arrangement, that's the first chord.
+    pub fn next_position(&self, at: Option<ChartPosition>) -> Option<ChartPosition> {
+        let next = match at {
+            Some(at) if self.chord_at(at).is_some() => {
+                let section = &self.sections[at.section];
+                if at.chord + 1 < section.chords.len() {
+                    return Some(ChartPosition {
+                        chord: at.chord + 1,
+                        ..at
+                    });
+                }
+                if at.pass + 1 < section.repeats {
+                    return Some(ChartPosition {
+                        pass: at.pass + 1,
+                        chord: 0,
+                        ..at
+                    });
+                }
+                at.section + 1
+            }
+            _ => 0,
+        };
+        let count = self.sections.len();
+        (0..count)
+            .map(|i| (next + i) % count)
+            .find(|&i| !self.sections[i].chords.is_empty())
+            .map(|section| ChartPosition {
+                section,
+                ..ChartPosition::default()
+            })
+    }
+
+    /// The whole arrangement as one session, sections repeated in order.
+    pub fn to_session(&self, key: Option<String>) -> Session {
+        let entries = self
+            .sections
+            .iter()
+            .flat_map(|s| {
+                (0..s.repeats).flat_map(move |_| {
+                    s.chords.iter().map(|e| SessionEntry {
+                        at: None,
+                        ..e.clone()
+                    })
+                })
+            })
+            .collect();
+        Session {
+            key,
+            entries,
+            ..Session::default()
+        }
+    }
+
+    /// Moved by `semitones` into `key`, which spells the chord names.
+    pub fn transposed(&self, semitones: i8, key: Note) -> Sketch {
+        let spelling = Spelling::for_key(key);
+        Sketch {
+            sections: self
+                .sections
+                .iter()
+                .map(|s| Section {
+                    chords: s
+                        .chords
+                        .iter()
+                        .map(|e| e.transposed(semitones, spelling))
+                        .collect(),
+                    ..s.clone()
+                })
+                .collect(),
+        }
+    }
+
+    /// A lead-sheet style chart: each section's name and repeats, then its
+    /// chords in bars, with roots in the `names` convention and the usual
+    /// section names from `section_names`.
+    pub fn to_text(&self, names: NoteNames, section_names: &[&str; 7]) -> String {
+        let mut text = String::new();
+        for section in &self.sections {
+            text.push_str(section.name_in(section_names));
+            if section.repeats > 1 {
+                text.push_str(&format!(" x{}", section.repeats));
+            }
+            text.push('\n');
+            let bars: Vec<String> = section
+                .chords
+                .iter()
+                .map(|e| match Chord::from_name(&e.chord) {
+                    Some(chord) => {
+                        chord.name_in(NamingStyle::Plain, Spelling::of_name(&e.chord), names)
+                    }
+                    None => e.chord.clone(),
+                })
+                .collect();
+            for line in bars.chunks(4) {
+                text.push_str(&format!("| {} |\n", line.join(" | ")));
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(names: &[&str]) -> Vec<SessionEntry> {
+        names
+            .iter()
+            .map(|name| SessionEntry {
+                chord: name.to_string(),
+                notes: Vec::new(),
+                at: Some(1.0),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_arrange() {
+        let mut sketch = Sketch::default();
+        assert_eq!(sketch.add(entries(&["C", "Am", "F", "G"])), 0);
+        sketch.add(entries(&["F", "G", "C"]));
+        assert_eq!(sketch.sections[1].name, "Chorus");
+        sketch.sections[0].set_repeats(2);
+        sketch.duplicate(1);
+
+        let session = sketch.to_session(Some("C".to_string()));
+        let names: Vec<String> = session.chords().iter().map(|c| c.name()).collect();
+        assert_eq!(
+            names,
+            ["C", "Am", "F", "G", "C", "Am", "F", "G", "F", "G", "C", "F", "G", "C"]
+        );
+        assert!(session.onsets().is_none());
+
+        assert_eq!(sketch.shift(2, false), 1);
+        assert_eq!(sketch.shift(0, false), 0);
+        sketch.remove(1);
+        assert_eq!(sketch.sections.len(), 2);
+    }
+
+    #[test]
+    fn test_section_names() {
+        let mut sketch = Sketch::default();
+        sketch.add(entries(&["C"]));
+        sketch.sections[0].cycle_name();
+        assert_eq!(sketch.sections[0].name, "Chorus");
+        sketch.sections[0].set_repeats(0);
+        assert_eq!(sketch.sections[0].repeats, 1);
+        // Verse is free again
+        sketch.add(entries(&["C"]));
+        assert_eq!(sketch.sections[1].name, "Verse");
+    }
+
+    #[test]
+    fn test_step_through() {
+        let mut sketch = Sketch::default();
+        assert_eq!(sketch.next_position(None), None);
+        sketch.add(entries(&["C", "G"]));
+        sketch.add(Vec::new());
+        sketch.add(entries(&["F"]));
+        sketch.sections[0].set_repeats(2);
+
+        let mut at = None;
+        let mut played = Vec::new();
+        for _ in 0..6 {
+            at = sketch.next_position(at);
+            played.push(sketch.chord_at(at.unwrap()).unwrap().chord.clone());
+        }
+        assert_eq!(played, ["C", "G", "C", "G", "F", "C"]);
+        assert_eq!(
+            at,
+            Some(ChartPosition {
+                section: 0,
+                pass: 0,
+                chord: 0
+            })
+        );
+
+        sketch.remove(0);
+        assert_eq!(sketch.next_position(at).unwrap().section, 1);
+    }
+
+    #[test]
+    fn test_to_text() {
+        let mut sketch = Sketch::default();
+        sketch.add(entries(&["C", "Am", "F", "G", "Em"]));
+        sketch.sections[0].set_repeats(2);
+        let english = &SECTION_NAMES;
+        assert_eq!(
+            sketch.to_text(NoteNames::English, english),
+            "Verse x2\n| C | Am | F | G |\n| Em |\n\n"
+        );
+        let moved = sketch.transposed(-2, Note::new(70));
+        assert_eq!(
+            moved.to_text(NoteNames::English, english),
+            "Verse x2\n| Bb | Gm | Eb | F |\n| Dm |\n\n"
+        );
+
+        // B flat is B in German, and Verse is Strophe
+        let mut german = SECTION_NAMES;
+        german[0] = "Strophe";
+        assert_eq!(
+            moved.to_text(NoteNames::German, &german),
+            "Strophe x2\n| B | Gm | Es | F |\n| Dm |\n\n"
+        );
+        let mut renamed = moved;
+        renamed.sections[0].name = "Coda".to_string();
+        assert!(renamed
+            .to_text(NoteNames::German, &german)
+            .starts_with("Coda x2\n"));
+    }
+}
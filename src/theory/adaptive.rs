@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::chord::Chord;
+use super::model::{ProgressionModel, Transition};
+use super::note::Note;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveModel {
+    pub counts: BTreeMap<String, BTreeMap<String, u32>>,
+}
+
+impl AdaptiveModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn load_or_default(path: &Path) -> Self {
+        Self::load(path).unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn observe(&mut self, previous: &Chord, next: &Chord, key: Note) {
+        let from = previous.roman_numeral(key);
+        let to = next.roman_numeral(key);
+        *self.counts.entry(from).or_default().entry(to).or_default() += 1;
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.values().flat_map(|m| m.values()).sum()
+    }
+
+    pub fn reset(&mut self) {
+        self.counts.clear();
+    }
+
+    pub fn blend(&self, base: &ProgressionModel, factor: f32) -> ProgressionModel {
+        let factor = factor.clamp(0.0, 1.0);
+        let mut blended = base.clone();
+
+        if factor == 0.0 {
+            return blended;
+        }
+
+        let reference = Note::new(60);
+
+        for (from, learned) in &self.counts {
+            let Some(from_chord) = Chord::from_roman(from, reference) else {
+                continue;
+            };
+
+            let base_transitions = base.transitions_for(&from_chord, reference);
+            let base_total: f32 = base_transitions.iter().map(|t| t.weight).sum();
+            let learned_total: u32 = learned.values().sum();
+
+            let mut weights: BTreeMap<String, f32> = BTreeMap::new();
+
+            if base_total > 0.0 {
+                for t in base_transitions {
+                    *weights.entry(t.to.clone()).or_default() +=
+                        (1.0 - factor) * t.weight / base_total;
+                }
+            }
+
+            if learned_total > 0 {
+                for (to, &count) in learned {
+                    *weights.entry(to.clone()).or_default() +=
+                        factor * count as f32 / learned_total as f32;
+                }
+            }
+
+            blended.transitions.insert(
+                from.clone(),
+                weights
+                    .into_iter()
+                    .map(|(to, weight)| Transition { to, weight })
+                    .collect(),
+            );
+        }
+
+        blended
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::Quality;
+
+    fn chord(name: &str) -> Chord {
+        Chord::from_name(name).unwrap()
+    }
+
+    #[test]
+    fn test_observe_counts_numerals() {
+        let mut adaptive = AdaptiveModel::new();
+        let key = Note::new(67);
+
+        adaptive.observe(&chord("G"), &chord("F"), key);
+        adaptive.observe(&chord("G"), &chord("F"), key);
+
+        assert_eq!(adaptive.counts["I"]["bVII"], 2);
+        assert_eq!(adaptive.total(), 2);
+    }
+
+    #[test]
+    fn test_blend_shifts_suggestions() {
+        let mut adaptive = AdaptiveModel::new();
+        let key = Note::new(60);
+        for _ in 0..5 {
+            adaptive.observe(&chord("C"), &chord("Bb"), key);
+        }
+
+        let base = ProgressionModel::default();
+        let c = Chord::new(Note::new(60), Quality::Major);
+
+        let none = adaptive.blend(&base, 0.0);
+        assert_eq!(none.candidates(&c, key)[0].0.name(), "F");
+
+        let half = adaptive.blend(&base, 0.5);
+        let candidates = half.candidates(&c, key);
+        assert_eq!(candidates[0].0.name(), "A#");
+        assert_eq!(candidates[1].0.name(), "F");
+        let total: f32 = candidates.iter().map(|(_, w)| w).sum();
+        assert!((total - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_save_load_and_reset() {
+        let path = std::env::temp_dir().join(format!(
+            "chordvery-adaptive-test-{}.json",
+            std::process::id()
+        ));
+
+        let mut adaptive = AdaptiveModel::new();
+        adaptive.observe(&chord("Dm7"), &chord("G7"), Note::new(60));
+        adaptive.save(&path).unwrap();
+        assert_eq!(AdaptiveModel::load_or_default(&path), adaptive);
+
+        adaptive.reset();
+        assert_eq!(adaptive.total(), 0);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
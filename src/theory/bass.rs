@@ -0,0 +1,122 @@
+use super::chord::Chord;
+
+const BASS_LOW: u8 = 36;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BassStyle {
+    RootFifth,
+    #[default]
+    Walking,
+}
+
+impl BassStyle {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BassStyle::RootFifth => "Root-fifth",
+            BassStyle::Walking => "Walking",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            BassStyle::RootFifth => BassStyle::Walking,
+            BassStyle::Walking => BassStyle::RootFifth,
+        }
+    }
+}
+
+/// One bar of quarter notes over `current`, leading into `next`.
+pub fn bass_line(current: &Chord, next: &Chord, style: BassStyle) -> Vec<u8> {
+    let root = BASS_LOW + current.root.pitch_class();
+    let fifth = root + fifth_above(current);
+
+    match style {
+        BassStyle::RootFifth => vec![root, fifth, root + 12, fifth],
+        BassStyle::Walking => {
+            let third = root + third_above(current);
+            let target = nearest(next.root.pitch_class(), fifth);
+            let approach = if target > fifth {
+                target - 1
+            } else {
+                target + 1
+            };
+            vec![root, third, fifth, approach]
+        }
+    }
+}
+
+pub fn bass_lines(chords: &[Chord], style: BassStyle) -> Vec<Vec<u8>> {
+    chords
+        .iter()
+        .enumerate()
+        .map(|(i, chord)| bass_line(chord, chords.get(i + 1).unwrap_or(chord), style))
+        .collect()
+}
+
+fn third_above(chord: &Chord) -> u8 {
+    chord
+        .quality
+        .intervals()
+        .get(1)
+        .copied()
+        .filter(|&i| i < 7)
+        .unwrap_or(4)
+}
+
+fn fifth_above(chord: &Chord) -> u8 {
+    chord
+        .quality
+        .intervals()
+        .iter()
+        .copied()
+        .find(|&i| (6..=8).contains(&i))
+        .unwrap_or(7)
+}
+
+fn nearest(pitch_class: u8, to: u8) -> u8 {
+    let below = to - (to % 12 + 12 - pitch_class) % 12;
+    let above = below + 12;
+    if to - below <= above - to {
+        below
+    } else {
+        above
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::{Note, Quality};
+
+    #[test]
+    fn test_root_fifth() {
+        let c = Chord::new(Note::new(60), Quality::Major);
+        let g = Chord::new(Note::new(67), Quality::Major);
+        assert_eq!(
+            bass_line(&c, &g, BassStyle::RootFifth),
+            vec![36, 43, 48, 43]
+        );
+    }
+
+    #[test]
+    fn test_walking_approach() {
+        let dm7 = Chord::new(Note::new(62), Quality::Minor7);
+        let g7 = Chord::new(Note::new(67), Quality::Dominant7);
+
+        // D F A, then a half step into G
+        let line = bass_line(&dm7, &g7, BassStyle::Walking);
+        assert_eq!(line, vec![38, 41, 45, 44]);
+        assert_eq!((line[3] as i8 - 43).abs(), 1);
+    }
+
+    #[test]
+    fn test_lines_per_chord() {
+        let chords = vec![
+            Chord::new(Note::new(60), Quality::Major),
+            Chord::new(Note::new(65), Quality::Major),
+        ];
+        let lines = bass_lines(&chords, BassStyle::Walking);
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.len() == 4));
+    }
+}
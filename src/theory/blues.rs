@@ -0,0 +1,154 @@
+use std::time::{Duration, Instant};
+
+use super::{Chord, Note, Quality};
+
+/// Bars in the form
+pub const BARS: usize = 12;
+
+/// A change often played instead of a bar's chord.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Variant {
+    pub name: &'static str,
+    /// Semitones above the tonic
+    pub root: u8,
+    pub quality: Quality,
+}
+
+/// A bar of the form: a dominant seventh `root` semitones above the tonic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FormBar {
+    pub root: u8,
+    pub variant: Option<Variant>,
+}
+
+const fn bar(root: u8) -> FormBar {
+    FormBar {
+        root,
+        variant: None,
+    }
+}
+
+const fn or(root: u8, name: &'static str, variant: u8, quality: Quality) -> FormBar {
+    FormBar {
+        root,
+        variant: Some(Variant {
+            name,
+            root: variant,
+            quality,
+        }),
+    }
+}
+
+/// The 12-bar blues, with the quick change to IV in bar 2 and the
+/// I–VI–ii–V turnaround over the last two bars.
+pub const TWELVE_BAR: [FormBar; BARS] = [
+    bar(0),
+    or(0, "quick change", 5, Quality::Dominant7),
+    bar(0),
+    bar(0),
+    bar(5),
+    bar(5),
+    bar(0),
+    bar(0),
+    bar(7),
+    bar(5),
+    or(0, "turnaround", 9, Quality::Dominant7),
+    or(7, "turnaround", 2, Quality::Minor7),
+];
+
+impl FormBar {
+    pub fn chord(&self, tonic: Note) -> Chord {
+        Chord::new(on(tonic, self.root), Quality::Dominant7)
+    }
+
+    pub fn variant_chord(&self, tonic: Note) -> Option<(Chord, &'static str)> {
+        self.variant
+            .map(|v| (Chord::new(on(tonic, v.root), v.quality), v.name))
+    }
+}
+
+fn on(tonic: Note, semitones: u8) -> Note {
+    Note::new(60 + (tonic.pitch_class() + semitones) % 12)
+}
+
+/// Counts bars through the 12-bar form from the first tonic chord played:
+/// by the clock at a tempo, otherwise a bar for each chord.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BluesForm {
+    started: Option<Instant>,
+    chords: usize,
+}
+
+impl BluesForm {
+    pub fn chord_played(&mut self, chord: &Chord, tonic: Note, now: Instant) {
+        if self.started.is_some() {
+            self.chords += 1;
+        } else if chord.root.pitch_class() == tonic.pitch_class() {
+            self.started = Some(now);
+        }
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// The bar of the form, from 0, at `now` with bars lasting `bar`.
+    pub fn bar(&self, now: Instant, bar: Option<Duration>) -> Option<usize> {
+        let started = self.started?;
+        let bars = match bar.filter(|b| !b.is_zero()) {
+            Some(bar) => {
+                (now.saturating_duration_since(started).as_secs_f64() / bar.as_secs_f64()) as usize
+            }
+            None => self.chords,
+        };
+        Some(bars % BARS)
+    }
+
+    pub fn restart(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_form() {
+        let g = Note::new(67);
+        let names: Vec<String> = TWELVE_BAR.iter().map(|b| b.chord(g).name()).collect();
+        assert_eq!(
+            names,
+            ["G7", "G7", "G7", "G7", "C7", "C7", "G7", "G7", "D7", "C7", "G7", "D7"]
+        );
+        assert_eq!(
+            TWELVE_BAR[1].variant_chord(g).map(|(c, n)| (c.name(), n)),
+            Some(("C7".to_string(), "quick change"))
+        );
+        assert_eq!(
+            TWELVE_BAR[11].variant_chord(g).map(|(c, _)| c.name()),
+            Some("Am7".to_string())
+        );
+    }
+
+    #[test]
+    fn test_counts_bars_from_the_tonic() {
+        let start = Instant::now();
+        let g = Note::new(67);
+        let mut form = BluesForm::default();
+        form.chord_played(&Chord::from_name("C7").unwrap(), g, start);
+        assert_eq!(form.bar(start, None), None);
+
+        form.chord_played(&Chord::from_name("G7").unwrap(), g, start);
+        let bar = Some(Duration::from_secs(2));
+        assert_eq!(form.bar(start + Duration::from_secs(9), bar), Some(4));
+        assert_eq!(form.bar(start + Duration::from_secs(25), bar), Some(0));
+
+        // Without a tempo each chord is a bar
+        form.chord_played(&Chord::from_name("C7").unwrap(), g, start);
+        assert_eq!(form.bar(start, None), Some(1));
+
+        form.restart();
+        assert!(!form.is_started());
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use super::Onsets;
+
+/// Struck softer than this, a note beside a chord tone may be a brushed key
+const BRUSH_VELOCITY: u8 = 40;
+/// Held longer than this, a soft note was meant
+const BRUSH_TIME: Duration = Duration::from_millis(150);
+/// Notes that have to stay for there to be a chord without the brushes
+const MIN_CHORD: usize = 3;
+
+/// The held notes that look brushed by accident next to the ones meant:
+/// struck much softer than the note a semitone away and not yet held long.
+/// They stay on the keyboard but are left out of detection. Notes without
+/// a velocity count as struck in full.
+pub fn brushes(
+    held: &HashSet<u8>,
+    velocities: &HashMap<u8, u8>,
+    onsets: &Onsets,
+    now: Instant,
+) -> HashSet<u8> {
+    let velocity = |note: u8| velocities.get(&note).copied().unwrap_or(127);
+    let brushes: HashSet<u8> = held
+        .iter()
+        .copied()
+        .filter(|&note| {
+            let soft = velocity(note);
+            let louder_neighbour = [note.checked_sub(1), Some(note + 1)]
+                .into_iter()
+                .flatten()
+                .any(|n| held.contains(&n) && velocity(n) as u16 >= soft as u16 * 2);
+            soft < BRUSH_VELOCITY
+                && now.saturating_duration_since(onsets.of(note, now)) < BRUSH_TIME
+                && louder_neighbour
+        })
+        .collect();
+    if held.len() - brushes.len() < MIN_CHORD {
+        return HashSet::new();
+    }
+    brushes
+}
+
+/// The held notes without the brushed ones.
+pub fn without_brushes(
+    held: &HashSet<u8>,
+    velocities: &HashMap<u8, u8>,
+    onsets: &Onsets,
+    now: Instant,
+) -> HashSet<u8> {
+    let brushes = brushes(held, velocities, onsets, now);
+    held.difference(&brushes).copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes(notes: &[u8]) -> HashSet<u8> {
+        notes.iter().copied().collect()
+    }
+
+    fn velocities(velocities: &[(u8, u8)]) -> HashMap<u8, u8> {
+        velocities.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_brushed_neighbour() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut onsets = Onsets::default();
+        let held = notes(&[60, 64, 65, 67]);
+        let struck = velocities(&[(60, 90), (64, 90), (65, 15), (67, 90)]);
+        onsets.update(&held, ms(0));
+
+        let kept = without_brushes(&held, &struck, &onsets, ms(50));
+        assert_eq!(kept, notes(&[60, 64, 67]));
+        // Held on, it was meant
+        assert_eq!(without_brushes(&held, &struck, &onsets, ms(200)), held);
+    }
+
+    #[test]
+    fn test_soft_chords_kept() {
+        let now = Instant::now();
+        let mut onsets = Onsets::default();
+
+        // A cluster played softly throughout is not brushed
+        let cluster = notes(&[60, 61, 62, 64]);
+        let soft = velocities(&[(60, 20), (61, 20), (62, 20), (64, 20)]);
+        onsets.update(&cluster, now);
+        assert!(brushes(&cluster, &soft, &onsets, now).is_empty());
+
+        // Nor is a soft note away from the others
+        let held = notes(&[60, 64, 67, 72]);
+        let struck = velocities(&[(60, 90), (64, 90), (67, 90), (72, 10)]);
+        onsets.update(&held, now);
+        assert!(brushes(&held, &struck, &onsets, now).is_empty());
+
+        // Nor one that would leave too few notes for a chord
+        let dyad = notes(&[60, 64, 65]);
+        let struck = velocities(&[(60, 90), (64, 90), (65, 15)]);
+        onsets.update(&dyad, now);
+        assert!(brushes(&dyad, &struck, &onsets, now).is_empty());
+    }
+}
@@ -1,19 +1,30 @@
 use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
+use anyhow::{anyhow, Result};
+
+use super::detection::{Confidence, Detection, Preferences};
+use super::naming::NamingStyle;
 use super::note::Note;
 use super::quality::Quality;
+use super::spelling::{parse_pitch, NoteNames, Spelling};
 
-const NOTE_NAMES: [&str; 12] = [
-    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
-];
-
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Chord {
     pub root: Note,
     pub quality: Quality,
     pub bass: Option<Note>,
 }
 
+/// What a note is to a chord: the degree it sits on above the root, and
+/// whether the chord has it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ToneRole {
+    pub degree: &'static str,
+    pub chord_tone: bool,
+}
+
 impl Chord {
     pub fn new(root: Note, quality: Quality) -> Self {
         Self {
@@ -29,6 +40,16 @@ impl Chord {
     }
 
     pub fn detect(notes: &HashSet<u8>) -> Option<Self> {
+        Self::detect_with(notes, &Preferences::default())
+    }
+
+    pub fn detect_with(notes: &HashSet<u8>, prefs: &Preferences) -> Option<Self> {
+        Self::analyze(notes, prefs).map(|d| d.chord)
+    }
+
+    /// Detects the chord along with how sure the reading is. Seventh chords
+    /// missing their fifth are recognised when nothing matches exactly.
+    pub fn analyze(notes: &HashSet<u8>, prefs: &Preferences) -> Option<Detection> {
         if notes.len() < 3 {
             return None;
         }
@@ -42,8 +63,23 @@ impl Chord {
         let lowest_note = *notes.iter().min()?;
         let lowest_pitch_class = lowest_note % 12;
 
-        let mut best_match: Option<Chord> = None;
-        let mut best_score = 0;
+        let exact = Quality::all_sevenths()
+            .iter()
+            .chain(Quality::all_sixths())
+            .chain(Quality::all_triads())
+            .chain([Quality::Quartal].iter())
+            .map(|&q| (q, false));
+        let no_fifth = [
+            Quality::Major7,
+            Quality::Minor7,
+            Quality::Dominant7,
+            Quality::MinorMajor7,
+        ]
+        .into_iter()
+        .map(|q| (q, true));
+        let readings: Vec<(Quality, bool)> = exact.chain(no_fifth).collect();
+
+        let mut best: Option<(i32, Detection)> = None;
 
         for &potential_root in pitch_classes.iter() {
             let intervals: HashSet<u8> = pitch_classes
@@ -51,41 +87,79 @@ impl Chord {
                 .map(|&pc| (pc + 12 - potential_root) % 12)
                 .collect();
 
-            for quality in Quality::all_sevenths()
-                .iter()
-                .chain(Quality::all_triads().iter())
-            {
-                let quality_intervals: HashSet<u8> =
-                    quality.intervals().iter().map(|&i| i % 12).collect();
+            for &(quality, omit_fifth) in &readings {
+                let quality_intervals: HashSet<u8> = quality
+                    .intervals()
+                    .iter()
+                    .map(|&i| i % 12)
+                    .filter(|&i| !omit_fifth || i != 7)
+                    .collect();
 
                 if intervals == quality_intervals {
                     let is_root_position = potential_root == lowest_pitch_class;
                     let is_seventh = quality.intervals().len() == 4;
-                    let score =
-                        if is_root_position { 10 } else { 5 } + if is_seventh { 2 } else { 0 };
+                    let score = if is_root_position { 10 } else { 5 }
+                        + if is_seventh { 2 } else { 0 }
+                        + if omit_fifth { -20 } else { 0 }
+                        + prefs.bias(quality);
 
-                    if score > best_score {
-                        let mut chord = Chord::new(Note::new(potential_root + 60), *quality);
+                    if best.as_ref().is_none_or(|(s, _)| score > *s) {
+                        let mut chord = Chord::new(Note::new(potential_root + 60), quality);
 
                         if !is_root_position {
                             chord.bass = Some(Note::new(lowest_note));
                         }
 
-                        best_match = Some(chord);
-                        best_score = score;
+                        let confidence = Confidence {
+                            completeness: intervals.len() as f32 / quality.intervals().len() as f32,
+                            bass: if is_root_position { 1.0 } else { 0.8 },
+                        };
+                        best = Some((score, Detection { chord, confidence }));
                     }
                 }
             }
         }
 
-        best_match
+        match best.map(|(_, d)| d) {
+            // Am7 heard as the relative major triad over its root
+            Some(Detection { chord, confidence })
+                if prefs.slash && chord.quality == Quality::Minor7 && chord.bass.is_none() =>
+            {
+                let major = Note::new((chord.root.pitch_class() + 3) % 12 + 60);
+                Some(Detection {
+                    chord: Chord::new(major, Quality::Major).with_bass(Note::new(lowest_note)),
+                    confidence: Confidence {
+                        bass: 0.6,
+                        ..confidence
+                    },
+                })
+            }
+            other => other,
+        }
     }
 
     pub fn name(&self) -> String {
-        let base = format!("{}{}", self.root.name(), self.quality.symbol());
+        self.name_styled(NamingStyle::Plain)
+    }
+
+    pub fn name_styled(&self, style: NamingStyle) -> String {
+        self.name_spelled(style, Spelling::Sharp)
+    }
+
+    pub fn name_spelled(&self, style: NamingStyle, spelling: Spelling) -> String {
+        self.name_in(style, spelling, NoteNames::English)
+    }
+
+    /// The name for display, with roots in the `names` convention.
+    pub fn name_in(&self, style: NamingStyle, spelling: Spelling, names: NoteNames) -> String {
+        let base = format!(
+            "{}{}",
+            self.root.name_in(spelling, names),
+            self.quality.symbol_styled(style)
+        );
         match &self.bass {
             Some(bass) if bass.pitch_class() != self.root.pitch_class() => {
-                format!("{}/{}", base, bass.name())
+                format!("{}/{}", base, bass.name_in(spelling, names))
             }
             _ => base,
         }
@@ -137,22 +211,177 @@ impl Chord {
         format!("{}{}", base, suffix)
     }
 
-    pub fn from_name(name: &str) -> Option<Self> {
-        let name = name.trim();
-        if name.is_empty() {
-            return None;
+    pub fn from_roman(numeral: &str, key: Note) -> Option<Self> {
+        let numeral = numeral.trim();
+
+        let mut offset: i8 = 0;
+        let mut rest = numeral;
+        while let Some(c) = rest.chars().next() {
+            match c {
+                'b' | '♭' => offset -= 1,
+                '#' | '♯' => offset += 1,
+                _ => break,
+            }
+            rest = &rest[c.len_utf8()..];
         }
 
-        let (root_str, rest) = if name.len() >= 2 && name.chars().nth(1) == Some('#') {
-            (&name[..2], &name[2..])
-        } else if !name.is_empty() {
-            (&name[..1], &name[1..])
-        } else {
-            return None;
+        const NUMERALS: [(&str, i8); 7] = [
+            ("VII", 11),
+            ("VI", 9),
+            ("V", 7),
+            ("IV", 5),
+            ("III", 4),
+            ("II", 2),
+            ("I", 0),
+        ];
+
+        let (len, degree) = NUMERALS.iter().find_map(|&(n, degree)| {
+            rest.get(..n.len())
+                .filter(|prefix| prefix.eq_ignore_ascii_case(n))
+                .map(|_| (n.len(), degree))
+        })?;
+
+        let is_lower = rest.starts_with(|c: char| c.is_ascii_lowercase());
+        let suffix = &rest[len..];
+
+        let quality = match (suffix, is_lower) {
+            ("", false) => Quality::Major,
+            ("", true) => Quality::Minor,
+            ("7", false) => Quality::Dominant7,
+            ("7", true) => Quality::Minor7,
+            ("maj7" | "M7" | "Δ7", true) => Quality::MinorMajor7,
+            ("°" | "o" | "dim", _) => Quality::Diminished,
+            ("°7" | "o7" | "dim7", _) => Quality::Diminished7,
+            ("ø7" | "ø", _) => Quality::HalfDim7,
+            _ => Quality::from_symbol(suffix)?,
         };
 
-        let root_pitch_class = NOTE_NAMES.iter().position(|&n| n == root_str)? as u8;
-        let root = Note::new(root_pitch_class + 60);
+        let pitch_class = (key.pitch_class() as i8 + degree + offset).rem_euclid(12) as u8;
+        Some(Chord::new(Note::new(pitch_class + 60), quality))
+    }
+
+    pub fn notes(&self) -> Vec<u8> {
+        let mut notes: Vec<u8> = self
+            .quality
+            .intervals()
+            .iter()
+            .map(|&i| self.root.midi.saturating_add(i).min(127))
+            .collect();
+
+        if let Some(bass) = self.bass {
+            let below = (self.root.pitch_class() + 12 - bass.pitch_class()) % 12;
+            if below != 0 {
+                notes.insert(0, self.root.midi.saturating_sub(below));
+            }
+        }
+
+        notes
+    }
+
+    pub fn pitch_classes(&self) -> HashSet<u8> {
+        self.quality
+            .intervals()
+            .iter()
+            .map(|&i| (self.root.pitch_class() + i) % 12)
+            .collect()
+    }
+
+    /// Every chord whose tones include all of `pitch_classes`, the ones
+    /// adding the fewest notes first.
+    pub fn containing(pitch_classes: &[u8]) -> Vec<Chord> {
+        let wanted: HashSet<u8> = pitch_classes.iter().map(|pc| pc % 12).collect();
+
+        let mut chords: Vec<Chord> = Quality::all()
+            .iter()
+            .flat_map(|&quality| (0..12).map(move |root| Chord::new(Note::new(60 + root), quality)))
+            .filter(|chord| wanted.is_subset(&chord.pitch_classes()))
+            .collect();
+        chords.sort_by_key(|chord| chord.pitch_classes().len() - wanted.len());
+        chords
+    }
+
+    pub fn interval_label(&self, midi: u8) -> &'static str {
+        let interval = (midi % 12 + 12 - self.root.pitch_class()) % 12;
+        let is_seventh = self.quality.intervals().len() >= 4;
+
+        match interval {
+            0 => "R",
+            1 => "b9",
+            2 if self.quality == Quality::Sus2 => "2",
+            2 => "9",
+            3 => "b3",
+            4 => "3",
+            5 => "4",
+            6 => "b5",
+            7 => "5",
+            8 => "#5",
+            9 if self.quality == Quality::Diminished7 => "bb7",
+            9 if is_seventh => "13",
+            9 => "6",
+            10 => "b7",
+            11 => "7",
+            _ => unreachable!(),
+        }
+    }
+
+    /// The 3rd and, on seventh chords, the 7th: the notes that say most
+    /// about the chord, from middle C up.
+    pub fn guide_tones(&self) -> Vec<u8> {
+        self.quality
+            .intervals()
+            .iter()
+            .filter(|&&i| match i % 12 {
+                3 | 4 | 10 | 11 => true,
+                9 => self.quality == Quality::Diminished7,
+                _ => false,
+            })
+            .map(|&i| 60 + (self.root.pitch_class() + i) % 12)
+            .collect()
+    }
+
+    /// The role of a note in the chord, with degrees spelled out: root,
+    /// 3rd, b7, or 9th and 11th for tones the chord doesn't have. A slash
+    /// bass outside the chord is the bass.
+    pub fn tone_role(&self, midi: u8) -> ToneRole {
+        let pitch_class = midi % 12;
+        let chord_tone = self.pitch_classes().contains(&pitch_class);
+        if !chord_tone && self.bass.is_some_and(|b| b.pitch_class() == pitch_class) {
+            return ToneRole {
+                degree: "bass",
+                chord_tone: true,
+            };
+        }
+        let degree = match (self.interval_label(midi), chord_tone) {
+            ("R", _) => "root",
+            ("2", _) => "2nd",
+            ("3", _) => "3rd",
+            ("4", true) => "4th",
+            ("4", false) => "11th",
+            ("b5", false) => "#11",
+            ("5", _) => "5th",
+            ("#5", false) => "b13",
+            ("6", _) => "6th",
+            ("7", _) => "7th",
+            ("9", _) => "9th",
+            ("13", _) => "13th",
+            (label, _) => label,
+        };
+        ToneRole { degree, chord_tone }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        name.parse().ok()
+    }
+}
+
+impl FromStr for Chord {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        let name = name.trim();
+        let (semitone, _, rest) =
+            parse_pitch(name).ok_or_else(|| anyhow!("Invalid chord root in '{}'", name))?;
+        let root = Note::new(semitone.rem_euclid(12) as u8 + 60);
 
         let (quality_str, bass_str) = if let Some(idx) = rest.find('/') {
             (&rest[..idx], Some(&rest[idx + 1..]))
@@ -160,32 +389,33 @@ impl Chord {
             (rest, None)
         };
 
-        let quality = match quality_str {
-            "" => Quality::Major,
-            "m" => Quality::Minor,
-            "dim" | "°" => Quality::Diminished,
-            "+" | "aug" => Quality::Augmented,
-            "maj7" | "M7" => Quality::Major7,
-            "m7" | "min7" => Quality::Minor7,
-            "7" | "dom7" => Quality::Dominant7,
-            "dim7" | "°7" => Quality::Diminished7,
-            "m7b5" | "ø7" | "ø" => Quality::HalfDim7,
-            "mMaj7" | "mM7" => Quality::MinorMajor7,
-            "+7" | "aug7" => Quality::Augmented7,
-            "sus2" => Quality::Sus2,
-            "sus4" | "sus" => Quality::Sus4,
-            "add9" => Quality::Add9,
-            _ => return None,
-        };
+        let quality = Quality::from_symbol(quality_str)
+            .ok_or_else(|| anyhow!("Unknown chord quality '{}' in '{}'", quality_str, name))?;
 
         let mut chord = Chord::new(root, quality);
 
         if let Some(bass_name) = bass_str {
-            let bass_pitch_class = NOTE_NAMES.iter().position(|&n| n == bass_name)? as u8;
-            chord.bass = Some(Note::new(bass_pitch_class + 60));
+            chord.bass = Some(
+                Note::from_pitch_name(bass_name)
+                    .ok_or_else(|| anyhow!("Invalid bass note '{}' in '{}'", bass_name, name))?,
+            );
         }
 
-        Some(chord)
+        Ok(chord)
+    }
+}
+
+impl TryFrom<&str> for Chord {
+    type Error = anyhow::Error;
+
+    fn try_from(name: &str) -> Result<Self> {
+        name.parse()
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name())
     }
 }
 
@@ -197,6 +427,50 @@ mod tests {
         midi_notes.iter().copied().collect()
     }
 
+    #[test]
+    fn test_from_str_and_display_round_trip() {
+        for &quality in Quality::all() {
+            for root in 60..72 {
+                let chord = Chord::new(Note::new(root), quality);
+                assert_eq!(chord.to_string().parse::<Chord>().unwrap(), chord);
+
+                let bass = Note::new(60 + (root + 4) % 12);
+                let slash = chord.clone().with_bass(bass);
+                assert_eq!(slash.to_string().parse::<Chord>().unwrap(), slash);
+            }
+        }
+
+        let bb: Chord = "Bbm7/Db".parse().unwrap();
+        assert_eq!(bb.to_string(), "A#m7/C#");
+        assert_eq!(Chord::try_from("Ebmaj7").unwrap().to_string(), "D#maj7");
+        assert_eq!(format!("{}", Chord::from_name("G7").unwrap()), "G7");
+
+        let err = "Cxyz".parse::<Chord>().unwrap_err().to_string();
+        assert!(err.contains("xyz"));
+        assert!("".parse::<Chord>().is_err());
+        assert!("C/H".parse::<Chord>().is_err());
+    }
+
+    #[test]
+    fn test_containing() {
+        // C and E: C, Am, C7, Cmaj7, Am7, C6, Fmaj7 ... but never Cm
+        let chords = Chord::containing(&[0, 64]);
+        let names: Vec<String> = chords.iter().map(|c| c.name()).collect();
+        for name in ["C", "Am", "C7", "Cmaj7", "Am7", "Fmaj7", "C6"] {
+            assert!(names.contains(&name.to_string()), "missing {}", name);
+        }
+        assert!(!names.contains(&"Cm".to_string()));
+        assert!(chords
+            .iter()
+            .all(|c| c.pitch_classes().contains(&0) && c.pitch_classes().contains(&4)));
+        // Triads come before four-note chords
+        assert_eq!(chords[0].pitch_classes().len(), 3);
+        assert_eq!(chords.last().unwrap().pitch_classes().len(), 4);
+
+        assert!(Chord::containing(&[0, 1, 2, 3, 4]).is_empty());
+        assert_eq!(Chord::containing(&[]).len(), Quality::all().len() * 12);
+    }
+
     #[test]
     fn test_detect_major() {
         let notes = notes_set(&[60, 64, 67]); // C, E, G
@@ -234,6 +508,71 @@ mod tests {
         assert_eq!(chord.name(), "C/E");
     }
 
+    #[test]
+    fn test_detect_preferences() {
+        use crate::theory::SixthPreference;
+
+        let c6 = notes_set(&[60, 64, 67, 69]);
+        let am7 = notes_set(&[57, 60, 64, 67]);
+        let sus = notes_set(&[60, 65, 67]);
+        let quartal = notes_set(&[60, 65, 70]);
+
+        let prefs = Preferences::default();
+        assert_eq!(Chord::detect_with(&c6, &prefs).unwrap().name(), "C6");
+        assert_eq!(Chord::detect_with(&am7, &prefs).unwrap().name(), "Am7");
+        assert_eq!(Chord::detect_with(&sus, &prefs).unwrap().name(), "Csus4");
+
+        let sixths = Preferences {
+            sixths: SixthPreference::Sixth,
+            ..prefs
+        };
+        assert_eq!(Chord::detect_with(&am7, &sixths).unwrap().name(), "C6/A");
+
+        let minor7 = Preferences {
+            sixths: SixthPreference::Minor7,
+            ..prefs
+        };
+        assert_eq!(Chord::detect_with(&c6, &minor7).unwrap().name(), "Am7/C");
+
+        let prefs = Preferences {
+            quartal: true,
+            slash: true,
+            ..prefs
+        };
+        assert_eq!(
+            Chord::detect_with(&sus, &prefs).unwrap().name(),
+            "Gquartal/C"
+        );
+        assert_eq!(
+            Chord::detect_with(&quartal, &prefs).unwrap().name(),
+            "Cquartal"
+        );
+        assert_eq!(Chord::detect_with(&am7, &prefs).unwrap().name(), "C/A");
+    }
+
+    #[test]
+    fn test_analyze_confidence() {
+        let prefs = Preferences::default();
+
+        let c = Chord::analyze(&notes_set(&[60, 64, 67]), &prefs).unwrap();
+        assert_eq!(c.confidence.value(), 1.0);
+
+        let inversion = Chord::analyze(&notes_set(&[64, 67, 72]), &prefs).unwrap();
+        assert!(inversion.confidence.value() < 1.0);
+
+        // C E Bb: a C7 shell without its fifth
+        let shell = Chord::analyze(&notes_set(&[48, 52, 58]), &prefs).unwrap();
+        assert_eq!(shell.chord.name(), "C7");
+        assert_eq!(shell.confidence.completeness, 0.75);
+
+        let slash = Preferences {
+            slash: true,
+            ..prefs
+        };
+        let c_over_a = Chord::analyze(&notes_set(&[57, 60, 64, 67]), &slash).unwrap();
+        assert!(c_over_a.confidence.bass < inversion.confidence.bass);
+    }
+
     #[test]
     fn test_detect_empty() {
         let notes = notes_set(&[]);
@@ -277,4 +616,158 @@ mod tests {
         assert_eq!(chord.root.name(), "F#");
         assert_eq!(chord.quality, Quality::Minor7);
     }
+
+    #[test]
+    fn test_name_styled() {
+        let chord = Chord::new(Note::new(60), Quality::Minor7);
+        assert_eq!(chord.name_styled(NamingStyle::Plain), "Cm7");
+        assert_eq!(chord.name_styled(NamingStyle::Jazz), "Cmin7");
+        assert_eq!(chord.name_styled(NamingStyle::Short), "C-7");
+
+        let chord = Chord::new(Note::new(60), Quality::Major7).with_bass(Note::new(64));
+        assert_eq!(chord.name_styled(NamingStyle::Unicode), "CΔ7/E");
+    }
+
+    #[test]
+    fn test_from_name_styled_round_trip() {
+        for &style in NamingStyle::all() {
+            for &quality in Quality::all() {
+                let chord = Chord::new(Note::new(62), quality);
+                let name = chord.name_styled(style);
+                assert_eq!(Chord::from_name(&name), Some(chord), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_name_flats() {
+        let chord = Chord::from_name("Bb7").unwrap();
+        assert_eq!(chord.root.pitch_class(), 10);
+        assert_eq!(chord.quality, Quality::Dominant7);
+
+        let chord = Chord::from_name("ebm").unwrap();
+        assert_eq!(chord.root.pitch_class(), 3);
+        assert_eq!(chord.quality, Quality::Minor);
+
+        let chord = Chord::from_name("Abmaj7/C").unwrap();
+        assert_eq!(chord.root.pitch_class(), 8);
+        assert_eq!(chord.bass.map(|b| b.pitch_class()), Some(0));
+
+        let chord = Chord::from_name("Fx").unwrap();
+        assert_eq!(chord.root.pitch_class(), 7);
+
+        let chord = Chord::from_name("bb").unwrap();
+        assert_eq!(chord.root.pitch_class(), 10);
+        assert_eq!(chord.quality, Quality::Major);
+
+        assert!(Chord::from_name("Hm").is_none());
+        assert!(Chord::from_name("C/X").is_none());
+    }
+
+    #[test]
+    fn test_spelled_round_trip() {
+        for spelling in [Spelling::Sharp, Spelling::Flat] {
+            for pc in 0..12u8 {
+                for &quality in Quality::all() {
+                    let chord = Chord::new(Note::new(60 + pc), quality)
+                        .with_bass(Note::new(60 + (pc + 4) % 12));
+                    let name = chord.name_spelled(NamingStyle::Plain, spelling);
+                    let parsed = Chord::from_name(&name).unwrap();
+                    assert_eq!(parsed, chord);
+                    assert_eq!(
+                        parsed.name_spelled(NamingStyle::Plain, Spelling::of_name(&name)),
+                        name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_roman() {
+        let key = Note::new(60);
+        assert_eq!(Chord::from_roman("I", key).unwrap().name(), "C");
+        assert_eq!(Chord::from_roman("vi", key).unwrap().name(), "Am");
+        assert_eq!(Chord::from_roman("ii7", key).unwrap().name(), "Dm7");
+        assert_eq!(Chord::from_roman("V7", key).unwrap().name(), "G7");
+        assert_eq!(Chord::from_roman("bVII", key).unwrap().name(), "A#");
+        assert_eq!(Chord::from_roman("vii°", key).unwrap().name(), "Bdim");
+        assert_eq!(
+            Chord::from_roman("IVmaj7", Note::new(67)).unwrap().name(),
+            "Cmaj7"
+        );
+        assert!(Chord::from_roman("X", key).is_none());
+        assert!(Chord::from_roman("Iwhat", key).is_none());
+    }
+
+    #[test]
+    fn test_roman_round_trip() {
+        let key = Note::new(62);
+        for pc in 0..12u8 {
+            for &quality in Quality::all() {
+                let chord = Chord::new(Note::new(60 + pc), quality);
+                let numeral = chord.roman_numeral(key);
+                assert_eq!(Chord::from_roman(&numeral, key), Some(chord), "{}", numeral);
+            }
+        }
+    }
+
+    #[test]
+    fn test_notes() {
+        let chord = Chord::new(Note::new(60), Quality::Major);
+        assert_eq!(chord.notes(), vec![60, 64, 67]);
+
+        let chord = Chord::new(Note::new(60), Quality::Major).with_bass(Note::new(64));
+        assert_eq!(chord.notes(), vec![52, 60, 64, 67]);
+    }
+
+    #[test]
+    fn test_interval_label() {
+        let chord = Chord::new(Note::new(69), Quality::Minor7);
+        assert_eq!(chord.interval_label(57), "R");
+        assert_eq!(chord.interval_label(60), "b3");
+        assert_eq!(chord.interval_label(64), "5");
+        assert_eq!(chord.interval_label(67), "b7");
+        assert_eq!(chord.interval_label(71), "9");
+
+        let chord = Chord::new(Note::new(60), Quality::Diminished7);
+        assert_eq!(chord.interval_label(69), "bb7");
+    }
+
+    #[test]
+    fn test_guide_tones() {
+        let tones = |name: &str| Chord::from_name(name).unwrap().guide_tones();
+        assert_eq!(tones("G7"), [71, 65]);
+        assert_eq!(tones("Cmaj7"), [64, 71]);
+        assert_eq!(tones("Am"), [60]);
+        assert_eq!(tones("Bdim7"), [62, 68]);
+        assert!(tones("Csus4").is_empty());
+    }
+
+    #[test]
+    fn test_tone_role() {
+        let chord: Chord = "C7".parse().unwrap();
+        let role = |midi| {
+            let role = chord.tone_role(midi);
+            (role.degree, role.chord_tone)
+        };
+        assert_eq!(role(48), ("root", true));
+        assert_eq!(role(64), ("3rd", true));
+        assert_eq!(role(67), ("5th", true));
+        assert_eq!(role(70), ("b7", true));
+        assert_eq!(role(62), ("9th", false));
+        assert_eq!(role(65), ("11th", false));
+        assert_eq!(role(66), ("#11", false));
+
+        let chord: Chord = "Gsus4/C".parse().unwrap();
+        assert_eq!(chord.tone_role(60).degree, "4th");
+        let chord: Chord = "G/C".parse().unwrap();
+        assert_eq!(
+            chord.tone_role(48),
+            ToneRole {
+                degree: "bass",
+                chord_tone: true
+            }
+        );
+    }
 }
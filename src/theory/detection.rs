@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::chord::Chord;
+use super::quality::Quality;
+
+const PREFERENCE_BIAS: i32 = 6;
+const STABLE_AFTER: Duration = Duration::from_millis(300);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SixthPreference {
+    /// Whichever reading has its root in the bass
+    #[default]
+    Bass,
+    Sixth,
+    Minor7,
+}
+
+/// Tie-breaking rules for sonorities with more than one reasonable name.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Preferences {
+    /// C6 vs Am7/C, Am6 vs F#m7b5/A
+    pub sixths: SixthPreference,
+    /// C F Bb as stacked fourths rather than Fsus4/C
+    pub quartal: bool,
+    /// A C E G as C/A rather than Am7
+    pub slash: bool,
+    /// Read chords over the last few note changes, so passing tones have
+    /// to be held a while before they change the chord
+    pub smoothing: bool,
+    /// Leave a lone top note played over a chord out of detection
+    pub melody: bool,
+}
+
+impl Preferences {
+    /// Score adjustment for a candidate quality in the detection scorer.
+    pub fn bias(&self, quality: Quality) -> i32 {
+        match quality {
+            Quality::Major6 | Quality::Minor6 => match self.sixths {
+                SixthPreference::Bass => 0,
+                SixthPreference::Sixth => PREFERENCE_BIAS,
+                SixthPreference::Minor7 => -PREFERENCE_BIAS,
+            },
+            Quality::Quartal if self.quartal => PREFERENCE_BIAS,
+            Quality::Quartal => -PREFERENCE_BIAS,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Confidence {
+    /// Share of the chord's tones that are sounding
+    pub completeness: f32,
+    /// 1 with the root in the bass, less for inversions and foreign basses
+    pub bass: f32,
+}
+
+impl Default for Confidence {
+    fn default() -> Self {
+        Self {
+            completeness: 1.0,
+            bass: 1.0,
+        }
+    }
+}
+
+impl Confidence {
+    pub fn value(&self) -> f32 {
+        self.completeness * self.bass
+    }
+
+    /// Confidence once the chord has been held for `held`; a reading that
+    /// just appeared may still be a passing shape on the way to another.
+    pub fn held(&self, held: Duration) -> f32 {
+        let stability = (held.as_secs_f32() / STABLE_AFTER.as_secs_f32()).min(1.0);
+        self.value() * (0.5 + 0.5 * stability)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Detection {
+    pub chord: Chord,
+    pub confidence: Confidence,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confidence_stability() {
+        let confidence = Confidence {
+            completeness: 0.75,
+            bass: 1.0,
+        };
+        assert_eq!(confidence.held(Duration::ZERO), 0.375);
+        assert_eq!(confidence.held(Duration::from_secs(1)), 0.75);
+        assert!(confidence.held(Duration::from_millis(150)) < 0.75);
+    }
+
+    #[test]
+    fn test_bias() {
+        let prefs = Preferences::default();
+        assert_eq!(prefs.bias(Quality::Major6), 0);
+        assert!(prefs.bias(Quality::Quartal) < 0);
+        assert_eq!(prefs.bias(Quality::Sus4), 0);
+
+        let prefs = Preferences {
+            sixths: SixthPreference::Minor7,
+            quartal: true,
+            slash: false,
+            smoothing: false,
+            melody: false,
+        };
+        assert!(prefs.bias(Quality::Minor6) < 0);
+        assert!(prefs.bias(Quality::Quartal) > 0);
+    }
+}
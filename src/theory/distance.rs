@@ -0,0 +1,102 @@
+use super::chord::Chord;
+use super::quality::Quality;
+
+const TONE_WEIGHT: f32 = 0.5;
+const ROOT_WEIGHT: f32 = 0.3;
+const FUNCTION_WEIGHT: f32 = 0.2;
+
+/// What a chord tends to do, judged from its quality alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tendency {
+    /// Can rest: major, minor and their sixths and sevenths
+    Stable,
+    /// Wants to resolve: dominant sevenths and diminished chords
+    Dominant,
+    /// Neither: suspensions, stacked fourths and augmented triads
+    Open,
+}
+
+fn tendency(quality: Quality) -> Tendency {
+    match quality {
+        Quality::Major
+        | Quality::Minor
+        | Quality::Major7
+        | Quality::Minor7
+        | Quality::MinorMajor7
+        | Quality::Major6
+        | Quality::Minor6
+        | Quality::Add9 => Tendency::Stable,
+        Quality::Dominant7
+        | Quality::Augmented7
+        | Quality::Diminished
+        | Quality::Diminished7
+        | Quality::HalfDim7 => Tendency::Dominant,
+        Quality::Augmented
+        | Quality::Sus2
+        | Quality::Sus4
+        | Quality::Quartal
+        | Quality::Unknown => Tendency::Open,
+    }
+}
+
+impl Chord {
+    /// How far apart two chords sound, from 0 (the same chord) to 1. Tones
+    /// in common count most, then how far the root moves around the circle
+    /// of fifths, then whether both chords play the same role. The bass
+    /// note is ignored, so inversions are the same chord.
+    pub fn distance(&self, other: &Chord) -> f32 {
+        let (ours, theirs) = (self.pitch_classes(), other.pitch_classes());
+        let union = ours.union(&theirs).count();
+        let tones = if union == 0 {
+            0.0
+        } else {
+            1.0 - ours.intersection(&theirs).count() as f32 / union as f32
+        };
+
+        let interval = (other.root.pitch_class() + 12 - self.root.pitch_class()) % 12;
+        let fifths = (interval * 7) % 12;
+        let root = fifths.min(12 - fifths) as f32 / 6.0;
+
+        let function = if tendency(self.quality) == tendency(other.quality) {
+            0.0
+        } else {
+            1.0
+        };
+
+        TONE_WEIGHT * tones + ROOT_WEIGHT * root + FUNCTION_WEIGHT * function
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(name: &str) -> Chord {
+        Chord::from_name(name).unwrap()
+    }
+
+    #[test]
+    fn test_distance_basics() {
+        assert_eq!(chord("C").distance(&chord("C")), 0.0);
+        assert_eq!(chord("C").distance(&chord("C/E")), 0.0);
+        assert_eq!(
+            chord("C").distance(&chord("G7")),
+            chord("G7").distance(&chord("C"))
+        );
+
+        let c = chord("C");
+        let d = |name: &str| c.distance(&chord(name));
+        assert!(d("Am") < d("F#"));
+        assert!(d("Cmaj7") < d("Em"));
+        assert!(d("F") < d("Db"));
+        assert!(d("F#") <= 1.0);
+    }
+
+    #[test]
+    fn test_function_counts() {
+        // Gsus4 shares more with C than G does, but plays a different role
+        let c = chord("C");
+        assert!(c.distance(&chord("G")) < c.distance(&chord("Gsus4")));
+        assert!(c.distance(&chord("Em")) < c.distance(&chord("E+")));
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+use super::chord::Chord;
+use super::progression::ProgressionNode;
+
+/// Which branch of the suggestion tree the next chord took.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Followed {
+    Expected,
+    Surprise,
+    Neither,
+}
+
+impl Followed {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Followed::Expected => "expected",
+            Followed::Surprise => "surprise",
+            Followed::Neither => "neither",
+        }
+    }
+
+    /// Compares the chord played next with the suggestions shown for the
+    /// previous one. Inversions of a suggested chord count as following it.
+    pub fn classify(node: &ProgressionNode, played: &Chord) -> Self {
+        let matches = |branch: &Option<Box<ProgressionNode>>| {
+            branch.as_ref().is_some_and(|n| {
+                n.chord.root.pitch_class() == played.root.pitch_class()
+                    && n.chord.quality == played.quality
+            })
+        };
+        if matches(&node.left) {
+            Followed::Expected
+        } else if matches(&node.right) {
+            Followed::Surprise
+        } else {
+            Followed::Neither
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FollowStats {
+    pub expected: u32,
+    pub surprise: u32,
+    pub neither: u32,
+}
+
+impl FollowStats {
+    pub fn record(&mut self, followed: Followed) {
+        match followed {
+            Followed::Expected => self.expected += 1,
+            Followed::Surprise => self.surprise += 1,
+            Followed::Neither => self.neither += 1,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.expected + self.surprise + self.neither
+    }
+
+    /// Share of changes that went to `followed`, 0 before any change.
+    pub fn rate(&self, followed: Followed) -> f32 {
+        self.share(match followed {
+            Followed::Expected => self.expected,
+            Followed::Surprise => self.surprise,
+            Followed::Neither => self.neither,
+        })
+    }
+
+    /// Share of changes that took either suggestion.
+    pub fn follow_rate(&self) -> f32 {
+        self.share(self.expected + self.surprise)
+    }
+
+    fn share(&self, count: u32) -> f32 {
+        match self.total() {
+            0 => 0.0,
+            total => count as f32 / total as f32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::{Note, ProgressionTree};
+
+    #[test]
+    fn test_classify() {
+        let tree = ProgressionTree::new();
+        let c = Chord::from_name("C").unwrap();
+        let node = tree.suggest(&c, Some(Note::new(60)));
+        let expected = node.left.as_ref().unwrap().chord.clone();
+        let surprise = node.right.as_ref().unwrap().chord.clone();
+
+        assert_eq!(Followed::classify(&node, &expected), Followed::Expected);
+        assert_eq!(Followed::classify(&node, &surprise), Followed::Surprise);
+        let inverted = expected
+            .clone()
+            .with_bass(Note::new(expected.root.midi + 4));
+        assert_eq!(Followed::classify(&node, &inverted), Followed::Expected);
+        let elsewhere = Chord::from_name("F#7").unwrap();
+        assert_eq!(Followed::classify(&node, &elsewhere), Followed::Neither);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut stats = FollowStats::default();
+        assert_eq!(stats.follow_rate(), 0.0);
+
+        stats.record(Followed::Expected);
+        stats.record(Followed::Expected);
+        stats.record(Followed::Surprise);
+        stats.record(Followed::Neither);
+        assert_eq!(stats.total(), 4);
+        assert_eq!(stats.rate(Followed::Expected), 0.5);
+        assert_eq!(stats.follow_rate(), 0.75);
+    }
+}
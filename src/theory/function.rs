@@ -0,0 +1,93 @@
+use super::chord::Chord;
+use super::quality::Quality;
+use super::scale::Scale;
+
+/// The part a chord plays in its key: at rest, moving away or pulling home.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Function {
+    Tonic,
+    Subdominant,
+    Dominant,
+    /// Outside the key
+    Chromatic,
+}
+
+impl Function {
+    pub fn all() -> &'static [Function] {
+        &[
+            Function::Tonic,
+            Function::Subdominant,
+            Function::Dominant,
+            Function::Chromatic,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Function::Tonic => "tonic",
+            Function::Subdominant => "subdominant",
+            Function::Dominant => "dominant",
+            Function::Chromatic => "chromatic",
+        }
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            Function::Tonic => 'T',
+            Function::Subdominant => 'S',
+            Function::Dominant => 'D',
+            Function::Chromatic => 'C',
+        }
+    }
+
+    /// I, iii and vi are tonic, ii and IV subdominant, V and vii°
+    /// dominant, by scale degree. A major V outside a minor key still
+    /// counts as dominant.
+    pub fn of(chord: &Chord, scale: Scale) -> Self {
+        let from_tonic = (chord.root.pitch_class() + 12 - scale.tonic.pitch_class()) % 12;
+        if !scale.contains_chord(chord) {
+            let major = matches!(chord.quality, Quality::Major | Quality::Dominant7);
+            return if from_tonic == 7 && major {
+                Function::Dominant
+            } else {
+                Function::Chromatic
+            };
+        }
+        match scale.kind.intervals().iter().position(|&i| i == from_tonic) {
+            Some(0 | 2 | 5) => Function::Tonic,
+            Some(1 | 3) => Function::Subdominant,
+            Some(4 | 6) => Function::Dominant,
+            _ => Function::Chromatic,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::{Note, ScaleKind};
+
+    fn letters(names: &[&str], scale: Scale) -> String {
+        names
+            .iter()
+            .map(|n| Function::of(&Chord::from_name(n).unwrap(), scale).letter())
+            .collect()
+    }
+
+    #[test]
+    fn test_major() {
+        let c = Scale::major(Note::new(60));
+        assert_eq!(
+            letters(&["C", "Dm", "Em", "F", "G7", "Am", "Bdim", "Eb", "D7"], c),
+            "TSTSDTDCC"
+        );
+    }
+
+    #[test]
+    fn test_minor_and_blues() {
+        let am = Scale::new(Note::new(69), ScaleKind::Minor);
+        assert_eq!(letters(&["Am", "Dm", "E7", "F", "Bb"], am), "TSDTC");
+        let blues = Scale::new(Note::new(67), ScaleKind::Blues);
+        assert_eq!(letters(&["G7", "C7", "D7", "Eb7"], blues), "TSDC");
+    }
+}
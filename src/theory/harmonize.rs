@@ -0,0 +1,191 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::chord::Chord;
+use super::note::Note;
+use super::quality::Quality;
+use super::scale::{Scale, ScaleKind};
+
+/// Without a tempo to count bars by, a pause this long ends a phrase
+const PHRASE_GAP: Duration = Duration::from_secs(1);
+/// Phrases kept, the one being played included
+const KEPT_PHRASES: usize = 4;
+/// Chords proposed under each phrase
+pub const OPTIONS: usize = 3;
+/// Weight of a phrase's first note, which the chord is struck under
+const FIRST_NOTE_WEIGHT: f32 = 2.0;
+/// Nudges towards the tonic, subdominant and dominant when coverage ties
+const PRIMARY_BONUS: f32 = 0.05;
+/// Nudges towards triads when a seventh covers no more of the melody
+const SEVENTH_PENALTY: f32 = 0.02;
+
+/// Gathers a melody played one note at a time into phrases to put chords
+/// under: a bar each when the tempo is known, otherwise runs of notes
+/// between pauses.
+#[derive(Clone, Debug, Default)]
+pub struct MelodyHarmonizer {
+    phrases: VecDeque<Vec<u8>>,
+    started: Option<Instant>,
+    last: Option<Instant>,
+}
+
+impl MelodyHarmonizer {
+    /// Adds a melody note; `bar` is how long a bar lasts at the tempo.
+    pub fn note(&mut self, note: u8, now: Instant, bar: Option<Duration>) {
+        let since = |at: Option<Instant>| at.map(|at| now.saturating_duration_since(at));
+        let new_phrase = match bar {
+            Some(bar) => since(self.started).is_none_or(|d| d >= bar),
+            None => since(self.last).is_none_or(|d| d > PHRASE_GAP),
+        };
+        if new_phrase {
+            if self.phrases.len() == KEPT_PHRASES {
+                self.phrases.pop_front();
+            }
+            self.phrases.push_back(Vec::new());
+            self.started = Some(now);
+        }
+        self.last = Some(now);
+        if let Some(phrase) = self.phrases.back_mut() {
+            phrase.push(note);
+        }
+    }
+
+    /// The phrases kept, oldest first; the last is still being played.
+    pub fn phrases(&self) -> impl Iterator<Item = &Vec<u8>> {
+        self.phrases.iter()
+    }
+
+    /// The key the melody so far sits best in.
+    pub fn key(&self) -> Option<Scale> {
+        let notes: Vec<u8> = self.phrases.iter().flatten().copied().collect();
+        melody_key(&notes)
+    }
+}
+
+/// The major or minor key holding most of the melody's notes, with ties
+/// going to a key whose tonic starts or ends it, then to the major key.
+pub fn melody_key(notes: &[u8]) -> Option<Scale> {
+    let (first, last) = (notes.first()?, notes.last()?);
+    let score = |scale: &Scale| {
+        let inside = notes.iter().filter(|&&n| scale.contains(n)).count() as f32;
+        let tonic = |n: u8| n % 12 == scale.tonic.pitch_class();
+        let ends = [*first, *last].into_iter().filter(|&n| tonic(n)).count() as f32;
+        inside + ends * 0.25
+    };
+    // The last of equal scores wins, so majors come after minors
+    [ScaleKind::Minor, ScaleKind::Major]
+        .into_iter()
+        .flat_map(|kind| (0..12u8).map(move |pc| Scale::new(Note::new(60 + pc), kind)))
+        .max_by(|a, b| score(a).total_cmp(&score(b)))
+}
+
+/// Chords to play under a phrase, best first, each with the share of the
+/// melody it has as chord tones. Without a key, every major and minor
+/// triad is a candidate.
+pub fn harmonize(melody: &[u8], key: Option<Scale>) -> Vec<(Chord, f32)> {
+    if melody.is_empty() {
+        return Vec::new();
+    }
+    let candidates: Vec<(Chord, bool)> = match key {
+        Some(scale) => (0..7)
+            .flat_map(|degree| {
+                let primary = matches!(degree, 0 | 3 | 4);
+                [
+                    (scale.diatonic(degree, false), primary),
+                    (scale.diatonic(degree, true), primary),
+                ]
+            })
+            .collect(),
+        None => [Quality::Major, Quality::Minor]
+            .into_iter()
+            .flat_map(|q| (0..12u8).map(move |pc| (Chord::new(Note::new(60 + pc), q), false)))
+            .collect(),
+    };
+
+    let weight = |i: usize| if i == 0 { FIRST_NOTE_WEIGHT } else { 1.0 };
+    let total: f32 = (0..melody.len()).map(weight).sum();
+    let mut scored: Vec<(Chord, f32, f32)> = candidates
+        .into_iter()
+        .map(|(chord, primary)| {
+            let tones = chord.pitch_classes();
+            let covered: f32 = melody
+                .iter()
+                .enumerate()
+                .filter(|(_, n)| tones.contains(&(*n % 12)))
+                .map(|(i, _)| weight(i))
+                .sum();
+            let coverage = covered / total;
+            let mut score = coverage;
+            if primary {
+                score += PRIMARY_BONUS;
+            }
+            if tones.len() > 3 {
+                score -= SEVENTH_PENALTY;
+            }
+            (chord, coverage, score)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.2.total_cmp(&a.2));
+    scored
+        .into_iter()
+        .take(OPTIONS)
+        .map(|(chord, coverage, _)| (chord, coverage))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phrases_split_on_pauses() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut harmonizer = MelodyHarmonizer::default();
+        for (i, note) in [60, 62, 64].into_iter().enumerate() {
+            harmonizer.note(note, ms(i as u64 * 300), None);
+        }
+        harmonizer.note(65, ms(3000), None);
+        let phrases: Vec<&Vec<u8>> = harmonizer.phrases().collect();
+        assert_eq!(phrases, [&vec![60, 62, 64], &vec![65]]);
+    }
+
+    #[test]
+    fn test_phrases_by_bar() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut harmonizer = MelodyHarmonizer::default();
+        // A bar of 4/4 at 120 bpm is two seconds
+        for (at, note) in [(0, 60), (500, 64), (1500, 67), (2000, 65), (2500, 62)] {
+            harmonizer.note(note, ms(at), Some(Duration::from_secs(2)));
+        }
+        let phrases: Vec<&Vec<u8>> = harmonizer.phrases().collect();
+        assert_eq!(phrases, [&vec![60, 64, 67], &vec![65, 62]]);
+    }
+
+    #[test]
+    fn test_harmonize() {
+        let c = Scale::major(Note::new(60));
+        let best = |melody: &[u8]| harmonize(melody, Some(c))[0].0.name();
+        assert_eq!(best(&[60, 64, 67]), "C");
+        assert_eq!(best(&[65, 69, 72, 69]), "F");
+        assert_eq!(best(&[67, 71, 74, 65]), "G7");
+
+        let options = harmonize(&[60, 64, 67], Some(c));
+        assert_eq!(options.len(), OPTIONS);
+        assert_eq!(options[0].1, 1.0);
+        assert!(harmonize(&[], Some(c)).is_empty());
+    }
+
+    #[test]
+    fn test_melody_key() {
+        let key = melody_key(&[67, 69, 71, 72, 74, 76, 78, 79]).unwrap();
+        assert_eq!(key.name(), "G");
+        let key = melody_key(&[69, 72, 76, 74, 72, 71, 69]).unwrap();
+        assert_eq!(key.name(), "Am");
+        // As much C major as A minor
+        let key = melody_key(&[65, 69, 72, 67, 71, 74]).unwrap();
+        assert_eq!(key.name(), "C");
+        assert!(melody_key(&[]).is_none());
+    }
+}
@@ -0,0 +1,218 @@
+use std::fmt;
+
+use super::chord::Chord;
+use super::note::Note;
+
+/// Quality and scale degree of each simple interval, by semitones
+const SIMPLE: [(&str, u8); 12] = [
+    ("P", 1),
+    ("m", 2),
+    ("M", 2),
+    ("m", 3),
+    ("M", 3),
+    ("P", 4),
+    ("TT", 0),
+    ("P", 5),
+    ("m", 6),
+    ("M", 6),
+    ("m", 7),
+    ("M", 7),
+];
+
+/// The distance from one note to another in semitones, negative when the
+/// second note is lower.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval(pub i8);
+
+impl Interval {
+    pub fn semitones(&self) -> i8 {
+        self.0
+    }
+
+    pub fn is_descending(&self) -> bool {
+        self.0 < 0
+    }
+
+    /// Whole octaves spanned, ignoring direction.
+    pub fn octaves(&self) -> u8 {
+        self.0.unsigned_abs() / 12
+    }
+
+    /// The interval folded into a single octave, 0 to 11 semitones upward.
+    pub fn simple(&self) -> Interval {
+        Interval(self.0.rem_euclid(12))
+    }
+
+    /// What the interval becomes with its lower note moved above the upper
+    /// one: a major third inverts to a minor sixth.
+    pub fn inverted(&self) -> Interval {
+        Interval((12 - self.simple().0) % 12)
+    }
+
+    /// Short name of the interval ignoring direction: `m3`, `P5`, `P8`, `M9`.
+    pub fn name(&self) -> String {
+        let size = self.0.unsigned_abs();
+        let (quality, degree) = SIMPLE[(size % 12) as usize];
+        match (size, degree) {
+            (_, 0) => quality.to_string(),
+            (0, _) => "P1".to_string(),
+            (_, 1) => format!("P{}", 1 + 7 * (size / 12)),
+            _ => format!("{}{}", quality, degree + 7 * (size / 12)),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_descending() {
+            f.write_str("-")?;
+        }
+        f.write_str(&self.name())
+    }
+}
+
+impl Note {
+    /// The note `semitones` away, or None past either end of the MIDI range.
+    pub fn transpose(&self, semitones: i8) -> Option<Note> {
+        u8::try_from(self.midi as i16 + semitones as i16)
+            .ok()
+            .filter(|&midi| midi <= 127)
+            .map(Note::new)
+    }
+
+    /// The note `semitones` away, moved back by octaves if that falls off
+    /// either end of the MIDI range, so the pitch class is always right.
+    pub fn transpose_saturating(&self, semitones: i8) -> Note {
+        let mut midi = self.midi as i16 + semitones as i16;
+        while midi > 127 {
+            midi -= 12;
+        }
+        while midi < 0 {
+            midi += 12;
+        }
+        Note::new(midi as u8)
+    }
+
+    pub fn interval_to(&self, other: Note) -> Interval {
+        Interval(other.midi as i8 - self.midi as i8)
+    }
+}
+
+impl Chord {
+    /// The same chord `semitones` higher, or lower when negative. The root
+    /// and bass keep their pitch classes in step even at the edges of the
+    /// MIDI range.
+    pub fn transpose(&self, semitones: i8) -> Chord {
+        Chord {
+            root: self.root.transpose_saturating(semitones),
+            quality: self.quality,
+            bass: self.bass.map(|bass| bass.transpose_saturating(semitones)),
+        }
+    }
+
+    /// The shape a guitarist fingers for this sounding chord with a capo
+    /// at fret `capo`: a sounding Bb is an A shape with the capo at 1.
+    pub fn capo_shape(&self, capo: u8) -> Chord {
+        self.transpose(-((capo % 12) as i8))
+    }
+}
+
+/// A song moved from the key it was written in to the key it's sung in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transposition {
+    pub from: Note,
+    pub to: Note,
+}
+
+impl Transposition {
+    pub fn new(from: Note, to: Note) -> Self {
+        Self { from, to }
+    }
+
+    /// Semitones from the original key to the sung one, the shorter way:
+    /// up to a tritone up, otherwise down.
+    pub fn semitones(&self) -> i8 {
+        let up = (self.to.pitch_class() + 12 - self.from.pitch_class()) % 12;
+        if up > 6 {
+            up as i8 - 12
+        } else {
+            up as i8
+        }
+    }
+
+    pub fn chord(&self, chord: &Chord) -> Chord {
+        chord.transpose(self.semitones())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_names() {
+        let names: Vec<String> = (0..=12).map(|s| Interval(s).name()).collect();
+        assert_eq!(
+            names,
+            ["P1", "m2", "M2", "m3", "M3", "P4", "TT", "P5", "m6", "M6", "m7", "M7", "P8"]
+        );
+        assert_eq!(Interval(14).name(), "M9");
+        assert_eq!(Interval(17).name(), "P11");
+        assert_eq!(Interval(24).name(), "P15");
+        assert_eq!(Interval(-3).to_string(), "-m3");
+    }
+
+    #[test]
+    fn test_interval_arithmetic() {
+        assert_eq!(Interval(16).simple(), Interval(4));
+        assert_eq!(Interval(-3).simple(), Interval(9));
+        assert_eq!(Interval(4).inverted(), Interval(8));
+        assert_eq!(Interval(0).inverted(), Interval(0));
+        assert_eq!(Interval(-26).octaves(), 2);
+    }
+
+    #[test]
+    fn test_note_transpose() {
+        let c4 = Note::new(60);
+        assert_eq!(c4.transpose(7), Some(Note::new(67)));
+        assert_eq!(c4.transpose(-60), Some(Note::new(0)));
+        assert_eq!(c4.transpose(-61), None);
+        assert_eq!(Note::new(120).transpose(8), None);
+
+        assert_eq!(Note::new(120).transpose_saturating(8), Note::new(116));
+        assert_eq!(Note::new(2).transpose_saturating(-5), Note::new(9));
+        assert_eq!(c4.interval_to(Note::new(55)), Interval(-5));
+        assert_eq!(Note::new(0).interval_to(Note::new(127)), Interval(127));
+    }
+
+    #[test]
+    fn test_chord_transpose() {
+        let chord = Chord::from_name("Am7/G").unwrap();
+        assert_eq!(chord.transpose(2).name(), "Bm7/A");
+        assert_eq!(chord.transpose(-9).name(), "Cm7/A#");
+        assert_eq!(chord.transpose(12).transpose(-12), chord);
+        assert_eq!(chord.transpose(5).quality, chord.quality);
+    }
+
+    #[test]
+    fn test_transposition() {
+        let g_to_bb = Transposition::new(Note::new(67), Note::new(70));
+        assert_eq!(g_to_bb.semitones(), 3);
+        assert_eq!(g_to_bb.chord(&Chord::from_name("D7").unwrap()).name(), "F7");
+
+        let down = Transposition::new(Note::new(60), Note::new(55));
+        assert_eq!(down.semitones(), -5);
+        let tritone = Transposition::new(Note::new(60), Note::new(66));
+        assert_eq!(tritone.semitones(), 6);
+    }
+
+    #[test]
+    fn test_capo_shape() {
+        let bb = Chord::from_name("Bb").unwrap();
+        assert_eq!(bb.capo_shape(1).name(), "A");
+        assert_eq!(bb.capo_shape(3).name(), "G");
+        assert_eq!(bb.capo_shape(0), bb);
+        let slash = Chord::from_name("F#m/C#").unwrap();
+        assert_eq!(slash.capo_shape(2).name(), "Em/B");
+    }
+}
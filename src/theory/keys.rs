@@ -0,0 +1,109 @@
+use super::chord::Chord;
+use super::note::Note;
+use super::scale::{Scale, ScaleKind};
+
+/// Extra weight for a key whose tonic chord opens or closes the chords
+const TONIC_BONUS: f32 = 0.5;
+/// How sharply the better fitting keys take the share; higher favours the
+/// best one more
+const SHARPNESS: f32 = 2.0;
+
+/// A key the chords could be in, its share of the likelihood across all
+/// keys, and which of the chords belong to it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyCandidate {
+    pub scale: Scale,
+    pub share: f32,
+    pub diatonic: Vec<bool>,
+}
+
+impl KeyCandidate {
+    pub fn percent(&self) -> u32 {
+        (self.share * 100.0).round() as u32
+    }
+}
+
+/// Every major and minor key ranked by how well it fits the chords, most
+/// likely first. Keys gain for each chord made of their notes and for a
+/// tonic chord at either end.
+pub fn rank_keys(chords: &[Chord]) -> Vec<KeyCandidate> {
+    if chords.is_empty() {
+        return Vec::new();
+    }
+    let is_tonic = |scale: &Scale, chord: Option<&Chord>| {
+        chord.is_some_and(|c| {
+            let third = match scale.kind {
+                ScaleKind::Major | ScaleKind::Blues => 4,
+                ScaleKind::Minor => 3,
+            };
+            c.root.pitch_class() == scale.tonic.pitch_class()
+                && c.quality.intervals().starts_with(&[0, third, 7])
+        })
+    };
+
+    let scored: Vec<(Scale, f32, Vec<bool>)> = [ScaleKind::Major, ScaleKind::Minor]
+        .into_iter()
+        .flat_map(|kind| (0..12u8).map(move |pc| Scale::new(Note::new(60 + pc), kind)))
+        .map(|scale| {
+            let diatonic: Vec<bool> = chords.iter().map(|c| scale.contains_chord(c)).collect();
+            let fits = diatonic.iter().filter(|&&d| d).count() as f32;
+            let ends = [chords.first(), chords.last()]
+                .into_iter()
+                .filter(|&c| is_tonic(&scale, c))
+                .count() as f32;
+            (scale, fits + ends * TONIC_BONUS, diatonic)
+        })
+        .collect();
+
+    let best = scored.iter().map(|(_, s, _)| *s).fold(0.0, f32::max);
+    let weights: Vec<f32> = scored
+        .iter()
+        .map(|(_, score, _)| (SHARPNESS * (score - best)).exp())
+        .collect();
+    let total: f32 = weights.iter().sum();
+
+    let mut candidates: Vec<KeyCandidate> = scored
+        .into_iter()
+        .zip(weights)
+        .map(|((scale, _, diatonic), weight)| KeyCandidate {
+            scale,
+            share: weight / total,
+            diatonic,
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.share.total_cmp(&a.share));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chords(names: &[&str]) -> Vec<Chord> {
+        names.iter().map(|n| Chord::from_name(n).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_rank_keys() {
+        let ranked = rank_keys(&chords(&["C", "Am", "F", "G"]));
+        assert_eq!(ranked.len(), 24);
+        assert_eq!(ranked[0].scale.name(), "C");
+        assert_eq!(ranked[1].scale.name(), "Am");
+        assert!(ranked[0].percent() > 50);
+        assert_eq!(ranked[0].diatonic, [true; 4]);
+        let total: f32 = ranked.iter().map(|k| k.share).sum();
+        assert!((total - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_minor_tonic_wins() {
+        let ranked = rank_keys(&chords(&["Am", "Dm", "E7", "Am"]));
+        assert_eq!(ranked[0].scale.name(), "Am");
+        assert_eq!(ranked[0].diatonic, [true, true, false, true]);
+    }
+
+    #[test]
+    fn test_no_chords() {
+        assert!(rank_keys(&[]).is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use super::Onsets;
+
+/// A top note struck this long after the notes under it is played over
+/// them rather than with them
+const CHORD_SPREAD: Duration = Duration::from_millis(80);
+/// Held longer than this, a top note has become part of the harmony
+const MELODY_HOLD: Duration = Duration::from_millis(800);
+/// Semitones a melody note sits clear of the notes under it
+const MIN_GAP: u8 = 3;
+/// Notes that have to stay under a melody note for there to be a chord
+const MIN_HARMONY: usize = 2;
+
+/// The held note being played as melody over the chord, so a lone top
+/// note can be left out of detection: the top voice, clear of the chord
+/// under it, struck after it and not yet held long.
+pub fn melody(held: &HashSet<u8>, onsets: &Onsets, now: Instant) -> Option<u8> {
+    let mut notes: Vec<u8> = held.iter().copied().collect();
+    notes.sort_unstable();
+    let (&top, rest) = notes.split_last()?;
+    let &below = rest.last()?;
+    if rest.len() < MIN_HARMONY || top - below < MIN_GAP {
+        return None;
+    }
+
+    let top_onset = onsets.of(top, now);
+    let harmony_onset = rest.iter().map(|&note| onsets.of(note, now)).max()?;
+    let struck_after = top_onset.saturating_duration_since(harmony_onset) > CHORD_SPREAD;
+    let short = now.saturating_duration_since(top_onset) < MELODY_HOLD;
+    (struck_after && short).then_some(top)
+}
+
+/// The held notes without the melody.
+pub fn harmony(held: &HashSet<u8>, onsets: &Onsets, now: Instant) -> HashSet<u8> {
+    let mut harmony = held.clone();
+    if let Some(melody) = melody(held, onsets, now) {
+        harmony.remove(&melody);
+    }
+    harmony
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes(notes: &[u8]) -> HashSet<u8> {
+        notes.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_melody_over_chord() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut onsets = Onsets::default();
+        onsets.update(&notes(&[48, 52, 55]), ms(0));
+        let held = notes(&[48, 52, 55, 74]);
+        onsets.update(&held, ms(500));
+
+        assert_eq!(melody(&held, &onsets, ms(600)), Some(74));
+        assert_eq!(harmony(&held, &onsets, ms(600)), notes(&[48, 52, 55]));
+        // Held on, it joins the chord
+        assert_eq!(melody(&held, &onsets, ms(1400)), None);
+    }
+
+    #[test]
+    fn test_chord_tones_stay_harmony() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut onsets = Onsets::default();
+
+        // Struck together with the chord
+        let held = notes(&[48, 52, 55, 72]);
+        onsets.update(&held, ms(0));
+        assert_eq!(melody(&held, &onsets, ms(100)), None);
+
+        // Too close to the note under it
+        let mut onsets = Onsets::default();
+        onsets.update(&notes(&[48, 52, 55]), ms(0));
+        let held = notes(&[48, 52, 55, 57]);
+        onsets.update(&held, ms(300));
+        assert_eq!(melody(&held, &onsets, ms(400)), None);
+
+        // Nothing left to be a chord under it
+        let mut onsets = Onsets::default();
+        onsets.update(&notes(&[48]), ms(0));
+        let held = notes(&[48, 67]);
+        onsets.update(&held, ms(300));
+        assert_eq!(melody(&held, &onsets, ms(400)), None);
+    }
+}
@@ -1,9 +1,68 @@
+pub mod adaptive;
+pub mod bass;
+pub mod blues;
+pub mod brush;
 pub mod chord;
+pub mod detection;
+pub mod distance;
+pub mod follow;
+pub mod function;
+pub mod harmonize;
+pub mod interval;
+pub mod keys;
+pub mod melody;
+pub mod model;
+pub mod modulation;
+pub mod naming;
 pub mod note;
+pub mod onsets;
+pub mod pedal;
+pub mod pitch;
+pub mod plan;
+pub mod presets;
 pub mod progression;
+#[cfg(test)]
+mod properties;
 pub mod quality;
+pub mod reharm;
+pub mod scale;
+#[cfg(feature = "scripting")]
+pub mod script;
+pub mod similar;
+pub mod smoothing;
+pub mod spelling;
+pub mod tension;
+pub mod voice_leading;
+pub mod voicing;
 
-pub use chord::Chord;
+pub use adaptive::AdaptiveModel;
+pub use bass::BassStyle;
+pub use blues::BluesForm;
+pub use chord::{Chord, ToneRole};
+pub use detection::{Confidence, Detection, Preferences, SixthPreference};
+pub use follow::{FollowStats, Followed};
+pub use function::Function;
+pub use harmonize::MelodyHarmonizer;
+pub use interval::{Interval, Transposition};
+pub use keys::KeyCandidate;
+pub use model::{ProgressionModel, Transition};
+pub use modulation::{KeyAnalysis, KeySegment, Modulation};
+pub use naming::NamingStyle;
 pub use note::Note;
-pub use progression::{ProgressionNode, ProgressionTree};
-pub use quality::Quality;
+pub use onsets::Onsets;
+pub use pedal::PedalTracker;
+pub use pitch::Pitch;
+pub use plan::{Branch, Plan};
+pub use presets::{Preset, PresetCategory};
+pub use progression::{ProgressionNode, ProgressionTree, Suggester};
+pub use quality::{Quality, QualityFamily};
+pub use reharm::{Reharmonization, Technique};
+pub use scale::{Scale, ScaleKind};
+#[cfg(feature = "scripting")]
+pub use script::ScriptSuggester;
+pub use similar::{Relation, Similar};
+pub use smoothing::ChordSmoother;
+pub use spelling::{NoteNames, Spelling};
+pub use tension::Tension;
+pub use voice_leading::{Parallel, ParallelKind};
+pub use voicing::{Shape, Voicing, VoicingOption, VoicingOptions};
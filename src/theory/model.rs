@@ -0,0 +1,375 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::chord::Chord;
+use super::note::Note;
+
+pub const FALLBACK: &str = "*";
+/// Chords before the current one a transition can be conditioned on, so
+/// "ii V" and "vi ii V" are looked up ahead of "V"
+pub const MAX_CONTEXT: usize = 2;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Transition {
+    pub to: String,
+    pub weight: f32,
+}
+
+impl Transition {
+    pub fn new(to: &str, weight: f32) -> Self {
+        Self {
+            to: to.to_string(),
+            weight,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProgressionModel {
+    #[serde(default)]
+    pub name: String,
+    pub transitions: BTreeMap<String, Vec<Transition>>,
+}
+
+impl Default for ProgressionModel {
+    fn default() -> Self {
+        Self::from_table(
+            "default",
+            &[
+                ("I", &[("IV", 0.6), ("vi", 0.4)]),
+                ("ii", &[("V", 0.6), ("IV", 0.4)]),
+                ("iii", &[("vi", 0.6), ("IV", 0.4)]),
+                ("IV", &[("V", 0.6), ("I", 0.4)]),
+                ("V", &[("I", 0.6), ("vi", 0.4)]),
+                ("vi", &[("ii", 0.6), ("IV", 0.4)]),
+                ("vii°", &[("I", 0.6), ("iii", 0.4)]),
+                ("ii V", &[("I", 0.9), ("vi", 0.1)]),
+                ("IV V", &[("I", 0.7), ("vi", 0.3)]),
+                ("I V", &[("vi", 0.6), ("IV", 0.4)]),
+                ("V vi", &[("IV", 0.7), ("ii", 0.3)]),
+                ("vi IV", &[("I", 0.6), ("V", 0.4)]),
+                ("vi ii", &[("V", 0.9), ("IV", 0.1)]),
+                ("iii vi", &[("ii", 0.7), ("IV", 0.3)]),
+                ("I vi IV", &[("V", 0.8), ("I", 0.2)]),
+                ("vi ii V", &[("I", 0.9), ("iii", 0.1)]),
+                (FALLBACK, &[("V", 0.6), ("I", 0.4)]),
+            ],
+        )
+    }
+}
+
+impl ProgressionModel {
+    /// Common moves in a minor key, numerals relative to the minor tonic.
+    pub fn minor() -> Self {
+        Self::from_table(
+            "minor",
+            &[
+                ("i", &[("iv", 0.6), ("bVI", 0.4)]),
+                ("ii°", &[("V", 0.6), ("i", 0.4)]),
+                ("bIII", &[("bVI", 0.6), ("iv", 0.4)]),
+                ("iv", &[("V", 0.6), ("i", 0.4)]),
+                ("v", &[("bVI", 0.6), ("i", 0.4)]),
+                ("V", &[("i", 0.6), ("bVI", 0.4)]),
+                ("bVI", &[("bVII", 0.6), ("iv", 0.4)]),
+                ("bVII", &[("bIII", 0.6), ("i", 0.4)]),
+                ("ii° V", &[("i", 0.9), ("bVI", 0.1)]),
+                ("iv V", &[("i", 0.8), ("bVI", 0.2)]),
+                ("i bVII", &[("bVI", 0.6), ("bIII", 0.4)]),
+                ("i bVII bVI", &[("V", 0.7), ("bVII", 0.3)]),
+                (FALLBACK, &[("V", 0.6), ("i", 0.4)]),
+            ],
+        )
+    }
+
+    /// Moves around a 12-bar blues, every chord on I, IV and V a dominant
+    /// seventh: the quick change back from IV and the I–VI–ii–V turnaround.
+    pub fn blues() -> Self {
+        Self::from_table(
+            "blues",
+            &[
+                ("I", &[("IV7", 0.6), ("V7", 0.4)]),
+                ("IV", &[("I7", 0.7), ("V7", 0.3)]),
+                ("V", &[("IV7", 0.6), ("I7", 0.4)]),
+                ("ii", &[("V7", 0.8), ("I7", 0.2)]),
+                ("VI", &[("ii7", 0.8), ("V7", 0.2)]),
+                ("I IV", &[("I7", 0.6), ("IV7", 0.4)]),
+                ("V IV", &[("I7", 0.7), ("V7", 0.3)]),
+                ("I VI", &[("ii7", 0.9), ("V7", 0.1)]),
+                ("VI ii", &[("V7", 0.9), ("I7", 0.1)]),
+                (FALLBACK, &[("I7", 0.6), ("IV7", 0.4)]),
+            ],
+        )
+    }
+
+    fn from_table(name: &str, table: &[(&str, &[(&str, f32)])]) -> Self {
+        let transitions = table
+            .iter()
+            .map(|(from, to)| {
+                let to = to.iter().map(|&(n, w)| Transition::new(n, w)).collect();
+                (from.to_string(), to)
+            })
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            transitions,
+        }
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        let model: Self = serde_json::from_str(text)?;
+        model.validate()?;
+        Ok(model)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let c = Note::new(60);
+
+        for (from, transitions) in &self.transitions {
+            if from != FALLBACK {
+                let numerals: Vec<&str> = from.split_whitespace().collect();
+                if numerals.is_empty() || numerals.len() > MAX_CONTEXT + 1 {
+                    return Err(anyhow!(
+                        "'{}' should be the current chord, after at most {} before it",
+                        from,
+                        MAX_CONTEXT
+                    ));
+                }
+                if let Some(bad) = numerals.iter().find(|n| Chord::from_roman(n, c).is_none()) {
+                    return Err(anyhow!("Invalid Roman numeral '{}'", bad));
+                }
+            }
+            for t in transitions {
+                if Chord::from_roman(&t.to, c).is_none() {
+                    return Err(anyhow!(
+                        "Invalid Roman numeral '{}' (from '{}')",
+                        t.to,
+                        from
+                    ));
+                }
+                if !t.weight.is_finite() || t.weight < 0.0 {
+                    return Err(anyhow!(
+                        "Invalid weight {} for '{}' -> '{}'",
+                        t.weight,
+                        from,
+                        t.to
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn transitions_for(&self, current: &Chord, key: Note) -> &[Transition] {
+        let numeral = current.roman_numeral(key);
+        if let Some(exact) = self.transitions.get(&numeral) {
+            return exact;
+        }
+
+        let by_degree = self
+            .transitions
+            .iter()
+            .find(|(from, _)| !from.contains(' ') && on_degree(from, current, key));
+
+        by_degree
+            .map(|(_, t)| t.as_slice())
+            .or_else(|| self.transitions.get(FALLBACK).map(Vec::as_slice))
+            .unwrap_or(&[])
+    }
+
+    /// Transitions after `current` played following `context`, oldest
+    /// first. The longest run of numerals ending in the current chord
+    /// that the model has, such as "ii V", wins; with no context or none
+    /// that matches, it's the current chord alone.
+    pub fn transitions_in_context(
+        &self,
+        context: &[Chord],
+        current: &Chord,
+        key: Note,
+    ) -> &[Transition] {
+        let context = &context[context.len().saturating_sub(MAX_CONTEXT)..];
+        let played: Vec<&Chord> = context.iter().chain([current]).collect();
+
+        for length in (2..=played.len()).rev() {
+            let tail = &played[played.len() - length..];
+            let found = self.transitions.iter().find(|(from, _)| {
+                let numerals: Vec<&str> = from.split_whitespace().collect();
+                numerals.len() == length
+                    && numerals
+                        .iter()
+                        .zip(tail)
+                        .all(|(numeral, chord)| on_degree(numeral, chord, key))
+            });
+            if let Some((_, transitions)) = found {
+                return transitions;
+            }
+        }
+        self.transitions_for(current, key)
+    }
+
+    pub fn candidates(&self, current: &Chord, key: Note) -> Vec<(Chord, f32)> {
+        self.candidates_in_context(&[], current, key)
+    }
+
+    pub fn candidates_in_context(
+        &self,
+        context: &[Chord],
+        current: &Chord,
+        key: Note,
+    ) -> Vec<(Chord, f32)> {
+        let mut candidates: Vec<(Chord, f32)> = self
+            .transitions_in_context(context, current, key)
+            .iter()
+            .filter_map(|t| Chord::from_roman(&t.to, key).map(|c| (c, t.weight)))
+            .collect();
+
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates
+    }
+}
+
+/// Whether `chord` is the numeral, or at least sits on its degree.
+fn on_degree(numeral: &str, chord: &Chord, key: Note) -> bool {
+    numeral == chord.roman_numeral(key)
+        || Chord::from_roman(numeral, key)
+            .is_some_and(|c| c.root.pitch_class() == chord.root.pitch_class())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::Quality;
+
+    #[test]
+    fn test_default_candidates() {
+        let model = ProgressionModel::default();
+        let key = Note::new(60);
+
+        let c = Chord::new(Note::new(60), Quality::Major);
+        let names: Vec<String> = model
+            .candidates(&c, key)
+            .iter()
+            .map(|(c, _)| c.name())
+            .collect();
+        assert_eq!(names, vec!["F", "Am"]);
+    }
+
+    #[test]
+    fn test_degree_and_fallback_lookup() {
+        let model = ProgressionModel::default();
+        let key = Note::new(60);
+
+        let g7 = Chord::new(Note::new(67), Quality::Dominant7);
+        assert_eq!(model.candidates(&g7, key)[0].0.name(), "C");
+
+        let eb = Chord::new(Note::new(63), Quality::Major);
+        assert_eq!(model.candidates(&eb, key)[0].0.name(), "G");
+    }
+
+    #[test]
+    fn test_context() {
+        let model = ProgressionModel::default();
+        let key = Note::new(60);
+        let chords = |names: &[&str]| -> Vec<Chord> {
+            names.iter().map(|n| Chord::from_name(n).unwrap()).collect()
+        };
+        let after = |context: &[&str], current: &str| -> Vec<(String, f32)> {
+            let current = Chord::from_name(current).unwrap();
+            model
+                .candidates_in_context(&chords(context), &current, key)
+                .into_iter()
+                .map(|(c, w)| (c.name(), w))
+                .collect()
+        };
+
+        // After ii–V the tonic is all but certain
+        assert_eq!(after(&["Dm7"], "G7")[0], ("C".to_string(), 0.9));
+        assert_eq!(after(&["Am", "Dm7"], "G7")[0], ("C".to_string(), 0.9));
+        assert_eq!(after(&["C", "Am", "F"], "G")[0], ("C".to_string(), 0.7));
+        assert_eq!(after(&["C", "Am"], "F")[0], ("G".to_string(), 0.8));
+
+        // Short or unknown context falls back to the chord alone
+        assert_eq!(after(&[], "G7"), after(&["Eb"], "G7"));
+        assert_eq!(after(&[], "G7")[0], ("C".to_string(), 0.6));
+    }
+
+    #[test]
+    fn test_blues() {
+        let model = ProgressionModel::blues();
+        model.validate().unwrap();
+        let key = Note::new(67);
+        let after = |context: &[&str], current: &str| -> String {
+            let context: Vec<Chord> = context
+                .iter()
+                .map(|n| Chord::from_name(n).unwrap())
+                .collect();
+            let current = Chord::from_name(current).unwrap();
+            model.candidates_in_context(&context, &current, key)[0]
+                .0
+                .name()
+        };
+
+        assert_eq!(after(&[], "G7"), "C7");
+        assert_eq!(after(&["D7"], "C7"), "G7");
+        assert_eq!(after(&["G7", "E7"], "Am7"), "D7");
+        assert_eq!(after(&[], "Bb"), "G7");
+    }
+
+    #[test]
+    fn test_invalid_context() {
+        let json = r#"{ "transitions": { "ii V Q": [{ "to": "I", "weight": 1.0 }] } }"#;
+        assert!(ProgressionModel::from_json(json).is_err());
+        let json = r#"{ "transitions": { "iii vi ii V": [{ "to": "I", "weight": 1.0 }] } }"#;
+        assert!(ProgressionModel::from_json(json).is_err());
+        let json = r#"{ "transitions": { "bVI bVII": [{ "to": "I", "weight": 1.0 }] } }"#;
+        assert!(ProgressionModel::from_json(json).is_ok());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let model = ProgressionModel::default();
+        let json = model.to_json().unwrap();
+        assert_eq!(ProgressionModel::from_json(&json).unwrap(), model);
+    }
+
+    #[test]
+    fn test_custom_weights() {
+        let json = r#"{ "transitions": { "I": [
+            { "to": "IV", "weight": 0.1 },
+            { "to": "bVII", "weight": 0.9 }
+        ] } }"#;
+        let model = ProgressionModel::from_json(json).unwrap();
+
+        let c = Chord::new(Note::new(60), Quality::Major);
+        let candidates = model.candidates(&c, Note::new(60));
+        assert_eq!(candidates[0].0.name(), "A#");
+        assert_eq!(candidates[1].0.name(), "F");
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let json = r#"{ "transitions": { "I": [{ "to": "Q", "weight": 1.0 }] } }"#;
+        assert!(ProgressionModel::from_json(json).is_err());
+
+        let json = r#"{ "transitions": { "I": [{ "to": "V", "weight": -1.0 }] } }"#;
+        assert!(ProgressionModel::from_json(json).is_err());
+    }
+}
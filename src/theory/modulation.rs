@@ -0,0 +1,155 @@
+use super::chord::Chord;
+use super::note::Note;
+use super::scale::Scale;
+
+const MAJOR_PROFILE: [f32; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+
+const HYSTERESIS: f32 = 0.92;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeySegment {
+    pub scale: Scale,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Modulation {
+    pub index: usize,
+    pub from: Scale,
+    pub to: Scale,
+}
+
+impl Modulation {
+    pub fn label(&self) -> String {
+        format!("modulates {}→{}", self.from.name(), self.to.name())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyAnalysis {
+    pub segments: Vec<KeySegment>,
+}
+
+impl KeyAnalysis {
+    pub fn analyze(chords: &[Chord], window: usize) -> Self {
+        let n = chords.len();
+        let window = window.max(1);
+        let mut keys: Vec<u8> = Vec::with_capacity(n);
+        let mut current: Option<u8> = None;
+
+        for i in 0..n {
+            let start = i.min(n.saturating_sub(window));
+            let scores = key_scores(&chords[start..(start + window).min(n)]);
+
+            let best = (0..12u8)
+                .max_by(|&a, &b| scores[a as usize].total_cmp(&scores[b as usize]))
+                .unwrap_or(0);
+            let key = current
+                .filter(|&k| scores[k as usize] >= scores[best as usize] * HYSTERESIS)
+                .unwrap_or(best);
+
+            current = Some(key);
+            keys.push(key);
+        }
+
+        let mut segments: Vec<KeySegment> = Vec::new();
+        for (i, &key) in keys.iter().enumerate() {
+            let scale = Scale::major(Note::new(60 + key));
+            match segments.last_mut() {
+                Some(last) if last.scale == scale => last.end = i + 1,
+                _ => segments.push(KeySegment {
+                    scale,
+                    start: i,
+                    end: i + 1,
+                }),
+            }
+        }
+
+        Self { segments }
+    }
+
+    pub fn key_at(&self, index: usize) -> Option<Scale> {
+        self.segments
+            .iter()
+            .find(|s| (s.start..s.end).contains(&index))
+            .map(|s| s.scale)
+    }
+
+    pub fn modulations(&self) -> Vec<Modulation> {
+        self.segments
+            .windows(2)
+            .map(|pair| Modulation {
+                index: pair[1].start,
+                from: pair[0].scale,
+                to: pair[1].scale,
+            })
+            .collect()
+    }
+
+    pub fn numerals(&self, chords: &[Chord]) -> Vec<String> {
+        chords
+            .iter()
+            .enumerate()
+            .map(|(i, chord)| match self.key_at(i) {
+                Some(scale) => chord.roman_numeral(scale.tonic),
+                None => "?".to_string(),
+            })
+            .collect()
+    }
+}
+
+fn key_scores(chords: &[Chord]) -> [f32; 12] {
+    let mut scores = [0.0; 12];
+    for (tonic, score) in scores.iter_mut().enumerate() {
+        for chord in chords {
+            for &interval in chord.quality.intervals() {
+                let pc = (chord.root.pitch_class() + interval) as usize % 12;
+                *score += MAJOR_PROFILE[(pc + 12 - tonic) % 12];
+            }
+        }
+    }
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chords(names: &[&str]) -> Vec<Chord> {
+        names.iter().map(|n| Chord::from_name(n).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_single_key() {
+        let analysis = KeyAnalysis::analyze(&chords(&["C", "F", "G", "C", "Am", "Dm", "G"]), 4);
+        assert_eq!(analysis.segments.len(), 1);
+        assert_eq!(analysis.segments[0].scale.name(), "C");
+        assert!(analysis.modulations().is_empty());
+    }
+
+    #[test]
+    fn test_modulation_up_minor_third() {
+        let progression = chords(&["C", "F", "G", "C", "Eb", "Ab", "Bb", "Eb"]);
+        let analysis = KeyAnalysis::analyze(&progression, 4);
+
+        let modulations = analysis.modulations();
+        assert_eq!(modulations.len(), 1);
+        assert_eq!(modulations[0].label(), "modulates C→Eb");
+        assert!((3..=5).contains(&modulations[0].index));
+
+        let numerals = analysis.numerals(&progression);
+        assert_eq!(numerals[0], "I");
+        assert_eq!(numerals[7], "I");
+        assert_eq!(numerals[6], "V");
+    }
+
+    #[test]
+    fn test_empty() {
+        let analysis = KeyAnalysis::analyze(&[], 4);
+        assert!(analysis.segments.is_empty());
+        assert_eq!(analysis.key_at(0), None);
+    }
+}
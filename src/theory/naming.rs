@@ -0,0 +1,70 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NamingStyle {
+    #[default]
+    Plain,
+    Jazz,
+    Short,
+    Unicode,
+}
+
+impl NamingStyle {
+    pub fn all() -> &'static [NamingStyle] {
+        &[
+            NamingStyle::Plain,
+            NamingStyle::Jazz,
+            NamingStyle::Short,
+            NamingStyle::Unicode,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NamingStyle::Plain => "plain",
+            NamingStyle::Jazz => "jazz",
+            NamingStyle::Short => "short",
+            NamingStyle::Unicode => "unicode",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::all()
+            .iter()
+            .copied()
+            .find(|style| style.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            NamingStyle::Plain => NamingStyle::Jazz,
+            NamingStyle::Jazz => NamingStyle::Short,
+            NamingStyle::Short => NamingStyle::Unicode,
+            NamingStyle::Unicode => NamingStyle::Plain,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(NamingStyle::from_name("plain"), Some(NamingStyle::Plain));
+        assert_eq!(NamingStyle::from_name("Jazz"), Some(NamingStyle::Jazz));
+        assert_eq!(
+            NamingStyle::from_name(" unicode "),
+            Some(NamingStyle::Unicode)
+        );
+        assert_eq!(NamingStyle::from_name("fancy"), None);
+    }
+
+    #[test]
+    fn test_next_cycles_all() {
+        let mut style = NamingStyle::default();
+        for _ in 0..NamingStyle::all().len() {
+            style = style.next();
+        }
+        assert_eq!(style, NamingStyle::Plain);
+    }
+}
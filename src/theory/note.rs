@@ -1,6 +1,9 @@
-const NOTE_NAMES: [&str; 12] = [
-    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
-];
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+use super::spelling::{parse_pitch, NoteNames, Spelling};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Note {
@@ -13,7 +16,15 @@ impl Note {
     }
 
     pub fn name(&self) -> &'static str {
-        NOTE_NAMES[self.pitch_class() as usize]
+        self.name_spelled(Spelling::Sharp)
+    }
+
+    pub fn name_spelled(&self, spelling: Spelling) -> &'static str {
+        spelling.names()[self.pitch_class() as usize]
+    }
+
+    pub fn name_in(&self, spelling: Spelling, names: NoteNames) -> &'static str {
+        names.names(spelling)[self.pitch_class() as usize]
     }
 
     pub fn octave(&self) -> i8 {
@@ -21,7 +32,15 @@ impl Note {
     }
 
     pub fn display(&self) -> String {
-        format!("{}{}", self.name(), self.octave())
+        self.to_string()
+    }
+
+    pub fn display_spelled(&self, spelling: Spelling) -> String {
+        format!("{}{}", self.name_spelled(spelling), self.octave())
+    }
+
+    pub fn display_in(&self, spelling: Spelling, names: NoteNames) -> String {
+        format!("{}{}", self.name_in(spelling, names), self.octave())
     }
 
     pub fn pitch_class(&self) -> u8 {
@@ -29,22 +48,45 @@ impl Note {
     }
 
     pub fn from_name(name: &str) -> Option<Self> {
-        let name = name.trim();
-        if name.is_empty() {
+        let (semitone, _, octave_part) = parse_pitch(name.trim())?;
+        let octave: i16 = octave_part.parse().ok()?;
+        let midi = (octave + 1) * 12 + semitone as i16;
+
+        u8::try_from(midi).ok().filter(|&m| m <= 127).map(Self::new)
+    }
+
+    pub fn from_pitch_name(name: &str) -> Option<Self> {
+        let (semitone, _, rest) = parse_pitch(name.trim())?;
+        if !rest.is_empty() {
             return None;
         }
+        Some(Self::new(semitone.rem_euclid(12) as u8 + 60))
+    }
+}
+
+/// Parses `C4`, `Bb-1` or `F##3`; a name without an octave is taken to be
+/// in octave 4.
+impl FromStr for Note {
+    type Err = anyhow::Error;
 
-        let (note_part, octave_part) = if name.len() >= 2 && name.chars().nth(1) == Some('#') {
-            (&name[..2], &name[2..])
-        } else {
-            (&name[..1], &name[1..])
-        };
+    fn from_str(name: &str) -> Result<Self> {
+        Self::from_name(name)
+            .or_else(|| Self::from_pitch_name(name))
+            .ok_or_else(|| anyhow!("Invalid note name '{}'", name.trim()))
+    }
+}
 
-        let pitch_class = NOTE_NAMES.iter().position(|&n| n == note_part)? as u8;
-        let octave: i8 = octave_part.parse().ok()?;
-        let midi = ((octave + 1) * 12) as u8 + pitch_class;
+impl TryFrom<&str> for Note {
+    type Error = anyhow::Error;
 
-        Some(Self { midi })
+    fn try_from(name: &str) -> Result<Self> {
+        name.parse()
+    }
+}
+
+impl fmt::Display for Note {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display_spelled(Spelling::Sharp))
     }
 }
 
@@ -93,10 +135,61 @@ mod tests {
         assert_eq!(Note::new(61).display(), "C#4");
     }
 
+    #[test]
+    fn test_from_str_and_display() {
+        for midi in 0..=127 {
+            let note = Note::new(midi);
+            assert_eq!(note.to_string().parse::<Note>().unwrap(), note);
+        }
+        assert_eq!("Eb2".parse::<Note>().unwrap(), Note::new(39));
+        assert_eq!("G".parse::<Note>().unwrap(), Note::new(67));
+        assert_eq!(Note::try_from("A0").unwrap(), Note::new(21));
+        assert!("H2".parse::<Note>().is_err());
+        assert!("C10".parse::<Note>().is_err());
+        assert_eq!(format!("{:>4}|", Note::new(61).to_string()), " C#4|");
+    }
+
     #[test]
     fn test_pitch_class() {
         assert_eq!(Note::new(60).pitch_class(), 0); // C
         assert_eq!(Note::new(72).pitch_class(), 0); // C (octave up)
         assert_eq!(Note::new(69).pitch_class(), 9); // A
     }
+
+    #[test]
+    fn test_from_name_flats_and_case() {
+        assert_eq!(Note::from_name("Bb3"), Some(Note::new(58)));
+        assert_eq!(Note::from_name("eb4"), Some(Note::new(63)));
+        assert_eq!(Note::from_name("Cb4"), Some(Note::new(59)));
+        assert_eq!(Note::from_name("B#3"), Some(Note::new(60)));
+        assert_eq!(Note::from_name("F##4"), Some(Note::new(67)));
+        assert_eq!(Note::from_name("Dbb4"), Some(Note::new(60)));
+        assert_eq!(Note::from_name("C-1"), Some(Note::new(0)));
+        assert_eq!(Note::from_name("G9"), Some(Note::new(127)));
+        assert_eq!(Note::from_name("A9"), None);
+        assert_eq!(Note::from_name("Cb-1"), None);
+    }
+
+    #[test]
+    fn test_from_pitch_name() {
+        assert_eq!(
+            Note::from_pitch_name("Bb").map(|n| n.pitch_class()),
+            Some(10)
+        );
+        assert_eq!(
+            Note::from_pitch_name("cb").map(|n| n.pitch_class()),
+            Some(11)
+        );
+        assert_eq!(Note::from_pitch_name("Bb4"), None);
+    }
+
+    #[test]
+    fn test_spelled_round_trip() {
+        for spelling in [Spelling::Sharp, Spelling::Flat] {
+            for midi in 0..=127u8 {
+                let note = Note::new(midi);
+                assert_eq!(Note::from_name(&note.display_spelled(spelling)), Some(note));
+            }
+        }
+    }
 }
@@ -0,0 +1,46 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// When each held key went down, for telling notes struck together from
+/// ones struck after. A released key is forgotten, so striking it again
+/// starts over.
+#[derive(Clone, Debug, Default)]
+pub struct Onsets(HashMap<u8, Instant>);
+
+impl Onsets {
+    /// Notes onsets as keys go down and forgets released ones.
+    pub fn update(&mut self, held: &HashSet<u8>, now: Instant) {
+        self.0.retain(|note, _| held.contains(note));
+        for &note in held {
+            self.0.entry(note).or_insert(now);
+        }
+    }
+
+    /// When `note` went down, or `now` if it hasn't been seen yet.
+    pub fn of(&self, note: u8, now: Instant) -> Instant {
+        self.0.get(&note).copied().unwrap_or(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_onsets() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut onsets = Onsets::default();
+        onsets.update(&[60, 64].into(), ms(0));
+        onsets.update(&[60, 64, 67].into(), ms(100));
+        assert_eq!(onsets.of(60, ms(200)), ms(0));
+        assert_eq!(onsets.of(67, ms(200)), ms(100));
+        assert_eq!(onsets.of(72, ms(200)), ms(200));
+
+        // Struck again after letting go
+        onsets.update(&[64, 67].into(), ms(300));
+        onsets.update(&[60, 64, 67].into(), ms(400));
+        assert_eq!(onsets.of(60, ms(500)), ms(400));
+    }
+}
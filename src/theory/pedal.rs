@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use super::{Chord, Confidence, Detection, Note, Preferences};
+
+/// A bass let go and struck again within this long is still the same pedal,
+/// so a repeated ostinato bass holds it as well as a sustained one
+const RESTRIKE: Duration = Duration::from_millis(1000);
+/// Times the upper structure has to change over the bass for it to be a pedal
+const MIN_CHANGES: u32 = 1;
+/// Distinct pitch classes above the bass that make an upper structure
+const MIN_UPPER: usize = 2;
+
+/// Follows a bass note held or repeated while the chords above it change,
+/// so the upper structure can be named over it: F/C and G/C over a C pedal.
+#[derive(Clone, Debug, Default)]
+pub struct PedalTracker {
+    bass: Option<u8>,
+    released: Option<Instant>,
+    upper: HashSet<u8>,
+    changes: u32,
+}
+
+impl PedalTracker {
+    /// Follows the lowest held note and counts the upper structures played
+    /// over it.
+    pub fn update(&mut self, held: &HashSet<u8>, now: Instant) {
+        let within = self
+            .released
+            .is_none_or(|at| now.saturating_duration_since(at) <= RESTRIKE);
+        let Some(&low) = held.iter().min() else {
+            self.released.get_or_insert(now);
+            return;
+        };
+        match self.bass {
+            Some(bass) if low % 12 == bass % 12 && within => {
+                self.bass = Some(low);
+                self.released = None;
+            }
+            // The bass lifted to be struck again under chords still sounding
+            Some(bass) if low > bass && within => {
+                self.released.get_or_insert(now);
+                return;
+            }
+            _ => {
+                *self = Self {
+                    bass: Some(low),
+                    ..Self::default()
+                };
+            }
+        }
+
+        let upper: HashSet<u8> = held.iter().filter(|&&n| n != low).map(|n| n % 12).collect();
+        if upper.len() < MIN_UPPER || upper == self.upper {
+            return;
+        }
+        if !self.upper.is_empty() {
+            self.changes += 1;
+        }
+        self.upper = upper;
+    }
+
+    /// The pedal note, once chords have changed over it and while it sounds.
+    pub fn pedal(&self) -> Option<u8> {
+        self.bass
+            .filter(|_| self.changes >= MIN_CHANGES && self.released.is_none())
+    }
+
+    /// Reads the held notes as the chord above the pedal over the pedal. A
+    /// chord rooted on the pedal keeps its own reading, as does anything
+    /// that is not a chord without the pedal.
+    pub fn analyze(&self, notes: &HashSet<u8>, prefs: &Preferences) -> Option<Detection> {
+        let full = Chord::analyze(notes, prefs);
+        let Some(pedal) = self.pedal().filter(|p| notes.iter().min() == Some(p)) else {
+            return full;
+        };
+        let on_pedal = |d: &Detection| d.chord.root.pitch_class() == pedal % 12;
+        if full
+            .as_ref()
+            .is_some_and(|d| on_pedal(d) && d.chord.bass.is_none())
+        {
+            return full;
+        }
+
+        let mut upper = notes.clone();
+        upper.remove(&pedal);
+        let Some(Detection { chord, confidence }) = Chord::analyze(&upper, prefs) else {
+            return full;
+        };
+        let chord = Chord {
+            bass: None,
+            ..chord
+        };
+        if chord.root.pitch_class() == pedal % 12 {
+            return Some(Detection { chord, confidence });
+        }
+        Some(Detection {
+            chord: chord.with_bass(Note::new(pedal)),
+            confidence: Confidence {
+                bass: 0.8,
+                ..confidence
+            },
+        })
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes(notes: &[u8]) -> HashSet<u8> {
+        notes.iter().copied().collect()
+    }
+
+    fn name(tracker: &PedalTracker, held: &[u8]) -> Option<String> {
+        tracker
+            .analyze(&notes(held), &Preferences::default())
+            .map(|d| d.chord.name())
+    }
+
+    #[test]
+    fn test_held_pedal() {
+        let start = Instant::now();
+        let mut tracker = PedalTracker::default();
+        tracker.update(&notes(&[36, 64, 67, 72]), start);
+        assert_eq!(tracker.pedal(), None);
+
+        tracker.update(&notes(&[36, 65, 69, 72]), start);
+        assert_eq!(tracker.pedal(), Some(36));
+        assert_eq!(name(&tracker, &[36, 65, 69, 72]).as_deref(), Some("F/C"));
+
+        tracker.update(&notes(&[36, 62, 67, 71]), start);
+        assert_eq!(name(&tracker, &[36, 62, 67, 71]).as_deref(), Some("G/C"));
+
+        // A chord on the pedal itself keeps its own name
+        tracker.update(&notes(&[36, 64, 67, 71]), start);
+        assert_eq!(name(&tracker, &[36, 64, 67, 71]).as_deref(), Some("Cmaj7"));
+    }
+
+    #[test]
+    fn test_ostinato() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut tracker = PedalTracker::default();
+        tracker.update(&notes(&[36, 64, 67]), ms(0));
+        tracker.update(&notes(&[64, 67]), ms(200));
+        assert_eq!(tracker.pedal(), None);
+        tracker.update(&notes(&[48, 65, 69]), ms(400));
+        assert_eq!(tracker.pedal(), Some(48));
+
+        tracker.update(&notes(&[]), ms(600));
+        tracker.update(&notes(&[36, 62, 67, 71]), ms(3000));
+        assert_eq!(tracker.pedal(), None);
+    }
+
+    #[test]
+    fn test_new_bass() {
+        let start = Instant::now();
+        let mut tracker = PedalTracker::default();
+        tracker.update(&notes(&[36, 64, 67]), start);
+        tracker.update(&notes(&[36, 65, 69]), start);
+        tracker.update(&notes(&[31, 62, 67, 71]), start);
+        assert_eq!(tracker.pedal(), None);
+        assert_eq!(name(&tracker, &[31, 62, 67, 71]).as_deref(), Some("G"));
+    }
+}
@@ -0,0 +1,82 @@
+use super::note::Note;
+
+const CENTS_PER_SEMITONE: i32 = 100;
+
+/// A pitch at any tuning, in cents above MIDI note 0. Detection still works
+/// in 12-EDO pitch classes, so pitches are snapped to the nearest note.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pitch {
+    pub cents: i32,
+}
+
+impl Pitch {
+    pub fn new(cents: i32) -> Self {
+        Self { cents }
+    }
+
+    pub fn from_midi(midi: u8) -> Self {
+        Self::new(midi as i32 * CENTS_PER_SEMITONE)
+    }
+
+    /// A MIDI note under a 14-bit pitch bend (-8192..=8191), where a full
+    /// bend reaches `range` semitones.
+    pub fn bent(midi: u8, bend: i16, range: u8) -> Self {
+        let offset = bend as i64 * range as i64 * CENTS_PER_SEMITONE as i64 / 8192;
+        Self::new(midi as i32 * CENTS_PER_SEMITONE + offset as i32)
+    }
+
+    /// Step `step` of an `edo`-tone equal temperament, step 0 at MIDI note 0.
+    pub fn edo(step: i32, edo: u16) -> Self {
+        let cents = step as i64 * 1200 / edo.max(1) as i64;
+        Self::new(cents as i32)
+    }
+
+    /// The nearest 12-EDO note, if in MIDI range.
+    pub fn nearest(&self) -> Option<Note> {
+        let midi = (self.cents as f32 / CENTS_PER_SEMITONE as f32).round() as i32;
+        u8::try_from(midi).ok().filter(|&m| m < 128).map(Note::new)
+    }
+
+    /// Cents sharp (positive) or flat of the nearest note.
+    pub fn deviation(&self) -> i32 {
+        let offset = self.cents.rem_euclid(CENTS_PER_SEMITONE);
+        if offset > CENTS_PER_SEMITONE / 2 {
+            offset - CENTS_PER_SEMITONE
+        } else {
+            offset
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bent_pitches() {
+        assert_eq!(Pitch::bent(60, 0, 48), Pitch::from_midi(60));
+        // A quarter of a 2-semitone range is 50 cents up
+        assert_eq!(Pitch::bent(60, 2048, 2).cents, 6050);
+        assert_eq!(Pitch::bent(60, -8192, 48).nearest(), Some(Note::new(12)));
+        assert_eq!(Pitch::bent(127, 8191, 48).nearest(), None);
+    }
+
+    #[test]
+    fn test_nearest_and_deviation() {
+        let pitch = Pitch::new(6438);
+        assert_eq!(pitch.nearest(), Some(Note::new(64)));
+        assert_eq!(pitch.deviation(), 38);
+        let pitch = Pitch::new(6462);
+        assert_eq!(pitch.nearest(), Some(Note::new(65)));
+        assert_eq!(pitch.deviation(), -38);
+    }
+
+    #[test]
+    fn test_edo() {
+        // 31-EDO's major third is about 13.7 cents flat of 12-EDO's
+        let third = Pitch::edo(31 * 5 + 10, 31);
+        assert_eq!(third.nearest(), Some(Note::new(64)));
+        assert_eq!(third.deviation(), -13);
+        assert_eq!(Pitch::edo(12 * 5, 12), Pitch::from_midi(60));
+    }
+}
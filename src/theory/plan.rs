@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+
+use super::chord::Chord;
+use super::progression::ProgressionNode;
+
+/// Deepest branch of the suggestion tree that can be planned
+pub const PLAN_DEPTH: usize = 2;
+
+/// One of a suggestion node's two branches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Branch {
+    Expected,
+    Surprise,
+}
+
+impl Branch {
+    fn of(node: &ProgressionNode, branch: Branch) -> Option<&ProgressionNode> {
+        match branch {
+            Branch::Expected => node.left.as_deref(),
+            Branch::Surprise => node.right.as_deref(),
+        }
+    }
+
+    fn of_mut(node: &mut ProgressionNode, branch: Branch) -> &mut Option<Box<ProgressionNode>> {
+        match branch {
+            Branch::Expected => &mut node.left,
+            Branch::Surprise => &mut node.right,
+        }
+    }
+}
+
+/// The chord at `path` below `node`.
+pub fn chord_at<'a>(node: &'a ProgressionNode, path: &[Branch]) -> Option<&'a Chord> {
+    path.iter()
+        .try_fold(node, |node, &branch| Branch::of(node, branch))
+        .map(|node| &node.chord)
+}
+
+/// Chords pinned or entered by hand into the suggestion tree, keyed by
+/// their path from the chord being played. Playing a planned chord moves
+/// the plan on a step.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Plan {
+    chords: BTreeMap<Vec<Branch>, Chord>,
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.chords.is_empty()
+    }
+
+    pub fn get(&self, path: &[Branch]) -> Option<&Chord> {
+        self.chords.get(path)
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &Vec<Branch>> {
+        self.chords.keys()
+    }
+
+    /// Plans `chord` at `path`. A different chord there drops what was
+    /// planned after the old one.
+    pub fn set(&mut self, path: Vec<Branch>, chord: Chord) {
+        if path.is_empty() || path.len() > PLAN_DEPTH {
+            return;
+        }
+        if self.chords.get(&path).map(|c| c.name()) != Some(chord.name()) {
+            self.remove_below(&path);
+        }
+        self.chords.insert(path, chord);
+    }
+
+    /// Unplans `path` and everything after it.
+    pub fn remove(&mut self, path: &[Branch]) {
+        self.chords.remove(path);
+        self.remove_below(path);
+    }
+
+    fn remove_below(&mut self, path: &[Branch]) {
+        self.chords
+            .retain(|planned, _| planned.len() <= path.len() || !planned.starts_with(path));
+    }
+
+    pub fn clear(&mut self) {
+        self.chords.clear();
+    }
+
+    /// Puts the planned chords into a suggested tree. Branches after a
+    /// chord that replaced a suggestion are suggested afresh by `suggest`.
+    pub fn apply(
+        &self,
+        mut node: ProgressionNode,
+        suggest: impl Fn(&Chord) -> ProgressionNode,
+    ) -> ProgressionNode {
+        self.apply_at(&mut node, &mut Vec::new(), &suggest);
+        node
+    }
+
+    fn apply_at(
+        &self,
+        node: &mut ProgressionNode,
+        path: &mut Vec<Branch>,
+        suggest: &impl Fn(&Chord) -> ProgressionNode,
+    ) {
+        if path.len() >= PLAN_DEPTH {
+            return;
+        }
+        for branch in [Branch::Expected, Branch::Surprise] {
+            path.push(branch);
+            if let Some(planned) = self.chords.get(path.as_slice()) {
+                let child = Branch::of_mut(node, branch);
+                let replaced = child
+                    .as_ref()
+                    .is_none_or(|c| c.chord.name() != planned.name());
+                if replaced {
+                    let mut fresh = ProgressionNode::new(planned.clone());
+                    let next = suggest(planned);
+                    fresh.left = next.left.map(|n| Box::new(ProgressionNode::new(n.chord)));
+                    fresh.right = next.right.map(|n| Box::new(ProgressionNode::new(n.chord)));
+                    *child = Some(Box::new(fresh));
+                }
+            }
+            if let Some(child) = Branch::of_mut(node, branch) {
+                self.apply_at(child, path, suggest);
+            }
+            path.pop();
+        }
+    }
+
+    /// Moves the plan on once `played` is played. Returns whether it was a
+    /// planned next chord; if not, the plan no longer applies and is
+    /// dropped.
+    pub fn advance(&mut self, played: &Chord) -> bool {
+        let next = self
+            .chords
+            .iter()
+            .find(|(path, chord)| path.len() == 1 && chord.name() == played.name())
+            .map(|(path, _)| path[0]);
+        let Some(branch) = next else {
+            self.clear();
+            return false;
+        };
+        self.chords = std::mem::take(&mut self.chords)
+            .into_iter()
+            .filter(|(path, _)| path.len() > 1 && path[0] == branch)
+            .map(|(path, chord)| (path[1..].to_vec(), chord))
+            .collect();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(name: &str) -> Chord {
+        Chord::from_name(name).unwrap()
+    }
+
+    fn node(name: &str, children: Option<(&str, &str)>) -> ProgressionNode {
+        let node = ProgressionNode::new(chord(name));
+        match children {
+            Some((left, right)) => node.with_children(
+                ProgressionNode::new(chord(left)),
+                ProgressionNode::new(chord(right)),
+            ),
+            None => node,
+        }
+    }
+
+    /// Suggests the minor chord on the same root, or E
+    fn suggest(current: &Chord) -> ProgressionNode {
+        let minor = format!("{}m", current.name());
+        node(current.name().as_str(), Some((minor.as_str(), "E")))
+    }
+
+    fn tree() -> ProgressionNode {
+        let mut root = node("C", None);
+        root.left = Some(Box::new(node("F", Some(("G", "Dm")))));
+        root.right = Some(Box::new(node("Am", Some(("Dm", "G")))));
+        root
+    }
+
+    #[test]
+    fn test_apply_replaces_branches() {
+        let mut plan = Plan::default();
+        plan.set(vec![Branch::Surprise], chord("D"));
+        plan.set(vec![Branch::Expected, Branch::Surprise], chord("E"));
+        let planned = plan.apply(tree(), suggest);
+
+        let name = |path: &[Branch]| chord_at(&planned, path).unwrap().name();
+        assert_eq!(name(&[Branch::Expected]), "F");
+        assert_eq!(name(&[Branch::Expected, Branch::Surprise]), "E");
+        assert_eq!(name(&[Branch::Surprise]), "D");
+        // Suggested afresh after the chord put in by hand
+        assert_eq!(name(&[Branch::Surprise, Branch::Expected]), "Dm");
+        assert!(chord_at(
+            &planned,
+            &[Branch::Surprise, Branch::Expected, Branch::Expected]
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_replacing_drops_what_followed() {
+        let mut plan = Plan::default();
+        plan.set(vec![Branch::Expected], chord("F"));
+        plan.set(vec![Branch::Expected, Branch::Expected], chord("G"));
+        // Pinning the same chord again keeps the rest
+        plan.set(vec![Branch::Expected], chord("F"));
+        assert!(plan.get(&[Branch::Expected, Branch::Expected]).is_some());
+
+        plan.set(vec![Branch::Expected], chord("Dm"));
+        assert!(plan.get(&[Branch::Expected, Branch::Expected]).is_none());
+        plan.remove(&[Branch::Expected]);
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn test_advance() {
+        let mut plan = Plan::default();
+        plan.set(vec![Branch::Expected], chord("F"));
+        plan.set(vec![Branch::Expected, Branch::Surprise], chord("G7"));
+        plan.set(vec![Branch::Surprise], chord("Am"));
+
+        assert!(plan.advance(&chord("F")));
+        assert_eq!(plan.get(&[Branch::Surprise]), Some(&chord("G7")));
+        assert!(plan.get(&[Branch::Expected]).is_none());
+
+        assert!(!plan.advance(&chord("D")));
+        assert!(plan.is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+use super::chord::Chord;
+use super::note::Note;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresetCategory {
+    Pop,
+    Jazz,
+    Blues,
+}
+
+impl PresetCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PresetCategory::Pop => "Pop",
+            PresetCategory::Jazz => "Jazz",
+            PresetCategory::Blues => "Blues",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Preset {
+    pub name: &'static str,
+    pub category: PresetCategory,
+    pub numerals: &'static [&'static str],
+}
+
+const PRESETS: &[Preset] = &[
+    Preset {
+        name: "Axis (I–V–vi–IV)",
+        category: PresetCategory::Pop,
+        numerals: &["I", "V", "vi", "IV"],
+    },
+    Preset {
+        name: "Sensitive (vi–IV–I–V)",
+        category: PresetCategory::Pop,
+        numerals: &["vi", "IV", "I", "V"],
+    },
+    Preset {
+        name: "Doo-wop (I–vi–IV–V)",
+        category: PresetCategory::Pop,
+        numerals: &["I", "vi", "IV", "V"],
+    },
+    Preset {
+        name: "Pachelbel",
+        category: PresetCategory::Pop,
+        numerals: &["I", "V", "vi", "iii", "IV", "I", "IV", "V"],
+    },
+    Preset {
+        name: "Mixolydian rock (I–bVII–IV–I)",
+        category: PresetCategory::Pop,
+        numerals: &["I", "bVII", "IV", "I"],
+    },
+    Preset {
+        name: "Andalusian cadence",
+        category: PresetCategory::Pop,
+        numerals: &["vi", "V", "IV", "III"],
+    },
+    Preset {
+        name: "ii–V–I",
+        category: PresetCategory::Jazz,
+        numerals: &["ii7", "V7", "Imaj7"],
+    },
+    Preset {
+        name: "Minor ii–V–i",
+        category: PresetCategory::Jazz,
+        numerals: &["iiø7", "V7", "i7"],
+    },
+    Preset {
+        name: "I–vi–ii–V turnaround",
+        category: PresetCategory::Jazz,
+        numerals: &["Imaj7", "vi7", "ii7", "V7"],
+    },
+    Preset {
+        name: "iii–vi–ii–V turnaround",
+        category: PresetCategory::Jazz,
+        numerals: &["iii7", "vi7", "ii7", "V7"],
+    },
+    Preset {
+        name: "Rhythm changes A",
+        category: PresetCategory::Jazz,
+        numerals: &[
+            "Imaj7", "vi7", "ii7", "V7", "iii7", "VI7", "ii7", "V7", "I7", "I7", "IVmaj7", "#iv°7",
+            "iii7", "VI7", "ii7", "V7",
+        ],
+    },
+    Preset {
+        name: "12-bar blues",
+        category: PresetCategory::Blues,
+        numerals: &[
+            "I7", "I7", "I7", "I7", "IV7", "IV7", "I7", "I7", "V7", "IV7", "I7", "V7",
+        ],
+    },
+    Preset {
+        name: "Quick-change blues",
+        category: PresetCategory::Blues,
+        numerals: &[
+            "I7", "IV7", "I7", "I7", "IV7", "IV7", "I7", "I7", "V7", "IV7", "I7", "V7",
+        ],
+    },
+    Preset {
+        name: "Minor blues",
+        category: PresetCategory::Blues,
+        numerals: &[
+            "i7", "i7", "i7", "i7", "iv7", "iv7", "i7", "i7", "bVI7", "V7", "i7", "V7",
+        ],
+    },
+    Preset {
+        name: "8-bar blues",
+        category: PresetCategory::Blues,
+        numerals: &["I7", "V7", "IV7", "IV7", "I7", "V7", "I7", "V7"],
+    },
+];
+
+impl Preset {
+    pub fn all() -> &'static [Preset] {
+        PRESETS
+    }
+
+    pub fn find(name: &str) -> Option<&'static Preset> {
+        PRESETS.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn chords(&self, key: Note) -> Vec<Chord> {
+        self.numerals
+            .iter()
+            .filter_map(|n| Chord::from_roman(n, key))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_presets_parse() {
+        for preset in Preset::all() {
+            let chords = preset.chords(Note::new(60));
+            assert_eq!(chords.len(), preset.numerals.len(), "{}", preset.name);
+        }
+    }
+
+    #[test]
+    fn test_axis_in_g() {
+        let preset = Preset::find("axis (i–v–vi–iv)").unwrap();
+        let names: Vec<String> = preset
+            .chords(Note::new(67))
+            .iter()
+            .map(|c| c.name())
+            .collect();
+        assert_eq!(names, vec!["G", "D", "Em", "C"]);
+    }
+
+    #[test]
+    fn test_ii_v_i() {
+        let preset = Preset::find("ii–V–I").unwrap();
+        let names: Vec<String> = preset
+            .chords(Note::new(60))
+            .iter()
+            .map(|c| c.name())
+            .collect();
+        assert_eq!(names, vec!["Dm7", "G7", "Cmaj7"]);
+    }
+}
@@ -1,6 +1,14 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
 use super::chord::Chord;
+use super::model::{ProgressionModel, FALLBACK, MAX_CONTEXT};
 use super::note::Note;
 use super::quality::Quality;
+use super::scale::{Scale, ScaleKind};
 
 #[derive(Clone, Debug)]
 pub struct ProgressionNode {
@@ -25,8 +33,30 @@ impl ProgressionNode {
     }
 }
 
+pub trait Suggester {
+    fn name(&self) -> &str;
+    fn suggest(&self, current: &Chord, key: Note, history: &[Chord]) -> anyhow::Result<Vec<Chord>>;
+}
+
+/// Trees kept before the cache starts over
+const CACHED_TREES: usize = 256;
+
+/// The chords a tree follows on from, its chord and the scale it's read in
+type TreeKey = (Vec<Chord>, Chord, Scale);
+
 pub struct ProgressionTree {
     extended_mode: bool,
+    creativity: f32,
+    model: ProgressionModel,
+    minor_model: ProgressionModel,
+    blues_model: ProgressionModel,
+    suggester: Option<Box<dyn Suggester>>,
+    history: Vec<Chord>,
+    /// Trees already worked out, by the chords leading up to the chord, the
+    /// chord and scale. Changing anything else they depend on empties it.
+    cache: RefCell<HashMap<TreeKey, ProgressionNode>>,
+    /// Draws the creative suggestions
+    rng: RefCell<StdRng>,
 }
 
 impl Default for ProgressionTree {
@@ -39,23 +69,153 @@ impl ProgressionTree {
     pub fn new() -> Self {
         Self {
             extended_mode: false,
+            creativity: 0.0,
+            model: ProgressionModel::default(),
+            minor_model: ProgressionModel::minor(),
+            blues_model: ProgressionModel::blues(),
+            suggester: None,
+            history: Vec::new(),
+            cache: RefCell::default(),
+            rng: RefCell::new(StdRng::seed_from_u64(0)),
         }
     }
 
+    pub fn seeded(seed: u64) -> Self {
+        let mut tree = Self::new();
+        tree.seed(seed);
+        tree
+    }
+
+    /// Starts the creative suggestions' draws over from `seed`. The same
+    /// seed makes the same suggestions.
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = RefCell::new(StdRng::seed_from_u64(seed));
+        self.cache.get_mut().clear();
+    }
+
     pub fn set_extended(&mut self, extended: bool) {
         self.extended_mode = extended;
+        self.cache.get_mut().clear();
+    }
+
+    pub fn set_creativity(&mut self, creativity: f32) {
+        self.creativity = creativity.clamp(0.0, 1.0);
+        self.cache.get_mut().clear();
+    }
+
+    pub fn creativity(&self) -> f32 {
+        self.creativity
+    }
+
+    pub fn set_model(&mut self, model: ProgressionModel) {
+        self.model = model;
+        self.cache.get_mut().clear();
+    }
+
+    pub fn model(&self) -> &ProgressionModel {
+        &self.model
+    }
+
+    pub fn set_suggester(&mut self, suggester: Option<Box<dyn Suggester>>) {
+        self.suggester = suggester;
+        self.cache.get_mut().clear();
+    }
+
+    pub fn suggester(&self) -> Option<&dyn Suggester> {
+        self.suggester.as_deref()
+    }
+
+    /// Only a suggester reads the whole history; the model's trees are
+    /// kept by the last few chords they follow.
+    pub fn set_history(&mut self, history: Vec<Chord>) {
+        self.history = history;
+        if self.suggester.is_some() {
+            self.cache.get_mut().clear();
+        }
+    }
+
+    /// Works out the trees for every chord in `scale` ahead of time, so
+    /// they're ready when played next.
+    pub fn precompute(&self, scale: Scale) {
+        let context = self.recent(&self.history);
+        for degree in 0..7 {
+            let chord = scale.diatonic(degree, self.extended_mode);
+            self.suggest_after(context.clone(), &chord, scale);
+        }
+    }
+
+    /// The chords played before `current`, when it's the last one played.
+    /// Suggesting for any other chord goes without context.
+    fn context(&self, current: &Chord) -> Vec<Chord> {
+        match self.history.split_last() {
+            Some((last, before)) if last == current => self.recent(before),
+            _ => Vec::new(),
+        }
+    }
+
+    fn recent(&self, chords: &[Chord]) -> Vec<Chord> {
+        chords[chords.len().saturating_sub(MAX_CONTEXT)..].to_vec()
+    }
+
+    pub fn cached(&self) -> usize {
+        self.cache.borrow().len()
     }
 
     pub fn suggest(&self, current: &Chord, key: Option<Note>) -> ProgressionNode {
-        let key = key.unwrap_or(current.root);
-        let degree = self.get_degree(current, key);
+        self.suggest_in(current, Scale::major(key.unwrap_or(current.root)))
+    }
+
+    /// The `n` chords closest to `current` by [`Chord::distance`], using
+    /// seventh chords only in extended mode.
+    pub fn nearest(&self, current: &Chord, n: usize) -> Vec<Chord> {
+        let qualities = if self.extended_mode {
+            Quality::all_sevenths()
+        } else {
+            Quality::all_triads()
+        };
+        let mut chords: Vec<(Chord, f32)> = qualities
+            .iter()
+            .flat_map(|&quality| (0..12).map(move |root| Chord::new(Note::new(60 + root), quality)))
+            .filter(|c| {
+                c.root.pitch_class() != current.root.pitch_class() || c.quality != current.quality
+            })
+            .map(|c| {
+                let distance = current.distance(&c);
+                (c, distance)
+            })
+            .collect();
+        chords.sort_by(|a, b| a.1.total_cmp(&b.1));
+        chords.into_iter().take(n).map(|(c, _)| c).collect()
+    }
+
+    /// Suggestions read in `scale`; minor keys use their own model.
+    pub fn suggest_in(&self, current: &Chord, scale: Scale) -> ProgressionNode {
+        self.suggest_after(self.context(current), current, scale)
+    }
 
-        let (left_chord, right_chord) = self.get_suggestions(degree, key, current);
+    fn suggest_after(&self, context: Vec<Chord>, current: &Chord, scale: Scale) -> ProgressionNode {
+        let key = (context, current.clone(), scale);
+        if let Some(node) = self.cache.borrow().get(&key) {
+            return node.clone();
+        }
+        let node = self.build(&key.0, current, scale);
+        let mut cache = self.cache.borrow_mut();
+        if cache.len() >= CACHED_TREES {
+            cache.clear();
+        }
+        cache.insert(key, node.clone());
+        node
+    }
 
-        let left_left_right =
-            self.get_suggestions(self.get_degree(&left_chord, key), key, &left_chord);
-        let right_left_right =
-            self.get_suggestions(self.get_degree(&right_chord, key), key, &right_chord);
+    fn build(&self, context: &[Chord], current: &Chord, scale: Scale) -> ProgressionNode {
+        let (left_chord, right_chord) = self.get_suggestions(scale, context, current);
+
+        // Each branch follows on from the current chord
+        let mut next: Vec<Chord> = context.to_vec();
+        next.push(current.clone());
+        let next = self.recent(&next);
+        let left_left_right = self.get_suggestions(scale, &next, &left_chord);
+        let right_left_right = self.get_suggestions(scale, &next, &right_chord);
 
         let left_node = ProgressionNode::new(left_chord.clone()).with_children(
             ProgressionNode::new(left_left_right.0),
@@ -70,41 +230,122 @@ impl ProgressionTree {
         ProgressionNode::new(current.clone()).with_children(left_node, right_node)
     }
 
-    fn get_degree(&self, chord: &Chord, key: Note) -> u8 {
-        (chord.root.pitch_class() + 12 - key.pitch_class()) % 12
+    fn model_for(&self, scale: Scale) -> &ProgressionModel {
+        match scale.kind {
+            ScaleKind::Major => &self.model,
+            ScaleKind::Minor => &self.minor_model,
+            ScaleKind::Blues => &self.blues_model,
+        }
     }
 
-    fn get_suggestions(&self, degree: u8, key: Note, _current: &Chord) -> (Chord, Chord) {
-        let (left_interval, left_quality, right_interval, right_quality) = match degree {
-            0 => (5, Quality::Major, 9, Quality::Minor), // I -> IV, vi
-            2 => (7, Quality::Major, 5, Quality::Major), // ii -> V, IV
-            4 => (9, Quality::Minor, 5, Quality::Major), // iii -> vi, IV
-            5 => (7, Quality::Major, 0, Quality::Major), // IV -> V, I
-            7 => (0, Quality::Major, 9, Quality::Minor), // V -> I, vi
-            9 => (2, Quality::Minor, 5, Quality::Major), // vi -> ii, IV
-            11 => (0, Quality::Major, 4, Quality::Minor), // vii° -> I, iii
-            _ => (7, Quality::Major, 0, Quality::Major), // Default: V, I
+    fn get_suggestions(&self, scale: Scale, context: &[Chord], current: &Chord) -> (Chord, Chord) {
+        let key = scale.tonic;
+        let scripted = self
+            .suggester
+            .as_ref()
+            .and_then(|s| s.suggest(current, key, &self.history).ok())
+            .unwrap_or_default();
+        if let [left, right, ..] = &scripted[..] {
+            return (
+                self.apply_extended(left.clone()),
+                self.apply_extended(right.clone()),
+            );
+        }
+
+        let tonic = match scale.kind {
+            ScaleKind::Major => Quality::Major,
+            ScaleKind::Minor => Quality::Minor,
+            ScaleKind::Blues => Quality::Dominant7,
         };
+        let left = self
+            .ranked(scale, context, current, self.creativity * 0.25)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| Chord::new(Note::new(key.pitch_class() + 60), tonic));
+        let right = self
+            .ranked(scale, context, current, self.creativity)
+            .into_iter()
+            .find(|c| *c != left)
+            .unwrap_or_else(|| left.clone());
+
+        (self.apply_extended(left), self.apply_extended(right))
+    }
 
-        let left_root = Note::new((key.pitch_class() + left_interval) % 12 + 60);
-        let right_root = Note::new((key.pitch_class() + right_interval) % 12 + 60);
+    /// The chords to go to from `current`, most likely first. With any
+    /// creativity they're drawn at random, each weighted by its blend of
+    /// the model's chance and how adventurous it is.
+    fn ranked(
+        &self,
+        scale: Scale,
+        context: &[Chord],
+        current: &Chord,
+        creativity: f32,
+    ) -> Vec<Chord> {
+        let (key, model) = (scale.tonic, self.model_for(scale));
+        let candidates = model.candidates_in_context(context, current, key);
+        let total: f32 = candidates.iter().map(|(_, w)| w).sum();
+
+        let mut pool: Vec<(Chord, f32)> = candidates
+            .into_iter()
+            .map(|(c, w)| (c, if total > 0.0 { w / total } else { 0.0 }))
+            .collect();
+
+        let fallback = model
+            .transitions
+            .get(FALLBACK)
+            .into_iter()
+            .flatten()
+            .filter_map(|t| Chord::from_roman(&t.to, key));
+        for chord in fallback {
+            if !pool.iter().any(|(c, _)| *c == chord) {
+                pool.push((chord, 0.0));
+            }
+        }
 
-        let left_quality = if self.extended_mode {
-            self.extend_quality(left_quality)
-        } else {
-            left_quality
-        };
+        if creativity <= 0.0 {
+            return pool.into_iter().map(|(c, _)| c).collect();
+        }
 
-        let right_quality = if self.extended_mode {
-            self.extend_quality(right_quality)
-        } else {
-            right_quality
-        };
+        let mut scored: Vec<(Chord, f32)> = pool
+            .iter()
+            .map(|(c, w)| {
+                let adventure = if scale.contains_chord(c) { 0.0 } else { 0.6 };
+                (c.clone(), *w, adventure)
+            })
+            .chain(
+                adventurous(&pool, current, key)
+                    .into_iter()
+                    .map(|(c, a)| (c, 0.0, a)),
+            )
+            .map(|(c, w, a)| (c, w * (1.0 - creativity) + a * creativity))
+            .collect();
+
+        // Drawing without replacement: each chord's key is u^(1/weight) for a
+        // uniform u, and the highest keys are drawn first
+        let mut rng = self.rng.borrow_mut();
+        for (_, weight) in &mut scored {
+            *weight = if *weight > 0.0 {
+                rng.random::<f32>().powf(1.0 / *weight)
+            } else {
+                0.0
+            };
+        }
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let mut ranked: Vec<Chord> = Vec::new();
+        for (chord, _) in scored {
+            if !ranked.contains(&chord) {
+                ranked.push(chord);
+            }
+        }
+        ranked
+    }
 
-        (
-            Chord::new(left_root, left_quality),
-            Chord::new(right_root, right_quality),
-        )
+    fn apply_extended(&self, chord: Chord) -> Chord {
+        if self.extended_mode {
+            Chord::new(chord.root, self.extend_quality(chord.quality))
+        } else {
+            chord
+        }
     }
 
     fn extend_quality(&self, quality: Quality) -> Quality {
@@ -116,10 +357,69 @@ impl ProgressionTree {
     }
 }
 
+fn adventurous(pool: &[(Chord, f32)], current: &Chord, key: Note) -> Vec<(Chord, f32)> {
+    let mut chords = Vec::new();
+
+    for (chord, _) in pool {
+        let is_dominant = (chord.root.pitch_class() + 12 - key.pitch_class()) % 12 == 7;
+        if chord.quality == Quality::Dominant7 || (is_dominant && chord.quality == Quality::Major) {
+            let sub = Note::new(60 + (chord.root.pitch_class() + 6) % 12);
+            chords.push((Chord::new(sub, Quality::Dominant7), 1.0));
+        }
+    }
+
+    let quality = match current.quality {
+        Quality::Minor | Quality::Minor7 => Quality::Minor,
+        _ => Quality::Major,
+    };
+    for (offset, adventure) in [(4, 0.8), (8, 0.8), (3, 0.7), (9, 0.7)] {
+        let root = Note::new(60 + (current.root.pitch_class() + offset) % 12);
+        chords.push((Chord::new(root, quality), adventure));
+    }
+
+    chords
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_nearest() {
+        let mut tree = ProgressionTree::new();
+        let c = Chord::from_name("C").unwrap();
+        let nearest = tree.nearest(&c, 3);
+        assert_eq!(nearest.len(), 3);
+        assert!(!nearest.contains(&c));
+        let names: Vec<String> = nearest.iter().map(|c| c.name()).collect();
+        assert!(names.contains(&"Am".to_string()));
+
+        tree.set_extended(true);
+        assert!(tree
+            .nearest(&c, 5)
+            .iter()
+            .all(|c| c.quality.intervals().len() == 4));
+    }
+
+    #[test]
+    fn test_suggest_in_minor() {
+        let tree = ProgressionTree::new();
+        let dm = Chord::from_name("Dm").unwrap();
+        let names = |node: ProgressionNode| -> Vec<String> {
+            [node.left, node.right]
+                .into_iter()
+                .flatten()
+                .map(|n| n.chord.name())
+                .collect()
+        };
+
+        // iv in A minor heads for the major dominant, ii in C for G
+        let minor = tree.suggest_in(&dm, Scale::new(Note::new(57), ScaleKind::Minor));
+        assert_eq!(names(minor), vec!["E", "Am"]);
+        let major = tree.suggest(&dm, Some(Note::new(60)));
+        assert_eq!(names(major), vec!["G", "F"]);
+    }
+
     #[test]
     fn test_suggest_from_i() {
         let tree = ProgressionTree::new();
@@ -187,4 +487,186 @@ mod tests {
 
         assert_eq!(left.chord.quality, Quality::Major7);
     }
+
+    #[test]
+    fn test_custom_model() {
+        let json = r#"{ "transitions": { "I": [
+            { "to": "bVI", "weight": 0.7 },
+            { "to": "iv", "weight": 0.3 }
+        ] } }"#;
+
+        let mut tree = ProgressionTree::new();
+        tree.set_model(ProgressionModel::from_json(json).unwrap());
+
+        let c_major = Chord::new(Note::new(60), Quality::Major);
+        let result = tree.suggest(&c_major, Some(Note::new(60)));
+
+        assert_eq!(result.left.as_ref().unwrap().chord.name(), "G#");
+        assert_eq!(result.right.as_ref().unwrap().chord.name(), "Fm");
+        assert!(result.left.unwrap().left.is_some());
+    }
+
+    #[test]
+    fn test_creativity_zero_matches_model() {
+        let mut tree = ProgressionTree::new();
+        tree.set_creativity(0.0);
+
+        let c_major = Chord::new(Note::new(60), Quality::Major);
+        let result = tree.suggest(&c_major, Some(Note::new(60)));
+        assert_eq!(result.left.unwrap().chord.name(), "F");
+        assert_eq!(result.right.unwrap().chord.name(), "Am");
+    }
+
+    #[test]
+    fn test_creativity_surprise_goes_chromatic() {
+        let mut tree = ProgressionTree::new();
+        tree.set_creativity(1.0);
+
+        let key = Note::new(60);
+        let scale = Scale::major(key);
+        let c_major = Chord::new(Note::new(60), Quality::Major);
+        let result = tree.suggest(&c_major, Some(key));
+
+        let right = result.right.unwrap().chord;
+        assert!(!scale.contains_chord(&right), "{}", right.name());
+    }
+
+    #[test]
+    fn test_creativity_tritone_sub() {
+        let dm7 = Chord::new(Note::new(62), Quality::Minor7);
+        let mut surprises: HashMap<String, usize> = HashMap::new();
+        for seed in 0..200 {
+            let mut tree = ProgressionTree::seeded(seed);
+            tree.set_creativity(0.8);
+            let right = tree.suggest(&dm7, Some(Note::new(60))).right.unwrap();
+            *surprises.entry(right.chord.name()).or_default() += 1;
+        }
+
+        // Drawn, so not always the same, but the tritone sub most often
+        assert!(surprises.len() > 1, "{surprises:?}");
+        let most = surprises.iter().max_by_key(|(_, n)| **n).unwrap();
+        assert_eq!(most.0, "C#7", "{surprises:?}");
+    }
+
+    #[test]
+    fn test_creativity_seeded() {
+        let dm7 = Chord::new(Note::new(62), Quality::Minor7);
+        let suggest = |seed| {
+            let mut tree = ProgressionTree::seeded(seed);
+            tree.set_creativity(0.5);
+            let node = tree.suggest(&dm7, Some(Note::new(60)));
+            (node.left.unwrap().chord, node.right.unwrap().chord)
+        };
+
+        for seed in 0..20 {
+            assert_eq!(suggest(seed), suggest(seed));
+        }
+        assert!((0..20).any(|seed| suggest(seed) != suggest(0)));
+    }
+
+    struct Fixed;
+
+    impl Suggester for Fixed {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn suggest(&self, _: &Chord, key: Note, history: &[Chord]) -> anyhow::Result<Vec<Chord>> {
+            if history.is_empty() {
+                anyhow::bail!("no history");
+            }
+            Ok(vec![
+                Chord::from_roman("bVI", key).unwrap(),
+                Chord::from_roman("bVII", key).unwrap(),
+            ])
+        }
+    }
+
+    #[test]
+    fn test_suggester_overrides_model() {
+        let mut tree = ProgressionTree::new();
+        tree.set_suggester(Some(Box::new(Fixed)));
+
+        let c_major = Chord::new(Note::new(60), Quality::Major);
+        let result = tree.suggest(&c_major, Some(Note::new(60)));
+        assert_eq!(result.left.unwrap().chord.name(), "F");
+
+        tree.set_history(vec![c_major.clone()]);
+        let result = tree.suggest(&c_major, Some(Note::new(60)));
+        assert_eq!(result.left.unwrap().chord.name(), "G#");
+        assert_eq!(result.right.unwrap().chord.name(), "A#");
+    }
+
+    struct Counting(std::rc::Rc<std::cell::Cell<usize>>);
+
+    impl Suggester for Counting {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn suggest(&self, _: &Chord, _: Note, _: &[Chord]) -> anyhow::Result<Vec<Chord>> {
+            self.0.set(self.0.get() + 1);
+            anyhow::bail!("no suggestions")
+        }
+    }
+
+    #[test]
+    fn test_cached_trees() {
+        let calls = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut tree = ProgressionTree::new();
+        tree.set_suggester(Some(Box::new(Counting(calls.clone()))));
+        let c = Chord::from_name("C").unwrap();
+        let key = Some(Note::new(60));
+
+        let first = tree.suggest(&c, key);
+        assert_eq!(calls.get(), 3);
+        let again = tree.suggest(&c, key);
+        assert_eq!(calls.get(), 3);
+        assert_eq!(again.left.unwrap().chord, first.left.unwrap().chord);
+
+        tree.set_creativity(0.5);
+        tree.suggest(&c, key);
+        assert_eq!(calls.get(), 6);
+
+        tree.precompute(Scale::major(Note::new(60)));
+        assert_eq!(tree.cached(), 7);
+        tree.suggest(&Chord::from_name("Am").unwrap(), key);
+        assert_eq!(calls.get(), 6 + 6 * 3);
+        tree.set_history(vec![c]);
+        assert_eq!(tree.cached(), 0);
+    }
+
+    #[test]
+    fn test_suggest_after_two_five() {
+        let mut tree = ProgressionTree::new();
+        let key = Some(Note::new(60));
+        let chords = |names: &[&str]| -> Vec<Chord> {
+            names.iter().map(|n| Chord::from_name(n).unwrap()).collect()
+        };
+        let g = Chord::from_name("G").unwrap();
+
+        let expected = |tree: &ProgressionTree| tree.suggest(&g, key).left.unwrap().chord.name();
+        assert_eq!(expected(&tree), "C");
+        // I–V heads on to vi, ii–V goes home
+        tree.set_history(chords(&["C", "G"]));
+        assert_eq!(expected(&tree), "Am");
+        tree.set_history(chords(&["Dm", "G"]));
+        assert_eq!(expected(&tree), "C");
+        assert_eq!(tree.cached(), 3);
+
+        // The branches follow on: vi–ii leads to V, and vi–ii–V to I
+        tree.set_history(chords(&["Am", "Dm"]));
+        let dm = Chord::from_name("Dm").unwrap();
+        let node = tree.suggest(&dm, key);
+        let g = node.left.unwrap();
+        assert_eq!(g.chord.name(), "G");
+        assert_eq!(g.left.unwrap().chord.name(), "C");
+    }
+
+    #[test]
+    fn test_creativity_clamped() {
+        let mut tree = ProgressionTree::new();
+        tree.set_creativity(1.5);
+        assert_eq!(tree.creativity(), 1.0);
+    }
 }
@@ -0,0 +1,119 @@
+//! Randomised checks over generated voicings. A failing case is shrunk to
+//! the smallest one that still fails and saved under proptest-regressions,
+//! so it's tried first on every later run.
+
+use std::collections::HashSet;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use super::voicing::stacked;
+use super::{Chord, Note, Quality, VoicingOptions};
+
+/// A chord with no bass of its own: any root, any quality detection looks
+/// for. Add9 isn't one of them.
+fn chord() -> impl Strategy<Value = Chord> {
+    let qualities: Vec<Quality> = Quality::all()
+        .iter()
+        .copied()
+        .filter(|&q| !matches!(q, Quality::Unknown | Quality::Add9))
+        .collect();
+    (0u8..12, select(qualities)).prop_map(|(pc, quality)| Chord::new(Note::new(60 + pc), quality))
+}
+
+/// A chord and one way of playing it: any inversion, up to three octave
+/// doublings, from C1 up to C5.
+fn voiced_chord() -> impl Strategy<Value = (Chord, Vec<u8>)> {
+    (chord(), 1i8..=4, 0usize..4, vec(0usize..4, 0..=3)).prop_map(
+        |(chord, octave, inversion, doubled)| {
+            let notes = stacked(&chord, octave, inversion, &doubled);
+            (chord, notes)
+        },
+    )
+}
+
+fn voicing_options() -> impl Strategy<Value = VoicingOptions> {
+    (1i8..=5, 0usize..4, any::<bool>()).prop_map(|(octave, inversion, spread)| VoicingOptions {
+        octave,
+        inversion,
+        spread,
+    })
+}
+
+/// Every detectable chord made of exactly these pitch classes.
+fn readings(pitch_classes: &HashSet<u8>) -> Vec<Chord> {
+    let wanted: Vec<u8> = pitch_classes.iter().copied().collect();
+    Chord::containing(&wanted)
+        .into_iter()
+        .filter(|chord| chord.pitch_classes() == *pitch_classes)
+        .collect()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(500))]
+
+    #[test]
+    fn test_detect_recovers_the_chord((chord, notes) in voiced_chord()) {
+        let held: HashSet<u8> = notes.iter().copied().collect();
+        let detected = Chord::detect(&held);
+        prop_assert!(detected.is_some(), "nothing detected");
+        let detected = detected.unwrap();
+
+        prop_assert_eq!(detected.pitch_classes(), chord.pitch_classes());
+        let bass = detected.bass.unwrap_or(detected.root).pitch_class();
+        prop_assert_eq!(bass, notes[0] % 12, "{} doesn't name the lowest note", detected);
+        // Sixths, sus chords and symmetric chords have several right names
+        if readings(&chord.pitch_classes()).len() == 1 {
+            prop_assert_eq!(
+                (detected.quality, detected.root.pitch_class()),
+                (chord.quality, chord.root.pitch_class()),
+                "detected {} for {}", detected, chord
+            );
+        }
+    }
+
+    #[test]
+    fn test_names_round_trip((_, notes) in voiced_chord()) {
+        let held: HashSet<u8> = notes.iter().copied().collect();
+        if let Some(detected) = Chord::detect(&held) {
+            let name = detected.name();
+            let parsed = Chord::from_name(&name);
+            prop_assert!(parsed.is_some(), "'{}' doesn't parse", name);
+            let parsed = parsed.unwrap();
+
+            prop_assert_eq!(parsed.name(), name);
+            prop_assert_eq!(parsed.quality, detected.quality);
+            prop_assert_eq!(parsed.root.pitch_class(), detected.root.pitch_class());
+            prop_assert_eq!(
+                parsed.bass.map(|n| n.pitch_class()),
+                detected.bass.map(|n| n.pitch_class())
+            );
+        }
+    }
+
+    #[test]
+    fn test_stacked_voicings((chord, notes) in voiced_chord()) {
+        let classes: HashSet<u8> = notes.iter().map(|n| n % 12).collect();
+        prop_assert_eq!(classes, chord.pitch_classes());
+        prop_assert!(notes.windows(2).all(|w| w[0] < w[1]), "{:?} isn't ascending", notes);
+    }
+
+    #[test]
+    fn test_voicing_puts_the_bass_lowest(
+        chord in chord(),
+        bass in 0u8..12,
+        options in voicing_options(),
+    ) {
+        let chord = chord.with_bass(Note::new(60 + bass));
+        let notes = chord.voicing(options);
+        prop_assert!(!notes.is_empty(), "no notes");
+        prop_assert_eq!(Some(notes[0] % 12), chord.bass.map(|b| b.pitch_class()));
+        for pc in chord.pitch_classes() {
+            prop_assert!(
+                notes.iter().any(|n| n % 12 == pc),
+                "{:?} is missing a tone of {}", notes, chord
+            );
+        }
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+use super::naming::NamingStyle;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Quality {
     Major,
     Minor,
@@ -11,33 +13,125 @@ pub enum Quality {
     HalfDim7,
     MinorMajor7,
     Augmented7,
+    Major6,
+    Minor6,
     Sus2,
     Sus4,
+    Quartal,
     Add9,
     Unknown,
 }
 
+/// Qualities grouped by how they sound, for colouring chord names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum QualityFamily {
+    Major,
+    Minor,
+    Dominant,
+    Diminished,
+    Suspended,
+}
+
 impl Quality {
+    pub fn family(&self) -> Option<QualityFamily> {
+        Some(match self {
+            Quality::Major
+            | Quality::Major7
+            | Quality::Major6
+            | Quality::Add9
+            | Quality::Augmented => QualityFamily::Major,
+            Quality::Minor | Quality::Minor7 | Quality::Minor6 | Quality::MinorMajor7 => {
+                QualityFamily::Minor
+            }
+            Quality::Dominant7 | Quality::Augmented7 => QualityFamily::Dominant,
+            Quality::Diminished | Quality::Diminished7 | Quality::HalfDim7 => {
+                QualityFamily::Diminished
+            }
+            Quality::Sus2 | Quality::Sus4 | Quality::Quartal => QualityFamily::Suspended,
+            Quality::Unknown => return None,
+        })
+    }
+
     pub fn symbol(&self) -> &'static str {
-        match self {
-            Quality::Major => "",
-            Quality::Minor => "m",
-            Quality::Diminished => "dim",
-            Quality::Augmented => "+",
-            Quality::Major7 => "maj7",
-            Quality::Minor7 => "m7",
-            Quality::Dominant7 => "7",
-            Quality::Diminished7 => "dim7",
-            Quality::HalfDim7 => "m7b5",
-            Quality::MinorMajor7 => "mMaj7",
-            Quality::Augmented7 => "+7",
-            Quality::Sus2 => "sus2",
-            Quality::Sus4 => "sus4",
-            Quality::Add9 => "add9",
-            Quality::Unknown => "?",
+        self.symbol_styled(NamingStyle::Plain)
+    }
+
+    pub fn symbol_styled(&self, style: NamingStyle) -> &'static str {
+        match style {
+            NamingStyle::Plain => match self {
+                Quality::Major => "",
+                Quality::Minor => "m",
+                Quality::Diminished => "dim",
+                Quality::Augmented => "+",
+                Quality::Major7 => "maj7",
+                Quality::Minor7 => "m7",
+                Quality::Dominant7 => "7",
+                Quality::Diminished7 => "dim7",
+                Quality::HalfDim7 => "m7b5",
+                Quality::MinorMajor7 => "mMaj7",
+                Quality::Augmented7 => "+7",
+                Quality::Major6 => "6",
+                Quality::Minor6 => "m6",
+                Quality::Sus2 => "sus2",
+                Quality::Sus4 => "sus4",
+                Quality::Quartal => "quartal",
+                Quality::Add9 => "add9",
+                Quality::Unknown => "?",
+            },
+            NamingStyle::Jazz => match self {
+                Quality::Minor => "min",
+                Quality::Augmented => "aug",
+                Quality::Minor7 => "min7",
+                Quality::HalfDim7 => "min7b5",
+                Quality::MinorMajor7 => "minMaj7",
+                Quality::Augmented7 => "aug7",
+                Quality::Minor6 => "min6",
+                _ => self.symbol(),
+            },
+            NamingStyle::Short => match self {
+                Quality::Minor => "-",
+                Quality::Diminished => "o",
+                Quality::Major7 => "M7",
+                Quality::Minor7 => "-7",
+                Quality::Diminished7 => "o7",
+                Quality::HalfDim7 => "-7b5",
+                Quality::MinorMajor7 => "-M7",
+                Quality::Minor6 => "-6",
+                Quality::Sus4 => "sus",
+                _ => self.symbol(),
+            },
+            NamingStyle::Unicode => match self {
+                Quality::Diminished => "°",
+                Quality::Major7 => "Δ7",
+                Quality::Diminished7 => "°7",
+                Quality::HalfDim7 => "ø7",
+                Quality::MinorMajor7 => "mΔ7",
+                _ => self.symbol(),
+            },
         }
     }
 
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        let styled = NamingStyle::all().iter().find_map(|&style| {
+            Self::all()
+                .iter()
+                .copied()
+                .find(|q| q.symbol_styled(style) == symbol)
+        });
+
+        styled.or(match symbol {
+            "M" | "maj" => Some(Quality::Major),
+            "mi" | "−" => Some(Quality::Minor),
+            "Δ" | "^7" | "ma7" => Some(Quality::Major7),
+            "mi7" | "m-7" => Some(Quality::Minor7),
+            "dom7" => Some(Quality::Dominant7),
+            "ø" | "m7-5" => Some(Quality::HalfDim7),
+            "mM7" | "m(maj7)" => Some(Quality::MinorMajor7),
+            "7#5" => Some(Quality::Augmented7),
+            _ => None,
+        })
+    }
+
     pub fn intervals(&self) -> &'static [u8] {
         match self {
             Quality::Major => &[0, 4, 7],
@@ -51,13 +145,38 @@ impl Quality {
             Quality::HalfDim7 => &[0, 3, 6, 10],
             Quality::MinorMajor7 => &[0, 3, 7, 11],
             Quality::Augmented7 => &[0, 4, 8, 10],
+            Quality::Major6 => &[0, 4, 7, 9],
+            Quality::Minor6 => &[0, 3, 7, 9],
             Quality::Sus2 => &[0, 2, 7],
             Quality::Sus4 => &[0, 5, 7],
+            Quality::Quartal => &[0, 5, 10],
             Quality::Add9 => &[0, 4, 7, 14],
             Quality::Unknown => &[],
         }
     }
 
+    pub fn all() -> &'static [Quality] {
+        &[
+            Quality::Major,
+            Quality::Minor,
+            Quality::Diminished,
+            Quality::Augmented,
+            Quality::Major7,
+            Quality::Minor7,
+            Quality::Dominant7,
+            Quality::Diminished7,
+            Quality::HalfDim7,
+            Quality::MinorMajor7,
+            Quality::Augmented7,
+            Quality::Major6,
+            Quality::Minor6,
+            Quality::Sus2,
+            Quality::Sus4,
+            Quality::Quartal,
+            Quality::Add9,
+        ]
+    }
+
     pub fn all_triads() -> &'static [Quality] {
         &[
             Quality::Major,
@@ -80,6 +199,10 @@ impl Quality {
             Quality::Augmented7,
         ]
     }
+
+    pub fn all_sixths() -> &'static [Quality] {
+        &[Quality::Major6, Quality::Minor6]
+    }
 }
 
 #[cfg(test)]
@@ -97,6 +220,16 @@ mod tests {
         assert_eq!(Quality::Minor7.intervals(), &[0, 3, 7, 10]);
     }
 
+    #[test]
+    fn test_families() {
+        assert_eq!(Quality::Major7.family(), Some(QualityFamily::Major));
+        assert_eq!(Quality::Minor6.family(), Some(QualityFamily::Minor));
+        assert_eq!(Quality::Dominant7.family(), Some(QualityFamily::Dominant));
+        assert_eq!(Quality::HalfDim7.family(), Some(QualityFamily::Diminished));
+        assert_eq!(Quality::Quartal.family(), Some(QualityFamily::Suspended));
+        assert_eq!(Quality::Unknown.family(), None);
+    }
+
     #[test]
     fn test_quality_symbol() {
         assert_eq!(Quality::Major.symbol(), "");
@@ -108,4 +241,30 @@ mod tests {
         assert_eq!(Quality::Minor7.symbol(), "m7");
         assert_eq!(Quality::HalfDim7.symbol(), "m7b5");
     }
+
+    #[test]
+    fn test_quality_symbol_styled() {
+        assert_eq!(Quality::Minor7.symbol_styled(NamingStyle::Jazz), "min7");
+        assert_eq!(Quality::Minor7.symbol_styled(NamingStyle::Short), "-7");
+        assert_eq!(Quality::Major7.symbol_styled(NamingStyle::Unicode), "Δ7");
+        assert_eq!(Quality::HalfDim7.symbol_styled(NamingStyle::Unicode), "ø7");
+        assert_eq!(Quality::Dominant7.symbol_styled(NamingStyle::Jazz), "7");
+    }
+
+    #[test]
+    fn test_from_symbol_all_styles() {
+        for &style in NamingStyle::all() {
+            for &quality in Quality::all() {
+                assert_eq!(
+                    Quality::from_symbol(quality.symbol_styled(style)),
+                    Some(quality),
+                    "{:?} in {:?}",
+                    quality,
+                    style
+                );
+            }
+        }
+        assert_eq!(Quality::from_symbol("dom7"), Some(Quality::Dominant7));
+        assert_eq!(Quality::from_symbol("xyz"), None);
+    }
 }
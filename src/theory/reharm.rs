@@ -0,0 +1,173 @@
+use super::chord::Chord;
+use super::note::Note;
+use super::quality::Quality;
+
+/// A way of reharmonizing a progression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Technique {
+    /// Dominants swapped for the dominant a tritone away
+    TritoneSub,
+    /// Chords inside the progression swapped for their relative major or
+    /// minor
+    Relative,
+    /// A diminished seventh slipped between chords a whole step apart
+    PassingDiminished,
+    /// Minor chords stretched over a falling chromatic line
+    LineCliche,
+}
+
+impl Technique {
+    pub fn all() -> &'static [Technique] {
+        &[
+            Technique::TritoneSub,
+            Technique::Relative,
+            Technique::PassingDiminished,
+            Technique::LineCliche,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::TritoneSub => "tritone subs",
+            Technique::Relative => "relative subs",
+            Technique::PassingDiminished => "passing dim",
+            Technique::LineCliche => "line cliché",
+        }
+    }
+
+    /// The progression reharmonized, each chord marked with whether it's
+    /// new; `None` when the technique finds nothing to change.
+    pub fn apply(&self, chords: &[Chord]) -> Option<Reharmonization> {
+        let mut out: Vec<(Chord, bool)> = Vec::new();
+        for (i, chord) in chords.iter().enumerate() {
+            let next = chords.get(i + 1);
+            let inner = i > 0 && i + 1 < chords.len();
+            match self {
+                Technique::TritoneSub if is_dominant(chord, next) => {
+                    out.push((shifted(chord, 6, Quality::Dominant7), true));
+                }
+                Technique::Relative if inner => match relative(chord) {
+                    Some(sub) => out.push((sub, true)),
+                    None => out.push((chord.clone(), false)),
+                },
+                Technique::PassingDiminished => {
+                    out.push((chord.clone(), false));
+                    if next.is_some_and(|n| interval(chord, n) == 2) {
+                        out.push((shifted(chord, 1, Quality::Diminished7), true));
+                    }
+                }
+                Technique::LineCliche if is_minor_triad(chord) => {
+                    out.push((chord.clone(), false));
+                    for quality in [Quality::MinorMajor7, Quality::Minor7, Quality::Minor6] {
+                        out.push((shifted(chord, 0, quality), true));
+                    }
+                }
+                _ => out.push((chord.clone(), false)),
+            }
+        }
+        out.iter().any(|(_, new)| *new).then(|| Reharmonization {
+            technique: *self,
+            chords: out.iter().map(|(c, _)| c.clone()).collect(),
+            changed: out.iter().map(|(_, new)| *new).collect(),
+        })
+    }
+}
+
+/// A progression reworked by one technique, with the chords it brought in
+/// marked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reharmonization {
+    pub technique: Technique,
+    pub chords: Vec<Chord>,
+    pub changed: Vec<bool>,
+}
+
+/// Every technique that changes something in the progression, in the
+/// order of [`Technique::all`].
+pub fn reharmonize(chords: &[Chord]) -> Vec<Reharmonization> {
+    Technique::all()
+        .iter()
+        .filter_map(|t| t.apply(chords))
+        .collect()
+}
+
+fn interval(from: &Chord, to: &Chord) -> u8 {
+    (to.root.pitch_class() + 12 - from.root.pitch_class()) % 12
+}
+
+fn shifted(chord: &Chord, semitones: u8, quality: Quality) -> Chord {
+    Chord::new(
+        Note::new(60 + (chord.root.pitch_class() + semitones) % 12),
+        quality,
+    )
+}
+
+/// A dominant seventh, or a major triad resolving down a fifth.
+fn is_dominant(chord: &Chord, next: Option<&Chord>) -> bool {
+    chord.quality == Quality::Dominant7
+        || (chord.quality == Quality::Major && next.is_some_and(|n| interval(chord, n) == 5))
+}
+
+fn is_minor_triad(chord: &Chord) -> bool {
+    chord.quality == Quality::Minor
+}
+
+fn relative(chord: &Chord) -> Option<Chord> {
+    match chord.quality {
+        Quality::Major => Some(shifted(chord, 9, Quality::Minor)),
+        Quality::Major7 => Some(shifted(chord, 9, Quality::Minor7)),
+        Quality::Minor => Some(shifted(chord, 3, Quality::Major)),
+        Quality::Minor7 => Some(shifted(chord, 3, Quality::Major7)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chords(names: &[&str]) -> Vec<Chord> {
+        names.iter().map(|n| Chord::from_name(n).unwrap()).collect()
+    }
+
+    fn names(reharm: &Reharmonization) -> Vec<String> {
+        reharm.chords.iter().map(|c| c.name()).collect()
+    }
+
+    #[test]
+    fn test_tritone_subs() {
+        let reharm = Technique::TritoneSub
+            .apply(&chords(&["C", "A7", "Dm", "G7", "C"]))
+            .unwrap();
+        assert_eq!(names(&reharm), ["C", "D#7", "Dm", "C#7", "C"]);
+        assert_eq!(reharm.changed, [false, true, false, true, false]);
+    }
+
+    #[test]
+    fn test_relative_subs() {
+        let reharm = Technique::Relative
+            .apply(&chords(&["C", "F", "Dm", "C"]))
+            .unwrap();
+        assert_eq!(names(&reharm), ["C", "Dm", "F", "C"]);
+    }
+
+    #[test]
+    fn test_passing_diminished() {
+        let reharm = Technique::PassingDiminished
+            .apply(&chords(&["C", "Dm", "F", "G"]))
+            .unwrap();
+        assert_eq!(names(&reharm), ["C", "C#dim7", "Dm", "F", "F#dim7", "G"]);
+    }
+
+    #[test]
+    fn test_line_cliche() {
+        let reharm = Technique::LineCliche.apply(&chords(&["Am", "D"])).unwrap();
+        assert_eq!(names(&reharm), ["Am", "AmMaj7", "Am7", "Am6", "D"]);
+    }
+
+    #[test]
+    fn test_nothing_to_change() {
+        assert!(reharmonize(&chords(&["C", "E"])).is_empty());
+        assert_eq!(reharmonize(&chords(&["C", "Am", "F", "G7", "C"])).len(), 4);
+    }
+}
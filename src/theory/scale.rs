@@ -0,0 +1,181 @@
+use super::chord::Chord;
+use super::note::Note;
+use super::quality::Quality;
+use super::spelling::{NoteNames, Spelling};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ScaleKind {
+    #[default]
+    Major,
+    Minor,
+    /// I7, IV7 and V7 count as in the key, over a mixolydian scale
+    Blues,
+}
+
+impl ScaleKind {
+    pub fn intervals(&self) -> &'static [u8] {
+        match self {
+            ScaleKind::Major => &[0, 2, 4, 5, 7, 9, 11],
+            ScaleKind::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            ScaleKind::Blues => &[0, 2, 4, 5, 7, 9, 10],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Scale {
+    pub tonic: Note,
+    pub kind: ScaleKind,
+}
+
+impl Scale {
+    pub fn new(tonic: Note, kind: ScaleKind) -> Self {
+        Self { tonic, kind }
+    }
+
+    pub fn major(tonic: Note) -> Self {
+        Self::new(tonic, ScaleKind::Major)
+    }
+
+    pub fn name(&self) -> String {
+        self.name_in(NoteNames::English)
+    }
+
+    pub fn name_in(&self, names: NoteNames) -> String {
+        let tonic = self.tonic.name_in(Spelling::for_key(self.tonic), names);
+        match self.kind {
+            ScaleKind::Major => tonic.to_string(),
+            ScaleKind::Minor => format!("{}m", tonic),
+            ScaleKind::Blues => format!("{} blues", tonic),
+        }
+    }
+
+    pub fn contains(&self, pitch_class: u8) -> bool {
+        let degree = (pitch_class % 12 + 12 - self.tonic.pitch_class()) % 12;
+        self.kind.intervals().contains(&degree)
+    }
+
+    pub fn contains_chord(&self, chord: &Chord) -> bool {
+        if self.kind == ScaleKind::Blues {
+            return self.is_blues_chord(chord);
+        }
+        chord
+            .quality
+            .intervals()
+            .iter()
+            .all(|&i| self.contains(chord.root.pitch_class() + i))
+    }
+
+    /// A major or dominant chord on I, IV or V: the blue notes make the
+    /// flat seventh on every one of them at home.
+    fn is_blues_chord(&self, chord: &Chord) -> bool {
+        let degree = (chord.root.pitch_class() + 12 - self.tonic.pitch_class()) % 12;
+        [0, 5, 7].contains(&degree)
+            && matches!(
+                chord.quality,
+                Quality::Major | Quality::Dominant7 | Quality::Major6 | Quality::Add9
+            )
+    }
+
+    /// The chord built in thirds on scale degree `degree` (0 is the tonic),
+    /// a triad or with `sevenths` a seventh chord.
+    pub fn diatonic(&self, degree: usize, sevenths: bool) -> Chord {
+        if self.kind == ScaleKind::Blues && [0, 3, 4].contains(&degree) {
+            let root = self.kind.intervals()[degree];
+            return Chord::new(
+                Note::new(60 + (self.tonic.pitch_class() + root) % 12),
+                Quality::Dominant7,
+            );
+        }
+        let steps = self.kind.intervals();
+        let at = |step: usize| steps[(degree + step) % 7] + if degree + step >= 7 { 12 } else { 0 };
+        let root = at(0);
+        let stack: Vec<u8> = (0..if sevenths { 4 } else { 3 })
+            .map(|i| at(2 * i) - root)
+            .collect();
+
+        let quality = Quality::all()
+            .iter()
+            .copied()
+            .find(|q| q.intervals() == stack.as_slice())
+            .unwrap_or(Quality::Unknown);
+        Chord::new(
+            Note::new(60 + (self.tonic.pitch_class() + root) % 12),
+            quality,
+        )
+    }
+
+    pub fn fit(chords: &[Chord], prefer: Option<Note>) -> Option<Scale> {
+        let preferred = prefer.map(|n| n.pitch_class()).unwrap_or(0);
+
+        (0..12u8)
+            .map(|offset| Scale::major(Note::new(60 + (preferred + offset) % 12)))
+            .find(|scale| chords.iter().all(|c| scale.contains_chord(c)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(name: &str) -> Chord {
+        Chord::from_name(name).unwrap()
+    }
+
+    #[test]
+    fn test_contains_chord() {
+        let c = Scale::major(Note::new(60));
+        assert!(c.contains_chord(&chord("Am")));
+        assert!(c.contains_chord(&chord("G7")));
+        assert!(!c.contains_chord(&chord("Eb")));
+        assert!(!c.contains_chord(&chord("D")));
+
+        let cm = Scale::new(Note::new(60), ScaleKind::Minor);
+        assert!(cm.contains_chord(&chord("Eb")));
+        assert_eq!(cm.name(), "Cm");
+    }
+
+    #[test]
+    fn test_diatonic() {
+        let names = |scale: Scale, sevenths: bool| -> Vec<String> {
+            (0..7).map(|d| scale.diatonic(d, sevenths).name()).collect()
+        };
+        let c = Scale::major(Note::new(60));
+        assert_eq!(names(c, false), ["C", "Dm", "Em", "F", "G", "Am", "Bdim"]);
+        assert_eq!(
+            names(c, true),
+            ["Cmaj7", "Dm7", "Em7", "Fmaj7", "G7", "Am7", "Bm7b5"]
+        );
+        let am = Scale::new(Note::new(69), ScaleKind::Minor);
+        assert_eq!(names(am, false), ["Am", "Bdim", "C", "Dm", "Em", "F", "G"]);
+        let blues = Scale::new(Note::new(67), ScaleKind::Blues);
+        assert_eq!(
+            names(blues, true),
+            ["G7", "Am7", "Bm7b5", "C7", "D7", "Em7", "Fmaj7"]
+        );
+    }
+
+    #[test]
+    fn test_blues_chords() {
+        let blues = Scale::new(Note::new(60), ScaleKind::Blues);
+        assert_eq!(blues.name(), "C blues");
+        for name in ["C7", "F7", "G7", "C", "F6"] {
+            assert!(blues.contains_chord(&chord(name)), "{}", name);
+        }
+        assert!(!blues.contains_chord(&chord("Dm7")));
+        assert!(!blues.contains_chord(&chord("Cmaj7")));
+    }
+
+    #[test]
+    fn test_fit() {
+        let chords = [chord("Eb"), chord("Bb"), chord("Cm")];
+        let scale = Scale::fit(&chords, Some(Note::new(60))).unwrap();
+        assert_eq!(scale.tonic.pitch_class(), 3);
+
+        assert_eq!(
+            Scale::fit(&[chord("C")], Some(Note::new(67))).map(|s| s.name()),
+            Some("G".to_string())
+        );
+        assert!(Scale::fit(&[chord("C"), chord("C#")], None).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use rhai::{Array, Dynamic, Engine, Scope, AST};
+
+use super::chord::Chord;
+use super::note::Note;
+use super::progression::Suggester;
+use crate::paths;
+
+pub struct ScriptSuggester {
+    name: String,
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptSuggester {
+    pub fn from_source(name: &str, source: &str) -> Result<Self> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(100_000);
+        engine.register_fn("roman", |numeral: &str, key: &str| -> String {
+            Note::from_pitch_name(key)
+                .and_then(|k| Chord::from_roman(numeral, k))
+                .map(|c| c.name())
+                .unwrap_or_default()
+        });
+        engine.register_fn("numeral", |chord: &str, key: &str| -> String {
+            match (Chord::from_name(chord), Note::from_pitch_name(key)) {
+                (Some(c), Some(k)) => c.roman_numeral(k),
+                _ => String::new(),
+            }
+        });
+
+        let ast = engine
+            .compile(source)
+            .map_err(|e| anyhow!("{}: {}", name, e))?;
+        if !ast.iter_functions().any(|f| f.name == "suggest") {
+            return Err(anyhow!("{}: script does not define fn suggest", name));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            engine,
+            ast,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "script".to_string());
+        Self::from_source(&name, &fs::read_to_string(path)?)
+    }
+
+    pub fn find(profile: &str) -> Result<Self> {
+        let path = paths::scripts_dir().join(format!("{}.rhai", profile));
+        Self::load(&path).map_err(|e| anyhow!("Profile '{}' ({}): {}", profile, path.display(), e))
+    }
+}
+
+impl Suggester for ScriptSuggester {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn suggest(&self, current: &Chord, key: Note, history: &[Chord]) -> Result<Vec<Chord>> {
+        let history: Array = history.iter().map(|c| Dynamic::from(c.name())).collect();
+
+        let result: Array = self
+            .engine
+            .call_fn(
+                &mut Scope::new(),
+                &self.ast,
+                "suggest",
+                (current.name(), key.name().to_string(), history),
+            )
+            .map_err(|e| anyhow!("{}: {}", self.name, e))?;
+
+        Ok(result
+            .into_iter()
+            .filter_map(|d| d.into_string().ok())
+            .filter_map(|name| Chord::from_name(&name))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BACKDOOR: &str = r#"
+        fn suggest(current, key, history) {
+            if numeral(current, key) == "IV" {
+                [roman("iv", key), roman("bVII7", key)]
+            } else if history.len() > 2 {
+                [roman("I", key), "Bb7"]
+            } else {
+                []
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_script_suggestions() {
+        let script = ScriptSuggester::from_source("backdoor", BACKDOOR).unwrap();
+        let key = Note::new(60);
+
+        let f = Chord::from_name("F").unwrap();
+        let names: Vec<String> = script
+            .suggest(&f, key, &[])
+            .unwrap()
+            .iter()
+            .map(|c| c.name())
+            .collect();
+        assert_eq!(names, vec!["Fm", "A#7"]);
+
+        let c = Chord::from_name("C").unwrap();
+        assert!(script.suggest(&c, key, &[]).unwrap().is_empty());
+        let history = vec![c.clone(), c.clone(), c.clone()];
+        assert_eq!(script.suggest(&c, key, &history).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_scripts() {
+        assert!(ScriptSuggester::from_source("bad", "fn suggest(").is_err());
+        assert!(ScriptSuggester::from_source("none", "fn other() { 1 }").is_err());
+
+        let looping =
+            ScriptSuggester::from_source("loop", "fn suggest(c, k, h) { loop { } }").unwrap();
+        let c = Chord::from_name("C").unwrap();
+        assert!(looping.suggest(&c, Note::new(60), &[]).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use super::chord::Chord;
+use super::note::Note;
+use super::quality::Quality;
+use super::scale::Scale;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    Relative,
+    Parallel,
+    Extension,
+    Passing,
+    CommonTone,
+}
+
+impl Relation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Relation::Relative => "relative",
+            Relation::Parallel => "parallel",
+            Relation::Extension => "extension",
+            Relation::Passing => "passing",
+            Relation::CommonTone => "common tone",
+        }
+    }
+
+    fn bonus(&self) -> f32 {
+        match self {
+            Relation::Relative => 1.0,
+            Relation::Parallel => 0.75,
+            Relation::Passing => 0.5,
+            Relation::Extension => 0.25,
+            Relation::CommonTone => 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Similar {
+    pub chord: Chord,
+    pub shared: usize,
+    pub relation: Relation,
+    pub score: f32,
+}
+
+pub fn similar(chord: &Chord, key: Option<Note>) -> Vec<Similar> {
+    let tones = chord.pitch_classes();
+    let mut results = Vec::new();
+
+    for root in 0..12u8 {
+        for &quality in Quality::all() {
+            if root == chord.root.pitch_class() && quality == chord.quality {
+                continue;
+            }
+
+            let candidate = Chord::new(Note::new(60 + root), quality);
+            let shared = candidate.pitch_classes().intersection(&tones).count();
+            if shared < 2 {
+                continue;
+            }
+
+            let relation = relation(chord, &candidate);
+            let mut score = shared as f32 + relation.bonus();
+            if key.is_some_and(|k| Scale::major(k).contains_chord(&candidate)) {
+                score += 1.0;
+            }
+
+            results.push(Similar {
+                chord: candidate,
+                shared,
+                relation,
+                score,
+            });
+        }
+    }
+
+    results.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(
+                chord
+                    .distance(&a.chord)
+                    .total_cmp(&chord.distance(&b.chord)),
+            )
+            .then(a.chord.root.midi.cmp(&b.chord.root.midi))
+    });
+    results
+}
+
+fn is_major(quality: Quality) -> bool {
+    matches!(quality, Quality::Major | Quality::Major7)
+}
+
+fn is_minor(quality: Quality) -> bool {
+    matches!(quality, Quality::Minor | Quality::Minor7)
+}
+
+fn relation(from: &Chord, to: &Chord) -> Relation {
+    let interval = (to.root.pitch_class() + 12 - from.root.pitch_class()) % 12;
+
+    if matches!(to.quality, Quality::Diminished | Quality::Diminished7) {
+        return Relation::Passing;
+    }
+
+    if (is_major(from.quality) && is_minor(to.quality) && interval == 9)
+        || (is_minor(from.quality) && is_major(to.quality) && interval == 3)
+    {
+        return Relation::Relative;
+    }
+
+    if interval == 0 {
+        if (is_major(from.quality) && is_minor(to.quality))
+            || (is_minor(from.quality) && is_major(to.quality))
+        {
+            return Relation::Parallel;
+        }
+        return Relation::Extension;
+    }
+
+    Relation::CommonTone
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(results: &'a [Similar], name: &str) -> Option<&'a Similar> {
+        results.iter().find(|s| s.chord.name() == name)
+    }
+
+    #[test]
+    fn test_relative_and_parallel() {
+        let c = Chord::from_name("C").unwrap();
+        let results = similar(&c, Some(Note::new(60)));
+
+        let am = find(&results, "Am").unwrap();
+        assert_eq!(am.relation, Relation::Relative);
+        assert_eq!(am.shared, 2);
+
+        let cm = find(&results, "Cm").unwrap();
+        assert_eq!(cm.relation, Relation::Parallel);
+
+        assert!(find(&results, "C").is_none());
+        assert!(find(&results, "F#").is_none());
+    }
+
+    #[test]
+    fn test_ranking_prefers_diatonic() {
+        let c = Chord::from_name("C").unwrap();
+        let results = similar(&c, Some(Note::new(60)));
+
+        let am = results.iter().position(|s| s.chord.name() == "Am").unwrap();
+        let cm = results.iter().position(|s| s.chord.name() == "Cm").unwrap();
+        assert!(am < cm);
+        assert!(results.iter().all(|s| s.shared >= 2));
+    }
+
+    #[test]
+    fn test_diminished_passing() {
+        let g7 = Chord::from_name("G7").unwrap();
+        let results = similar(&g7, None);
+
+        let bdim7 = find(&results, "Bdim7").unwrap();
+        assert_eq!(bdim7.relation, Relation::Passing);
+        assert_eq!(bdim7.shared, 3);
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::{HashSet, VecDeque};
+use std::time::Instant;
+
+use super::chord::Chord;
+use super::detection::Detection;
+
+/// Note changes the smoother looks back over
+const MAX_FRAMES: usize = 16;
+/// Log-likelihood per second a frame's notes lend their evidence at,
+/// and the most one frame can lend
+const EVIDENCE_PER_SECOND: f32 = 4.0;
+const MAX_FRAME_SECONDS: f32 = 1.0;
+/// Log-probability cost of changing chord between two frames
+const SWITCH_COST: f32 = 3.0;
+/// Log-likelihood of each sounding note outside a chord and each chord tone
+/// not sounding
+const FOREIGN_TONE: f32 = -2.3;
+const MISSING_TONE: f32 = -0.7;
+
+/// The notes sounding from one note change to the next.
+#[derive(Clone, Debug)]
+struct Frame {
+    pitch_classes: HashSet<u8>,
+    detection: Option<Detection>,
+    start: Instant,
+}
+
+/// Viterbi smoothing of chord readings over the last few note changes.
+/// Each frame is scored against every chord read in the window, weighted by
+/// how long it sounded, and changing chord costs a fixed penalty, so a
+/// passing tone has to be held a while before it counts as a new chord.
+#[derive(Clone, Debug, Default)]
+pub struct ChordSmoother {
+    frames: VecDeque<Frame>,
+}
+
+impl ChordSmoother {
+    pub fn push(&mut self, notes: &HashSet<u8>, detection: Option<Detection>, at: Instant) {
+        self.frames.push_back(Frame {
+            pitch_classes: notes.iter().map(|n| n % 12).collect(),
+            detection,
+            start: at,
+        });
+        while self.frames.len() > MAX_FRAMES {
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// The chords read in the window, oldest first, latest reading of each.
+    fn states(&self) -> Vec<Detection> {
+        let mut states: Vec<Detection> = Vec::new();
+        for detection in self.frames.iter().filter_map(|f| f.detection.as_ref()) {
+            match states
+                .iter_mut()
+                .find(|s| s.chord.name() == detection.chord.name())
+            {
+                Some(state) => *state = detection.clone(),
+                None => states.push(detection.clone()),
+            }
+        }
+        states
+    }
+
+    fn weight(&self, index: usize, now: Instant) -> f32 {
+        let end = self.frames.get(index + 1).map_or(now, |f| f.start);
+        let seconds = end
+            .saturating_duration_since(self.frames[index].start)
+            .as_secs_f32();
+        seconds.min(MAX_FRAME_SECONDS) * EVIDENCE_PER_SECOND
+    }
+
+    /// The most likely chord for each frame, oldest first.
+    pub fn sequence(&self, now: Instant) -> Vec<Chord> {
+        let states = self.states();
+        self.viterbi(&states, now)
+            .into_iter()
+            .map(|s| states[s].chord.clone())
+            .collect()
+    }
+
+    /// The most likely chord sounding now.
+    pub fn current(&self, now: Instant) -> Option<Detection> {
+        let states = self.states();
+        let last = *self.viterbi(&states, now).last()?;
+        Some(states[last].clone())
+    }
+
+    fn viterbi(&self, states: &[Detection], now: Instant) -> Vec<usize> {
+        if states.is_empty() {
+            return Vec::new();
+        }
+        let mut scores = vec![0.0; states.len()];
+        let mut back: Vec<Vec<usize>> = Vec::with_capacity(self.frames.len());
+        for (i, frame) in self.frames.iter().enumerate() {
+            let weight = self.weight(i, now);
+            let previous = scores.clone();
+            let mut pointers = Vec::with_capacity(states.len());
+            for (s, state) in states.iter().enumerate() {
+                // Staying wins ties, as does the older chord
+                let mut from = s;
+                let mut best = previous[s];
+                for (p, &score) in previous.iter().enumerate() {
+                    if score - SWITCH_COST > best {
+                        from = p;
+                        best = score - SWITCH_COST;
+                    }
+                }
+                // The window starts on whatever was read then
+                if i == 0 {
+                    let read = frame.detection.as_ref().map(|d| d.chord.name());
+                    best = match read {
+                        Some(name) if name != state.chord.name() => -SWITCH_COST,
+                        _ => 0.0,
+                    };
+                }
+                scores[s] = best + weight * likelihood(&frame.pitch_classes, &state.chord);
+                pointers.push(from);
+            }
+            back.push(pointers);
+        }
+
+        let mut state =
+            (0..states.len()).fold(0, |best, s| if scores[s] > scores[best] { s } else { best });
+        let mut path = vec![state; self.frames.len()];
+        for i in (1..self.frames.len()).rev() {
+            state = back[i][state];
+            path[i - 1] = state;
+        }
+        path
+    }
+}
+
+/// Log-likelihood of hearing `pitch_classes` while `chord` is the harmony.
+fn likelihood(pitch_classes: &HashSet<u8>, chord: &Chord) -> f32 {
+    if pitch_classes.is_empty() {
+        return 0.0;
+    }
+    let mut tones = chord.pitch_classes();
+    if let Some(bass) = chord.bass {
+        tones.insert(bass.pitch_class());
+    }
+    let foreign = pitch_classes.difference(&tones).count() as f32;
+    let missing = tones.difference(pitch_classes).count() as f32;
+    foreign * FOREIGN_TONE + missing * MISSING_TONE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::Preferences;
+    use std::time::Duration;
+
+    fn push(smoother: &mut ChordSmoother, notes: &[u8], at: Instant) {
+        let notes: HashSet<u8> = notes.iter().copied().collect();
+        let detection = Chord::analyze(&notes, &Preferences::default());
+        smoother.push(&notes, detection, at);
+    }
+
+    fn current(smoother: &ChordSmoother, now: Instant) -> String {
+        smoother.current(now).unwrap().chord.name()
+    }
+
+    #[test]
+    fn test_passing_tone_is_smoothed() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut smoother = ChordSmoother::default();
+        push(&mut smoother, &[60, 64, 67], ms(0));
+        // D passing through over C major for a moment
+        push(&mut smoother, &[60, 64, 67, 74], ms(800));
+        assert_eq!(current(&smoother, ms(900)), "C");
+        push(&mut smoother, &[60, 64, 67, 76], ms(950));
+        assert_eq!(current(&smoother, ms(1200)), "C");
+        assert_eq!(
+            smoother
+                .sequence(ms(1200))
+                .iter()
+                .map(|c| c.name())
+                .collect::<Vec<_>>(),
+            vec!["C", "C", "C"]
+        );
+    }
+
+    #[test]
+    fn test_held_change_goes_through() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut smoother = ChordSmoother::default();
+        push(&mut smoother, &[60, 64, 67], ms(0));
+        push(&mut smoother, &[65, 69, 72], ms(800));
+        assert_eq!(current(&smoother, ms(810)), "C");
+        assert_eq!(current(&smoother, ms(1000)), "F");
+
+        // A held added tone becomes the chord after a while
+        push(&mut smoother, &[65, 69, 72, 76], ms(2000));
+        assert_eq!(current(&smoother, ms(2100)), "F");
+        assert_eq!(current(&smoother, ms(3000)), "Fmaj7");
+    }
+
+    #[test]
+    fn test_empty() {
+        let smoother = ChordSmoother::default();
+        assert!(smoother.current(Instant::now()).is_none());
+        assert!(smoother.sequence(Instant::now()).is_empty());
+    }
+}
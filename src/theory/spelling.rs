@@ -0,0 +1,196 @@
+use serde::Deserialize;
+
+use super::note::Note;
+
+const SHARP_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+const FLAT_NAMES: [&str; 12] = [
+    "C", "Db", "D", "Eb", "E", "F", "Gb", "G", "Ab", "A", "Bb", "B",
+];
+
+const GERMAN_SHARP_NAMES: [&str; 12] = [
+    "C", "Cis", "D", "Dis", "E", "F", "Fis", "G", "Gis", "A", "Ais", "H",
+];
+
+const GERMAN_FLAT_NAMES: [&str; 12] = [
+    "C", "Des", "D", "Es", "E", "F", "Ges", "G", "As", "A", "B", "H",
+];
+
+const SOLFEGE_SHARP_NAMES: [&str; 12] = [
+    "Do", "Do#", "Re", "Re#", "Mi", "Fa", "Fa#", "Sol", "Sol#", "La", "La#", "Si",
+];
+
+const SOLFEGE_FLAT_NAMES: [&str; 12] = [
+    "Do", "Reb", "Re", "Mib", "Mi", "Fa", "Solb", "Sol", "Lab", "La", "Sib", "Si",
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Spelling {
+    #[default]
+    Sharp,
+    Flat,
+}
+
+impl Spelling {
+    pub fn names(&self) -> &'static [&'static str; 12] {
+        match self {
+            Spelling::Sharp => &SHARP_NAMES,
+            Spelling::Flat => &FLAT_NAMES,
+        }
+    }
+
+    pub fn for_key(key: Note) -> Self {
+        match key.pitch_class() {
+            1 | 3 | 5 | 8 | 10 => Spelling::Flat,
+            _ => Spelling::Sharp,
+        }
+    }
+
+    pub fn of_name(name: &str) -> Self {
+        let is_flat = name
+            .split('/')
+            .filter_map(|part| parse_pitch(part.trim()))
+            .any(|(_, spelling, _)| spelling == Spelling::Flat);
+
+        if is_flat {
+            Spelling::Flat
+        } else {
+            Spelling::Sharp
+        }
+    }
+}
+
+/// Which names notes are shown with, on screen and in the charts exported
+/// for reading. Chord names that are stored or parsed stay in letter names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteNames {
+    #[default]
+    English,
+    /// H for B, B for Bb, and -is/-es endings
+    German,
+    /// Fixed-do: Do, Re, Mi, Fa, Sol, La, Si
+    Solfege,
+}
+
+impl NoteNames {
+    pub fn all() -> &'static [NoteNames] {
+        &[NoteNames::English, NoteNames::German, NoteNames::Solfege]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoteNames::English => "english",
+            NoteNames::German => "german",
+            NoteNames::Solfege => "solfege",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::all().iter().copied().find(|n| n.name() == name)
+    }
+
+    pub fn names(&self, spelling: Spelling) -> &'static [&'static str; 12] {
+        match (self, spelling) {
+            (NoteNames::English, spelling) => spelling.names(),
+            (NoteNames::German, Spelling::Sharp) => &GERMAN_SHARP_NAMES,
+            (NoteNames::German, Spelling::Flat) => &GERMAN_FLAT_NAMES,
+            (NoteNames::Solfege, Spelling::Sharp) => &SOLFEGE_SHARP_NAMES,
+            (NoteNames::Solfege, Spelling::Flat) => &SOLFEGE_FLAT_NAMES,
+        }
+    }
+}
+
+/// The letter and accidentals a name starts with, as semitones above C,
+/// and what follows them. Sharps and flats don't mix, and at most a double
+/// one is accepted.
+pub(crate) fn parse_pitch(text: &str) -> Option<(i8, Spelling, &str)> {
+    let letter = text.chars().next()?;
+
+    let natural = match letter.to_ascii_uppercase() {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+
+    let mut offset: i8 = 0;
+    let mut spelling = Spelling::Sharp;
+    let mut rest = &text[letter.len_utf8()..];
+
+    while let Some(c) = rest.chars().next() {
+        let delta = match c {
+            '#' | '♯' => 1,
+            'x' | '𝄪' => 2,
+            'b' | '♭' => -1,
+            '𝄫' => -2,
+            _ => break,
+        };
+        if offset * delta < 0 || (offset + delta).abs() > 2 {
+            return None;
+        }
+        if delta < 0 {
+            spelling = Spelling::Flat;
+        }
+        offset += delta;
+        rest = &rest[c.len_utf8()..];
+    }
+
+    Some((natural + offset, spelling, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_key() {
+        assert_eq!(Spelling::for_key(Note::new(60)), Spelling::Sharp); // C
+        assert_eq!(Spelling::for_key(Note::new(65)), Spelling::Flat); // F
+        assert_eq!(Spelling::for_key(Note::new(70)), Spelling::Flat); // Bb
+        assert_eq!(Spelling::for_key(Note::new(67)), Spelling::Sharp); // G
+    }
+
+    #[test]
+    fn test_parse_pitch() {
+        assert_eq!(parse_pitch("C"), Some((0, Spelling::Sharp, "")));
+        assert_eq!(parse_pitch("Bb7"), Some((10, Spelling::Flat, "7")));
+        assert_eq!(parse_pitch("ebm"), Some((3, Spelling::Flat, "m")));
+        assert_eq!(parse_pitch("Cbb"), Some((-2, Spelling::Flat, "")));
+        assert_eq!(parse_pitch("F##4"), Some((7, Spelling::Sharp, "4")));
+        assert_eq!(parse_pitch("Fx"), Some((7, Spelling::Sharp, "")));
+        assert_eq!(parse_pitch("Bbbb"), None);
+        assert_eq!(parse_pitch("C#x"), None);
+        assert_eq!(parse_pitch("Cx#"), None);
+        assert_eq!(parse_pitch("C#b"), None);
+        assert_eq!(parse_pitch("Eb#m"), None);
+        assert_eq!(parse_pitch("H"), None);
+    }
+
+    #[test]
+    fn test_note_names() {
+        assert_eq!(NoteNames::German.names(Spelling::Flat)[10], "B");
+        assert_eq!(NoteNames::German.names(Spelling::Sharp)[11], "H");
+        assert_eq!(NoteNames::German.names(Spelling::Flat)[3], "Es");
+        assert_eq!(NoteNames::Solfege.names(Spelling::Sharp)[7], "Sol");
+        assert_eq!(NoteNames::Solfege.names(Spelling::Flat)[10], "Sib");
+        assert_eq!(NoteNames::English.names(Spelling::Flat)[10], "Bb");
+        assert_eq!(NoteNames::from_name("Solfege"), Some(NoteNames::Solfege));
+        assert_eq!(NoteNames::from_name("dutch"), None);
+    }
+
+    #[test]
+    fn test_of_name() {
+        assert_eq!(Spelling::of_name("Bbm7"), Spelling::Flat);
+        assert_eq!(Spelling::of_name("C#"), Spelling::Sharp);
+        assert_eq!(Spelling::of_name("G"), Spelling::Sharp);
+        assert_eq!(Spelling::of_name("D/Gb"), Spelling::Flat);
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+
+use super::chord::Chord;
+use super::note::Note;
+
+const DISSONANCE_WEIGHT: f32 = 0.45;
+const FUNCTION_WEIGHT: f32 = 0.35;
+const LEADING_TONE_WEIGHT: f32 = 0.2;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Tension {
+    pub dissonance: f32,
+    pub function: f32,
+    pub leading_tone: f32,
+}
+
+impl Tension {
+    pub fn measure(notes: &HashSet<u8>, key: Option<Note>) -> Self {
+        let pitch_classes: HashSet<u8> = notes.iter().map(|&n| n % 12).collect();
+
+        let dissonance = Self::dissonance(&pitch_classes);
+
+        let Some(key) = key else {
+            return Self {
+                dissonance,
+                ..Self::default()
+            };
+        };
+
+        let function = Chord::detect(notes)
+            .map(|chord| Self::function(&chord, key))
+            .unwrap_or(if pitch_classes.is_empty() { 0.0 } else { 0.5 });
+
+        let leading_tone = if pitch_classes.contains(&((key.pitch_class() + 11) % 12)) {
+            1.0
+        } else {
+            0.0
+        };
+
+        Self {
+            dissonance,
+            function,
+            leading_tone,
+        }
+    }
+
+    pub fn total(&self) -> f32 {
+        (self.dissonance * DISSONANCE_WEIGHT
+            + self.function * FUNCTION_WEIGHT
+            + self.leading_tone * LEADING_TONE_WEIGHT)
+            .clamp(0.0, 1.0)
+    }
+
+    fn dissonance(pitch_classes: &HashSet<u8>) -> f32 {
+        let pcs: Vec<u8> = pitch_classes.iter().copied().collect();
+        let mut sum = 0.0;
+        let mut pairs = 0;
+
+        for (i, &a) in pcs.iter().enumerate() {
+            for &b in &pcs[i + 1..] {
+                let diff = (a + 12 - b) % 12;
+                sum += Self::interval_roughness(diff.min(12 - diff));
+                pairs += 1;
+            }
+        }
+
+        if pairs == 0 {
+            0.0
+        } else {
+            sum / pairs as f32
+        }
+    }
+
+    fn interval_roughness(interval_class: u8) -> f32 {
+        match interval_class {
+            1 => 1.0,
+            2 => 0.6,
+            3 => 0.15,
+            4 => 0.1,
+            5 => 0.05,
+            6 => 0.8,
+            _ => 0.0,
+        }
+    }
+
+    fn function(chord: &Chord, key: Note) -> f32 {
+        let degree = (chord.root.pitch_class() + 12 - key.pitch_class()) % 12;
+        match degree {
+            0 | 4 | 9 => 0.0,
+            2 | 5 => 0.5,
+            7 | 11 => 1.0,
+            _ => 0.8,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes_set(midi_notes: &[u8]) -> HashSet<u8> {
+        midi_notes.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_tonic_is_relaxed() {
+        let tension = Tension::measure(&notes_set(&[60, 64, 67]), Some(Note::new(60)));
+        assert_eq!(tension.function, 0.0);
+        assert_eq!(tension.leading_tone, 0.0);
+        assert!(tension.total() < 0.1);
+    }
+
+    #[test]
+    fn test_dominant_seventh_is_tense() {
+        let tonic = Tension::measure(&notes_set(&[60, 64, 67]), Some(Note::new(60)));
+        let dominant = Tension::measure(&notes_set(&[67, 71, 74, 77]), Some(Note::new(60)));
+        assert_eq!(dominant.function, 1.0);
+        assert_eq!(dominant.leading_tone, 1.0);
+        assert!(dominant.total() > tonic.total() + 0.4);
+    }
+
+    #[test]
+    fn test_cluster_is_dissonant() {
+        let cluster = Tension::measure(&notes_set(&[60, 61, 62]), None);
+        let triad = Tension::measure(&notes_set(&[60, 64, 67]), None);
+        assert!(cluster.dissonance > triad.dissonance);
+    }
+
+    #[test]
+    fn test_empty() {
+        let tension = Tension::measure(&HashSet::new(), Some(Note::new(60)));
+        assert_eq!(tension.total(), 0.0);
+    }
+}
@@ -0,0 +1,206 @@
+use super::chord::Chord;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParallelKind {
+    Fifth,
+    Octave,
+}
+
+impl ParallelKind {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ParallelKind::Fifth => "5",
+            ParallelKind::Octave => "8",
+        }
+    }
+
+    fn of(low: u8, high: u8) -> Option<Self> {
+        match (high - low) % 12 {
+            7 => Some(ParallelKind::Fifth),
+            0 => Some(ParallelKind::Octave),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Parallel {
+    pub kind: ParallelKind,
+    pub from: (u8, u8),
+    pub to: (u8, u8),
+}
+
+/// Pairs each voice of `prev` with a voice of `next`: bass to bass, top to
+/// top, and inner voices to the nearest unused inner note.
+pub fn pair_voices(prev: &[u8], next: &[u8]) -> Vec<(u8, u8)> {
+    if prev.is_empty() || next.is_empty() {
+        return Vec::new();
+    }
+
+    let mut prev = prev.to_vec();
+    let mut next = next.to_vec();
+    prev.sort_unstable();
+    next.sort_unstable();
+
+    if prev.len() == next.len() {
+        return prev.into_iter().zip(next).collect();
+    }
+
+    let mut pairs = vec![(prev[0], next[0])];
+    if prev.len() == 1 || next.len() == 1 {
+        return pairs;
+    }
+    pairs.push((prev[prev.len() - 1], next[next.len() - 1]));
+
+    let mut inner: Vec<u8> = next[1..next.len() - 1].to_vec();
+    for &voice in &prev[1..prev.len() - 1] {
+        let Some((i, _)) = inner
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &n)| (n as i16 - voice as i16).abs())
+        else {
+            break;
+        };
+        pairs.push((voice, inner.remove(i)));
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+pub fn parallels(prev: &[u8], next: &[u8]) -> Vec<Parallel> {
+    let pairs = pair_voices(prev, next);
+    let mut found = Vec::new();
+
+    for (i, &(a0, a1)) in pairs.iter().enumerate() {
+        for &(b0, b1) in &pairs[i + 1..] {
+            let (low0, high0, low1, high1) = if a0 <= b0 {
+                (a0, b0, a1, b1)
+            } else {
+                (b0, a0, b1, a1)
+            };
+            if low0 == high0 || low1 > high1 || (low0, high0) == (low1, high1) {
+                continue;
+            }
+
+            let low_motion = low1 as i16 - low0 as i16;
+            let high_motion = high1 as i16 - high0 as i16;
+            if low_motion.signum() != high_motion.signum() || low_motion == 0 {
+                continue;
+            }
+
+            match (ParallelKind::of(low0, high0), ParallelKind::of(low1, high1)) {
+                (Some(before), Some(after)) if before == after => found.push(Parallel {
+                    kind: after,
+                    from: (low0, high0),
+                    to: (low1, high1),
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    found
+}
+
+/// A voice resolving by step onto a chord tone of the same harmony, from
+/// a dissonance above its root: the 4 of Gsus4 falling to the 3 of G.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Suspension {
+    /// "4–3", "2–3" or "9–8"
+    pub figure: &'static str,
+    pub from: u8,
+    pub to: u8,
+}
+
+/// The suspension resolved going from `prev` voiced as `prev_notes` to
+/// `next` as `next_notes`, if any. Both chords need the same root; the
+/// suspended note has to be gone from the second one.
+pub fn suspension(
+    prev: &Chord,
+    prev_notes: &[u8],
+    next: &Chord,
+    next_notes: &[u8],
+) -> Option<Suspension> {
+    let root = next.root.pitch_class();
+    if prev.root.pitch_class() != root {
+        return None;
+    }
+    let degree = |note: u8| (note % 12 + 12 - root) % 12;
+    let tones: Vec<u8> = next.quality.intervals().iter().map(|i| i % 12).collect();
+
+    pair_voices(prev_notes, next_notes)
+        .into_iter()
+        .find_map(|(from, to)| {
+            let (held, landed) = (degree(from), degree(to));
+            if from.abs_diff(to) > 2 || tones.contains(&held) || !tones.contains(&landed) {
+                return None;
+            }
+            let figure = match (held, landed) {
+                (5, 3 | 4) if from > to => "4–3",
+                (2, 3 | 4) if to > from => "2–3",
+                (2, 0) if from > to => "9–8",
+                _ => return None,
+            };
+            Some(Suspension { figure, from, to })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_fifths() {
+        // C-G-C-E to D-A-D-F#: every voice moves up a step
+        let found = parallels(&[48, 55, 60, 64], &[50, 57, 62, 66]);
+        assert!(found.iter().any(|p| p.kind == ParallelKind::Fifth));
+        assert!(found.iter().any(|p| p.kind == ParallelKind::Octave));
+    }
+
+    #[test]
+    fn test_good_voice_leading() {
+        // C major to G major with common tone held and contrary bass
+        assert!(parallels(&[48, 64, 67, 72], &[43, 62, 67, 71]).is_empty());
+        // Oblique motion over a held bass is fine
+        assert!(parallels(&[48, 55, 64], &[48, 55, 65]).is_empty());
+    }
+
+    #[test]
+    fn test_suspensions() {
+        let chord = |name: &str| Chord::from_name(name).unwrap();
+        let found = |prev: &str, prev_notes: &[u8], next: &str, next_notes: &[u8]| {
+            suspension(&chord(prev), prev_notes, &chord(next), next_notes).map(|s| s.figure)
+        };
+
+        assert_eq!(
+            found("Gsus4", &[55, 60, 62], "G", &[55, 59, 62]),
+            Some("4–3")
+        );
+        assert_eq!(
+            found("Dsus2", &[50, 52, 57], "Dm", &[50, 53, 57]),
+            Some("2–3")
+        );
+        assert_eq!(
+            found("Cadd9", &[48, 55, 62, 64], "C", &[48, 55, 60, 64]),
+            Some("9–8")
+        );
+        // A 4–3 over a moving voicing, the top voice held into the change
+        assert_eq!(
+            found("Csus4", &[48, 65, 67, 72], "C", &[36, 64, 67, 72]),
+            Some("4–3")
+        );
+
+        // Leaping away, rising from the fourth or changing root isn't one
+        assert_eq!(found("Gsus4", &[55, 60, 62], "G", &[55, 62, 71]), None);
+        assert_eq!(found("Csus4", &[48, 53, 55], "F", &[53, 57, 60]), None);
+        assert_eq!(found("G", &[55, 59, 62], "Gsus4", &[55, 60, 62]), None);
+    }
+
+    #[test]
+    fn test_pairing_unequal_voices() {
+        let pairs = pair_voices(&[48, 55, 64], &[50, 57, 62, 66]);
+        assert_eq!(pairs, vec![(48, 50), (55, 57), (64, 66)]);
+        assert!(pair_voices(&[], &[60]).is_empty());
+    }
+}
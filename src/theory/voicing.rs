@@ -0,0 +1,397 @@
+use super::chord::Chord;
+use super::quality::Quality;
+
+const ROOTLESS_LOW: u8 = 50;
+const SHELL_LOW: u8 = 40;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Voicing {
+    RootlessA,
+    RootlessB,
+    Shell,
+}
+
+impl Voicing {
+    pub fn all() -> &'static [Voicing] {
+        &[Voicing::RootlessA, Voicing::RootlessB, Voicing::Shell]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Voicing::RootlessA => "A",
+            Voicing::RootlessB => "B",
+            Voicing::Shell => "Shell",
+        }
+    }
+
+    fn intervals(&self, quality: Quality) -> Option<&'static [u8]> {
+        use Quality::*;
+
+        let intervals: &'static [u8] = match (self, quality) {
+            (Voicing::RootlessA, Major7) => &[4, 7, 11, 14],
+            (Voicing::RootlessB, Major7) => &[11, 14, 16, 19],
+            (Voicing::RootlessA, Major) => &[4, 7, 9, 14],
+            (Voicing::RootlessB, Major) => &[9, 14, 16, 19],
+            (Voicing::RootlessA, Minor7 | Minor) => &[3, 7, 10, 14],
+            (Voicing::RootlessB, Minor7 | Minor) => &[10, 14, 15, 19],
+            (Voicing::RootlessA, Dominant7) => &[4, 9, 10, 14],
+            (Voicing::RootlessB, Dominant7) => &[10, 14, 16, 21],
+            (Voicing::RootlessA, HalfDim7) => &[3, 6, 10, 14],
+            (Voicing::RootlessB, HalfDim7) => &[10, 14, 15, 18],
+            (Voicing::RootlessA, MinorMajor7) => &[3, 7, 11, 14],
+            (Voicing::RootlessB, MinorMajor7) => &[11, 14, 15, 19],
+            (Voicing::Shell, Major7) => &[0, 4, 11],
+            (Voicing::Shell, Minor7 | Minor) => &[0, 3, 10],
+            (Voicing::Shell, Dominant7) => &[0, 4, 10],
+            (Voicing::Shell, HalfDim7) => &[0, 3, 10],
+            (Voicing::Shell, MinorMajor7) => &[0, 3, 11],
+            (Voicing::Shell, Major) => &[0, 4, 9],
+            _ => return None,
+        };
+        Some(intervals)
+    }
+
+    /// Concrete notes for `chord`, with the lowest note placed in the usual
+    /// left-hand register: around D3 for rootless forms, E2 for shells.
+    pub fn voice(&self, chord: &Chord) -> Option<Vec<u8>> {
+        let intervals = self.intervals(chord.quality)?;
+        let low = match self {
+            Voicing::Shell => SHELL_LOW,
+            _ => ROOTLESS_LOW,
+        };
+
+        let first = (chord.root.pitch_class() + intervals[0]) % 12;
+        let bottom = low + (first + 12 - low % 12) % 12;
+        Some(
+            intervals
+                .iter()
+                .map(|&i| bottom + i - intervals[0])
+                .collect(),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    Close,
+    Drop2,
+    Drop3,
+    Spread,
+}
+
+impl Shape {
+    pub fn all() -> &'static [Shape] {
+        &[Shape::Close, Shape::Drop2, Shape::Drop3, Shape::Spread]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Close => "Close",
+            Shape::Drop2 => "Drop-2",
+            Shape::Drop3 => "Drop-3",
+            Shape::Spread => "Spread",
+        }
+    }
+
+    /// Voices (counted from the top, starting at 1) lowered by an octave.
+    fn dropped(&self) -> &'static [usize] {
+        match self {
+            Shape::Close => &[],
+            Shape::Drop2 => &[2],
+            Shape::Drop3 => &[3],
+            Shape::Spread => &[2, 4],
+        }
+    }
+
+    pub fn voice(&self, chord: &Chord, inversion: usize) -> Option<Vec<u8>> {
+        let mut notes = close(chord, inversion);
+        let min_voices = match self {
+            Shape::Close => 1,
+            Shape::Drop2 => 3,
+            Shape::Drop3 | Shape::Spread => 4,
+        };
+        if notes.len() < min_voices {
+            return None;
+        }
+
+        let top = notes.len();
+        for &voice in self.dropped() {
+            notes[top - voice] -= 12;
+        }
+        notes.sort_unstable();
+        Some(notes)
+    }
+}
+
+pub fn inversion_name(inversion: usize) -> &'static str {
+    match inversion {
+        0 => "root position",
+        1 => "1st inversion",
+        2 => "2nd inversion",
+        3 => "3rd inversion",
+        _ => "inversion",
+    }
+}
+
+/// The chord tones in close position above middle C, with the lowest
+/// `inversion` notes moved up an octave.
+pub fn close(chord: &Chord, inversion: usize) -> Vec<u8> {
+    let classes = tone_classes(chord);
+    let root = 60 + chord.root.pitch_class();
+    let mut notes: Vec<u8> = classes.iter().map(|&i| root + i).collect();
+    for i in 0..inversion % notes.len() {
+        notes[i] += 12;
+    }
+    notes.sort_unstable();
+    notes
+}
+
+/// The chord tones stacked upward from the bass of `inversion`, which sits
+/// in `octave` (C4 is middle C). Each index in `doubled` repeats that tone
+/// of the stack an octave higher; notes past MIDI 127 are dropped.
+pub fn stacked(chord: &Chord, octave: i8, inversion: usize, doubled: &[usize]) -> Vec<u8> {
+    let mut classes = tone_classes(chord);
+    if classes.is_empty() {
+        return Vec::new();
+    }
+    let len = classes.len();
+    classes.rotate_left(inversion % len);
+
+    let root = chord.root.pitch_class() as i32;
+    let mut pitch = (octave as i32 + 1) * 12 + (root + classes[0] as i32) % 12;
+    let mut stack = Vec::with_capacity(classes.len() + doubled.len());
+    for (i, &class) in classes.iter().enumerate() {
+        if i > 0 {
+            pitch += (class as i32 - classes[i - 1] as i32).rem_euclid(12);
+        }
+        stack.push(pitch);
+    }
+    for &i in doubled {
+        if let Some(&pitch) = stack.get(i % len) {
+            stack.push(pitch + 12);
+        }
+    }
+
+    let mut notes: Vec<u8> = stack
+        .into_iter()
+        .filter(|p| (0..=127).contains(p))
+        .map(|p| p as u8)
+        .collect();
+    notes.sort_unstable();
+    notes.dedup();
+    notes
+}
+
+/// The chord's intervals within one octave, lowest first.
+fn tone_classes(chord: &Chord) -> Vec<u8> {
+    let mut classes: Vec<u8> = chord.quality.intervals().iter().map(|&i| i % 12).collect();
+    classes.sort_unstable();
+    classes.dedup();
+    classes
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VoicingOptions {
+    /// Octave of the lowest note, C4 being middle C
+    pub octave: i8,
+    /// Which chord tone is lowest, counting up from the root; a slash
+    /// chord's own bass takes its place
+    pub inversion: usize,
+    /// Open position: every other voice above the bass goes up an octave
+    pub spread: bool,
+}
+
+impl Default for VoicingOptions {
+    fn default() -> Self {
+        Self {
+            octave: 4,
+            inversion: 0,
+            spread: false,
+        }
+    }
+}
+
+impl Chord {
+    /// Concrete notes for the chord, lowest first. A bass note outside the
+    /// chord is played under the root position stack.
+    pub fn voicing(&self, options: VoicingOptions) -> Vec<u8> {
+        let classes = tone_classes(self);
+        let root = self.root.pitch_class();
+        let bass = self.bass.map(|b| (b.pitch_class() + 12 - root) % 12);
+
+        let (mut notes, above) = match bass {
+            Some(bass) if !classes.contains(&bass) => {
+                let bass = (options.octave as i32 + 1) * 12 + ((root + bass) % 12) as i32;
+                let mut stack = stacked(self, options.octave, 0, &[]);
+                if stack.first().is_some_and(|&n| n as i32 <= bass) {
+                    stack = stacked(self, options.octave + 1, 0, &[]);
+                }
+                let mut notes: Vec<u8> = (0..=127)
+                    .contains(&bass)
+                    .then_some(bass as u8)
+                    .into_iter()
+                    .collect();
+                notes.extend(stack);
+                (notes, 1)
+            }
+            Some(bass) => {
+                let inversion = classes.iter().position(|&c| c == bass).unwrap_or(0);
+                (stacked(self, options.octave, inversion, &[]), 0)
+            }
+            None => (stacked(self, options.octave, options.inversion, &[]), 0),
+        };
+
+        if options.spread {
+            for note in notes.iter_mut().skip(above + 1).step_by(2) {
+                *note = note.saturating_add(12).min(127);
+            }
+            notes.sort_unstable();
+            notes.dedup();
+        }
+        notes
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VoicingOption {
+    pub shape: Shape,
+    pub inversion: usize,
+    pub notes: Vec<u8>,
+}
+
+impl VoicingOption {
+    pub fn label(&self) -> String {
+        format!("{}, {}", self.shape.name(), inversion_name(self.inversion))
+    }
+}
+
+pub fn explore(chord: &Chord) -> Vec<VoicingOption> {
+    let inversions = close(chord, 0).len();
+
+    Shape::all()
+        .iter()
+        .flat_map(|&shape| {
+            (0..inversions).filter_map(move |inversion| {
+                shape.voice(chord, inversion).map(|notes| VoicingOption {
+                    shape,
+                    inversion,
+                    notes,
+                })
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theory::Note;
+
+    #[test]
+    fn test_stacked() {
+        let c = Chord::new(Note::new(60), Quality::Major);
+        assert_eq!(stacked(&c, 4, 0, &[]), vec![60, 64, 67]);
+        assert_eq!(stacked(&c, 3, 1, &[]), vec![52, 55, 60]);
+        assert_eq!(stacked(&c, 3, 2, &[0, 2]), vec![55, 60, 64, 67, 76]);
+
+        // A ninth folds into the stack as a second
+        let add9 = Chord::new(Note::new(60), Quality::Add9);
+        assert_eq!(stacked(&add9, 4, 0, &[]), vec![60, 62, 64, 67]);
+        assert_eq!(stacked(&c, 9, 0, &[]), vec![120, 124, 127]);
+    }
+
+    #[test]
+    fn test_voicing() {
+        let c = Chord::new(Note::new(60), Quality::Major);
+        assert_eq!(c.voicing(VoicingOptions::default()), vec![60, 64, 67]);
+
+        let first = VoicingOptions {
+            octave: 3,
+            inversion: 1,
+            spread: false,
+        };
+        assert_eq!(c.voicing(first), vec![52, 55, 60]);
+        let open = VoicingOptions {
+            spread: true,
+            ..VoicingOptions::default()
+        };
+        assert_eq!(c.voicing(open), vec![60, 67, 76]);
+
+        // The slash bass wins over the requested inversion
+        let c_g = c.clone().with_bass(Note::new(67));
+        assert_eq!(c_g.voicing(first), vec![55, 60, 64]);
+
+        // A foreign bass goes under the stack
+        let c_d = c.clone().with_bass(Note::new(62));
+        assert_eq!(c_d.voicing(VoicingOptions::default()), vec![62, 72, 76, 79]);
+        let c_d3 = VoicingOptions {
+            spread: true,
+            ..first
+        };
+        assert_eq!(c_d.voicing(c_d3), vec![50, 60, 67, 76]);
+    }
+
+    #[test]
+    fn test_rootless_dominant() {
+        let g7 = Chord::new(Note::new(67), Quality::Dominant7);
+
+        // A form: B E F A
+        let a = Voicing::RootlessA.voice(&g7).unwrap();
+        assert_eq!(a, vec![59, 64, 65, 69]);
+
+        // B form: F A B E
+        let b = Voicing::RootlessB.voice(&g7).unwrap();
+        assert_eq!(b, vec![53, 57, 59, 64]);
+        assert!(!b.iter().any(|n| n % 12 == 7));
+    }
+
+    #[test]
+    fn test_register() {
+        for root in 0..12 {
+            let chord = Chord::new(Note::new(60 + root), Quality::Minor7);
+            let a = Voicing::RootlessA.voice(&chord).unwrap();
+            assert!((ROOTLESS_LOW..ROOTLESS_LOW + 12).contains(&a[0]));
+
+            let shell = Voicing::Shell.voice(&chord).unwrap();
+            assert_eq!(shell[0] % 12, chord.root.pitch_class());
+            assert!((SHELL_LOW..SHELL_LOW + 12).contains(&shell[0]));
+        }
+    }
+
+    #[test]
+    fn test_unsupported_quality() {
+        let dim = Chord::new(Note::new(60), Quality::Diminished7);
+        assert!(Voicing::RootlessA.voice(&dim).is_none());
+    }
+
+    #[test]
+    fn test_drop_voicings() {
+        let cmaj7 = Chord::new(Note::new(60), Quality::Major7);
+
+        assert_eq!(close(&cmaj7, 0), vec![60, 64, 67, 71]);
+        assert_eq!(close(&cmaj7, 1), vec![64, 67, 71, 72]);
+        // Drop-2: G drops below C E B
+        assert_eq!(Shape::Drop2.voice(&cmaj7, 0).unwrap(), vec![55, 60, 64, 71]);
+        // Drop-3: E drops below C G B
+        assert_eq!(Shape::Drop3.voice(&cmaj7, 0).unwrap(), vec![52, 60, 67, 71]);
+        assert_eq!(
+            Shape::Spread.voice(&cmaj7, 0).unwrap(),
+            vec![48, 55, 64, 71]
+        );
+    }
+
+    #[test]
+    fn test_explore_triad() {
+        let c = Chord::new(Note::new(48), Quality::Major);
+        let options = explore(&c);
+
+        // Close and drop-2 in three inversions; no fourth voice to drop
+        assert_eq!(options.len(), 6);
+        assert!(options.iter().all(|o| o.notes.len() == 3));
+        assert_eq!(options[3].label(), "Drop-2, root position");
+        assert_eq!(
+            explore(&Chord::new(Note::new(60), Quality::Dominant7)).len(),
+            16
+        );
+    }
+}
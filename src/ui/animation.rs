@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+/// How long a frame of an effect lasts, however often the UI ticks
+pub const FRAME: Duration = Duration::from_millis(50);
+/// Frames each effect runs for
+const FLASH_FRAMES: u16 = 8;
+const SLIDE_FRAMES: u16 = 6;
+const FADE_FRAMES: u16 = 9;
+
+/// A fixed-length effect advanced one frame per tick.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Animation {
+    frame: u16,
+    frames: u16,
+}
+
+impl Animation {
+    pub fn new(frames: u16) -> Self {
+        Self { frame: 0, frames }
+    }
+
+    pub fn tick(&mut self) {
+        self.frame = (self.frame + 1).min(self.frames);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.frame < self.frames
+    }
+
+    /// 0 when the effect starts, 1 once it has settled.
+    pub fn progress(&self) -> f32 {
+        if self.frames == 0 {
+            1.0
+        } else {
+            self.frame as f32 / self.frames as f32
+        }
+    }
+}
+
+/// The effects that play when the chord changes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Animations {
+    enabled: bool,
+    /// The chord name flashes, then settles into its normal style
+    pub flash: Animation,
+    /// The newest history entry slides in from the right
+    pub slide: Animation,
+    /// The suggestion tree fades out and the new one fades in
+    pub fade: Animation,
+}
+
+impl Default for Animations {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl Animations {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            flash: Animation::default(),
+            slide: Animation::default(),
+            fade: Animation::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        *self = Self::new(enabled);
+    }
+
+    pub fn chord_changed(&mut self) {
+        if self.enabled {
+            self.flash = Animation::new(FLASH_FRAMES);
+            self.fade = Animation::new(FADE_FRAMES);
+        }
+    }
+
+    pub fn chord_recorded(&mut self) {
+        if self.enabled {
+            self.slide = Animation::new(SLIDE_FRAMES);
+        }
+    }
+
+    pub fn tick(&mut self) {
+        self.flash.tick();
+        self.slide.tick();
+        self.fade.tick();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.flash.is_running() || self.slide.is_running() || self.fade.is_running()
+    }
+
+    pub fn is_flashing(&self) -> bool {
+        self.flash.is_running() && self.flash.progress() < 0.5
+    }
+
+    /// Columns the newest history entry is still to travel.
+    pub fn slide_offset(&self, distance: u16) -> u16 {
+        let remaining = 1.0 - self.slide.progress();
+        (distance as f32 * remaining * remaining).round() as u16
+    }
+
+    /// Where the tree cross-fade is: the old suggestions dimming, the new
+    /// ones brightening, or done.
+    pub fn fade_stage(&self) -> FadeStage {
+        match self.fade.progress() {
+            p if p < 1.0 / 3.0 => FadeStage::Out,
+            p if p < 2.0 / 3.0 => FadeStage::In,
+            _ => FadeStage::Done,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FadeStage {
+    Out,
+    In,
+    Done,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_animation_runs_and_settles() {
+        let mut animation = Animation::new(4);
+        assert!(animation.is_running());
+        assert_eq!(animation.progress(), 0.0);
+        for _ in 0..10 {
+            animation.tick();
+        }
+        assert!(!animation.is_running());
+        assert_eq!(animation.progress(), 1.0);
+        assert!(!Animation::default().is_running());
+    }
+
+    #[test]
+    fn test_chord_change_effects() {
+        let mut animations = Animations::new(true);
+        assert_eq!(animations.fade_stage(), FadeStage::Done);
+
+        animations.chord_changed();
+        animations.chord_recorded();
+        assert!(animations.is_flashing());
+        assert_eq!(animations.fade_stage(), FadeStage::Out);
+        assert_eq!(animations.slide_offset(10), 10);
+
+        for _ in 0..4 {
+            animations.tick();
+        }
+        assert!(!animations.is_flashing());
+        assert_eq!(animations.fade_stage(), FadeStage::In);
+        assert!(animations.slide_offset(10) < 10);
+
+        for _ in 0..FADE_FRAMES {
+            animations.tick();
+        }
+        assert_eq!(animations.fade_stage(), FadeStage::Done);
+        assert_eq!(animations.slide_offset(10), 0);
+        assert!(!animations.is_running());
+    }
+
+    #[test]
+    fn test_disabled() {
+        let mut animations = Animations::new(false);
+        animations.chord_changed();
+        animations.chord_recorded();
+        assert!(!animations.is_flashing());
+        assert_eq!(animations.slide_offset(10), 0);
+        assert_eq!(animations.fade_stage(), FadeStage::Done);
+    }
+}
@@ -1,18 +1,90 @@
+use std::cell::Cell;
 use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use crossterm::event::KeyCode;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
-use crate::midi::MidiInput;
-use crate::theory::{Chord, Note, ProgressionTree};
-use crate::ui::components::{ChordHistory, ChordTree, Piano};
-use crate::ui::theme::Theme;
+use crate::clock::{Clock, SystemClock};
+use crate::config::Config;
+use crate::export::lilypond::{self, LilypondOptions};
+use crate::export::midi::MidiOptions;
+use crate::export::{self, ExportFormat};
+use crate::guitar::{fingerings, Fingering, Tuning};
+use crate::hooks::Hooks;
+use crate::midi::velocity::CALIBRATION_NOTES;
+use crate::midi::{arpeggio, sync};
+use crate::midi::{
+    Arpeggiator, Calibration, CalibrationStage, ClockSender, ClockSource, Control, DeviceProfiles,
+    Groove, Humanize, MidiFile, MidiInput, MidiOutput, NoteSource, Playback, SUSTAIN_PEDAL,
+};
+use crate::paths::{self, UserDirs};
+use crate::practice::{
+    interval_name, milestones, Dictation, DictationResult, DictationStats, Goal, IntervalDrill,
+    Milestone, Milestones, PlayAlong, PracticeLog, Trainer, TrainerResult,
+};
+use crate::rhythm::{
+    harmonic, Accent, Feel, Looper, Quantizer, Resolution, TapTempo, TempoEstimator, TimeSignature,
+    Transport,
+};
+use crate::session::{
+    browser, Autosave, Change, ChartPosition, Event, Session, SessionBrowser, SessionEntry,
+    SessionWriter, Sketch, TakeDiff, TimedEvent,
+};
+use crate::theory::plan::{chord_at, PLAN_DEPTH};
+use crate::theory::{
+    bass, blues, brush, harmonize, keys, melody, reharm, similar, voicing, AdaptiveModel,
+    BassStyle, BluesForm, Branch, Chord, ChordSmoother, Confidence, Detection, FollowStats,
+    Followed, Function, KeyAnalysis, MelodyHarmonizer, NamingStyle, Note, NoteNames, Onsets,
+    ParallelKind, PedalTracker, Plan, Preferences, Preset, ProgressionModel, ProgressionNode,
+    ProgressionTree, Reharmonization, Scale, ScaleKind, Spelling, Suggester, Tension,
+    Transposition, Voicing, VoicingOption, VoicingOptions,
+};
+use crate::ui::animation::{self, Animations, FadeStage};
+use crate::ui::components::{
+    centred_pips, heatmap, pips, sparkline, ChordHeatmap, ChordHistory, ChordTree, Fretboard,
+    FunctionRibbon, Meter, Piano,
+};
+use crate::ui::controls::{ControlAction, ControlMap, Learn, Mapping};
+use crate::ui::i18n::{Language, Text};
+use crate::ui::keymap::{self, Command, Conflict, Keymap};
+use crate::ui::screen::{Screen, Screens};
+use crate::ui::status::StatusSegment;
+use crate::ui::theme::{QualityColors, Theme};
+
+const KEY_WINDOW: usize = 4;
+const LOW_CONFIDENCE: f32 = 0.6;
+/// Columns a new history entry slides in from
+const SLIDE_DISTANCE: u16 = 8;
+/// Harmonic rhythm sparkline length, in 15-second bars
+const SPARK_BUCKETS: usize = 12;
+/// Pads 1-7 are the scale degrees, pad 8 the tonic an octave up
+const PADS: u8 = 8;
+/// Highest fret the capo key steps to before going back to none
+pub const MAX_CAPO: u8 = 7;
+/// Two sustain pedal presses this close together turn the page
+const DOUBLE_TAP: Duration = Duration::from_millis(400);
+/// How long the playing pauses before what was learned is blended into
+/// the suggestions, so they aren't rebuilt on every chord
+const LEARNING_SETTLES: Duration = Duration::from_secs(2);
+/// Latest chords the key finder weighs when none are typed in
+const KEY_FINDER_CHORDS: usize = 16;
+/// Bookmarks listed under a session in the browser
+const BOOKMARKS_SHOWN: usize = 4;
+/// Keys the key finder lists
+const KEY_FINDER_KEYS: usize = 6;
+/// Latest chords offered for reharmonizing
+const REHARM_CHORDS: usize = 8;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Mode {
@@ -29,6 +101,42 @@ impl Mode {
     }
 }
 
+/// A second suggestion tree read in a minor key next to the major one.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum MinorTree {
+    #[default]
+    Off,
+    Relative,
+    Parallel,
+}
+
+impl MinorTree {
+    pub fn cycle(self) -> Self {
+        match self {
+            MinorTree::Off => MinorTree::Relative,
+            MinorTree::Relative => MinorTree::Parallel,
+            MinorTree::Parallel => MinorTree::Off,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MinorTree::Off => "off",
+            MinorTree::Relative => "relative minor",
+            MinorTree::Parallel => "parallel minor",
+        }
+    }
+
+    pub fn scale(self, major: Note) -> Option<Scale> {
+        let tonic = match self {
+            MinorTree::Off => return None,
+            MinorTree::Relative => Note::new(60 + (major.pitch_class() + 9) % 12),
+            MinorTree::Parallel => major,
+        };
+        Some(Scale::new(tonic, ScaleKind::Minor))
+    }
+}
+
 pub struct App {
     pub mode: Mode,
     pub midi: Option<MidiInput>,
@@ -37,9 +145,144 @@ pub struct App {
     pub tree: ProgressionTree,
     pub should_quit: bool,
     pub extended_chords: bool,
-    pub show_help: bool,
+    /// Open screens, the newest last
+    screens: Screens,
+    help_page: usize,
+    /// Pages the help took when last drawn, which depends on the height
+    help_pages: Cell<usize>,
+    /// Where the function ribbon was last drawn, for clicks on it
+    ribbon_area: Cell<Rect>,
+    pub naming: NamingStyle,
+    pub note_names: NoteNames,
+    pub language: Language,
+    pub detection: Preferences,
+    pub min_confidence: f32,
+    /// Draw the piano with the high-resolution renderer
+    pub piano_hires: bool,
+    pub session: Session,
+    pub output: Option<MidiOutput>,
+    pub trainer: Option<Trainer>,
+    pub dictation: Option<Dictation>,
+    pub bass_style: BassStyle,
+    pub bpm: Option<f32>,
+    pub auto_tempo: bool,
+    pub feel: Feel,
+    pub resolution: Resolution,
+    pub looper: Option<Looper>,
+    pub loop_bars: u32,
+    /// Bars of the looper, export and guide tones; a change applies from
+    /// the next chord
+    pub time_signature: TimeSignature,
+    /// Blues mode, counting bars through the 12-bar form
+    pub blues: Option<BluesForm>,
+    /// Send MIDI Start, Clock and Stop on the output while the looper runs
+    pub send_clock: bool,
+    clock_out: Option<ClockSender>,
+    /// From the latest note-on to the chord it made being read
+    latency: Option<Duration>,
+    pub humanize: Humanize,
+    pub along: Option<PlayAlong>,
+    along_file: MidiFile,
+    along_started: Instant,
+    tap_tempo: TapTempo,
+    tempo_estimator: TempoEstimator,
+    session_started: Instant,
+    confidence: Confidence,
+    chord_since: Instant,
+    previous_chord: Option<Chord>,
+    smoother: ChordSmoother,
+    /// Suggestions pinned or replaced by hand
+    pub plan: Plan,
+    /// A chord name being typed in for the selected branch
+    plan_input: Option<String>,
+    /// Sections arranged from captured chords
+    pub sketch: Sketch,
+    /// Session entries already captured into sections
+    sketch_captured: usize,
+    /// The chord reached stepping through the sketch
+    sketch_position: Option<ChartPosition>,
+    /// When the sustain pedal was last pressed, if it could be the first
+    /// of a double tap
+    pedal_tapped: Option<Instant>,
+    /// When each held key went down
+    onsets: Onsets,
+    /// Phrases of a melody being harmonized, while the tree proposes chords
+    /// under them instead
+    harmonizer: Option<MelodyHarmonizer>,
+    pedal_point: PedalTracker,
+    /// The notes the current chord was read from
+    harmony: HashSet<u8>,
+    pub follow: FollowStats,
+    pub minor_tree: MinorTree,
+    /// Fret the guitarist's capo is on, 0 for none
+    pub capo: u8,
+    /// The song's original key and the key it's sung in; chords are shown
+    /// in both and exports are moved to the sung key
+    pub transposition: Option<Transposition>,
+    pub tuning: Tuning,
+    pub left_handed: bool,
+    animations: Animations,
+    /// Controller buttons mapped to actions
+    pub controls: ControlMap,
+    controls_path: Option<PathBuf>,
+    /// Status bar segments, in order
+    pub status: Vec<StatusSegment>,
+    /// Chord names coloured by quality family, if on
+    pub quality_colors: Option<QualityColors>,
+    pub keymap: Keymap,
+    pub goals: Vec<Goal>,
+    pub show_similar: bool,
+    /// Side panel naming each held note's role in the chord
+    pub show_spelling: bool,
+    /// Number keys trigger the key's chords
+    pub pads: bool,
+    /// Breaks up auditioned and pad chords
+    pub arp: Arpeggiator,
+    /// Comping patterns, built in and the user's own
+    pub grooves: Vec<Groove>,
+    /// Which groove progressions and loops are played back to, if any
+    pub groove: Option<usize>,
+    pub partner: Option<MidiInput>,
+    pub duet: bool,
+    pub partner_chord: Option<Chord>,
+    pub message: Option<String>,
+    /// Whether anything shown may have changed since the frontend last
+    /// drew
+    dirty: bool,
+    animation_frame: Instant,
+    clock: Arc<dyn Clock>,
+    /// Scripted MIDI played into the input as time passes
+    source: Option<Box<dyn NoteSource>>,
+    preset_cursor: usize,
+    interval_cursor: usize,
+    pub adaptive_enabled: bool,
+    pub blend: f32,
+    playback: Playback,
+    model_path: Option<PathBuf>,
+    base_model: ProgressionModel,
+    adaptive: AdaptiveModel,
+    adaptive_path: Option<PathBuf>,
+    /// When a transition was last learned that the suggestions don't take
+    /// in yet
+    learned_at: Option<Instant>,
+    /// Reset-adaptive was pressed once and waits for a second press
+    confirm_reset: bool,
+    stats_path: Option<PathBuf>,
+    /// Where device settings are saved
+    devices_path: Option<PathBuf>,
+    milestones: Option<(Milestones, PathBuf)>,
+    practice: Option<(PracticeLog, PathBuf)>,
+    user: Option<UserDirs>,
+    sessions_dir: Option<PathBuf>,
+    autosave: Option<Autosave>,
+    event_log: Option<SessionWriter>,
+    /// When the event log was opened, by the app's clock
+    log_started: Instant,
+    hooks: Hooks,
     key: Option<Note>,
+    analysis: KeyAnalysis,
     last_notes: HashSet<u8>,
+    partner_notes: HashSet<u8>,
 }
 
 impl Default for App {
@@ -55,12 +298,108 @@ impl App {
             midi: None,
             current_chord: None,
             history: ChordHistory::new(16),
-            tree: ProgressionTree::new(),
+            tree: ProgressionTree::seeded(rand::random()),
             should_quit: false,
             extended_chords: false,
-            show_help: false,
+            screens: Screens::default(),
+            help_page: 0,
+            help_pages: Cell::new(1),
+            ribbon_area: Cell::default(),
+            naming: NamingStyle::default(),
+            note_names: NoteNames::default(),
+            language: Language::default(),
+            detection: Preferences::default(),
+            piano_hires: false,
+            min_confidence: 0.0,
+            session: Session::new(),
+            output: None,
+            trainer: None,
+            dictation: None,
+            controls: ControlMap::default(),
+            controls_path: None,
+            status: StatusSegment::defaults(),
+            quality_colors: None,
+            keymap: Keymap::default(),
+            goals: Goal::defaults(),
+            show_similar: false,
+            show_spelling: false,
+            pads: false,
+            arp: Arpeggiator::default(),
+            grooves: Groove::built_in(),
+            groove: None,
+            partner: None,
+            duet: false,
+            partner_chord: None,
+            message: None,
+            dirty: true,
+            animation_frame: Instant::now(),
+            clock: Arc::new(SystemClock),
+            source: None,
+            preset_cursor: 0,
+            interval_cursor: 0,
+            bass_style: BassStyle::default(),
+            bpm: None,
+            auto_tempo: false,
+            feel: Feel::default(),
+            resolution: Resolution::default(),
+            looper: None,
+            send_clock: false,
+            clock_out: None,
+            latency: None,
+            loop_bars: 4,
+            time_signature: TimeSignature::default(),
+            blues: None,
+            humanize: Humanize::default(),
+            along: None,
+            along_file: MidiFile::default(),
+            along_started: Instant::now(),
+            tap_tempo: TapTempo::new(),
+            tempo_estimator: TempoEstimator::new(),
+            session_started: Instant::now(),
+            confidence: Confidence::default(),
+            chord_since: Instant::now(),
+            previous_chord: None,
+            smoother: ChordSmoother::default(),
+            plan: Plan::default(),
+            plan_input: None,
+            sketch: Sketch::default(),
+            sketch_captured: 0,
+            sketch_position: None,
+            pedal_tapped: None,
+            onsets: Onsets::default(),
+            harmonizer: None,
+            pedal_point: PedalTracker::default(),
+            harmony: HashSet::new(),
+            follow: FollowStats::default(),
+            minor_tree: MinorTree::default(),
+            capo: 0,
+            transposition: None,
+            tuning: Tuning::standard(),
+            left_handed: false,
+            animations: Animations::default(),
+            adaptive_enabled: false,
+            blend: 0.5,
+            playback: Playback::new(),
+            model_path: None,
+            base_model: ProgressionModel::default(),
+            adaptive: AdaptiveModel::new(),
+            adaptive_path: None,
+            learned_at: None,
+            confirm_reset: false,
+            stats_path: None,
+            devices_path: None,
+            milestones: None,
+            practice: None,
+            user: None,
+            sessions_dir: None,
+            autosave: None,
+            event_log: None,
+            log_started: Instant::now(),
+            hooks: Hooks::default(),
             key: None,
+            analysis: KeyAnalysis::default(),
             last_notes: HashSet::new(),
+            partner_notes: HashSet::new(),
         }
     }
 
@@ -74,271 +413,7134 @@ impl App {
         Ok(())
     }
 
+    pub fn connect_partner_port(&mut self, port: usize) -> Result<()> {
+        self.partner = Some(MidiInput::connect(port)?);
+        self.duet = true;
+        Ok(())
+    }
+
+    fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    /// Takes the time from `clock` from here on, starting the session,
+    /// the held chord and effects over from its now.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        let now = clock.now();
+        self.session_started = now;
+        self.log_started = now;
+        self.chord_since = now;
+        self.along_started = now;
+        self.animation_frame = now;
+        for input in self.midi.iter_mut().chain(self.partner.iter_mut()) {
+            input.set_clock(Arc::clone(&clock));
+        }
+        self.clock = clock;
+    }
+
+    /// Plays what `source` sends through the MIDI input as if a port had
+    /// sent it, opening an input without a port if there isn't one.
+    pub fn set_note_source(&mut self, source: Box<dyn NoteSource>) {
+        let midi = self.midi.get_or_insert_with(MidiInput::new);
+        midi.set_clock(Arc::clone(&self.clock));
+        self.source = Some(source);
+    }
+
+    fn tick_source(&mut self) {
+        let (Some(source), Some(midi)) = (&mut self.source, &self.midi) else {
+            return;
+        };
+        for message in source.due(self.clock.now()) {
+            midi.receive(&message);
+        }
+    }
+
+    pub fn toggle_duet(&mut self) {
+        self.duet = !self.duet;
+    }
+
+    pub fn combined_notes(&self) -> HashSet<u8> {
+        self.last_notes
+            .union(&self.partner_notes)
+            .copied()
+            .collect()
+    }
+
+    /// How sure the current reading is, growing as the chord is held.
+    pub fn confidence(&self) -> Option<f32> {
+        self.current_chord.as_ref().map(|_| {
+            self.confidence
+                .held(self.now().saturating_duration_since(self.chord_since))
+        })
+    }
+
+    pub fn combined_chord(&self) -> Option<Chord> {
+        Chord::detect_with(&self.combined_notes(), &self.detection)
+    }
+
+    pub fn connect_output_port(&mut self, port: usize) -> Result<()> {
+        self.output = Some(MidiOutput::connect(port)?);
+        Ok(())
+    }
+
     pub fn toggle_mode(&mut self) {
         self.mode = match self.mode {
             Mode::Discovery => Mode::Jam,
             Mode::Jam => Mode::Discovery,
         };
         self.history.set_fade(self.mode == Mode::Jam);
+        self.log(Event::Mode {
+            mode: self.mode.name().to_string(),
+        });
     }
 
     pub fn toggle_extended(&mut self) {
         self.extended_chords = !self.extended_chords;
         self.tree.set_extended(self.extended_chords);
+        self.precompute_suggestions();
     }
 
-    pub fn toggle_help(&mut self) {
-        self.show_help = !self.show_help;
+    pub fn creativity(&self) -> f32 {
+        self.tree.creativity()
     }
 
-    pub fn tick(&mut self) {
-        let notes = self
-            .midi
-            .as_ref()
-            .map(|m| m.held_notes())
-            .unwrap_or_default();
+    pub fn set_creativity(&mut self, creativity: f32) {
+        self.tree.set_creativity(creativity);
+        self.precompute_suggestions();
+    }
 
-        if notes != self.last_notes {
-            self.last_notes = notes.clone();
+    /// Has the trees for the key's chords worked out before they're played.
+    fn precompute_suggestions(&self) {
+        if let Some(scale) = self.scale() {
+            self.tree.precompute(scale);
+        }
+    }
 
-            if let Some(chord) = Chord::detect(&notes) {
-                if self.current_chord.as_ref().map(|c| c.name()) != Some(chord.name()) {
-                    self.history.push(chord.clone());
+    pub fn set_naming(&mut self, style: NamingStyle) {
+        self.naming = style;
+        self.history.set_naming(style);
+    }
 
-                    if self.key.is_none() {
-                        self.key = Some(chord.root);
-                    }
-                }
-                self.current_chord = Some(chord);
-            }
+    pub fn set_note_names(&mut self, names: NoteNames) {
+        self.note_names = names;
+        self.history.set_note_names(names);
+    }
+
+    /// A chord's name as shown, in the current key, style and note names.
+    pub fn chord_name(&self, chord: &Chord) -> String {
+        chord.name_in(self.naming, self.spelling(), self.note_names)
+    }
+
+    pub fn tr(&self, text: Text) -> &'static str {
+        self.language.text(text)
+    }
+
+    /// A message with its `{}`s filled in, in order.
+    pub fn tr_with(&self, text: Text, args: &[&dyn std::fmt::Display]) -> String {
+        self.language.fill(text, args)
+    }
+
+    pub fn cycle_naming(&mut self) {
+        self.set_naming(self.naming.next());
+    }
+
+    pub fn key(&self) -> Option<Note> {
+        self.key
+    }
+
+    pub fn set_key(&mut self, key: Option<Note>) {
+        self.key = key;
+        if let Some(form) = &mut self.blues {
+            form.restart();
         }
+        self.session.set_key(key);
+        self.history.set_spelling(self.spelling());
+        self.history.set_scale(self.scale());
+        self.precompute_suggestions();
+        self.log(Event::Key {
+            key: self.session.key.clone(),
+        });
+    }
 
-        self.history.tick();
+    pub fn enable_event_log(&mut self, writer: SessionWriter) {
+        self.log_started = self.now();
+        self.event_log = Some(writer);
     }
 
-    pub fn handle_key(&mut self, key: KeyCode) {
-        match key {
-            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
-            KeyCode::Tab => self.toggle_mode(),
-            KeyCode::Char('e') => self.toggle_extended(),
-            KeyCode::Char('?') => self.toggle_help(),
-            KeyCode::Char('c') => {
-                self.history.clear();
-                self.key = None;
+    pub fn set_hooks(&mut self, hooks: Hooks) {
+        hooks.session_start();
+        self.hooks = hooks;
+    }
+
+    fn log(&mut self, event: Event) {
+        let t = self.log_time();
+        self.log_at(t, event);
+    }
+
+    /// Milliseconds since the event log was opened, as events are stamped.
+    fn log_time(&self) -> u64 {
+        if self.event_log.is_none() {
+            return 0;
+        }
+        self.now()
+            .saturating_duration_since(self.log_started)
+            .as_millis() as u64
+    }
+
+    /// Logs an event as happening at `t`, for one finished after the moment
+    /// it belongs to.
+    fn log_at(&mut self, t: u64, event: Event) {
+        self.hooks.fire(&event);
+
+        let Some(writer) = &mut self.event_log else {
+            return;
+        };
+        if let Err(e) = writer.write(&TimedEvent { t, event }) {
+            self.message = Some(self.tr_with(Text::EventLogDisabled, &[&e]));
+            self.event_log = None;
+        }
+    }
+
+    pub fn scale(&self) -> Option<Scale> {
+        let kind = match self.blues {
+            Some(_) => ScaleKind::Blues,
+            None => ScaleKind::Major,
+        };
+        self.key.map(|key| Scale::new(key, kind))
+    }
+
+    pub fn toggle_blues(&mut self) {
+        self.blues = match self.blues {
+            Some(_) => None,
+            None => Some(BluesForm::default()),
+        };
+        self.history.set_scale(self.scale());
+        self.precompute_suggestions();
+        self.message = Some(match (self.blues, self.scale()) {
+            (None, _) => self.tr(Text::BluesOff).to_string(),
+            (Some(_), Some(scale)) => {
+                self.tr_with(Text::BluesFormIn, &[&scale.name_in(self.note_names)])
             }
-            _ => {}
+            (Some(_), None) => self.tr(Text::BluesFirstChord).to_string(),
+        });
+    }
+
+    /// The bar of the 12-bar form being played, from 0.
+    pub fn blues_bar(&self) -> Option<usize> {
+        self.blues?.bar(self.now(), self.bar_length())
+    }
+
+    /// The tree after `chord`. With the blues form under way, the expected
+    /// chord is the next bar's and the surprise the change often played
+    /// instead, or what the model suggests.
+    fn suggest(&self, chord: &Chord) -> ProgressionNode {
+        let scale = self.scale().unwrap_or_else(|| Scale::major(chord.root));
+        let node = self.tree.suggest_in(chord, scale);
+        let Some(bar) = self.blues_bar() else {
+            return node;
+        };
+
+        let next = blues::TWELVE_BAR[(bar + 1) % blues::BARS];
+        let expected = next.chord(scale.tonic);
+        let surprise = next
+            .variant_chord(scale.tonic)
+            .map(|(chord, _)| chord)
+            .into_iter()
+            .chain(
+                [&node.left, &node.right]
+                    .into_iter()
+                    .flatten()
+                    .map(|n| n.chord.clone()),
+            )
+            .find(|c| *c != expected)
+            .unwrap_or_else(|| expected.clone());
+        let branch = |chord: Chord| {
+            let after = self.tree.suggest_in(&chord, scale);
+            let leaf = |n: Option<Box<ProgressionNode>>| n.map(|n| ProgressionNode::new(n.chord));
+            ProgressionNode {
+                chord,
+                left: leaf(after.left).map(Box::new),
+                right: leaf(after.right).map(Box::new),
+            }
+        };
+        ProgressionNode::new(chord.clone()).with_children(branch(expected), branch(surprise))
+    }
+
+    pub fn is_out_of_key(&self) -> bool {
+        match (&self.current_chord, self.scale()) {
+            (Some(chord), Some(scale)) => !scale.contains_chord(chord),
+            _ => false,
         }
     }
 
-    pub fn render(&self, frame: &mut Frame) {
-        let area = frame.area();
+    /// The function of each chord of the history, in the key set or the
+    /// one it was found in. Empty until the chords have a key.
+    pub fn functions(&self) -> Vec<Function> {
+        self.history
+            .entries()
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let scale = self.scale().or_else(|| self.analysis.key_at(i))?;
+                Some(Function::of(&entry.chord, scale))
+            })
+            .collect::<Option<Vec<Function>>>()
+            .unwrap_or_default()
+    }
 
-        let main_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(1),
-                Constraint::Min(10),
-                Constraint::Length(8),
-                Constraint::Length(1),
-            ])
-            .split(area);
+    pub fn key_hint(&self) -> Option<Scale> {
+        if !self.is_out_of_key() {
+            return None;
+        }
 
-        self.render_title(frame, main_layout[0]);
+        let entries = self.history.entries();
+        let recent: Vec<Chord> = entries[entries.len().saturating_sub(4)..]
+            .iter()
+            .map(|e| e.chord.clone())
+            .collect();
 
-        let content_layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-            .split(main_layout[1]);
+        Scale::fit(&recent, self.key).filter(|s| Some(*s) != self.scale())
+    }
 
-        self.render_tree(frame, content_layout[0]);
-        self.render_history(frame, content_layout[1]);
+    pub fn reinterpret_key(&mut self) {
+        if let Some(scale) = self.key_hint() {
+            self.set_key(Some(scale.tonic));
+            self.message = Some(self.tr_with(Text::KeySetTo, &[&scale.name_in(self.note_names)]));
+        }
+    }
 
-        self.render_piano(frame, main_layout[2]);
-        self.render_status(frame, main_layout[3]);
+    pub fn spelling(&self) -> Spelling {
+        self.key.map(Spelling::for_key).unwrap_or_default()
+    }
 
-        if self.show_help {
-            self.render_help_overlay(frame, area);
+    pub fn enable_autosave(&mut self, autosave: Autosave) {
+        if let Some(session) = autosave.recover() {
+            self.screens.open(Screen::Recovery(session));
         }
+        self.autosave = Some(autosave);
     }
 
-    fn render_title(&self, frame: &mut Frame, area: Rect) {
-        let title = Paragraph::new(Line::from(vec![
-            Span::styled(" Chordvery ", Theme::title()),
-            Span::styled("─ Chord Discovery Tool", Theme::text_dim()),
-        ]));
-        frame.render_widget(title, area);
+    pub fn restore(&mut self, session: Session) {
+        self.history.clear();
+        for (chord, notes) in session.voiced_chords() {
+            self.history.push_voiced(chord, notes);
+        }
+        self.current_chord = None;
+        if let Some(last) = session.onsets().and_then(|o| o.last().copied()) {
+            let resume = Duration::from_secs_f64(last.max(0.0) + 2.0);
+            self.session_started = self
+                .now()
+                .checked_sub(resume)
+                .unwrap_or(self.session_started);
+        }
+        self.time_signature = session.time_signature_at(session.len());
+        self.session = session;
+        self.set_key(self.session.key());
+        self.refresh_analysis();
     }
 
-    fn render_tree(&self, frame: &mut Frame, area: Rect) {
-        let block = Block::default()
-            .title(" Suggestions ")
-            .borders(Borders::ALL)
-            .border_style(Theme::border());
+    pub fn flush_event_log(&mut self) -> Result<()> {
+        if let Some(writer) = &mut self.event_log {
+            writer.flush()?;
+        }
+        Ok(())
+    }
 
-        let inner = block.inner(area);
-        frame.render_widget(block, area);
+    pub fn shutdown(&mut self) -> Result<()> {
+        self.hooks.session_end();
+        if let Some(writer) = &mut self.event_log {
+            writer.flush()?;
+        }
+        self.save_user_state()?;
 
-        if let Some(chord) = &self.current_chord {
-            let node = self.tree.suggest(chord, self.key);
-            let tree_widget = ChordTree::new().root(node);
-            frame.render_widget(tree_widget, inner);
-        } else {
-            let tree_widget = ChordTree::new();
-            frame.render_widget(tree_widget, inner);
+        if let Some(output) = &mut self.output {
+            self.playback.stop(output);
         }
+        if self.clock_out.take().is_some() {
+            self.send_realtime(sync::STOP);
+        }
+        if let Some(autosave) = &mut self.autosave {
+            autosave.finish()?;
+        }
+        Ok(())
     }
 
-    fn render_history(&self, frame: &mut Frame, area: Rect) {
-        let block = Block::default()
-            .title(" History ")
-            .borders(Borders::ALL)
-            .border_style(Theme::border());
+    /// Applies the settings a config file controls, apart from the hooks,
+    /// returning the key bindings it couldn't make.
+    pub fn apply_config(&mut self, config: &Config) -> Vec<Conflict> {
+        self.detection = config.detection;
+        self.piano_hires = config.display.piano.hires();
+        self.set_animations(config.display.animations);
+        self.language = config.display.language();
+        self.set_note_names(config.display.note_names());
+        self.status = config.display.status.clone();
+        self.set_quality_colors(
+            config
+                .display
+                .color_qualities
+                .then_some(config.display.quality_colors),
+        );
+        self.history.set_max_entries(config.display.history_length);
+        let (keymap, conflicts) = Keymap::from_config(&config.keys);
+        self.keymap = keymap;
+        self.capo = config.guitar.capo.min(MAX_CAPO);
+        self.tuning = config.guitar.tuning.clone();
+        self.left_handed = config.guitar.left_handed;
+        self.goals = config.practice.goals.clone();
+        conflicts
+    }
 
-        let inner = block.inner(area);
-        frame.render_widget(block, area);
-        frame.render_widget(&self.history, inner);
+    pub fn user(&self) -> Option<&UserDirs> {
+        self.user.as_ref()
     }
 
-    fn render_piano(&self, frame: &mut Frame, area: Rect) {
-        let block = Block::default()
-            .title(" Piano ")
-            .borders(Borders::ALL)
-            .border_style(Theme::border());
+    /// Loads a user's stats, practice log, milestones and, if learning is
+    /// on, learned transitions.
+    pub fn set_user(&mut self, user: UserDirs) {
+        self.set_stats_path(user.dictation_stats_path());
+        self.enable_milestones(user.milestones_path());
+        self.enable_practice_log(user.practice_log_path());
+        self.set_sessions_dir(user.sessions_dir());
+        if self.adaptive_path.is_some() {
+            let enabled = self.adaptive_enabled;
+            self.enable_adaptive(user.adaptive_model_path());
+            self.adaptive_enabled = enabled;
+            self.refresh_model();
+        }
+        self.user = Some(user);
+    }
 
-        let inner = block.inner(area);
-        frame.render_widget(block, area);
+    fn save_user_state(&self) -> Result<()> {
+        self.save_dictation_stats()?;
+        if let Some((milestones, path)) = &self.milestones {
+            milestones.save(path)?;
+        }
+        if let Some((log, path)) = &self.practice {
+            log.save(path)?;
+        }
+        if let Some(path) = &self.adaptive_path {
+            self.adaptive.save(path)?;
+        }
+        Ok(())
+    }
 
-        let notes = self.last_notes.clone();
-        let root = self.current_chord.as_ref().map(|c| c.root.midi);
+    /// Saves the current user's state and carries on as `user`, with
+    /// their config.
+    pub fn switch_user(&mut self, user: UserDirs) {
+        if let Err(e) = self.save_user_state() {
+            self.message = Some(self.tr_with(Text::StatsNotSaved, &[&self.user_name(), &e]));
+            return;
+        }
+        self.dictation = None;
+        self.hooks.session_end();
+        let config = Config::load_or_default(&user.config_path());
+        self.message = Some(match &config {
+            Ok(_) => self.tr_with(Text::SwitchedTo, &[&user.display_name()]),
+            Err(e) => self.tr_with(Text::ConfigIgnored, &[&user.display_name(), e]),
+        });
+        let config = config.unwrap_or_default();
+        // A clash in their key bindings matters more than the greeting
+        if let Some(conflict) = self.apply_config(&config).first() {
+            self.message = Some(conflict.to_string());
+        }
+        self.set_hooks(Hooks::new(config.hooks));
+        if let Some(mut autosave) = self.autosave.take() {
+            let _ = autosave.finish();
+            match Autosave::claim(&user.autosave_dir()) {
+                Ok(autosave) => self.enable_autosave(autosave),
+                Err(e) => self.message = Some(self.tr_with(Text::AutosaveOff, &[&e])),
+            }
+        }
+        self.set_user(user);
+    }
 
-        let piano = Piano::dynamic(&notes).pressed(notes).root(root);
-        frame.render_widget(piano, inner);
+    fn user_name(&self) -> &str {
+        self.user.as_ref().map_or("default", |u| u.display_name())
     }
 
-    fn render_status(&self, frame: &mut Frame, area: Rect) {
-        let mode_style = match self.mode {
-            Mode::Discovery => Theme::mode_discovery(),
-            Mode::Jam => Theme::mode_jam(),
+    pub fn toggle_user_picker(&mut self) {
+        if self.screens.close(|s| matches!(s, Screen::UserPicker(..))) {
+            return;
+        }
+        let Some(user) = &self.user else {
+            self.message = Some(self.tr(Text::NoUserDirectories).to_string());
+            return;
         };
+        let users: Vec<Option<String>> = std::iter::once(None)
+            .chain(user.users().into_iter().map(Some))
+            .collect();
+        let cursor = users.iter().position(|u| *u == user.name).unwrap_or(0);
+        self.screens.open(Screen::UserPicker(users, cursor));
+    }
 
-        let chord_text = self
-            .current_chord
-            .as_ref()
-            .map(|c| c.name())
-            .unwrap_or_else(|| "—".to_string());
-
-        let extended_text = if self.extended_chords { "ON" } else { "OFF" };
-
-        let status = Line::from(vec![
-            Span::styled(" [Tab] ", Theme::help_key()),
-            Span::styled("Mode: ", Theme::status_bar()),
-            Span::styled(self.mode.name(), mode_style),
-            Span::styled(" │ ", Theme::status_bar()),
-            Span::styled("Playing: ", Theme::status_bar()),
-            Span::styled(&chord_text, Theme::chord_name()),
-            Span::styled(" │ ", Theme::status_bar()),
-            Span::styled("[e] ", Theme::help_key()),
-            Span::styled("Extended: ", Theme::status_bar()),
-            Span::styled(extended_text, Theme::text()),
-            Span::styled(" │ ", Theme::status_bar()),
-            Span::styled("[?] ", Theme::help_key()),
-            Span::styled("Help", Theme::status_bar()),
-        ]);
+    fn handle_user_picker_key(&mut self, key: KeyCode) {
+        let Some(Screen::UserPicker(users, cursor)) = self.screens.top_mut() else {
+            return;
+        };
+        let count = users.len();
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => *cursor = (*cursor + count - 1) % count,
+            KeyCode::Down | KeyCode::Char('j') => *cursor = (*cursor + 1) % count,
+            KeyCode::Enter => {
+                let name = users[*cursor].clone();
+                self.screens.close_top();
+                let Some(current) = &self.user else {
+                    return;
+                };
+                if name != current.name {
+                    match current.switch(name.as_deref()) {
+                        Ok(user) => self.switch_user(user),
+                        Err(e) => self.message = Some(e.to_string()),
+                    }
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('W') | KeyCode::Char('q') => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+    }
 
-        let paragraph = Paragraph::new(status);
-        frame.render_widget(paragraph, area);
+    pub fn export(&mut self, format: ExportFormat) {
+        self.message = Some(self.write_export(&self.session, format));
     }
 
-    fn render_help_overlay(&self, frame: &mut Frame, area: Rect) {
-        let help_width = 40;
-        let help_height = 12;
-        let x = (area.width.saturating_sub(help_width)) / 2;
-        let y = (area.height.saturating_sub(help_height)) / 2;
+    fn write_export(&self, session: &Session, format: ExportFormat) -> String {
+        let path = export::default_path(format, timestamp());
+        let session = &match self.transposition {
+            Some(t) => session.transposed(t.semitones()),
+            None => session.clone(),
+        };
 
-        let help_area = Rect::new(x, y, help_width, help_height);
+        let bytes = match format {
+            ExportFormat::Midi => export::midi::render(
+                session,
+                &MidiOptions::default()
+                    .bass(Some(self.bass_style))
+                    .quantize(self.quantizer()),
+            ),
+            ExportFormat::Lilypond => lilypond::render(
+                session,
+                &LilypondOptions::default()
+                    .block_chords(true)
+                    .note_names(self.note_names),
+            )
+            .into_bytes(),
+        };
 
-        let help_text = vec![
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("  Tab    ", Theme::help_key()),
-                Span::styled("Toggle Discovery/Jam mode", Theme::help_text()),
-            ]),
-            Line::from(vec![
-                Span::styled("  e      ", Theme::help_key()),
-                Span::styled("Toggle extended chords", Theme::help_text()),
-            ]),
-            Line::from(vec![
-                Span::styled("  c      ", Theme::help_key()),
-                Span::styled("Clear history", Theme::help_text()),
-            ]),
-            Line::from(vec![
-                Span::styled("  ?      ", Theme::help_key()),
-                Span::styled("Toggle this help", Theme::help_text()),
-            ]),
-            Line::from(vec![
-                Span::styled("  q/Esc  ", Theme::help_key()),
-                Span::styled("Quit", Theme::help_text()),
-            ]),
-            Line::from(""),
-            Line::from(vec![Span::styled(
-                "  Press any key to close",
-                Theme::text_dim(),
-            )]),
-        ];
+        match fs::write(&path, bytes) {
+            Ok(()) => self.tr_with(Text::Exported, &[&path.display()]),
+            Err(e) => self.tr_with(Text::ExportFailed, &[&format.name(), &e]),
+        }
+    }
 
-        let block = Block::default()
-            .title(" Help ")
-            .borders(Borders::ALL)
-            .border_style(Theme::border_focused());
+    pub fn clear_history(&mut self) {
+        self.log(Event::Clear);
+        self.history.clear();
+        self.session.clear();
+        self.set_time_signature(self.time_signature);
+        self.sketch_captured = 0;
+        self.follow = FollowStats::default();
+        self.session_started = self.now();
+        self.set_key(None);
+        self.refresh_analysis();
+    }
 
-        let help = Paragraph::new(help_text).block(block);
-        frame.render_widget(help, help_area);
+    pub fn analysis(&self) -> &KeyAnalysis {
+        &self.analysis
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn refresh_analysis(&mut self) {
+        let chords: Vec<Chord> = self
+            .history
+            .entries()
+            .iter()
+            .map(|e| e.chord.clone())
+            .collect();
+        self.analysis = KeyAnalysis::analyze(&chords, KEY_WINDOW);
+        self.history.set_modulations(self.analysis.modulations());
+        self.tree.set_history(chords);
+    }
 
-    #[test]
-    fn test_mode_toggle() {
-        let mut app = App::new();
-        assert_eq!(app.mode, Mode::Discovery);
+    pub fn set_suggester(&mut self, suggester: Box<dyn Suggester>) {
+        self.message = Some(self.tr_with(Text::SuggestionProfile, &[&suggester.name()]));
+        self.tree.set_suggester(Some(suggester));
+        self.precompute_suggestions();
+    }
 
-        app.toggle_mode();
-        assert_eq!(app.mode, Mode::Jam);
+    pub fn toggle_presets(&mut self) {
+        self.toggle_screen(Screen::Presets);
+    }
 
-        app.toggle_mode();
-        assert_eq!(app.mode, Mode::Discovery);
+    pub fn preset_cursor(&self) -> usize {
+        self.preset_cursor
     }
 
-    #[test]
-    fn test_extended_toggle() {
-        let mut app = App::new();
-        assert!(!app.extended_chords);
+    pub fn load_preset(&mut self, preset: &Preset) {
+        let key = self.key.unwrap_or(Note::new(60));
+        self.trainer = Some(Trainer::new(preset.name, preset.chords(key)));
+    }
 
-        app.toggle_extended();
-        assert!(app.extended_chords);
+    pub fn toggle_playback(&mut self) {
+        let Some(output) = &mut self.output else {
+            return;
+        };
 
-        app.toggle_extended();
-        assert!(!app.extended_chords);
+        if self.playback.is_playing() {
+            self.playback.stop(output);
+        } else if let Some(trainer) = &self.trainer {
+            let playback = match self.groove.and_then(|i| self.grooves.get(i)) {
+                Some(groove) => {
+                    let bpm = self.bpm.unwrap_or(arpeggio::DEFAULT_BPM);
+                    let voicings: Vec<Vec<u8>> = trainer
+                        .target()
+                        .iter()
+                        .map(|c| c.voicing(VoicingOptions::default()))
+                        .collect();
+                    // A pass of the groove for each chord
+                    let bar = Duration::from_secs_f32(60.0 / bpm * groove.beats);
+                    Playback::grooved(&voicings, bar, groove, bpm)
+                }
+                None => Playback::chords(trainer.target(), Duration::from_secs(2)),
+            };
+            self.playback = playback.humanize(&self.humanize, &mut rand::rng());
+            self.playback.start(self.now());
+        }
     }
 
-    #[test]
-    fn test_handle_key_quit() {
-        let mut app = App::new();
-        assert!(!app.should_quit);
+    pub fn cycle_groove(&mut self) {
+        self.groove = match self.groove {
+            None if !self.grooves.is_empty() => Some(0),
+            Some(i) if i + 1 < self.grooves.len() => Some(i + 1),
+            _ => None,
+        };
+        self.message = Some(match self.groove() {
+            Some(groove) => self.tr_with(Text::GrooveIs, &[&groove.name]),
+            None => self.tr(Text::GrooveOff).to_string(),
+        });
+    }
 
-        app.handle_key(KeyCode::Char('q'));
-        assert!(app.should_quit);
+    pub fn groove(&self) -> Option<&Groove> {
+        self.groove.and_then(|i| self.grooves.get(i))
+    }
+
+    /// Picks a groove by name, case aside.
+    pub fn set_groove(&mut self, name: &str) -> Result<()> {
+        let index = self
+            .grooves
+            .iter()
+            .position(|g| g.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| anyhow!("Unknown groove: {}", name))?;
+        self.groove = Some(index);
+        Ok(())
+    }
+
+    fn audition(&mut self, chords: &[Chord]) {
+        let voicings: Vec<Vec<u8>> = chords
+            .iter()
+            .map(|c| c.voicing(VoicingOptions::default()))
+            .collect();
+        self.audition_voicings(&voicings, Duration::from_millis(1500));
+    }
+
+    /// Plays chords for listening, through the arpeggiator when it's on.
+    fn audition_voicings(&mut self, voicings: &[Vec<u8>], chord_duration: Duration) {
+        if self.arp.is_on() {
+            let bpm = self.bpm.unwrap_or(arpeggio::DEFAULT_BPM);
+            self.play(Playback::arpeggiated(
+                voicings,
+                chord_duration,
+                &self.arp,
+                bpm,
+                &mut rand::rng(),
+            ));
+        } else {
+            self.play(Playback::voicings(voicings, chord_duration));
+        }
+    }
+
+    /// Plays the chord held, then the expected or surprise chord after it,
+    /// then the tonic, so the two branches can be told apart by ear.
+    pub fn audition_branch(&mut self, surprise: bool) {
+        let Some(chords) = self.branch_in_context(surprise) else {
+            self.message = Some(self.tr(Text::PlayToAudition).to_string());
+            return;
+        };
+        let names: Vec<String> = chords.iter().map(|c| self.chord_name(c)).collect();
+        let branch = if surprise {
+            Text::SurpriseBranch
+        } else {
+            Text::ExpectedBranch
+        };
+        self.message = Some(self.tr_with(branch, &[&names.join(" → ")]));
+        self.audition(&chords);
+    }
+
+    /// The chord held, a branch of its suggestions and the tonic of the
+    /// key set or, failing that, the key the recent chords fit best.
+    fn branch_in_context(&self, surprise: bool) -> Option<Vec<Chord>> {
+        let chord = self.current_chord.clone()?;
+        let tree = self.planned_tree(&chord);
+        let branch = if surprise { tree.right } else { tree.left };
+        let mut chords = vec![chord, branch?.chord];
+        let entries = self.history.entries();
+        let recent: Vec<Chord> = entries[entries.len().saturating_sub(KEY_FINDER_CHORDS)..]
+            .iter()
+            .map(|e| e.chord.clone())
+            .collect();
+        let home = self
+            .scale()
+            .or_else(|| keys::rank_keys(&recent).first().map(|k| k.scale));
+        if let Some(tonic) = home.map(|scale| scale.diatonic(0, false)) {
+            if chords.last() != Some(&tonic) {
+                chords.push(tonic);
+            }
+        }
+        Some(chords)
+    }
+
+    pub fn cycle_arp_pattern(&mut self) {
+        self.arp.pattern = self.arp.pattern.next();
+        self.message = Some(self.tr_with(Text::ArpPattern, &[&self.arp.pattern.name()]));
+    }
+
+    pub fn cycle_arp_rate(&mut self) {
+        self.arp.rate = self.arp.rate.next();
+        self.message = Some(self.tr_with(Text::ArpRate, &[&self.arp.rate.name()]));
+    }
+
+    fn play(&mut self, playback: Playback) {
+        let Some(output) = &mut self.output else {
+            self.message = Some(self.tr(Text::NoOutputForDrills).to_string());
+            return;
+        };
+
+        self.playback.stop(output);
+        self.playback = playback.humanize(&self.humanize, &mut rand::rng());
+        self.playback.start(self.now());
+    }
+
+    pub fn toggle_intervals(&mut self) {
+        if self.screens.close(|s| matches!(s, Screen::Intervals(_))) {
+            return;
+        }
+
+        self.screens.open(Screen::Intervals(Box::default()));
+        self.interval_cursor = 0;
+        self.next_interval();
+    }
+
+    pub fn interval_cursor(&self) -> usize {
+        self.interval_cursor
+    }
+
+    /// The interval drill, if one is open.
+    pub fn intervals(&self) -> Option<&IntervalDrill> {
+        self.screens.iter().find_map(|s| match s {
+            Screen::Intervals(drill) => Some(drill.as_ref()),
+            _ => None,
+        })
+    }
+
+    fn next_interval(&mut self) {
+        let Some(drill) = self.screens.iter_mut().find_map(|s| match s {
+            Screen::Intervals(drill) => Some(drill),
+            _ => None,
+        }) else {
+            return;
+        };
+
+        let question = match drill.current() {
+            Some(question) if !drill.is_answered() => question,
+            _ => drill.next_question(),
+        };
+        self.play(Playback::melody(
+            &question.notes(),
+            Duration::from_millis(700),
+        ));
+    }
+
+    fn answer_interval(&mut self, semitones: Option<u8>, played: Option<(u8, u8)>) {
+        let Some(drill) = self.screens.iter_mut().find_map(|s| match s {
+            Screen::Intervals(drill) => Some(drill),
+            _ => None,
+        }) else {
+            return;
+        };
+
+        let result = match (semitones, played) {
+            (Some(semitones), _) => drill.answer(semitones),
+            (None, Some((a, b))) => drill.answer_played(a, b),
+            (None, None) => None,
+        };
+        let (Some(correct), Some(question)) = (result, drill.current()) else {
+            return;
+        };
+
+        let name = interval_name(question.semitones);
+        let text = if correct {
+            Text::IntervalRight
+        } else {
+            Text::IntervalWrong
+        };
+        self.message = Some(self.language.fill(text, &[&name, &drill.level()]));
+        self.interval_cursor = self.interval_cursor.min(drill.choices().len() - 1);
+    }
+
+    fn handle_intervals_key(&mut self, key: KeyCode) {
+        let Some(Screen::Intervals(drill)) = self.screens.top() else {
+            return;
+        };
+        let choices = drill.choices();
+
+        match key {
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.interval_cursor = (self.interval_cursor + choices.len() - 1) % choices.len();
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.interval_cursor = (self.interval_cursor + 1) % choices.len();
+            }
+            KeyCode::Enter => {
+                let semitones = choices[self.interval_cursor.min(choices.len() - 1)];
+                self.answer_interval(Some(semitones), None);
+            }
+            KeyCode::Char(' ') => self.next_interval(),
+            KeyCode::Esc | KeyCode::Char('i') | KeyCode::Char('q') => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+    }
+
+    pub fn toggle_explorer(&mut self) {
+        if self.screens.close(|s| matches!(s, Screen::Explorer(..))) {
+            return;
+        }
+
+        match &self.current_chord {
+            Some(chord) => self.screens.open(Screen::Explorer(chord.clone(), 0)),
+            None => self.message = Some(self.tr(Text::PlayToExplore).to_string()),
+        }
+    }
+
+    pub fn explorer_voicing(&self) -> Option<VoicingOption> {
+        let (chord, index) = self.screens.iter().find_map(|s| match s {
+            Screen::Explorer(chord, index) => Some((chord, index)),
+            _ => None,
+        })?;
+        let options = voicing::explore(chord);
+        options.get(index % options.len().max(1)).cloned()
+    }
+
+    fn handle_explorer_key(&mut self, key: KeyCode) {
+        let Some(Screen::Explorer(chord, index)) = self.screens.top_mut() else {
+            return;
+        };
+        let count = voicing::explore(chord).len().max(1);
+
+        match key {
+            KeyCode::Left | KeyCode::Char('h') => *index = (*index + count - 1) % count,
+            KeyCode::Right | KeyCode::Char('l') => *index = (*index + 1) % count,
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if let Some(option) = self.explorer_voicing() {
+                    self.audition_voicings(&[option.notes], Duration::from_millis(1500));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('v') | KeyCode::Char('q') => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+    }
+
+    pub fn toggle_fingering(&mut self) {
+        if self.screens.close(|s| matches!(s, Screen::Fingering(..))) {
+            return;
+        }
+
+        match &self.current_chord {
+            Some(chord) => self.screens.open(Screen::Fingering(chord.clone(), 0)),
+            None => self.message = Some(self.tr(Text::PlayForGrips).to_string()),
+        }
+    }
+
+    /// The chord whose guitar grips are shown, and which grip.
+    pub fn fingering(&self) -> Option<(&Chord, usize)> {
+        self.screens.iter().find_map(|s| match s {
+            Screen::Fingering(chord, index) => Some((chord, *index)),
+            _ => None,
+        })
+    }
+
+    pub fn fingering_grips(&self) -> Vec<Fingering> {
+        self.fingering()
+            .map(|(chord, _)| fingerings(chord, &self.tuning, self.capo))
+            .unwrap_or_default()
+    }
+
+    pub fn selected_grip(&self) -> Option<Fingering> {
+        let (_, index) = self.fingering()?;
+        let grips = self.fingering_grips();
+        grips.get(index % grips.len().max(1)).cloned()
+    }
+
+    /// Steps the grips between the chord being played and its two
+    /// suggestions.
+    fn next_fingering_chord(&mut self) {
+        let Some(current) = self.current_chord.clone() else {
+            return;
+        };
+        let node = self.planned_tree(&current);
+        let mut targets = vec![current];
+        targets.extend(node.left.map(|n| n.chord));
+        targets.extend(node.right.map(|n| n.chord));
+
+        if let Some(Screen::Fingering(chord, index)) = self.screens.top_mut() {
+            let at = targets.iter().position(|c| c == chord);
+            *chord = targets[at.map_or(0, |at| (at + 1) % targets.len())].clone();
+            *index = 0;
+        }
+    }
+
+    fn handle_fingering_key(&mut self, key: KeyCode) {
+        let count = self.fingering_grips().len().max(1);
+        let Some(Screen::Fingering(_, index)) = self.screens.top_mut() else {
+            return;
+        };
+
+        match key {
+            KeyCode::Left | KeyCode::Char('h') => *index = (*index + count - 1) % count,
+            KeyCode::Right | KeyCode::Char('l') => *index = (*index + 1) % count,
+            KeyCode::Tab => self.next_fingering_chord(),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if let Some(grip) = self.selected_grip() {
+                    let notes: Vec<u8> = grip
+                        .frets
+                        .iter()
+                        .zip(&self.tuning.strings)
+                        .filter_map(|(fret, open)| {
+                            fret.and_then(|f| open.transpose((f + self.capo) as i8))
+                        })
+                        .map(|n| n.midi)
+                        .collect();
+                    self.audition_voicings(&[notes], Duration::from_millis(1500));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('f') | KeyCode::Char('q') => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+    }
+
+    /// Latched notes keep sounding after release until a new chord is
+    /// started, so chords can be built a note at a time.
+    pub fn toggle_latch(&mut self) {
+        let Some(midi) = &self.midi else {
+            self.message = Some(self.tr(Text::LatchNeedsInput).to_string());
+            return;
+        };
+        let latched = !midi.is_latched();
+        midi.set_latched(latched);
+        let text = if latched {
+            Text::LatchOn
+        } else {
+            Text::LatchOff
+        };
+        self.message = Some(self.tr(text).to_string());
+    }
+
+    pub fn toggle_smoothing(&mut self) {
+        self.detection.smoothing = !self.detection.smoothing;
+        self.smoother.clear();
+        let text = if self.detection.smoothing {
+            Text::SmoothingOn
+        } else {
+            Text::SmoothingOff
+        };
+        self.message = Some(self.tr(text).to_string());
+    }
+
+    pub fn toggle_melody(&mut self) {
+        self.detection.melody = !self.detection.melody;
+        let text = if self.detection.melody {
+            Text::MelodyOn
+        } else {
+            Text::MelodyOff
+        };
+        self.message = Some(self.tr(text).to_string());
+    }
+
+    /// The held note being played as melody over the chord, if any.
+    pub fn melody_note(&self) -> Option<u8> {
+        if !self.detection.melody {
+            return None;
+        }
+        melody::melody(&self.last_notes, &self.onsets, self.now())
+    }
+
+    /// The bass note held or repeated as a pedal under the changing chords.
+    pub fn pedal_note(&self) -> Option<u8> {
+        self.pedal_point.pedal()
+    }
+
+    pub fn is_latched(&self) -> bool {
+        self.midi.as_ref().is_some_and(|m| m.is_latched())
+    }
+
+    pub fn toggle_pads(&mut self) {
+        self.pads = !self.pads;
+        self.message = Some(if self.pads {
+            self.tr_with(Text::PadsOn, &[&self.pad_scale().name_in(self.note_names)])
+        } else {
+            self.tr(Text::PadsOff).to_string()
+        });
+    }
+
+    /// The key the pads play in, C until one is known.
+    fn pad_scale(&self) -> Scale {
+        Scale::major(self.key.unwrap_or(Note::new(60)))
+    }
+
+    /// The chord on pad `pad` (0-7), seventh chords with extended on.
+    pub fn pad_chord(&self, pad: u8) -> Option<Chord> {
+        (pad < PADS).then(|| {
+            self.pad_scale()
+                .diatonic(pad as usize % 7, self.extended_chords)
+        })
+    }
+
+    /// Plays a pad's chord and feeds it through detection as if it had
+    /// been played on the keyboard; it is let go on the next tick.
+    pub fn trigger_pad(&mut self, pad: u8) {
+        let Some(chord) = self.pad_chord(pad) else {
+            return;
+        };
+        let octave = if pad == PADS - 1 { 5 } else { 4 };
+        let notes = chord.voicing(VoicingOptions {
+            octave,
+            ..VoicingOptions::default()
+        });
+
+        if self.output.is_some() {
+            let length = if self.arp.is_on() { 2000 } else { 800 };
+            self.audition_voicings(std::slice::from_ref(&notes), Duration::from_millis(length));
+        }
+        self.notes_changed(notes.into_iter().collect());
+    }
+
+    pub fn tap(&mut self, now: Instant) {
+        self.auto_tempo = false;
+        if let Some(bpm) = self.tap_tempo.tap(now) {
+            self.bpm = Some(bpm);
+        }
+    }
+
+    pub fn toggle_auto_tempo(&mut self) {
+        self.auto_tempo = !self.auto_tempo;
+        let text = if self.auto_tempo {
+            Text::AutoTempoOn
+        } else {
+            Text::AutoTempoOff
+        };
+        self.message = Some(self.tr(text).to_string());
+    }
+
+    pub fn quantizer(&self) -> Option<Quantizer> {
+        self.bpm.map(|bpm| {
+            Quantizer::new(bpm)
+                .resolution(self.resolution)
+                .feel(self.feel)
+                .time_signature(self.session.time_signature_at(0))
+        })
+    }
+
+    pub fn set_time_signature(&mut self, time_signature: TimeSignature) {
+        self.time_signature = time_signature;
+        self.session.set_time_signature(time_signature);
+        self.log(Event::TimeSignature {
+            time_signature: time_signature.name().to_string(),
+        });
+    }
+
+    pub fn cycle_time_signature(&mut self) {
+        let time_signature = self.time_signature.next();
+        self.set_time_signature(time_signature);
+        let text = match self.looper {
+            Some(_) => Text::TimeSignatureFromNextLoop,
+            None => Text::TimeSignatureIs,
+        };
+        self.message = Some(self.tr_with(text, &[&time_signature.name()]));
+    }
+
+    /// How long a bar lasts at the tempo.
+    fn bar_length(&self) -> Option<Duration> {
+        self.bpm
+            .map(|bpm| Duration::from_secs_f32(60.0 / bpm * self.time_signature.quarters()))
+    }
+
+    pub fn toggle_looper(&mut self) {
+        if let Some(looper) = &mut self.looper {
+            looper.toggle_overdub();
+            return;
+        }
+
+        match self.bpm {
+            Some(bpm) => {
+                let now = self.now();
+                self.looper = Some(
+                    Looper::record(self.loop_bars, bpm, now).time_signature(self.time_signature),
+                );
+                if self.send_clock {
+                    self.send_realtime(sync::START);
+                    self.clock_out = Some(ClockSender::start(bpm, now));
+                }
+            }
+            None => self.message = Some(self.tr(Text::SetTempoFirst).to_string()),
+        }
+    }
+
+    pub fn stop_looper(&mut self) {
+        if self.looper.take().is_some() {
+            if let Some(output) = &mut self.output {
+                self.playback.stop(output);
+            }
+        }
+        if self.clock_out.take().is_some() {
+            self.send_realtime(sync::STOP);
+        }
+    }
+
+    pub fn clock_source(&self) -> ClockSource {
+        if self
+            .midi
+            .as_ref()
+            .is_some_and(|m| m.clock_tempo().is_some())
+        {
+            ClockSource::Following
+        } else if self.clock_out.is_some() {
+            ClockSource::Sending
+        } else {
+            ClockSource::Internal
+        }
+    }
+
+    fn send_realtime(&mut self, message: u8) {
+        if let Some(output) = &mut self.output {
+            output.send(&[message]);
+        }
+    }
+
+    fn tick_looper(&mut self, now: Instant) {
+        if let Some(bpm) = self.midi.as_ref().and_then(|m| m.clock_tempo()) {
+            self.bpm = Some(bpm);
+        }
+        if let Some(clock) = &mut self.clock_out {
+            for _ in 0..clock.due(now) {
+                self.send_realtime(sync::CLOCK);
+            }
+        }
+        let Some(looper) = &mut self.looper else {
+            return;
+        };
+        if !looper.tick(now) {
+            return;
+        }
+
+        if let Some(output) = &mut self.output {
+            self.playback.stop(output);
+            let groove = self.groove.and_then(|i| self.grooves.get(i));
+            self.playback = looper
+                .playback(groove)
+                .humanize(&self.humanize, &mut rand::rng());
+            self.playback.start(now);
+        }
+    }
+
+    pub fn cycle_humanize(&mut self) {
+        let amount = (self.humanize.amount + 0.25) % 1.25;
+        self.humanize = Humanize::new(amount).roll(self.humanize.roll);
+        self.message = Some(if self.humanize.is_off() {
+            self.tr(Text::HumanizeOff).to_string()
+        } else {
+            let percent = format!("{:.0}", self.humanize.amount * 100.0);
+            self.tr_with(Text::HumanizeBy, &[&percent])
+        });
+    }
+
+    pub fn start_play_along(&mut self, name: &str, file: MidiFile) {
+        self.along = Some(PlayAlong::new(name, file.chord_timeline()));
+        self.along_started = self.now();
+
+        match &mut self.output {
+            Some(output) => {
+                self.playback.stop(output);
+                self.playback = file.playback();
+                self.playback.start(self.along_started);
+            }
+            None => self.message = Some(self.tr(Text::NoOutputForAlong).to_string()),
+        }
+        self.along_file = file;
+    }
+
+    pub fn along_time(&self) -> f64 {
+        self.now()
+            .saturating_duration_since(self.along_started)
+            .as_secs_f64()
+    }
+
+    fn tick_along(&mut self) {
+        let time = self.along_time();
+        let Some(along) = &mut self.along else {
+            return;
+        };
+
+        if let (false, Some(chord)) = (self.last_notes.is_empty(), &self.current_chord) {
+            along.check(chord, time);
+        }
+        if along.is_finished(time) && self.message.is_none() {
+            let score = along.score(time);
+            let name = along.name().to_string();
+            self.message = Some(self.tr_with(
+                Text::AlongFinished,
+                &[&name, &score.correct, &score.attempts],
+            ));
+        }
+    }
+
+    /// The suggestion tree after `chord` with the plan put in.
+    pub fn planned_tree(&self, chord: &Chord) -> ProgressionNode {
+        let node = self.suggest(chord);
+        self.plan.apply(node, |c| self.suggest(c))
+    }
+
+    /// Editing moves a cursor over the suggestion tree to pin branches or
+    /// replace them with chords typed in by name.
+    pub fn toggle_plan_editing(&mut self) {
+        self.plan_input = None;
+        if self.screens.close(|s| matches!(s, Screen::Plan(_))) {
+            return;
+        }
+        if self.current_chord.is_none() {
+            self.message = Some(self.tr(Text::PlayToPlan).to_string());
+            return;
+        }
+        self.screens.open(Screen::Plan(vec![Branch::Expected]));
+    }
+
+    pub fn is_editing_plan(&self) -> bool {
+        self.plan_cursor().is_some()
+    }
+
+    /// The branch selected while editing the plan.
+    fn plan_cursor(&self) -> Option<&Vec<Branch>> {
+        self.screens.iter().find_map(|s| match s {
+            Screen::Plan(cursor) => Some(cursor),
+            _ => None,
+        })
+    }
+
+    fn handle_plan_key(&mut self, key: KeyCode) {
+        let Some(Screen::Plan(cursor)) = self.screens.top() else {
+            return;
+        };
+        let mut cursor = cursor.clone();
+        if let Some(input) = &mut self.plan_input {
+            match key {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let name = input.trim().to_string();
+                    self.plan_input = None;
+                    match Chord::from_name(&name) {
+                        Some(chord) => {
+                            self.message =
+                                Some(self.tr_with(Text::Planned, &[&self.chord_name(&chord)]));
+                            self.plan.set(cursor, chord);
+                        }
+                        None => self.message = Some(self.tr_with(Text::UnknownChord, &[&name])),
+                    }
+                }
+                KeyCode::Esc => self.plan_input = None,
+                _ => {}
+            }
+            return;
+        }
+
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => {
+                cursor.pop();
+                cursor.push(Branch::Expected);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                cursor.pop();
+                cursor.push(Branch::Surprise);
+            }
+            KeyCode::Right | KeyCode::Char('l') if cursor.len() < PLAN_DEPTH => {
+                cursor.push(Branch::Expected);
+            }
+            KeyCode::Left | KeyCode::Char('h') if cursor.len() > 1 => {
+                cursor.pop();
+            }
+            KeyCode::Char(' ') | KeyCode::Char('p') => self.toggle_pin(&cursor),
+            KeyCode::Enter => self.plan_input = Some(String::new()),
+            KeyCode::Backspace | KeyCode::Delete | KeyCode::Char('x') => {
+                self.plan.remove(&cursor);
+            }
+            KeyCode::Char('c') => self.plan.clear(),
+            KeyCode::Esc | KeyCode::Char('E') => {
+                self.screens.close_top();
+                return;
+            }
+            _ => {}
+        }
+        if let Some(Screen::Plan(open)) = self.screens.top_mut() {
+            *open = cursor;
+        }
+    }
+
+    /// Pins the suggestion at `path` so it stays put, or unpins it.
+    fn toggle_pin(&mut self, path: &[Branch]) {
+        if self.plan.get(path).is_some() {
+            self.plan.remove(path);
+            return;
+        }
+        let Some(current) = &self.current_chord else {
+            return;
+        };
+        if let Some(chord) = chord_at(&self.planned_tree(current), path).cloned() {
+            self.message = Some(self.tr_with(Text::Pinned, &[&self.chord_name(&chord)]));
+            self.plan.set(path.to_vec(), chord);
+        }
+    }
+
+    pub fn toggle_sketch(&mut self) {
+        if self.screens.close(|s| matches!(s, Screen::Sketch(_))) {
+            return;
+        }
+        let last = self.sketch.sections.len().saturating_sub(1);
+        self.screens.open(Screen::Sketch(last));
+    }
+
+    pub fn is_sketching(&self) -> bool {
+        self.screens.any(|s| matches!(s, Screen::Sketch(_)))
+    }
+
+    /// Selects a section of the open sketch, opening it if it isn't.
+    fn select_section(&mut self, index: usize) {
+        let open = self.screens.iter_mut().find_map(|s| match s {
+            Screen::Sketch(cursor) => Some(cursor),
+            _ => None,
+        });
+        match open {
+            Some(cursor) => *cursor = index,
+            None => self.screens.open(Screen::Sketch(index)),
+        }
+    }
+
+    /// Steps on hands-free: through the sketch while it's open, otherwise
+    /// to the next dictation chord or the loaded progression's next chord,
+    /// back to its start once it's done.
+    pub fn page_turn(&mut self) {
+        if self.is_sketching() {
+            self.sketch_position = self.sketch.next_position(self.sketch_position);
+            if let Some(at) = self.sketch_position {
+                self.select_section(at.section);
+            }
+        } else if self.dictation.is_some() {
+            self.next_dictation();
+        } else if let Some(trainer) = &mut self.trainer {
+            if trainer.is_complete() {
+                trainer.restart();
+            } else {
+                trainer.skip();
+            }
+        }
+    }
+
+    fn pedal_tap(&mut self, now: Instant) {
+        let double = self
+            .pedal_tapped
+            .take()
+            .is_some_and(|first| now.duration_since(first) <= DOUBLE_TAP);
+        if double {
+            self.page_turn();
+        } else {
+            self.pedal_tapped = Some(now);
+        }
+    }
+
+    /// Makes a section of the chords played since the last one was captured.
+    pub fn capture_section(&mut self) {
+        let start = self.sketch_captured.min(self.session.entries.len());
+        let chords = self.session.entries[start..].to_vec();
+        if chords.is_empty() {
+            self.message = Some(self.tr(Text::NothingSinceSection).to_string());
+            return;
+        }
+        let count = chords.len();
+        let index = self.sketch.add(chords);
+        self.sketch_captured = self.session.entries.len();
+        self.select_section(index);
+        let name = self.sketch.sections[index].name_in(self.language.section_names());
+        self.message = Some(self.tr_with(Text::Captured, &[&name, &count]));
+    }
+
+    fn handle_sketch_key(&mut self, key: KeyCode) {
+        let Some(&Screen::Sketch(cursor)) = self.screens.top() else {
+            return;
+        };
+        let count = self.sketch.sections.len();
+        let section = self.sketch.sections.get_mut(cursor);
+        match key {
+            KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                self.select_section((cursor + count - 1) % count)
+            }
+            KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                self.select_section((cursor + 1) % count)
+            }
+            KeyCode::Char('K') => {
+                let index = self.sketch.shift(cursor, false);
+                self.select_section(index);
+            }
+            KeyCode::Char('J') => {
+                let index = self.sketch.shift(cursor, true);
+                self.select_section(index);
+            }
+            KeyCode::Char('a') => self.capture_section(),
+            KeyCode::Char('n') => {
+                if let Some(section) = section {
+                    section.cycle_name();
+                }
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                if let Some(section) = section {
+                    section.set_repeats(section.repeats + 1);
+                }
+            }
+            KeyCode::Char('-') => {
+                if let Some(section) = section {
+                    section.set_repeats(section.repeats - 1);
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some(index) = self.sketch.duplicate(cursor) {
+                    self.select_section(index);
+                }
+            }
+            KeyCode::Char('x') | KeyCode::Delete | KeyCode::Backspace => {
+                self.sketch.remove(cursor);
+                let last = self.sketch.sections.len().saturating_sub(1);
+                self.select_section(cursor.min(last));
+            }
+            KeyCode::Char(' ') => self.page_turn(),
+            KeyCode::Char('m') => self.export_sketch(ExportFormat::Midi),
+            KeyCode::Char('t') => self.export_sketch_text(),
+            KeyCode::Esc | KeyCode::Char('C') | KeyCode::Char('q') => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+    }
+
+    pub fn open_reharm(&mut self) {
+        let entries = self.history.entries();
+        let chords: Vec<Chord> = entries[entries.len().saturating_sub(REHARM_CHORDS)..]
+            .iter()
+            .map(|e| e.chord.clone())
+            .collect();
+        if chords.is_empty() {
+            self.message = Some(self.tr(Text::PlayToReharmonize).to_string());
+            return;
+        }
+        self.screens.open(Screen::Reharm(chords, 0));
+    }
+
+    fn handle_reharm_key(&mut self, key: KeyCode) {
+        let Some(Screen::Reharm(chords, cursor)) = self.screens.top_mut() else {
+            return;
+        };
+        let options = reharm::reharmonize(chords);
+        let count = options.len();
+        match key {
+            KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                *cursor = (*cursor + count - 1) % count
+            }
+            KeyCode::Down | KeyCode::Char('j') if count > 0 => *cursor = (*cursor + 1) % count,
+            KeyCode::Enter => {
+                let cursor = *cursor;
+                self.screens.close_top();
+                if let Some(option) = options.get(cursor) {
+                    self.replace_sketch(option);
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('h') | KeyCode::Char('q') => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+    }
+
+    /// Makes the reharmonized chords the whole sketch, as one section.
+    fn replace_sketch(&mut self, reharm: &Reharmonization) {
+        let chords = reharm
+            .chords
+            .iter()
+            .map(|chord| SessionEntry {
+                chord: chord.name(),
+                notes: chord.notes(),
+                at: None,
+            })
+            .collect();
+        self.sketch = Sketch::default();
+        self.sketch.add(chords);
+        self.sketch_position = None;
+        self.message = Some(self.tr_with(Text::SketchReplaced, &[&reharm.technique.name()]));
+    }
+
+    pub fn export_sketch(&mut self, format: ExportFormat) {
+        if self.sketch.is_empty() {
+            self.message = Some(self.tr(Text::NoSections).to_string());
+            return;
+        }
+        let session = self.sketch.to_session(self.session.key.clone());
+        self.message = Some(self.write_export(&session, format));
+    }
+
+    pub fn export_sketch_text(&mut self) {
+        if self.sketch.is_empty() {
+            self.message = Some(self.tr(Text::NoSections).to_string());
+            return;
+        }
+        let path = PathBuf::from(format!("chordvery-{}.txt", timestamp()));
+        let sketch = match self.transposition {
+            Some(t) => self.sketch.transposed(t.semitones(), t.to),
+            None => self.sketch.clone(),
+        };
+        let text = sketch.to_text(self.note_names, self.language.section_names());
+        self.message = Some(match fs::write(&path, text) {
+            Ok(()) => self.tr_with(Text::Exported, &[&path.display()]),
+            Err(e) => self.tr_with(Text::TextExportFailed, &[&e]),
+        });
+    }
+
+    pub fn toggle_feel(&mut self) {
+        self.feel = self.feel.toggle();
+        self.message = Some(self.tr_with(Text::QuantizeFeel, &[&self.feel.name()]));
+    }
+
+    /// Scores the new chord against what was suggested after the last one.
+    fn check_followed(&mut self, chord: &Chord) {
+        let Some(previous) = self.history.entries().last().map(|e| e.chord.clone()) else {
+            return;
+        };
+        let node = self.suggest(&previous);
+        let followed = Followed::classify(&node, chord);
+        self.follow.record(followed);
+
+        let name = |branch: &Option<Box<ProgressionNode>>| {
+            branch.as_ref().map(|n| n.chord.name()).unwrap_or_default()
+        };
+        self.log(Event::Suggestion {
+            expected: name(&node.left),
+            surprise: name(&node.right),
+            followed,
+        });
+    }
+
+    /// Starts the blues form on the tonic, the first chord setting the key
+    /// when there isn't one.
+    fn count_blues_bar(&mut self, chord: &Chord) {
+        if self.blues.is_none() {
+            return;
+        }
+        if self.key.is_none() {
+            self.set_key(Some(chord.root));
+        }
+        let now = self.now();
+        if let (Some(form), Some(key)) = (&mut self.blues, self.key) {
+            form.chord_played(chord, key, now);
+        }
+    }
+
+    fn record_chord_change(&mut self, now: Instant) {
+        self.tempo_estimator.record(now);
+        if self.auto_tempo {
+            if let Some(bpm) = self.tempo_estimator.bpm() {
+                self.bpm = Some(bpm);
+            }
+        }
+    }
+
+    pub fn set_quality_colors(&mut self, colors: Option<QualityColors>) {
+        self.quality_colors = colors;
+        self.history.set_quality_colors(colors);
+    }
+
+    /// `base` in the chord's quality colour, when chords are coloured so.
+    fn chord_style(&self, chord: &Chord, base: Style) -> Style {
+        match &self.quality_colors {
+            Some(colors) => colors.style(chord, base),
+            None => base,
+        }
+    }
+
+    pub fn set_animations(&mut self, enabled: bool) {
+        self.animations.set_enabled(enabled);
+        self.history.set_slide(0);
+    }
+
+    pub fn set_sessions_dir(&mut self, dir: PathBuf) {
+        self.sessions_dir = Some(dir);
+    }
+
+    pub fn toggle_browser(&mut self) {
+        if self.screens.close(|s| matches!(s, Screen::Sessions(_))) {
+            return;
+        }
+        match &self.sessions_dir {
+            Some(dir) => self
+                .screens
+                .open(Screen::Sessions(SessionBrowser::open(dir))),
+            None => self.message = Some(self.tr(Text::NoSessionsDirectory).to_string()),
+        }
+    }
+
+    fn handle_browser_key(&mut self, key: KeyCode) {
+        let Some(Screen::Sessions(browser)) = self.screens.top_mut() else {
+            return;
+        };
+        let confirm_delete = std::mem::take(&mut browser.confirm_delete);
+
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => browser.prev(),
+            KeyCode::Down | KeyCode::Char('j') => browser.next(),
+            KeyCode::Esc | KeyCode::Char('S') | KeyCode::Char('q') => {
+                self.screens.close_top();
+            }
+            KeyCode::Enter => {
+                if let Some(summary) = browser.selected() {
+                    let message = self.language.fill(Text::ResumedSession, &[&summary.date()]);
+                    let session = summary.session.clone();
+                    self.screens.close_top();
+                    self.restore(session);
+                    self.message = Some(message);
+                }
+            }
+            KeyCode::Char('c') => {
+                if let Some(diff) = browser.compare_selected() {
+                    self.screens.open(Screen::Compare(diff, 0));
+                } else if browser.reference.is_some() {
+                    self.message = Some(self.tr(Text::MarkedToCompare).to_string());
+                }
+            }
+            KeyCode::Tab => browser.next_bookmark(),
+            KeyCode::Char('p') => {
+                if let Some(summary) = browser.selected() {
+                    let playback = match browser.selected_bookmark() {
+                        Some(bookmark) => browser::replay_from(&summary.session, bookmark.at),
+                        None => browser::replay(&summary.session),
+                    };
+                    self.play(playback);
+                }
+            }
+            KeyCode::Char('x') | KeyCode::Char('X') => {
+                if let Some(session) = browser.selected().map(|s| s.session.clone()) {
+                    let format = if key == KeyCode::Char('x') {
+                        ExportFormat::Lilypond
+                    } else {
+                        ExportFormat::Midi
+                    };
+                    self.message = Some(self.write_export(&session, format));
+                }
+            }
+            KeyCode::Char('d') => {
+                let recording = self.event_log.as_ref().map(|w| w.path().to_path_buf());
+                let Some(path) = browser.selected().map(|s| s.path.clone()) else {
+                    return;
+                };
+                if recording.as_ref() == Some(&path) {
+                    self.message = Some(self.tr(Text::StillRecording).to_string());
+                } else if !confirm_delete {
+                    browser.confirm_delete = true;
+                    self.message = Some(self.tr(Text::ConfirmDelete).to_string());
+                } else {
+                    self.message = Some(match browser.delete_selected() {
+                        Ok(()) => self.language.fill(Text::Deleted, &[&path.display()]),
+                        Err(e) => self
+                            .language
+                            .fill(Text::DeleteFailed, &[&path.display(), &e]),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn set_stats_path(&mut self, path: PathBuf) {
+        self.stats_path = Some(path);
+    }
+
+    pub fn set_devices_path(&mut self, path: PathBuf) {
+        self.devices_path = Some(path);
+    }
+
+    /// Starts measuring soft and hard playing on the MIDI input, or gives
+    /// up on a calibration under way.
+    pub fn toggle_calibration(&mut self) {
+        if self.screens.close(|s| matches!(s, Screen::Calibration(_))) {
+            return;
+        }
+        let Some(midi) = &self.midi else {
+            self.message = Some(self.tr(Text::ConnectToCalibrate).to_string());
+            return;
+        };
+        midi.take_velocities();
+        self.screens
+            .open(Screen::Calibration(Calibration::default()));
+    }
+
+    /// The velocity calibration under way, if any.
+    pub fn calibration(&self) -> Option<&Calibration> {
+        self.screens.iter().find_map(|s| match s {
+            Screen::Calibration(calibration) => Some(calibration),
+            _ => None,
+        })
+    }
+
+    fn handle_calibration_key(&mut self, key: KeyCode) {
+        let Some(Screen::Calibration(calibration)) = self.screens.top_mut() else {
+            return;
+        };
+        match key {
+            KeyCode::Enter => self.finish_calibration(),
+            KeyCode::Char('r') => *calibration = Calibration::default(),
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+    }
+
+    /// Puts the measured curve to use for the connected device and saves
+    /// it with the device's settings.
+    fn finish_calibration(&mut self) {
+        let Some(Screen::Calibration(calibration)) = self.screens.top_mut() else {
+            return;
+        };
+        if calibration.stage() != CalibrationStage::Done {
+            return;
+        }
+        let Some(curve) = calibration.curve() else {
+            self.message = Some(self.language.text(Text::HardNotesNotLouder).to_string());
+            *calibration = Calibration::default();
+            return;
+        };
+        self.screens.close_top();
+        let Some(midi) = &self.midi else {
+            return;
+        };
+        let mut settings = midi.settings();
+        settings.velocity_curve = curve;
+        midi.set_settings(settings.clone());
+        let port = midi.port_name().to_string();
+
+        let saved = self.devices_path.as_ref().map(|path| {
+            let mut devices = DeviceProfiles::load_or_default(path)?;
+            devices.set(&port, settings);
+            devices.save(path)
+        });
+        self.message = Some(match saved {
+            Some(Err(e)) => self.tr_with(Text::CurveNotSaved, &[&e]),
+            _ => self.tr(Text::CurveCalibrated).to_string(),
+        });
+    }
+
+    /// Uses `controls` for the controller's buttons, saving changes to
+    /// them in `path`.
+    pub fn set_controls(&mut self, controls: ControlMap, path: PathBuf) {
+        self.controls = controls;
+        self.controls_path = Some(path);
+        self.sync_controls();
+    }
+
+    /// Tells the input which notes are controls and whether to learn one.
+    fn sync_controls(&self) {
+        if let Some(midi) = &self.midi {
+            midi.set_control_notes(self.controls.notes());
+            midi.set_learning(
+                self.screens
+                    .any(|s| matches!(s, Screen::ControlMapping(m) if m.learn == Learn::Control)),
+            );
+        }
+    }
+
+    pub fn toggle_control_mapping(&mut self) {
+        if self
+            .screens
+            .close(|s| matches!(s, Screen::ControlMapping(_)))
+        {
+            self.sync_controls();
+            return;
+        }
+        let profiles = if cfg!(feature = "scripting") {
+            paths::profiles()
+        } else {
+            Vec::new()
+        };
+        let mapping = Mapping::new(&self.controls, profiles);
+        self.screens.open(Screen::ControlMapping(mapping));
+    }
+
+    /// The controller mapping screen, if open.
+    pub fn control_mapping(&self) -> Option<&Mapping> {
+        self.screens.iter().find_map(|s| match s {
+            Screen::ControlMapping(mapping) => Some(mapping),
+            _ => None,
+        })
+    }
+
+    fn handle_control_mapping_key(&mut self, key: KeyCode) {
+        let Some(Screen::ControlMapping(mapping)) = self.screens.top_mut() else {
+            return;
+        };
+        match (mapping.learn, key) {
+            (Learn::Key, KeyCode::Esc) => mapping.learn = Learn::Off,
+            (Learn::Key, key) => {
+                if let Some(command) = self.keymap.press(key) {
+                    mapping.learn(ControlAction::Command(command));
+                }
+            }
+            (_, KeyCode::Up | KeyCode::Char('k')) => mapping.move_cursor(false),
+            (_, KeyCode::Down | KeyCode::Char('j')) => mapping.move_cursor(true),
+            (Learn::Off, KeyCode::Enter) => mapping.learn = Learn::Control,
+            (Learn::Control, KeyCode::Enter) => mapping.learn = Learn::Off,
+            (_, KeyCode::Char('l')) => mapping.learn = Learn::Key,
+            (_, KeyCode::Char('x') | KeyCode::Delete | KeyCode::Backspace) => {
+                let action = mapping.selected().clone();
+                mapping.learn = Learn::Off;
+                self.controls.unbind(&action);
+                let done = self.tr_with(Text::Unmapped, &[&action.name()]);
+                self.save_controls(done);
+            }
+            (Learn::Control, KeyCode::Esc | KeyCode::Char('q')) => mapping.learn = Learn::Off,
+            (Learn::Off, KeyCode::Esc | KeyCode::Char('q')) => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+        self.sync_controls();
+    }
+
+    /// Runs what each control pressed since the last tick is mapped to,
+    /// or maps the first to the action waiting for one.
+    fn tick_controls(&mut self) {
+        let Some(midi) = &self.midi else {
+            return;
+        };
+        for control in midi.take_controls() {
+            self.dirty = true;
+            let learning = self.screens.iter_mut().find_map(|s| match s {
+                Screen::ControlMapping(m) if m.learn == Learn::Control => Some(m),
+                _ => None,
+            });
+            if let Some(mapping) = learning {
+                mapping.learn = Learn::Off;
+                let action = mapping.selected().clone();
+                self.controls.bind(control, action.clone());
+                self.sync_controls();
+                let done = self.tr_with(Text::MappedTo, &[&control, &action.name()]);
+                self.save_controls(done);
+            } else if let Some(action) = self.controls.get(control).cloned() {
+                self.run_control_action(action);
+            } else if control == Control::Cc(SUSTAIN_PEDAL) {
+                self.pedal_tap(self.now());
+            }
+        }
+    }
+
+    fn run_control_action(&mut self, action: ControlAction) {
+        match action {
+            ControlAction::Command(command) => self.run(command),
+            ControlAction::Profile(profile) => self.load_profile(&profile),
+        }
+    }
+
+    #[cfg(feature = "scripting")]
+    fn load_profile(&mut self, profile: &str) {
+        match crate::theory::ScriptSuggester::find(profile) {
+            Ok(script) => self.set_suggester(Box::new(script)),
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    fn load_profile(&mut self, profile: &str) {
+        self.message = Some(self.tr_with(Text::NoScripting, &[&profile]));
+    }
+
+    fn save_controls(&mut self, done: String) {
+        let saved = self
+            .controls_path
+            .as_ref()
+            .map(|path| self.controls.save(path));
+        self.message = Some(match saved {
+            Some(Err(e)) => self.tr_with(Text::NotSaved, &[&done, &e]),
+            _ => done,
+        });
+    }
+
+    /// Tracks practice milestones in `path`, announcing each one in the
+    /// status line and through the milestone hooks.
+    pub fn enable_milestones(&mut self, path: PathBuf) {
+        self.milestones = Some((Milestones::load_or_default(&path), path));
+    }
+
+    pub fn milestones(&self) -> Option<&Milestones> {
+        self.milestones.as_ref().map(|(m, _)| m)
+    }
+
+    /// Keeps daily practice time and chords in `path`, for the goals screen.
+    pub fn enable_practice_log(&mut self, path: PathBuf) {
+        self.practice = Some((PracticeLog::load_or_default(&path), path));
+    }
+
+    pub fn practice_log(&self) -> Option<&PracticeLog> {
+        self.practice.as_ref().map(|(log, _)| log)
+    }
+
+    pub fn toggle_goals(&mut self) {
+        self.toggle_screen(Screen::Goals);
+    }
+
+    /// Records a chord in today's practice, returning the goals it met.
+    fn record_practice(&mut self, chord: &Chord, at: f64) -> Vec<Milestone> {
+        let Some((log, _)) = &mut self.practice else {
+            return Vec::new();
+        };
+        let today = milestones::today();
+        let before = log.day(today);
+        log.record(today, chord, at);
+        let after = log.day(today);
+        self.goals
+            .iter()
+            .filter(|goal| !goal.is_met(&before) && goal.is_met(&after))
+            .map(|goal| Milestone::Goal(goal.describe()))
+            .collect()
+    }
+
+    fn reach(&mut self, milestone: Milestone) {
+        self.hooks.milestone(&milestone);
+        // Practice feedback already showing comes first
+        if self.message.is_none() {
+            self.message = Some(format!("★ {}", self.milestone_message(&milestone)));
+        }
+    }
+
+    fn milestone_message(&self, milestone: &Milestone) -> String {
+        match milestone {
+            Milestone::NewQuality(quality) => self.tr_with(Text::FirstQuality, &[quality]),
+            Milestone::PatternComplete { name, misses: 0 } => {
+                self.tr_with(Text::CompleteClean, &[name])
+            }
+            Milestone::PatternComplete { name, misses } => {
+                self.tr_with(Text::CompleteWithMisses, &[name, misses])
+            }
+            Milestone::Streak(days) => self.tr_with(Text::Streak, &[days]),
+            Milestone::Goal(goal) => self.tr_with(Text::GoalMet, &[goal]),
+        }
+    }
+
+    pub fn toggle_dictation(&mut self) {
+        if self.dictation.is_some() {
+            self.message = Some(match self.save_dictation_stats() {
+                Ok(()) => self.tr(Text::DictationStopped).to_string(),
+                Err(e) => self.tr_with(Text::DictationStatsNotSaved, &[&e]),
+            });
+            self.dictation = None;
+            return;
+        }
+
+        let stats = self
+            .stats_path
+            .as_deref()
+            .map(DictationStats::load_or_default)
+            .unwrap_or_default();
+        self.dictation = Some(Dictation::new(stats));
+        self.next_dictation();
+    }
+
+    pub fn next_dictation(&mut self) {
+        let Some(dictation) = &mut self.dictation else {
+            return;
+        };
+
+        let chord = match dictation.current() {
+            Some(chord) if !dictation.is_answered() => chord.clone(),
+            _ => dictation.next_chord(),
+        };
+        self.audition(&[chord]);
+    }
+
+    fn check_dictation(&mut self, played: &Chord) {
+        let (naming, spelling, names) = (self.naming, self.spelling(), self.note_names);
+        let Some(dictation) = &mut self.dictation else {
+            return;
+        };
+        let Some(result) = dictation.answer(played) else {
+            return;
+        };
+
+        let target = dictation
+            .current()
+            .map(|c| c.name_in(naming, spelling, names))
+            .unwrap_or_default();
+        let text = match result {
+            DictationResult::Correct => Text::DictationRight,
+            DictationResult::Wrong => Text::DictationWrong,
+        };
+        self.message = Some(self.tr_with(text, &[&target]));
+    }
+
+    fn save_dictation_stats(&self) -> Result<()> {
+        match (&self.dictation, &self.stats_path) {
+            (Some(dictation), Some(path)) => dictation.stats().save(path),
+            _ => Ok(()),
+        }
+    }
+
+    fn handle_presets_key(&mut self, key: KeyCode) {
+        let count = Preset::all().len();
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.preset_cursor = (self.preset_cursor + count - 1) % count;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.preset_cursor = (self.preset_cursor + 1) % count;
+            }
+            KeyCode::Enter => {
+                self.load_preset(&Preset::all()[self.preset_cursor]);
+                self.screens.close_top();
+            }
+            KeyCode::Char('c') => {
+                self.compare_with_preset(&Preset::all()[self.preset_cursor]);
+            }
+            KeyCode::Esc | KeyCode::Char('b') | KeyCode::Char('q') => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+    }
+
+    /// Lines what has been played up against a preset, looped round as
+    /// often as it was played.
+    pub fn compare_with_preset(&mut self, preset: &Preset) {
+        let take = self.session.chords();
+        if take.is_empty() {
+            self.message = Some(self.tr(Text::PlayThenCompare).to_string());
+            return;
+        }
+        let key = self.key.or(self.session.key()).unwrap_or(Note::new(60));
+        let diff = TakeDiff::looped(preset.name, &preset.chords(key), "This take", &take);
+        self.screens.open(Screen::Compare(diff, 0));
+    }
+
+    fn handle_compare_key(&mut self, key: KeyCode) {
+        let Some(Screen::Compare(diff, scroll)) = self.screens.top_mut() else {
+            return;
+        };
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                *scroll = (*scroll + 1).min(diff.rows.len().saturating_sub(1))
+            }
+            _ => {
+                self.screens.close_top();
+            }
+        }
+    }
+
+    pub fn load_model(&mut self, path: PathBuf) -> Result<()> {
+        self.base_model = ProgressionModel::load(&path)?;
+        self.model_path = Some(path);
+        self.refresh_model();
+        Ok(())
+    }
+
+    pub fn enable_adaptive(&mut self, path: PathBuf) {
+        self.adaptive = AdaptiveModel::load_or_default(&path);
+        self.adaptive_path = Some(path);
+        self.adaptive_enabled = true;
+        self.refresh_model();
+    }
+
+    pub fn adaptive(&self) -> &AdaptiveModel {
+        &self.adaptive
+    }
+
+    /// Learning is only turned on with somewhere to keep what it learns:
+    /// the path it was enabled with, or else the user's own.
+    pub fn toggle_adaptive(&mut self) {
+        if !self.adaptive_enabled && self.adaptive_path.is_none() {
+            match &self.user {
+                Some(user) => {
+                    let path = user.adaptive_model_path();
+                    self.enable_adaptive(path);
+                }
+                None => {
+                    self.message = Some(self.tr(Text::NoUserToLearnFor).to_string());
+                }
+            }
+            return;
+        }
+        self.adaptive_enabled = !self.adaptive_enabled;
+        self.refresh_model();
+    }
+
+    pub fn set_blend(&mut self, blend: f32) {
+        self.blend = blend.clamp(0.0, 1.0);
+        self.refresh_model();
+    }
+
+    /// Resets on the second press in a row, so one stray key doesn't wipe
+    /// what's been learned.
+    fn confirm_reset_adaptive(&mut self, confirmed: bool) {
+        if confirmed {
+            self.reset_adaptive();
+        } else {
+            self.confirm_reset = true;
+            let keys = self
+                .keymap
+                .describe(Command::ResetAdaptive)
+                .unwrap_or_default();
+            self.message = Some(self.tr_with(Text::ConfirmReset, &[&keys]));
+        }
+    }
+
+    pub fn reset_adaptive(&mut self) {
+        self.adaptive.reset();
+        self.message = Some(match &self.adaptive_path {
+            Some(path) => match self.adaptive.save(path) {
+                Ok(()) => self.tr(Text::LearningReset).to_string(),
+                Err(e) => self.tr_with(Text::ResetFailed, &[&e]),
+            },
+            None => self.tr(Text::LearningReset).to_string(),
+        });
+        self.refresh_model();
+    }
+
+    fn refresh_model(&mut self) {
+        self.learned_at = None;
+        let model = if self.adaptive_enabled {
+            self.adaptive.blend(&self.base_model, self.blend)
+        } else {
+            self.base_model.clone()
+        };
+        self.tree.set_model(model);
+        self.precompute_suggestions();
+    }
+
+    pub fn reload_model(&mut self) {
+        let Some(path) = self.model_path.clone() else {
+            self.message = Some(self.tr(Text::NoModelFile).to_string());
+            return;
+        };
+
+        self.message = Some(match self.load_model(path) {
+            Ok(()) => self.tr_with(Text::ModelReloaded, &[&self.tree.model().name]),
+            Err(e) => self.tr_with(Text::ModelReloadFailed, &[&e]),
+        });
+    }
+
+    pub fn toggle_similar(&mut self) {
+        self.show_similar = !self.show_similar;
+    }
+
+    pub fn open_key_finder(&mut self) {
+        self.screens.open(Screen::KeyFinder(String::new()));
+    }
+
+    /// The chords the key finder weighs: those typed in, or else the
+    /// latest ones played.
+    fn key_finder_chords(&self) -> Vec<Chord> {
+        let typed: Vec<Chord> = self
+            .screens
+            .iter()
+            .filter_map(|s| match s {
+                Screen::KeyFinder(input) => Some(input),
+                _ => None,
+            })
+            .flat_map(|input| input.split([' ', ',']))
+            .filter_map(Chord::from_name)
+            .collect();
+        if !typed.is_empty() {
+            return typed;
+        }
+        let entries = self.history.entries();
+        entries[entries.len().saturating_sub(KEY_FINDER_CHORDS)..]
+            .iter()
+            .map(|e| e.chord.clone())
+            .collect()
+    }
+
+    fn handle_key_finder_key(&mut self, key: KeyCode) {
+        let Some(Screen::KeyFinder(input)) = self.screens.top_mut() else {
+            return;
+        };
+        match key {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Esc | KeyCode::Enter => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+    }
+
+    pub fn toggle_harmonizer(&mut self) {
+        self.harmonizer = match self.harmonizer {
+            Some(_) => None,
+            None => Some(MelodyHarmonizer::default()),
+        };
+        let text = if self.harmonizer.is_some() {
+            Text::HarmonizingOn
+        } else {
+            Text::HarmonizingOff
+        };
+        self.message = Some(self.tr(text).to_string());
+    }
+
+    pub fn toggle_spelling(&mut self) {
+        self.show_spelling = !self.show_spelling;
+    }
+
+    pub fn cycle_minor_tree(&mut self) {
+        self.minor_tree = self.minor_tree.cycle();
+        self.message = Some(self.tr_with(Text::MinorTree, &[&self.minor_tree.name()]));
+    }
+
+    pub fn cycle_capo(&mut self) {
+        self.capo = (self.capo + 1) % (MAX_CAPO + 1);
+        self.message = Some(match self.capo {
+            0 => self.tr(Text::CapoOff).to_string(),
+            fret => self.tr_with(Text::CapoOn, &[&fret]),
+        });
+    }
+
+    pub fn open_transposer(&mut self) {
+        let start = self.transposition.unwrap_or_else(|| {
+            let tonic = self.key().unwrap_or(Note::new(60));
+            Transposition::new(tonic, tonic)
+        });
+        self.screens.open(Screen::Transposer(start, false));
+    }
+
+    /// Moves the selected key a semitone with ←/→; Enter transposes,
+    /// Delete goes back to the keys as played.
+    fn handle_transposer_key(&mut self, key: KeyCode) {
+        let Some(Screen::Transposer(picked, sung)) = self.screens.top_mut() else {
+            return;
+        };
+        let note = if *sung {
+            &mut picked.to
+        } else {
+            &mut picked.from
+        };
+        match key {
+            KeyCode::Left | KeyCode::Char('h') => {
+                *note = Note::new(60 + (note.pitch_class() + 11) % 12)
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                *note = Note::new(60 + (note.pitch_class() + 1) % 12)
+            }
+            KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::Tab
+            | KeyCode::Char('k')
+            | KeyCode::Char('j') => *sung = !*sung,
+            KeyCode::Enter => {
+                let picked = *picked;
+                self.screens.close_top();
+                self.set_transposition(Some(picked).filter(|t| t.semitones() != 0));
+            }
+            KeyCode::Delete | KeyCode::Backspace => {
+                self.screens.close_top();
+                self.set_transposition(None);
+            }
+            KeyCode::Esc | KeyCode::Char('y') | KeyCode::Char('q') => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+    }
+
+    pub fn set_transposition(&mut self, transposition: Option<Transposition>) {
+        self.transposition = transposition;
+        self.history.set_transposition(transposition);
+        self.message = Some(match transposition {
+            Some(t) => self.tr_with(
+                Text::SingingIn,
+                &[&self.note_name(t.to), &self.note_name(t.from)],
+            ),
+            None => self.tr(Text::TranspositionOff).to_string(),
+        });
+    }
+
+    fn note_name(&self, note: Note) -> &'static str {
+        note.name_in(self.spelling(), self.note_names)
+    }
+
+    /// Marks the moment in the session log; a note can be typed for it
+    /// before Enter saves it.
+    pub fn bookmark(&mut self) {
+        self.screens
+            .open(Screen::Bookmark(self.log_time(), String::new()));
+        self.show_bookmark_note();
+    }
+
+    fn show_bookmark_note(&mut self) {
+        if let Some(Screen::Bookmark(t, note)) = self.screens.top() {
+            let at = format!("{}:{:02}", t / 60_000, t / 1000 % 60);
+            self.message = Some(self.tr_with(Text::BookmarkNote, &[&at, note]));
+        }
+    }
+
+    fn handle_bookmark_key(&mut self, key: KeyCode) {
+        let Some(Screen::Bookmark(t, note)) = self.screens.top_mut() else {
+            return;
+        };
+        match key {
+            KeyCode::Char(c) => note.push(c),
+            KeyCode::Backspace => {
+                note.pop();
+            }
+            KeyCode::Enter | KeyCode::Esc => {
+                let t = *t;
+                let note = std::mem::take(note);
+                let note = (key == KeyCode::Enter && !note.trim().is_empty())
+                    .then(|| note.trim().to_string());
+                self.screens.close_top();
+                self.log_at(t, Event::Bookmark { note });
+                let text = if self.event_log.is_some() {
+                    Text::Bookmarked
+                } else {
+                    Text::BookmarkedUnlogged
+                };
+                self.message = Some(self.tr(text).to_string());
+                return;
+            }
+            _ => {}
+        }
+        self.show_bookmark_note();
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.toggle_screen(Screen::Help);
+        self.help_page = 0;
+    }
+
+    /// Pages through the help; any other key closes it.
+    fn handle_help_key(&mut self, key: KeyCode) {
+        let last = self.help_pages.get().saturating_sub(1);
+        match key {
+            KeyCode::Right | KeyCode::PageDown | KeyCode::Char(' ') | KeyCode::Char('j') => {
+                self.help_page = (self.help_page + 1).min(last);
+            }
+            KeyCode::Left | KeyCode::PageUp | KeyCode::Char('k') => {
+                self.help_page = self.help_page.saturating_sub(1);
+            }
+            _ => {
+                self.screens.close_top();
+            }
+        }
+    }
+
+    /// Logs what was pressed and released and records the chord the new
+    /// set of notes makes.
+    fn notes_changed(&mut self, notes: HashSet<u8>) {
+        let mut pressed: Vec<u8> = notes.difference(&self.last_notes).copied().collect();
+        let mut released: Vec<u8> = self.last_notes.difference(&notes).copied().collect();
+        pressed.sort_unstable();
+        released.sort_unstable();
+        for note in released {
+            self.log(Event::NoteOff { note });
+        }
+        for &note in &pressed {
+            self.log(Event::NoteOn { note });
+        }
+
+        self.last_notes = notes.clone();
+
+        if let [a, b] = notes.iter().copied().collect::<Vec<u8>>()[..] {
+            self.answer_interval(None, Some((a, b)));
+        }
+
+        let now = self.now();
+        let bar = self.bar_length();
+        if let Some(harmonizer) = &mut self.harmonizer {
+            for note in pressed {
+                harmonizer.note(note, now, bar);
+            }
+        }
+        self.onsets.update(&notes, now);
+        self.read_harmony(now);
+    }
+
+    /// The held notes to read the chord from: without keys brushed by
+    /// accident, and without the melody when melody separation is on.
+    fn harmony_notes(&self, now: Instant) -> HashSet<u8> {
+        let velocities = self
+            .midi
+            .as_ref()
+            .map(|m| m.held_velocities())
+            .unwrap_or_default();
+        let notes = brush::without_brushes(&self.last_notes, &velocities, &self.onsets, now);
+        if self.detection.melody {
+            melody::harmony(&notes, &self.onsets, now)
+        } else {
+            notes
+        }
+    }
+
+    /// Reads the chord from the held notes, without the melody when melody
+    /// separation is on, and over the pedal when the bass is held under
+    /// changing chords.
+    fn read_harmony(&mut self, now: Instant) {
+        let notes = self.harmony_notes(now);
+        self.harmony = notes.clone();
+        self.pedal_point.update(&notes, now);
+        let detection = self.pedal_point.analyze(&notes, &self.detection);
+        let detection = if self.detection.smoothing {
+            self.smoother.push(&notes, detection, now);
+            self.smoother.current(now)
+        } else {
+            detection
+        };
+        if let Some(detection) = detection {
+            self.chord_detected(detection, &notes);
+        }
+    }
+
+    /// Re-reads the chord while the notes are unchanged: a held melody note
+    /// joins the harmony, and the smoothed chord shifts as the notes held
+    /// lend it more weight.
+    fn tick_detection(&mut self) {
+        let now = self.now();
+        if self.harmony_notes(now) != self.harmony {
+            self.read_harmony(now);
+            return;
+        }
+        if !self.detection.smoothing {
+            return;
+        }
+        let Some(detection) = self.smoother.current(now) else {
+            return;
+        };
+        if self.current_chord.as_ref().map(|c| c.name()) != Some(detection.chord.name()) {
+            let notes = self.harmony.clone();
+            self.chord_detected(detection, &notes);
+        }
+    }
+
+    fn chord_detected(&mut self, detection: Detection, notes: &HashSet<u8>) {
+        let Detection { chord, confidence } = detection;
+        let changed = self.current_chord.as_ref().map(|c| c.name()) != Some(chord.name());
+        if changed {
+            if !self.plan.is_empty() && !self.plan.advance(&chord) {
+                self.message = Some(self.tr(Text::OffThePlan).to_string());
+            }
+            let now = self.now();
+            // Chords changed by letting go of keys aren't timed
+            if let Some(at) = self
+                .midi
+                .as_ref()
+                .and_then(|m| m.last_note_on())
+                .filter(|&at| at >= self.chord_since)
+            {
+                self.latency = Some(now.saturating_duration_since(at));
+            }
+            self.chord_since = now;
+            self.previous_chord = self.current_chord.clone();
+            self.animations.chord_changed();
+        }
+        // Shaky readings are still shown, just not recorded
+        if changed && confidence.value() >= self.min_confidence {
+            self.check_followed(&chord);
+            self.count_blues_bar(&chord);
+            let mut voicing: Vec<u8> = notes.iter().copied().collect();
+            voicing.sort_unstable();
+
+            self.log(Event::Chord {
+                name: chord.name(),
+                notes: voicing.clone(),
+            });
+            self.history.push_voiced(chord.clone(), voicing.clone());
+            self.animations.chord_recorded();
+            let now = self.now();
+            let at = now.duration_since(self.session_started).as_secs_f64();
+            if let Some(looper) = &mut self.looper {
+                looper.capture(voicing.clone(), now);
+            }
+            self.session.push_timed(&chord, voicing, at);
+            self.refresh_analysis();
+            self.record_chord_change(now);
+
+            let completed = self.trainer.as_mut().and_then(|trainer| {
+                if trainer.is_complete() {
+                    return None;
+                }
+                (trainer.check(&chord) == TrainerResult::Complete).then(|| {
+                    Milestone::PatternComplete {
+                        name: trainer.name().to_string(),
+                        misses: trainer.misses(),
+                    }
+                })
+            });
+            self.check_dictation(&chord);
+            let mut reached = self
+                .milestones
+                .as_mut()
+                .map(|(m, _)| m.chord_played(&chord, milestones::today()))
+                .unwrap_or_default();
+            reached.extend(self.record_practice(&chord, at));
+            for milestone in reached.into_iter().chain(completed) {
+                self.reach(milestone);
+            }
+
+            if let (true, Some(previous), Some(key)) =
+                (self.adaptive_enabled, &self.current_chord, self.key)
+            {
+                self.adaptive.observe(previous, &chord, key);
+                // Nothing learned shows with the blend at zero
+                if self.blend > 0.0 {
+                    self.learned_at = Some(self.now());
+                }
+            }
+
+            if self.key.is_none() {
+                self.set_key(Some(chord.root));
+            }
+        }
+        self.current_chord = Some(chord);
+        self.confidence = confidence;
+    }
+
+    pub fn tick(&mut self) {
+        self.tick_source();
+        let notes = self
+            .midi
+            .as_ref()
+            .map(|m| m.held_notes())
+            .unwrap_or_default();
+
+        if notes != self.last_notes {
+            self.notes_changed(notes);
+            self.dirty = true;
+        } else {
+            self.tick_detection();
+        }
+
+        let calibration = self.screens.iter_mut().find_map(|s| match s {
+            Screen::Calibration(calibration) => Some(calibration),
+            _ => None,
+        });
+        if let (Some(calibration), Some(midi)) = (calibration, &self.midi) {
+            for velocity in midi.take_velocities() {
+                calibration.record(velocity);
+                self.dirty = true;
+            }
+        }
+        self.tick_controls();
+
+        let partner_notes = self
+            .partner
+            .as_ref()
+            .map(|m| m.held_notes())
+            .unwrap_or_default();
+        if partner_notes != self.partner_notes {
+            if let Some(chord) = Chord::detect_with(&partner_notes, &self.detection) {
+                self.partner_chord = Some(chord);
+            }
+            self.partner_notes = partner_notes;
+            self.dirty = true;
+        }
+
+        if self.history.tick() > 0 {
+            self.refresh_analysis();
+        }
+        let now = self.now();
+        if now.duration_since(self.animation_frame) >= animation::FRAME {
+            self.animation_frame = now;
+            self.animations.tick();
+        }
+        self.history
+            .set_slide(self.animations.slide_offset(SLIDE_DISTANCE));
+        self.tick_looper(self.now());
+        self.tick_along();
+        if self
+            .learned_at
+            .is_some_and(|at| now.duration_since(at) >= LEARNING_SETTLES)
+        {
+            self.refresh_model();
+        }
+
+        if let Some(output) = &mut self.output {
+            self.playback.tick(self.clock.now(), output);
+        }
+
+        if !self.screens.any(|s| matches!(s, Screen::Recovery(_))) {
+            if let Some(autosave) = &mut self.autosave {
+                let _ = autosave.save_if_due(&self.session, self.clock.now());
+            }
+        }
+        self.dirty |= self.is_live();
+    }
+
+    /// Whether what's shown moves on its own: effects, a loop or
+    /// play-along running, or notes held while confidence in their chord
+    /// builds.
+    fn is_live(&self) -> bool {
+        self.animations.is_running()
+            || self.looper.is_some()
+            || self.along.is_some()
+            || self.playback.is_playing()
+            || !self.last_notes.is_empty()
+            || !self.partner_notes.is_empty()
+            || self.is_targeting()
+            || self
+                .midi
+                .as_ref()
+                .is_some_and(|m| !m.expression().is_resting())
+    }
+
+    /// Whether anything shown may have changed since this was last asked,
+    /// for frontends that only draw when it has.
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// The screen on top, taking the keys.
+    pub fn screen(&self) -> Option<&Screen> {
+        self.screens.top()
+    }
+
+    pub fn open_screen(&mut self, screen: Screen) {
+        self.dirty = true;
+        self.screens.open(screen);
+    }
+
+    /// Opens `screen`, or closes it if one like it is open.
+    fn toggle_screen(&mut self, screen: Screen) {
+        let kind = std::mem::discriminant(&screen);
+        if !self.screens.close(|s| std::mem::discriminant(s) == kind) {
+            self.screens.open(screen);
+        }
+    }
+
+    /// Whether something is open over the main screen.
+    fn has_overlay(&self) -> bool {
+        self.screens.any(Screen::is_overlay)
+    }
+
+    /// A left click on a cell of the screen. On the function ribbon, it
+    /// moves the review cursor to that chord.
+    pub fn handle_click(&mut self, column: u16, row: u16) {
+        let area = self.ribbon_area.get();
+        if self.has_overlay() || !area.contains(Position::new(column, row)) {
+            return;
+        }
+        let functions = self.functions();
+        let ribbon = FunctionRibbon::new(&functions).selected(self.history.selected_index());
+        if let Some(index) = ribbon.index_at(area, column) {
+            self.history.select(index);
+            self.dirty = true;
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) {
+        self.dirty = true;
+        if !self.screens.is_empty() {
+            self.handle_screen_key(key);
+            return;
+        }
+
+        if let KeyCode::Char(c @ '1'..='8') = key {
+            if self.pads && !self.keymap.is_pending() {
+                self.trigger_pad(c as u8 - b'1');
+                return;
+            }
+        }
+
+        if let Some(command) = self.keymap.press(key) {
+            self.run(command);
+        }
+    }
+
+    /// Hands a key to the screen on top.
+    fn handle_screen_key(&mut self, key: KeyCode) {
+        let Some(screen) = self.screens.top() else {
+            return;
+        };
+        match screen {
+            Screen::Recovery(_) => self.handle_recovery_key(key),
+            Screen::Help => self.handle_help_key(key),
+            Screen::Sessions(_) => self.handle_browser_key(key),
+            Screen::Compare(..) => self.handle_compare_key(key),
+            Screen::Presets => self.handle_presets_key(key),
+            Screen::Detail | Screen::Goals | Screen::Heatmap => {
+                self.screens.close_top();
+            }
+            Screen::KeyFinder(_) => self.handle_key_finder_key(key),
+            Screen::Reharm(..) => self.handle_reharm_key(key),
+            Screen::Transposer(..) => self.handle_transposer_key(key),
+            Screen::Bookmark(..) => self.handle_bookmark_key(key),
+            Screen::Calibration(_) => self.handle_calibration_key(key),
+            Screen::ControlMapping(_) => self.handle_control_mapping_key(key),
+            Screen::UserPicker(..) => self.handle_user_picker_key(key),
+            Screen::Plan(_) => self.handle_plan_key(key),
+            Screen::Sketch(_) => self.handle_sketch_key(key),
+            Screen::Intervals(_) => self.handle_intervals_key(key),
+            Screen::Explorer(..) => self.handle_explorer_key(key),
+            Screen::Fingering(..) => self.handle_fingering_key(key),
+        }
+    }
+
+    fn handle_recovery_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                if let Some(Screen::Recovery(session)) = self.screens.close_top() {
+                    self.restore(session);
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Esc => {
+                self.screens.close_top();
+            }
+            _ => {}
+        }
+    }
+
+    pub fn run(&mut self, command: Command) {
+        let confirm_reset = std::mem::take(&mut self.confirm_reset);
+        match command {
+            Command::Quit => self.should_quit = true,
+            Command::ToggleMode => self.toggle_mode(),
+            Command::ToggleExtended => self.toggle_extended(),
+            Command::CycleMinorTree => self.cycle_minor_tree(),
+            Command::CycleCapo => self.cycle_capo(),
+            Command::ToggleFingering => self.toggle_fingering(),
+            Command::TogglePads => self.toggle_pads(),
+            Command::ToggleLatch => self.toggle_latch(),
+            Command::ToggleSmoothing => self.toggle_smoothing(),
+            Command::ToggleMelody => self.toggle_melody(),
+            Command::EditPlan => self.toggle_plan_editing(),
+            Command::Sketch => self.toggle_sketch(),
+            Command::Reharmonize => self.open_reharm(),
+            Command::Goals => self.toggle_goals(),
+            Command::Heatmap => self.open_screen(Screen::Heatmap),
+            Command::CalibrateVelocity => self.toggle_calibration(),
+            Command::MapControls => self.toggle_control_mapping(),
+            Command::SwitchUser => self.toggle_user_picker(),
+            Command::CycleArpPattern => self.cycle_arp_pattern(),
+            Command::CycleArpRate => self.cycle_arp_rate(),
+            Command::CycleNaming => self.cycle_naming(),
+            Command::Help => self.toggle_help(),
+            Command::ClearHistory => self.clear_history(),
+            Command::ExportLilypond => self.export(ExportFormat::Lilypond),
+            Command::ExportMidi => self.export(ExportFormat::Midi),
+            Command::CycleBass => self.bass_style = self.bass_style.next(),
+            Command::TapTempo => self.tap(self.now()),
+            Command::AutoTempo => self.toggle_auto_tempo(),
+            Command::ToggleFeel => self.toggle_feel(),
+            Command::ToggleBlues => self.toggle_blues(),
+            Command::CycleTimeSignature => self.cycle_time_signature(),
+            Command::CycleGroove => self.cycle_groove(),
+            Command::Looper => self.toggle_looper(),
+            Command::CycleHumanize => self.cycle_humanize(),
+            Command::StopLooper => self.stop_looper(),
+            Command::ReloadModel => self.reload_model(),
+            Command::ToggleAdaptive => self.toggle_adaptive(),
+            Command::ResetAdaptive => self.confirm_reset_adaptive(confirm_reset),
+            Command::BlendDown => self.set_blend(self.blend - 0.1),
+            Command::BlendUp => self.set_blend(self.blend + 0.1),
+            Command::Presets => self.toggle_presets(),
+            Command::Sessions => self.toggle_browser(),
+            Command::Similar => self.toggle_similar(),
+            Command::SpellOut => self.toggle_spelling(),
+            Command::Bookmark => self.bookmark(),
+            Command::AuditionExpected => self.audition_branch(false),
+            Command::AuditionSurprise => self.audition_branch(true),
+            Command::Transpose => self.open_transposer(),
+            Command::HarmonizeMelody => self.toggle_harmonizer(),
+            Command::KeyFinder => self.open_key_finder(),
+            Command::Duet => self.toggle_duet(),
+            Command::Dictation => self.toggle_dictation(),
+            Command::Intervals => self.toggle_intervals(),
+            Command::Explorer => self.toggle_explorer(),
+            Command::NextDictation => self.next_dictation(),
+            Command::PageTurn => self.page_turn(),
+            Command::CreativityDown => self.set_creativity(self.creativity() - 0.1),
+            Command::CreativityUp => self.set_creativity(self.creativity() + 0.1),
+            Command::ReinterpretKey => self.reinterpret_key(),
+            Command::HistoryPrev => self.history.select_prev(),
+            Command::HistoryNext => self.history.select_next(),
+            Command::ChordDetail => {
+                if self.history.selected().is_some() {
+                    self.open_screen(Screen::Detail);
+                }
+            }
+            Command::Playback => self.toggle_playback(),
+            Command::RestartTrainer => {
+                if let Some(trainer) = &mut self.trainer {
+                    trainer.restart();
+                }
+            }
+        }
+    }
+
+    /// The suggestions as shown. While the chord changes, the old chord's
+    /// dim out and then the new one's dim in; the plan belongs to the
+    /// chord being played, so it's left off the one fading out.
+    pub fn shown_tree(&self) -> Option<ProgressionNode> {
+        match self.animations.fade_stage() {
+            FadeStage::Out => self.previous_chord.as_ref().map(|c| self.suggest(c)),
+            FadeStage::In | FadeStage::Done => {
+                self.current_chord.as_ref().map(|c| self.planned_tree(c))
+            }
+        }
+    }
+
+    /// The notes lit on the piano.
+    pub fn pressed_notes(&self) -> &HashSet<u8> {
+        &self.last_notes
+    }
+
+    /// The notes shown faintly on the piano: the comping voicing and the
+    /// guide tones being aimed for.
+    pub fn ghost_notes(&self) -> HashSet<u8> {
+        &self.comping_ghost() | &self.guide_tone_ghost()
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        self.ribbon_area.set(Rect::default());
+
+        let looper_height = if self.looper.is_some() { 3 } else { 0 };
+        let main_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(10),
+                Constraint::Length(looper_height),
+                Constraint::Length(8),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(area);
+
+        self.render_title(frame, main_layout[0]);
+
+        let constraints = match (self.show_similar, self.show_spelling) {
+            (true, true) => vec![
+                Constraint::Percentage(35),
+                Constraint::Percentage(20),
+                Constraint::Percentage(20),
+                Constraint::Percentage(25),
+            ],
+            (true, false) | (false, true) => vec![
+                Constraint::Percentage(45),
+                Constraint::Percentage(25),
+                Constraint::Percentage(30),
+            ],
+            (false, false) => vec![Constraint::Percentage(60), Constraint::Percentage(40)],
+        };
+
+        let content_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(constraints)
+            .split(main_layout[1]);
+
+        self.render_tree(frame, content_layout[0]);
+        if self.show_similar {
+            self.render_similar(frame, content_layout[1]);
+        }
+        if self.show_spelling {
+            self.render_spelling(frame, content_layout[content_layout.len() - 2]);
+        }
+        self.render_history(frame, content_layout[content_layout.len() - 1]);
+
+        if let Some(looper) = &self.looper {
+            self.render_looper(frame, main_layout[2], looper);
+        }
+        self.render_piano(frame, main_layout[3]);
+        self.render_tension(frame, main_layout[4]);
+        self.render_status(frame, main_layout[5]);
+
+        for screen in self.screens.iter() {
+            self.render_screen(frame, area, screen);
+        }
+    }
+
+    /// Draws an open screen over what's under it.
+    fn render_screen(&self, frame: &mut Frame, area: Rect, screen: &Screen) {
+        match screen {
+            Screen::Recovery(session) => self.render_recovery_overlay(frame, area, session),
+            Screen::Help => self.render_help_overlay(frame, area),
+            Screen::Sessions(browser) => self.render_browser_overlay(frame, area, browser),
+            Screen::Compare(diff, scroll) => {
+                self.render_compare_overlay(frame, area, diff, *scroll)
+            }
+            Screen::Presets => self.render_presets_overlay(frame, area),
+            Screen::Detail => self.render_detail_overlay(frame, area),
+            Screen::Goals => self.render_goals_overlay(frame, area),
+            Screen::Heatmap => self.render_heatmap_overlay(frame, area),
+            Screen::KeyFinder(input) => self.render_key_finder_overlay(frame, area, input),
+            Screen::Reharm(chords, cursor) => {
+                self.render_reharm_overlay(frame, area, chords, *cursor)
+            }
+            Screen::Transposer(picked, sung) => {
+                self.render_transposer_overlay(frame, area, *picked, *sung)
+            }
+            Screen::Calibration(calibration) => {
+                self.render_calibration_overlay(frame, area, calibration)
+            }
+            Screen::ControlMapping(mapping) => self.render_control_mapping(frame, area, mapping),
+            Screen::UserPicker(users, cursor) => {
+                self.render_user_picker(frame, area, users, *cursor)
+            }
+            Screen::Sketch(cursor) => self.render_sketch_overlay(frame, area, *cursor),
+            Screen::Intervals(drill) => self.render_intervals_overlay(frame, area, drill),
+            Screen::Explorer(chord, index) => {
+                self.render_explorer_overlay(frame, area, chord, *index)
+            }
+            Screen::Fingering(chord, index) => {
+                self.render_fingering_overlay(frame, area, chord, *index)
+            }
+            // Drawn in the main view
+            Screen::Plan(_) | Screen::Bookmark(..) => {}
+        }
+    }
+
+    /// Chords proposed under each phrase of the melody, newest phrase last.
+    fn render_harmonizer(&self, frame: &mut Frame, area: Rect, harmonizer: &MelodyHarmonizer) {
+        let key = self.key.map(Scale::major).or_else(|| harmonizer.key());
+        let title = match key {
+            Some(key) => format!(" Harmonize melody · key {} ", key.name_in(self.note_names)),
+            None => " Harmonize melody ".to_string(),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Theme::border());
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let mut lines: Vec<Line> = Vec::new();
+        for phrase in harmonizer.phrases() {
+            let notes: Vec<&str> = phrase
+                .iter()
+                .map(|&n| Note::new(n).name_in(self.spelling(), self.note_names))
+                .collect();
+            lines.push(Line::from(Span::styled(
+                format!(" {}", notes.join(" ")),
+                Theme::text(),
+            )));
+            let mut options = vec![Span::styled("   → ", Theme::text_dim())];
+            for (i, (chord, coverage)) in harmonize::harmonize(phrase, key).iter().enumerate() {
+                let style = if i == 0 {
+                    Theme::tree_expected()
+                } else {
+                    Theme::chord_name()
+                };
+                options.push(Span::styled(self.chord_name(chord), style));
+                options.push(Span::styled(
+                    format!(" {:.0}%  ", coverage * 100.0),
+                    Theme::text_dim(),
+                ));
+            }
+            lines.push(Line::from(options));
+        }
+        if lines.is_empty() {
+            lines.push(Line::from(Span::styled(
+                " Play a melody, one note at a time",
+                Theme::text_dim(),
+            )));
+        }
+        let skip = lines.len().saturating_sub(inner.height as usize);
+        frame.render_widget(Paragraph::new(lines.split_off(skip)), inner);
+    }
+
+    fn render_title(&self, frame: &mut Frame, area: Rect) {
+        let title = Paragraph::new(Line::from(vec![
+            Span::styled(" Chordvery ", Theme::title()),
+            Span::styled(self.tr(Text::Subtitle), Theme::text_dim()),
+        ]));
+        frame.render_widget(title, area);
+    }
+
+    fn render_tree(&self, frame: &mut Frame, area: Rect) {
+        if let Some(harmonizer) = &self.harmonizer {
+            self.render_harmonizer(frame, area, harmonizer);
+            return;
+        }
+        let title = match self.follow.total() {
+            0 => format!(" {} ", self.tr(Text::Suggestions)),
+            _ => format!(
+                " {} · followed {:.0}% (expected {:.0}%, surprise {:.0}%) ",
+                self.tr(Text::Suggestions),
+                self.follow.follow_rate() * 100.0,
+                self.follow.rate(Followed::Expected) * 100.0,
+                self.follow.rate(Followed::Surprise) * 100.0,
+            ),
+        };
+        let title = match (&self.plan_input, self.is_editing_plan()) {
+            (Some(input), _) => format!("{}· chord: {}_ ", title, input),
+            (None, true) => format!(
+                "{}· plan: arrows move, Space pins, Enter types a chord, x removes ",
+                title
+            ),
+            (None, false) => title,
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Theme::border());
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let stage = self.animations.fade_stage();
+        if let Some(node) = self.shown_tree() {
+            let planned = stage != FadeStage::Out;
+            let chord = &node.chord.clone();
+            let suggested: Vec<Chord> = [&node.left, &node.right]
+                .into_iter()
+                .flatten()
+                .map(|n| n.chord.clone())
+                .collect();
+
+            let tree_widget = ChordTree::new()
+                .root(node)
+                .naming(self.naming)
+                .spelling(self.spelling())
+                .note_names(self.note_names)
+                .capo(self.capo)
+                .quality_colors(self.quality_colors)
+                .planned(if planned {
+                    self.plan.paths().cloned().collect()
+                } else {
+                    Vec::new()
+                })
+                .cursor(self.plan_cursor().cloned().filter(|_| planned));
+
+            let bass = if inner.height >= 7 { 1 } else { 0 };
+            let comping = if self.extended_chords && inner.height >= 9 {
+                2
+            } else {
+                0
+            };
+
+            let layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(4),
+                    Constraint::Length(comping),
+                    Constraint::Length(bass),
+                ])
+                .split(inner);
+            let major = Scale::major(self.key.unwrap_or(chord.root));
+            match self.minor_tree.scale(major.tonic) {
+                Some(minor) => {
+                    let halves = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                        .split(layout[0]);
+                    let minor_widget = ChordTree::new()
+                        .root(self.tree.suggest_in(chord, minor))
+                        .naming(self.naming)
+                        .spelling(self.spelling())
+                        .note_names(self.note_names)
+                        .capo(self.capo)
+                        .quality_colors(self.quality_colors);
+                    self.render_keyed_tree(frame, halves[0], major, tree_widget);
+                    self.render_keyed_tree(frame, halves[1], minor, minor_widget);
+                }
+                None => frame.render_widget(tree_widget, layout[0]),
+            }
+            if comping > 0 {
+                self.render_comping(frame, layout[1], &suggested);
+            }
+            if let (true, Some(next)) = (bass > 0, suggested.first()) {
+                self.render_bass(frame, layout[2], chord, next);
+            }
+        } else {
+            let tree_widget = ChordTree::new().placeholder(self.tr(Text::PlayAChord));
+            frame.render_widget(tree_widget, inner);
+        }
+
+        if stage != FadeStage::Done {
+            frame
+                .buffer_mut()
+                .set_style(inner, Style::default().add_modifier(Modifier::DIM));
+        }
+    }
+
+    fn render_keyed_tree(&self, frame: &mut Frame, area: Rect, scale: Scale, tree: ChordTree) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(3)])
+            .split(area);
+        let label = Line::from(vec![
+            Span::styled(" in ", Theme::text_dim()),
+            Span::styled(scale.name_in(self.note_names), Theme::text()),
+        ]);
+        frame.render_widget(Paragraph::new(label), layout[0]);
+        frame.render_widget(tree, layout[1]);
+    }
+
+    fn render_bass(&self, frame: &mut Frame, area: Rect, current: &Chord, next: &Chord) {
+        let spelling = self.spelling();
+        let notes: Vec<String> = bass::bass_line(current, next, self.bass_style)
+            .iter()
+            .map(|&n| Note::new(n).display_in(spelling, self.note_names))
+            .collect();
+
+        let line = Line::from(vec![
+            Span::styled(
+                format!(" {} bass: ", self.bass_style.name()),
+                Theme::text_dim(),
+            ),
+            Span::styled(notes.join(" "), Theme::text()),
+            Span::styled(" → ", Theme::text_dim()),
+            Span::styled(
+                next.name_in(self.naming, spelling, self.note_names),
+                Theme::chord_name(),
+            ),
+        ]);
+        frame.render_widget(Paragraph::new(line), area);
+    }
+
+    fn render_comping(&self, frame: &mut Frame, area: Rect, chords: &[Chord]) {
+        let spelling = self.spelling();
+        let lines: Vec<Line> = chords
+            .iter()
+            .map(|chord| {
+                let mut spans = vec![Span::styled(
+                    format!(
+                        " {:<7}",
+                        chord.name_in(self.naming, spelling, self.note_names)
+                    ),
+                    Theme::chord_name(),
+                )];
+                for voicing in Voicing::all() {
+                    let Some(notes) = voicing.voice(chord) else {
+                        continue;
+                    };
+                    let names: Vec<String> = notes
+                        .iter()
+                        .map(|&n| Note::new(n).name_in(spelling, self.note_names).to_string())
+                        .collect();
+                    spans.push(Span::styled(
+                        format!(" {}: ", voicing.name()),
+                        Theme::text_dim(),
+                    ));
+                    spans.push(Span::styled(names.join(" "), Theme::text()));
+                }
+                Line::from(spans)
+            })
+            .collect();
+
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+
+    /// The chord coming next: the sketch's while stepping through it,
+    /// otherwise the expected suggestion.
+    fn upcoming_chord(&self) -> Option<Chord> {
+        let stepped = self
+            .sketch_position
+            .and_then(|at| self.sketch.next_position(Some(at)))
+            .and_then(|at| self.sketch.chord_at(at));
+        if let Some(entry) = stepped {
+            return Chord::from_name(&entry.chord);
+        }
+        let chord = self.current_chord.as_ref()?;
+        self.planned_tree(chord).left.map(|node| node.chord)
+    }
+
+    /// Whether guide tones may come up: jamming to a tempo over a chord.
+    fn is_targeting(&self) -> bool {
+        self.mode == Mode::Jam && self.bpm.is_some() && self.current_chord.is_some()
+    }
+
+    /// The next chord's guide tones, to aim a solo at, through the last
+    /// beat of every bar the current chord has been held. Only in Jam mode
+    /// with a tempo set; the chord is taken to change on the bar.
+    pub fn guide_tone_ghost(&self) -> HashSet<u8> {
+        let Some(bpm) = self.bpm.filter(|_| self.is_targeting()) else {
+            return HashSet::new();
+        };
+        let beat = 60.0 / bpm * self.time_signature.pulse();
+        let bar = 60.0 / bpm * self.time_signature.quarters();
+        let held = self
+            .now()
+            .saturating_duration_since(self.chord_since)
+            .as_secs_f32();
+        if held % bar < bar - beat {
+            return HashSet::new();
+        }
+        self.upcoming_chord()
+            .map(|chord| chord.guide_tones().into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn comping_ghost(&self) -> HashSet<u8> {
+        if !self.extended_chords {
+            return HashSet::new();
+        }
+
+        self.current_chord
+            .as_ref()
+            .and_then(|chord| self.planned_tree(chord).left)
+            .and_then(|expected| Voicing::RootlessA.voice(&expected.chord))
+            .map(|notes| notes.into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    fn render_similar(&self, frame: &mut Frame, area: Rect) {
+        let dyad = self.held_dyad();
+        let block = Block::default()
+            .title(format!(
+                " {} ",
+                self.tr(if dyad.is_some() {
+                    Text::CouldBecome
+                } else {
+                    Text::SoundsLike
+                })
+            ))
+            .borders(Borders::ALL)
+            .border_style(Theme::border());
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        if let Some(dyad) = dyad {
+            self.render_completions(frame, inner, &dyad);
+            return;
+        }
+
+        let Some(chord) = &self.current_chord else {
+            let hint = Paragraph::new(Span::styled(" Play a chord", Theme::text_dim()));
+            frame.render_widget(hint, inner);
+            return;
+        };
+
+        let lines: Vec<Line> = similar::similar(chord, self.key)
+            .iter()
+            .take(inner.height as usize)
+            .map(|s| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {:<8}", self.chord_name(&s.chord)),
+                        Theme::chord_name(),
+                    ),
+                    Span::styled(format!("{} ", s.shared), Theme::text()),
+                    Span::styled(s.relation.name(), Theme::text_dim()),
+                ])
+            })
+            .collect();
+
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    /// Each held note, lowest first, with its role in the current chord;
+    /// the chord's own tones while nothing is held.
+    fn render_spelling(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(format!(" {} ", self.tr(Text::SpelledOut)))
+            .borders(Borders::ALL)
+            .border_style(Theme::border());
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let Some(chord) = &self.current_chord else {
+            let hint = Paragraph::new(Span::styled(" Play a chord", Theme::text_dim()));
+            frame.render_widget(hint, inner);
+            return;
+        };
+
+        let mut notes: Vec<u8> = if self.last_notes.is_empty() {
+            chord.notes()
+        } else {
+            self.last_notes.iter().copied().collect()
+        };
+        notes.sort_unstable();
+
+        let mut lines = vec![Line::from(Span::styled(
+            format!(" {}", self.chord_name(chord)),
+            Theme::chord_name(),
+        ))];
+        lines.extend(notes.iter().take(inner.height as usize).map(|&note| {
+            let name = Note::new(note).name_in(self.spelling(), self.note_names);
+            let role = chord.tone_role(note);
+            if role.chord_tone {
+                Line::from(vec![
+                    Span::styled(format!(" {:<3}", name), Theme::text()),
+                    Span::styled(role.degree, Theme::text_dim()),
+                ])
+            } else {
+                Line::from(vec![
+                    Span::styled(format!(" {:<3}", name), Theme::out_of_key()),
+                    Span::styled(
+                        format!("{} / non-chord tone", role.degree),
+                        Theme::out_of_key(),
+                    ),
+                ])
+            }
+        }));
+
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    /// The two pitch classes held, when exactly two are.
+    fn held_dyad(&self) -> Option<Vec<u8>> {
+        let mut pitch_classes: Vec<u8> = self.last_notes.iter().map(|n| n % 12).collect();
+        pitch_classes.sort_unstable();
+        pitch_classes.dedup();
+        (pitch_classes.len() == 2).then_some(pitch_classes)
+    }
+
+    /// Chords the held dyad could grow into, those in the key first.
+    pub fn completions(&self, dyad: &[u8]) -> Vec<Chord> {
+        let mut chords = Chord::containing(dyad);
+        if let Some(key) = self.key {
+            let scale = Scale::major(key);
+            chords.sort_by_key(|c| !scale.contains_chord(c));
+        }
+        chords
+    }
+
+    fn render_completions(&self, frame: &mut Frame, area: Rect, dyad: &[u8]) {
+        let spelling = self.spelling();
+        let lines: Vec<Line> = self
+            .completions(dyad)
+            .iter()
+            .take(area.height as usize)
+            .map(|chord| {
+                let mut added: Vec<u8> = chord
+                    .pitch_classes()
+                    .into_iter()
+                    .filter(|pc| !dyad.contains(pc))
+                    .collect();
+                added.sort_unstable_by_key(|pc| (pc + 12 - chord.root.pitch_class()) % 12);
+                let added: Vec<&str> = added
+                    .iter()
+                    .map(|&pc| Note::new(60 + pc).name_in(spelling, self.note_names))
+                    .collect();
+                Line::from(vec![
+                    Span::styled(
+                        format!(
+                            " {:<8}",
+                            chord.name_in(self.naming, spelling, self.note_names)
+                        ),
+                        Theme::chord_name(),
+                    ),
+                    Span::styled(format!("+{}", added.join(" ")), Theme::text_dim()),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+
+    fn render_looper(&self, frame: &mut Frame, area: Rect, looper: &Looper) {
+        let meter = looper.meter();
+        let title = match meter {
+            TimeSignature::FourFour => format!(" Looper: {} bars ", looper.bars()),
+            _ => format!(" Looper: {} bars of {} ", looper.bars(), meter.name()),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Theme::border());
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let now = self.now();
+        let state_style = match looper.state() {
+            Transport::Recording | Transport::Overdubbing => Theme::out_of_key(),
+            Transport::Playing => Theme::mode_jam(),
+        };
+        let (bar, beat) = looper.bar_beat(now);
+        let position = (looper.position(now) / meter.pulse()).floor() as usize;
+        let pulses = meter.pulses();
+        let grid: String = (0..looper.bars() as usize * pulses.len())
+            .map(|p| match (p == position, pulses[p % pulses.len()]) {
+                (true, _) => '●',
+                (false, Accent::Downbeat) => '┃',
+                (false, Accent::Secondary) => '╎',
+                (false, Accent::Weak) => '·',
+            })
+            .collect();
+
+        let line = Line::from(vec![
+            Span::styled(format!(" {} ", looper.state().name()), state_style),
+            Span::styled(format!(" {}.{} ", bar, beat), Theme::text()),
+            Span::styled(grid, Theme::text_dim()),
+            Span::styled(
+                format!("  {} layer(s)  ", looper.layers().len()),
+                Theme::text_dim(),
+            ),
+            Span::styled("[L] ", Theme::help_key()),
+            Span::styled("overdub ", Theme::status_bar()),
+            Span::styled("[O] ", Theme::help_key()),
+            Span::styled("stop", Theme::status_bar()),
+        ]);
+        frame.render_widget(Paragraph::new(line), inner);
+    }
+
+    fn render_history(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(format!(" {} ", self.tr(Text::History)))
+            .borders(Borders::ALL)
+            .border_style(Theme::border());
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let onsets: Vec<f64> = self.session.entries.iter().filter_map(|e| e.at).collect();
+        let now = self
+            .now()
+            .saturating_duration_since(self.session_started)
+            .as_secs_f64();
+        let rate = harmonic::changes_per_minute(&onsets, now);
+        let functions = self.functions();
+        let ribbon = !functions.is_empty() && inner.height >= 3;
+        let stats = rate.is_some() && inner.height >= if ribbon { 4 } else { 3 };
+        let mut constraints = vec![Constraint::Min(1)];
+        constraints.extend(ribbon.then_some(Constraint::Length(1)));
+        constraints.extend(stats.then_some(Constraint::Length(1)));
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(inner);
+        frame.render_widget(&self.history, layout[0]);
+
+        if ribbon {
+            // Lined up under the history, which starts a column in
+            let area = Rect {
+                x: layout[1].x + 1,
+                width: layout[1].width.saturating_sub(2),
+                ..layout[1]
+            };
+            frame.render_widget(
+                FunctionRibbon::new(&functions).selected(self.history.selected_index()),
+                area,
+            );
+            self.ribbon_area.set(area);
+        }
+
+        if let (Some(per_minute), true) = (rate, stats) {
+            let area = layout[layout.len() - 1];
+            let mut spans = vec![
+                Span::styled(" Changes ", Theme::text_dim()),
+                Span::styled(format!("{:.0}/min", per_minute), Theme::text()),
+            ];
+            if let Some(bpm) = self.bpm {
+                spans.push(Span::styled(
+                    format!(
+                        "  {:.1}/bar",
+                        harmonic::per_bar(per_minute, bpm, self.time_signature)
+                    ),
+                    Theme::text(),
+                ));
+            }
+            spans.push(Span::styled(
+                format!(
+                    "  {}",
+                    sparkline(&harmonic::recent(&onsets, now, SPARK_BUCKETS))
+                ),
+                Theme::sparkline(),
+            ));
+            frame.render_widget(Paragraph::new(Line::from(spans)), area);
+        }
+    }
+
+    fn render_piano(&self, frame: &mut Frame, area: Rect) {
+        if let Some(along) = &self.along {
+            self.render_along(frame, area, along);
+            return;
+        }
+
+        if !self.duet {
+            self.render_player_piano(
+                frame,
+                area,
+                " Piano ",
+                self.player_piano(
+                    self.pressed_notes(),
+                    &self.current_chord,
+                    &self.ghost_notes(),
+                ),
+                self.midi.as_ref(),
+            );
+            return;
+        }
+
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+
+        let title = |player: u8, chord: &Option<Chord>| {
+            let name = chord
+                .as_ref()
+                .map(|c| self.chord_name(c))
+                .unwrap_or_else(|| "—".to_string());
+            format!(" Player {}: {} ", player, name)
+        };
+
+        self.render_player_piano(
+            frame,
+            halves[0],
+            &title(1, &self.current_chord),
+            self.player_piano(&self.last_notes, &self.current_chord, &HashSet::new()),
+            self.midi.as_ref(),
+        );
+        self.render_player_piano(
+            frame,
+            halves[1],
+            &title(2, &self.partner_chord),
+            self.player_piano(&self.partner_notes, &self.partner_chord, &HashSet::new()),
+            self.partner.as_ref(),
+        );
+    }
+
+    fn render_along(&self, frame: &mut Frame, area: Rect, along: &PlayAlong) {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+
+        let time = self.along_time();
+        let name = |chord: Option<&Chord>| {
+            chord
+                .map(|c| self.chord_name(c))
+                .unwrap_or_else(|| "—".to_string())
+        };
+
+        let file_chord = along.segment_at(time).map(|s| s.chord.clone());
+        let hit = along.segment_index(time).is_some_and(|i| along.is_hit(i));
+
+        self.render_player_piano(
+            frame,
+            halves[0],
+            &format!(" {}: {} ", along.name(), name(file_chord.as_ref())),
+            self.player_piano(
+                &self.along_file.sounding_at(time),
+                &file_chord,
+                &HashSet::new(),
+            ),
+            None,
+        );
+        self.render_player_piano(
+            frame,
+            halves[1],
+            &format!(
+                " You: {}{} ",
+                name(self.current_chord.as_ref()),
+                if hit { " ✓" } else { "" }
+            ),
+            self.player_piano(&self.last_notes, &self.current_chord, &HashSet::new()),
+            self.midi.as_ref(),
+        );
+    }
+
+    /// A piano with `notes` lit, `ghost` shown faintly and the chord's
+    /// root marked.
+    pub fn player_piano<'a>(
+        &self,
+        notes: &'a HashSet<u8>,
+        chord: &Option<Chord>,
+        ghost: &'a HashSet<u8>,
+    ) -> Piano<'a> {
+        let root = chord.as_ref().map(|c| c.root.midi);
+        Piano::spanning(notes.iter().chain(ghost).copied())
+            .pressed(notes)
+            .ghost(ghost)
+            .root(root)
+            .hires(self.piano_hires)
+    }
+
+    /// A player's piano, with their bend and pressure along the bottom
+    /// while they use them.
+    fn render_player_piano(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        mut piano: Piano<'_>,
+        input: Option<&MidiInput>,
+    ) {
+        let mut block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Theme::border());
+
+        if let Some(input) = input {
+            let expression = input.expression();
+            if !expression.is_resting() {
+                // Bends read over the usual whole tone unless the device
+                // says otherwise
+                let range = input.settings().bend_range.unwrap_or(2);
+                let semitones = expression.semitones(range);
+                piano = piano.bend(semitones);
+                block = block.title_bottom(Line::from(vec![
+                    Span::styled(" bend ", Theme::text_dim()),
+                    Span::styled(centred_pips(expression.bend, 8), Theme::help_key()),
+                    Span::styled(format!(" {:+.1} ", semitones), Theme::text()),
+                    Span::styled(" pressure ", Theme::text_dim()),
+                    Span::styled(pips(expression.pressure, 5), Theme::help_key()),
+                    Span::raw(" "),
+                ]));
+            }
+        }
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        frame.render_widget(piano, inner);
+    }
+
+    pub fn tension(&self) -> Tension {
+        if self.duet {
+            Tension::measure(&self.combined_notes(), self.key)
+        } else {
+            Tension::measure(&self.last_notes, self.key)
+        }
+    }
+
+    fn render_tension(&self, frame: &mut Frame, area: Rect) {
+        let value = self.tension().total();
+        let label = match (self.duet, self.combined_chord()) {
+            (true, Some(chord)) => format!(" Together: {}", self.chord_name(&chord)),
+            (true, None) => " Together".to_string(),
+            (false, _) => " Tension".to_string(),
+        };
+        let meter = Meter::new(&label).value(value).style(Theme::tension(value));
+        frame.render_widget(meter, area);
+    }
+
+    fn render_status(&self, frame: &mut Frame, area: Rect) {
+        let mut spans = vec![Span::raw(" ")];
+        for &segment in &self.status {
+            let segment = self.status_segment(segment);
+            if segment.is_empty() {
+                continue;
+            }
+            if spans.len() > 1 {
+                spans.push(Span::styled(" │ ", Theme::status_bar()));
+            }
+            spans.extend(segment);
+        }
+
+        let paragraph = Paragraph::new(Line::from(spans));
+        frame.render_widget(paragraph, area);
+    }
+
+    /// One part of the status bar, empty while there's nothing to show.
+    fn status_segment(&self, segment: StatusSegment) -> Vec<Span<'_>> {
+        match segment {
+            StatusSegment::Mode => {
+                let mode_style = match self.mode {
+                    Mode::Discovery => Theme::mode_discovery(),
+                    Mode::Jam => Theme::mode_jam(),
+                };
+                vec![
+                    Span::styled("[Tab] ", Theme::help_key()),
+                    Span::styled(format!("{}: ", self.tr(Text::Mode)), Theme::status_bar()),
+                    Span::styled(self.mode.name(), mode_style),
+                ]
+            }
+            StatusSegment::Chord => {
+                let mut chord_text = self
+                    .current_chord
+                    .as_ref()
+                    .map(|c| self.chord_name(c))
+                    .unwrap_or_else(|| "—".to_string());
+                let confidence = self.confidence();
+                let chord_style = if self.is_out_of_key() {
+                    chord_text.push('*');
+                    Theme::out_of_key()
+                } else {
+                    let base = if confidence.is_some_and(|c| c < LOW_CONFIDENCE) {
+                        Theme::chord_name_uncertain()
+                    } else {
+                        Theme::chord_name()
+                    };
+                    match &self.current_chord {
+                        Some(chord) => self.chord_style(chord, base),
+                        None => base,
+                    }
+                };
+                let chord_style = if self.animations.is_flashing() {
+                    chord_style.patch(Theme::chord_flash())
+                } else {
+                    chord_style
+                };
+                vec![
+                    Span::styled(format!("{}: ", self.tr(Text::Playing)), Theme::status_bar()),
+                    Span::styled(chord_text, chord_style),
+                    Span::styled(
+                        confidence
+                            .map(|c| format!(" {}", pips(c, 4)))
+                            .unwrap_or_default(),
+                        Theme::text_dim(),
+                    ),
+                ]
+            }
+            StatusSegment::Extended => {
+                let extended_text = if self.extended_chords { "ON" } else { "OFF" };
+                vec![
+                    Span::styled("[e] ", Theme::help_key()),
+                    Span::styled(
+                        format!("{}: ", self.tr(Text::Extended)),
+                        Theme::status_bar(),
+                    ),
+                    Span::styled(extended_text, Theme::text()),
+                ]
+            }
+            StatusSegment::Creativity => vec![
+                Span::styled(
+                    format!("{}: ", self.tr(Text::Creativity)),
+                    Theme::status_bar(),
+                ),
+                Span::styled(format!("{:.0}%", self.creativity() * 100.0), Theme::text()),
+            ],
+            StatusSegment::Tempo => {
+                let Some(bpm) = self.bpm else {
+                    return Vec::new();
+                };
+                let mut spans = vec![
+                    Span::styled("[t] ", Theme::help_key()),
+                    Span::styled(format!("♩={:.0}", bpm), Theme::text()),
+                ];
+                if self.time_signature != TimeSignature::FourFour {
+                    spans.push(Span::styled(
+                        format!(" {}", self.time_signature.name()),
+                        Theme::text(),
+                    ));
+                }
+                if self.auto_tempo {
+                    spans.push(Span::styled(" auto", Theme::status_bar()));
+                }
+                if self.feel == Feel::Swing {
+                    spans.push(Span::styled(" swing", Theme::status_bar()));
+                }
+                spans
+            }
+            StatusSegment::User => match self.user.as_ref().and_then(|u| u.name.as_deref()) {
+                Some(name) => vec![
+                    Span::styled("[W] ", Theme::help_key()),
+                    Span::styled(name, Theme::status_bar()),
+                ],
+                None => Vec::new(),
+            },
+            StatusSegment::Latch if self.is_latched() => vec![
+                Span::styled("[z] ", Theme::help_key()),
+                Span::styled("Latch", Theme::status_bar()),
+            ],
+            StatusSegment::Melody if self.detection.melody => {
+                let mut spans = vec![
+                    Span::styled("[V] ", Theme::help_key()),
+                    Span::styled("Melody", Theme::status_bar()),
+                ];
+                if let Some(note) = self.melody_note() {
+                    spans.push(Span::styled(
+                        format!(
+                            " {}",
+                            Note::new(note).display_in(self.spelling(), self.note_names)
+                        ),
+                        Theme::text(),
+                    ));
+                }
+                spans
+            }
+            StatusSegment::Pedal => self
+                .pedal_note()
+                .map(|note| {
+                    vec![
+                        Span::styled("Pedal ", Theme::status_bar()),
+                        Span::styled(
+                            Note::new(note)
+                                .name_in(self.spelling(), self.note_names)
+                                .to_string(),
+                            Theme::text(),
+                        ),
+                    ]
+                })
+                .unwrap_or_default(),
+            StatusSegment::Smoothing if self.detection.smoothing => vec![
+                Span::styled("[o] ", Theme::help_key()),
+                Span::styled("Smooth", Theme::status_bar()),
+            ],
+            StatusSegment::Pads if self.pads => {
+                let mut spans = vec![
+                    Span::styled("[P] ", Theme::help_key()),
+                    Span::styled("Pads", Theme::status_bar()),
+                ];
+                for chord in (0..PADS - 1).filter_map(|pad| self.pad_chord(pad)) {
+                    spans.push(Span::styled(
+                        format!(" {}", self.chord_name(&chord)),
+                        self.chord_style(&chord, Theme::text()),
+                    ));
+                }
+                spans
+            }
+            StatusSegment::Arp if self.arp.is_on() => vec![
+                Span::styled("[u] ", Theme::help_key()),
+                Span::styled("Arp ", Theme::status_bar()),
+                Span::styled(
+                    format!("{} {}", self.arp.pattern.name(), self.arp.rate.name()),
+                    Theme::text(),
+                ),
+            ],
+            StatusSegment::Capo if self.capo > 0 => {
+                let mut spans = vec![
+                    Span::styled("[g] ", Theme::help_key()),
+                    Span::styled(format!("Capo {}", self.capo), Theme::status_bar()),
+                ];
+                if let Some(chord) = &self.current_chord {
+                    let shape = chord.capo_shape(self.capo);
+                    spans.push(Span::styled(
+                        format!(": {} shape", self.chord_name(&shape)),
+                        Theme::text(),
+                    ));
+                }
+                spans
+            }
+            StatusSegment::Transpose => match self.transposition {
+                Some(t) => {
+                    let mut spans = vec![
+                        Span::styled("[y] ", Theme::help_key()),
+                        Span::styled(
+                            format!("Sing {}→{}", self.note_name(t.from), self.note_name(t.to)),
+                            Theme::status_bar(),
+                        ),
+                    ];
+                    if let Some(chord) = &self.current_chord {
+                        spans.push(Span::styled(
+                            format!(": {}", self.chord_name(&t.chord(chord))),
+                            Theme::transposed(),
+                        ));
+                    }
+                    spans
+                }
+                None => Vec::new(),
+            },
+            StatusSegment::Key => match self.key_hint() {
+                Some(hint) => vec![
+                    Span::styled("[K] ", Theme::help_key()),
+                    Span::styled(format!("{} ", self.tr(Text::KeyOf)), Theme::status_bar()),
+                    Span::styled(format!("{}?", hint.name_in(self.note_names)), Theme::text()),
+                ],
+                None => Vec::new(),
+            },
+            StatusSegment::Blues if self.blues.is_some() => {
+                let mut spans = vec![
+                    Span::styled("[J] ", Theme::help_key()),
+                    Span::styled("Blues", Theme::status_bar()),
+                ];
+                if let (Some(bar), Some(key)) = (self.blues_bar(), self.key) {
+                    let chord = blues::TWELVE_BAR[bar].chord(key);
+                    spans.push(Span::styled(
+                        format!(" {}/{} {}", bar + 1, blues::BARS, self.chord_name(&chord)),
+                        Theme::text(),
+                    ));
+                }
+                spans
+            }
+            StatusSegment::Along => match &self.along {
+                Some(along) => {
+                    let score = along.score(self.along_time());
+                    vec![
+                        Span::styled("Along: ", Theme::status_bar()),
+                        Span::styled(
+                            format!("{}/{}", score.correct, score.attempts),
+                            Theme::tree_expected(),
+                        ),
+                    ]
+                }
+                None => Vec::new(),
+            },
+            StatusSegment::Humanize if !self.humanize.is_off() => vec![
+                Span::styled("Human: ", Theme::status_bar()),
+                Span::styled(
+                    format!("{:.0}%", self.humanize.amount * 100.0),
+                    Theme::text(),
+                ),
+            ],
+            StatusSegment::Learn if self.adaptive_enabled => vec![
+                Span::styled("Learn: ", Theme::status_bar()),
+                Span::styled(format!("{:.0}%", self.blend * 100.0), Theme::text()),
+            ],
+            StatusSegment::Dictation => match &self.dictation {
+                Some(dictation) => {
+                    let target = match dictation.current() {
+                        Some(chord) if dictation.is_answered() => self.chord_name(chord),
+                        _ => "?".to_string(),
+                    };
+                    let session = dictation.session();
+                    vec![
+                        Span::styled("Dictation: ", Theme::status_bar()),
+                        Span::styled(target, Theme::tree_expected()),
+                        Span::styled(
+                            format!(" ({}/{})", session.correct, session.attempts),
+                            Theme::status_bar(),
+                        ),
+                    ]
+                }
+                None => Vec::new(),
+            },
+            StatusSegment::Trainer => match &self.trainer {
+                Some(trainer) => {
+                    let target = trainer
+                        .expected()
+                        .map(|c| self.chord_name(c))
+                        .unwrap_or_else(|| "done".to_string());
+                    vec![
+                        Span::styled("Target: ", Theme::status_bar()),
+                        Span::styled(target, Theme::tree_expected()),
+                        Span::styled(
+                            format!(" ({}/{})", trainer.position(), trainer.target().len()),
+                            Theme::status_bar(),
+                        ),
+                    ]
+                }
+                None => Vec::new(),
+            },
+            StatusSegment::Port => match &self.midi {
+                Some(midi) if !midi.port_name().is_empty() => vec![
+                    Span::styled("MIDI: ", Theme::status_bar()),
+                    Span::styled(midi.port_name(), Theme::text()),
+                ],
+                _ => Vec::new(),
+            },
+            StatusSegment::Latency => vec![
+                Span::styled("Latency: ", Theme::status_bar()),
+                Span::styled(
+                    self.latency
+                        .map(|l| format!("{}ms", l.as_millis()))
+                        .unwrap_or_else(|| "—".to_string()),
+                    Theme::text(),
+                ),
+            ],
+            StatusSegment::Clock => vec![
+                Span::styled("Clock: ", Theme::status_bar()),
+                Span::styled(self.clock_source().name(), Theme::text()),
+            ],
+            StatusSegment::Keys if self.keymap.is_pending() => {
+                let keys: Vec<String> = self
+                    .keymap
+                    .pending()
+                    .iter()
+                    .map(|&k| keymap::key_name(k))
+                    .collect();
+                vec![Span::styled(
+                    format!("{} …", keys.join(" ")),
+                    Theme::help_key(),
+                )]
+            }
+            StatusSegment::Help => vec![
+                Span::styled("[?] ", Theme::help_key()),
+                Span::styled("Help", Theme::status_bar()),
+            ],
+            StatusSegment::Message => match &self.message {
+                Some(message) => vec![Span::styled(message, Theme::text())],
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// The help, a page per `rows` lines: each category's commands with
+    /// the keys they're bound to now. Unbound commands are left out, and a
+    /// category starts on a new page rather than at the bottom of one.
+    fn help_pages(&self, rows: usize) -> Vec<Vec<Line<'static>>> {
+        let rows = rows.max(2);
+        let mut pages: Vec<Vec<Line>> = vec![Vec::new()];
+        for category in keymap::Category::all() {
+            let entries: Vec<Line> = Command::all()
+                .filter(|c| c.category() == *category)
+                .filter_map(|c| {
+                    let keys = self.keymap.describe(c)?;
+                    Some(Line::from(vec![
+                        Span::styled(format!("  {:<7} ", keys), Theme::help_key()),
+                        Span::styled(self.tr(Text::Command(c)), Theme::help_text()),
+                    ]))
+                })
+                .collect();
+            if entries.is_empty() {
+                continue;
+            }
+
+            let page = pages.last_mut().unwrap();
+            if !page.is_empty() && page.len() + 3 > rows {
+                pages.push(Vec::new());
+            } else if !page.is_empty() {
+                page.push(Line::from(""));
+            }
+            pages.last_mut().unwrap().push(Line::from(Span::styled(
+                self.tr(Text::Category(*category)),
+                Theme::help_heading(),
+            )));
+            for entry in entries {
+                if pages.last().unwrap().len() >= rows {
+                    pages.push(Vec::new());
+                }
+                pages.last_mut().unwrap().push(entry);
+            }
+        }
+        pages
+    }
+
+    fn render_help_overlay(&self, frame: &mut Frame, area: Rect) {
+        let help_width = 46.min(area.width);
+        let all = self.help_pages(usize::MAX);
+        // Blank line and footer below the commands
+        let help_height = (all[0].len() as u16 + 4).min(area.height);
+        let x = (area.width.saturating_sub(help_width)) / 2;
+        let y = (area.height.saturating_sub(help_height)) / 2;
+        let help_area = Rect::new(x, y, help_width, help_height);
+
+        let mut pages = self.help_pages(help_height.saturating_sub(4) as usize);
+        self.help_pages.set(pages.len());
+        let page = self.help_page.min(pages.len() - 1);
+        let mut help_text = std::mem::take(&mut pages[page]);
+        help_text.push(Line::from(""));
+        let footer = if pages.len() > 1 {
+            format!(
+                "  Page {}/{}  ←/→ · any other key closes",
+                page + 1,
+                pages.len()
+            )
+        } else {
+            "  Press any key to close".to_string()
+        };
+        help_text.push(Line::from(Span::styled(footer, Theme::text_dim())));
+
+        let block = Block::default()
+            .title(format!(" {} ", self.tr(Text::Help)))
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        frame.render_widget(Clear, help_area);
+        let help = Paragraph::new(help_text).block(block);
+        frame.render_widget(help, help_area);
+    }
+
+    fn render_user_picker(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        users: &[Option<String>],
+        cursor: usize,
+    ) {
+        let width = 36.min(area.width);
+        let height = (users.len() as u16 + 2).min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let current = self.user.as_ref().and_then(|u| u.name.clone());
+        let lines: Vec<Line> = users
+            .iter()
+            .enumerate()
+            .map(|(i, user)| {
+                let name = user.as_deref().unwrap_or("default");
+                let marker = if *user == current { "●" } else { " " };
+                let style = if i == cursor {
+                    Theme::text().patch(Theme::selected())
+                } else {
+                    Theme::text()
+                };
+                Line::from(vec![
+                    Span::styled(format!(" {} ", marker), Theme::help_key()),
+                    Span::styled(name.to_string(), style),
+                ])
+            })
+            .collect();
+
+        let block = Block::default()
+            .title(" User [↑↓ Enter Esc] ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
+    }
+
+    fn render_sketch_overlay(&self, frame: &mut Frame, area: Rect, cursor: usize) {
+        let width = 60.min(area.width);
+        let height = (self.sketch.sections.len() as u16 + 4).min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let mut lines: Vec<Line> = self
+            .sketch
+            .sections
+            .iter()
+            .enumerate()
+            .map(|(i, section)| {
+                let style = if i == cursor {
+                    Theme::text().patch(Theme::selected())
+                } else {
+                    Theme::text()
+                };
+                let at = self.sketch_position.filter(|at| at.section == i);
+                let repeats = match at {
+                    Some(at) => format!("{}/{}", at.pass + 1, section.repeats),
+                    None => format!("×{}", section.repeats),
+                };
+                let mut spans = vec![
+                    Span::styled(
+                        format!(" {:<11}", section.name_in(self.language.section_names())),
+                        style,
+                    ),
+                    Span::styled(format!("{:<4}", repeats), Theme::help_key()),
+                ];
+                for (j, entry) in section.chords.iter().enumerate() {
+                    let name = Chord::from_name(&entry.chord)
+                        .map_or(entry.chord.clone(), |c| self.chord_name(&c));
+                    let style = if at.is_some_and(|at| at.chord == j) {
+                        Theme::text().patch(Theme::selected())
+                    } else {
+                        Theme::text_dim()
+                    };
+                    spans.push(Span::styled(name, style));
+                    spans.push(Span::raw(" "));
+                }
+                Line::from(spans)
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push(Line::from(Span::styled(
+                " Play a progression, then [a] to capture it",
+                Theme::text_dim(),
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            " a capture  n name  +/- repeat  K/J move  d dup  x del",
+            Theme::help_text(),
+        )));
+
+        let block = Block::default()
+            .title(" Song sketch [Space step  m MIDI  t text  Esc] ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
+    }
+
+    fn render_reharm_overlay(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        chords: &[Chord],
+        cursor: usize,
+    ) {
+        let options = reharm::reharmonize(chords);
+
+        let width = 76.min(area.width);
+        let height = (options.len() as u16 + 5).min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let row = |label: Span<'static>, chords: &[Chord], changed: &[bool]| {
+            let mut spans = vec![label];
+            for (i, chord) in chords.iter().enumerate() {
+                let style = if changed.get(i).copied().unwrap_or(false) {
+                    Theme::chord_name()
+                } else {
+                    Theme::text_dim()
+                };
+                spans.push(Span::styled(self.chord_name(chord), style));
+                spans.push(Span::raw(" "));
+            }
+            Line::from(spans)
+        };
+
+        let mut lines = vec![row(
+            Span::styled(format!(" {:<14}", "played"), Theme::text()),
+            chords,
+            &[],
+        )];
+        for (i, option) in options.iter().enumerate() {
+            let style = if i == cursor {
+                Theme::text().patch(Theme::selected())
+            } else {
+                Theme::text()
+            };
+            lines.push(row(
+                Span::styled(format!(" {:<14}", option.technique.name()), style),
+                &option.chords,
+                &option.changed,
+            ));
+        }
+        if options.is_empty() {
+            lines.push(Line::from(Span::styled(
+                " Nothing to reharmonize in these chords",
+                Theme::text_dim(),
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            " ↑/↓ choose  Enter replaces the sketch  Esc closes",
+            Theme::help_text(),
+        )));
+
+        let block = Block::default()
+            .title(" Reharmonize ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
+    }
+
+    fn render_goals_overlay(&self, frame: &mut Frame, area: Rect) {
+        let today = milestones::today();
+        let log = self.practice_log().cloned().unwrap_or_default();
+        let day = log.day(today);
+
+        let width = 56.min(area.width);
+        let height = (self.goals.len() as u16 + 8).min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let streak = match log.streak(today) {
+            1 => "1 day".to_string(),
+            days => format!("{} days", days),
+        };
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(" Today ", Theme::text_dim()),
+                Span::styled(format!("{} min", day.minutes()), Theme::text()),
+                Span::styled("  ", Theme::text_dim()),
+                Span::styled(format!("{} chords", day.unique_chords()), Theme::text()),
+                Span::styled("  Streak ", Theme::text_dim()),
+                Span::styled(streak, Theme::chord_name()),
+            ]),
+            Line::from(""),
+        ];
+
+        for goal in &self.goals {
+            let (done, target) = goal.progress(&day);
+            let (mark, style) = if goal.is_met(&day) {
+                ("✓", Theme::tree_expected())
+            } else {
+                ("·", Theme::text_dim())
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {} ", mark), style),
+                Span::styled(format!("{:<28}", goal.describe()), Theme::text()),
+                Span::styled(
+                    pips(done as f32 / target.max(1) as f32, 10),
+                    Theme::sparkline(),
+                ),
+                Span::styled(format!(" {}/{}", done, target), Theme::text_dim()),
+            ]));
+        }
+
+        let mastered = day.mastered();
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(" Mastered today ", Theme::text_dim()),
+            Span::styled(
+                if mastered.is_empty() {
+                    "—".to_string()
+                } else {
+                    mastered.join(" ")
+                },
+                Theme::text(),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled(" Last 14 days   ", Theme::text_dim()),
+            Span::styled(
+                sparkline(&log.recent_minutes(today, 14)),
+                Theme::sparkline(),
+            ),
+        ]));
+
+        let block = Block::default()
+            .title(format!(" {} ", self.tr(Text::PracticeGoals)))
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
+    }
+
+    fn render_heatmap_overlay(&self, frame: &mut Frame, area: Rect) {
+        let counts = self
+            .practice_log()
+            .map(|log| log.chord_counts())
+            .unwrap_or_default();
+
+        let width = 48.min(area.width);
+        let height = (counts.len() as u16 + 6).min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let block = Block::default()
+            .title(" Chords played [circle of fifths] ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+        let inner = block.inner(overlay_area);
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(block, overlay_area);
+
+        if counts.is_empty() {
+            let text = if self.practice_log().is_some() {
+                " Nothing played yet"
+            } else {
+                " No practice log to count from"
+            };
+            frame.render_widget(Paragraph::new(Span::styled(text, Theme::text_dim())), inner);
+            return;
+        }
+
+        let unplayed: Vec<&str> = heatmap::unplayed_roots(&counts)
+            .into_iter()
+            .map(|pc| Note::new(pc).name_in(self.spelling(), self.note_names))
+            .collect();
+        let grid = Rect {
+            height: inner.height.saturating_sub(2),
+            ..inner
+        };
+        frame.render_widget(ChordHeatmap::new(&counts).note_names(self.note_names), grid);
+        let footer = Line::from(vec![
+            Span::styled(" Never played in ", Theme::text_dim()),
+            Span::styled(
+                if unplayed.is_empty() {
+                    "—".to_string()
+                } else {
+                    unplayed.join(" ")
+                },
+                Theme::out_of_key(),
+            ),
+        ]);
+        let footer_area = Rect {
+            y: inner.bottom().saturating_sub(1),
+            height: 1,
+            ..inner
+        };
+        frame.render_widget(Paragraph::new(footer), footer_area);
+    }
+
+    fn render_transposer_overlay(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        picked: Transposition,
+        sung: bool,
+    ) {
+        let width = 60.min(area.width);
+        let height = 8.min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let row = |label: &str, note: Note, selected: bool| {
+            let style = if selected {
+                Theme::chord_name().patch(Theme::selected())
+            } else {
+                Theme::chord_name()
+            };
+            Line::from(vec![
+                Span::styled(format!(" {:<14}", label), Theme::text()),
+                Span::styled(format!("◂ {:<2} ▸", self.note_name(note)), style),
+            ])
+        };
+        let semitones = picked.semitones();
+        let mut shift = match semitones {
+            0 => " As played".to_string(),
+            s if s > 0 => format!(" Up {} semitones", s),
+            s => format!(" Down {} semitones", -s),
+        };
+        if let Some(chord) = &self.current_chord {
+            shift.push_str(&format!(
+                " · {} → {}",
+                self.chord_name(chord),
+                self.chord_name(&picked.chord(chord))
+            ));
+        }
+        let lines = vec![
+            row("Original key", picked.from, !sung),
+            row("Sung in", picked.to, sung),
+            Line::from(""),
+            Line::from(Span::styled(shift, Theme::transposed())),
+            Line::from(""),
+            Line::from(Span::styled(
+                " ←/→ key  ↑/↓ switch  Enter sets  Del turns off  Esc",
+                Theme::help_text(),
+            )),
+        ];
+
+        let block = Block::default()
+            .title(" Transpose for a singer ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+        let inner = block.inner(overlay_area);
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(block, overlay_area);
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    fn render_key_finder_overlay(&self, frame: &mut Frame, area: Rect, input: &str) {
+        let chords = self.key_finder_chords();
+        let ranked = keys::rank_keys(&chords);
+
+        let width = 64.min(area.width);
+        let height = (KEY_FINDER_KEYS as u16 + 7).min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let block = Block::default()
+            .title(" Which key? ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+        let inner = block.inner(overlay_area);
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(block, overlay_area);
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(" Chords ", Theme::text_dim()),
+                Span::styled(format!("{}▏", input), Theme::text()),
+            ]),
+            Line::from(""),
+        ];
+        if ranked.is_empty() {
+            lines.push(Line::from(Span::styled(
+                " Play or type some chords",
+                Theme::text_dim(),
+            )));
+        }
+        for candidate in ranked.iter().take(KEY_FINDER_KEYS) {
+            let mut spans = vec![
+                Span::styled(
+                    format!(" {:<4}", candidate.scale.name_in(self.note_names)),
+                    Theme::chord_name(),
+                ),
+                Span::styled(format!("{:>3}% ", candidate.percent()), Theme::text()),
+                Span::styled(format!("{} ", pips(candidate.share, 5)), Theme::help_key()),
+            ];
+            for (chord, &diatonic) in chords.iter().zip(&candidate.diatonic) {
+                let style = if diatonic {
+                    Theme::text()
+                } else {
+                    Theme::out_of_key()
+                };
+                spans.push(Span::styled(format!(" {}", self.chord_name(chord)), style));
+            }
+            lines.push(Line::from(spans));
+        }
+        frame.render_widget(Paragraph::new(lines), inner);
+
+        let footer = Line::from(Span::styled(
+            " Type chords to weigh them instead · Esc closes",
+            Theme::text_dim(),
+        ));
+        let footer_area = Rect {
+            y: inner.bottom().saturating_sub(1),
+            height: 1,
+            ..inner
+        };
+        frame.render_widget(Paragraph::new(footer), footer_area);
+    }
+
+    fn render_calibration_overlay(&self, frame: &mut Frame, area: Rect, calibration: &Calibration) {
+        let width = 52.min(area.width);
+        let height = 8.min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let progress = |count: usize| {
+            Span::styled(
+                format!(
+                    " {}",
+                    pips(count as f32 / CALIBRATION_NOTES as f32, CALIBRATION_NOTES)
+                ),
+                Theme::help_key(),
+            )
+        };
+        let measured = |name: &str, velocity: Option<u8>| {
+            Line::from(vec![
+                Span::styled(format!(" {:<6}", name), Theme::text_dim()),
+                Span::styled(
+                    velocity.map_or("—".to_string(), |v| v.to_string()),
+                    Theme::text(),
+                ),
+            ])
+        };
+        let mut lines = vec![Line::from("")];
+        match calibration.stage() {
+            CalibrationStage::Soft => {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        " Play {} notes as softly as you ever would",
+                        CALIBRATION_NOTES
+                    ),
+                    Theme::text(),
+                )));
+                lines.push(Line::from(progress(calibration.count())));
+            }
+            CalibrationStage::Hard => {
+                lines.push(Line::from(Span::styled(
+                    format!(" Now {} notes as hard as you ever would", CALIBRATION_NOTES),
+                    Theme::text(),
+                )));
+                lines.push(Line::from(progress(calibration.count())));
+            }
+            CalibrationStage::Done => {
+                lines.push(Line::from(Span::styled(
+                    " Enter uses the curve for this keyboard",
+                    Theme::text(),
+                )));
+                lines.push(Line::from(""));
+            }
+        }
+        lines.push(Line::from(""));
+        lines.push(measured("Soft", calibration.soft()));
+        lines.push(measured("Hard", calibration.hard()));
+        if let Some(midi) = &self.midi {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    " Now using the {} curve",
+                    midi.settings().velocity_curve.name()
+                ),
+                Theme::text_dim(),
+            )));
+        }
+
+        let block = Block::default()
+            .title(" Velocity calibration [Enter save  r redo  Esc] ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
+    }
+
+    fn render_control_mapping(&self, frame: &mut Frame, area: Rect, mapping: &Mapping) {
+        let width = 64.min(area.width);
+        let height = (mapping.actions.len() as u16 + 4).min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let mut lines: Vec<Line> = mapping
+            .actions
+            .iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let selected = i == mapping.cursor;
+                let control = match self.controls.control_for(action) {
+                    _ if selected && mapping.learn == Learn::Control => {
+                        "press a button, pad or key…".to_string()
+                    }
+                    Some(control) => control.to_string(),
+                    None => "—".to_string(),
+                };
+                let style = if selected {
+                    Theme::text().patch(Theme::selected())
+                } else {
+                    Theme::text()
+                };
+                Line::from(vec![
+                    Span::styled(format!(" {:<34}", action.description(self.language)), style),
+                    Span::styled(control, Theme::help_key()),
+                ])
+            })
+            .collect();
+        lines.push(Line::from(""));
+        if self.midi.is_none() {
+            lines.push(Line::from(Span::styled(
+                " Connect a MIDI controller to map its buttons",
+                Theme::text_dim(),
+            )));
+        } else if mapping.learn == Learn::Key {
+            lines.push(Line::from(Span::styled(
+                " Press the keys of any command to map it",
+                Theme::text(),
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(
+                " l maps any command by its keys",
+                Theme::text_dim(),
+            )));
+        }
+
+        let block = Block::default()
+            .title(" Controller [↑↓ Enter learn  l by key  x unmap  Esc] ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
+    }
+
+    fn render_detail_overlay(&self, frame: &mut Frame, area: Rect) {
+        let Some(entry) = self.history.selected() else {
+            return;
+        };
+
+        let width = 50.min(area.width);
+        let height = 13.min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+
+        let overlay_area = Rect::new(x, y, width, height);
+        let spelling = self.spelling();
+
+        let block = Block::default()
+            .title(format!(
+                " {} ",
+                entry.chord.name_in(self.naming, spelling, self.note_names)
+            ))
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        let inner = block.inner(overlay_area);
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(block, overlay_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(6),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .split(inner);
+
+        let notes: HashSet<u8> = entry.notes.iter().copied().collect();
+        let root = entry
+            .notes
+            .iter()
+            .copied()
+            .find(|&n| n % 12 == entry.chord.root.pitch_class());
+        let piano = Piano::dynamic(&notes).root(root).hires(self.piano_hires);
+        frame.render_widget(piano, layout[0]);
+
+        let index = self.history.selected_index().unwrap_or_default();
+        if let Some(scale) = self.analysis.key_at(index) {
+            let mut key_spans = vec![
+                Span::styled(" Key: ", Theme::text_dim()),
+                Span::styled(scale.name_in(self.note_names), Theme::text()),
+                Span::styled("  ", Theme::text_dim()),
+                Span::styled(entry.chord.roman_numeral(scale.tonic), Theme::chord_name()),
+            ];
+            if let Some(modulation) = self.history.modulation_at(index) {
+                key_spans.push(Span::styled(
+                    format!("  ({})", modulation.label()),
+                    Theme::modulation(),
+                ));
+            }
+            frame.render_widget(Paragraph::new(Line::from(key_spans)), layout[1]);
+        }
+
+        let spans: Vec<Span> = entry
+            .notes
+            .iter()
+            .flat_map(|&midi| {
+                [
+                    Span::styled(
+                        format!(" {}", Note::new(midi).display_in(spelling, self.note_names)),
+                        Theme::text(),
+                    ),
+                    Span::styled(
+                        format!(" ({})", entry.chord.interval_label(midi)),
+                        Theme::text_dim(),
+                    ),
+                ]
+            })
+            .collect();
+
+        let mut lines = vec![Line::from(spans)];
+        for parallel in self.history.parallels_at(index) {
+            let word = match parallel.kind {
+                ParallelKind::Fifth => "fifths",
+                ParallelKind::Octave => "octaves",
+            };
+            lines.push(Line::from(Span::styled(
+                format!(
+                    " Parallel {}: {}-{} → {}-{}",
+                    word,
+                    Note::new(parallel.from.0).display_in(spelling, self.note_names),
+                    Note::new(parallel.from.1).display_in(spelling, self.note_names),
+                    Note::new(parallel.to.0).display_in(spelling, self.note_names),
+                    Note::new(parallel.to.1).display_in(spelling, self.note_names),
+                ),
+                Theme::parallel(),
+            )));
+        }
+
+        if let Some(suspension) = self.history.suspension_at(index) {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    " Suspension {}: {} → {}",
+                    suspension.figure,
+                    Note::new(suspension.from).display_in(spelling, self.note_names),
+                    Note::new(suspension.to).display_in(spelling, self.note_names),
+                ),
+                Theme::suspension(),
+            )));
+        }
+
+        frame.render_widget(Paragraph::new(lines), layout[2]);
+    }
+
+    fn render_presets_overlay(&self, frame: &mut Frame, area: Rect) {
+        let width = 48.min(area.width);
+        let height = (Preset::all().len() as u16 + 6).min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let mut lines = Vec::new();
+        let mut category = None;
+
+        for (i, preset) in Preset::all().iter().enumerate() {
+            if category != Some(preset.category) {
+                category = Some(preset.category);
+                lines.push(Line::from(Span::styled(
+                    format!(" {}", preset.category.name()),
+                    Theme::title(),
+                )));
+            }
+
+            let style = if i == self.preset_cursor {
+                Theme::border_focused()
+            } else {
+                Theme::help_text()
+            };
+            let marker = if i == self.preset_cursor { "▸" } else { " " };
+            lines.push(Line::from(Span::styled(
+                format!(" {} {}", marker, preset.name),
+                style,
+            )));
+        }
+
+        let block = Block::default()
+            .title(" Presets [↑↓ Enter c compare Esc] ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
+    }
+
+    fn render_compare_overlay(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        diff: &TakeDiff,
+        scroll: usize,
+    ) {
+        let width = 52.min(area.width);
+        let rows = diff.rows.len().clamp(1, 16);
+        let height = (rows as u16 + 6).min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(
+                    format!(" {:.0}% matched", diff.accuracy() * 100.0),
+                    Theme::chord_name(),
+                ),
+                Span::styled(
+                    format!(
+                        "  {} changed  {} missed  {} extra",
+                        diff.count(Change::Changed),
+                        diff.count(Change::Missed),
+                        diff.count(Change::Extra)
+                    ),
+                    Theme::text_dim(),
+                ),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("   {:<22}{}", diff.reference_name, diff.take_name),
+                Theme::title(),
+            )),
+        ];
+        let name = |chord: &Option<Chord>| {
+            chord
+                .as_ref()
+                .map_or("—".to_string(), |c| self.chord_name(c))
+        };
+        for row in diff.rows.iter().skip(scroll).take(rows) {
+            let (marker, style) = match row.change() {
+                Change::Same => (" ", Theme::text()),
+                Change::Changed => ("≠", Theme::out_of_key()),
+                Change::Missed => ("−", Theme::out_of_key()),
+                Change::Extra => ("+", Theme::modulation()),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {} ", marker), style),
+                Span::styled(format!("{:<22}", name(&row.reference)), Theme::text()),
+                Span::styled(name(&row.take), style),
+            ]));
+        }
+
+        let block = Block::default()
+            .title(" Compare takes [↑↓ Esc] ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
+    }
+
+    fn render_browser_overlay(&self, frame: &mut Frame, area: Rect, browser: &SessionBrowser) {
+        const VISIBLE: usize = 10;
+
+        let width = 64.min(area.width);
+        let rows = browser.sessions.len().clamp(1, VISIBLE);
+        let marks = browser
+            .selected()
+            .map_or(0, |s| s.bookmarks.len().min(BOOKMARKS_SHOWN));
+        let height = (rows as u16 + marks as u16 + 7).min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+
+        let overlay_area = Rect::new(x, y, width, height);
+        let mut lines = Vec::new();
+
+        if browser.sessions.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  No saved sessions yet",
+                Theme::text_dim(),
+            )));
+        }
+
+        let first = browser.cursor.saturating_sub(VISIBLE - 1);
+        for (i, summary) in browser
+            .sessions
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(VISIBLE)
+        {
+            let selected = i == browser.cursor;
+            let style = if selected {
+                Theme::border_focused()
+            } else {
+                Theme::help_text()
+            };
+            let minutes = summary.duration.as_secs() / 60;
+            let seconds = summary.duration.as_secs() % 60;
+            let keys = if summary.keys.is_empty() {
+                "—".to_string()
+            } else {
+                summary.keys.join("→")
+            };
+            let marks = match summary.bookmarks.len() {
+                0 => String::new(),
+                n => format!(" ★{}", n),
+            };
+            lines.push(Line::from(Span::styled(
+                format!(
+                    " {} {}  {:>3}:{:02}  {:<12} {:>4} chords{}",
+                    if browser.reference.as_ref() == Some(&summary.path) {
+                        "◆"
+                    } else if selected {
+                        "▸"
+                    } else {
+                        " "
+                    },
+                    summary.date(),
+                    minutes,
+                    seconds,
+                    keys,
+                    summary.chord_count(),
+                    marks
+                ),
+                style,
+            )));
+        }
+
+        lines.push(Line::from(""));
+        if let Some(summary) = browser.selected() {
+            let (naming, spelling, names) = (self.naming, self.spelling(), self.note_names);
+            let chords = summary.session.chords();
+            let mut preview = vec![Span::raw(" ")];
+            for chord in chords.iter().take(12) {
+                preview.push(Span::styled(
+                    format!(" {}", chord.name_in(naming, spelling, names)),
+                    self.chord_style(chord, Theme::chord_name()),
+                ));
+            }
+            if summary.chord_count() > chords.len().min(12) {
+                preview.push(Span::styled(" …", Theme::chord_name()));
+            }
+            lines.push(Line::from(preview));
+
+            let first = browser
+                .bookmark
+                .map_or(0, |i| (i + 1).saturating_sub(BOOKMARKS_SHOWN));
+            for (i, bookmark) in summary
+                .bookmarks
+                .iter()
+                .enumerate()
+                .skip(first)
+                .take(BOOKMARKS_SHOWN)
+            {
+                let style = if browser.bookmark == Some(i) {
+                    Theme::help_text().patch(Theme::selected())
+                } else {
+                    Theme::help_text()
+                };
+                let secs = bookmark.at.as_secs();
+                let chord = summary
+                    .chord_at(bookmark.at)
+                    .and_then(|e| Chord::from_name(&e.chord))
+                    .map(|c| c.name_in(naming, spelling, names))
+                    .unwrap_or_default();
+                lines.push(Line::from(vec![
+                    Span::styled("  ★ ", Theme::help_key()),
+                    Span::styled(
+                        format!("{:>3}:{:02} {:<8}", secs / 60, secs % 60, chord),
+                        style,
+                    ),
+                    Span::styled(bookmark.note.clone().unwrap_or_default(), Theme::text_dim()),
+                ]));
+            }
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  Enter ", Theme::help_key()),
+            Span::styled("resume  ", Theme::help_text()),
+            Span::styled("p ", Theme::help_key()),
+            Span::styled("replay  ", Theme::help_text()),
+            Span::styled("Tab ", Theme::help_key()),
+            Span::styled("★  ", Theme::help_text()),
+            Span::styled("x/X ", Theme::help_key()),
+            Span::styled("export  ", Theme::help_text()),
+            Span::styled("c ", Theme::help_key()),
+            Span::styled("compare  ", Theme::help_text()),
+            Span::styled("d ", Theme::help_key()),
+            Span::styled("delete", Theme::help_text()),
+        ]));
+
+        let block = Block::default()
+            .title(" Sessions [↑↓ Esc] ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
+    }
+
+    fn render_intervals_overlay(&self, frame: &mut Frame, area: Rect, drill: &IntervalDrill) {
+        let width = 60.min(area.width);
+        let height = 6.min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = area.height.saturating_sub(height + 12);
+
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let choices: Vec<Span> = drill
+            .choices()
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &semitones)| {
+                let style = if i == self.interval_cursor {
+                    Theme::help_key().patch(Theme::selected())
+                } else {
+                    Theme::help_text()
+                };
+                [
+                    Span::raw(" "),
+                    Span::styled(interval_name(semitones), style),
+                ]
+            })
+            .collect();
+
+        let score = drill.score();
+        let lines = vec![
+            Line::from(choices),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(" Level ", Theme::text_dim()),
+                Span::styled(drill.level().to_string(), Theme::text()),
+                Span::styled("  Score ", Theme::text_dim()),
+                Span::styled(
+                    format!("{}/{}", score.correct, score.attempts),
+                    Theme::text(),
+                ),
+                Span::styled("  or play the interval", Theme::text_dim()),
+            ]),
+        ];
+
+        let block = Block::default()
+            .title(" Intervals [←→ Enter Space Esc] ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
+    }
+
+    fn render_fingering_overlay(&self, frame: &mut Frame, area: Rect, chord: &Chord, index: usize) {
+        let grips = fingerings(chord, &self.tuning, self.capo);
+        let strings = self.tuning.string_count() as u16;
+
+        let width = 40.min(area.width);
+        let height = (strings + 5).min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let block = Block::default()
+            .title(format!(
+                " Guitar: {} [←→ Tab Space Esc] ",
+                self.chord_name(chord)
+            ))
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        let inner = block.inner(overlay_area);
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(block, overlay_area);
+
+        let Some(grip) = grips.get(index % grips.len().max(1)) else {
+            let none = Paragraph::new(Span::styled(
+                format!(" No playable grip in {}", self.tuning),
+                Theme::text_dim(),
+            ));
+            frame.render_widget(none, inner);
+            return;
+        };
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .split(inner);
+
+        let mut header = vec![
+            Span::styled(format!(" {}", grip.tab()), Theme::chord_name()),
+            Span::styled(format!("  {}", grip.label), Theme::text()),
+            Span::styled(
+                format!("  ({}/{})", index % grips.len() + 1, grips.len()),
+                Theme::text_dim(),
+            ),
+        ];
+        if self.capo > 0 {
+            header.push(Span::styled(
+                format!("  capo {}", self.capo),
+                Theme::text_dim(),
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(header)), layout[0]);
+
+        let board = Fretboard::new(&self.tuning)
+            .fingering(grip)
+            .root(chord.root.pitch_class())
+            .capo(self.capo)
+            .left_handed(self.left_handed);
+        frame.render_widget(board, layout[1]);
+    }
+
+    fn render_explorer_overlay(&self, frame: &mut Frame, area: Rect, chord: &Chord, index: usize) {
+        let options = voicing::explore(chord);
+        let count = options.len();
+        let Some(option) = options.get(index % count.max(1)) else {
+            return;
+        };
+        let spelling = self.spelling();
+
+        let width = 50.min(area.width);
+        let height = 12.min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let block = Block::default()
+            .title(format!(
+                " Voicings: {} [←→ Space Esc] ",
+                chord.name_in(self.naming, spelling, self.note_names)
+            ))
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        let inner = block.inner(overlay_area);
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(block, overlay_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(6),
+                Constraint::Min(1),
+            ])
+            .split(inner);
+
+        let header = Line::from(vec![
+            Span::styled(format!(" {}", option.label()), Theme::chord_name()),
+            Span::styled(
+                format!("  ({}/{})", index % count.max(1) + 1, count),
+                Theme::text_dim(),
+            ),
+        ]);
+        frame.render_widget(Paragraph::new(header), layout[0]);
+
+        let notes: HashSet<u8> = option.notes.iter().copied().collect();
+        let root = option
+            .notes
+            .iter()
+            .copied()
+            .find(|&n| n % 12 == chord.root.pitch_class());
+        let piano = Piano::dynamic(&notes).root(root).hires(self.piano_hires);
+        frame.render_widget(piano, layout[1]);
+
+        let names: Vec<Span> = option
+            .notes
+            .iter()
+            .map(|&n| {
+                Span::styled(
+                    format!(" {}", Note::new(n).display_in(spelling, self.note_names)),
+                    Theme::text(),
+                )
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(Line::from(names)), layout[2]);
+    }
+
+    fn render_recovery_overlay(&self, frame: &mut Frame, area: Rect, session: &Session) {
+        let width = 44.min(area.width);
+        let height = 7.min(area.height);
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
+
+        let overlay_area = Rect::new(x, y, width, height);
+
+        let text = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("  Found an unsaved session ({} chords).", session.len()),
+                Theme::help_text(),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("  y ", Theme::help_key()),
+                Span::styled("Restore   ", Theme::help_text()),
+                Span::styled("n ", Theme::help_key()),
+                Span::styled("Discard", Theme::help_text()),
+            ]),
+        ];
+
+        let block = Block::default()
+            .title(" Recover Session ")
+            .borders(Borders::ALL)
+            .border_style(Theme::border_focused());
+
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(text).block(block), overlay_area);
+    }
+}
+
+/// Seconds since the epoch, for naming exported files.
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionEntry;
+    use crate::theory::Quality;
+    use crate::ui::snapshot;
+
+    /// Mid-jam, set up directly rather than played so nothing on screen
+    /// depends on the time: a few chords down and G7 held.
+    fn jamming() -> App {
+        let mut app = App::new();
+        app.set_animations(false);
+        for (name, notes) in [
+            ("C", vec![48, 55, 64]),
+            ("Am", vec![45, 57, 64]),
+            ("F", vec![41, 57, 65]),
+            ("G7", vec![43, 59, 65]),
+        ] {
+            app.history
+                .push_voiced(Chord::from_name(name).unwrap(), notes);
+        }
+        app.current_chord = Chord::from_name("G7");
+        app.last_notes = HashSet::from([43, 59, 65]);
+        // Held long enough that confidence has settled
+        app.chord_since = Instant::now() - Duration::from_secs(60);
+        app
+    }
+
+    /// Lets go of the notes held on the app's input and plays `notes`.
+    fn play(app: &mut App, notes: &[u8]) {
+        let input = app.midi.as_ref().expect("a MIDI input");
+        for note in input.held_notes() {
+            input.note_off(note);
+        }
+        for &note in notes {
+            input.note_on(note);
+        }
+        app.tick();
+    }
+
+    #[test]
+    fn test_layout_snapshots() {
+        let empty = App::new();
+        let app = jamming();
+        for (width, height) in snapshot::SIZES {
+            insta::assert_snapshot!(
+                format!("empty_{}x{}", width, height),
+                snapshot::app(&empty, width, height)
+            );
+            insta::assert_snapshot!(
+                format!("jamming_{}x{}", width, height),
+                snapshot::app(&app, width, height)
+            );
+        }
+    }
+
+    #[test]
+    fn test_overlay_snapshots() {
+        let overlay = |name: &str, open: fn(&mut App)| {
+            let mut app = jamming();
+            open(&mut app);
+            insta::assert_snapshot!(format!("overlay_{}", name), snapshot::app(&app, 100, 32));
+        };
+        overlay("help", App::toggle_help);
+        overlay("detail", |app| {
+            app.history.select_prev();
+            app.open_screen(Screen::Detail);
+        });
+        overlay("heatmap", |app| app.open_screen(Screen::Heatmap));
+        overlay("presets", App::toggle_presets);
+        overlay("controls", App::toggle_control_mapping);
+        overlay("key_finder", App::open_key_finder);
+        overlay("reharm", App::open_reharm);
+        overlay("transposer", App::open_transposer);
+    }
+
+    #[test]
+    fn test_transpose_for_singer() {
+        let mut app = jamming();
+        app.set_key(Some(Note::new(67)));
+        app.handle_key(KeyCode::Char('y'));
+        app.handle_key(KeyCode::Down);
+        for _ in 0..3 {
+            app.handle_key(KeyCode::Right);
+        }
+        app.handle_key(KeyCode::Enter);
+        assert!(app.screen().is_none());
+        let t = app.transposition.unwrap();
+        assert_eq!((t.from.pitch_class(), t.to.pitch_class()), (7, 10));
+
+        // G7 is shown as played, and as Bb7 in the sung key
+        let shown = snapshot::app(&app, 120, 30);
+        let bb7 = app.chord_name(&Chord::from_name("Bb7").unwrap());
+        assert!(shown.contains(&format!(": {}", bb7)), "{}", shown);
+        assert!(shown.contains(&format!("G7* ({})", bb7)), "{}", shown);
+
+        app.handle_key(KeyCode::Char('y'));
+        app.handle_key(KeyCode::Delete);
+        assert!(app.transposition.is_none());
+        assert!(!snapshot::app(&app, 120, 30).contains(&bb7));
+    }
+
+    #[test]
+    fn test_reharm_replaces_sketch() {
+        let mut app = jamming();
+        app.handle_key(KeyCode::Char('h'));
+        assert!(matches!(app.screen(), Some(Screen::Reharm(..))));
+        // Tritone subs, relative subs, passing dim, line cliché
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Enter);
+        assert!(app.screen().is_none());
+
+        assert_eq!(app.sketch.sections.len(), 1);
+        let chords: Vec<&str> = app.sketch.sections[0]
+            .chords
+            .iter()
+            .map(|e| e.chord.as_str())
+            .collect();
+        assert_eq!(chords, ["C", "Am", "F", "F#dim7", "G7"]);
+    }
+
+    #[test]
+    fn test_key_finder() {
+        let mut app = jamming();
+        app.handle_key(KeyCode::Char('k'));
+        let chords: Vec<String> = app.key_finder_chords().iter().map(|c| c.name()).collect();
+        assert_eq!(chords, ["C", "Am", "F", "G7"]);
+
+        for c in "Am Dm E7 Am".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        let ranked = keys::rank_keys(&app.key_finder_chords());
+        assert_eq!(ranked[0].scale.name(), "Am");
+
+        app.handle_key(KeyCode::Esc);
+        assert!(app.screen().is_none());
+    }
+
+    #[test]
+    fn test_mode_toggle() {
+        let mut app = App::new();
+        assert_eq!(app.mode, Mode::Discovery);
+
+        app.toggle_mode();
+        assert_eq!(app.mode, Mode::Jam);
+
+        app.toggle_mode();
+        assert_eq!(app.mode, Mode::Discovery);
+    }
+
+    #[test]
+    fn test_extended_toggle() {
+        let mut app = App::new();
+        assert!(!app.extended_chords);
+
+        app.toggle_extended();
+        assert!(app.extended_chords);
+
+        app.toggle_extended();
+        assert!(!app.extended_chords);
+    }
+
+    #[test]
+    fn test_comping_ghost_in_extended_mode() {
+        let mut app = App::new();
+        app.current_chord = Some(Chord::new(Note::new(62), Quality::Minor7));
+        assert!(app.comping_ghost().is_empty());
+
+        app.toggle_extended();
+        let ghost = app.comping_ghost();
+        assert_eq!(ghost.len(), 4);
+        assert!(ghost.iter().all(|&n| (50..75).contains(&n)));
+    }
+
+    #[test]
+    fn test_voicing_explorer() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('v'));
+        assert!(app.screen().is_none());
+
+        app.current_chord = Some(Chord::new(Note::new(60), Quality::Major7));
+        app.handle_key(KeyCode::Char('v'));
+        assert_eq!(
+            app.explorer_voicing().unwrap().label(),
+            "Close, root position"
+        );
+
+        app.handle_key(KeyCode::Left);
+        assert_eq!(
+            app.explorer_voicing().unwrap().label(),
+            "Spread, 3rd inversion"
+        );
+        app.handle_key(KeyCode::Right);
+        app.handle_key(KeyCode::Right);
+        assert_eq!(app.explorer_voicing().unwrap().notes, vec![64, 67, 71, 72]);
+
+        app.handle_key(KeyCode::Esc);
+        assert!(app.screen().is_none());
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_bass_style_toggle() {
+        let mut app = App::new();
+        assert_eq!(app.bass_style, BassStyle::Walking);
+
+        app.handle_key(KeyCode::Char('B'));
+        assert_eq!(app.bass_style, BassStyle::RootFifth);
+        app.handle_key(KeyCode::Char('B'));
+        assert_eq!(app.bass_style, BassStyle::Walking);
+    }
+
+    #[test]
+    fn test_tap_tempo_sets_bpm() {
+        let mut app = App::new();
+        let start = Instant::now();
+        app.auto_tempo = true;
+
+        app.tap(start);
+        assert_eq!(app.bpm, None);
+        app.tap(start + Duration::from_millis(600));
+        app.tap(start + Duration::from_millis(1200));
+
+        assert!((app.bpm.unwrap() - 100.0).abs() < 0.5);
+        assert!(!app.auto_tempo);
+    }
+
+    #[test]
+    fn test_captured_chords_are_timed() {
+        let mut app = App::new();
+        let input = MidiInput::new();
+        for note in [48, 52, 55] {
+            input.note_on(note);
+        }
+        app.midi = Some(input);
+        app.tick();
+
+        assert_eq!(app.session.onsets().map(|o| o.len()), Some(1));
+        assert!(app.quantizer().is_none());
+
+        app.bpm = Some(90.0);
+        app.handle_key(KeyCode::Char('w'));
+        assert_eq!(app.quantizer().unwrap().feel, Feel::Swing);
+    }
+
+    #[test]
+    fn test_follow_stats() {
+        let mut app = App::new();
+        app.midi = Some(MidiInput::new());
+
+        play(&mut app, &[60, 64, 67]);
+        assert_eq!(app.follow.total(), 0);
+
+        let node = app
+            .tree
+            .suggest(app.current_chord.as_ref().unwrap(), app.key);
+        play(&mut app, &node.left.unwrap().chord.notes());
+        play(&mut app, &[66, 70, 73]);
+        assert_eq!(app.follow.expected, 1);
+        assert_eq!(app.follow.total(), 2);
+        assert_eq!(app.follow.follow_rate(), 0.5);
+    }
+
+    #[test]
+    fn test_looper_needs_tempo() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('L'));
+        assert!(app.looper.is_none());
+
+        app.bpm = Some(120.0);
+        app.loop_bars = 8;
+        app.handle_key(KeyCode::Char('L'));
+        let looper = app.looper.as_ref().unwrap();
+        assert_eq!(looper.state(), Transport::Recording);
+        assert_eq!(looper.bars(), 8);
+
+        let input = MidiInput::new();
+        for note in [48, 52, 55] {
+            input.note_on(note);
+        }
+        app.midi = Some(input);
+        app.tick();
+        assert_eq!(app.looper.as_ref().unwrap().layers()[0].len(), 1);
+
+        app.handle_key(KeyCode::Char('O'));
+        assert!(app.looper.is_none());
+    }
+
+    #[test]
+    fn test_time_signature() {
+        let mut app = App::new();
+        app.bpm = Some(120.0);
+        app.handle_key(KeyCode::Char('/'));
+        assert_eq!(app.message.as_deref(), Some("Time signature 3/4"));
+        app.handle_key(KeyCode::Char('/'));
+        assert_eq!(app.time_signature, TimeSignature::SixEight);
+        assert_eq!(app.bar_length(), Some(Duration::from_millis(1500)));
+
+        app.handle_key(KeyCode::Char('L'));
+        assert_eq!(app.looper.as_ref().unwrap().beats(), 12.0);
+        let screen = snapshot::app(&app, 100, 30);
+        assert!(screen.contains("Looper: 4 bars of 6/8"));
+        assert!(screen.contains("♩=120 6/8"));
+
+        // Changed mid-loop, it waits for the next one
+        app.handle_key(KeyCode::Char('/'));
+        assert!(app
+            .message
+            .as_deref()
+            .unwrap()
+            .ends_with("from the next loop"));
+        assert_eq!(
+            app.looper.as_ref().unwrap().meter(),
+            TimeSignature::SixEight
+        );
+
+        app.clear_history();
+        assert_eq!(app.session.time_signature_at(0), TimeSignature::FiveFour);
+        assert_eq!(
+            app.quantizer().unwrap().time_signature,
+            TimeSignature::FiveFour
+        );
+    }
+
+    #[test]
+    fn test_blues() {
+        let mut app = App::new();
+        app.midi = Some(MidiInput::new());
+        let text =
+            |spans: Vec<Span>| -> String { spans.iter().map(|s| s.content.as_ref()).collect() };
+
+        app.handle_key(KeyCode::Char('J'));
+        assert!(app.blues.is_some());
+        play(&mut app, &[55, 59, 62, 65]);
+        assert_eq!(app.key().map(|k| k.pitch_class()), Some(7));
+        assert!(!app.is_out_of_key());
+        assert_eq!(app.blues_bar(), Some(0));
+
+        // Bar 2 stays on G7, or makes the quick change to C7
+        let node = app.planned_tree(app.current_chord.as_ref().unwrap());
+        assert_eq!(node.left.unwrap().chord.name(), "G7");
+        assert_eq!(node.right.unwrap().chord.name(), "C7");
+
+        play(&mut app, &[48, 52, 55, 58]);
+        assert!(!app.is_out_of_key());
+        assert_eq!(
+            text(app.status_segment(StatusSegment::Blues)),
+            "[J] Blues 2/12 G7"
+        );
+
+        app.handle_key(KeyCode::Char('J'));
+        assert!(app.is_out_of_key());
+        assert!(app.status_segment(StatusSegment::Blues).is_empty());
+    }
+
+    #[test]
+    fn test_function_ribbon() {
+        let mut app = App::new();
+        app.set_key(Some(Note::new(60)));
+        for name in ["C", "F", "G7", "Eb"] {
+            app.history.push(Chord::from_name(name).unwrap());
+        }
+        let functions: String = app.functions().iter().map(|f| f.letter()).collect();
+        assert_eq!(functions, "TSDC");
+
+        let screen = snapshot::app(&app, 100, 30);
+        assert!(screen.contains("T S D C"));
+        let area = app.ribbon_area.get();
+        app.handle_click(area.x + 2, area.y);
+        assert_eq!(app.history.selected_index(), Some(1));
+        app.handle_click(area.x + 2, area.y + 1);
+        assert_eq!(app.history.selected_index(), Some(1));
+
+        app.toggle_help();
+        app.handle_click(area.x + 4, area.y);
+        assert_eq!(app.history.selected_index(), Some(1));
+        app.toggle_help();
+        app.handle_click(area.x + 4, area.y);
+        assert_eq!(app.history.selected().unwrap().chord.name(), "G7");
+    }
+
+    #[test]
+    fn test_cycle_groove() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('Q'));
+        assert_eq!(app.message.as_deref(), Some("Groove: Pad"));
+        for _ in 0..app.grooves.len() {
+            app.handle_key(KeyCode::Char('Q'));
+        }
+        assert!(app.groove().is_none());
+
+        app.set_groove(" bossa").unwrap();
+        assert_eq!(app.groove().unwrap().name, "Bossa");
+        assert!(app.set_groove("polka").is_err());
+    }
+
+    #[test]
+    fn test_audition_branches() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('j'));
+        assert!(app.message.as_deref().unwrap().starts_with("Play a chord"));
+
+        let mut app = jamming();
+        let names = |app: &App, surprise| -> Vec<String> {
+            let chords = app.branch_in_context(surprise).unwrap();
+            chords.iter().map(|c| c.name()).collect()
+        };
+        // Already resolving to the tonic, so it isn't played twice
+        assert_eq!(names(&app, false), ["G7", "C"]);
+        assert_eq!(names(&app, true), ["G7", "Em", "C"]);
+
+        app.set_key(Some(Note::new(67)));
+        assert_eq!(names(&app, true), ["G7", "Em", "G"]);
+    }
+
+    #[test]
+    fn test_looper_sends_clock() {
+        let mut app = App::new();
+        app.bpm = Some(120.0);
+        app.handle_key(KeyCode::Char('L'));
+        assert!(app.clock_out.is_none());
+        app.handle_key(KeyCode::Char('O'));
+
+        app.send_clock = true;
+        app.handle_key(KeyCode::Char('L'));
+        assert!(app.clock_out.is_some());
+        app.handle_key(KeyCode::Char('O'));
+        assert!(app.clock_out.is_none());
+    }
+
+    #[test]
+    fn test_cycle_humanize() {
+        let mut app = App::new();
+        assert!(app.humanize.is_off());
+
+        app.handle_key(KeyCode::Char('H'));
+        assert_eq!(app.humanize.amount, 0.25);
+        for _ in 0..3 {
+            app.handle_key(KeyCode::Char('H'));
+        }
+        assert_eq!(app.humanize.amount, 1.0);
+        app.handle_key(KeyCode::Char('H'));
+        assert!(app.humanize.is_off());
+    }
+
+    #[test]
+    fn test_browser_resumes_session() {
+        let dir =
+            std::env::temp_dir().join(format!("chordvery-app-browser-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut writer = SessionWriter::open(&dir.join("session-42.jsonl")).unwrap();
+        for (t, name) in [(0, "Dm7"), (2000, "G7"), (4000, "Cmaj7")] {
+            writer
+                .write(&crate::session::TimedEvent {
+                    t,
+                    event: Event::Chord {
+                        name: name.to_string(),
+                        notes: vec![],
+                    },
+                })
+                .unwrap();
+        }
+        drop(writer);
+
+        let mut app = App::new();
+        app.toggle_browser();
+        assert!(app.screen().is_none());
+        assert!(app.message.take().is_some());
+
+        app.set_sessions_dir(dir.clone());
+        app.handle_key(KeyCode::Char('S'));
+        assert!(matches!(app.screen(), Some(Screen::Sessions(b)) if b.sessions.len() == 1));
+
+        // Delete needs a second press; anything else cancels it
+        app.handle_key(KeyCode::Char('d'));
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Char('d'));
+        assert!(dir.join("session-42.jsonl").exists());
+
+        app.handle_key(KeyCode::Enter);
+        assert!(app.screen().is_none());
+        assert_eq!(app.session.len(), 3);
+        assert_eq!(app.history.entries().len(), 3);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_confidence_grows_while_held() {
+        let mut app = App::new();
+        assert_eq!(app.confidence(), None);
+
+        app.current_chord = Some(Chord::new(Note::new(60), Quality::Dominant7));
+        app.confidence = Confidence {
+            completeness: 0.75,
+            bass: 1.0,
+        };
+        app.chord_since = Instant::now();
+        assert!(app.confidence().unwrap() < LOW_CONFIDENCE);
+
+        app.chord_since = Instant::now() - Duration::from_secs(1);
+        assert_eq!(app.confidence(), Some(0.75));
+    }
+
+    #[test]
+    fn test_play_along_scores_matches() {
+        use crate::export::midi::render;
+
+        let mut session = Session::new();
+        session.push(&Chord::new(Note::new(60), Quality::Major));
+        session.push(&Chord::new(Note::new(67), Quality::Major));
+        let file = MidiFile::parse(&render(&session, &MidiOptions::default().bass(None))).unwrap();
+
+        let mut app = App::new();
+        app.start_play_along("test", file);
+        assert!(app.message.is_some());
+
+        let input = MidiInput::new();
+        for note in [48, 52, 55] {
+            input.note_on(note);
+        }
+        app.midi = Some(input);
+        app.tick();
+
+        let score = app.along.as_ref().unwrap().score(app.along_time());
+        assert_eq!((score.correct, score.attempts), (1, 1));
+    }
+
+    #[test]
+    fn test_handle_key_quit() {
+        let mut app = App::new();
+        assert!(!app.should_quit);
+
+        app.handle_key(KeyCode::Char('q'));
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_handle_key_tab() {
+        let mut app = App::new();
+        assert_eq!(app.mode, Mode::Discovery);
+
+        app.handle_key(KeyCode::Tab);
+        assert_eq!(app.mode, Mode::Jam);
+    }
+
+    #[test]
+    fn test_cycle_naming() {
+        let mut app = App::new();
+        assert_eq!(app.naming, NamingStyle::Plain);
+
+        app.handle_key(KeyCode::Char('n'));
+        assert_eq!(app.naming, NamingStyle::Jazz);
+    }
+
+    #[test]
+    fn test_localized_names() {
+        let mut app = App::new();
+        let config = Config::from_toml("[display]\nlanguage = \"de\"").unwrap();
+        app.apply_config(&config);
+        assert_eq!(app.language, Language::German);
+        assert_eq!(app.tr(Text::History), "Verlauf");
+        assert_eq!(app.chord_name(&Chord::from_name("Bm").unwrap()), "Hm");
+        app.set_key(Some(Note::new(65)));
+        assert_eq!(app.chord_name(&Chord::from_name("Bb").unwrap()), "B");
+        app.cycle_capo();
+        assert_eq!(
+            app.message.as_deref(),
+            Some("Kapodaster im 1. Bund: Akkorde zeigen ihre Griffe")
+        );
+
+        app.set_note_names(NoteNames::Solfege);
+        assert_eq!(app.chord_name(&Chord::from_name("Gm7").unwrap()), "Solm7");
+    }
+
+    #[test]
+    fn test_spelling_follows_key() {
+        let mut app = App::new();
+        assert_eq!(app.spelling(), Spelling::Sharp);
+
+        app.set_key(Some(Note::new(65)));
+        assert_eq!(app.spelling(), Spelling::Flat);
+
+        app.handle_key(KeyCode::Char('c'));
+        assert_eq!(app.spelling(), Spelling::Sharp);
+    }
+
+    #[test]
+    fn test_recovery_prompt() {
+        use crate::theory::Quality;
+
+        let mut session = Session::new();
+        session.set_key(Some(Note::new(60)));
+        session.push(&Chord::new(Note::new(60), Quality::Major));
+        session.push(&Chord::new(Note::new(67), Quality::Major));
+
+        let mut app = App::new();
+        app.open_screen(Screen::Recovery(session.clone()));
+
+        app.handle_key(KeyCode::Char('q'));
+        assert!(!app.should_quit);
+        assert!(matches!(app.screen(), Some(Screen::Recovery(_))));
+
+        app.handle_key(KeyCode::Char('y'));
+        assert!(app.screen().is_none());
+        assert_eq!(app.session, session);
+        assert_eq!(app.history.entries().len(), 2);
+        assert_eq!(app.key().map(|k| k.pitch_class()), Some(0));
+    }
+
+    #[test]
+    fn test_recovery_discard() {
+        let mut app = App::new();
+        app.open_screen(Screen::Recovery(Session::new()));
+
+        app.handle_key(KeyCode::Char('n'));
+        assert!(app.screen().is_none());
+        assert!(app.session.is_empty());
+    }
+
+    #[test]
+    fn test_preset_browser_loads_trainer() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('b'));
+        assert!(matches!(app.screen(), Some(Screen::Presets)));
+
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.preset_cursor(), 1);
+
+        app.handle_key(KeyCode::Up);
+        app.handle_key(KeyCode::Enter);
+        assert!(app.screen().is_none());
+
+        let trainer = app.trainer.as_ref().unwrap();
+        assert_eq!(trainer.name(), Preset::all()[0].name);
+        assert_eq!(trainer.expected().unwrap().name(), "C");
+    }
+
+    #[test]
+    fn test_preset_follows_key() {
+        let mut app = App::new();
+        app.set_key(Some(Note::new(67)));
+        app.load_preset(Preset::find("ii–V–I").unwrap());
+
+        let names: Vec<String> = app
+            .trainer
+            .as_ref()
+            .unwrap()
+            .target()
+            .iter()
+            .map(|c| c.name())
+            .collect();
+        assert_eq!(names, vec!["Am7", "D7", "Gmaj7"]);
+    }
+
+    #[test]
+    fn test_detail_requires_selection() {
+        use crate::theory::Quality;
+
+        let mut app = App::new();
+        app.handle_key(KeyCode::Enter);
+        assert!(app.screen().is_none());
+
+        app.history
+            .push_voiced(Chord::new(Note::new(60), Quality::Major), vec![48, 64, 67]);
+        app.handle_key(KeyCode::Left);
+        app.handle_key(KeyCode::Enter);
+        assert!(matches!(app.screen(), Some(Screen::Detail)));
+        assert_eq!(app.history.selected().unwrap().notes, vec![48, 64, 67]);
+
+        app.handle_key(KeyCode::Char('q'));
+        assert!(app.screen().is_none());
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_load_and_reload_model() {
+        let path =
+            std::env::temp_dir().join(format!("chordvery-model-test-{}.json", std::process::id()));
+        let model = ProgressionModel {
+            name: "custom".to_string(),
+            ..ProgressionModel::default()
+        };
+        model.save(&path).unwrap();
+
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('M'));
+        assert!(app
+            .message
+            .as_ref()
+            .unwrap()
+            .contains("No progression model"));
+
+        app.load_model(path.clone()).unwrap();
+        assert_eq!(app.tree.model().name, "custom");
+
+        std::fs::write(&path, "{ not json").unwrap();
+        app.handle_key(KeyCode::Char('M'));
+        assert!(app.message.as_ref().unwrap().contains("failed"));
+        assert_eq!(app.tree.model().name, "custom");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_adaptive_blend_changes_suggestions() {
+        use crate::theory::Quality;
+
+        let mut app = App::new();
+        app.set_key(Some(Note::new(60)));
+        app.adaptive_enabled = true;
+        app.set_blend(1.0);
+
+        let c = Chord::new(Note::new(60), Quality::Major);
+        let bb = Chord::new(Note::new(70), Quality::Major);
+        app.adaptive.observe(&c, &bb, Note::new(60));
+        app.refresh_model();
+
+        let node = app.tree.suggest(&c, app.key());
+        assert_eq!(node.left.unwrap().chord.name(), "A#");
+
+        app.handle_key(KeyCode::Char('a'));
+        let node = app.tree.suggest(&c, app.key());
+        assert_eq!(node.left.unwrap().chord.name(), "F");
+
+        // Nowhere to save it, so learning stays off
+        app.handle_key(KeyCode::Char('a'));
+        assert!(!app.adaptive_enabled);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("No user to save learned transitions for")
+        );
+
+        app.handle_key(KeyCode::Char('A'));
+        assert_eq!(app.adaptive().total(), 1);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("Press A again to reset learned transitions")
+        );
+        app.handle_key(KeyCode::Char('a'));
+        app.handle_key(KeyCode::Char('A'));
+        assert_eq!(app.adaptive().total(), 1);
+        app.handle_key(KeyCode::Char('A'));
+        assert_eq!(app.adaptive().total(), 0);
+    }
+
+    #[test]
+    fn test_learning_waits_for_a_pause() {
+        use crate::clock::ManualClock;
+
+        let clock = ManualClock::new();
+        let mut app = App::new();
+        app.midi = Some(MidiInput::new());
+        app.set_clock(Arc::new(clock.clone()));
+        app.set_key(Some(Note::new(60)));
+        app.adaptive_enabled = true;
+        app.set_blend(1.0);
+
+        let c = Chord::from_name("C").unwrap();
+        play(&mut app, &[60, 64, 67]);
+        play(&mut app, &[58, 62, 65]);
+        assert_eq!(app.adaptive().total(), 1);
+        // Still suggesting from the model as it was while the chords come
+        let node = app.tree.suggest(&c, app.key());
+        assert_eq!(node.left.unwrap().chord.name(), "F");
+
+        clock.advance(LEARNING_SETTLES);
+        app.tick();
+        let node = app.tree.suggest(&c, app.key());
+        assert_eq!(node.left.unwrap().chord.name(), "A#");
+    }
+
+    #[test]
+    fn test_learning_saves_for_the_user() {
+        use crate::theory::Quality;
+
+        let root = std::env::temp_dir().join(format!("chordvery-app-learn-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let user = UserDirs::under(&root.join("data"), &root.join("config"), None).unwrap();
+        let mut app = App::new();
+        app.set_user(user.clone());
+
+        app.handle_key(KeyCode::Char('a'));
+        assert!(app.adaptive_enabled);
+        let c = Chord::new(Note::new(60), Quality::Major);
+        let f = Chord::new(Note::new(65), Quality::Major);
+        app.adaptive.observe(&c, &f, Note::new(60));
+        app.save_user_state().unwrap();
+        assert!(user.adaptive_model_path().exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_blend_clamped() {
+        let mut app = App::new();
+        app.set_blend(0.95);
+        app.handle_key(KeyCode::Char(']'));
+        assert_eq!(app.blend, 1.0);
+    }
+
+    #[test]
+    fn test_minor_tree_cycle() {
+        let mut app = App::new();
+        assert_eq!(app.minor_tree.scale(Note::new(60)), None);
+
+        app.handle_key(KeyCode::Char('m'));
+        let relative = app.minor_tree.scale(Note::new(60)).unwrap();
+        assert_eq!(relative.name(), "Am");
+
+        app.handle_key(KeyCode::Char('m'));
+        assert_eq!(app.minor_tree.scale(Note::new(60)).unwrap().name(), "Cm");
+
+        app.handle_key(KeyCode::Char('m'));
+        assert_eq!(app.minor_tree, MinorTree::Off);
+    }
+
+    #[test]
+    fn test_fingering_overlay() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('f'));
+        assert!(app.fingering().is_none());
+
+        app.current_chord = Chord::from_name("C");
+        app.handle_key(KeyCode::Char('f'));
+        assert_eq!(app.selected_grip().unwrap().tab(), "x32010");
+        app.handle_key(KeyCode::Right);
+        assert_ne!(app.selected_grip().unwrap().tab(), "x32010");
+
+        // Tab moves on to the expected suggestion
+        let expected = app
+            .tree
+            .suggest(&Chord::from_name("C").unwrap(), app.key)
+            .left
+            .unwrap()
+            .chord;
+        app.handle_key(KeyCode::Tab);
+        assert_eq!(app.fingering().unwrap().0, &expected);
+        assert_eq!(app.mode, Mode::Discovery);
+
+        app.capo = 2;
+        app.screens.close_top();
+        app.open_screen(Screen::Fingering(Chord::from_name("D").unwrap(), 0));
+        assert_eq!(app.selected_grip().unwrap().tab(), "x32010");
+
+        app.handle_key(KeyCode::Esc);
+        assert!(app.fingering().is_none());
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_milestones() {
+        let path = std::env::temp_dir().join(format!(
+            "chordvery-app-milestones-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let mut app = App::new();
+        app.enable_milestones(path.clone());
+        app.trainer = Some(Trainer::new(
+            "Two chords",
+            vec![
+                Chord::from_name("C").unwrap(),
+                Chord::from_name("G").unwrap(),
+            ],
+        ));
+        app.midi = Some(MidiInput::new());
+
+        let play = |app: &mut App, notes: &[u8]| {
+            for &note in notes {
+                app.midi.as_ref().unwrap().note_on(note);
+            }
+            app.tick();
+            for &note in notes {
+                app.midi.as_ref().unwrap().note_off(note);
+            }
+            app.tick();
+        };
+        play(&mut app, &[60, 64, 67]);
+        assert_eq!(
+            app.message.take().as_deref(),
+            Some("★ First maj chord played")
+        );
+        play(&mut app, &[55, 59, 62]);
+        assert_eq!(
+            app.message.take().as_deref(),
+            Some("★ Two chords complete without a miss")
+        );
+        play(&mut app, &[55, 59, 62, 65]);
+        assert!(app.milestones().unwrap().qualities.contains("7"));
+
+        app.shutdown().unwrap();
+        assert_eq!(Milestones::load(&path).unwrap().qualities.len(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_goals() {
+        let path = std::env::temp_dir().join(format!(
+            "chordvery-app-practice-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let mut app = App::new();
+        app.enable_practice_log(path.clone());
+        app.goals = vec![Goal::Chords(2)];
+
+        let c = Chord::from_name("C").unwrap();
+        let g = Chord::from_name("G").unwrap();
+        assert!(app.record_practice(&c, 0.0).is_empty());
+        assert!(app.record_practice(&c, 5.0).is_empty());
+        assert_eq!(
+            app.record_practice(&g, 10.0),
+            vec![Milestone::Goal("Play 2 different chords".to_string())]
+        );
+        assert!(app.record_practice(&c, 15.0).is_empty());
+        let today = app.practice_log().unwrap().day(milestones::today());
+        assert_eq!(today.seconds, 15.0);
+
+        app.handle_key(KeyCode::Char('G'));
+        assert!(matches!(app.screen(), Some(Screen::Goals)));
+        app.handle_key(KeyCode::Char('x'));
+        assert!(app.screen().is_none());
+
+        let counts = app.practice_log().unwrap().chord_counts();
+        assert_eq!(counts["maj"][0], 3);
+        app.handle_key(KeyCode::Char('F'));
+        assert!(matches!(app.screen(), Some(Screen::Heatmap)));
+        app.handle_key(KeyCode::Char('x'));
+        assert!(app.screen().is_none());
+
+        app.shutdown().unwrap();
+        assert_eq!(
+            PracticeLog::load(&path)
+                .unwrap()
+                .streak(milestones::today()),
+            1
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_switch_user() {
+        let root = std::env::temp_dir().join(format!("chordvery-app-users-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let parent = UserDirs::under(&root.join("data"), &root.join("config"), None).unwrap();
+        let kid = parent.switch(Some("kid")).unwrap();
+        std::fs::create_dir_all(&kid.config).unwrap();
+        std::fs::write(kid.config_path(), "[guitar]\ncapo = 2").unwrap();
+
+        let mut app = App::new();
+        app.set_user(parent.clone());
+        app.enable_autosave(Autosave::claim(&parent.autosave_dir()).unwrap());
+        app.record_practice(&Chord::from_name("C").unwrap(), 0.0);
+
+        app.handle_key(KeyCode::Char('W'));
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.user(), Some(&kid));
+        let autosave = app.autosave.as_ref().unwrap();
+        assert!(autosave.path().starts_with(kid.autosave_dir()));
+        assert_eq!(app.capo, 2);
+        assert!(app.practice_log().unwrap().days.is_empty());
+        assert!(parent.practice_log_path().exists());
+
+        app.handle_key(KeyCode::Char('W'));
+        app.handle_key(KeyCode::Up);
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.user(), Some(&parent));
+        assert_eq!(app.capo, 0);
+        assert_eq!(app.practice_log().unwrap().days.len(), 1);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_latch() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('z'));
+        assert!(!app.is_latched());
+
+        app.midi = Some(MidiInput::new());
+        app.handle_key(KeyCode::Char('z'));
+        assert!(app.is_latched());
+        let input = app.midi.as_ref().unwrap();
+        for note in [57, 60, 64] {
+            input.note_on(note);
+            input.note_off(note);
+        }
+        app.tick();
+        assert_eq!(app.current_chord.as_ref().unwrap().name(), "Am");
+        assert_eq!(app.last_notes.len(), 3);
+
+        app.handle_key(KeyCode::Char('z'));
+        app.tick();
+        assert!(app.last_notes.is_empty());
+    }
+
+    #[test]
+    fn test_smoothing() {
+        let mut app = App::new();
+        app.midi = Some(MidiInput::new());
+        app.handle_key(KeyCode::Char('o'));
+        assert!(app.detection.smoothing);
+        for note in [60, 64, 67] {
+            app.midi.as_ref().unwrap().note_on(note);
+        }
+        app.tick();
+        // A passing D doesn't make it Cadd9
+        app.midi.as_ref().unwrap().note_on(74);
+        app.tick();
+        app.tick();
+        assert_eq!(app.current_chord.as_ref().unwrap().name(), "C");
+        assert_eq!(app.history.entries().len(), 1);
+
+        app.handle_key(KeyCode::Char('o'));
+        app.midi.as_ref().unwrap().note_off(74);
+        app.tick();
+        app.midi.as_ref().unwrap().note_on(74);
+        app.tick();
+        assert_eq!(app.history.entries().len(), 2);
+    }
+
+    #[test]
+    fn test_melody_separation() {
+        let mut app = App::new();
+        app.midi = Some(MidiInput::new());
+        app.handle_key(KeyCode::Char('V'));
+        let chord: HashSet<u8> = [48, 52, 55].into();
+        for &note in &chord {
+            app.midi.as_ref().unwrap().note_on(note);
+        }
+        app.tick();
+        // As if the chord went down half a second ago
+        app.onsets = Onsets::default();
+        app.onsets
+            .update(&chord, Instant::now() - Duration::from_millis(500));
+
+        app.midi.as_ref().unwrap().note_on(74);
+        app.tick();
+        assert_eq!(app.melody_note(), Some(74));
+        assert_eq!(app.current_chord.as_ref().unwrap().name(), "C");
+        assert_eq!(app.history.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_spelling_panel() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('N'));
+        assert!(app.show_spelling);
+
+        app.current_chord = Some("C7".parse().unwrap());
+        app.last_notes = [48, 52, 55, 58].into();
+        let screen = snapshot::app(&app, 160, 48);
+        assert!(screen.contains("E  3rd"));
+        assert!(screen.contains("A# b7"));
+
+        app.last_notes.insert(62);
+        let screen = snapshot::app(&app, 160, 48);
+        assert!(screen.contains("D  9th / non-chord tone"));
     }
 
     #[test]
-    fn test_handle_key_tab() {
+    fn test_harmonize_melody() {
+        use crate::clock::ManualClock;
+
+        let clock = ManualClock::new();
+        let mut app = App::new();
+        app.set_animations(false);
+        app.midi = Some(MidiInput::new());
+        app.set_clock(Arc::new(clock.clone()));
+        app.handle_key(KeyCode::Char('I'));
+        assert!(app.harmonizer.is_some());
+
+        for phrase in [[65, 69, 72], [67, 71, 74]] {
+            for note in phrase {
+                let midi = app.midi.as_ref().unwrap();
+                midi.receive(&[0x90, note, 90]);
+                app.tick();
+                clock.advance(Duration::from_millis(300));
+                app.midi.as_ref().unwrap().receive(&[0x80, note, 0]);
+                app.tick();
+            }
+            clock.advance(Duration::from_secs(2));
+        }
+        let phrases: Vec<&Vec<u8>> = app.harmonizer.as_ref().unwrap().phrases().collect();
+        assert_eq!(phrases, [&vec![65, 69, 72], &vec![67, 71, 74]]);
+
+        let screen = snapshot::app(&app, 120, 40);
+        assert!(screen.contains("Harmonize melody · key C"));
+        assert!(screen.contains("→ F 100%"));
+        assert!(screen.contains("→ G 100%"));
+    }
+
+    #[test]
+    fn test_guide_tones_before_the_bar() {
+        use crate::clock::ManualClock;
+
+        let clock = ManualClock::new();
+        let mut app = jamming();
+        app.set_clock(Arc::new(clock.clone()));
+        app.current_chord = Chord::from_name("C");
+        app.bpm = Some(120.0);
+        assert!(app.guide_tone_ghost().is_empty());
+
+        app.toggle_mode();
+        let expected = app.upcoming_chord().unwrap();
+        clock.advance(Duration::from_millis(1000));
+        assert!(app.guide_tone_ghost().is_empty());
+        // The last beat of a two-second bar
+        clock.advance(Duration::from_millis(600));
+        let ghost: HashSet<u8> = expected.guide_tones().into_iter().collect();
+        assert!(!ghost.is_empty());
+        assert_eq!(app.guide_tone_ghost(), ghost);
+
+        clock.advance(Duration::from_millis(600));
+        assert!(app.guide_tone_ghost().is_empty());
+
+        app.bpm = None;
+        clock.advance(Duration::from_millis(1600));
+        assert!(app.guide_tone_ghost().is_empty());
+    }
+
+    #[test]
+    fn test_brushed_key() {
+        use crate::clock::ManualClock;
+
+        let clock = ManualClock::new();
+        let mut app = App::new();
+        app.midi = Some(MidiInput::new());
+        app.set_clock(Arc::new(clock.clone()));
+        let midi = app.midi.as_ref().unwrap();
+        for message in [
+            [0x90, 60, 90],
+            [0x90, 64, 90],
+            [0x90, 67, 90],
+            [0x90, 65, 12],
+        ] {
+            midi.receive(&message);
+        }
+        app.tick();
+        assert_eq!(app.last_notes, HashSet::from([60, 64, 65, 67]));
+        assert_eq!(app.current_chord.as_ref().unwrap().name(), "C");
+
+        clock.advance(Duration::from_millis(300));
+        app.tick();
+        assert_eq!(app.harmony, HashSet::from([60, 64, 65, 67]));
+    }
+
+    #[test]
+    fn test_pedal_point() {
+        let mut app = App::new();
+        app.set_animations(false);
+        let midi = MidiInput::new();
+        midi.receive(&[0x90, 36, 100]);
+        for message in [[0x90, 64, 90], [0x90, 67, 90], [0x90, 72, 90]] {
+            midi.receive(&message);
+        }
+        app.midi = Some(midi);
+        app.tick();
+        assert_eq!(app.current_chord.as_ref().unwrap().name(), "C");
+        assert_eq!(app.pedal_note(), None);
+
+        let midi = app.midi.as_ref().unwrap();
+        for message in [[0x80, 64, 0], [0x80, 67, 0], [0x90, 62, 90], [0x90, 67, 90]] {
+            midi.receive(&message);
+        }
+        midi.receive(&[0x80, 72, 0]);
+        midi.receive(&[0x90, 71, 90]);
+        app.tick();
+        assert_eq!(app.current_chord.as_ref().unwrap().name(), "G/C");
+        assert_eq!(app.pedal_note(), Some(36));
+        let status = snapshot::app(&app, 120, 40);
+        assert!(status.contains("Pedal C"));
+    }
+
+    #[test]
+    fn test_plan_editing() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('E'));
+        assert!(!app.is_editing_plan());
+
+        app.midi = Some(MidiInput::new());
+        play(&mut app, &[60, 64, 67]);
+        let c = app.current_chord.clone().unwrap();
+        let expected = app.tree.suggest(&c, app.key).left.unwrap().chord;
+
+        app.handle_key(KeyCode::Char('E'));
+        app.handle_key(KeyCode::Char(' '));
+        assert_eq!(app.plan.get(&[Branch::Expected]), Some(&expected));
+
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Enter);
+        for key in "Dm7".chars() {
+            app.handle_key(KeyCode::Char(key));
+        }
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.planned_tree(&c).right.unwrap().chord.name(), "Dm7");
+        app.handle_key(KeyCode::Esc);
+        assert!(!app.is_editing_plan());
+
+        // Playing a planned chord moves the plan on
+        app.message = None;
+        play(&mut app, &[62, 65, 69, 72]);
+        assert!(app.plan.is_empty());
+        assert!(app.message.is_none());
+
+        app.plan
+            .set(vec![Branch::Expected], Chord::from_name("G").unwrap());
+        play(&mut app, &[64, 67, 71]);
+        assert!(app.plan.is_empty());
+        assert!(app.message.as_ref().unwrap().contains("Off the plan"));
+    }
+
+    #[test]
+    fn test_sketch() {
+        let mut app = App::new();
+        app.midi = Some(MidiInput::new());
+        play(&mut app, &[60, 64, 67]);
+        play(&mut app, &[57, 60, 64]);
+
+        app.handle_key(KeyCode::Char('C'));
+        assert!(app.is_sketching());
+        app.handle_key(KeyCode::Char('a'));
+        app.handle_key(KeyCode::Char('+'));
+        app.handle_key(KeyCode::Char('a'));
+        assert_eq!(app.sketch.sections.len(), 1);
+        assert!(app.message.as_ref().unwrap().contains("Nothing played"));
+        app.handle_key(KeyCode::Esc);
+        assert!(!app.is_sketching());
+
+        play(&mut app, &[65, 69, 72]);
+        play(&mut app, &[67, 71, 74]);
+        app.handle_key(KeyCode::Char('C'));
+        app.handle_key(KeyCode::Char('a'));
+        app.handle_key(KeyCode::Char('K'));
+        let sections: Vec<(&str, u32, usize)> = app
+            .sketch
+            .sections
+            .iter()
+            .map(|s| (s.name.as_str(), s.repeats, s.chords.len()))
+            .collect();
+        assert_eq!(sections, [("Chorus", 1, 2), ("Verse", 2, 2)]);
+        assert_eq!(app.sketch.to_session(None).len(), 6);
+    }
+
+    #[test]
+    fn test_compare_with_preset() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('b'));
+        app.handle_key(KeyCode::Char('c'));
+        assert!(matches!(app.screen(), Some(Screen::Presets)));
+
+        app.handle_key(KeyCode::Esc);
+        app.handle_key(KeyCode::Char('P'));
+        for pad in "1564152".chars() {
+            app.handle_key(KeyCode::Char(pad));
+        }
+        app.handle_key(KeyCode::Char('b'));
+        app.handle_key(KeyCode::Char('c'));
+        let Some(Screen::Compare(diff, _)) = app.screen() else {
+            panic!("no comparison");
+        };
+        assert_eq!(diff.reference_name, Preset::all()[0].name);
+        assert_eq!(diff.count(Change::Same), 6);
+        assert_eq!(diff.count(Change::Changed), 1);
+
+        // Closing the comparison goes back to the presets
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Esc);
+        assert!(matches!(app.screen(), Some(Screen::Presets)));
+    }
+
+    #[test]
+    fn test_key_sequences() {
+        let config = Config::from_toml("[keys.bind]\nexport-midi = []\nsketch = \"g s\"").unwrap();
+        let mut app = App::new();
+        app.apply_config(&config);
+
+        app.handle_key(KeyCode::Char('X'));
+        assert!(app.message.is_none());
+        app.handle_key(KeyCode::Char('g'));
+        assert_eq!(app.capo, 0);
+        assert_eq!(app.status_segment(StatusSegment::Keys)[0].content, "g …");
+        app.handle_key(KeyCode::Char('s'));
+        assert!(app.is_sketching());
+        app.handle_key(KeyCode::Esc);
+
+        // Esc gives up on a sequence rather than quitting
+        app.handle_key(KeyCode::Char('g'));
+        app.handle_key(KeyCode::Esc);
+        assert!(!app.should_quit);
+        app.handle_key(KeyCode::Esc);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_help_pages() {
+        fn text(line: &Line) -> String {
+            line.spans.iter().map(|s| s.content.as_ref()).collect()
+        }
+
+        let config = Config::from_toml("[keys.bind]\nsketch = \"g s\"\nheatmap = []").unwrap();
+        let mut app = App::new();
+        app.apply_config(&config);
+        let all = app.help_pages(usize::MAX);
+        assert_eq!(all.len(), 1);
+        assert_eq!(text(&all[0][0]), "General");
+        let lines: Vec<String> = all[0].iter().map(text).collect();
+        assert!(lines.contains(&"  g s     Song sketch: arrange sections".to_string()));
+        assert!(!lines.iter().any(|l| l.contains("heatmap")));
+
+        let pages = app.help_pages(12);
+        assert!(pages.len() > 1);
+        assert!(pages.iter().all(|page| page.len() <= 12));
+        // No heading left at the foot of a page
+        assert!(pages
+            .iter()
+            .all(|page| page.last().unwrap().spans.len() == 2));
+
+        app.handle_key(KeyCode::Char('?'));
+        app.help_pages.set(3);
+        app.handle_key(KeyCode::Right);
+        app.handle_key(KeyCode::Right);
+        app.handle_key(KeyCode::Right);
+        assert_eq!(app.help_page, 2);
+        app.handle_key(KeyCode::Left);
+        assert_eq!(app.help_page, 1);
+        app.handle_key(KeyCode::Char('q'));
+        assert!(app.screen().is_none());
+        assert!(!app.should_quit);
+        app.handle_key(KeyCode::Char('?'));
+        assert_eq!(app.help_page, 0);
+    }
+
+    #[test]
+    fn test_velocity_calibration() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('Y'));
+        assert!(app.calibration().is_none());
+
+        app.midi = Some(MidiInput::new());
+        app.handle_key(KeyCode::Char('Y'));
+        let play = |app: &mut App, velocity: u8| {
+            let midi = app.midi.as_ref().unwrap();
+            midi.receive(&[0x90, 60, velocity]);
+            midi.receive(&[0x80, 60, 0]);
+            app.tick();
+        };
+        for velocity in [8, 10, 12, 9, 11, 10] {
+            play(&mut app, velocity);
+        }
+        assert_eq!(app.calibration().unwrap().stage(), CalibrationStage::Hard);
+        // Not done yet
+        app.handle_key(KeyCode::Enter);
+        assert!(app.calibration().is_some());
+        for velocity in [80, 85, 90, 84, 88, 86] {
+            play(&mut app, velocity);
+        }
+        app.handle_key(KeyCode::Enter);
+        assert!(app.calibration().is_none());
+        let curve = app.midi.as_ref().unwrap().settings().velocity_curve;
+        assert_eq!(curve.name(), "custom");
+        assert!(curve.apply(10) > 15);
+    }
+
+    #[test]
+    fn test_control_mapping() {
         let mut app = App::new();
+        app.midi = Some(MidiInput::new());
         assert_eq!(app.mode, Mode::Discovery);
 
-        app.handle_key(KeyCode::Tab);
+        app.handle_key(KeyCode::Char('R'));
+        app.handle_key(KeyCode::Enter);
+        app.midi.as_ref().unwrap().receive(&[0xC0, 5]);
+        app.tick();
+        let mapping = app.control_mapping().unwrap();
+        assert_eq!(mapping.learn, Learn::Off);
+        assert_eq!(
+            app.controls.get(Control::Program(5)),
+            Some(&ControlAction::Command(Command::ToggleMode))
+        );
+        // Learning doesn't run the action
+        assert_eq!(app.mode, Mode::Discovery);
+
+        app.handle_key(KeyCode::Esc);
+        assert!(app.control_mapping().is_none());
+        app.midi.as_ref().unwrap().receive(&[0xC0, 5]);
+        app.midi.as_ref().unwrap().receive(&[0xC0, 6]);
+        app.tick();
+        assert_eq!(app.mode, Mode::Jam);
+
+        app.handle_key(KeyCode::Char('R'));
+        app.handle_key(KeyCode::Char('x'));
+        assert!(app.controls.get(Control::Program(5)).is_none());
+    }
+
+    #[test]
+    fn test_scripted_time() {
+        use crate::clock::ManualClock;
+        use crate::midi::MidiScript;
+
+        let clock = ManualClock::new();
+        let mut app = App::new();
+        app.set_clock(Arc::new(clock.clone()));
+        let second = Duration::from_secs(1);
+        app.set_note_source(Box::new(
+            MidiScript::new()
+                .chord(Duration::ZERO, &[60, 64, 67], 100, second)
+                .chord(second * 2, &[57, 60, 64], 100, second),
+        ));
+        let playing = |app: &App| app.current_chord.as_ref().map(|c| c.name());
+
+        app.tick();
+        assert_eq!(playing(&app).as_deref(), Some("C"));
+        let held = app.confidence().unwrap();
+        app.tick();
+        assert_eq!(app.confidence(), Some(held));
+        clock.advance(second / 2);
+        app.tick();
+        assert!(app.confidence().unwrap() > held);
+
+        clock.advance(second * 3 / 2);
+        app.tick();
+        assert_eq!(playing(&app).as_deref(), Some("Am"));
+        let onsets: Vec<f64> = app.session.entries.iter().filter_map(|e| e.at).collect();
+        assert_eq!(onsets, [0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_demo_songs_read_back() {
+        use crate::clock::ManualClock;
+        use crate::midi::{Demo, DemoSong};
+
+        for (song, expected) in [
+            (DemoSong::Pop, ["C", "G", "Am", "F"]),
+            (DemoSong::Jazz, ["Dm7", "G7", "Cmaj7", "A7"]),
+        ] {
+            let clock = ManualClock::new();
+            let mut app = App::new();
+            app.set_clock(Arc::new(clock.clone()));
+            app.set_note_source(Box::new(Demo::new(song)));
+            let mut heard = Vec::new();
+            for _ in 0..40 {
+                app.tick();
+                if let Some(chord) = &app.current_chord {
+                    if heard.last() != Some(&chord.name()) {
+                        heard.push(chord.name());
+                    }
+                }
+                clock.advance(Duration::from_millis(250));
+            }
+            assert_eq!(heard[..4], expected, "{}", song.name());
+        }
+    }
+
+    #[test]
+    fn test_dirty() {
+        let mut app = App::new();
+        app.midi = Some(MidiInput::new());
+        assert!(app.take_dirty());
+        app.tick();
+        assert!(!app.take_dirty());
+
+        app.handle_key(KeyCode::F(12));
+        assert!(app.take_dirty());
+        assert!(!app.take_dirty());
+
+        // Held notes keep it live; once let go and settled it rests
+        let midi = app.midi.as_ref().unwrap();
+        midi.receive(&[0x90, 60, 100]);
+        app.tick();
+        assert!(app.take_dirty());
+        app.tick();
+        assert!(app.take_dirty());
+        app.midi.as_ref().unwrap().receive(&[0x80, 60, 0]);
+        app.tick();
+        assert!(app.take_dirty());
+        // Skip the chord change effects
+        app.set_animations(true);
+        app.tick();
+        assert!(!app.take_dirty());
+    }
+
+    #[test]
+    fn test_learn_command_by_key() {
+        let mut app = App::new();
+        app.midi = Some(MidiInput::new());
+        app.handle_key(KeyCode::Char('R'));
+        app.handle_key(KeyCode::Char('l'));
+        app.handle_key(KeyCode::Char('z'));
+        let play = |app: &mut App, note: u8| {
+            let midi = app.midi.as_ref().unwrap();
+            midi.receive(&[0x90, note, 100]);
+            midi.receive(&[0x80, note, 0]);
+            app.tick();
+        };
+        // The pad struck while learning is mapped, not played
+        play(&mut app, 36);
+        assert_eq!(
+            app.controls.get(Control::Note(36)),
+            Some(&ControlAction::Command(Command::ToggleLatch))
+        );
+        assert!(app.history.entries().is_empty());
+
+        app.handle_key(KeyCode::Esc);
+        let latched = app.midi.as_ref().unwrap().is_latched();
+        play(&mut app, 36);
+        assert_ne!(app.midi.as_ref().unwrap().is_latched(), latched);
+        assert!(app.midi.as_ref().unwrap().held_notes().is_empty());
+    }
+
+    #[test]
+    fn test_page_turn() {
+        let mut app = App::new();
+        app.trainer = Some(Trainer::new(
+            "I–IV",
+            vec![
+                Chord::from_name("C").unwrap(),
+                Chord::from_name("F").unwrap(),
+            ],
+        ));
+        app.handle_key(KeyCode::Char(' '));
+        assert_eq!(app.trainer.as_ref().unwrap().position(), 1);
+
+        // A double tap of the sustain pedal turns the page, a single one
+        // doesn't
+        app.midi = Some(MidiInput::new());
+        let pedal = |app: &mut App| {
+            let midi = app.midi.as_ref().unwrap();
+            midi.receive(&[0xB0, 64, 127]);
+            midi.receive(&[0xB0, 64, 0]);
+        };
+        pedal(&mut app);
+        app.tick();
+        assert_eq!(app.trainer.as_ref().unwrap().position(), 1);
+        pedal(&mut app);
+        app.tick();
+        assert!(app.trainer.as_ref().unwrap().is_complete());
+        app.run(Command::PageTurn);
+        assert_eq!(app.trainer.as_ref().unwrap().position(), 0);
+    }
+
+    #[test]
+    fn test_page_turn_through_sketch() {
+        let mut app = App::new();
+        for name in ["C", "G"] {
+            app.session.entries.push(SessionEntry {
+                chord: name.to_string(),
+                notes: Vec::new(),
+                at: None,
+            });
+        }
+        app.capture_section();
+        app.sketch.sections[0].set_repeats(2);
+        assert!(app.is_sketching());
+        for _ in 0..3 {
+            app.handle_key(KeyCode::Char(' '));
+        }
+        assert_eq!(
+            app.sketch_position,
+            Some(ChartPosition {
+                section: 0,
+                pass: 1,
+                chord: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_suggestions_precomputed_for_key() {
+        let mut app = App::new();
+        app.set_key(Some(Note::new(62)));
+        assert_eq!(app.tree.cached(), 7);
+        app.toggle_extended();
+        assert_eq!(app.tree.cached(), 7);
+        // The key's chords are ready, others are worked out when played
+        app.tree.suggest(&Chord::from_name("Em7").unwrap(), app.key);
+        assert_eq!(app.tree.cached(), 7);
+        app.tree.suggest(&Chord::from_name("Bb7").unwrap(), app.key);
+        assert_eq!(app.tree.cached(), 8);
+    }
+
+    #[test]
+    fn test_status_segments() {
+        fn text(spans: Vec<Span>) -> String {
+            spans.iter().map(|s| s.content.as_ref()).collect()
+        }
+
+        let mut app = App::new();
+        assert!(app.status_segment(StatusSegment::Latch).is_empty());
+        assert!(app.status_segment(StatusSegment::Message).is_empty());
+        assert_eq!(text(app.status_segment(StatusSegment::Help)), "[?] Help");
+
+        app.midi = Some(MidiInput::new());
+        app.handle_key(KeyCode::Char('z'));
+        assert_eq!(text(app.status_segment(StatusSegment::Latch)), "[z] Latch");
+        assert!(text(app.status_segment(StatusSegment::Message)).starts_with("Latch on"));
+        // A MIDI input not connected to a port
+        assert!(app.status_segment(StatusSegment::Port).is_empty());
+    }
+
+    #[test]
+    fn test_latency_and_clock_segments() {
+        use crate::clock::ManualClock;
+        use crate::midi::sync;
+
+        fn text(spans: Vec<Span>) -> String {
+            spans.iter().map(|s| s.content.as_ref()).collect()
+        }
+
+        let clock = ManualClock::new();
+        let mut app = App::new();
+        app.midi = Some(MidiInput::new());
+        app.set_clock(Arc::new(clock.clone()));
+        assert_eq!(
+            text(app.status_segment(StatusSegment::Latency)),
+            "Latency: —"
+        );
+        assert_eq!(
+            text(app.status_segment(StatusSegment::Clock)),
+            "Clock: internal"
+        );
+
+        let midi = app.midi.as_ref().unwrap();
+        for note in [60, 64, 67] {
+            midi.receive(&[0x90, note, 90]);
+        }
+        clock.advance(Duration::from_millis(12));
+        app.tick();
+        assert_eq!(
+            text(app.status_segment(StatusSegment::Latency)),
+            "Latency: 12ms"
+        );
+
+        // A beat of MIDI clock at 90 bpm takes the tempo over
+        for _ in 0..=sync::PPQN {
+            app.midi.as_ref().unwrap().receive(&[sync::CLOCK]);
+            clock.advance(Duration::from_secs_f32(60.0 / 90.0) / sync::PPQN);
+        }
+        app.tick();
+        assert_eq!(
+            text(app.status_segment(StatusSegment::Clock)),
+            "Clock: following"
+        );
+        assert_eq!(app.bpm.map(f32::round), Some(90.0));
+    }
+
+    #[test]
+    fn test_pads() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('5'));
+        assert!(app.current_chord.is_none());
+
+        app.handle_key(KeyCode::Char('P'));
+        assert!(app.pads);
+        app.handle_key(KeyCode::Char('1'));
+        app.handle_key(KeyCode::Char('6'));
+        assert_eq!(app.current_chord.as_ref().unwrap().name(), "Am");
+        assert_eq!(app.history.entries().len(), 2);
+        assert_eq!(app.key, Some(Note::new(60)));
+
+        // The pad lets go on the next tick, and the chord stays
+        app.tick();
+        assert!(app.last_notes.is_empty());
+        assert_eq!(app.current_chord.as_ref().unwrap().name(), "Am");
+
+        app.set_key(Some(Note::new(67)));
+        app.toggle_extended();
+        assert_eq!(app.pad_chord(4).unwrap().name(), "D7");
+        assert_eq!(app.pad_chord(7), app.pad_chord(0));
+        assert_eq!(app.pad_chord(8), None);
+    }
+
+    #[test]
+    fn test_arpeggiator_keys() {
+        use crate::midi::{ArpPattern, ArpRate};
+
+        let mut app = App::new();
+        assert!(!app.arp.is_on());
+        app.handle_key(KeyCode::Char('u'));
+        assert_eq!(app.arp.pattern, ArpPattern::Up);
+        app.handle_key(KeyCode::Char('U'));
+        assert_eq!(app.arp.rate, ArpRate::Triplet);
+        for _ in 0..4 {
+            app.handle_key(KeyCode::Char('u'));
+        }
+        assert!(!app.arp.is_on());
+    }
+
+    #[test]
+    fn test_capo_cycle() {
+        let mut app = App::new();
+        assert_eq!(app.capo, 0);
+        app.handle_key(KeyCode::Char('g'));
+        assert_eq!(app.capo, 1);
+        for _ in 0..MAX_CAPO {
+            app.handle_key(KeyCode::Char('g'));
+        }
+        assert_eq!(app.capo, 0);
+        assert_eq!(app.message.as_deref(), Some("Capo off"));
+    }
+
+    #[test]
+    fn test_dyad_completions_prefer_key() {
+        let mut app = App::new();
+        app.set_key(Some(Note::new(60)));
+        let input = MidiInput::new();
+        input.note_on(60);
+        input.note_on(67);
+        app.midi = Some(input);
+        app.tick();
+
+        let dyad = app.held_dyad().unwrap();
+        assert_eq!(dyad, vec![0, 7]);
+        let completions = app.completions(&dyad);
+        assert_eq!(completions[0].name(), "C");
+        assert!(completions.iter().any(|c| c.name() == "Cm"));
+    }
+
+    #[test]
+    fn test_similar_panel_toggle() {
+        let mut app = App::new();
+        assert!(!app.show_similar);
+        app.handle_key(KeyCode::Char('s'));
+        assert!(app.show_similar);
+    }
+
+    #[test]
+    fn test_out_of_key_hint() {
+        let mut app = App::new();
+        app.set_key(Some(Note::new(60)));
+        for name in ["Cm", "Ab", "Eb"] {
+            let chord = Chord::from_name(name).unwrap();
+            app.history.push(chord.clone());
+            app.current_chord = Some(chord);
+        }
+
+        assert!(app.is_out_of_key());
+        assert_eq!(app.key_hint().map(|s| s.name()), Some("Eb".to_string()));
+
+        app.handle_key(KeyCode::Char('K'));
+        assert_eq!(app.key().map(|k| k.pitch_class()), Some(3));
+        assert!(!app.is_out_of_key());
+        assert!(app.key_hint().is_none());
+    }
+
+    #[test]
+    fn test_modulation_analysis_follows_history() {
+        let mut session = Session::new();
+        session.set_key(Some(Note::new(60)));
+        for name in ["C", "F", "G", "C", "Eb", "Ab", "Bb", "Eb"] {
+            session.push(&Chord::from_name(name).unwrap());
+        }
+
+        let mut app = App::new();
+        app.restore(session);
+
+        let modulations = app.analysis().modulations();
+        assert_eq!(modulations.len(), 1);
+        assert!(app.history.modulation_at(modulations[0].index).is_some());
+
+        app.clear_history();
+        assert!(app.analysis().segments.is_empty());
+    }
+
+    #[test]
+    fn test_modulations_follow_faded_history() {
+        let mut app = App::new();
+        app.set_animations(false);
+        app.midi = Some(MidiInput::new());
+        app.toggle_mode();
         assert_eq!(app.mode, Mode::Jam);
+        for name in ["G", "D", "C", "F", "G", "C", "Eb", "Ab", "Bb", "Eb"] {
+            play(&mut app, &Chord::from_name(name).unwrap().notes());
+        }
+
+        // The oldest chords have faded out; the label is still on the
+        // chord the key changed at, not one further on
+        assert_eq!(app.history.entries().len(), 8);
+        let modulations = app.analysis().modulations();
+        assert_eq!(modulations.len(), 1);
+        assert_eq!(modulations[0].index, 3);
+        assert!(app.history.modulation_at(3).is_some());
+        assert!(snapshot::widget(&app.history, 80, 1).contains("G∥5 → ‖Eb‖ C"));
+    }
+
+    #[test]
+    fn test_creativity_keys() {
+        let mut app = App::new();
+        assert_eq!(app.creativity(), 0.0);
+
+        app.handle_key(KeyCode::Char('+'));
+        app.handle_key(KeyCode::Char('='));
+        assert!((app.creativity() - 0.2).abs() < 1e-6);
+
+        app.handle_key(KeyCode::Char('-'));
+        app.handle_key(KeyCode::Char('-'));
+        app.handle_key(KeyCode::Char('-'));
+        assert_eq!(app.creativity(), 0.0);
+    }
+
+    #[test]
+    fn test_duet_combined_harmony() {
+        let mut app = App::new();
+        let player = MidiInput::new();
+        let partner = MidiInput::new();
+        for note in [48, 52, 55] {
+            player.note_on(note);
+        }
+        for note in [64, 67, 71] {
+            partner.note_on(note);
+        }
+        app.midi = Some(player);
+        app.partner = Some(partner);
+        app.handle_key(KeyCode::Char('D'));
+        app.tick();
+
+        assert_eq!(
+            app.current_chord.as_ref().map(|c| c.name()),
+            Some("C".to_string())
+        );
+        assert_eq!(
+            app.partner_chord.as_ref().map(|c| c.name()),
+            Some("Em".to_string())
+        );
+        assert_eq!(
+            app.combined_chord().map(|c| c.name()),
+            Some("Cmaj7".to_string())
+        );
+    }
+
+    #[test]
+    fn test_dictation_scores_played_chord() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('d'));
+        assert!(app.message.as_deref().unwrap().contains("MIDI output"));
+
+        let target = app.dictation.as_ref().unwrap().current().cloned().unwrap();
+        let input = MidiInput::new();
+        for note in target.notes() {
+            input.note_on(note);
+        }
+        app.midi = Some(input);
+        app.tick();
+
+        let dictation = app.dictation.as_ref().unwrap();
+        assert!(dictation.is_answered());
+        assert_eq!(dictation.session().correct, 1);
+        assert!(app.message.as_deref().unwrap().starts_with('✓'));
+
+        app.handle_key(KeyCode::Char(' '));
+        assert!(!app.dictation.as_ref().unwrap().is_answered());
+
+        app.handle_key(KeyCode::Char('d'));
+        assert!(app.dictation.is_none());
+    }
+
+    #[test]
+    fn test_interval_drill_keys_and_playing() {
+        let mut app = App::new();
+        app.handle_key(KeyCode::Char('i'));
+        assert!(app.intervals().is_some());
+
+        let question = app.intervals().unwrap().current().unwrap();
+        let input = MidiInput::new();
+        for note in question.notes() {
+            input.note_on(note);
+        }
+        app.midi = Some(input);
+        app.tick();
+
+        let drill = app.intervals().unwrap();
+        assert!(drill.is_answered());
+        assert_eq!(drill.score().correct, 1);
+
+        app.handle_key(KeyCode::Char(' '));
+        assert!(!app.intervals().unwrap().is_answered());
+        app.handle_key(KeyCode::Right);
+        assert_eq!(app.interval_cursor(), 1);
+        app.handle_key(KeyCode::Enter);
+        assert!(app.intervals().unwrap().is_answered());
+
+        app.handle_key(KeyCode::Char('q'));
+        assert!(app.intervals().is_none());
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_event_log_is_source_of_truth() {
+        use crate::clock::ManualClock;
+        use crate::session::log;
+
+        let path = std::env::temp_dir().join(format!(
+            "chordvery-app-log-test-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let mut app = App::new();
+        let clock = ManualClock::new();
+        app.set_clock(Arc::new(clock.clone()));
+        app.enable_event_log(SessionWriter::open(&path).unwrap());
+
+        let input = MidiInput::new();
+        for note in [55, 59, 62] {
+            input.note_on(note);
+        }
+        app.midi = Some(input);
+        app.tick();
+        clock.advance(Duration::from_millis(1500));
+        app.toggle_mode();
+        app.shutdown().unwrap();
+
+        let events = log::read(&path).unwrap();
+        let mode = events
+            .iter()
+            .find(|e| matches!(e.event, Event::Mode { .. }));
+        assert_eq!(mode.unwrap().t, 1500);
+        assert!(events.iter().any(|e| e.event == Event::NoteOn { note: 59 }));
+        assert!(events
+            .iter()
+            .any(|e| matches!(&e.event, Event::Mode { mode } if mode == "Jam")));
+        let rebuilt = Session::from_events(&events);
+        assert_eq!(rebuilt.voiced_chords(), app.session.voiced_chords());
+        assert_eq!(rebuilt.key, app.session.key);
+        assert!(rebuilt.onsets().is_some() && app.session.onsets().is_some());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bookmark_with_note() {
+        use crate::session::log;
+
+        let path = std::env::temp_dir().join(format!(
+            "chordvery-app-bookmark-test-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let mut app = jamming();
+        app.enable_event_log(SessionWriter::open(&path).unwrap());
+        app.handle_key(KeyCode::Char('*'));
+        // Keys go to the note rather than their commands while it's typed
+        for c in "nice x".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Backspace);
+        assert!(app.message.as_deref().unwrap().contains("nice ▏"));
+        app.handle_key(KeyCode::Enter);
+        assert!(app.screen().is_none());
+
+        app.handle_key(KeyCode::Char('*'));
+        app.handle_key(KeyCode::Char('a'));
+        app.handle_key(KeyCode::Esc);
+        app.flush_event_log().unwrap();
+
+        let notes: Vec<Option<String>> = log::read(&path)
+            .unwrap()
+            .into_iter()
+            .filter_map(|e| match e.event {
+                Event::Bookmark { note } => Some(note),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(notes, [Some("nice".to_string()), None]);
+        assert!(!app.adaptive_enabled);
+
+        std::fs::remove_file(&path).unwrap();
     }
 }
@@ -0,0 +1,215 @@
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
+
+use crate::guitar::{Fingering, Tuning};
+use crate::ui::theme::Theme;
+
+/// Frets drawn to the right of the nut
+const FRETS_SHOWN: u8 = 5;
+
+/// A chord grip drawn as a fretboard, highest string on top as in a tab.
+pub struct Fretboard<'a> {
+    tuning: &'a Tuning,
+    fingering: Option<&'a Fingering>,
+    /// Pitch class shown as the root
+    root: Option<u8>,
+    capo: u8,
+    left_handed: bool,
+}
+
+impl<'a> Fretboard<'a> {
+    pub fn new(tuning: &'a Tuning) -> Self {
+        Self {
+            tuning,
+            fingering: None,
+            root: None,
+            capo: 0,
+            left_handed: false,
+        }
+    }
+
+    pub fn fingering(mut self, fingering: &'a Fingering) -> Self {
+        self.fingering = Some(fingering);
+        self
+    }
+
+    pub fn root(mut self, pitch_class: u8) -> Self {
+        self.root = Some(pitch_class);
+        self
+    }
+
+    pub fn capo(mut self, capo: u8) -> Self {
+        self.capo = capo;
+        self
+    }
+
+    /// Mirrors the neck so the nut is on the right.
+    pub fn left_handed(mut self, left_handed: bool) -> Self {
+        self.left_handed = left_handed;
+        self
+    }
+
+    /// First fret drawn: the nut unless the grip sits higher up the neck.
+    fn first_fret(&self) -> u8 {
+        match self.fingering {
+            Some(f) if f.highest_fret() > FRETS_SHOWN => f.lowest_fret().unwrap_or(1),
+            _ => 1,
+        }
+    }
+
+    /// One string's row as glyphs from the headstock end.
+    fn row(&self, string: usize, first: u8) -> Vec<(String, Style)> {
+        let open = self.tuning.strings[string];
+        let fret = self
+            .fingering
+            .and_then(|f| f.frets.get(string).copied().flatten());
+        let muted = self
+            .fingering
+            .is_some_and(|f| f.frets.get(string) == Some(&None));
+
+        let mut glyphs = vec![(format!("{:>2} ", open.name()), Theme::text_dim())];
+        glyphs.push(match fret {
+            _ if muted => ("x".to_string(), Theme::text_dim()),
+            Some(0) => ("o".to_string(), self.note_style(open.pitch_class())),
+            _ => (" ".to_string(), Theme::text()),
+        });
+        let nut = if first == 1 { "‖" } else { "│" };
+        glyphs.push((nut.to_string(), Theme::text()));
+
+        for at in first..first + FRETS_SHOWN {
+            glyphs.push(("─".to_string(), Theme::border()));
+            if fret == Some(at) {
+                let pc = (open.pitch_class() + self.capo + at) % 12;
+                glyphs.push(("●".to_string(), self.note_style(pc)));
+            } else {
+                glyphs.push(("─".to_string(), Theme::border()));
+            }
+            glyphs.push(("─".to_string(), Theme::border()));
+            glyphs.push(("┼".to_string(), Theme::border()));
+        }
+        glyphs
+    }
+
+    fn note_style(&self, pitch_class: u8) -> Style {
+        if self.root == Some(pitch_class) {
+            Theme::fret_root()
+        } else {
+            Theme::fret_note()
+        }
+    }
+}
+
+impl Widget for Fretboard<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let strings = self.tuning.string_count();
+        if area.height < strings as u16 + 1 {
+            return;
+        }
+        let first = self.first_fret();
+
+        // Fret numbers above the neck
+        let mut header: Vec<(String, Style)> = vec![("      ".to_string(), Theme::text_dim())];
+        for at in first..first + FRETS_SHOWN {
+            header.push((format!(" {:<3}", at), Theme::text_dim()));
+        }
+        if self.left_handed {
+            header.reverse();
+        }
+        let mut x = area.x;
+        for (text, style) in header {
+            buf.set_stringn(
+                x,
+                area.y,
+                &text,
+                (area.right().saturating_sub(x)) as usize,
+                style,
+            );
+            x += text.chars().count() as u16;
+        }
+
+        for (line, string) in (0..strings).rev().enumerate() {
+            let mut glyphs = self.row(string, first);
+            if self.left_handed {
+                glyphs.reverse();
+            }
+            let y = area.y + 1 + line as u16;
+            let mut x = area.x;
+            for (text, style) in glyphs {
+                if x >= area.right() {
+                    break;
+                }
+                buf.set_stringn(x, y, &text, (area.right() - x) as usize, style);
+                x += text.chars().count() as u16;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guitar::fingerings;
+    use crate::theory::Chord;
+    use crate::ui::snapshot;
+
+    fn render(board: Fretboard, width: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, 7);
+        let mut buf = Buffer::empty(area);
+        board.render(area, &mut buf);
+        (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol().to_string())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_open_c() {
+        let tuning = Tuning::standard();
+        let c = fingerings(&Chord::from_name("C").unwrap(), &tuning, 0);
+        let rows = render(Fretboard::new(&tuning).fingering(&c[0]), 30);
+
+        // High E on top, low E muted at the bottom
+        assert!(rows[1].starts_with(" E o‖"));
+        assert!(rows[2].starts_with(" B  ‖─●─┼"));
+        assert!(rows[6].starts_with(" E x‖"));
+        assert!(rows[5].contains("───┼───┼─●─┼"));
+    }
+
+    #[test]
+    fn test_left_handed_mirrors() {
+        let tuning = Tuning::standard();
+        let c = fingerings(&Chord::from_name("C").unwrap(), &tuning, 0);
+        let rows = render(
+            Fretboard::new(&tuning).fingering(&c[0]).left_handed(true),
+            25,
+        );
+        assert!(rows[1].ends_with("‖o E "));
+        assert!(rows[2].ends_with("┼─●─‖  B "));
+    }
+
+    #[test]
+    fn test_high_grip_starts_at_its_fret() {
+        let tuning = Tuning::standard();
+        let grip = Fingering {
+            frets: vec![Some(8), Some(10), Some(10), Some(9), Some(8), Some(8)],
+            barre: Some(8),
+            label: "E shape, fret 8".to_string(),
+        };
+        let rows = render(Fretboard::new(&tuning).fingering(&grip), 30);
+        assert!(rows[0].starts_with("       8"));
+        assert!(rows[1].starts_with(" E  │─●─┼"));
+        assert!(rows[4].starts_with(" D  │───┼───┼─●─┼"));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let tuning = Tuning::standard();
+        let f = fingerings(&Chord::from_name("F").unwrap(), &tuning, 0);
+        insta::assert_snapshot!(
+            "fretboard_f",
+            snapshot::widget(Fretboard::new(&tuning).fingering(&f[0]).root(5), 30, 7)
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::{Line, Span},
+    widgets::Widget,
+};
+
+use crate::theory::{Note, NoteNames, Spelling};
+use crate::ui::theme::Theme;
+
+/// Root pitch classes around the circle of fifths, from C
+pub const FIFTHS: [u8; 12] = [0, 7, 2, 9, 4, 11, 6, 1, 8, 3, 10, 5];
+const SHADES: [&str; 4] = ["░░", "▒▒", "▓▓", "██"];
+const LABEL_WIDTH: usize = 6;
+
+/// Roots never played in any quality, in circle-of-fifths order.
+pub fn unplayed_roots(counts: &BTreeMap<String, [u32; 12]>) -> Vec<u8> {
+    FIFTHS
+        .into_iter()
+        .filter(|&pc| counts.values().all(|row| row[pc as usize] == 0))
+        .collect()
+}
+
+/// How often each chord was played: a row per quality, most played first,
+/// with roots across in circle-of-fifths order.
+pub struct ChordHeatmap<'a> {
+    counts: &'a BTreeMap<String, [u32; 12]>,
+    note_names: NoteNames,
+}
+
+impl<'a> ChordHeatmap<'a> {
+    pub fn new(counts: &'a BTreeMap<String, [u32; 12]>) -> Self {
+        Self {
+            counts,
+            note_names: NoteNames::default(),
+        }
+    }
+
+    pub fn note_names(mut self, note_names: NoteNames) -> Self {
+        self.note_names = note_names;
+        self
+    }
+
+    /// Root labels, sharps up to F# and flats on the way back round.
+    fn label(&self, position: usize) -> &'static str {
+        let spelling = if position <= 6 {
+            Spelling::Sharp
+        } else {
+            Spelling::Flat
+        };
+        Note::new(FIFTHS[position]).name_in(spelling, self.note_names)
+    }
+
+    fn rows(&self) -> Vec<(&'a str, &'a [u32; 12])> {
+        let mut rows: Vec<(&str, &[u32; 12])> = self
+            .counts
+            .iter()
+            .map(|(q, row)| (q.as_str(), row))
+            .collect();
+        rows.sort_by_key(|(_, row)| std::cmp::Reverse(row.iter().sum::<u32>()));
+        rows
+    }
+}
+
+impl Widget for ChordHeatmap<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 2 || (area.width as usize) < LABEL_WIDTH + 12 * 3 {
+            return;
+        }
+
+        let mut header = vec![Span::raw(" ".repeat(LABEL_WIDTH))];
+        header.extend(
+            (0..12).map(|i| Span::styled(format!("{:<3}", self.label(i)), Theme::text_dim())),
+        );
+        buf.set_line(area.x, area.y, &Line::from(header), area.width);
+
+        let max = self
+            .counts
+            .values()
+            .flat_map(|row| row.iter().copied())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        for (y, (quality, row)) in (area.y + 1..area.bottom()).zip(self.rows()) {
+            let mut spans = vec![Span::styled(
+                format!("{:<width$}", quality, width = LABEL_WIDTH),
+                Theme::text(),
+            )];
+            for &pc in &FIFTHS {
+                let count = row[pc as usize];
+                spans.push(match count {
+                    0 => Span::styled("·  ", Theme::text_dim()),
+                    _ => {
+                        let level = count as f32 / max as f32;
+                        let shade = ((level * 4.0).ceil() as usize).clamp(1, 4) - 1;
+                        Span::styled(format!("{} ", SHADES[shade]), Theme::heat(level))
+                    }
+                });
+            }
+            buf.set_line(area.x, y, &Line::from(spans), area.width);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::snapshot;
+
+    fn counts() -> BTreeMap<String, [u32; 12]> {
+        let mut major = [0; 12];
+        major[0] = 8;
+        major[7] = 2;
+        let mut minor7 = [0; 12];
+        minor7[9] = 20;
+        [("maj".to_string(), major), ("m7".to_string(), minor7)]
+            .into_iter()
+            .collect()
+    }
+
+    fn row(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width)
+            .map(|x| buf[(x, y)].symbol())
+            .collect::<String>()
+    }
+
+    #[test]
+    fn test_render() {
+        let counts = counts();
+        let area = Rect::new(0, 0, 44, 3);
+        let mut buf = Buffer::empty(area);
+        ChordHeatmap::new(&counts).render(area, &mut buf);
+
+        assert_eq!(
+            row(&buf, 0).trim_end(),
+            "      C  G  D  A  E  B  F# Db Ab Eb Bb F"
+        );
+        // Most played quality first
+        assert!(row(&buf, 1).starts_with("m7    ·  ·  ·  ██ "));
+        assert!(row(&buf, 2).starts_with("maj   ▒▒ ░░ ·  "));
+    }
+
+    #[test]
+    fn test_unplayed_roots() {
+        let unplayed = unplayed_roots(&counts());
+        assert_eq!(unplayed.len(), 9);
+        assert_eq!(unplayed[0], 2);
+        assert!(!unplayed.contains(&9));
+        assert_eq!(unplayed_roots(&BTreeMap::new()), FIFTHS.to_vec());
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let counts = counts();
+        insta::assert_snapshot!(
+            "heatmap",
+            snapshot::widget(ChordHeatmap::new(&counts), 44, 3)
+        );
+    }
+}
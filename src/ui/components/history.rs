@@ -1,23 +1,46 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::Widget,
 };
 
-use crate::theory::Chord;
-use crate::ui::theme::Theme;
+use crate::theory::voice_leading::{self, Suspension};
+use crate::theory::{
+    Chord, Modulation, NamingStyle, NoteNames, Parallel, Scale, Spelling, Transposition,
+};
+use crate::ui::theme::{QualityColors, Theme};
 
 #[derive(Clone)]
 pub struct ChordEntry {
     pub chord: Chord,
+    pub notes: Vec<u8>,
     pub age: u8,
+    /// The chord's name as shown, kept so drawing doesn't rebuild it
+    pub name: String,
+    /// The name in the key it's sung in, empty unless transposing
+    transposed: String,
+    /// Parallel motion into this chord from the one before, as shown
+    parallels: String,
+    /// A suspension resolved into this chord from the one before
+    suspension: Option<Suspension>,
 }
 
 pub struct ChordHistory {
     entries: Vec<ChordEntry>,
     max_entries: usize,
     fade: bool,
+    naming: NamingStyle,
+    spelling: Spelling,
+    note_names: NoteNames,
+    transposition: Option<Transposition>,
+    selected: Option<usize>,
+    scale: Option<Scale>,
+    modulations: Vec<Modulation>,
+    modulation_labels: Vec<String>,
+    slide: u16,
+    quality_colors: Option<QualityColors>,
 }
 
 impl Default for ChordHistory {
@@ -32,10 +55,25 @@ impl ChordHistory {
             entries: Vec::new(),
             max_entries: max,
             fade: false,
+            naming: NamingStyle::default(),
+            spelling: Spelling::default(),
+            note_names: NoteNames::default(),
+            transposition: None,
+            selected: None,
+            scale: None,
+            modulations: Vec::new(),
+            modulation_labels: Vec::new(),
+            slide: 0,
+            quality_colors: None,
         }
     }
 
     pub fn push(&mut self, chord: Chord) {
+        let notes = chord.notes();
+        self.push_voiced(chord, notes);
+    }
+
+    pub fn push_voiced(&mut self, chord: Chord, mut notes: Vec<u8>) {
         if let Some(last) = self.entries.last() {
             if last.chord.name() == chord.name() {
                 return;
@@ -46,30 +84,282 @@ impl ChordHistory {
             entry.age = entry.age.saturating_add(1);
         }
 
-        self.entries.push(ChordEntry { chord, age: 0 });
+        notes.sort_unstable();
+        let parallels = match self.entries.last() {
+            Some(last) => {
+                let mut kinds: Vec<&str> = voice_leading::parallels(&last.notes, &notes)
+                    .iter()
+                    .map(|p| p.kind.symbol())
+                    .collect();
+                kinds.dedup();
+                if kinds.is_empty() {
+                    String::new()
+                } else {
+                    format!("∥{}", kinds.join(""))
+                }
+            }
+            None => String::new(),
+        };
+        let suspension = self
+            .entries
+            .last()
+            .and_then(|last| voice_leading::suspension(&last.chord, &last.notes, &chord, &notes));
+        let name = chord.name_in(self.naming, self.spelling, self.note_names);
+        let transposed = self.transposed_name(&chord);
+        self.entries.push(ChordEntry {
+            chord,
+            notes,
+            age: 0,
+            name,
+            transposed,
+            parallels,
+            suspension,
+        });
 
         if self.entries.len() > self.max_entries {
             self.entries.remove(0);
+            self.selected = self.selected.and_then(|i| i.checked_sub(1));
         }
     }
 
+    /// Keeps up to `max` entries, dropping the oldest beyond it.
+    pub fn set_max_entries(&mut self, max: usize) {
+        self.max_entries = max.max(1);
+        let over = self.entries.len().saturating_sub(self.max_entries);
+        self.entries.drain(..over);
+        self.selected = self.selected.and_then(|i| i.checked_sub(over));
+    }
+
     pub fn set_fade(&mut self, fade: bool) {
         self.fade = fade;
     }
 
-    pub fn tick(&mut self) {
-        if self.fade {
-            self.entries.retain(|e| e.age < 8);
+    pub fn set_naming(&mut self, style: NamingStyle) {
+        self.naming = style;
+        self.relabel();
+    }
+
+    pub fn set_note_names(&mut self, names: NoteNames) {
+        self.note_names = names;
+        self.relabel();
+    }
+
+    pub fn set_spelling(&mut self, spelling: Spelling) {
+        self.spelling = spelling;
+        self.relabel();
+    }
+
+    /// Shows each chord's name in the key it's sung in beside it.
+    pub fn set_transposition(&mut self, transposition: Option<Transposition>) {
+        self.transposition = transposition;
+        self.relabel();
+    }
+
+    fn transposed_name(&self, chord: &Chord) -> String {
+        self.transposition
+            .map(|t| {
+                let name = t
+                    .chord(chord)
+                    .name_in(self.naming, self.spelling, self.note_names);
+                format!(" ({})", name)
+            })
+            .unwrap_or_default()
+    }
+
+    fn relabel(&mut self) {
+        let labels: Vec<(String, String)> = self
+            .entries
+            .iter()
+            .map(|e| {
+                (
+                    e.chord.name_in(self.naming, self.spelling, self.note_names),
+                    self.transposed_name(&e.chord),
+                )
+            })
+            .collect();
+        for (entry, (name, transposed)) in self.entries.iter_mut().zip(labels) {
+            entry.name = name;
+            entry.transposed = transposed;
+        }
+    }
+
+    /// Colours chord names by quality family, or by age and key if `None`.
+    pub fn set_quality_colors(&mut self, colors: Option<QualityColors>) {
+        self.quality_colors = colors;
+    }
+
+    pub fn set_scale(&mut self, scale: Option<Scale>) {
+        self.scale = scale;
+    }
+
+    pub fn set_modulations(&mut self, modulations: Vec<Modulation>) {
+        self.modulation_labels = modulations
+            .iter()
+            .map(|m| format!("‖{}‖ ", m.to.name()))
+            .collect();
+        self.modulations = modulations;
+    }
+
+    /// Pushes the newest entry this many columns right of its place.
+    pub fn set_slide(&mut self, columns: u16) {
+        self.slide = columns;
+    }
+
+    pub fn modulation_at(&self, index: usize) -> Option<&Modulation> {
+        self.modulations.iter().find(|m| m.index == index)
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn is_out_of_key(&self, entry: &ChordEntry) -> bool {
+        self.scale.is_some_and(|s| !s.contains_chord(&entry.chord))
+    }
+
+    pub fn suspension_at(&self, index: usize) -> Option<Suspension> {
+        self.entries.get(index)?.suspension
+    }
+
+    pub fn parallels_at(&self, index: usize) -> Vec<Parallel> {
+        match (index.checked_sub(1), self.entries.get(index)) {
+            (Some(prev), Some(entry)) => {
+                voice_leading::parallels(&self.entries[prev].notes, &entry.notes)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Drops entries faded out, returning how many. Modulations are left
+    /// for the owner to work out again over what's left.
+    pub fn tick(&mut self) -> usize {
+        if !self.fade {
+            return 0;
         }
+        let before = self.entries.len();
+        self.entries.retain(|e| e.age < 8);
+        let removed = before - self.entries.len();
+        self.selected = self.selected.and_then(|i| i.checked_sub(removed));
+        removed
     }
 
     pub fn clear(&mut self) {
         self.entries.clear();
+        self.modulations.clear();
+        self.modulation_labels.clear();
+        self.selected = None;
+    }
+
+    pub fn selected(&self) -> Option<&ChordEntry> {
+        self.selected.and_then(|i| self.entries.get(i))
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.entries.len() {
+            self.selected = Some(index);
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if self.entries.is_empty() {
+            return;
+        }
+        self.selected = Some(match self.selected {
+            Some(i) => i.saturating_sub(1),
+            None => self.entries.len() - 1,
+        });
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = match self.selected {
+            Some(i) if i + 1 < self.entries.len() => Some(i + 1),
+            _ => None,
+        };
     }
 
     pub fn entries(&self) -> &[ChordEntry] {
         &self.entries
     }
+
+    /// Hands `write` what's drawn for the entry at `index`, in order: a
+    /// modulation, the name, the marks after it and the arrow on to the
+    /// next.
+    fn pieces(&self, index: usize, mut write: impl FnMut(&str, Style)) {
+        let entry = &self.entries[index];
+        if let Some(at) = self.modulations.iter().position(|m| m.index == index) {
+            write(&self.modulation_labels[at], Theme::modulation());
+        }
+
+        let mut style = if self.fade {
+            Theme::chord_history(entry.age)
+        } else {
+            Theme::chord_name()
+        };
+        if let Some(colors) = &self.quality_colors {
+            style = colors.style(&entry.chord, style);
+            if self.fade && entry.age >= 2 {
+                style = style.add_modifier(Modifier::DIM);
+            }
+        }
+        let out_of_key = self.is_out_of_key(entry);
+        if out_of_key {
+            style = style.patch(Theme::out_of_key());
+        }
+        if self.selected == Some(index) {
+            style = style.patch(Theme::selected());
+        }
+        write(&entry.name, style);
+        if out_of_key {
+            write("*", style);
+        }
+        write(&entry.transposed, Theme::transposed());
+
+        if index > 0 {
+            write(&entry.parallels, Theme::parallel());
+        }
+        if entry.suspension.is_some() {
+            write(" sus→res", Theme::suspension());
+        }
+        if index + 1 < self.entries.len() {
+            write(" → ", Theme::text_dim());
+        }
+    }
+
+    fn width_of(&self, index: usize) -> usize {
+        let slide = if index + 1 == self.entries.len() {
+            self.slide as usize
+        } else {
+            0
+        };
+        let mut width = slide;
+        self.pieces(index, |text, _| width += Span::raw(text).width());
+        width
+    }
+
+    /// The first entry of the run ending at `anchor` that fits in `width`.
+    fn fits_back_from(&self, anchor: usize, width: u16) -> usize {
+        let mut used = self.width_of(anchor);
+        let mut first = anchor;
+        while first > 0 {
+            used += self.width_of(first - 1);
+            if used > width as usize {
+                break;
+            }
+            first -= 1;
+        }
+        first
+    }
+
+    /// The first entry drawn across `width` columns. Only the entries on
+    /// screen are looked at, however long the history: the newest that fit,
+    /// or with the review cursor further back, a run ending at it.
+    fn first_shown(&self, width: u16) -> usize {
+        let newest = self.fits_back_from(self.entries.len() - 1, width);
+        match self.selected {
+            Some(selected) if selected < newest => self.fits_back_from(selected, width),
+            _ => newest,
+        }
+    }
 }
 
 impl Widget for &ChordHistory {
@@ -84,25 +374,46 @@ impl Widget for &ChordHistory {
             return;
         }
 
-        let mut spans: Vec<Span> = Vec::new();
-
-        for (i, entry) in self.entries.iter().enumerate() {
-            let style = if self.fade {
-                Theme::chord_history(entry.age)
-            } else {
-                Theme::chord_name()
-            };
-
-            spans.push(Span::styled(entry.chord.name(), style));
-
-            if i < self.entries.len() - 1 {
-                spans.push(Span::styled(" → ", Theme::text_dim()));
+        let mut pen = Pen {
+            buf,
+            x: area.x + 1,
+            y: area.y + area.height / 2,
+            remaining: area.width.saturating_sub(2),
+        };
+        let last = self.entries.len() - 1;
+        for i in self.first_shown(pen.remaining)..=last {
+            if pen.remaining == 0 {
+                break;
+            }
+            if i == last {
+                for _ in 0..self.slide {
+                    pen.write(" ", Style::default());
+                }
             }
+            self.pieces(i, |text, style| pen.write(text, style));
         }
+    }
+}
+
+/// Writes along a row as `Buffer::set_line` would, without gathering the
+/// spans into a line first.
+struct Pen<'b> {
+    buf: &'b mut Buffer,
+    x: u16,
+    y: u16,
+    remaining: u16,
+}
 
-        let line = Line::from(spans);
-        let y = area.y + area.height / 2;
-        buf.set_line(area.x + 1, y, &line, area.width.saturating_sub(2));
+impl Pen<'_> {
+    fn write(&mut self, text: &str, style: Style) {
+        if self.remaining == 0 {
+            return;
+        }
+        let (x, _) = self
+            .buf
+            .set_stringn(self.x, self.y, text, self.remaining as usize, style);
+        self.remaining = self.remaining.saturating_sub(x.saturating_sub(self.x));
+        self.x = x;
     }
 }
 
@@ -110,6 +421,7 @@ impl Widget for &ChordHistory {
 mod tests {
     use super::*;
     use crate::theory::{Note, Quality};
+    use crate::ui::snapshot;
 
     #[test]
     fn test_push_and_age() {
@@ -154,6 +466,124 @@ mod tests {
         assert_eq!(history.entries.len(), 1);
     }
 
+    #[test]
+    fn test_push_voiced_keeps_notes() {
+        let mut history = ChordHistory::new(10);
+        history.push_voiced(Chord::new(Note::new(60), Quality::Major), vec![67, 52, 60]);
+
+        assert_eq!(history.entries[0].notes, vec![52, 60, 67]);
+    }
+
+    #[test]
+    fn test_slide_offsets_newest_entry() {
+        let mut history = ChordHistory::new(10);
+        history.push(Chord::new(Note::new(60), Quality::Major));
+        history.push(Chord::new(Note::new(67), Quality::Major));
+        let area = Rect::new(0, 0, 30, 1);
+
+        let position = |history: &ChordHistory| {
+            let mut buf = Buffer::empty(area);
+            history.render(area, &mut buf);
+            (0..30).position(|x| buf[(x, 0)].symbol() == "G")
+        };
+        let settled = position(&history).unwrap();
+        history.set_slide(5);
+        assert_eq!(position(&history), Some(settled + 5));
+    }
+
+    #[test]
+    fn test_suspension_resolved() {
+        let mut history = ChordHistory::new(10);
+        history.push_voiced(Chord::from_name("Gsus4").unwrap(), vec![55, 60, 62]);
+        history.push_voiced(Chord::from_name("G").unwrap(), vec![55, 59, 62]);
+        history.push_voiced(Chord::from_name("C").unwrap(), vec![48, 55, 64]);
+
+        assert!(history.suspension_at(0).is_none());
+        assert_eq!(
+            history.suspension_at(1).map(|s| (s.from, s.to)),
+            Some((60, 59))
+        );
+        assert!(history.suspension_at(2).is_none());
+
+        assert!(snapshot::widget(&history, 40, 1).contains("Gsus4 → G sus→res → C"));
+    }
+
+    #[test]
+    fn test_quality_colors() {
+        let mut history = ChordHistory::new(10);
+        history.push(Chord::from_name("Am").unwrap());
+        history.push(Chord::from_name("G7").unwrap());
+        let area = Rect::new(0, 0, 20, 1);
+        let fg = |history: &ChordHistory, x: u16| {
+            let mut buf = Buffer::empty(area);
+            history.render(area, &mut buf);
+            buf[(x, 0)].fg
+        };
+        assert_eq!(fg(&history, 1), fg(&history, 6));
+
+        let colors = QualityColors::default();
+        history.set_quality_colors(Some(colors));
+        assert_eq!(fg(&history, 1), colors.minor);
+        assert_eq!(fg(&history, 6), colors.dominant);
+    }
+
+    #[test]
+    fn test_long_history_shows_a_window() {
+        let mut history = ChordHistory::new(16);
+        history.set_max_entries(2000);
+        let names = ["C", "Am", "F", "G7"];
+        for i in 0..1000 {
+            history.push(Chord::from_name(names[i % 4]).unwrap());
+        }
+        assert_eq!(history.entries().len(), 1000);
+
+        // Only the newest that fit are drawn, up to the last
+        let shown = snapshot::widget(&history, 40, 1);
+        assert!(shown.contains("→ G7  \""));
+        assert!(history.first_shown(38) > 990);
+
+        // Scrolled back to the review cursor
+        for _ in 0..500 {
+            history.select_prev();
+        }
+        assert_eq!(history.selected_index(), Some(500));
+        let first = history.first_shown(38);
+        assert!(first < 500 && 500 - first < 10);
+        let mut first_piece = None;
+        history.pieces(500, |text, _| {
+            first_piece.get_or_insert(text.to_string());
+        });
+        assert_eq!(first_piece.as_deref(), Some("C"));
+
+        history.set_max_entries(100);
+        assert_eq!(history.entries().len(), 100);
+        assert!(history.selected().is_none());
+    }
+
+    #[test]
+    fn test_selection() {
+        let mut history = ChordHistory::new(3);
+        history.select_prev();
+        assert!(history.selected().is_none());
+
+        history.push(Chord::new(Note::new(60), Quality::Major));
+        history.push(Chord::new(Note::new(62), Quality::Minor));
+        history.push(Chord::new(Note::new(64), Quality::Minor));
+
+        history.select_prev();
+        assert_eq!(history.selected().unwrap().chord.name(), "Em");
+        history.select_prev();
+        assert_eq!(history.selected().unwrap().chord.name(), "Dm");
+
+        history.push(Chord::new(Note::new(65), Quality::Major));
+        assert_eq!(history.selected().unwrap().chord.name(), "Dm");
+
+        history.select_next();
+        history.select_next();
+        history.select_next();
+        assert!(history.selected().is_none());
+    }
+
     #[test]
     fn test_fade_tick() {
         let mut history = ChordHistory::new(10);
@@ -168,4 +598,77 @@ mod tests {
 
         assert!(history.entries.iter().all(|e| e.age < 8));
     }
+
+    #[test]
+    fn test_out_of_key() {
+        let mut history = ChordHistory::new(10);
+        history.push(Chord::new(Note::new(60), Quality::Major));
+        history.push(Chord::new(Note::new(63), Quality::Major));
+
+        assert!(!history.is_out_of_key(&history.entries[1]));
+
+        history.set_scale(Some(Scale::major(Note::new(60))));
+        assert!(!history.is_out_of_key(&history.entries[0]));
+        assert!(history.is_out_of_key(&history.entries[1]));
+    }
+
+    #[test]
+    fn test_parallels_between_voicings() {
+        let mut history = ChordHistory::new(10);
+        history.push_voiced(Chord::new(Note::new(60), Quality::Major), vec![48, 55, 64]);
+        history.push_voiced(Chord::new(Note::new(62), Quality::Minor), vec![50, 57, 65]);
+        history.push_voiced(Chord::new(Note::new(67), Quality::Major), vec![47, 55, 62]);
+
+        assert!(history.parallels_at(0).is_empty());
+        assert_eq!(history.parallels_at(1).len(), 1);
+        assert!(history.parallels_at(2).is_empty());
+    }
+
+    #[test]
+    fn test_names_follow_settings() {
+        let mut history = ChordHistory::new(10);
+        history.push(Chord::new(Note::new(61), Quality::Minor));
+        assert_eq!(history.entries[0].name, "C#m");
+
+        history.set_spelling(Spelling::Flat);
+        assert_eq!(history.entries[0].name, "Dbm");
+        history.push(Chord::new(Note::new(63), Quality::Major));
+        assert_eq!(history.entries[1].name, "Eb");
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let mut history = ChordHistory::new(10);
+        history.push_voiced(Chord::new(Note::new(60), Quality::Major), vec![48, 55, 64]);
+        history.push_voiced(Chord::new(Note::new(62), Quality::Minor), vec![50, 57, 65]);
+        history.push_voiced(Chord::new(Note::new(63), Quality::Major), vec![51, 58, 67]);
+        history.push_voiced(
+            Chord::new(Note::new(67), Quality::Dominant7),
+            vec![43, 59, 65],
+        );
+        insta::assert_snapshot!("history_plain", snapshot::widget(&history, 50, 3));
+
+        history.set_scale(Some(Scale::major(Note::new(60))));
+        history.set_modulations(vec![Modulation {
+            index: 2,
+            from: Scale::major(Note::new(60)),
+            to: Scale::major(Note::new(63)),
+        }]);
+        history.select_prev();
+        insta::assert_snapshot!("history_marked", snapshot::widget(&history, 50, 3));
+    }
+
+    #[test]
+    fn test_transposed_names() {
+        let mut history = ChordHistory::new(10);
+        history.push(Chord::new(Note::new(67), Quality::Major));
+        history.set_transposition(Some(Transposition::new(Note::new(67), Note::new(70))));
+        history.push(Chord::new(Note::new(62), Quality::Dominant7));
+        history.set_spelling(Spelling::Flat);
+        let shown = snapshot::widget(&history, 40, 1);
+        assert!(shown.contains("G (Bb) → D7 (F7)"), "{}", shown);
+
+        history.set_transposition(None);
+        assert!(snapshot::widget(&history, 40, 1).contains("G → D7"));
+    }
 }
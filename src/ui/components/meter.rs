@@ -0,0 +1,147 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Widget,
+};
+
+use crate::ui::theme::Theme;
+
+pub struct Meter<'a> {
+    label: &'a str,
+    value: f32,
+    style: Style,
+}
+
+impl<'a> Meter<'a> {
+    pub fn new(label: &'a str) -> Self {
+        Self {
+            label,
+            value: 0.0,
+            style: Theme::text(),
+        }
+    }
+
+    pub fn value(mut self, value: f32) -> Self {
+        self.value = value.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl Widget for Meter<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let label_width = self.label.chars().count() as u16 + 1;
+        let percent = format!(" {:>3}%", (self.value * 100.0).round() as u8);
+        let bar_width = area
+            .width
+            .saturating_sub(label_width + percent.len() as u16) as usize;
+
+        if area.height < 1 || bar_width < 4 {
+            return;
+        }
+
+        let filled = (self.value * bar_width as f32).round() as usize;
+
+        let line = Line::from(vec![
+            Span::styled(format!("{} ", self.label), Theme::status_bar()),
+            Span::styled("█".repeat(filled), self.style),
+            Span::styled("░".repeat(bar_width - filled), Theme::text_dim()),
+            Span::styled(percent, Theme::text()),
+        ]);
+        buf.set_line(area.x, area.y, &line, area.width);
+    }
+}
+
+/// A compact inline meter, e.g. `▰▰▰▱` for 0.75 over four cells.
+pub fn pips(value: f32, width: usize) -> String {
+    let filled = (value.clamp(0.0, 1.0) * width as f32).round() as usize;
+    format!("{}{}", "▰".repeat(filled), "▱".repeat(width - filled))
+}
+
+/// A meter filling out from the middle, left for -1 and right for 1, as
+/// for pitch bend: `───│━━──` for 0.5 over eight cells.
+pub fn centred_pips(value: f32, width: usize) -> String {
+    let half = width / 2;
+    let filled = (value.abs().min(1.0) * half as f32).round() as usize;
+    let (left, right) = if value < 0.0 {
+        (filled, 0)
+    } else {
+        (0, filled)
+    };
+    format!(
+        "{}{}│{}{}",
+        "─".repeat(half - left),
+        "━".repeat(left),
+        "━".repeat(right),
+        "─".repeat(half - right)
+    )
+}
+
+/// One-row bar chart of `values`, scaled to the largest.
+pub fn sparkline(values: &[u32]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    values
+        .iter()
+        .map(|&v| match v {
+            0 => ' ',
+            v => BARS[((v * 8).div_ceil(max) as usize - 1).min(7)],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pips() {
+        assert_eq!(pips(0.75, 4), "▰▰▰▱");
+        assert_eq!(pips(0.0, 3), "▱▱▱");
+        assert_eq!(pips(2.0, 2), "▰▰");
+    }
+
+    #[test]
+    fn test_centred_pips() {
+        assert_eq!(centred_pips(0.5, 8), "────│━━──");
+        assert_eq!(centred_pips(-1.0, 4), "━━│──");
+        assert_eq!(centred_pips(0.0, 2), "─│─");
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 1, 2, 4, 8]), " ▁▂▄█");
+        assert_eq!(sparkline(&[0, 0]), "  ");
+        assert_eq!(sparkline(&[3]), "█");
+    }
+
+    #[test]
+    fn test_render_half() {
+        let area = Rect::new(0, 0, 30, 1);
+        let mut buf = Buffer::empty(area);
+
+        Meter::new("Tension").value(0.5).render(area, &mut buf);
+
+        let content: String = buf.content.iter().map(|c| c.symbol()).collect();
+        assert!(content.starts_with("Tension "));
+        assert!(content.contains("█"));
+        assert!(content.contains("░"));
+        assert!(content.ends_with(" 50%"));
+    }
+
+    #[test]
+    fn test_too_narrow() {
+        let area = Rect::new(0, 0, 10, 1);
+        let mut buf = Buffer::empty(area);
+
+        Meter::new("Tension").value(1.0).render(area, &mut buf);
+
+        assert!(buf.content.iter().all(|c| c.symbol() == " "));
+    }
+}
@@ -1,7 +1,15 @@
+pub mod fretboard;
+pub mod heatmap;
 pub mod history;
+pub mod meter;
 pub mod piano;
+pub mod ribbon;
 pub mod tree;
 
+pub use fretboard::Fretboard;
+pub use heatmap::ChordHeatmap;
 pub use history::{ChordEntry, ChordHistory};
-pub use piano::Piano;
+pub use meter::{centred_pips, pips, sparkline, Meter};
+pub use piano::{Piano, PianoStyle};
+pub use ribbon::FunctionRibbon;
 pub use tree::ChordTree;
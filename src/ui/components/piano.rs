@@ -1,52 +1,113 @@
 use std::collections::HashSet;
 
-use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+use serde::Deserialize;
 
 use crate::ui::theme::Theme;
 
 const BLACK_KEY_PATTERN: [bool; 12] = [
     false, true, false, true, false, false, true, false, true, false, true, false,
 ];
+/// How the piano is drawn, from the `[display]` config section.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PianoStyle {
+    #[default]
+    Blocks,
+    /// Quadrant-block key ends and markers on sounding keys
+    Hires,
+    /// Hires where the terminal looks able to draw it, blocks otherwise
+    Auto,
+}
+
+impl PianoStyle {
+    pub fn hires(self) -> bool {
+        match self {
+            PianoStyle::Blocks => false,
+            PianoStyle::Hires => true,
+            PianoStyle::Auto => {
+                let term = std::env::var("TERM").ok();
+                let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+                    .iter()
+                    .filter_map(|var| std::env::var(var).ok())
+                    .find(|value| !value.is_empty());
+                unicode_terminal(term.as_deref(), locale.as_deref())
+            }
+        }
+    }
+}
+
+fn unicode_terminal(term: Option<&str>, locale: Option<&str>) -> bool {
+    let basic_term = term.is_none_or(|t| matches!(t, "linux" | "dumb" | "vt100" | "vt220"));
+    let utf8 = locale.is_some_and(|l| {
+        let l = l.to_ascii_lowercase();
+        l.contains("utf-8") || l.contains("utf8")
+    });
+    !basic_term && utf8
+}
 
-pub struct Piano {
+/// Black key width as a share of a white key's
+pub const BLACK_KEY_SHARE: f32 = 0.6;
+/// Bends smaller than this, in semitones, leave the root marker be
+const MIN_BEND: f32 = 0.1;
+
+/// Drawn each frame, so it borrows the notes it shows and keeps its
+/// working out on the stack.
+pub struct Piano<'a> {
     start_midi: u8,
     num_keys: usize,
-    pressed: HashSet<u8>,
+    pressed: Option<&'a HashSet<u8>>,
+    ghost: Option<&'a HashSet<u8>>,
     root: Option<u8>,
+    hires: bool,
+    /// Semitones the root is bent by
+    bend: f32,
 }
 
-impl Piano {
+impl<'a> Piano<'a> {
     pub fn new(start_midi: u8, num_keys: usize) -> Self {
         Self {
             start_midi,
             num_keys,
-            pressed: HashSet::new(),
+            pressed: None,
+            ghost: None,
             root: None,
+            hires: false,
+            bend: 0.0,
         }
     }
 
-    pub fn dynamic(pressed: &HashSet<u8>) -> Self {
-        if pressed.is_empty() {
+    /// Octaves enough to show `notes` with some room either side.
+    pub fn spanning(notes: impl IntoIterator<Item = u8>) -> Self {
+        let range = notes.into_iter().fold(None, |range, note| match range {
+            Some((min, max)) => Some((note.min(min), note.max(max))),
+            None => Some((note, note)),
+        });
+        let Some((min, max)) = range else {
             return Self::new(48, 25);
-        }
-
-        let min = *pressed.iter().min().unwrap();
-        let max = *pressed.iter().max().unwrap();
+        };
 
         let start = (min.saturating_sub(5) / 12) * 12;
         let end = ((max + 7) / 12 + 1) * 12;
-        let num_keys = (end - start).max(25) as usize;
+        Self::new(start, (end - start).max(25) as usize)
+    }
 
-        Self {
-            start_midi: start,
-            num_keys,
-            pressed: pressed.clone(),
-            root: None,
-        }
+    pub fn dynamic(pressed: &'a HashSet<u8>) -> Self {
+        Self::spanning(pressed.iter().copied()).pressed(pressed)
     }
 
-    pub fn pressed(mut self, keys: HashSet<u8>) -> Self {
-        self.pressed = keys;
+    pub fn pressed(mut self, keys: &'a HashSet<u8>) -> Self {
+        self.pressed = Some(keys);
+        self
+    }
+
+    pub fn ghost(mut self, keys: &'a HashSet<u8>) -> Self {
+        self.ghost = Some(keys);
         self
     }
 
@@ -55,104 +116,323 @@ impl Piano {
         self
     }
 
-    fn is_black_key(midi: u8) -> bool {
+    pub fn hires(mut self, hires: bool) -> Self {
+        self.hires = hires;
+        self
+    }
+
+    /// Draws the root's marker leaning towards where it's bent, by
+    /// `semitones`.
+    pub fn bend(mut self, semitones: f32) -> Self {
+        self.bend = semitones;
+        self
+    }
+
+    fn is_bent(&self) -> bool {
+        self.bend.abs() >= MIN_BEND
+    }
+
+    pub fn is_black_key(midi: u8) -> bool {
         BLACK_KEY_PATTERN[(midi % 12) as usize]
     }
 
-    fn white_key_count(&self) -> usize {
-        (self.start_midi..self.start_midi + self.num_keys as u8)
-            .filter(|&m| !Self::is_black_key(m))
-            .count()
+    fn is_pressed(&self, midi: u8) -> bool {
+        self.pressed.is_some_and(|keys| keys.contains(&midi))
+    }
+
+    fn is_ghost(&self, midi: u8) -> bool {
+        self.ghost.is_some_and(|keys| keys.contains(&midi))
+    }
+
+    /// The white keys from `start` up to `end`, written into `keys`.
+    fn whites(start: u8, end: u8, keys: &mut [u8; 128]) -> &[u8] {
+        let mut count = 0;
+        for midi in (start..end).filter(|&m| !Self::is_black_key(m)) {
+            keys[count] = midi;
+            count += 1;
+        }
+        &keys[..count]
+    }
+
+    /// The white key under column `x`: key i covers columns
+    /// `[i * width / n, (i + 1) * width / n)`.
+    fn white_at(x: usize, width: usize, n: usize) -> usize {
+        (((x + 1) * n).div_ceil(width) - 1).min(n - 1)
+    }
+
+    /// The black key covering half-cell `half`, if any. Each sits centred
+    /// on the boundary after its white key, `black_halves` wide; where two
+    /// overlap, the higher one is on top.
+    fn black_at(
+        whites: &[u8],
+        end: u8,
+        half: usize,
+        halves: usize,
+        black_halves: usize,
+    ) -> Option<u8> {
+        let n = whites.len();
+        let guess = half * n / halves;
+        (guess.saturating_sub(2)..=(guess + 1).min(n - 1))
+            .rev()
+            .find_map(|i| {
+                let sharp = whites[i] + 1;
+                if i + 1 >= n || !Self::is_black_key(sharp) || sharp >= end {
+                    return None;
+                }
+                let left = ((i + 1) * halves / n).saturating_sub(black_halves / 2);
+                (left..left + black_halves).contains(&half).then_some(sharp)
+            })
+    }
+
+    fn end_midi(&self) -> u8 {
+        (self.start_midi as usize + self.num_keys).min(128) as u8
+    }
+
+    /// The keys it spans, for drawing it some other way.
+    pub fn keys(&self) -> std::ops::Range<u8> {
+        self.start_midi..self.end_midi()
+    }
+
+    pub fn key_style(&self, midi: u8) -> Style {
+        if Self::is_black_key(midi) {
+            self.black_style(midi)
+        } else {
+            self.white_style(midi)
+        }
+    }
+
+    /// The keys that fit in `width` columns at one cell per white key. When
+    /// the whole range doesn't fit, it's cropped around the sounding notes.
+    fn visible_range(&self, width: usize) -> (u8, u8) {
+        let (start, end) = (self.start_midi, self.end_midi());
+        let mut keys = [0; 128];
+        let whites = Self::whites(start, end, &mut keys);
+        if whites.len() <= width || width == 0 {
+            return (start, end);
+        }
+
+        let focus = self
+            .pressed
+            .into_iter()
+            .flatten()
+            .chain(self.ghost.into_iter().flatten())
+            .chain(&self.root)
+            .copied()
+            .filter(|m| (start..end).contains(m))
+            .fold(None, |range, m| match range {
+                Some((low, high)) => Some((m.min(low), m.max(high))),
+                None => Some((m, m)),
+            });
+        let centre = match focus {
+            Some((low, high)) => (low as u16 + high as u16) / 2,
+            _ => whites[whites.len() / 2] as u16,
+        };
+        let centre = whites
+            .iter()
+            .position(|&m| m as u16 >= centre)
+            .unwrap_or(whites.len() - 1);
+        let first = centre.saturating_sub(width / 2).min(whites.len() - width);
+        (whites[first], whites[first + width - 1] + 1)
+    }
+
+    fn marker(&self, midi: u8) -> Option<&'static str> {
+        if self.root == Some(midi) && self.is_bent() {
+            // Drawn on its own, where the bend takes it
+            self.is_pressed(midi).then_some("●")
+        } else if self.root == Some(midi) {
+            Some("◆")
+        } else if self.is_pressed(midi) {
+            Some("●")
+        } else if self.is_ghost(midi) {
+            Some("○")
+        } else {
+            None
+        }
+    }
+
+    fn white_style(&self, midi: u8) -> Style {
+        if self.root == Some(midi) {
+            Theme::white_key_root()
+        } else if self.is_pressed(midi) {
+            Theme::white_key_pressed()
+        } else if self.is_ghost(midi) {
+            Theme::white_key_ghost()
+        } else {
+            Theme::white_key()
+        }
+    }
+
+    fn black_style(&self, midi: u8) -> Style {
+        if self.root == Some(midi) {
+            Theme::black_key_root()
+        } else if self.is_pressed(midi) {
+            Theme::black_key_pressed()
+        } else if self.is_ghost(midi) {
+            Theme::black_key_ghost()
+        } else {
+            Theme::black_key()
+        }
+    }
+
+    /// The root's marker, moved along by the bend: a semitone is taken as
+    /// seven twelfths of a white key.
+    fn render_bent_root(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        whites: &[u8],
+        black_row: u16,
+        white_row: u16,
+    ) {
+        let Some(root) = self.root.filter(|_| self.is_bent()) else {
+            return;
+        };
+        let width = area.width as usize;
+        let n = whites.len();
+        let (x, row) = if let Some(i) = whites.iter().position(|&m| m == root) {
+            ((i * width / n + (i + 1) * width / n - 1) / 2, white_row)
+        } else if let Some(i) = whites.iter().position(|&m| m + 1 == root) {
+            (((i + 1) * width * 2 / n) / 2, black_row)
+        } else {
+            return;
+        };
+        let columns = self.bend * 7.0 / 12.0 * width as f32 / n as f32;
+        let x = (x as i32 + columns.round() as i32).clamp(0, width as i32 - 1) as u16;
+
+        let (x, y) = (area.x + x, area.y + row);
+        let color = Theme::white_key_root().bg.unwrap_or(Color::Yellow);
+        let bg = buf[(x, y)].bg;
+        buf.set_string(x, y, "◆", Style::default().fg(color).bg(bg));
     }
 }
 
-impl Widget for Piano {
+impl Widget for Piano<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         if area.height < 4 || area.width < 10 {
             return;
         }
 
-        let white_keys = self.white_key_count();
-        let key_width = (area.width as usize / white_keys).max(2);
-        let black_key_width = key_width.saturating_sub(1).max(1);
+        let width = area.width as usize;
+        let (start, end) = self.visible_range(width);
+        let mut keys = [0; 128];
+        let whites = Self::whites(start, end, &mut keys);
+        if whites.is_empty() {
+            return;
+        }
 
         let piano_height = area.height.min(6);
         let black_key_height = (piano_height * 3 / 5).max(2);
 
-        let mut white_key_x = area.x;
-
-        for midi in self.start_midi..self.start_midi + self.num_keys as u8 {
-            if Self::is_black_key(midi) {
-                continue;
+        // White key i covers columns [i * width / n, (i + 1) * width / n), so
+        // uneven widths are spread across the keyboard instead of piling up
+        // at the right edge.
+        let n = whites.len();
+        for (i, &midi) in whites.iter().enumerate() {
+            let (left, right) = (i * width / n, (i + 1) * width / n);
+            let style = self.white_style(midi);
+            for x in left..right {
+                let x = area.x + x as u16;
+                for y in area.y..area.y + piano_height {
+                    buf.set_string(x, y, " ", style);
+                }
             }
 
-            let is_pressed = self.pressed.contains(&midi);
-            let is_root = self.root == Some(midi);
-
-            let style = if is_root {
-                Theme::white_key_root()
-            } else if is_pressed {
-                Theme::white_key_pressed()
-            } else {
-                Theme::white_key()
-            };
-
+            let border = if right - left > 1 { "│" } else { "▕" };
             for y in area.y..area.y + piano_height {
-                for x in white_key_x..white_key_x + key_width as u16 {
-                    if x < area.x + area.width {
-                        buf.set_string(x, y, " ", style);
-                    }
-                }
+                buf.set_string(area.x + right as u16 - 1, y, border, Theme::border());
             }
+        }
 
-            if white_key_x + key_width as u16 <= area.x + area.width {
-                for y in area.y..area.y + piano_height {
-                    buf.set_string(white_key_x + key_width as u16 - 1, y, "│", Theme::border());
-                }
+        // Black keys sit on the boundary between their neighbours and are
+        // laid out in half cells, drawn with half blocks where they start or
+        // end mid-cell.
+        let halves = width * 2;
+        let black_halves = ((halves as f32 / n as f32) * BLACK_KEY_SHARE)
+            .round()
+            .max(1.0) as usize;
+        let black = |half| Self::black_at(whites, end, half, halves, black_halves);
+
+        for x in 0..width {
+            let white_bg = self
+                .white_style(whites[Self::white_at(x, width, n)])
+                .bg
+                .unwrap_or(Color::White);
+            let (symbol, style) = match (black(2 * x), black(2 * x + 1)) {
+                (None, None) => continue,
+                (Some(a), Some(b)) if a == b => (" ", self.black_style(a)),
+                (Some(a), right) => (
+                    "▌",
+                    Style::default()
+                        .fg(self.black_style(a).bg.unwrap_or(Color::Black))
+                        .bg(right
+                            .map_or(white_bg, |b| self.black_style(b).bg.unwrap_or(Color::Black))),
+                ),
+                (None, Some(b)) => (
+                    "▐",
+                    Style::default()
+                        .fg(self.black_style(b).bg.unwrap_or(Color::Black))
+                        .bg(white_bg),
+                ),
+            };
+            for y in area.y..area.y + black_key_height {
+                buf.set_string(area.x + x as u16, y, symbol, style);
             }
+        }
 
-            white_key_x += key_width as u16;
+        if !self.hires || piano_height < black_key_height + 2 {
+            self.render_bent_root(area, buf, whites, black_key_height - 1, piano_height - 1);
+            return;
         }
 
-        white_key_x = area.x;
+        // Black keys end half a row lower, with quadrant blocks where the
+        // key covers half a cell
+        let end_row = area.y + black_key_height;
+        for x in 0..width {
+            let white_bg = self
+                .white_style(whites[Self::white_at(x, width, n)])
+                .bg
+                .unwrap_or(Color::White);
+            let (symbol, key) = match (black(2 * x), black(2 * x + 1)) {
+                (None, None) => continue,
+                (Some(a), Some(_)) => ("▀", a),
+                (Some(a), None) => ("▘", a),
+                (None, Some(b)) => ("▝", b),
+            };
+            let fg = self.black_style(key).bg.unwrap_or(Color::Black);
+            buf.set_string(
+                area.x + x as u16,
+                end_row,
+                symbol,
+                Style::default().fg(fg).bg(white_bg),
+            );
+        }
 
-        for midi in self.start_midi..self.start_midi + self.num_keys as u8 {
-            if Self::is_black_key(midi) {
-                continue;
+        // A marker on each sounding key, near where the finger goes
+        let bottom = area.y + piano_height - 1;
+        for (i, &midi) in whites.iter().enumerate() {
+            if let Some(marker) = self.marker(midi) {
+                let x = (i * width / n + (i + 1) * width / n - 1) / 2;
+                let style = self.white_style(midi);
+                buf.set_string(area.x + x as u16, bottom, marker, style);
             }
 
-            let next_midi = midi + 1;
-            if next_midi < self.start_midi + self.num_keys as u8 && Self::is_black_key(next_midi) {
-                let black_x = white_key_x + key_width as u16 - (black_key_width as u16 / 2) - 1;
-
-                let is_pressed = self.pressed.contains(&next_midi);
-                let is_root = self.root == Some(next_midi);
-
-                let style = if is_root {
-                    Theme::black_key_root()
-                } else if is_pressed {
-                    Theme::black_key_pressed()
-                } else {
-                    Theme::black_key()
-                };
-
-                for y in area.y..area.y + black_key_height {
-                    for x in black_x..black_x + black_key_width as u16 {
-                        if x < area.x + area.width && x >= area.x {
-                            buf.set_string(x, y, " ", style);
-                        }
-                    }
+            let sharp = midi + 1;
+            if i + 1 < n && Self::is_black_key(sharp) && sharp < end {
+                if let Some(marker) = self.marker(sharp) {
+                    let x = ((i + 1) * halves / n) / 2;
+                    let style = self.black_style(sharp);
+                    buf.set_string(area.x + x as u16, end_row - 1, marker, style);
                 }
             }
-
-            white_key_x += key_width as u16;
         }
+        self.render_bent_root(area, buf, whites, black_key_height - 1, piano_height - 1);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ui::snapshot;
 
     #[test]
     fn test_render_octave_off() {
@@ -173,7 +453,7 @@ mod tests {
         pressed.insert(64);
         pressed.insert(67);
 
-        let piano = Piano::new(60, 12).pressed(pressed);
+        let piano = Piano::new(60, 12).pressed(&pressed);
         let area = Rect::new(0, 0, 40, 6);
         let mut buf = Buffer::empty(area);
 
@@ -183,6 +463,131 @@ mod tests {
         assert!(has_content);
     }
 
+    #[test]
+    fn test_render_ghost_notes() {
+        let ghost = HashSet::from([62]);
+        let piano = Piano::new(60, 12).ghost(&ghost);
+        let area = Rect::new(0, 0, 40, 6);
+        let mut buf = Buffer::empty(area);
+
+        piano.render(area, &mut buf);
+
+        let ghost_bg = Theme::white_key_ghost().bg;
+        assert!(buf.content.iter().any(|c| Some(c.bg) == ghost_bg));
+    }
+
+    #[test]
+    fn test_white_keys_fill_width() {
+        let piano = Piano::new(48, 25);
+        let area = Rect::new(0, 0, 40, 6);
+        let mut buf = Buffer::empty(area);
+
+        piano.render(area, &mut buf);
+
+        // The bottom row has no black keys: every column belongs to a white key
+        let white_bg = Theme::white_key().bg.unwrap();
+        assert!((0..40).all(|x| buf[(x, 5)].bg == white_bg));
+    }
+
+    #[test]
+    fn test_black_keys_use_half_blocks() {
+        let keys = HashSet::from([61]);
+        let piano = Piano::new(60, 12).pressed(&keys);
+        let area = Rect::new(0, 0, 40, 6);
+        let mut buf = Buffer::empty(area);
+
+        piano.render(area, &mut buf);
+
+        let row: Vec<&str> = (0..40).map(|x| buf[(x, 0)].symbol()).collect();
+        assert!(row.iter().any(|s| *s == "▌" || *s == "▐"));
+
+        // C# straddles the boundary between C and D, columns 5 and 6
+        let pressed = Theme::black_key_pressed().bg.unwrap();
+        let cells = [buf[(5, 0)].clone(), buf[(6, 0)].clone()];
+        assert!(cells.iter().all(|c| c.bg == pressed || c.fg == pressed));
+        assert_eq!(buf[(5, 5)].bg, Theme::white_key().bg.unwrap());
+    }
+
+    #[test]
+    fn test_full_keyboard_squeezed() {
+        let keys = HashSet::from([60]);
+        let piano = Piano::new(21, 88).pressed(&keys);
+        assert_eq!(piano.visible_range(52), (21, 109));
+
+        let (start, end) = piano.visible_range(40);
+        assert!(start <= 60 && 60 < end);
+        assert_eq!(
+            (start..end).filter(|&m| !Piano::is_black_key(m)).count(),
+            40
+        );
+
+        let area = Rect::new(0, 0, 40, 6);
+        let mut buf = Buffer::empty(area);
+        piano.render(area, &mut buf);
+        let pressed = Theme::white_key_pressed().bg.unwrap();
+        assert!(buf.content.iter().any(|c| c.bg == pressed));
+    }
+
+    #[test]
+    fn test_hires_markers_and_key_ends() {
+        let keys: HashSet<u8> = [61, 64].into_iter().collect();
+        let area = Rect::new(0, 0, 40, 6);
+
+        let mut blocks = Buffer::empty(area);
+        Piano::new(60, 12).pressed(&keys).render(area, &mut blocks);
+        assert!(!blocks.content.iter().any(|c| c.symbol() == "●"));
+
+        let mut hires = Buffer::empty(area);
+        Piano::new(60, 12)
+            .pressed(&keys)
+            .root(Some(64))
+            .hires(true)
+            .render(area, &mut hires);
+        let symbols: Vec<&str> = hires.content.iter().map(|c| c.symbol()).collect();
+        assert!(symbols.contains(&"●"));
+        assert!(symbols.contains(&"◆"));
+        // Black keys are three rows and a half on a six-row piano
+        let ends = (0..40).map(|x| hires[(x, 3)].symbol());
+        assert!(ends.clone().any(|s| s == "▀"));
+        assert!(ends.clone().any(|s| s == "▘" || s == "▝"));
+    }
+
+    #[test]
+    fn test_bent_root() {
+        let area = Rect::new(0, 0, 42, 6);
+        let keys = HashSet::from([60, 64, 67]);
+        let marker = |bend: f32| {
+            let mut buf = Buffer::empty(area);
+            Piano::new(60, 12)
+                .pressed(&keys)
+                .root(Some(60))
+                .bend(bend)
+                .render(area, &mut buf);
+            (0..42).find(|&x| buf[(x, 5)].symbol() == "◆")
+        };
+        // Unbent, the blocks style shows the root by colour alone
+        assert_eq!(marker(0.0), None);
+        // C is columns 0-5 of seven keys; two semitones up is about a key
+        // further, over D
+        assert_eq!(marker(0.5), Some(4));
+        assert_eq!(marker(2.0), Some(9));
+        assert_eq!(marker(-2.0), Some(0));
+    }
+
+    #[test]
+    fn test_unicode_terminal() {
+        assert!(unicode_terminal(
+            Some("xterm-256color"),
+            Some("en_US.UTF-8")
+        ));
+        assert!(unicode_terminal(Some("tmux-256color"), Some("C.utf8")));
+        assert!(!unicode_terminal(Some("linux"), Some("en_US.UTF-8")));
+        assert!(!unicode_terminal(Some("xterm"), Some("C")));
+        assert!(!unicode_terminal(None, None));
+        assert!(!PianoStyle::Blocks.hires());
+        assert!(PianoStyle::Hires.hires());
+    }
+
     #[test]
     fn test_dynamic_range() {
         let mut pressed = HashSet::new();
@@ -214,4 +619,17 @@ mod tests {
         assert!(!Piano::is_black_key(65)); // F
         assert!(Piano::is_black_key(66)); // F#
     }
+
+    #[test]
+    fn test_snapshots() {
+        let keys = HashSet::from([48, 64, 67, 70]);
+        let ghost = HashSet::from([62]);
+        let piano = || Piano::dynamic(&keys).ghost(&ghost).root(Some(48));
+        insta::assert_snapshot!("piano_blocks", snapshot::widget(piano(), 60, 6));
+        insta::assert_snapshot!("piano_hires", snapshot::widget(piano().hires(true), 60, 6));
+        insta::assert_snapshot!(
+            "piano_squeezed",
+            snapshot::widget(Piano::new(21, 88).pressed(&keys), 40, 5)
+        );
+    }
 }
@@ -0,0 +1,111 @@
+use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+use crate::theory::Function;
+use crate::ui::theme::Theme;
+
+/// Columns each chord takes
+const BLOCK: u16 = 2;
+
+/// A row of coloured blocks, one per chord of the history by its function
+/// in the key, so the shape of a jam shows at a glance. The newest chords
+/// are kept when they don't all fit.
+pub struct FunctionRibbon<'a> {
+    functions: &'a [Function],
+    selected: Option<usize>,
+}
+
+impl<'a> FunctionRibbon<'a> {
+    pub fn new(functions: &'a [Function]) -> Self {
+        Self {
+            functions,
+            selected: None,
+        }
+    }
+
+    pub fn selected(mut self, selected: Option<usize>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// The first chord shown across `width` columns: the newest that fit,
+    /// or a run ending at the selected chord further back.
+    fn first(&self, width: u16) -> usize {
+        let fit = (width / BLOCK) as usize;
+        let newest = self.functions.len().saturating_sub(fit);
+        match self.selected {
+            Some(selected) if selected < newest => (selected + 1).saturating_sub(fit),
+            _ => newest,
+        }
+    }
+
+    /// The chord whose block is at `column` when drawn in `area`.
+    pub fn index_at(&self, area: Rect, column: u16) -> Option<usize> {
+        let offset = column.checked_sub(area.x)?;
+        let index = self.first(area.width) + (offset / BLOCK) as usize;
+        (offset < area.width && index < self.functions.len()).then_some(index)
+    }
+}
+
+impl Widget for FunctionRibbon<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 1 {
+            return;
+        }
+        let first = self.first(area.width);
+        for (i, function) in self.functions.iter().enumerate().skip(first) {
+            let x = area.x + (i - first) as u16 * BLOCK;
+            let mut style = Theme::function(*function);
+            if self.selected == Some(i) {
+                style = style.patch(Theme::selected());
+            }
+            buf.set_stringn(
+                x,
+                area.y,
+                format!("{} ", function.letter()),
+                BLOCK as usize,
+                style,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FUNCTIONS: [Function; 4] = [
+        Function::Tonic,
+        Function::Subdominant,
+        Function::Dominant,
+        Function::Tonic,
+    ];
+
+    #[test]
+    fn test_blocks() {
+        let area = Rect::new(0, 0, 10, 1);
+        let mut buf = Buffer::empty(area);
+        FunctionRibbon::new(&FUNCTIONS).render(area, &mut buf);
+        let row: String = (0..10).map(|x| buf[(x, 0)].symbol().to_string()).collect();
+        assert_eq!(row, "T S D T   ");
+        assert_eq!(buf[(2, 0)].bg, ratatui::style::Color::Green);
+    }
+
+    #[test]
+    fn test_index_at_keeps_newest() {
+        let ribbon = FunctionRibbon::new(&FUNCTIONS);
+        let wide = Rect::new(5, 3, 20, 1);
+        assert_eq!(ribbon.index_at(wide, 5), Some(0));
+        assert_eq!(ribbon.index_at(wide, 12), Some(3));
+        assert_eq!(ribbon.index_at(wide, 14), None);
+        assert_eq!(ribbon.index_at(wide, 4), None);
+
+        // Only the last two fit in four columns, unless one further back
+        // is selected
+        let narrow = Rect::new(0, 0, 4, 1);
+        assert_eq!(ribbon.index_at(narrow, 0), Some(2));
+        assert_eq!(ribbon.index_at(narrow, 3), Some(3));
+        let ribbon = ribbon.selected(Some(0));
+        assert_eq!(ribbon.index_at(narrow, 0), Some(0));
+        assert_eq!(ribbon.index_at(narrow, 2), Some(1));
+    }
+}
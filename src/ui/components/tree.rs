@@ -1,16 +1,25 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
+    style::Style,
     text::{Line, Span},
     widgets::Widget,
 };
 
-use crate::theory::ProgressionNode;
-use crate::ui::theme::Theme;
+use crate::theory::{Branch, Chord, NamingStyle, NoteNames, ProgressionNode, Spelling};
+use crate::ui::theme::{QualityColors, Theme};
 
 pub struct ChordTree {
     root: Option<ProgressionNode>,
     depth: usize,
+    naming: NamingStyle,
+    spelling: Spelling,
+    note_names: NoteNames,
+    capo: u8,
+    placeholder: &'static str,
+    planned: Vec<Vec<Branch>>,
+    cursor: Option<Vec<Branch>>,
+    quality_colors: Option<QualityColors>,
 }
 
 impl Default for ChordTree {
@@ -24,6 +33,14 @@ impl ChordTree {
         Self {
             root: None,
             depth: 2,
+            naming: NamingStyle::default(),
+            spelling: Spelling::default(),
+            note_names: NoteNames::default(),
+            capo: 0,
+            placeholder: "Play a chord...",
+            planned: Vec::new(),
+            cursor: None,
+            quality_colors: None,
         }
     }
 
@@ -37,9 +54,84 @@ impl ChordTree {
         self
     }
 
+    pub fn naming(mut self, style: NamingStyle) -> Self {
+        self.naming = style;
+        self
+    }
+
+    pub fn spelling(mut self, spelling: Spelling) -> Self {
+        self.spelling = spelling;
+        self
+    }
+
+    pub fn note_names(mut self, names: NoteNames) -> Self {
+        self.note_names = names;
+        self
+    }
+
+    /// Shown until there is a chord.
+    pub fn placeholder(mut self, text: &'static str) -> Self {
+        self.placeholder = text;
+        self
+    }
+
+    /// Branches to mark as planned.
+    pub fn planned(mut self, paths: Vec<Vec<Branch>>) -> Self {
+        self.planned = paths;
+        self
+    }
+
+    pub fn cursor(mut self, path: Option<Vec<Branch>>) -> Self {
+        self.cursor = path;
+        self
+    }
+
+    /// Colours chord names by quality family rather than by branch.
+    pub fn quality_colors(mut self, colors: Option<QualityColors>) -> Self {
+        self.quality_colors = colors;
+        self
+    }
+
+    fn style(&self, path: &[Branch], chord: &Chord, base: Style) -> Style {
+        let mut style = self.colored(chord, base);
+        if self.planned.iter().any(|p| p == path) {
+            style = style.patch(Theme::tree_planned());
+        }
+        if self.cursor.as_deref() == Some(path) {
+            style = style.patch(Theme::tree_cursor());
+        }
+        style
+    }
+
+    fn colored(&self, chord: &Chord, base: Style) -> Style {
+        match &self.quality_colors {
+            Some(colors) => colors.style(chord, base),
+            None => base,
+        }
+    }
+
+    /// With a capo on, each chord is followed by the shape to finger.
+    pub fn capo(mut self, capo: u8) -> Self {
+        self.capo = capo;
+        self
+    }
+
+    fn label(&self, chord: &Chord) -> String {
+        let name = chord.name_in(self.naming, self.spelling, self.note_names);
+        if self.capo == 0 {
+            return name;
+        }
+        let shape = chord.capo_shape(self.capo);
+        format!(
+            "{}({})",
+            name,
+            shape.name_in(self.naming, self.spelling, self.note_names)
+        )
+    }
+
     fn render_tree(&self, area: Rect, buf: &mut Buffer) {
         let Some(node) = &self.root else {
-            let line = Line::from(vec![Span::styled("Play a chord...", Theme::text_dim())]);
+            let line = Line::from(vec![Span::styled(self.placeholder, Theme::text_dim())]);
             buf.set_line(area.x + 1, area.y + area.height / 2, &line, area.width);
             return;
         };
@@ -48,11 +140,14 @@ impl ChordTree {
         let col_width = area.width / 4;
 
         let current_x = area.x + 1;
-        let current_name = node.chord.name();
-        let line = Line::from(vec![Span::styled(&current_name, Theme::tree_current())]);
+        let current_name = self.label(&node.chord);
+        let line = Line::from(vec![Span::styled(
+            &current_name,
+            self.colored(&node.chord, Theme::tree_current()),
+        )]);
         buf.set_line(current_x, center_y, &line, col_width);
 
-        let connector_x = current_x + current_name.len() as u16 + 1;
+        let connector_x = current_x + current_name.chars().count() as u16 + 1;
         buf.set_string(connector_x, center_y, "─┬─", Theme::tree_connector());
 
         if let Some(left) = &node.left {
@@ -61,24 +156,45 @@ impl ChordTree {
             buf.set_string(connector_x + 2, left_y, "─", Theme::tree_connector());
 
             let left_x = connector_x + 4;
-            let left_name = left.chord.name();
-            let line = Line::from(vec![Span::styled(&left_name, Theme::tree_expected())]);
+            let left_name = self.label(&left.chord);
+            let line = Line::from(vec![Span::styled(
+                &left_name,
+                self.style(&[Branch::Expected], &left.chord, Theme::tree_expected()),
+            )]);
             buf.set_line(left_x, left_y, &line, col_width);
 
             if let (Some(ll), Some(lr)) = (&left.left, &left.right) {
-                let ll_x = left_x + left_name.len() as u16 + 1;
+                let ll_x = left_x + left_name.chars().count() as u16 + 1;
                 buf.set_string(ll_x, left_y, "─┬─", Theme::tree_connector());
 
                 let ll_y = left_y.saturating_sub(1);
                 buf.set_string(ll_x + 1, ll_y, "┌", Theme::tree_connector());
-                let ll_name = ll.chord.name();
-                buf.set_string(ll_x + 3, ll_y, &ll_name, Theme::tree_expected());
+                let ll_name = self.label(&ll.chord);
+                buf.set_string(
+                    ll_x + 3,
+                    ll_y,
+                    &ll_name,
+                    self.style(
+                        &[Branch::Expected, Branch::Expected],
+                        &ll.chord,
+                        Theme::tree_expected(),
+                    ),
+                );
 
                 let lr_y = left_y + 1;
                 if lr_y < area.y + area.height {
                     buf.set_string(ll_x + 1, lr_y, "└", Theme::tree_connector());
-                    let lr_name = lr.chord.name();
-                    buf.set_string(ll_x + 3, lr_y, &lr_name, Theme::tree_surprise());
+                    let lr_name = self.label(&lr.chord);
+                    buf.set_string(
+                        ll_x + 3,
+                        lr_y,
+                        &lr_name,
+                        self.style(
+                            &[Branch::Expected, Branch::Surprise],
+                            &lr.chord,
+                            Theme::tree_surprise(),
+                        ),
+                    );
                 }
             }
         }
@@ -90,24 +206,45 @@ impl ChordTree {
                 buf.set_string(connector_x + 2, right_y, "─", Theme::tree_connector());
 
                 let right_x = connector_x + 4;
-                let right_name = right.chord.name();
-                let line = Line::from(vec![Span::styled(&right_name, Theme::tree_surprise())]);
+                let right_name = self.label(&right.chord);
+                let line = Line::from(vec![Span::styled(
+                    &right_name,
+                    self.style(&[Branch::Surprise], &right.chord, Theme::tree_surprise()),
+                )]);
                 buf.set_line(right_x, right_y, &line, col_width);
 
                 if let (Some(rl), Some(rr)) = (&right.left, &right.right) {
-                    let rl_x = right_x + right_name.len() as u16 + 1;
+                    let rl_x = right_x + right_name.chars().count() as u16 + 1;
                     buf.set_string(rl_x, right_y, "─┬─", Theme::tree_connector());
 
                     let rl_y = right_y;
                     buf.set_string(rl_x + 1, rl_y - 1, "┌", Theme::tree_connector());
-                    let rl_name = rl.chord.name();
-                    buf.set_string(rl_x + 3, rl_y - 1, &rl_name, Theme::tree_expected());
+                    let rl_name = self.label(&rl.chord);
+                    buf.set_string(
+                        rl_x + 3,
+                        rl_y - 1,
+                        &rl_name,
+                        self.style(
+                            &[Branch::Surprise, Branch::Expected],
+                            &rl.chord,
+                            Theme::tree_expected(),
+                        ),
+                    );
 
                     let rr_y = right_y + 1;
                     if rr_y < area.y + area.height {
                         buf.set_string(rl_x + 1, rr_y, "└", Theme::tree_connector());
-                        let rr_name = rr.chord.name();
-                        buf.set_string(rl_x + 3, rr_y, &rr_name, Theme::tree_surprise());
+                        let rr_name = self.label(&rr.chord);
+                        buf.set_string(
+                            rl_x + 3,
+                            rr_y,
+                            &rr_name,
+                            self.style(
+                                &[Branch::Surprise, Branch::Surprise],
+                                &rr.chord,
+                                Theme::tree_surprise(),
+                            ),
+                        );
                     }
                 }
             }
@@ -129,6 +266,8 @@ impl Widget for ChordTree {
 mod tests {
     use super::*;
     use crate::theory::{Chord, Note, Quality};
+    use crate::ui::snapshot;
+    use ratatui::style::Modifier;
 
     #[test]
     fn test_render_single_node() {
@@ -172,4 +311,92 @@ mod tests {
         assert!(content.contains("F"));
         assert!(content.contains("Am"));
     }
+
+    #[test]
+    fn test_render_plan_marks() {
+        let c = Chord::new(Note::new(60), Quality::Major);
+        let f = Chord::new(Note::new(65), Quality::Major);
+        let am = Chord::new(Note::new(69), Quality::Minor);
+        let root = ProgressionNode::new(c)
+            .with_children(ProgressionNode::new(f), ProgressionNode::new(am));
+        let tree = ChordTree::new()
+            .root(root)
+            .planned(vec![vec![Branch::Expected]])
+            .cursor(Some(vec![Branch::Surprise]));
+        let area = Rect::new(0, 0, 60, 10);
+        let mut buf = Buffer::empty(area);
+
+        tree.render(area, &mut buf);
+
+        let cell = |symbol: &str| {
+            buf.content
+                .iter()
+                .find(|c| c.symbol() == symbol)
+                .unwrap()
+                .modifier
+        };
+        assert!(cell("F").contains(Modifier::UNDERLINED));
+        assert!(cell("A").contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_render_capo_shapes() {
+        let bb = Chord::new(Note::new(70), Quality::Major);
+        let tree = ChordTree::new()
+            .root(ProgressionNode::new(bb))
+            .spelling(Spelling::Flat)
+            .capo(1);
+        let area = Rect::new(0, 0, 60, 10);
+        let mut buf = Buffer::empty(area);
+
+        tree.render(area, &mut buf);
+
+        let content: String = buf.content.iter().map(|c| c.symbol()).collect();
+        assert!(content.contains("Bb(A)"));
+    }
+
+    #[test]
+    fn test_render_quality_colors() {
+        let node = |name| ProgressionNode::new(Chord::from_name(name).unwrap());
+        let colors = QualityColors::default();
+        let tree = ChordTree::new()
+            .root(node("C").with_children(node("Am"), node("G7")))
+            .quality_colors(Some(colors));
+        let area = Rect::new(0, 0, 60, 10);
+        let mut buf = Buffer::empty(area);
+
+        tree.render(area, &mut buf);
+
+        let color_of = |symbol: &str| {
+            buf.content
+                .iter()
+                .find(|c| c.symbol() == symbol)
+                .map(|c| c.fg)
+        };
+        assert_eq!(color_of("A"), Some(colors.minor));
+        assert_eq!(color_of("G"), Some(colors.dominant));
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let chord = |name| ProgressionNode::new(Chord::from_name(name).unwrap());
+        let root = chord("C").with_children(
+            chord("F").with_children(chord("G"), chord("Dm")),
+            chord("Am").with_children(chord("Dm"), chord("E7")),
+        );
+        insta::assert_snapshot!(
+            "tree_two_levels",
+            snapshot::widget(ChordTree::new().root(root.clone()).depth(2), 60, 10)
+        );
+        let planned = ChordTree::new()
+            .root(root)
+            .depth(2)
+            .planned(vec![vec![Branch::Expected, Branch::Surprise]])
+            .cursor(Some(vec![Branch::Surprise]));
+        insta::assert_snapshot!("tree_planned", snapshot::widget(planned, 60, 10));
+        insta::assert_snapshot!(
+            "tree_empty",
+            snapshot::widget(ChordTree::new().placeholder("Play a chord"), 40, 5)
+        );
+    }
 }
@@ -0,0 +1,287 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::midi::Control;
+use crate::ui::i18n::{Language, Text};
+use crate::ui::keymap::Command;
+
+/// Commands offered for mapping to a controller button
+pub const MAPPABLE: [Command; 7] = [
+    Command::ToggleMode,
+    Command::ClearHistory,
+    Command::PageTurn,
+    Command::ToggleLatch,
+    Command::ToggleExtended,
+    Command::CreativityDown,
+    Command::CreativityUp,
+];
+
+/// What a controller button does: a command, or switching to a suggestion
+/// profile.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ControlAction {
+    Command(Command),
+    Profile(String),
+}
+
+impl ControlAction {
+    /// Parses a command name, or `profile:<name>`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().split_once(':') {
+            Some(("profile", profile)) if !profile.trim().is_empty() => {
+                Some(ControlAction::Profile(profile.trim().to_string()))
+            }
+            Some(_) => None,
+            None => Command::from_name(name).map(ControlAction::Command),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            ControlAction::Command(command) => command.name().to_string(),
+            ControlAction::Profile(profile) => format!("profile:{}", profile),
+        }
+    }
+
+    pub fn description(&self, language: Language) -> String {
+        match self {
+            ControlAction::Command(command) => language.text(Text::Command(*command)).to_string(),
+            ControlAction::Profile(profile) => {
+                language.fill(Text::SuggestionProfileAction, &[profile])
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for ControlAction {
+    type Error = anyhow::Error;
+
+    fn try_from(name: String) -> anyhow::Result<Self> {
+        Self::from_name(&name).ok_or_else(|| anyhow!("Unknown control action '{}'", name))
+    }
+}
+
+impl From<ControlAction> for String {
+    fn from(action: ControlAction) -> Self {
+        action.name()
+    }
+}
+
+/// Controller buttons and the actions they're mapped to.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ControlMap {
+    controls: BTreeMap<Control, ControlAction>,
+}
+
+impl ControlMap {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::from_toml(&text).map_err(|e| anyhow!("{}: {}", path.display(), e))
+    }
+
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, control: Control) -> Option<&ControlAction> {
+        self.controls.get(&control)
+    }
+
+    /// Maps `control` to `action` in place of whatever it did before, and
+    /// of any other control mapped to it.
+    pub fn bind(&mut self, control: Control, action: ControlAction) {
+        self.unbind(&action);
+        self.controls.insert(control, action);
+    }
+
+    pub fn unbind(&mut self, action: &ControlAction) {
+        self.controls.retain(|_, a| a != action);
+    }
+
+    /// The notes mapped, which aren't to be played.
+    pub fn notes(&self) -> HashSet<u8> {
+        self.controls
+            .keys()
+            .filter_map(|control| match control {
+                Control::Note(note) => Some(*note),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn control_for(&self, action: &ControlAction) -> Option<Control> {
+        self.controls
+            .iter()
+            .find(|(_, a)| *a == action)
+            .map(|(control, _)| *control)
+    }
+}
+
+/// What the mapping screen is waiting for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Learn {
+    Off,
+    /// The key of a command to map
+    Key,
+    /// A control to map the selected action to
+    Control,
+}
+
+/// The mapping screen: actions that can be mapped, the one under the
+/// cursor, and what it's waiting for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mapping {
+    pub actions: Vec<ControlAction>,
+    pub cursor: usize,
+    pub learn: Learn,
+}
+
+impl Mapping {
+    /// The mappable commands, then `profiles`, then anything else already
+    /// mapped in `map`.
+    pub fn new(map: &ControlMap, profiles: Vec<String>) -> Self {
+        let mut actions: Vec<ControlAction> = MAPPABLE
+            .into_iter()
+            .map(ControlAction::Command)
+            .chain(profiles.into_iter().map(ControlAction::Profile))
+            .collect();
+        for action in map.controls.values() {
+            if !actions.contains(action) {
+                actions.push(action.clone());
+            }
+        }
+        Self {
+            actions,
+            cursor: 0,
+            learn: Learn::Off,
+        }
+    }
+
+    pub fn selected(&self) -> &ControlAction {
+        &self.actions[self.cursor]
+    }
+
+    pub fn move_cursor(&mut self, down: bool) {
+        let count = self.actions.len();
+        self.cursor = if down {
+            (self.cursor + 1) % count
+        } else {
+            (self.cursor + count - 1) % count
+        };
+        self.learn = Learn::Off;
+    }
+
+    /// Selects `action`, adding it if it isn't listed, and waits for a
+    /// control to map it to.
+    pub fn learn(&mut self, action: ControlAction) {
+        self.cursor = match self.actions.iter().position(|a| *a == action) {
+            Some(at) => at,
+            None => {
+                self.actions.push(action);
+                self.actions.len() - 1
+            }
+        };
+        self.learn = Learn::Control;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_names() {
+        assert_eq!(
+            ControlAction::from_name("toggle-mode"),
+            Some(ControlAction::Command(Command::ToggleMode))
+        );
+        assert_eq!(
+            ControlAction::from_name("profile: jazz"),
+            Some(ControlAction::Profile("jazz".to_string()))
+        );
+        assert_eq!(ControlAction::from_name("profile:"), None);
+        assert_eq!(ControlAction::from_name("user:sam"), None);
+        for command in MAPPABLE {
+            let action = ControlAction::Command(command);
+            assert_eq!(ControlAction::from_name(&action.name()), Some(action));
+        }
+    }
+
+    #[test]
+    fn test_bind() {
+        let mut map = ControlMap::default();
+        let mode = ControlAction::Command(Command::ToggleMode);
+        map.bind(Control::Program(0), mode.clone());
+        map.bind(Control::Cc(64), mode.clone());
+        assert_eq!(map.get(Control::Program(0)), None);
+        assert_eq!(map.control_for(&mode), Some(Control::Cc(64)));
+
+        let jazz = ControlAction::Profile("jazz".to_string());
+        map.bind(Control::Cc(64), jazz.clone());
+        assert_eq!(map.control_for(&mode), None);
+        map.unbind(&jazz);
+        assert_eq!(map, ControlMap::default());
+    }
+
+    #[test]
+    fn test_mapping() {
+        let mut map = ControlMap::default();
+        map.bind(Control::Cc(20), ControlAction::Command(Command::Help));
+        let mut mapping = Mapping::new(&map, vec!["jazz".to_string()]);
+        assert_eq!(mapping.actions.len(), MAPPABLE.len() + 2);
+        assert_eq!(
+            mapping.actions.last(),
+            Some(&ControlAction::Command(Command::Help))
+        );
+        mapping.learn = Learn::Control;
+        mapping.move_cursor(false);
+        assert_eq!(mapping.selected(), &ControlAction::Command(Command::Help));
+        assert_eq!(mapping.learn, Learn::Off);
+
+        mapping.learn(ControlAction::Command(Command::Quit));
+        assert_eq!(mapping.actions.len(), MAPPABLE.len() + 3);
+        assert_eq!(mapping.selected(), &ControlAction::Command(Command::Quit));
+        mapping.learn(ControlAction::Command(Command::ToggleMode));
+        assert_eq!(mapping.cursor, 0);
+        assert_eq!(mapping.learn, Learn::Control);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut map = ControlMap::default();
+        map.bind(
+            Control::Program(5),
+            ControlAction::Command(Command::ClearHistory),
+        );
+        map.bind(Control::Cc(20), ControlAction::Profile("jazz".to_string()));
+        map.bind(Control::Note(36), ControlAction::Command(Command::Help));
+        assert_eq!(map.notes(), HashSet::from([36]));
+
+        let text = toml::to_string_pretty(&map).unwrap();
+        assert!(text.contains("\"program 5\" = \"clear-history\""));
+        assert_eq!(ControlMap::from_toml(&text).unwrap(), map);
+        assert!(ControlMap::from_toml("\"cc 200\" = \"toggle-mode\"").is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use crossterm::{
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind, MouseButton,
+        MouseEventKind,
+    },
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::{CrosstermBackend, TestBackend},
+    Terminal,
+};
+
+use super::App;
+
+/// How often the window ticks the app and repaints
+pub const TICK_RATE: Duration = Duration::from_millis(50);
+/// How long the terminal waits for a key before ticking, so keys and MIDI
+/// are answered well within a frame
+const POLL_RATE: Duration = Duration::from_millis(5);
+/// The least time between frames, about 30 a second
+const FRAME_TIME: Duration = Duration::from_millis(33);
+/// Redrawn this often even when nothing is known to have changed, for
+/// clocks and rates that drift on their own
+const IDLE_REDRAW: Duration = Duration::from_secs(1);
+
+/// Somewhere to show the app: draws it, passes it keys and ticks it until
+/// it quits. The app itself, MIDI included, is the same whichever is used,
+/// and is left to be shut down afterwards.
+pub trait Frontend {
+    fn run(&mut self, app: &mut App) -> Result<()>;
+}
+
+/// The full-screen terminal UI.
+#[derive(Default)]
+pub struct TerminalFrontend;
+
+impl Frontend for TerminalFrontend {
+    fn run(&mut self, app: &mut App) -> Result<()> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend)?;
+
+        // Input is read every few milliseconds, but a frame is only drawn
+        // when something changed, and no more often than the frame rate
+        let mut drawn: Option<Instant> = None;
+        let mut dirty = true;
+        loop {
+            if event::poll(POLL_RATE)? {
+                match event::read()? {
+                    Event::Key(key) if key.kind == KeyEventKind::Press => {
+                        app.handle_key(key.code);
+                    }
+                    Event::Mouse(mouse)
+                        if mouse.kind == MouseEventKind::Down(MouseButton::Left) =>
+                    {
+                        app.handle_click(mouse.column, mouse.row);
+                    }
+                    Event::Resize(..) => dirty = true,
+                    _ => {}
+                }
+            }
+
+            app.tick();
+
+            if app.should_quit {
+                break;
+            }
+
+            dirty |= app.take_dirty();
+            if should_draw(drawn.map(|at| at.elapsed()), dirty) {
+                terminal.draw(|f| app.render(f))?;
+                drawn = Some(Instant::now());
+                dirty = false;
+            }
+        }
+
+        disable_raw_mode()?;
+        execute!(
+            terminal.backend_mut(),
+            LeaveAlternateScreen,
+            DisableMouseCapture
+        )?;
+        terminal.show_cursor()?;
+        Ok(())
+    }
+}
+
+/// Whether to draw, given how long ago the last frame was and whether
+/// anything has changed since.
+fn should_draw(since: Option<Duration>, dirty: bool) -> bool {
+    match since {
+        None => true,
+        Some(since) => (dirty && since >= FRAME_TIME) || since >= IDLE_REDRAW,
+    }
+}
+
+/// How long frames took to draw.
+#[derive(Clone, Copy, Debug)]
+pub struct FrameTimes {
+    pub frames: usize,
+    pub mean: Duration,
+    pub worst: Duration,
+}
+
+/// Draws the app `frames` times off screen at `width` by `height`, timing
+/// each.
+pub fn bench(app: &App, frames: usize, width: u16, height: u16) -> Result<FrameTimes> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    let (mut total, mut worst) = (Duration::ZERO, Duration::ZERO);
+    for _ in 0..frames {
+        let start = Instant::now();
+        terminal.draw(|f| app.render(f))?;
+        let took = start.elapsed();
+        total += took;
+        worst = worst.max(took);
+    }
+    Ok(FrameTimes {
+        frames,
+        mean: total / frames.max(1) as u32,
+        worst,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        let times = bench(&App::new(), 3, 80, 24).unwrap();
+        assert_eq!(times.frames, 3);
+        assert!(times.worst >= times.mean);
+    }
+
+    #[test]
+    fn test_should_draw() {
+        assert!(should_draw(None, false));
+        assert!(!should_draw(Some(Duration::from_millis(10)), true));
+        assert!(should_draw(Some(FRAME_TIME), true));
+        assert!(!should_draw(Some(Duration::from_millis(500)), false));
+        assert!(should_draw(Some(IDLE_REDRAW), false));
+    }
+}
@@ -0,0 +1,470 @@
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+use eframe::egui::{
+    self, Align2, Color32, FontId, Key, Margin, Pos2, Rect, RichText, Stroke, Vec2,
+};
+use ratatui::style::{Color, Style};
+
+use super::components::piano::{Piano, BLACK_KEY_SHARE};
+use super::frontend::{Frontend, TICK_RATE};
+use super::i18n::Text;
+use super::keymap::Command;
+use super::theme::Theme;
+use super::{App, Mode, Screen};
+use crate::theory::ProgressionNode;
+
+const BACKGROUND: Color32 = Color32::from_rgb(0x1e, 0x1e, 0x1e);
+const FOREGROUND: Color32 = Color32::from_rgb(0xd4, 0xd4, 0xd4);
+/// The sixteen named colours, as most terminals show them
+const NAMED: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 49, 49),
+    (13, 188, 121),
+    (229, 229, 16),
+    (36, 114, 200),
+    (188, 63, 188),
+    (17, 168, 205),
+    (229, 229, 229),
+    (102, 102, 102),
+    (241, 76, 76),
+    (35, 209, 139),
+    (245, 245, 67),
+    (59, 142, 234),
+    (214, 112, 214),
+    (41, 184, 219),
+    (255, 255, 255),
+];
+const PIANO_HEIGHT: f32 = 140.0;
+/// Black keys reach this far down the white ones
+const BLACK_KEY_LENGTH: f32 = 0.6;
+/// Levels of the suggestion tree: the chord and two rounds of branches
+const TREE_ROWS: f32 = 3.0;
+
+/// A desktop window showing the piano, suggestion tree and history drawn
+/// with egui from the same app state the terminal draws. Screens opened
+/// over them are only drawn in full in the terminal.
+pub struct GuiFrontend {
+    pub font_size: f32,
+}
+
+impl Default for GuiFrontend {
+    fn default() -> Self {
+        Self { font_size: 16.0 }
+    }
+}
+
+impl Frontend for GuiFrontend {
+    fn run(&mut self, app: &mut App) -> Result<()> {
+        let options = eframe::NativeOptions {
+            viewport: egui::ViewportBuilder::default()
+                .with_title("Chordvery")
+                .with_inner_size([1100.0, 720.0]),
+            ..Default::default()
+        };
+        let font = FontId::monospace(self.font_size);
+        eframe::run_native(
+            "Chordvery",
+            options,
+            Box::new(move |_| {
+                Ok(Box::new(Window {
+                    app,
+                    font,
+                    last_tick: Instant::now(),
+                }))
+            }),
+        )
+        .map_err(|e| anyhow!("Couldn't open a window: {}", e))
+    }
+}
+
+struct Window<'a> {
+    app: &'a mut App,
+    font: FontId,
+    last_tick: Instant,
+}
+
+impl eframe::App for Window<'_> {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        for event in ctx.input(|i| i.events.clone()) {
+            for key in key_codes(&event) {
+                self.app.handle_key(key);
+            }
+        }
+        if self.last_tick.elapsed() >= TICK_RATE {
+            self.app.tick();
+            self.last_tick = Instant::now();
+        }
+        if self.app.should_quit {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+
+        let (app, font) = (&*self.app, &self.font);
+        egui::TopBottomPanel::top("title")
+            .frame(panel())
+            .show(ctx, |ui| title(ui, app));
+        egui::TopBottomPanel::bottom("status")
+            .frame(panel())
+            .show(ctx, |ui| status(ui, app));
+        egui::TopBottomPanel::bottom("piano")
+            .frame(panel())
+            .exact_height(PIANO_HEIGHT)
+            .show(ctx, |ui| piano(ui, app));
+        egui::SidePanel::right("history")
+            .frame(panel())
+            .default_width(320.0)
+            .show(ctx, |ui| history(ui, app, font));
+        egui::CentralPanel::default()
+            .frame(panel())
+            .show(ctx, |ui| tree(ui, app, font));
+        if let Some(screen) = app.screen().filter(|s| s.is_overlay()) {
+            overlay(ctx, app, screen);
+        }
+        ctx.request_repaint_after(TICK_RATE);
+    }
+}
+
+fn panel() -> egui::Frame {
+    egui::Frame::NONE
+        .fill(BACKGROUND)
+        .inner_margin(Margin::same(8))
+}
+
+fn label(text: impl Into<String>, style: Style) -> RichText {
+    RichText::new(text).color(fg(style))
+}
+
+fn title(ui: &mut egui::Ui, app: &App) {
+    ui.horizontal(|ui| {
+        ui.label(label("Chordvery", Theme::title()).strong());
+        ui.label(label(app.tr(Text::Subtitle), Theme::text_dim()));
+    });
+}
+
+fn status(ui: &mut egui::Ui, app: &App) {
+    ui.horizontal_wrapped(|ui| {
+        let mode_style = match app.mode {
+            Mode::Discovery => Theme::mode_discovery(),
+            Mode::Jam => Theme::mode_jam(),
+        };
+        ui.label(label(
+            format!("{}:", app.tr(Text::Mode)),
+            Theme::status_bar(),
+        ));
+        ui.label(label(app.mode.name(), mode_style));
+        ui.separator();
+        let chord = app.current_chord.as_ref();
+        let name = chord.map_or_else(|| "—".to_string(), |c| app.chord_name(c));
+        ui.label(label(name, Theme::chord_name()).strong());
+        if let Some(key) = app.key() {
+            ui.separator();
+            ui.label(label(app.tr(Text::KeyOf), Theme::status_bar()));
+            ui.label(label(
+                key.display_in(app.spelling(), app.note_names),
+                Theme::text(),
+            ));
+        }
+        if let Some(message) = &app.message {
+            ui.separator();
+            ui.label(label(message, Theme::text()));
+        }
+    });
+}
+
+fn history(ui: &mut egui::Ui, app: &App, font: &FontId) {
+    ui.label(label(app.tr(Text::History), Theme::title()));
+    egui::ScrollArea::vertical()
+        .stick_to_bottom(true)
+        .show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (i, entry) in app.history.entries().iter().enumerate() {
+                    if let Some(modulation) = app.history.modulation_at(i) {
+                        ui.label(label(modulation.label(), Theme::modulation()).italics());
+                    } else if i > 0 {
+                        ui.label(label("→", Theme::text_dim()));
+                    }
+                    let mut name =
+                        label(&entry.name, Theme::chord_history(entry.age)).font(font.clone());
+                    if app.history.selected_index() == Some(i) {
+                        name = name.background_color(fg(Theme::tree_connector()));
+                    }
+                    ui.label(name);
+                }
+            });
+        });
+}
+
+fn tree(ui: &mut egui::Ui, app: &App, font: &FontId) {
+    ui.label(label(app.tr(Text::Suggestions), Theme::title()));
+    let area = ui.available_rect_before_wrap();
+    let painter = ui.painter_at(area);
+    match app.shown_tree() {
+        Some(node) => branch(&painter, app, font, &node, area, Theme::tree_current()),
+        None => {
+            painter.text(
+                area.center(),
+                Align2::CENTER_CENTER,
+                app.tr(Text::PlayAChord),
+                font.clone(),
+                fg(Theme::text_dim()),
+            );
+        }
+    }
+}
+
+/// Draws `node` at the top of `area`, and what may follow it in the left
+/// and right halves below, expected and surprise.
+fn branch(
+    painter: &egui::Painter,
+    app: &App,
+    font: &FontId,
+    node: &ProgressionNode,
+    area: Rect,
+    style: Style,
+) {
+    let row = painter.clip_rect().height() / TREE_ROWS;
+    let at = Pos2::new(area.center().x, area.top() + row / 2.0);
+    let gap = Vec2::new(0.0, font.size);
+    let halves = [
+        (&node.left, area.left(), Theme::tree_expected()),
+        (&node.right, area.center().x, Theme::tree_surprise()),
+    ];
+    for (child, left, style) in halves {
+        let Some(child) = child else {
+            continue;
+        };
+        let below = Rect::from_min_max(
+            Pos2::new(left, area.top() + row),
+            Pos2::new(left + area.width() / 2.0, area.bottom()),
+        );
+        let to = Pos2::new(below.center().x, below.top() + row / 2.0);
+        painter.line_segment(
+            [at + gap, to - gap],
+            Stroke::new(1.0, fg(Theme::tree_connector())),
+        );
+        branch(painter, app, font, child, below, style);
+    }
+
+    let style = match &app.quality_colors {
+        Some(colors) => colors.style(&node.chord, style),
+        None => style,
+    };
+    painter.text(
+        at,
+        Align2::CENTER_CENTER,
+        app.chord_name(&node.chord),
+        font.clone(),
+        fg(style),
+    );
+}
+
+fn piano(ui: &mut egui::Ui, app: &App) {
+    let ghost = app.ghost_notes();
+    let piano = app.player_piano(app.pressed_notes(), &app.current_chord, &ghost);
+    let area = ui.available_rect_before_wrap();
+    let painter = ui.painter_at(area);
+
+    let whites: Vec<u8> = piano.keys().filter(|&m| !Piano::is_black_key(m)).collect();
+    let width = area.width() / whites.len().max(1) as f32;
+    let outline = Stroke::new(1.0, BACKGROUND);
+    for (i, &midi) in whites.iter().enumerate() {
+        let key = Rect::from_min_size(
+            area.min + Vec2::new(i as f32 * width, 0.0),
+            Vec2::new(width, area.height()),
+        );
+        painter.rect_filled(key, 2.0, bg(piano.key_style(midi)));
+        painter.rect_stroke(key, 2.0, outline, egui::StrokeKind::Inside);
+    }
+    // Each black key sits across the line after the white key below it
+    for (i, &midi) in whites.iter().enumerate() {
+        let sharp = midi + 1;
+        if i + 1 >= whites.len() || !Piano::is_black_key(sharp) {
+            continue;
+        }
+        let size = Vec2::new(width * BLACK_KEY_SHARE, area.height() * BLACK_KEY_LENGTH);
+        let centre = area.left() + (i + 1) as f32 * width;
+        let key = Rect::from_min_size(Pos2::new(centre - size.x / 2.0, area.top()), size);
+        painter.rect_filled(key, 2.0, bg(piano.key_style(sharp)));
+    }
+}
+
+/// Screens are drawn in full in the terminal; the window shows the ones
+/// needed to carry on, and says how to close the rest.
+fn overlay(ctx: &egui::Context, app: &App, screen: &Screen) {
+    let window = |title: &str| {
+        egui::Window::new(title.to_string())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+    };
+    match screen {
+        Screen::Recovery(session) => {
+            window("Recover Session").show(ctx, |ui| {
+                ui.label(format!(
+                    "Found an unsaved session ({} chords).",
+                    session.len()
+                ));
+                ui.label(label("y Restore   n Discard", Theme::help_key()));
+            });
+        }
+        Screen::Help => {
+            window(app.tr(Text::Help)).show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("keys").striped(true).show(ui, |ui| {
+                        for command in Command::all() {
+                            let Some(keys) = app.keymap.describe(command) else {
+                                continue;
+                            };
+                            ui.label(label(keys, Theme::help_key()));
+                            ui.label(label(app.tr(Text::Command(command)), Theme::help_text()));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        }
+        _ => {
+            window("Chordvery").show(ctx, |ui| {
+                ui.label("This screen is only shown in the terminal. Esc closes it.");
+            });
+        }
+    }
+}
+
+/// What a window event means to the app. Typed text comes as characters,
+/// so only keys that don't type anything are passed on as keys.
+fn key_codes(event: &egui::Event) -> Vec<KeyCode> {
+    match event {
+        egui::Event::Text(text) => text.chars().map(KeyCode::Char).collect(),
+        egui::Event::Key {
+            key, pressed: true, ..
+        } => named_key(*key).into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn named_key(key: Key) -> Option<KeyCode> {
+    Some(match key {
+        Key::Escape => KeyCode::Esc,
+        Key::Tab => KeyCode::Tab,
+        Key::Enter => KeyCode::Enter,
+        Key::Backspace => KeyCode::Backspace,
+        Key::Delete => KeyCode::Delete,
+        Key::ArrowLeft => KeyCode::Left,
+        Key::ArrowRight => KeyCode::Right,
+        Key::ArrowUp => KeyCode::Up,
+        Key::ArrowDown => KeyCode::Down,
+        Key::Home => KeyCode::Home,
+        Key::End => KeyCode::End,
+        Key::PageUp => KeyCode::PageUp,
+        Key::PageDown => KeyCode::PageDown,
+        Key::F1 => KeyCode::F(1),
+        Key::F2 => KeyCode::F(2),
+        Key::F3 => KeyCode::F(3),
+        Key::F4 => KeyCode::F(4),
+        Key::F5 => KeyCode::F(5),
+        Key::F6 => KeyCode::F(6),
+        Key::F7 => KeyCode::F(7),
+        Key::F8 => KeyCode::F(8),
+        Key::F9 => KeyCode::F(9),
+        Key::F10 => KeyCode::F(10),
+        Key::F11 => KeyCode::F(11),
+        Key::F12 => KeyCode::F(12),
+        _ => return None,
+    })
+}
+
+/// A theme style's text colour in the window.
+fn fg(style: Style) -> Color32 {
+    style.fg.map_or(FOREGROUND, |c| color(c, FOREGROUND))
+}
+
+/// A theme style's background colour in the window.
+fn bg(style: Style) -> Color32 {
+    style.bg.map_or(BACKGROUND, |c| color(c, BACKGROUND))
+}
+
+fn color(color: Color, default: Color32) -> Color32 {
+    let index = match color {
+        Color::Reset => return default,
+        Color::Rgb(r, g, b) => return Color32::from_rgb(r, g, b),
+        Color::Indexed(i) => i,
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+    };
+    indexed(index)
+}
+
+/// The xterm 256-colour palette: the named colours, a 6×6×6 cube, then
+/// greys.
+fn indexed(index: u8) -> Color32 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=15 => {
+            let (r, g, b) = NAMED[index as usize];
+            Color32::from_rgb(r, g, b)
+        }
+        16..=231 => {
+            let i = (index - 16) as usize;
+            Color32::from_rgb(LEVELS[i / 36], LEVELS[i / 6 % 6], LEVELS[i % 6])
+        }
+        _ => Color32::from_gray(8 + 10 * (index - 232)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pressed(key: Key) -> egui::Event {
+        egui::Event::Key {
+            key,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: egui::Modifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn test_key_codes() {
+        assert_eq!(
+            key_codes(&egui::Event::Text("?g".to_string())),
+            [KeyCode::Char('?'), KeyCode::Char('g')]
+        );
+        assert_eq!(key_codes(&pressed(Key::Escape)), [KeyCode::Esc]);
+        assert_eq!(key_codes(&pressed(Key::F10)), [KeyCode::F(10)]);
+        // Typed keys arrive as text as well
+        assert!(key_codes(&pressed(Key::A)).is_empty());
+        assert!(key_codes(&pressed(Key::Space)).is_empty());
+    }
+
+    #[test]
+    fn test_colors() {
+        assert_eq!(color(Color::Reset, BACKGROUND), BACKGROUND);
+        assert_eq!(
+            color(Color::Yellow, BACKGROUND),
+            Color32::from_rgb(229, 229, 16)
+        );
+        assert_eq!(indexed(196), Color32::from_rgb(255, 0, 0));
+        assert_eq!(indexed(16), Color32::BLACK);
+        assert_eq!(indexed(244), Color32::from_gray(128));
+        assert_eq!(fg(Theme::white_key()), Color32::BLACK);
+        assert_eq!(bg(Theme::text()), BACKGROUND);
+    }
+}
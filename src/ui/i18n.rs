@@ -0,0 +1,1418 @@
+use std::fmt::Display;
+
+use serde::Deserialize;
+
+use crate::session::sketch::SECTION_NAMES;
+use crate::theory::NoteNames;
+use crate::ui::keymap::{Category, Command};
+
+/// The language UI text is shown in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+    Italian,
+}
+
+/// A translated piece of UI text. Messages with a `{}` in them are
+/// filled in by `Language::fill`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Text {
+    Subtitle,
+    Suggestions,
+    History,
+    SoundsLike,
+    CouldBecome,
+    SpelledOut,
+    PlayAChord,
+    Mode,
+    Playing,
+    Extended,
+    Creativity,
+    KeyOf,
+    Help,
+    PracticeGoals,
+    /// What a command does, as the help puts it
+    Command(Command),
+    /// A heading in the help
+    Category(Category),
+
+    EventLogDisabled,
+    BluesOff,
+    BluesFormIn,
+    BluesFirstChord,
+    KeySetTo,
+    StatsNotSaved,
+    SwitchedTo,
+    ConfigIgnored,
+    AutosaveOff,
+    NoUserDirectories,
+    Exported,
+    ExportFailed,
+    TextExportFailed,
+    SuggestionProfile,
+    GrooveIs,
+    GrooveOff,
+    PlayToAudition,
+    ExpectedBranch,
+    SurpriseBranch,
+    ArpPattern,
+    ArpRate,
+    NoOutputForDrills,
+    IntervalRight,
+    IntervalWrong,
+    PlayToExplore,
+    PlayForGrips,
+    LatchNeedsInput,
+    LatchOn,
+    LatchOff,
+    SmoothingOn,
+    SmoothingOff,
+    MelodyOn,
+    MelodyOff,
+    PadsOn,
+    PadsOff,
+    AutoTempoOn,
+    AutoTempoOff,
+    TimeSignatureFromNextLoop,
+    TimeSignatureIs,
+    SetTempoFirst,
+    HumanizeOff,
+    HumanizeBy,
+    NoOutputForAlong,
+    AlongFinished,
+    PlayToPlan,
+    Planned,
+    UnknownChord,
+    Pinned,
+    NothingSinceSection,
+    Captured,
+    PlayToReharmonize,
+    SketchReplaced,
+    NoSections,
+    QuantizeFeel,
+    NoSessionsDirectory,
+    ResumedSession,
+    MarkedToCompare,
+    StillRecording,
+    ConfirmDelete,
+    Deleted,
+    DeleteFailed,
+    ConnectToCalibrate,
+    HardNotesNotLouder,
+    CurveNotSaved,
+    CurveCalibrated,
+    NoScripting,
+    Unmapped,
+    MappedTo,
+    NotSaved,
+    DictationStopped,
+    DictationStatsNotSaved,
+    DictationRight,
+    DictationWrong,
+    PlayThenCompare,
+    NoUserToLearnFor,
+    ConfirmReset,
+    LearningReset,
+    ResetFailed,
+    NoModelFile,
+    ModelReloaded,
+    ModelReloadFailed,
+    HarmonizingOn,
+    HarmonizingOff,
+    MinorTree,
+    CapoOff,
+    CapoOn,
+    SingingIn,
+    TranspositionOff,
+    BookmarkNote,
+    Bookmarked,
+    BookmarkedUnlogged,
+    OffThePlan,
+    SuggestionProfileAction,
+    FirstQuality,
+    CompleteClean,
+    CompleteWithMisses,
+    Streak,
+    GoalMet,
+}
+
+impl Language {
+    pub fn all() -> &'static [Language] {
+        &[
+            Language::English,
+            Language::German,
+            Language::French,
+            Language::Spanish,
+            Language::Italian,
+        ]
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::French => "fr",
+            Language::Spanish => "es",
+            Language::Italian => "it",
+        }
+    }
+
+    /// Accepts a language code or a locale such as `de_AT.UTF-8`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim().get(..2)?.to_lowercase();
+        Self::all().iter().copied().find(|l| l.code() == code)
+    }
+
+    /// The language of the environment's locale, English if unsupported.
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Self::from_code(&locale))
+            .unwrap_or_default()
+    }
+
+    /// How notes are usually named where the language is spoken.
+    pub fn note_names(&self) -> NoteNames {
+        match self {
+            Language::English => NoteNames::English,
+            Language::German => NoteNames::German,
+            Language::French | Language::Spanish | Language::Italian => NoteNames::Solfege,
+        }
+    }
+
+    pub fn text(&self, text: Text) -> &'static str {
+        use Language::*;
+        match (text, self) {
+            (Text::Subtitle, English) => "─ Chord Discovery Tool",
+            (Text::Subtitle, German) => "─ Akkorde entdecken",
+            (Text::Subtitle, French) => "─ Découverte d'accords",
+            (Text::Subtitle, Spanish) => "─ Descubre acordes",
+            (Text::Subtitle, Italian) => "─ Scopri gli accordi",
+
+            (Text::Suggestions, English) => "Suggestions",
+            (Text::Suggestions, German) => "Vorschläge",
+            (Text::Suggestions, French) => "Suggestions",
+            (Text::Suggestions, Spanish) => "Sugerencias",
+            (Text::Suggestions, Italian) => "Suggerimenti",
+
+            (Text::History, English) => "History",
+            (Text::History, German) => "Verlauf",
+            (Text::History, French) => "Historique",
+            (Text::History, Spanish) => "Historial",
+            (Text::History, Italian) => "Cronologia",
+
+            (Text::SoundsLike, English) => "Sounds Like",
+            (Text::SoundsLike, German) => "Klingt wie",
+            (Text::SoundsLike, French) => "Ressemble à",
+            (Text::SoundsLike, Spanish) => "Suena como",
+            (Text::SoundsLike, Italian) => "Suona come",
+
+            (Text::CouldBecome, English) => "Could Become",
+            (Text::CouldBecome, German) => "Könnte werden",
+            (Text::CouldBecome, French) => "Pourrait devenir",
+            (Text::CouldBecome, Spanish) => "Podría ser",
+            (Text::CouldBecome, Italian) => "Potrebbe diventare",
+
+            (Text::SpelledOut, English) => "Spelled Out",
+            (Text::SpelledOut, German) => "Aufgeschlüsselt",
+            (Text::SpelledOut, French) => "Note par note",
+            (Text::SpelledOut, Spanish) => "Nota por nota",
+            (Text::SpelledOut, Italian) => "Nota per nota",
+
+            (Text::PlayAChord, English) => "Play a chord...",
+            (Text::PlayAChord, German) => "Spiel einen Akkord...",
+            (Text::PlayAChord, French) => "Jouez un accord...",
+            (Text::PlayAChord, Spanish) => "Toca un acorde...",
+            (Text::PlayAChord, Italian) => "Suona un accordo...",
+
+            (Text::Mode, English) => "Mode",
+            (Text::Mode, German) => "Modus",
+            (Text::Mode, French) => "Mode",
+            (Text::Mode, Spanish) => "Modo",
+            (Text::Mode, Italian) => "Modalità",
+
+            (Text::Playing, English) => "Playing",
+            (Text::Playing, German) => "Gespielt",
+            (Text::Playing, French) => "Joué",
+            (Text::Playing, Spanish) => "Tocando",
+            (Text::Playing, Italian) => "Suonato",
+
+            (Text::Extended, English) => "Extended",
+            (Text::Extended, German) => "Erweitert",
+            (Text::Extended, French) => "Étendus",
+            (Text::Extended, Spanish) => "Extendidos",
+            (Text::Extended, Italian) => "Estesi",
+
+            (Text::Creativity, English) => "Creativity",
+            (Text::Creativity, German) => "Kreativität",
+            (Text::Creativity, French) => "Créativité",
+            (Text::Creativity, Spanish) => "Creatividad",
+            (Text::Creativity, Italian) => "Creatività",
+
+            (Text::KeyOf, English) => "Key of",
+            (Text::KeyOf, German) => "Tonart",
+            (Text::KeyOf, French) => "Tonalité",
+            (Text::KeyOf, Spanish) => "Tonalidad",
+            (Text::KeyOf, Italian) => "Tonalità",
+
+            (Text::Help, English) => "Help",
+            (Text::Help, German) => "Hilfe",
+            (Text::Help, French) => "Aide",
+            (Text::Help, Spanish) => "Ayuda",
+            (Text::Help, Italian) => "Aiuto",
+
+            (Text::PracticeGoals, English) => "Practice goals",
+            (Text::PracticeGoals, German) => "Übungsziele",
+            (Text::PracticeGoals, French) => "Objectifs",
+            (Text::PracticeGoals, Spanish) => "Objetivos",
+            (Text::PracticeGoals, Italian) => "Obiettivi",
+
+            (Text::Command(command), _) => self.describe(command),
+
+            (Text::Category(category), English) => category.name(),
+            (Text::Category(Category::General), German) => "Allgemein",
+            (Text::Category(Category::General), French) => "Général",
+            (Text::Category(Category::General), Spanish) => "General",
+            (Text::Category(Category::General), Italian) => "Generale",
+
+            (Text::Category(Category::Chords), German) => "Akkorde",
+            (Text::Category(Category::Chords), French) => "Accords",
+            (Text::Category(Category::Chords), Spanish) => "Acordes",
+            (Text::Category(Category::Chords), Italian) => "Accordi",
+
+            (Text::Category(Category::Suggestions), German) => "Vorschläge",
+            (Text::Category(Category::Suggestions), French) => "Suggestions",
+            (Text::Category(Category::Suggestions), Spanish) => "Sugerencias",
+            (Text::Category(Category::Suggestions), Italian) => "Suggerimenti",
+
+            (Text::Category(Category::History), German) => "Verlauf und Sitzungen",
+            (Text::Category(Category::History), French) => "Historique et sessions",
+            (Text::Category(Category::History), Spanish) => "Historial y sesiones",
+            (Text::Category(Category::History), Italian) => "Cronologia e sessioni",
+
+            (Text::Category(Category::Playing), German) => "Spielen",
+            (Text::Category(Category::Playing), French) => "Jeu",
+            (Text::Category(Category::Playing), Spanish) => "Tocar",
+            (Text::Category(Category::Playing), Italian) => "Suonare",
+
+            (Text::Category(Category::Practice), German) => "Üben",
+            (Text::Category(Category::Practice), French) => "Entraînement",
+            (Text::Category(Category::Practice), Spanish) => "Práctica",
+            (Text::Category(Category::Practice), Italian) => "Esercizio",
+
+            (Text::Category(Category::Guitar), German) => "Gitarre",
+            (Text::Category(Category::Guitar), French) => "Guitare",
+            (Text::Category(Category::Guitar), Spanish) => "Guitarra",
+            (Text::Category(Category::Guitar), Italian) => "Chitarra",
+
+            (Text::Category(Category::Midi), German) => "MIDI-Eingang",
+            (Text::Category(Category::Midi), French) => "Entrée MIDI",
+            (Text::Category(Category::Midi), Spanish) => "Entrada MIDI",
+            (Text::Category(Category::Midi), Italian) => "Ingresso MIDI",
+
+            (Text::EventLogDisabled, English) => "Event log disabled: {}",
+            (Text::EventLogDisabled, German) => "Ereignisprotokoll aus: {}",
+            (Text::EventLogDisabled, French) => "Journal des événements désactivé : {}",
+            (Text::EventLogDisabled, Spanish) => "Registro de eventos desactivado: {}",
+            (Text::EventLogDisabled, Italian) => "Registro eventi disattivato: {}",
+
+            (Text::BluesOff, English) => "Blues off",
+            (Text::BluesOff, German) => "Blues aus",
+            (Text::BluesOff, French) => "Blues désactivé",
+            (Text::BluesOff, Spanish) => "Blues desactivado",
+            (Text::BluesOff, Italian) => "Blues disattivato",
+
+            (Text::BluesFormIn, English) => "{}: the form starts on the I chord",
+            (Text::BluesFormIn, German) => "{}: die Form beginnt auf der I",
+            (Text::BluesFormIn, French) => "{} : la grille commence sur l'accord I",
+            (Text::BluesFormIn, Spanish) => "{}: la forma empieza en el acorde I",
+            (Text::BluesFormIn, Italian) => "{}: la forma parte dall'accordo I",
+
+            (Text::BluesFirstChord, English) => "Blues: the first chord sets the key and starts the form",
+            (Text::BluesFirstChord, German) => "Blues: der erste Akkord legt die Tonart fest und beginnt die Form",
+            (Text::BluesFirstChord, French) => "Blues : le premier accord fixe la tonalité et lance la grille",
+            (Text::BluesFirstChord, Spanish) => "Blues: el primer acorde fija la tonalidad y empieza la forma",
+            (Text::BluesFirstChord, Italian) => "Blues: il primo accordo fissa la tonalità e avvia la forma",
+
+            (Text::KeySetTo, English) => "Key set to {}",
+            (Text::KeySetTo, German) => "Tonart auf {} gesetzt",
+            (Text::KeySetTo, French) => "Tonalité réglée sur {}",
+            (Text::KeySetTo, Spanish) => "Tonalidad fijada en {}",
+            (Text::KeySetTo, Italian) => "Tonalità impostata su {}",
+
+            (Text::StatsNotSaved, English) => "Could not save {}'s stats: {}",
+            (Text::StatsNotSaved, German) => "Statistik von {} nicht gespeichert: {}",
+            (Text::StatsNotSaved, French) => "Statistiques de {} non enregistrées : {}",
+            (Text::StatsNotSaved, Spanish) => "No se pudieron guardar las estadísticas de {}: {}",
+            (Text::StatsNotSaved, Italian) => "Impossibile salvare le statistiche di {}: {}",
+
+            (Text::SwitchedTo, English) => "Switched to {}",
+            (Text::SwitchedTo, German) => "Gewechselt zu {}",
+            (Text::SwitchedTo, French) => "Passé à {}",
+            (Text::SwitchedTo, Spanish) => "Cambiado a {}",
+            (Text::SwitchedTo, Italian) => "Passato a {}",
+
+            (Text::ConfigIgnored, English) => "{}'s config ignored: {}",
+            (Text::ConfigIgnored, German) => "Konfiguration von {} ignoriert: {}",
+            (Text::ConfigIgnored, French) => "Configuration de {} ignorée : {}",
+            (Text::ConfigIgnored, Spanish) => "Configuración de {} ignorada: {}",
+            (Text::ConfigIgnored, Italian) => "Configurazione di {} ignorata: {}",
+
+            (Text::AutosaveOff, English) => "Autosave off: {}",
+            (Text::AutosaveOff, German) => "Automatisches Speichern aus: {}",
+            (Text::AutosaveOff, French) => "Sauvegarde automatique désactivée : {}",
+            (Text::AutosaveOff, Spanish) => "Autoguardado desactivado: {}",
+            (Text::AutosaveOff, Italian) => "Salvataggio automatico disattivato: {}",
+
+            (Text::NoUserDirectories, English) => "No user directories",
+            (Text::NoUserDirectories, German) => "Keine Benutzerverzeichnisse",
+            (Text::NoUserDirectories, French) => "Aucun dossier utilisateur",
+            (Text::NoUserDirectories, Spanish) => "No hay carpetas de usuario",
+            (Text::NoUserDirectories, Italian) => "Nessuna cartella utente",
+
+            (Text::Exported, English) => "Exported {}",
+            (Text::Exported, German) => "{} exportiert",
+            (Text::Exported, French) => "{} exporté",
+            (Text::Exported, Spanish) => "{} exportado",
+            (Text::Exported, Italian) => "{} esportato",
+
+            (Text::ExportFailed, English) => "{} export failed: {}",
+            (Text::ExportFailed, German) => "{}-Export fehlgeschlagen: {}",
+            (Text::ExportFailed, French) => "Échec de l'export {} : {}",
+            (Text::ExportFailed, Spanish) => "Falló la exportación {}: {}",
+            (Text::ExportFailed, Italian) => "Esportazione {} non riuscita: {}",
+
+            (Text::TextExportFailed, English) => "Text export failed: {}",
+            (Text::TextExportFailed, German) => "Textexport fehlgeschlagen: {}",
+            (Text::TextExportFailed, French) => "Échec de l'export texte : {}",
+            (Text::TextExportFailed, Spanish) => "Falló la exportación de texto: {}",
+            (Text::TextExportFailed, Italian) => "Esportazione del testo non riuscita: {}",
+
+            (Text::SuggestionProfile, English) => "Suggestion profile: {}",
+            (Text::SuggestionProfile, German) => "Vorschlagsprofil: {}",
+            (Text::SuggestionProfile, French) => "Profil de suggestions : {}",
+            (Text::SuggestionProfile, Spanish) => "Perfil de sugerencias: {}",
+            (Text::SuggestionProfile, Italian) => "Profilo dei suggerimenti: {}",
+
+            (Text::GrooveIs, English) => "Groove: {}",
+            (Text::GrooveIs, German) => "Groove: {}",
+            (Text::GrooveIs, French) => "Groove : {}",
+            (Text::GrooveIs, Spanish) => "Groove: {}",
+            (Text::GrooveIs, Italian) => "Groove: {}",
+
+            (Text::GrooveOff, English) => "Groove off: chords are held",
+            (Text::GrooveOff, German) => "Groove aus: Akkorde werden gehalten",
+            (Text::GrooveOff, French) => "Groove désactivé : les accords sont tenus",
+            (Text::GrooveOff, Spanish) => "Groove desactivado: los acordes se mantienen",
+            (Text::GrooveOff, Italian) => "Groove disattivato: gli accordi sono tenuti",
+
+            (Text::PlayToAudition, English) => "Play a chord to hear what could follow it",
+            (Text::PlayToAudition, German) => "Spiel einen Akkord, um zu hören, was folgen könnte",
+            (Text::PlayToAudition, French) => "Jouez un accord pour entendre ce qui pourrait suivre",
+            (Text::PlayToAudition, Spanish) => "Toca un acorde para oír lo que podría seguir",
+            (Text::PlayToAudition, Italian) => "Suona un accordo per sentire cosa potrebbe seguire",
+
+            (Text::ExpectedBranch, English) => "Expected: {}",
+            (Text::ExpectedBranch, German) => "Erwartet: {}",
+            (Text::ExpectedBranch, French) => "Attendu : {}",
+            (Text::ExpectedBranch, Spanish) => "Esperado: {}",
+            (Text::ExpectedBranch, Italian) => "Previsto: {}",
+
+            (Text::SurpriseBranch, English) => "Surprise: {}",
+            (Text::SurpriseBranch, German) => "Überraschung: {}",
+            (Text::SurpriseBranch, French) => "Surprise : {}",
+            (Text::SurpriseBranch, Spanish) => "Sorpresa: {}",
+            (Text::SurpriseBranch, Italian) => "Sorpresa: {}",
+
+            (Text::ArpPattern, English) => "Arpeggiator: {}",
+            (Text::ArpPattern, German) => "Arpeggiator: {}",
+            (Text::ArpPattern, French) => "Arpégiateur : {}",
+            (Text::ArpPattern, Spanish) => "Arpegiador: {}",
+            (Text::ArpPattern, Italian) => "Arpeggiatore: {}",
+
+            (Text::ArpRate, English) => "Arpeggiator rate: {}",
+            (Text::ArpRate, German) => "Arpeggiator-Tempo: {}",
+            (Text::ArpRate, French) => "Vitesse de l'arpégiateur : {}",
+            (Text::ArpRate, Spanish) => "Velocidad del arpegiador: {}",
+            (Text::ArpRate, Italian) => "Velocità dell'arpeggiatore: {}",
+
+            (Text::NoOutputForDrills, English) => "Connect a MIDI output (--out-port) to hear drills",
+            (Text::NoOutputForDrills, German) => "Schließ einen MIDI-Ausgang an (--out-port), um die Übungen zu hören",
+            (Text::NoOutputForDrills, French) => "Connectez une sortie MIDI (--out-port) pour entendre les exercices",
+            (Text::NoOutputForDrills, Spanish) => "Conecta una salida MIDI (--out-port) para oír los ejercicios",
+            (Text::NoOutputForDrills, Italian) => "Collega un'uscita MIDI (--out-port) per sentire gli esercizi",
+
+            (Text::IntervalRight, English) => "✓ {} — Space for next (level {})",
+            (Text::IntervalRight, German) => "✓ {} — Leertaste für das nächste (Stufe {})",
+            (Text::IntervalRight, French) => "✓ {} — Espace pour la suite (niveau {})",
+            (Text::IntervalRight, Spanish) => "✓ {} — Espacio para el siguiente (nivel {})",
+            (Text::IntervalRight, Italian) => "✓ {} — Spazio per il prossimo (livello {})",
+
+            (Text::IntervalWrong, English) => "✗ It was {} — Space for next (level {})",
+            (Text::IntervalWrong, German) => "✗ Es war {} — Leertaste für das nächste (Stufe {})",
+            (Text::IntervalWrong, French) => "✗ C'était {} — Espace pour la suite (niveau {})",
+            (Text::IntervalWrong, Spanish) => "✗ Era {} — Espacio para el siguiente (nivel {})",
+            (Text::IntervalWrong, Italian) => "✗ Era {} — Spazio per il prossimo (livello {})",
+
+            (Text::PlayToExplore, English) => "Play a chord to explore its voicings",
+            (Text::PlayToExplore, German) => "Spiel einen Akkord, um seine Voicings zu erkunden",
+            (Text::PlayToExplore, French) => "Jouez un accord pour explorer ses voicings",
+            (Text::PlayToExplore, Spanish) => "Toca un acorde para explorar sus voicings",
+            (Text::PlayToExplore, Italian) => "Suona un accordo per esplorarne i voicing",
+
+            (Text::PlayForGrips, English) => "Play a chord to see its guitar grips",
+            (Text::PlayForGrips, German) => "Spiel einen Akkord, um seine Gitarrengriffe zu sehen",
+            (Text::PlayForGrips, French) => "Jouez un accord pour voir ses doigtés de guitare",
+            (Text::PlayForGrips, Spanish) => "Toca un acorde para ver sus posiciones de guitarra",
+            (Text::PlayForGrips, Italian) => "Suona un accordo per vederne le diteggiature per chitarra",
+
+            (Text::LatchNeedsInput, English) => "Latch needs a MIDI input",
+            (Text::LatchNeedsInput, German) => "Halten braucht einen MIDI-Eingang",
+            (Text::LatchNeedsInput, French) => "Le maintien nécessite une entrée MIDI",
+            (Text::LatchNeedsInput, Spanish) => "Mantener necesita una entrada MIDI",
+            (Text::LatchNeedsInput, Italian) => "Tenere richiede un ingresso MIDI",
+
+            (Text::LatchOn, English) => "Latch on: notes hold until a new chord starts",
+            (Text::LatchOn, German) => "Halten an: Noten klingen bis zum nächsten Akkord",
+            (Text::LatchOn, French) => "Maintien activé : les notes tiennent jusqu'au prochain accord",
+            (Text::LatchOn, Spanish) => "Mantener activado: las notas suenan hasta un nuevo acorde",
+            (Text::LatchOn, Italian) => "Tenere attivo: le note restano fino a un nuovo accordo",
+
+            (Text::LatchOff, English) => "Latch off",
+            (Text::LatchOff, German) => "Halten aus",
+            (Text::LatchOff, French) => "Maintien désactivé",
+            (Text::LatchOff, Spanish) => "Mantener desactivado",
+            (Text::LatchOff, Italian) => "Tenere disattivato",
+
+            (Text::SmoothingOn, English) => "Smoothing on: passing tones won't change the chord",
+            (Text::SmoothingOn, German) => "Glätten an: Durchgangstöne ändern den Akkord nicht",
+            (Text::SmoothingOn, French) => "Lissage activé : les notes de passage ne changent pas l'accord",
+            (Text::SmoothingOn, Spanish) => "Suavizado activado: las notas de paso no cambian el acorde",
+            (Text::SmoothingOn, Italian) => "Attenuazione attiva: le note di passaggio non cambiano l'accordo",
+
+            (Text::SmoothingOff, English) => "Smoothing off",
+            (Text::SmoothingOff, German) => "Glätten aus",
+            (Text::SmoothingOff, French) => "Lissage désactivé",
+            (Text::SmoothingOff, Spanish) => "Suavizado desactivado",
+            (Text::SmoothingOff, Italian) => "Attenuazione disattivata",
+
+            (Text::MelodyOn, English) => "Melody separation on: lone top notes are left out of the chord",
+            (Text::MelodyOn, German) => "Melodietrennung an: einzelne hohe Noten zählen nicht zum Akkord",
+            (Text::MelodyOn, French) => "Séparation de la mélodie activée : les notes aiguës isolées sont exclues de l'accord",
+            (Text::MelodyOn, Spanish) => "Separación de melodía activada: las notas agudas sueltas quedan fuera del acorde",
+            (Text::MelodyOn, Italian) => "Separazione della melodia attiva: le note acute isolate restano fuori dall'accordo",
+
+            (Text::MelodyOff, English) => "Melody separation off",
+            (Text::MelodyOff, German) => "Melodietrennung aus",
+            (Text::MelodyOff, French) => "Séparation de la mélodie désactivée",
+            (Text::MelodyOff, Spanish) => "Separación de melodía desactivada",
+            (Text::MelodyOff, Italian) => "Separazione della melodia disattivata",
+
+            (Text::PadsOn, English) => "Pads: 1-8 play the chords of {}",
+            (Text::PadsOn, German) => "Pads: 1-8 spielen die Akkorde von {}",
+            (Text::PadsOn, French) => "Pads : 1-8 jouent les accords de {}",
+            (Text::PadsOn, Spanish) => "Pads: 1-8 tocan los acordes de {}",
+            (Text::PadsOn, Italian) => "Pad: 1-8 suonano gli accordi di {}",
+
+            (Text::PadsOff, English) => "Pads off",
+            (Text::PadsOff, German) => "Pads aus",
+            (Text::PadsOff, French) => "Pads désactivés",
+            (Text::PadsOff, Spanish) => "Pads desactivados",
+            (Text::PadsOff, Italian) => "Pad disattivati",
+
+            (Text::AutoTempoOn, English) => "Estimating tempo from chord changes",
+            (Text::AutoTempoOn, German) => "Tempo wird aus Akkordwechseln geschätzt",
+            (Text::AutoTempoOn, French) => "Tempo estimé d'après les changements d'accords",
+            (Text::AutoTempoOn, Spanish) => "Estimando el tempo por los cambios de acorde",
+            (Text::AutoTempoOn, Italian) => "Stima del tempo dai cambi di accordo",
+
+            (Text::AutoTempoOff, English) => "Tempo estimation off",
+            (Text::AutoTempoOff, German) => "Tempo-Schätzung aus",
+            (Text::AutoTempoOff, French) => "Estimation du tempo désactivée",
+            (Text::AutoTempoOff, Spanish) => "Estimación del tempo desactivada",
+            (Text::AutoTempoOff, Italian) => "Stima del tempo disattivata",
+
+            (Text::TimeSignatureFromNextLoop, English) => "Time signature {} from the next loop",
+            (Text::TimeSignatureFromNextLoop, German) => "Taktart {} ab dem nächsten Loop",
+            (Text::TimeSignatureFromNextLoop, French) => "Mesure {} à partir de la prochaine boucle",
+            (Text::TimeSignatureFromNextLoop, Spanish) => "Compás {} desde el próximo loop",
+            (Text::TimeSignatureFromNextLoop, Italian) => "Tempo {} dal prossimo loop",
+
+            (Text::TimeSignatureIs, English) => "Time signature {}",
+            (Text::TimeSignatureIs, German) => "Taktart {}",
+            (Text::TimeSignatureIs, French) => "Mesure {}",
+            (Text::TimeSignatureIs, Spanish) => "Compás {}",
+            (Text::TimeSignatureIs, Italian) => "Tempo {}",
+
+            (Text::SetTempoFirst, English) => "Set a tempo first (tap t or --bpm)",
+            (Text::SetTempoFirst, German) => "Erst ein Tempo setzen (t tippen oder --bpm)",
+            (Text::SetTempoFirst, French) => "Réglez d'abord un tempo (tapez t ou --bpm)",
+            (Text::SetTempoFirst, Spanish) => "Primero fija un tempo (pulsa t o --bpm)",
+            (Text::SetTempoFirst, Italian) => "Prima imposta un tempo (premi t o --bpm)",
+
+            (Text::HumanizeOff, English) => "Humanize off",
+            (Text::HumanizeOff, German) => "Humanisieren aus",
+            (Text::HumanizeOff, French) => "Humanisation désactivée",
+            (Text::HumanizeOff, Spanish) => "Humanización desactivada",
+            (Text::HumanizeOff, Italian) => "Umanizzazione disattivata",
+
+            (Text::HumanizeBy, English) => "Humanize {}%",
+            (Text::HumanizeBy, German) => "Humanisieren {} %",
+            (Text::HumanizeBy, French) => "Humanisation {} %",
+            (Text::HumanizeBy, Spanish) => "Humanización {} %",
+            (Text::HumanizeBy, Italian) => "Umanizzazione {}%",
+
+            (Text::NoOutputForAlong, English) => "No MIDI output: showing the file's chords silently",
+            (Text::NoOutputForAlong, German) => "Kein MIDI-Ausgang: die Akkorde der Datei werden stumm gezeigt",
+            (Text::NoOutputForAlong, French) => "Pas de sortie MIDI : les accords du fichier s'affichent sans son",
+            (Text::NoOutputForAlong, Spanish) => "Sin salida MIDI: los acordes del archivo se muestran en silencio",
+            (Text::NoOutputForAlong, Italian) => "Nessuna uscita MIDI: gli accordi del file sono mostrati in silenzio",
+
+            (Text::AlongFinished, English) => "{} finished: {}/{} chords matched",
+            (Text::AlongFinished, German) => "{} beendet: {}/{} Akkorde getroffen",
+            (Text::AlongFinished, French) => "{} terminé : {}/{} accords justes",
+            (Text::AlongFinished, Spanish) => "{} terminado: {}/{} acordes acertados",
+            (Text::AlongFinished, Italian) => "{} finito: {}/{} accordi azzeccati",
+
+            (Text::PlayToPlan, English) => "Play a chord to plan from",
+            (Text::PlayToPlan, German) => "Spiel einen Akkord als Ausgangspunkt für den Plan",
+            (Text::PlayToPlan, French) => "Jouez un accord à partir duquel planifier",
+            (Text::PlayToPlan, Spanish) => "Toca un acorde desde el que planear",
+            (Text::PlayToPlan, Italian) => "Suona un accordo da cui pianificare",
+
+            (Text::Planned, English) => "Planned {}",
+            (Text::Planned, German) => "{} geplant",
+            (Text::Planned, French) => "{} prévu",
+            (Text::Planned, Spanish) => "{} planeado",
+            (Text::Planned, Italian) => "{} pianificato",
+
+            (Text::UnknownChord, English) => "Unknown chord '{}'",
+            (Text::UnknownChord, German) => "Unbekannter Akkord '{}'",
+            (Text::UnknownChord, French) => "Accord inconnu '{}'",
+            (Text::UnknownChord, Spanish) => "Acorde desconocido '{}'",
+            (Text::UnknownChord, Italian) => "Accordo sconosciuto '{}'",
+
+            (Text::Pinned, English) => "Pinned {}",
+            (Text::Pinned, German) => "{} angeheftet",
+            (Text::Pinned, French) => "{} épinglé",
+            (Text::Pinned, Spanish) => "{} fijado",
+            (Text::Pinned, Italian) => "{} fissato",
+
+            (Text::NothingSinceSection, English) => "Nothing played since the last section",
+            (Text::NothingSinceSection, German) => "Seit dem letzten Teil nichts gespielt",
+            (Text::NothingSinceSection, French) => "Rien de joué depuis la dernière section",
+            (Text::NothingSinceSection, Spanish) => "Nada tocado desde la última sección",
+            (Text::NothingSinceSection, Italian) => "Niente suonato dall'ultima sezione",
+
+            (Text::Captured, English) => "Captured {}: {} chords",
+            (Text::Captured, German) => "{} aufgenommen: {} Akkorde",
+            (Text::Captured, French) => "{} capturé : {} accords",
+            (Text::Captured, Spanish) => "{} capturado: {} acordes",
+            (Text::Captured, Italian) => "{} catturato: {} accordi",
+
+            (Text::PlayToReharmonize, English) => "Play some chords to reharmonize",
+            (Text::PlayToReharmonize, German) => "Spiel ein paar Akkorde zum Reharmonisieren",
+            (Text::PlayToReharmonize, French) => "Jouez quelques accords à réharmoniser",
+            (Text::PlayToReharmonize, Spanish) => "Toca algunos acordes para rearmonizar",
+            (Text::PlayToReharmonize, Italian) => "Suona qualche accordo da riarmonizzare",
+
+            (Text::SketchReplaced, English) => "Sketch replaced with {}",
+            (Text::SketchReplaced, German) => "Skizze ersetzt durch {}",
+            (Text::SketchReplaced, French) => "Esquisse remplacée par {}",
+            (Text::SketchReplaced, Spanish) => "Boceto reemplazado por {}",
+            (Text::SketchReplaced, Italian) => "Bozza sostituita con {}",
+
+            (Text::NoSections, English) => "No sections to export",
+            (Text::NoSections, German) => "Keine Teile zum Exportieren",
+            (Text::NoSections, French) => "Aucune section à exporter",
+            (Text::NoSections, Spanish) => "No hay secciones que exportar",
+            (Text::NoSections, Italian) => "Nessuna sezione da esportare",
+
+            (Text::QuantizeFeel, English) => "Quantize feel: {}",
+            (Text::QuantizeFeel, German) => "Quantisierungsgefühl: {}",
+            (Text::QuantizeFeel, French) => "Feeling de quantification : {}",
+            (Text::QuantizeFeel, Spanish) => "Sensación de cuantización: {}",
+            (Text::QuantizeFeel, Italian) => "Feeling della quantizzazione: {}",
+
+            (Text::NoSessionsDirectory, English) => "No sessions directory",
+            (Text::NoSessionsDirectory, German) => "Kein Sitzungsverzeichnis",
+            (Text::NoSessionsDirectory, French) => "Aucun dossier de sessions",
+            (Text::NoSessionsDirectory, Spanish) => "No hay carpeta de sesiones",
+            (Text::NoSessionsDirectory, Italian) => "Nessuna cartella delle sessioni",
+
+            (Text::ResumedSession, English) => "Resumed session from {}",
+            (Text::ResumedSession, German) => "Sitzung vom {} fortgesetzt",
+            (Text::ResumedSession, French) => "Session du {} reprise",
+            (Text::ResumedSession, Spanish) => "Sesión del {} reanudada",
+            (Text::ResumedSession, Italian) => "Sessione del {} ripresa",
+
+            (Text::MarkedToCompare, English) => "Marked: press c on another take to compare",
+            (Text::MarkedToCompare, German) => "Markiert: c auf einer anderen Aufnahme zum Vergleichen",
+            (Text::MarkedToCompare, French) => "Marqué : appuyez sur c sur une autre prise pour comparer",
+            (Text::MarkedToCompare, Spanish) => "Marcado: pulsa c en otra toma para comparar",
+            (Text::MarkedToCompare, Italian) => "Segnato: premi c su un'altra ripresa per confrontare",
+
+            (Text::StillRecording, English) => "That session is still being recorded",
+            (Text::StillRecording, German) => "Diese Sitzung wird noch aufgenommen",
+            (Text::StillRecording, French) => "Cette session est encore en cours d'enregistrement",
+            (Text::StillRecording, Spanish) => "Esa sesión aún se está grabando",
+            (Text::StillRecording, Italian) => "Quella sessione è ancora in registrazione",
+
+            (Text::ConfirmDelete, English) => "Press d again to delete this session",
+            (Text::ConfirmDelete, German) => "Nochmal d drücken, um diese Sitzung zu löschen",
+            (Text::ConfirmDelete, French) => "Appuyez encore sur d pour supprimer cette session",
+            (Text::ConfirmDelete, Spanish) => "Pulsa d otra vez para borrar esta sesión",
+            (Text::ConfirmDelete, Italian) => "Premi di nuovo d per eliminare questa sessione",
+
+            (Text::Deleted, English) => "Deleted {}",
+            (Text::Deleted, German) => "{} gelöscht",
+            (Text::Deleted, French) => "{} supprimé",
+            (Text::Deleted, Spanish) => "{} borrado",
+            (Text::Deleted, Italian) => "{} eliminato",
+
+            (Text::DeleteFailed, English) => "Could not delete {}: {}",
+            (Text::DeleteFailed, German) => "{} nicht gelöscht: {}",
+            (Text::DeleteFailed, French) => "Impossible de supprimer {} : {}",
+            (Text::DeleteFailed, Spanish) => "No se pudo borrar {}: {}",
+            (Text::DeleteFailed, Italian) => "Impossibile eliminare {}: {}",
+
+            (Text::ConnectToCalibrate, English) => "Connect a MIDI keyboard to calibrate",
+            (Text::ConnectToCalibrate, German) => "Schließ ein MIDI-Keyboard zum Kalibrieren an",
+            (Text::ConnectToCalibrate, French) => "Connectez un clavier MIDI pour calibrer",
+            (Text::ConnectToCalibrate, Spanish) => "Conecta un teclado MIDI para calibrar",
+            (Text::ConnectToCalibrate, Italian) => "Collega una tastiera MIDI per calibrare",
+
+            (Text::HardNotesNotLouder, English) => "Hard notes weren't any louder: try again",
+            (Text::HardNotesNotLouder, German) => "Kräftige Noten waren nicht lauter: nochmal versuchen",
+            (Text::HardNotesNotLouder, French) => "Les notes fortes n'étaient pas plus fortes : réessayez",
+            (Text::HardNotesNotLouder, Spanish) => "Las notas fuertes no sonaron más fuerte: inténtalo de nuevo",
+            (Text::HardNotesNotLouder, Italian) => "Le note forti non erano più forti: riprova",
+
+            (Text::CurveNotSaved, English) => "Velocity curve set, but not saved: {}",
+            (Text::CurveNotSaved, German) => "Anschlagkurve gesetzt, aber nicht gespeichert: {}",
+            (Text::CurveNotSaved, French) => "Courbe de vélocité réglée, mais non enregistrée : {}",
+            (Text::CurveNotSaved, Spanish) => "Curva de velocidad fijada, pero no guardada: {}",
+            (Text::CurveNotSaved, Italian) => "Curva di velocity impostata, ma non salvata: {}",
+
+            (Text::CurveCalibrated, English) => "Velocity curve calibrated",
+            (Text::CurveCalibrated, German) => "Anschlagkurve kalibriert",
+            (Text::CurveCalibrated, French) => "Courbe de vélocité calibrée",
+            (Text::CurveCalibrated, Spanish) => "Curva de velocidad calibrada",
+            (Text::CurveCalibrated, Italian) => "Curva di velocity calibrata",
+
+            (Text::NoScripting, English) => "Cannot load profile '{}': built without the `scripting` feature",
+            (Text::NoScripting, German) => "Profil '{}' nicht ladbar: ohne das Feature `scripting` gebaut",
+            (Text::NoScripting, French) => "Impossible de charger le profil '{}' : compilé sans la fonctionnalité `scripting`",
+            (Text::NoScripting, Spanish) => "No se puede cargar el perfil '{}': compilado sin la función `scripting`",
+            (Text::NoScripting, Italian) => "Impossibile caricare il profilo '{}': compilato senza la funzione `scripting`",
+
+            (Text::Unmapped, English) => "{} unmapped",
+            (Text::Unmapped, German) => "{} nicht mehr zugewiesen",
+            (Text::Unmapped, French) => "{} dissocié",
+            (Text::Unmapped, Spanish) => "{} sin asignar",
+            (Text::Unmapped, Italian) => "{} non più assegnato",
+
+            (Text::MappedTo, English) => "{} mapped to {}",
+            (Text::MappedTo, German) => "{} zugewiesen an {}",
+            (Text::MappedTo, French) => "{} associé à {}",
+            (Text::MappedTo, Spanish) => "{} asignado a {}",
+            (Text::MappedTo, Italian) => "{} assegnato a {}",
+
+            (Text::NotSaved, English) => "{}, but not saved: {}",
+            (Text::NotSaved, German) => "{}, aber nicht gespeichert: {}",
+            (Text::NotSaved, French) => "{}, mais non enregistré : {}",
+            (Text::NotSaved, Spanish) => "{}, pero sin guardar: {}",
+            (Text::NotSaved, Italian) => "{}, ma non salvato: {}",
+
+            (Text::DictationStopped, English) => "Dictation stopped",
+            (Text::DictationStopped, German) => "Diktat beendet",
+            (Text::DictationStopped, French) => "Dictée arrêtée",
+            (Text::DictationStopped, Spanish) => "Dictado detenido",
+            (Text::DictationStopped, Italian) => "Dettato fermato",
+
+            (Text::DictationStatsNotSaved, English) => "Could not save dictation stats: {}",
+            (Text::DictationStatsNotSaved, German) => "Diktat-Statistik nicht gespeichert: {}",
+            (Text::DictationStatsNotSaved, French) => "Statistiques de dictée non enregistrées : {}",
+            (Text::DictationStatsNotSaved, Spanish) => "No se pudieron guardar las estadísticas del dictado: {}",
+            (Text::DictationStatsNotSaved, Italian) => "Impossibile salvare le statistiche del dettato: {}",
+
+            (Text::DictationRight, English) => "✓ {} — Space for next",
+            (Text::DictationRight, German) => "✓ {} — Leertaste für den nächsten",
+            (Text::DictationRight, French) => "✓ {} — Espace pour la suite",
+            (Text::DictationRight, Spanish) => "✓ {} — Espacio para el siguiente",
+            (Text::DictationRight, Italian) => "✓ {} — Spazio per il prossimo",
+
+            (Text::DictationWrong, English) => "✗ It was {} — Space for next",
+            (Text::DictationWrong, German) => "✗ Es war {} — Leertaste für den nächsten",
+            (Text::DictationWrong, French) => "✗ C'était {} — Espace pour la suite",
+            (Text::DictationWrong, Spanish) => "✗ Era {} — Espacio para el siguiente",
+            (Text::DictationWrong, Italian) => "✗ Era {} — Spazio per il prossimo",
+
+            (Text::PlayThenCompare, English) => "Play the progression, then compare",
+            (Text::PlayThenCompare, German) => "Spiel die Progression, dann vergleiche",
+            (Text::PlayThenCompare, French) => "Jouez la progression, puis comparez",
+            (Text::PlayThenCompare, Spanish) => "Toca la progresión y luego compara",
+            (Text::PlayThenCompare, Italian) => "Suona la progressione, poi confronta",
+
+            (Text::NoUserToLearnFor, English) => "No user to save learned transitions for",
+            (Text::NoUserToLearnFor, German) => "Kein Benutzer, für den Gelerntes gespeichert werden kann",
+            (Text::NoUserToLearnFor, French) => "Aucun utilisateur pour qui enregistrer les enchaînements appris",
+            (Text::NoUserToLearnFor, Spanish) => "No hay usuario para guardar las transiciones aprendidas",
+            (Text::NoUserToLearnFor, Italian) => "Nessun utente per cui salvare le transizioni apprese",
+
+            (Text::ConfirmReset, English) => "Press {} again to reset learned transitions",
+            (Text::ConfirmReset, German) => "Nochmal {} drücken, um gelernte Übergänge zurückzusetzen",
+            (Text::ConfirmReset, French) => "Appuyez encore sur {} pour réinitialiser les enchaînements appris",
+            (Text::ConfirmReset, Spanish) => "Pulsa {} otra vez para restablecer las transiciones aprendidas",
+            (Text::ConfirmReset, Italian) => "Premi di nuovo {} per azzerare le transizioni apprese",
+
+            (Text::LearningReset, English) => "Learned transitions reset",
+            (Text::LearningReset, German) => "Gelernte Übergänge zurückgesetzt",
+            (Text::LearningReset, French) => "Enchaînements appris réinitialisés",
+            (Text::LearningReset, Spanish) => "Transiciones aprendidas restablecidas",
+            (Text::LearningReset, Italian) => "Transizioni apprese azzerate",
+
+            (Text::ResetFailed, English) => "Reset failed: {}",
+            (Text::ResetFailed, German) => "Zurücksetzen fehlgeschlagen: {}",
+            (Text::ResetFailed, French) => "Échec de la réinitialisation : {}",
+            (Text::ResetFailed, Spanish) => "Falló el restablecimiento: {}",
+            (Text::ResetFailed, Italian) => "Azzeramento non riuscito: {}",
+
+            (Text::NoModelFile, English) => "No progression model file loaded",
+            (Text::NoModelFile, German) => "Keine Modelldatei geladen",
+            (Text::NoModelFile, French) => "Aucun fichier de modèle chargé",
+            (Text::NoModelFile, Spanish) => "No hay ningún archivo de modelo cargado",
+            (Text::NoModelFile, Italian) => "Nessun file di modello caricato",
+
+            (Text::ModelReloaded, English) => "Reloaded model '{}'",
+            (Text::ModelReloaded, German) => "Modell '{}' neu geladen",
+            (Text::ModelReloaded, French) => "Modèle '{}' rechargé",
+            (Text::ModelReloaded, Spanish) => "Modelo '{}' recargado",
+            (Text::ModelReloaded, Italian) => "Modello '{}' ricaricato",
+
+            (Text::ModelReloadFailed, English) => "Model reload failed: {}",
+            (Text::ModelReloadFailed, German) => "Neuladen des Modells fehlgeschlagen: {}",
+            (Text::ModelReloadFailed, French) => "Échec du rechargement du modèle : {}",
+            (Text::ModelReloadFailed, Spanish) => "Falló la recarga del modelo: {}",
+            (Text::ModelReloadFailed, Italian) => "Ricaricamento del modello non riuscito: {}",
+
+            (Text::HarmonizingOn, English) => "Harmonizing: play a melody one note at a time",
+            (Text::HarmonizingOn, German) => "Harmonisieren: spiel eine Melodie Note für Note",
+            (Text::HarmonizingOn, French) => "Harmonisation : jouez une mélodie note par note",
+            (Text::HarmonizingOn, Spanish) => "Armonizando: toca una melodía nota a nota",
+            (Text::HarmonizingOn, Italian) => "Armonizzazione: suona una melodia una nota alla volta",
+
+            (Text::HarmonizingOff, English) => "Harmonizing off",
+            (Text::HarmonizingOff, German) => "Harmonisieren aus",
+            (Text::HarmonizingOff, French) => "Harmonisation désactivée",
+            (Text::HarmonizingOff, Spanish) => "Armonización desactivada",
+            (Text::HarmonizingOff, Italian) => "Armonizzazione disattivata",
+
+            (Text::MinorTree, English) => "Minor-key tree: {}",
+            (Text::MinorTree, German) => "Baum in Moll: {}",
+            (Text::MinorTree, French) => "Arbre en mineur : {}",
+            (Text::MinorTree, Spanish) => "Árbol en menor: {}",
+            (Text::MinorTree, Italian) => "Albero in minore: {}",
+
+            (Text::CapoOff, English) => "Capo off",
+            (Text::CapoOff, German) => "Kapodaster ab",
+            (Text::CapoOff, French) => "Capodastre retiré",
+            (Text::CapoOff, Spanish) => "Sin cejilla",
+            (Text::CapoOff, Italian) => "Capotasto tolto",
+
+            (Text::CapoOn, English) => "Capo on fret {}: chords show their shapes",
+            (Text::CapoOn, German) => "Kapodaster im {}. Bund: Akkorde zeigen ihre Griffe",
+            (Text::CapoOn, French) => "Capodastre en case {} : les accords montrent leurs formes",
+            (Text::CapoOn, Spanish) => "Cejilla en el traste {}: los acordes muestran sus formas",
+            (Text::CapoOn, Italian) => "Capotasto al tasto {}: gli accordi mostrano le loro forme",
+
+            (Text::SingingIn, English) => "Singing in {}: chords also shown from {}, exports transposed",
+            (Text::SingingIn, German) => "Gesungen in {}: Akkorde auch von {} aus gezeigt, Exporte transponiert",
+            (Text::SingingIn, French) => "Chant en {} : accords aussi affichés depuis {}, exports transposés",
+            (Text::SingingIn, Spanish) => "Cantando en {}: acordes también mostrados desde {}, exportaciones transportadas",
+            (Text::SingingIn, Italian) => "Canto in {}: accordi mostrati anche da {}, esportazioni trasposte",
+
+            (Text::TranspositionOff, English) => "Transposition off",
+            (Text::TranspositionOff, German) => "Transponieren aus",
+            (Text::TranspositionOff, French) => "Transposition désactivée",
+            (Text::TranspositionOff, Spanish) => "Transporte desactivado",
+            (Text::TranspositionOff, Italian) => "Trasposizione disattivata",
+
+            (Text::BookmarkNote, English) => "★ {} note: {}▏ Enter saves, Esc saves without one",
+            (Text::BookmarkNote, German) => "★ {} Notiz: {}▏ Enter speichert, Esc speichert ohne",
+            (Text::BookmarkNote, French) => "★ {} note : {}▏ Entrée enregistre, Échap enregistre sans",
+            (Text::BookmarkNote, Spanish) => "★ {} nota: {}▏ Intro guarda, Esc guarda sin ella",
+            (Text::BookmarkNote, Italian) => "★ {} nota: {}▏ Invio salva, Esc salva senza",
+
+            (Text::Bookmarked, English) => "Bookmarked: find it in the session browser (S)",
+            (Text::Bookmarked, German) => "Gemerkt: zu finden in den Sitzungen (S)",
+            (Text::Bookmarked, French) => "Marqué : retrouvez-le dans les sessions (S)",
+            (Text::Bookmarked, Spanish) => "Marcado: búscalo en las sesiones (S)",
+            (Text::Bookmarked, Italian) => "Segnato: lo trovi nelle sessioni (S)",
+
+            (Text::BookmarkedUnlogged, English) => "Bookmarked, but no event log is being written",
+            (Text::BookmarkedUnlogged, German) => "Gemerkt, aber es wird kein Ereignisprotokoll geschrieben",
+            (Text::BookmarkedUnlogged, French) => "Marqué, mais aucun journal des événements n'est écrit",
+            (Text::BookmarkedUnlogged, Spanish) => "Marcado, pero no se está escribiendo ningún registro de eventos",
+            (Text::BookmarkedUnlogged, Italian) => "Segnato, ma nessun registro eventi è in scrittura",
+
+            (Text::OffThePlan, English) => "Off the plan: planned chords cleared",
+            (Text::OffThePlan, German) => "Vom Plan abgewichen: geplante Akkorde gelöscht",
+            (Text::OffThePlan, French) => "Hors du plan : accords prévus effacés",
+            (Text::OffThePlan, Spanish) => "Fuera del plan: acordes planeados borrados",
+            (Text::OffThePlan, Italian) => "Fuori dal piano: accordi pianificati cancellati",
+
+            (Text::SuggestionProfileAction, English) => "Suggestion profile {}",
+            (Text::SuggestionProfileAction, German) => "Vorschlagsprofil {}",
+            (Text::SuggestionProfileAction, French) => "Profil de suggestions {}",
+            (Text::SuggestionProfileAction, Spanish) => "Perfil de sugerencias {}",
+            (Text::SuggestionProfileAction, Italian) => "Profilo dei suggerimenti {}",
+
+            (Text::FirstQuality, English) => "First {} chord played",
+            (Text::FirstQuality, German) => "Erster {}-Akkord gespielt",
+            (Text::FirstQuality, French) => "Premier accord {} joué",
+            (Text::FirstQuality, Spanish) => "Primer acorde {} tocado",
+            (Text::FirstQuality, Italian) => "Primo accordo {} suonato",
+
+            (Text::CompleteClean, English) => "{} complete without a miss",
+            (Text::CompleteClean, German) => "{} ohne Fehler geschafft",
+            (Text::CompleteClean, French) => "{} réussi sans faute",
+            (Text::CompleteClean, Spanish) => "{} completado sin fallos",
+            (Text::CompleteClean, Italian) => "{} completato senza errori",
+
+            (Text::CompleteWithMisses, English) => "{} complete ({} missed)",
+            (Text::CompleteWithMisses, German) => "{} geschafft ({} daneben)",
+            (Text::CompleteWithMisses, French) => "{} réussi ({} manqués)",
+            (Text::CompleteWithMisses, Spanish) => "{} completado ({} fallados)",
+            (Text::CompleteWithMisses, Italian) => "{} completato ({} mancati)",
+
+            (Text::Streak, English) => "{}-day practice streak",
+            (Text::Streak, German) => "{} Tage in Folge geübt",
+            (Text::Streak, French) => "{} jours d'entraînement d'affilée",
+            (Text::Streak, Spanish) => "{} días seguidos de práctica",
+            (Text::Streak, Italian) => "{} giorni di esercizio di fila",
+
+            (Text::GoalMet, English) => "Goal met: {}",
+            (Text::GoalMet, German) => "Ziel erreicht: {}",
+            (Text::GoalMet, French) => "Objectif atteint : {}",
+            (Text::GoalMet, Spanish) => "Objetivo cumplido: {}",
+            (Text::GoalMet, Italian) => "Obiettivo raggiunto: {}",
+        }
+    }
+
+    /// The text with each `{}` in it replaced by the next of `args`.
+    pub fn fill(&self, text: Text, args: &[&dyn Display]) -> String {
+        let mut parts = self.text(text).split("{}");
+        let mut filled = parts.next().unwrap_or_default().to_string();
+        for (i, part) in parts.enumerate() {
+            if let Some(arg) = args.get(i) {
+                filled.push_str(&arg.to_string());
+            }
+            filled.push_str(part);
+        }
+        filled
+    }
+
+    /// The usual section names, in the order of `SECTION_NAMES`.
+    pub fn section_names(&self) -> &'static [&'static str; 7] {
+        match self {
+            Language::English => &SECTION_NAMES,
+            Language::German => &[
+                "Strophe",
+                "Refrain",
+                "Bridge",
+                "Intro",
+                "Pre-Chorus",
+                "Solo",
+                "Outro",
+            ],
+            Language::French => &[
+                "Couplet",
+                "Refrain",
+                "Pont",
+                "Intro",
+                "Pré-refrain",
+                "Solo",
+                "Outro",
+            ],
+            Language::Spanish => &[
+                "Estrofa",
+                "Estribillo",
+                "Puente",
+                "Intro",
+                "Preestribillo",
+                "Solo",
+                "Outro",
+            ],
+            Language::Italian => &[
+                "Strofa",
+                "Ritornello",
+                "Ponte",
+                "Intro",
+                "Pre-ritornello",
+                "Assolo",
+                "Outro",
+            ],
+        }
+    }
+
+    fn describe(&self, command: Command) -> &'static str {
+        use Language::*;
+        match (command, self) {
+            (_, English) => command.description(),
+
+            (Command::Quit, German) => "Beenden",
+            (Command::Quit, French) => "Quitter",
+            (Command::Quit, Spanish) => "Salir",
+            (Command::Quit, Italian) => "Esci",
+
+            (Command::ToggleMode, German) => "Entdecken-/Jam-Modus umschalten",
+            (Command::ToggleMode, French) => "Basculer mode Découverte/Jam",
+            (Command::ToggleMode, Spanish) => "Alternar modo Descubrir/Jam",
+            (Command::ToggleMode, Italian) => "Alterna modalità Scoperta/Jam",
+
+            (Command::ToggleExtended, German) => "Erweiterte Akkorde an/aus",
+            (Command::ToggleExtended, French) => "Accords étendus oui/non",
+            (Command::ToggleExtended, Spanish) => "Acordes extendidos sí/no",
+            (Command::ToggleExtended, Italian) => "Accordi estesi sì/no",
+
+            (Command::CycleMinorTree, German) => "Baum in Parallel-/gleichnamigem Moll",
+            (Command::CycleMinorTree, French) => "Arbre en mineur relatif/homonyme",
+            (Command::CycleMinorTree, Spanish) => "Árbol en menor relativo/paralelo",
+            (Command::CycleMinorTree, Italian) => "Albero nel minore relativo/parallelo",
+
+            (Command::CycleCapo, German) => "Kapodaster-Position (Gitarrengriffe)",
+            (Command::CycleCapo, French) => "Capodastre (formes de guitare)",
+            (Command::CycleCapo, Spanish) => "Cejilla (formas de guitarra)",
+            (Command::CycleCapo, Italian) => "Capotasto (forme per chitarra)",
+
+            (Command::ToggleFingering, German) => "Gitarrengriffe für den Akkord",
+            (Command::ToggleFingering, French) => "Doigtés de guitare de l'accord",
+            (Command::ToggleFingering, Spanish) => "Posiciones de guitarra del acorde",
+            (Command::ToggleFingering, Italian) => "Diteggiature per chitarra dell'accordo",
+
+            (Command::TogglePads, German) => "Pads: 1-8 spielen Akkorde der Tonart",
+            (Command::TogglePads, French) => "Pads : 1-8 jouent les accords du ton",
+            (Command::TogglePads, Spanish) => "Pads: 1-8 tocan los acordes del tono",
+            (Command::TogglePads, Italian) => "Pad: 1-8 suonano gli accordi del tono",
+
+            (Command::ToggleLatch, German) => "Noten halten bis zum nächsten Akkord",
+            (Command::ToggleLatch, French) => "Tenir les notes jusqu'au prochain accord",
+            (Command::ToggleLatch, Spanish) => "Mantener notas hasta un nuevo acorde",
+            (Command::ToggleLatch, Italian) => "Tieni le note fino a un nuovo accordo",
+
+            (Command::ToggleSmoothing, German) => "Durchgangstöne glätten",
+            (Command::ToggleSmoothing, French) => "Lisser les notes de passage",
+            (Command::ToggleSmoothing, Spanish) => "Suavizar notas de paso",
+            (Command::ToggleSmoothing, Italian) => "Attenua le note di passaggio",
+
+            (Command::ToggleMelody, German) => "Melodie aus dem Akkord heraushalten",
+            (Command::ToggleMelody, French) => "Exclure la mélodie de l'accord",
+            (Command::ToggleMelody, Spanish) => "Separar la melodía del acorde",
+            (Command::ToggleMelody, Italian) => "Escludi la melodia dall'accordo",
+
+            (Command::EditPlan, German) => "Plan bearbeiten: Akkorde anheften oder tippen",
+            (Command::EditPlan, French) => "Modifier le plan : épingler ou saisir",
+            (Command::EditPlan, Spanish) => "Editar el plan: fijar o escribir acordes",
+            (Command::EditPlan, Italian) => "Modifica il piano: fissa o digita accordi",
+
+            (Command::Sketch, German) => "Songskizze: Teile anordnen",
+            (Command::Sketch, French) => "Esquisse : arranger les sections",
+            (Command::Sketch, Spanish) => "Boceto: ordenar secciones",
+            (Command::Sketch, Italian) => "Bozza: disponi le sezioni",
+
+            (Command::Heatmap, German) => "Akkord-Heatmap: was du am meisten spielst",
+            (Command::Heatmap, French) => "Carte des accords les plus joués",
+            (Command::Heatmap, Spanish) => "Mapa de los acordes que más tocas",
+            (Command::Heatmap, Italian) => "Mappa degli accordi che suoni di più",
+
+            (Command::Goals, German) => "Übungsziele und Serie",
+            (Command::Goals, French) => "Objectifs et série",
+            (Command::Goals, Spanish) => "Objetivos y racha",
+            (Command::Goals, Italian) => "Obiettivi e serie",
+
+            (Command::SwitchUser, German) => "Benutzer wechseln",
+            (Command::SwitchUser, French) => "Changer d'utilisateur",
+            (Command::SwitchUser, Spanish) => "Cambiar de usuario",
+            (Command::SwitchUser, Italian) => "Cambia utente",
+
+            (Command::CycleArpPattern, German) => "Arpeggiator-Muster",
+            (Command::CycleArpPattern, French) => "Motif de l'arpégiateur",
+            (Command::CycleArpPattern, Spanish) => "Patrón del arpegiador",
+            (Command::CycleArpPattern, Italian) => "Schema dell'arpeggiatore",
+
+            (Command::CycleArpRate, German) => "Arpeggiator-Tempo",
+            (Command::CycleArpRate, French) => "Vitesse de l'arpégiateur",
+            (Command::CycleArpRate, Spanish) => "Velocidad del arpegiador",
+            (Command::CycleArpRate, Italian) => "Velocità dell'arpeggiatore",
+
+            (Command::CycleNaming, German) => "Akkordschreibweise wechseln",
+            (Command::CycleNaming, French) => "Changer la notation des accords",
+            (Command::CycleNaming, Spanish) => "Cambiar la notación de acordes",
+            (Command::CycleNaming, Italian) => "Cambia notazione degli accordi",
+
+            (Command::Help, German) => "Diese Hilfe ein/aus",
+            (Command::Help, French) => "Afficher/masquer cette aide",
+            (Command::Help, Spanish) => "Mostrar/ocultar esta ayuda",
+            (Command::Help, Italian) => "Mostra/nascondi questo aiuto",
+
+            (Command::ClearHistory, German) => "Verlauf löschen",
+            (Command::ClearHistory, French) => "Effacer l'historique",
+            (Command::ClearHistory, Spanish) => "Borrar historial",
+            (Command::ClearHistory, Italian) => "Cancella cronologia",
+
+            (Command::ExportLilypond, German) => "Sitzung als Lilypond exportieren",
+            (Command::ExportLilypond, French) => "Exporter la session en Lilypond",
+            (Command::ExportLilypond, Spanish) => "Exportar sesión como Lilypond",
+            (Command::ExportLilypond, Italian) => "Esporta sessione come Lilypond",
+
+            (Command::ExportMidi, German) => "Sitzung als MIDI + Bass exportieren",
+            (Command::ExportMidi, French) => "Exporter la session en MIDI + basse",
+            (Command::ExportMidi, Spanish) => "Exportar sesión como MIDI + bajo",
+            (Command::ExportMidi, Italian) => "Esporta sessione come MIDI + basso",
+
+            (Command::CycleBass, German) => "Walking- / Grundton-Quint-Bass",
+            (Command::CycleBass, French) => "Basse walking / fondamentale-quinte",
+            (Command::CycleBass, Spanish) => "Bajo walking / tónica-quinta",
+            (Command::CycleBass, Italian) => "Basso walking / fondamentale-quinta",
+
+            (Command::TapTempo, German) => "Tempo tippen",
+            (Command::TapTempo, French) => "Tempo au tap",
+            (Command::TapTempo, Spanish) => "Marcar el tempo",
+            (Command::TapTempo, Italian) => "Tap tempo",
+
+            (Command::AutoTempo, German) => "Tempo aus Akkordwechseln schätzen",
+            (Command::AutoTempo, French) => "Estimer le tempo d'après les changements",
+            (Command::AutoTempo, Spanish) => "Estimar el tempo por los cambios",
+            (Command::AutoTempo, Italian) => "Stima il tempo dai cambi",
+
+            (Command::ToggleFeel, German) => "Swing / gerade quantisieren",
+            (Command::ToggleFeel, French) => "Quantifier swing / droit",
+            (Command::ToggleFeel, Spanish) => "Cuantizar swing / recto",
+            (Command::ToggleFeel, Italian) => "Quantizza swing / dritto",
+
+            (Command::Looper, German) => "Loop aufnehmen / overdubben",
+            (Command::Looper, French) => "Enregistrer une boucle / overdub",
+            (Command::Looper, Spanish) => "Grabar loop / sobregrabar",
+            (Command::Looper, Italian) => "Registra loop / sovraincidi",
+
+            (Command::CycleHumanize, German) => "Stärke der Humanisierung",
+            (Command::CycleHumanize, French) => "Degré d'humanisation",
+            (Command::CycleHumanize, Spanish) => "Cantidad de humanización",
+            (Command::CycleHumanize, Italian) => "Quantità di umanizzazione",
+
+            (Command::StopLooper, German) => "Loop stoppen und löschen",
+            (Command::StopLooper, French) => "Arrêter et effacer la boucle",
+            (Command::StopLooper, Spanish) => "Parar y borrar el loop",
+            (Command::StopLooper, Italian) => "Ferma e cancella il loop",
+
+            (Command::ReloadModel, German) => "Progressionsmodell neu laden",
+            (Command::ReloadModel, French) => "Recharger le modèle de progression",
+            (Command::ReloadModel, Spanish) => "Recargar el modelo de progresiones",
+            (Command::ReloadModel, Italian) => "Ricarica il modello di progressioni",
+
+            (Command::ToggleAdaptive, German) => "Lernen aus meinem Spiel an/aus",
+            (Command::ToggleAdaptive, French) => "Apprendre de mon jeu oui/non",
+            (Command::ToggleAdaptive, Spanish) => "Aprender de cómo toco sí/no",
+            (Command::ToggleAdaptive, Italian) => "Impara da come suono sì/no",
+
+            (Command::ResetAdaptive, German) => "Gelernte Übergänge zurücksetzen",
+            (Command::ResetAdaptive, French) => "Oublier les enchaînements appris",
+            (Command::ResetAdaptive, Spanish) => "Restablecer transiciones aprendidas",
+            (Command::ResetAdaptive, Italian) => "Azzera le transizioni apprese",
+
+            (Command::BlendDown, German) => "Weniger auf Gelerntes stützen",
+            (Command::BlendDown, French) => "Moins s'appuyer sur l'appris",
+            (Command::BlendDown, Spanish) => "Apoyarse menos en lo aprendido",
+            (Command::BlendDown, Italian) => "Affidati meno a ciò che hai appreso",
+
+            (Command::BlendUp, German) => "Mehr auf Gelerntes stützen",
+            (Command::BlendUp, French) => "Plus s'appuyer sur l'appris",
+            (Command::BlendUp, Spanish) => "Apoyarse más en lo aprendido",
+            (Command::BlendUp, Italian) => "Affidati di più a ciò che hai appreso",
+
+            (Command::Presets, German) => "Progressions-Vorlagen durchsehen",
+            (Command::Presets, French) => "Parcourir les progressions types",
+            (Command::Presets, Spanish) => "Explorar progresiones predefinidas",
+            (Command::Presets, Italian) => "Sfoglia le progressioni predefinite",
+
+            (Command::Sessions, German) => "Gespeicherte Sitzungen durchsehen",
+            (Command::Sessions, French) => "Parcourir les sessions enregistrées",
+            (Command::Sessions, Spanish) => "Explorar sesiones guardadas",
+            (Command::Sessions, Italian) => "Sfoglia le sessioni salvate",
+
+            (Command::Similar, German) => "Ähnlich klingende Akkorde zeigen",
+            (Command::Similar, French) => "Accords au son semblable",
+            (Command::Similar, Spanish) => "Acordes de sonido parecido",
+            (Command::Similar, Italian) => "Accordi dal suono simile",
+
+            (Command::Duet, German) => "Duett-Ansicht an/aus",
+            (Command::Duet, French) => "Vue duo oui/non",
+            (Command::Duet, Spanish) => "Vista de dúo sí/no",
+            (Command::Duet, Italian) => "Vista duetto sì/no",
+
+            (Command::Dictation, German) => "Akkorddiktat starten/stoppen",
+            (Command::Dictation, French) => "Lancer/arrêter la dictée d'accords",
+            (Command::Dictation, Spanish) => "Iniciar/detener dictado de acordes",
+            (Command::Dictation, Italian) => "Avvia/ferma il dettato di accordi",
+
+            (Command::Intervals, German) => "Intervall-Gehörbildung",
+            (Command::Intervals, French) => "Oreille : intervalles",
+            (Command::Intervals, Spanish) => "Entrenar el oído con intervalos",
+            (Command::Intervals, Italian) => "Allena l'orecchio sugli intervalli",
+
+            (Command::Explorer, German) => "Voicings erkunden",
+            (Command::Explorer, French) => "Explorer les voicings",
+            (Command::Explorer, Spanish) => "Explorar voicings",
+            (Command::Explorer, Italian) => "Esplora i voicing",
+
+            (Command::NextDictation, German) => "Diktat-Akkord wiederholen / nächster",
+            (Command::NextDictation, French) => "Rejouer / accord de dictée suivant",
+            (Command::NextDictation, Spanish) => "Repetir / siguiente acorde del dictado",
+            (Command::NextDictation, Italian) => "Ripeti / prossimo accordo del dettato",
+
+            (Command::CreativityDown, German) => "Weniger kreative Vorschläge",
+            (Command::CreativityDown, French) => "Suggestions moins créatives",
+            (Command::CreativityDown, Spanish) => "Sugerencias menos creativas",
+            (Command::CreativityDown, Italian) => "Suggerimenti meno creativi",
+
+            (Command::CreativityUp, German) => "Kreativere Vorschläge",
+            (Command::CreativityUp, French) => "Suggestions plus créatives",
+            (Command::CreativityUp, Spanish) => "Sugerencias más creativas",
+            (Command::CreativityUp, Italian) => "Suggerimenti più creativi",
+
+            (Command::ReinterpretKey, German) => "Tonart neu deuten",
+            (Command::ReinterpretKey, French) => "Réinterpréter la tonalité",
+            (Command::ReinterpretKey, Spanish) => "Reinterpretar la tonalidad",
+            (Command::ReinterpretKey, Italian) => "Reinterpreta la tonalità",
+
+            (Command::HistoryPrev, German) => "Vorheriger Verlaufseintrag",
+            (Command::HistoryPrev, French) => "Entrée précédente de l'historique",
+            (Command::HistoryPrev, Spanish) => "Entrada anterior del historial",
+            (Command::HistoryPrev, Italian) => "Voce precedente della cronologia",
+
+            (Command::HistoryNext, German) => "Nächster Verlaufseintrag",
+            (Command::HistoryNext, French) => "Entrée suivante de l'historique",
+            (Command::HistoryNext, Spanish) => "Entrada siguiente del historial",
+            (Command::HistoryNext, Italian) => "Voce successiva della cronologia",
+
+            (Command::ChordDetail, German) => "Gewähltes Voicing zeigen",
+            (Command::ChordDetail, French) => "Afficher le voicing choisi",
+            (Command::ChordDetail, Spanish) => "Mostrar el voicing elegido",
+            (Command::ChordDetail, Italian) => "Mostra il voicing selezionato",
+
+            (Command::Playback, German) => "Geladene Progression abspielen/stoppen",
+            (Command::Playback, French) => "Jouer/arrêter la progression chargée",
+            (Command::Playback, Spanish) => "Reproducir/parar la progresión cargada",
+            (Command::Playback, Italian) => "Riproduci/ferma la progressione caricata",
+
+            (Command::RestartTrainer, German) => "Geladene Progression neu starten",
+            (Command::RestartTrainer, French) => "Relancer la progression chargée",
+            (Command::RestartTrainer, Spanish) => "Reiniciar la progresión cargada",
+            (Command::RestartTrainer, Italian) => "Ricomincia la progressione caricata",
+
+            (Command::CalibrateVelocity, German) => "Anschlagstärke kalibrieren",
+            (Command::CalibrateVelocity, French) => "Calibrer la vélocité des notes",
+            (Command::CalibrateVelocity, Spanish) => "Calibrar la velocidad de las notas",
+            (Command::CalibrateVelocity, Italian) => "Calibra la velocity delle note",
+
+            (Command::MapControls, German) => "Controller-Tasten Aktionen zuweisen",
+            (Command::MapControls, French) => "Associer les boutons du contrôleur",
+            (Command::MapControls, Spanish) => "Asignar botones del controlador",
+            (Command::MapControls, Italian) => "Assegna i pulsanti del controller",
+
+            (Command::PageTurn, German) => "Nächster Akkord von Progression, Skizze oder Diktat",
+            (Command::PageTurn, French) => {
+                "Accord suivant de la progression, de l'esquisse ou de la dictée"
+            }
+            (Command::PageTurn, Spanish) => {
+                "Siguiente acorde de la progresión, el boceto o el dictado"
+            }
+            (Command::PageTurn, Italian) => "Accordo successivo di progressione, bozza o dettato",
+
+            (Command::SpellOut, German) => "Rollen der gehaltenen Noten aufschlüsseln",
+            (Command::SpellOut, French) => "Rôle de chaque note tenue",
+            (Command::SpellOut, Spanish) => "Función de cada nota pulsada",
+            (Command::SpellOut, Italian) => "Ruolo di ogni nota tenuta",
+
+            (Command::KeyFinder, German) => "Welche Tonarten zu den Akkorden passen",
+            (Command::KeyFinder, French) => "Tonalités qui vont avec les accords joués",
+            (Command::KeyFinder, Spanish) => "Tonalidades que encajan con los acordes",
+            (Command::KeyFinder, Italian) => "Tonalità adatte agli accordi suonati",
+
+            (Command::Reharmonize, German) => "Letzte Akkorde reharmonisieren",
+            (Command::Reharmonize, French) => "Réharmoniser les derniers accords",
+            (Command::Reharmonize, Spanish) => "Rearmonizar los últimos acordes",
+            (Command::Reharmonize, Italian) => "Riarmonizza gli ultimi accordi",
+
+            (Command::HarmonizeMelody, German) => "Akkorde zu einer Melodie vorschlagen",
+            (Command::HarmonizeMelody, French) => "Proposer des accords sous une mélodie",
+            (Command::HarmonizeMelody, Spanish) => "Proponer acordes bajo una melodía",
+            (Command::HarmonizeMelody, Italian) => "Proponi accordi sotto una melodia",
+
+            (Command::Transpose, German) => "In eine Gesangstonart transponieren",
+            (Command::Transpose, French) => "Transposer dans la tonalité du chant",
+            (Command::Transpose, Spanish) => "Transportar al tono de quien canta",
+            (Command::Transpose, Italian) => "Trasponi nel tono di chi canta",
+
+            (Command::AuditionExpected, German) => "Erwarteten Zweig im Zusammenhang hören",
+            (Command::AuditionExpected, French) => "Écouter la branche attendue en contexte",
+            (Command::AuditionExpected, Spanish) => "Escuchar la rama esperada en contexto",
+            (Command::AuditionExpected, Italian) => "Ascolta il ramo previsto nel contesto",
+
+            (Command::AuditionSurprise, German) => "Überraschungszweig im Zusammenhang hören",
+            (Command::AuditionSurprise, French) => "Écouter la branche surprise en contexte",
+            (Command::AuditionSurprise, Spanish) => "Escuchar la rama sorpresa en contexto",
+            (Command::AuditionSurprise, Italian) => "Ascolta il ramo sorpresa nel contesto",
+
+            (Command::Bookmark, German) => "Diesen Moment merken (mit Notiz)",
+            (Command::Bookmark, French) => "Marquer ce moment (avec une note)",
+            (Command::Bookmark, Spanish) => "Marcar este momento (con una nota)",
+            (Command::Bookmark, Italian) => "Segna questo momento (con una nota)",
+
+            (Command::CycleTimeSignature, German) => "Taktart wechseln (4/4, 3/4, 6/8, 5/4, 7/8)",
+            (Command::CycleTimeSignature, French) => "Changer de mesure (4/4, 3/4, 6/8, 5/4, 7/8)",
+            (Command::CycleTimeSignature, Spanish) => "Cambiar el compás (4/4, 3/4, 6/8, 5/4, 7/8)",
+            (Command::CycleTimeSignature, Italian) => "Cambia il tempo (4/4, 3/4, 6/8, 5/4, 7/8)",
+
+            (Command::CycleGroove, German) => "Begleit-Groove für Wiedergabe und Loops",
+            (Command::CycleGroove, French) => "Groove d'accompagnement (lecture et boucles)",
+            (Command::CycleGroove, Spanish) => "Groove de acompañamiento (reproducción y loops)",
+            (Command::CycleGroove, Italian) => "Groove d'accompagnamento (riproduzione e loop)",
+
+            (Command::ToggleBlues, German) => "Blues: 12 Takte, I7 IV7 V7 in der Tonart",
+            (Command::ToggleBlues, French) => "Blues : grille de 12 mesures, I7 IV7 V7",
+            (Command::ToggleBlues, Spanish) => "Blues: forma de 12 compases, I7 IV7 V7",
+            (Command::ToggleBlues, Italian) => "Blues: forma di 12 battute, I7 IV7 V7",
+        }
+    }
+}
+
+impl TryFrom<String> for Language {
+    type Error = anyhow::Error;
+
+    fn try_from(code: String) -> anyhow::Result<Self> {
+        Self::from_code(&code).ok_or_else(|| anyhow::anyhow!("Unsupported language '{}'", code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(Language::from_code("de"), Some(Language::German));
+        assert_eq!(Language::from_code("fr_CA.UTF-8"), Some(Language::French));
+        assert_eq!(Language::from_code("C"), None);
+        assert_eq!(Language::from_code("ja_JP"), None);
+    }
+
+    #[test]
+    fn test_text_and_note_names() {
+        assert_eq!(Language::German.text(Text::History), "Verlauf");
+        assert_eq!(Language::English.text(Text::Help), "Help");
+        assert_eq!(Language::Italian.note_names(), NoteNames::Solfege);
+        assert_eq!(Language::German.note_names(), NoteNames::German);
+    }
+
+    #[test]
+    fn test_fill() {
+        let english = Language::English.fill(Text::CapoOn, &[&3]);
+        assert_eq!(english, "Capo on fret 3: chords show their shapes");
+        let german = Language::German.fill(Text::MappedTo, &[&"CC 64", &"help"]);
+        assert_eq!(german, "CC 64 zugewiesen an help");
+        // A missing argument leaves its place empty
+        assert_eq!(Language::English.fill(Text::KeySetTo, &[]), "Key set to ");
+    }
+
+    #[test]
+    fn test_translations_take_the_same_arguments() {
+        let filled = [
+            Text::EventLogDisabled,
+            Text::BluesFormIn,
+            Text::KeySetTo,
+            Text::StatsNotSaved,
+            Text::SwitchedTo,
+            Text::ConfigIgnored,
+            Text::AutosaveOff,
+            Text::Exported,
+            Text::ExportFailed,
+            Text::TextExportFailed,
+            Text::SuggestionProfile,
+            Text::GrooveIs,
+            Text::ExpectedBranch,
+            Text::SurpriseBranch,
+            Text::ArpPattern,
+            Text::ArpRate,
+            Text::IntervalRight,
+            Text::IntervalWrong,
+            Text::PadsOn,
+            Text::TimeSignatureFromNextLoop,
+            Text::TimeSignatureIs,
+            Text::HumanizeBy,
+            Text::AlongFinished,
+            Text::Planned,
+            Text::UnknownChord,
+            Text::Pinned,
+            Text::Captured,
+            Text::SketchReplaced,
+            Text::QuantizeFeel,
+            Text::ResumedSession,
+            Text::Deleted,
+            Text::DeleteFailed,
+            Text::CurveNotSaved,
+            Text::NoScripting,
+            Text::Unmapped,
+            Text::MappedTo,
+            Text::NotSaved,
+            Text::DictationStatsNotSaved,
+            Text::DictationRight,
+            Text::DictationWrong,
+            Text::ConfirmReset,
+            Text::ResetFailed,
+            Text::ModelReloaded,
+            Text::ModelReloadFailed,
+            Text::MinorTree,
+            Text::CapoOn,
+            Text::SingingIn,
+            Text::BookmarkNote,
+            Text::SuggestionProfileAction,
+            Text::FirstQuality,
+            Text::CompleteClean,
+            Text::CompleteWithMisses,
+            Text::Streak,
+            Text::GoalMet,
+        ];
+        for text in filled {
+            let wanted = Language::English.text(text).matches("{}").count();
+            for language in Language::all() {
+                let found = language.text(text).matches("{}").count();
+                assert_eq!(found, wanted, "{:?} in {:?}", text, language);
+            }
+        }
+    }
+
+    #[test]
+    fn test_commands_described() {
+        assert_eq!(
+            Language::English.text(Text::Command(Command::Quit)),
+            Command::Quit.description()
+        );
+        assert_eq!(
+            Language::French.text(Text::Command(Command::Quit)),
+            "Quitter"
+        );
+        assert_eq!(
+            Language::Spanish.text(Text::Category(Category::Midi)),
+            "Entrada MIDI"
+        );
+    }
+}
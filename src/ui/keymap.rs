@@ -0,0 +1,873 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crossterm::event::KeyCode;
+use serde::Deserialize;
+
+/// Something a key can be bound to in the main view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Command {
+    Quit,
+    ToggleMode,
+    ToggleExtended,
+    CycleMinorTree,
+    CycleCapo,
+    ToggleFingering,
+    TogglePads,
+    ToggleLatch,
+    ToggleSmoothing,
+    ToggleMelody,
+    EditPlan,
+    Sketch,
+    Heatmap,
+    Goals,
+    SwitchUser,
+    CycleArpPattern,
+    CycleArpRate,
+    CycleNaming,
+    Help,
+    ClearHistory,
+    ExportLilypond,
+    ExportMidi,
+    CycleBass,
+    TapTempo,
+    AutoTempo,
+    ToggleFeel,
+    Looper,
+    CycleHumanize,
+    StopLooper,
+    ReloadModel,
+    ToggleAdaptive,
+    ResetAdaptive,
+    BlendDown,
+    BlendUp,
+    Presets,
+    Sessions,
+    Similar,
+    Duet,
+    Dictation,
+    Intervals,
+    Explorer,
+    NextDictation,
+    CreativityDown,
+    CreativityUp,
+    ReinterpretKey,
+    HistoryPrev,
+    HistoryNext,
+    ChordDetail,
+    Playback,
+    RestartTrainer,
+    CalibrateVelocity,
+    MapControls,
+    PageTurn,
+    SpellOut,
+    KeyFinder,
+    Reharmonize,
+    HarmonizeMelody,
+    Transpose,
+    AuditionExpected,
+    AuditionSurprise,
+    Bookmark,
+    CycleTimeSignature,
+    CycleGroove,
+    ToggleBlues,
+}
+
+/// The default bindings, in the order commands are listed.
+const DEFAULTS: &[(Command, &[&str])] = &[
+    (Command::Quit, &["q", "Esc"]),
+    (Command::ToggleMode, &["Tab"]),
+    (Command::ToggleExtended, &["e"]),
+    (Command::CycleMinorTree, &["m"]),
+    (Command::CycleCapo, &["g"]),
+    (Command::ToggleFingering, &["f"]),
+    (Command::TogglePads, &["P"]),
+    (Command::ToggleLatch, &["z"]),
+    (Command::ToggleSmoothing, &["o"]),
+    (Command::ToggleMelody, &["V"]),
+    (Command::EditPlan, &["E"]),
+    (Command::Sketch, &["C"]),
+    (Command::Heatmap, &["F"]),
+    (Command::Goals, &["G"]),
+    (Command::SwitchUser, &["W"]),
+    (Command::CycleArpPattern, &["u"]),
+    (Command::CycleArpRate, &["U"]),
+    (Command::CycleNaming, &["n"]),
+    (Command::Help, &["?"]),
+    (Command::ClearHistory, &["c"]),
+    (Command::ExportLilypond, &["x"]),
+    (Command::ExportMidi, &["X"]),
+    (Command::CycleBass, &["B"]),
+    (Command::TapTempo, &["t"]),
+    (Command::AutoTempo, &["T"]),
+    (Command::ToggleFeel, &["w"]),
+    (Command::Looper, &["L"]),
+    (Command::CycleHumanize, &["H"]),
+    (Command::StopLooper, &["O"]),
+    (Command::ReloadModel, &["M"]),
+    (Command::ToggleAdaptive, &["a"]),
+    (Command::ResetAdaptive, &["A"]),
+    (Command::BlendDown, &["["]),
+    (Command::BlendUp, &["]"]),
+    (Command::Presets, &["b"]),
+    (Command::Sessions, &["S"]),
+    (Command::Similar, &["s"]),
+    (Command::Duet, &["D"]),
+    (Command::Dictation, &["d"]),
+    (Command::Intervals, &["i"]),
+    (Command::Explorer, &["v"]),
+    (Command::NextDictation, &[]),
+    (Command::CreativityDown, &["-"]),
+    (Command::CreativityUp, &["+", "="]),
+    (Command::ReinterpretKey, &["K"]),
+    (Command::HistoryPrev, &["Left"]),
+    (Command::HistoryNext, &["Right"]),
+    (Command::ChordDetail, &["Enter"]),
+    (Command::Playback, &["p"]),
+    (Command::RestartTrainer, &["r"]),
+    (Command::CalibrateVelocity, &["Y"]),
+    (Command::MapControls, &["R"]),
+    (Command::PageTurn, &["Space"]),
+    (Command::SpellOut, &["N"]),
+    (Command::KeyFinder, &["k"]),
+    (Command::Reharmonize, &["h"]),
+    (Command::HarmonizeMelody, &["I"]),
+    (Command::Transpose, &["y"]),
+    (Command::AuditionExpected, &["j"]),
+    (Command::AuditionSurprise, &["l"]),
+    (Command::Bookmark, &["*"]),
+    (Command::CycleTimeSignature, &["/"]),
+    (Command::CycleGroove, &["Q"]),
+    (Command::ToggleBlues, &["J"]),
+];
+
+/// Where a command is listed in the help.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    General,
+    Chords,
+    Suggestions,
+    History,
+    Playing,
+    Practice,
+    Guitar,
+    Midi,
+}
+
+impl Category {
+    pub fn all() -> &'static [Category] {
+        &[
+            Category::General,
+            Category::Chords,
+            Category::Suggestions,
+            Category::History,
+            Category::Playing,
+            Category::Practice,
+            Category::Guitar,
+            Category::Midi,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::General => "General",
+            Category::Chords => "Chords",
+            Category::Suggestions => "Suggestions",
+            Category::History => "History and sessions",
+            Category::Playing => "Playing",
+            Category::Practice => "Practice",
+            Category::Guitar => "Guitar",
+            Category::Midi => "MIDI input",
+        }
+    }
+}
+
+impl Command {
+    pub fn all() -> impl Iterator<Item = Command> {
+        DEFAULTS.iter().map(|(command, _)| *command)
+    }
+
+    /// The name used for it in the config, e.g. `export-midi`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Quit => "quit",
+            Command::ToggleMode => "toggle-mode",
+            Command::ToggleExtended => "toggle-extended",
+            Command::CycleMinorTree => "cycle-minor-tree",
+            Command::CycleCapo => "cycle-capo",
+            Command::ToggleFingering => "toggle-fingering",
+            Command::TogglePads => "toggle-pads",
+            Command::ToggleLatch => "toggle-latch",
+            Command::ToggleSmoothing => "toggle-smoothing",
+            Command::ToggleMelody => "toggle-melody",
+            Command::EditPlan => "edit-plan",
+            Command::Sketch => "sketch",
+            Command::Heatmap => "heatmap",
+            Command::Goals => "goals",
+            Command::SwitchUser => "switch-user",
+            Command::CycleArpPattern => "cycle-arp-pattern",
+            Command::CycleArpRate => "cycle-arp-rate",
+            Command::CycleNaming => "cycle-naming",
+            Command::Help => "help",
+            Command::ClearHistory => "clear-history",
+            Command::ExportLilypond => "export-lilypond",
+            Command::ExportMidi => "export-midi",
+            Command::CycleBass => "cycle-bass",
+            Command::TapTempo => "tap-tempo",
+            Command::AutoTempo => "auto-tempo",
+            Command::ToggleFeel => "toggle-feel",
+            Command::Looper => "looper",
+            Command::CycleHumanize => "cycle-humanize",
+            Command::StopLooper => "stop-looper",
+            Command::ReloadModel => "reload-model",
+            Command::ToggleAdaptive => "toggle-adaptive",
+            Command::ResetAdaptive => "reset-adaptive",
+            Command::BlendDown => "blend-down",
+            Command::BlendUp => "blend-up",
+            Command::Presets => "presets",
+            Command::Sessions => "sessions",
+            Command::Similar => "similar",
+            Command::Duet => "duet",
+            Command::Dictation => "dictation",
+            Command::Intervals => "intervals",
+            Command::Explorer => "explorer",
+            Command::NextDictation => "next-dictation",
+            Command::CreativityDown => "creativity-down",
+            Command::CreativityUp => "creativity-up",
+            Command::ReinterpretKey => "reinterpret-key",
+            Command::HistoryPrev => "history-prev",
+            Command::HistoryNext => "history-next",
+            Command::ChordDetail => "chord-detail",
+            Command::Playback => "playback",
+            Command::RestartTrainer => "restart-trainer",
+            Command::CalibrateVelocity => "calibrate-velocity",
+            Command::MapControls => "map-controls",
+            Command::PageTurn => "page-turn",
+            Command::SpellOut => "spell-out",
+            Command::KeyFinder => "key-finder",
+            Command::Reharmonize => "reharmonize",
+            Command::HarmonizeMelody => "harmonize-melody",
+            Command::Transpose => "transpose",
+            Command::AuditionExpected => "audition-expected",
+            Command::AuditionSurprise => "audition-surprise",
+            Command::Bookmark => "bookmark",
+            Command::CycleTimeSignature => "cycle-time-signature",
+            Command::CycleGroove => "cycle-groove",
+            Command::ToggleBlues => "toggle-blues",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase().replace('_', "-");
+        Self::all().find(|c| c.name() == name)
+    }
+
+    pub fn category(&self) -> Category {
+        match self {
+            Command::Help | Command::Quit | Command::ToggleMode | Command::SwitchUser => {
+                Category::General
+            }
+            Command::ToggleExtended
+            | Command::CycleNaming
+            | Command::ToggleLatch
+            | Command::ToggleSmoothing
+            | Command::ToggleMelody
+            | Command::ReinterpretKey
+            | Command::Similar
+            | Command::SpellOut
+            | Command::Transpose
+            | Command::KeyFinder
+            | Command::Explorer
+            | Command::Duet => Category::Chords,
+            Command::CycleMinorTree
+            | Command::EditPlan
+            | Command::CreativityDown
+            | Command::CreativityUp
+            | Command::ToggleAdaptive
+            | Command::BlendDown
+            | Command::BlendUp
+            | Command::ResetAdaptive
+            | Command::ReloadModel
+            | Command::HarmonizeMelody
+            | Command::AuditionExpected
+            | Command::AuditionSurprise
+            | Command::ToggleBlues => Category::Suggestions,
+            Command::HistoryPrev
+            | Command::HistoryNext
+            | Command::ChordDetail
+            | Command::ClearHistory
+            | Command::ExportLilypond
+            | Command::ExportMidi
+            | Command::Sessions
+            | Command::Sketch
+            | Command::Reharmonize
+            | Command::Bookmark => Category::History,
+            Command::TogglePads
+            | Command::CycleArpPattern
+            | Command::CycleArpRate
+            | Command::TapTempo
+            | Command::AutoTempo
+            | Command::ToggleFeel
+            | Command::CycleTimeSignature
+            | Command::CycleGroove
+            | Command::Looper
+            | Command::StopLooper
+            | Command::CycleHumanize
+            | Command::CycleBass => Category::Playing,
+            Command::Presets
+            | Command::Playback
+            | Command::RestartTrainer
+            | Command::Dictation
+            | Command::NextDictation
+            | Command::PageTurn
+            | Command::Intervals
+            | Command::Goals
+            | Command::Heatmap => Category::Practice,
+            Command::CycleCapo | Command::ToggleFingering => Category::Guitar,
+            Command::CalibrateVelocity | Command::MapControls => Category::Midi,
+        }
+    }
+
+    /// What it does, as the help puts it.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Command::Quit => "Quit",
+            Command::ToggleMode => "Toggle Discovery/Jam mode",
+            Command::ToggleExtended => "Toggle extended chords",
+            Command::CycleMinorTree => "Tree in relative/parallel minor",
+            Command::CycleCapo => "Capo position (guitar shapes)",
+            Command::ToggleFingering => "Guitar grips for the chord",
+            Command::TogglePads => "Pads: 1-8 play the key's chords",
+            Command::ToggleLatch => "Latch notes until a new chord",
+            Command::ToggleSmoothing => "Smooth out passing tones",
+            Command::ToggleMelody => "Keep melody out of the chord",
+            Command::EditPlan => "Edit the plan: pin or type chords",
+            Command::Sketch => "Song sketch: arrange sections",
+            Command::Heatmap => "Chord heatmap: what you play most",
+            Command::Goals => "Practice goals and streak",
+            Command::SwitchUser => "Switch user",
+            Command::CycleArpPattern => "Arpeggiator pattern",
+            Command::CycleArpRate => "Arpeggiator rate",
+            Command::CycleNaming => "Cycle chord naming style",
+            Command::Help => "Toggle this help",
+            Command::ClearHistory => "Clear history",
+            Command::ExportLilypond => "Export session as Lilypond",
+            Command::ExportMidi => "Export session as MIDI + bass",
+            Command::CycleBass => "Walking / root-fifth bass",
+            Command::TapTempo => "Tap tempo",
+            Command::AutoTempo => "Estimate tempo from changes",
+            Command::ToggleFeel => "Swing / straight quantize",
+            Command::Looper => "Record loop / overdub",
+            Command::CycleHumanize => "Humanize playback amount",
+            Command::StopLooper => "Stop and clear loop",
+            Command::ReloadModel => "Reload progression model",
+            Command::ToggleAdaptive => "Toggle learning from my playing",
+            Command::ResetAdaptive => "Reset learned transitions",
+            Command::BlendDown => "Lean less on learned blend",
+            Command::BlendUp => "Lean more on learned blend",
+            Command::Presets => "Browse progression presets",
+            Command::Sessions => "Browse saved sessions",
+            Command::Similar => "Show similar-sounding chords",
+            Command::Duet => "Toggle duet view",
+            Command::Dictation => "Start/stop chord dictation",
+            Command::Intervals => "Interval ear training",
+            Command::Explorer => "Explore voicings",
+            Command::NextDictation => "Replay / next dictation chord",
+            Command::CreativityDown => "Less creative suggestions",
+            Command::CreativityUp => "More creative suggestions",
+            Command::ReinterpretKey => "Reinterpret key",
+            Command::HistoryPrev => "Previous history entry",
+            Command::HistoryNext => "Next history entry",
+            Command::ChordDetail => "Show selected voicing",
+            Command::Playback => "Play/stop loaded progression",
+            Command::RestartTrainer => "Restart loaded progression",
+            Command::CalibrateVelocity => "Calibrate note velocities",
+            Command::MapControls => "Map controller buttons to actions",
+            Command::PageTurn => "Next chord of the loaded progression, sketch or dictation",
+            Command::SpellOut => "Spell out the held notes' roles",
+            Command::KeyFinder => "Which keys fit the chords played",
+            Command::Reharmonize => "Reharmonize the last chords",
+            Command::HarmonizeMelody => "Propose chords under a melody",
+            Command::Transpose => "Transpose to a singer's key",
+            Command::AuditionExpected => "Hear the expected branch in context",
+            Command::AuditionSurprise => "Hear the surprise branch in context",
+            Command::Bookmark => "Bookmark this moment (type a note)",
+            Command::CycleTimeSignature => "Cycle time signature (4/4, 3/4, 6/8, 5/4, 7/8)",
+            Command::CycleGroove => "Cycle comping groove for playback and loops",
+            Command::ToggleBlues => "Blues: 12-bar form, I7 IV7 V7 in key",
+        }
+    }
+}
+
+impl TryFrom<String> for Command {
+    type Error = anyhow::Error;
+
+    fn try_from(name: String) -> anyhow::Result<Self> {
+        Self::from_name(&name).ok_or_else(|| anyhow::anyhow!("Unknown command '{}'", name))
+    }
+}
+
+/// One key in a binding: a key, or whichever key is the leader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stroke {
+    Key(KeyCode),
+    Leader,
+}
+
+/// Parses a key name: a single character, or `Esc`, `Tab`, `Enter`,
+/// `Space`, `Backspace`, `Delete`, the arrows, `Home`, `End`, `PageUp`,
+/// `PageDown` or `F1` to `F12`.
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    let key = match name.to_lowercase().as_str() {
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "enter" | "return" => KeyCode::Enter,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        other => KeyCode::F(
+            other
+                .strip_prefix('f')?
+                .parse()
+                .ok()
+                .filter(|n| (1..=12).contains(n))?,
+        ),
+    };
+    Some(key)
+}
+
+/// How a key is written in bindings and help.
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// A binding as written in the config: keys separated by spaces, pressed
+/// one after another, e.g. `"g e"` or `"<leader> m"`. Empty unbinds.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Sequence(pub Vec<Stroke>);
+
+impl Sequence {
+    pub fn parse(text: &str) -> Option<Self> {
+        text.split_whitespace()
+            .map(|token| match token.to_lowercase().as_str() {
+                "<leader>" | "leader" => Some(Stroke::Leader),
+                _ => parse_key(token).map(Stroke::Key),
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Sequence)
+    }
+}
+
+impl TryFrom<String> for Sequence {
+    type Error = anyhow::Error;
+
+    fn try_from(text: String) -> anyhow::Result<Self> {
+        Self::parse(&text).ok_or_else(|| anyhow::anyhow!("Unknown key in '{}'", text))
+    }
+}
+
+/// One binding or several for a command.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Bindings {
+    One(Sequence),
+    Many(Vec<Sequence>),
+}
+
+impl Bindings {
+    pub fn sequences(&self) -> &[Sequence] {
+        match self {
+            Bindings::One(sequence) => std::slice::from_ref(sequence),
+            Bindings::Many(sequences) => sequences,
+        }
+    }
+}
+
+/// The `[keys]` config section.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeysConfig {
+    /// Key that `<leader>` in a binding stands for, `\` if unset
+    pub leader: Option<Sequence>,
+    /// Bindings replacing a command's defaults
+    pub bind: BTreeMap<Command, Bindings>,
+}
+
+/// A binding the config couldn't make, and the one in its way.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub keys: Vec<KeyCode>,
+    pub command: Command,
+    pub with: Vec<KeyCode>,
+    pub with_command: Command,
+    /// Both were in the config, so neither is bound; otherwise `command`
+    /// was a default the config's `with` took the place of
+    pub configured: bool,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (keys, with) = (sequence_name(&self.keys), sequence_name(&self.with));
+        if self.configured {
+            write!(
+                f,
+                "`{}` for {} and `{}` for {} overlap, so neither is bound",
+                keys,
+                self.command.name(),
+                with,
+                self.with_command.name()
+            )
+        } else {
+            write!(
+                f,
+                "`{}` no longer runs {}: `{}` is bound to {}",
+                keys,
+                self.command.name(),
+                with,
+                self.with_command.name()
+            )
+        }
+    }
+}
+
+/// Main-view key bindings, single keys or sequences, and the keys pressed
+/// so far towards one.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<(Vec<KeyCode>, Command)>,
+    pending: Vec<KeyCode>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut keymap = Self {
+            bindings: Vec::new(),
+            pending: Vec::new(),
+        };
+        for (command, keys) in DEFAULTS {
+            for key in keys.iter().filter_map(|k| parse_key(k)) {
+                keymap.bindings.push((vec![key], *command));
+            }
+        }
+        keymap
+    }
+}
+
+impl Keymap {
+    /// The defaults with the config's bindings in place of theirs, and
+    /// the bindings that couldn't stay. Config bindings win over defaults;
+    /// two in the config that overlap are both left out, whichever command
+    /// comes first.
+    pub fn from_config(config: &KeysConfig) -> (Self, Vec<Conflict>) {
+        let leader = match config.leader.as_ref().map(|s| s.0.as_slice()) {
+            Some([Stroke::Key(key)]) => *key,
+            _ => KeyCode::Char('\\'),
+        };
+        let mut keymap = Self::default();
+        let mut configured: Vec<(Vec<KeyCode>, Command)> = Vec::new();
+        for (command, bindings) in &config.bind {
+            keymap.bindings.retain(|(_, c)| c != command);
+            for sequence in bindings.sequences() {
+                let keys: Vec<KeyCode> = sequence
+                    .0
+                    .iter()
+                    .map(|stroke| match stroke {
+                        Stroke::Key(key) => *key,
+                        Stroke::Leader => leader,
+                    })
+                    .collect();
+                if !keys.is_empty() && !configured.contains(&(keys.clone(), *command)) {
+                    configured.push((keys, *command));
+                }
+            }
+        }
+
+        let mut conflicts = Vec::new();
+        let mut overlapping = vec![false; configured.len()];
+        for (i, (keys, command)) in configured.iter().enumerate() {
+            for (j, (with, with_command)) in configured.iter().enumerate().skip(i + 1) {
+                if overlap(keys, with) {
+                    overlapping[i] = true;
+                    overlapping[j] = true;
+                    conflicts.push(Conflict {
+                        keys: keys.clone(),
+                        command: *command,
+                        with: with.clone(),
+                        with_command: *with_command,
+                        configured: true,
+                    });
+                }
+            }
+        }
+
+        let kept = configured
+            .into_iter()
+            .zip(overlapping)
+            .filter(|(_, overlapping)| !overlapping);
+        for ((keys, command), _) in kept {
+            for (displaced, displaced_command) in keymap.bind(keys.clone(), command) {
+                conflicts.push(Conflict {
+                    keys: displaced,
+                    command: displaced_command,
+                    with: keys.clone(),
+                    with_command: command,
+                    configured: false,
+                });
+            }
+        }
+        (keymap, conflicts)
+    }
+
+    /// Binds `keys` to `command`, returning the bindings it took the place
+    /// of: those the new one would shadow, or that would shadow it. A key
+    /// starting a sequence can't also do something on its own.
+    pub fn bind(&mut self, keys: Vec<KeyCode>, command: Command) -> Vec<(Vec<KeyCode>, Command)> {
+        if keys.is_empty() {
+            return Vec::new();
+        }
+        let (displaced, kept) = std::mem::take(&mut self.bindings)
+            .into_iter()
+            .partition(|(bound, _)| overlap(bound, &keys));
+        self.bindings = kept;
+        self.bindings.push((keys, command));
+        displaced
+    }
+
+    /// Keys bound to `command`, each a sequence.
+    pub fn keys_for(&self, command: Command) -> Vec<&[KeyCode]> {
+        self.bindings
+            .iter()
+            .filter(|(_, c)| *c == command)
+            .map(|(keys, _)| keys.as_slice())
+            .collect()
+    }
+
+    /// The keys bound to `command` as the help shows them, e.g. `q/Esc`
+    /// or `g e`, if any are.
+    pub fn describe(&self, command: Command) -> Option<String> {
+        let bound: Vec<String> = self
+            .keys_for(command)
+            .iter()
+            .map(|keys| sequence_name(keys))
+            .collect();
+        (!bound.is_empty()).then(|| bound.join("/"))
+    }
+
+    /// Keys pressed so far towards a sequence.
+    pub fn pending(&self) -> &[KeyCode] {
+        &self.pending
+    }
+
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Feeds in a key press, returning the command it completes. Esc drops
+    /// a half-typed sequence; another key that doesn't continue it starts
+    /// afresh.
+    pub fn press(&mut self, key: KeyCode) -> Option<Command> {
+        if self.is_pending() && key == KeyCode::Esc {
+            self.pending.clear();
+            return None;
+        }
+        self.pending.push(key);
+        if let Some(command) = self.matching() {
+            self.pending.clear();
+            return Some(command);
+        }
+        if self
+            .bindings
+            .iter()
+            .any(|(keys, _)| keys.starts_with(&self.pending))
+        {
+            return None;
+        }
+        let restart = self.pending.len() > 1;
+        self.pending.clear();
+        if restart {
+            return self.press(key);
+        }
+        None
+    }
+
+    fn matching(&self) -> Option<Command> {
+        self.bindings
+            .iter()
+            .find(|(keys, _)| *keys == self.pending)
+            .map(|(_, command)| *command)
+    }
+}
+
+/// Whether one sequence is the other or starts it, so they can't both be
+/// bound.
+fn overlap(a: &[KeyCode], b: &[KeyCode]) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
+
+fn sequence_name(keys: &[KeyCode]) -> String {
+    keys.iter()
+        .map(|&k| key_name(k))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(text: &str) -> Vec<KeyCode> {
+        text.split_whitespace()
+            .map(|k| parse_key(k).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let mut keymap = Keymap::default();
+        assert_eq!(keymap.press(KeyCode::Char('X')), Some(Command::ExportMidi));
+        assert_eq!(keymap.press(KeyCode::Esc), Some(Command::Quit));
+        assert_eq!(
+            keymap.press(KeyCode::Char('=')),
+            Some(Command::CreativityUp)
+        );
+        assert_eq!(keymap.press(KeyCode::Char('Z')), None);
+        assert!(!keymap.is_pending());
+        assert_eq!(Command::all().count(), DEFAULTS.len());
+    }
+
+    #[test]
+    fn test_sequences() {
+        let mut keymap = Keymap::default();
+        keymap.bind(keys("g e"), Command::ExportMidi);
+        // `g` on its own is now the start of a sequence
+        assert_eq!(keymap.press(KeyCode::Char('g')), None);
+        assert_eq!(keymap.pending(), &[KeyCode::Char('g')]);
+        assert_eq!(keymap.press(KeyCode::Char('e')), Some(Command::ExportMidi));
+        assert!(keymap.keys_for(Command::CycleCapo).is_empty());
+
+        // Esc gives up on a sequence without quitting
+        keymap.press(KeyCode::Char('g'));
+        assert_eq!(keymap.press(KeyCode::Esc), None);
+        assert!(!keymap.is_pending());
+
+        // A key that doesn't continue the sequence counts on its own
+        keymap.press(KeyCode::Char('g'));
+        assert_eq!(
+            keymap.press(KeyCode::Char('x')),
+            Some(Command::ExportLilypond)
+        );
+    }
+
+    #[test]
+    fn test_config() {
+        let config: KeysConfig = toml::from_str(
+            "leader = \"Space\"\n[bind]\nexport-midi = \"<leader> m\"\nquit = [\"q\", \"F10\"]\nhelp = \"\"\n",
+        )
+        .unwrap();
+        let (mut keymap, conflicts) = Keymap::from_config(&config);
+        assert_eq!(keymap.press(KeyCode::Char(' ')), None);
+        assert_eq!(keymap.press(KeyCode::Char('m')), Some(Command::ExportMidi));
+        assert_eq!(keymap.press(KeyCode::F(10)), Some(Command::Quit));
+        assert_eq!(keymap.press(KeyCode::Esc), None);
+        assert!(keymap.keys_for(Command::Help).is_empty());
+        assert!(keymap.keys_for(Command::PageTurn).is_empty());
+        assert_eq!(
+            keymap.keys_for(Command::ExportMidi),
+            [keys("Space m").as_slice()]
+        );
+
+        // Space was the page turn's, and the leader now starts a sequence
+        assert_eq!(
+            conflicts,
+            [Conflict {
+                keys: keys("Space"),
+                command: Command::PageTurn,
+                with: keys("Space m"),
+                with_command: Command::ExportMidi,
+                configured: false,
+            }]
+        );
+        assert_eq!(
+            conflicts[0].to_string(),
+            "`Space` no longer runs page-turn: `Space m` is bound to export-midi"
+        );
+
+        assert!(toml::from_str::<KeysConfig>("[bind]\nfly = \"f\"").is_err());
+        assert!(toml::from_str::<KeysConfig>("[bind]\nquit = \"Hyper\"").is_err());
+    }
+
+    #[test]
+    fn test_config_overlaps() {
+        // Whichever command comes first, two overlapping bindings from the
+        // config are both left out and reported
+        for (first, second) in [("presets", "help"), ("help", "presets")] {
+            let config: KeysConfig =
+                toml::from_str(&format!("[bind]\n{first} = \"g\"\n{second} = \"g e\"\n")).unwrap();
+            let (keymap, conflicts) = Keymap::from_config(&config);
+            assert!(keymap.keys_for(Command::Presets).is_empty());
+            assert!(keymap.keys_for(Command::Help).is_empty());
+            assert_eq!(conflicts.len(), 1);
+            assert!(conflicts[0].configured);
+            assert!(conflicts[0]
+                .to_string()
+                .ends_with("overlap, so neither is bound"));
+            // Neither took `g` from its default
+            assert_eq!(keymap.describe(Command::CycleCapo).unwrap(), "g");
+        }
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut keymap = Keymap::default();
+        assert_eq!(keymap.describe(Command::Quit).unwrap(), "q/Esc");
+        assert_eq!(keymap.describe(Command::PageTurn).unwrap(), "Space");
+        keymap.bind(keys("g e"), Command::ExportMidi);
+        assert_eq!(keymap.describe(Command::ExportMidi).unwrap(), "X/g e");
+        assert_eq!(keymap.describe(Command::CycleCapo), None);
+        assert!(Category::all()
+            .iter()
+            .all(|category| Command::all().any(|c| c.category() == *category)));
+    }
+
+    #[test]
+    fn test_key_names() {
+        for name in ["a", "Esc", "Tab", "Space", "F5", "PageDown"] {
+            let key = parse_key(name).unwrap();
+            assert_eq!(parse_key(&key_name(key)), Some(key));
+        }
+        assert_eq!(parse_key("F13"), None);
+        assert_eq!(parse_key("Hyper"), None);
+    }
+}
@@ -1,5 +1,17 @@
+pub mod animation;
 pub mod app;
 pub mod components;
+pub mod controls;
+pub mod frontend;
+#[cfg(feature = "gui")]
+pub mod gui;
+pub mod i18n;
+pub mod keymap;
+pub mod screen;
+#[cfg(test)]
+pub(crate) mod snapshot;
+pub mod status;
 pub mod theme;
 
-pub use app::{App, Mode};
+pub use app::{App, MinorTree, Mode, MAX_CAPO};
+pub use screen::Screen;
@@ -0,0 +1,131 @@
+use crate::midi::Calibration;
+use crate::practice::IntervalDrill;
+use crate::session::{Session, SessionBrowser, TakeDiff};
+use crate::theory::{Branch, Chord, Transposition};
+use crate::ui::controls::Mapping;
+
+/// Something open over the main view, with what it needs to remember
+/// while open. Screens stack: the newest takes the keys and is drawn on
+/// top, and closing it goes back to the one under it.
+pub enum Screen {
+    /// The session autosaved before a crash, offered back
+    Recovery(Session),
+    Help,
+    Sessions(SessionBrowser),
+    /// Two takes lined up side by side, and how far they're scrolled
+    Compare(TakeDiff, usize),
+    Presets,
+    /// The selected history entry, in detail
+    Detail,
+    Goals,
+    Heatmap,
+    /// Chord names typed in, weighed instead of the history when any are
+    /// given
+    KeyFinder(String),
+    /// The chords being reharmonized and the alternative selected
+    Reharm(Vec<Chord>, usize),
+    /// The keys being picked, and whether the sung key is the one selected
+    Transposer(Transposition, bool),
+    /// When in the event log a moment was bookmarked, and the note being
+    /// typed for it
+    Bookmark(u64, String),
+    Calibration(Calibration),
+    ControlMapping(Mapping),
+    /// Users to switch to (None for the default one) and the cursor
+    UserPicker(Vec<Option<String>>, usize),
+    /// The branch selected while editing the plan
+    Plan(Vec<Branch>),
+    /// The selected section of the sketch
+    Sketch(usize),
+    Intervals(Box<IntervalDrill>),
+    /// The chord whose voicings are shown, and which one
+    Explorer(Chord, usize),
+    /// Chord whose guitar grips are shown, and which grip
+    Fingering(Chord, usize),
+}
+
+impl Screen {
+    /// Whether it's drawn over the main view. Editing the plan and typing
+    /// a bookmark's note take the keys but leave the main view showing.
+    pub fn is_overlay(&self) -> bool {
+        !matches!(self, Screen::Plan(_) | Screen::Bookmark(..))
+    }
+}
+
+/// The open screens, the newest last.
+#[derive(Default)]
+pub struct Screens(Vec<Screen>);
+
+impl Screens {
+    /// The screen taking the keys.
+    pub fn top(&self) -> Option<&Screen> {
+        self.0.last()
+    }
+
+    pub fn top_mut(&mut self) -> Option<&mut Screen> {
+        self.0.last_mut()
+    }
+
+    pub fn open(&mut self, screen: Screen) {
+        self.0.push(screen);
+    }
+
+    pub fn close_top(&mut self) -> Option<Screen> {
+        self.0.pop()
+    }
+
+    /// Closes the newest screen `is` picks out, if one is open.
+    pub fn close(&mut self, is: impl Fn(&Screen) -> bool) -> bool {
+        match self.0.iter().rposition(is) {
+            Some(at) => {
+                self.0.remove(at);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn any(&self, is: impl Fn(&Screen) -> bool) -> bool {
+        self.0.iter().any(is)
+    }
+
+    /// Oldest first, the order they're drawn in.
+    pub fn iter(&self) -> impl Iterator<Item = &Screen> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Screen> {
+        self.0.iter_mut()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack() {
+        let mut screens = Screens::default();
+        screens.open(Screen::Presets);
+        screens.open(Screen::Help);
+        assert!(matches!(screens.top(), Some(Screen::Help)));
+
+        // Closing one further down leaves the top taking the keys
+        assert!(screens.close(|s| matches!(s, Screen::Presets)));
+        assert!(!screens.close(|s| matches!(s, Screen::Presets)));
+        assert!(matches!(screens.close_top(), Some(Screen::Help)));
+        assert!(screens.is_empty());
+    }
+
+    #[test]
+    fn test_overlays() {
+        assert!(Screen::Help.is_overlay());
+        assert!(Screen::Sketch(0).is_overlay());
+        assert!(!Screen::Plan(vec![Branch::Expected]).is_overlay());
+        assert!(!Screen::Bookmark(0, String::new()).is_overlay());
+    }
+}
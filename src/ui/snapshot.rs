@@ -0,0 +1,24 @@
+//! Drawing widgets and the whole app off screen for snapshot tests, kept
+//! with `insta`. A snapshot holds the text as drawn and the styles it was
+//! drawn in, so a change to either shows up as a diff to review.
+
+use ratatui::{backend::TestBackend, buffer::Buffer, layout::Rect, widgets::Widget, Terminal};
+
+use super::App;
+
+/// Terminal sizes the full layout is checked at: the smallest usual
+/// terminal, a roomy one and a wide one
+pub const SIZES: [(u16, u16); 3] = [(80, 24), (120, 40), (160, 48)];
+
+pub fn widget(widget: impl Widget, width: u16, height: u16) -> String {
+    let area = Rect::new(0, 0, width, height);
+    let mut buf = Buffer::empty(area);
+    widget.render(area, &mut buf);
+    format!("{:?}", buf)
+}
+
+pub fn app(app: &App, width: u16, height: u16) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|f| app.render(f)).unwrap();
+    format!("{:?}", terminal.backend().buffer())
+}
@@ -0,0 +1,158 @@
+use serde::Deserialize;
+
+/// A part of the status bar. The config lists which to show and in what
+/// order; most only show while what they describe is on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum StatusSegment {
+    Mode,
+    Chord,
+    Extended,
+    Creativity,
+    Tempo,
+    User,
+    Latch,
+    Melody,
+    /// The bass note held as a pedal under changing chords
+    Pedal,
+    Smoothing,
+    Pads,
+    Arp,
+    Capo,
+    /// The original and sung keys, and the chord moved between them
+    Transpose,
+    Key,
+    /// The bar of the 12-bar form in blues mode
+    Blues,
+    Along,
+    Humanize,
+    Learn,
+    Dictation,
+    Trainer,
+    /// The MIDI input port
+    Port,
+    /// From the latest note-on to the chord it made being read
+    Latency,
+    /// Where the tempo comes from
+    Clock,
+    /// Keys typed so far of a sequence
+    Keys,
+    Help,
+    Message,
+}
+
+impl StatusSegment {
+    pub fn all() -> &'static [StatusSegment] {
+        &[
+            StatusSegment::Mode,
+            StatusSegment::Chord,
+            StatusSegment::Extended,
+            StatusSegment::Creativity,
+            StatusSegment::Tempo,
+            StatusSegment::User,
+            StatusSegment::Latch,
+            StatusSegment::Melody,
+            StatusSegment::Pedal,
+            StatusSegment::Smoothing,
+            StatusSegment::Pads,
+            StatusSegment::Arp,
+            StatusSegment::Capo,
+            StatusSegment::Transpose,
+            StatusSegment::Key,
+            StatusSegment::Blues,
+            StatusSegment::Along,
+            StatusSegment::Humanize,
+            StatusSegment::Learn,
+            StatusSegment::Dictation,
+            StatusSegment::Trainer,
+            StatusSegment::Port,
+            StatusSegment::Latency,
+            StatusSegment::Clock,
+            StatusSegment::Keys,
+            StatusSegment::Help,
+            StatusSegment::Message,
+        ]
+    }
+
+    /// Everything but the port, latency and clock, in the order above.
+    pub fn defaults() -> Vec<StatusSegment> {
+        Self::all()
+            .iter()
+            .copied()
+            .filter(|s| {
+                !matches!(
+                    s,
+                    StatusSegment::Port | StatusSegment::Latency | StatusSegment::Clock
+                )
+            })
+            .collect()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusSegment::Mode => "mode",
+            StatusSegment::Chord => "chord",
+            StatusSegment::Extended => "extended",
+            StatusSegment::Creativity => "creativity",
+            StatusSegment::Tempo => "tempo",
+            StatusSegment::User => "user",
+            StatusSegment::Latch => "latch",
+            StatusSegment::Melody => "melody",
+            StatusSegment::Pedal => "pedal",
+            StatusSegment::Smoothing => "smoothing",
+            StatusSegment::Pads => "pads",
+            StatusSegment::Arp => "arp",
+            StatusSegment::Capo => "capo",
+            StatusSegment::Transpose => "transpose",
+            StatusSegment::Key => "key",
+            StatusSegment::Blues => "blues",
+            StatusSegment::Along => "along",
+            StatusSegment::Humanize => "humanize",
+            StatusSegment::Learn => "learn",
+            StatusSegment::Dictation => "dictation",
+            StatusSegment::Trainer => "trainer",
+            StatusSegment::Port => "port",
+            StatusSegment::Latency => "latency",
+            StatusSegment::Clock => "clock",
+            StatusSegment::Keys => "keys",
+            StatusSegment::Help => "help",
+            StatusSegment::Message => "message",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::all().iter().copied().find(|s| s.name() == name)
+    }
+}
+
+impl TryFrom<String> for StatusSegment {
+    type Error = anyhow::Error;
+
+    fn try_from(name: String) -> anyhow::Result<Self> {
+        Self::from_name(&name).ok_or_else(|| anyhow::anyhow!("Unknown status segment '{}'", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        for segment in StatusSegment::all() {
+            assert_eq!(StatusSegment::from_name(segment.name()), Some(*segment));
+        }
+        assert_eq!(StatusSegment::from_name(" BPM "), None);
+        assert_eq!(
+            StatusSegment::from_name("Tempo"),
+            Some(StatusSegment::Tempo)
+        );
+        assert!(!StatusSegment::defaults().contains(&StatusSegment::Port));
+        assert!(!StatusSegment::defaults().contains(&StatusSegment::Clock));
+        assert_eq!(
+            StatusSegment::defaults().last(),
+            Some(&StatusSegment::Message)
+        );
+    }
+}
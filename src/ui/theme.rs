@@ -1,4 +1,10 @@
+use std::str::FromStr;
+
 use ratatui::style::{Color, Modifier, Style};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+
+use crate::theory::{Chord, Function, QualityFamily};
 
 pub struct Theme;
 
@@ -15,6 +21,10 @@ impl Theme {
         Style::default().fg(Color::White).bg(Color::Magenta)
     }
 
+    pub fn white_key_ghost() -> Style {
+        Style::default().fg(Color::Black).bg(Color::LightYellow)
+    }
+
     pub fn black_key() -> Style {
         Style::default().fg(Color::White).bg(Color::DarkGray)
     }
@@ -27,6 +37,20 @@ impl Theme {
         Style::default().fg(Color::Black).bg(Color::Magenta)
     }
 
+    pub fn black_key_ghost() -> Style {
+        Style::default().fg(Color::Black).bg(Color::Yellow)
+    }
+
+    pub fn fret_note() -> Style {
+        Style::default().fg(Color::Cyan)
+    }
+
+    pub fn fret_root() -> Style {
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD)
+    }
+
     pub fn border() -> Style {
         Style::default().fg(Color::DarkGray)
     }
@@ -55,6 +79,23 @@ impl Theme {
             .add_modifier(Modifier::BOLD)
     }
 
+    pub fn sparkline() -> Style {
+        Style::default().fg(Color::Cyan)
+    }
+
+    pub fn chord_flash() -> Style {
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    pub fn chord_name_uncertain() -> Style {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::DIM)
+    }
+
     pub fn chord_history(age: u8) -> Style {
         let color = match age {
             0 => Color::Yellow,
@@ -65,6 +106,51 @@ impl Theme {
         Style::default().fg(color)
     }
 
+    pub fn out_of_key() -> Style {
+        Style::default()
+            .fg(Color::LightRed)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    pub fn modulation() -> Style {
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::ITALIC)
+    }
+
+    pub fn transposed() -> Style {
+        Style::default()
+            .fg(Color::LightGreen)
+            .add_modifier(Modifier::ITALIC)
+    }
+
+    pub fn parallel() -> Style {
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::DIM)
+    }
+
+    pub fn suspension() -> Style {
+        Style::default()
+            .fg(Color::LightBlue)
+            .add_modifier(Modifier::ITALIC)
+    }
+
+    /// A block of the function ribbon
+    pub fn function(function: Function) -> Style {
+        let color = match function {
+            Function::Tonic => Color::Blue,
+            Function::Subdominant => Color::Green,
+            Function::Dominant => Color::Red,
+            Function::Chromatic => Color::Magenta,
+        };
+        Style::default().fg(Color::Black).bg(color)
+    }
+
+    pub fn selected() -> Style {
+        Style::default().add_modifier(Modifier::REVERSED)
+    }
+
     pub fn tree_current() -> Style {
         Style::default()
             .fg(Color::Yellow)
@@ -79,6 +165,15 @@ impl Theme {
         Style::default().fg(Color::Magenta)
     }
 
+    /// Pinned or entered by hand into the plan
+    pub fn tree_planned() -> Style {
+        Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    }
+
+    pub fn tree_cursor() -> Style {
+        Style::default().add_modifier(Modifier::REVERSED)
+    }
+
     pub fn tree_connector() -> Style {
         Style::default().fg(Color::DarkGray)
     }
@@ -104,4 +199,109 @@ impl Theme {
     pub fn help_text() -> Style {
         Style::default().fg(Color::White)
     }
+
+    pub fn help_heading() -> Style {
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::UNDERLINED)
+    }
+
+    /// A heatmap cell, `value` being its share of the most played
+    pub fn heat(value: f32) -> Style {
+        let color = if value < 0.25 {
+            Color::Blue
+        } else if value < 0.5 {
+            Color::Cyan
+        } else if value < 0.75 {
+            Color::Yellow
+        } else {
+            Color::Red
+        };
+        Style::default().fg(color)
+    }
+
+    pub fn tension(value: f32) -> Style {
+        let color = if value < 0.35 {
+            Color::Green
+        } else if value < 0.65 {
+            Color::Yellow
+        } else {
+            Color::Red
+        };
+        Style::default().fg(color)
+    }
+}
+
+/// Colours for chord names by quality family, when they are coloured that
+/// way. Each can be set in the config by name (`lightblue`), index or
+/// `#rrggbb`; any left out keep these.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QualityColors {
+    #[serde(deserialize_with = "color")]
+    pub major: Color,
+    #[serde(deserialize_with = "color")]
+    pub minor: Color,
+    #[serde(deserialize_with = "color")]
+    pub dominant: Color,
+    #[serde(deserialize_with = "color")]
+    pub diminished: Color,
+    #[serde(deserialize_with = "color")]
+    pub suspended: Color,
+}
+
+impl Default for QualityColors {
+    fn default() -> Self {
+        Self {
+            major: Color::Yellow,
+            minor: Color::LightBlue,
+            dominant: Color::LightRed,
+            diminished: Color::Magenta,
+            suspended: Color::LightGreen,
+        }
+    }
+}
+
+impl QualityColors {
+    pub fn color(&self, family: QualityFamily) -> Color {
+        match family {
+            QualityFamily::Major => self.major,
+            QualityFamily::Minor => self.minor,
+            QualityFamily::Dominant => self.dominant,
+            QualityFamily::Diminished => self.diminished,
+            QualityFamily::Suspended => self.suspended,
+        }
+    }
+
+    /// `base` in the colour of the chord's family, if it has one.
+    pub fn style(&self, chord: &Chord, base: Style) -> Style {
+        match chord.quality.family() {
+            Some(family) => base.fg(self.color(family)),
+            None => base,
+        }
+    }
+}
+
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    Color::from_str(&name).map_err(|_| D::Error::custom(format!("Unknown colour '{}'", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_colors() {
+        let colors: QualityColors =
+            toml::from_str("minor = \"cyan\"\ndominant = \"#ff8000\"").unwrap();
+        assert_eq!(colors.minor, Color::Cyan);
+        assert_eq!(colors.dominant, Color::Rgb(255, 128, 0));
+        assert_eq!(colors.major, QualityColors::default().major);
+        assert!(toml::from_str::<QualityColors>("major = \"sky\"").is_err());
+
+        let style = colors.style(&Chord::from_name("Am7").unwrap(), Theme::chord_name());
+        assert_eq!(style.fg, Some(Color::Cyan));
+        assert!(style.add_modifier.contains(Modifier::BOLD));
+    }
 }
@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+
+use wasm_bindgen::prelude::*;
+
+use crate::theory::{Chord, Note, Preferences, ProgressionNode, ProgressionTree};
+
+/// The chord name for a set of MIDI notes, if they make one.
+#[wasm_bindgen]
+pub fn detect(notes: &[u8]) -> Option<String> {
+    Chord::detect(&notes.iter().copied().collect()).map(|c| c.name())
+}
+
+/// A chord read from the notes held.
+#[wasm_bindgen]
+pub struct Reading {
+    name: String,
+    root: u8,
+    confidence: f32,
+}
+
+#[wasm_bindgen]
+impl Reading {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Pitch class of the root, 0 for C
+    #[wasm_bindgen(getter)]
+    pub fn root(&self) -> u8 {
+        self.root
+    }
+
+    /// 0 to 1
+    #[wasm_bindgen(getter)]
+    pub fn confidence(&self) -> f32 {
+        self.confidence
+    }
+}
+
+/// Detection and suggestions for a page listening to Web MIDI: hand it
+/// each `MIDIMessageEvent.data` and read back the chord held and where it
+/// might go next.
+#[wasm_bindgen]
+pub struct Engine {
+    held: HashSet<u8>,
+    preferences: Preferences,
+    tree: ProgressionTree,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl Engine {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Engine {
+        Engine {
+            held: HashSet::new(),
+            preferences: Preferences::default(),
+            tree: ProgressionTree::new(),
+        }
+    }
+
+    /// Takes a raw MIDI message, returning whether it changed the notes
+    /// held. Note-ons, note-offs and all-notes-off count, on any channel.
+    pub fn midi(&mut self, data: &[u8]) -> bool {
+        match *data {
+            [status, note, velocity, ..] if status & 0xF0 == 0x90 && velocity > 0 => {
+                self.held.insert(note)
+            }
+            [status, note, ..] if status & 0xF0 == 0x80 || status & 0xF0 == 0x90 => {
+                self.held.remove(&note)
+            }
+            [status, 123, ..] if status & 0xF0 == 0xB0 => {
+                let changed = !self.held.is_empty();
+                self.held.clear();
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    /// The notes held, lowest first.
+    pub fn held(&self) -> Vec<u8> {
+        let mut held: Vec<u8> = self.held.iter().copied().collect();
+        held.sort_unstable();
+        held
+    }
+
+    pub fn reading(&self) -> Option<Reading> {
+        let detection = Chord::analyze(&self.held, &self.preferences)?;
+        Some(Reading {
+            name: detection.chord.name(),
+            root: detection.chord.root.pitch_class(),
+            confidence: detection.confidence.value(),
+        })
+    }
+
+    #[wasm_bindgen(js_name = setExtended)]
+    pub fn set_extended(&mut self, extended: bool) {
+        self.tree.set_extended(extended);
+    }
+
+    /// 0 for the most likely suggestions, up to 1 for adventurous ones
+    #[wasm_bindgen(js_name = setCreativity)]
+    pub fn set_creativity(&mut self, creativity: f32) {
+        self.tree.set_creativity(creativity);
+    }
+
+    /// Where the adventurous suggestions' draws start, so the page can
+    /// pick a fresh one each visit
+    #[wasm_bindgen(js_name = setSeed)]
+    pub fn set_seed(&mut self, seed: u64) {
+        self.tree.seed(seed);
+    }
+
+    /// Chords to go to from `chord`, in `key` (a pitch class) or its own
+    /// root's major key: the two next chords, then the two after each,
+    /// breadth first. Empty if `chord` isn't a chord name.
+    pub fn suggest(&self, chord: &str, key: Option<u8>) -> Vec<String> {
+        let Some(chord) = Chord::from_name(chord) else {
+            return Vec::new();
+        };
+        let root = self.tree.suggest(&chord, key.map(|pc| Note::new(pc % 12)));
+        let mut names = Vec::new();
+        let mut level: Vec<&ProgressionNode> = vec![&root];
+        while !level.is_empty() {
+            level = level
+                .into_iter()
+                .flat_map(|node| [&node.left, &node.right])
+                .filter_map(|child| child.as_deref())
+                .collect();
+            names.extend(level.iter().map(|node| node.chord.name()));
+        }
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(&[60, 64, 67]).unwrap(), "C");
+        assert_eq!(detect(&[57, 60, 64, 67]).unwrap(), "Am7");
+        assert_eq!(detect(&[60, 64]), None);
+    }
+
+    #[test]
+    fn test_web_midi_messages() {
+        let mut engine = Engine::new();
+        assert!(engine.midi(&[0x90, 62, 100]));
+        assert!(engine.midi(&[0x91, 65, 90]));
+        assert!(engine.midi(&[0x90, 69, 80]));
+        assert!(!engine.midi(&[0xE0, 0, 64]));
+        assert_eq!(engine.held(), [62, 65, 69]);
+        let reading = engine.reading().unwrap();
+        assert_eq!(reading.name(), "Dm");
+        assert_eq!(reading.root(), 2);
+        assert!(reading.confidence() > 0.5);
+
+        // A note-on at velocity 0 is a note-off
+        assert!(engine.midi(&[0x90, 69, 0]));
+        assert!(engine.reading().is_none());
+        assert!(engine.midi(&[0xB0, 123, 0]));
+        assert!(engine.held().is_empty());
+    }
+
+    #[test]
+    fn test_suggest() {
+        let engine = Engine::new();
+        let suggestions = engine.suggest("C", None);
+        assert!(suggestions.len() >= 2);
+        assert!(suggestions
+            .iter()
+            .all(|name| Chord::from_name(name).is_some()));
+        assert_eq!(engine.suggest("C", Some(0)), suggestions);
+        assert!(engine.suggest("H9", None).is_empty());
+    }
+}